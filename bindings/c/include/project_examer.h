@@ -0,0 +1,42 @@
+/*
+ * C API for project-examer.
+ *
+ * Link against the library built by `cargo build --release -p project-examer-ffi`
+ * (libproject_examer_ffi.so, .dylib or .a; project_examer_ffi.dll or .lib on Windows).
+ * The static library also needs the platform's system libraries, e.g.
+ * `-lssl -lcrypto -lpthread -ldl -lm` on Linux.
+ */
+#ifndef PROJECT_EXAMER_H
+#define PROJECT_EXAMER_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Analyze the project at `path` and return its report as JSON, the same as
+ * `project-examer analyze --format json` writes, or NULL on failure.
+ *
+ * `config_json` is a partial config overlaid on the project's own
+ * (.project-examer.toml and PROJECT_EXAMER_* variables), for example
+ * {"llm": {"provider": "None"}}; pass NULL to use the project's config as is.
+ * A top-level "skip_llm": true runs only the local passes, like --skip-llm.
+ *
+ * Blocks until the analysis finishes. Free the result with analyze_string_free.
+ */
+char *analyze_to_json(const char *path, const char *config_json);
+
+/*
+ * Why the last call on this thread failed, or NULL when it succeeded. Valid
+ * until the next call on the same thread; do not free it.
+ */
+const char *analyze_last_error(void);
+
+/* Free a string returned by analyze_to_json. NULL is ignored. */
+void analyze_string_free(char *json);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* PROJECT_EXAMER_H */
@@ -0,0 +1,139 @@
+//! C API for embedding project-examer in build systems and editors that aren't
+//! written in Rust; `include/project_examer.h` declares these functions.
+//!
+//! ```c
+//! char *report = analyze_to_json("path/to/project", "{\"llm\": {\"provider\": \"None\"}}");
+//! if (report == NULL) {
+//!     fprintf(stderr, "analysis failed: %s\n", analyze_last_error());
+//! } else {
+//!     puts(report);
+//!     analyze_string_free(report);
+//! }
+//! ```
+
+use project_examer::config::Config;
+use project_examer::progress::{AnalyzeOptions, CancellationToken};
+use project_examer::{Analyzer, Reporter};
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::ptr;
+use std::time::Instant;
+
+thread_local! {
+    /// Why the last call on this thread failed, for `analyze_last_error`
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Analyze the project at `path` and return its report as JSON, the same as
+/// `analyze --format json` writes, or null on failure. `config_json` is
+/// overlaid on the project's config (`.project-examer.toml` and
+/// `PROJECT_EXAMER_*` variables) and may be null; a top-level `"skip_llm": true`
+/// in it runs only the local passes, like `--skip-llm`. Free the result with
+/// `analyze_string_free`.
+///
+/// # Safety
+///
+/// `path` must point to a NUL-terminated string, and `config_json` to one or be
+/// null; both only need to live for the duration of the call.
+#[no_mangle]
+pub unsafe extern "C" fn analyze_to_json(path: *const c_char, config_json: *const c_char) -> *mut c_char {
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+        let path = read_str(path)?.ok_or("path must not be null")?;
+        let config_json = read_str(config_json)?;
+        let json = analyze(Path::new(path), config_json)?;
+        CString::new(json).map_err(|e| e.to_string())
+    }))
+    .unwrap_or_else(|_| Err("analysis panicked".to_string()));
+
+    match outcome {
+        Ok(json) => {
+            set_last_error(None);
+            json.into_raw()
+        }
+        Err(message) => {
+            set_last_error(Some(message));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// The message for the last failed call on this thread, or null when the last
+/// call succeeded. Valid until the next call on this thread; don't free it.
+#[no_mangle]
+pub extern "C" fn analyze_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| error.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+/// Free a string returned by `analyze_to_json`; null is ignored
+///
+/// # Safety
+///
+/// `json` must be null or a pointer returned by `analyze_to_json` that hasn't
+/// been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn analyze_string_free(json: *mut c_char) {
+    if !json.is_null() {
+        drop(CString::from_raw(json));
+    }
+}
+
+/// Resolve the config, run every pass on a private runtime and build the report
+fn analyze(path: &Path, config_json: Option<&str>) -> Result<String, String> {
+    let mut config = Config::load_quietly_for(path).map_err(|e| e.to_string())?;
+    let mut skip_llm = false;
+    if let Some(json) = config_json.filter(|json| !json.trim().is_empty()) {
+        let mut overrides: serde_json::Value = serde_json::from_str(json).map_err(|e| format!("Invalid config JSON: {}", e))?;
+        // An option for this call rather than a config setting
+        if let Some(value) = overrides.as_object_mut().and_then(|options| options.remove("skip_llm")) {
+            skip_llm = value.as_bool().ok_or("skip_llm must be true or false")?;
+        }
+        config.apply_json(&overrides).map_err(|e| e.to_string())?;
+        config.apply_api_key_env(&Config::env_vars());
+    }
+    config.target_directory = path.to_path_buf();
+    config.validate().map_err(|e| e.to_string())?;
+
+    let mut reporter = Reporter::with_scoring(config.analysis.scoring.clone())
+        .with_maintainability(config.analysis.maintainability.clone());
+    if config.report.deterministic {
+        reporter = reporter.with_fixed_timestamp(config.report.timestamp.clone());
+    }
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| format!("Cannot start the async runtime: {}", e))?;
+
+    let started = Instant::now();
+    let mut analyzer = Analyzer::new(config.clone(), false).map_err(|e| e.to_string())?;
+    // Nothing listens for progress; the closed channel just drops the events
+    let (progress, _) = tokio::sync::mpsc::unbounded_channel();
+    let options = AnalyzeOptions { skip_llm, quiet: true };
+    let analysis = runtime
+        .block_on(analyzer.analyze_project_with(options, CancellationToken::new(), progress))
+        .map_err(|e| e.to_string())?;
+
+    let report = reporter.generate_report(&analysis, started.elapsed().as_millis(), config.llm.provider.name(), &config.llm.model);
+    serde_json::to_string_pretty(&report).map_err(|e| e.to_string())
+}
+
+/// A C string argument as UTF-8, `None` when null
+unsafe fn read_str<'a>(pointer: *const c_char) -> Result<Option<&'a str>, String> {
+    if pointer.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(pointer)
+        .to_str()
+        .map(Some)
+        .map_err(|_| "arguments must be UTF-8".to_string())
+}
+
+fn set_last_error(message: Option<String>) {
+    // Messages never contain NUL; should one appear, it is cut there
+    let message = message.map(|message| {
+        let end = message.find('\0').unwrap_or(message.len());
+        CString::new(&message[..end]).expect("NUL bytes were removed")
+    });
+    LAST_ERROR.with(|error| *error.borrow_mut() = message);
+}
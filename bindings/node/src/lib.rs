@@ -0,0 +1,135 @@
+//! Node.js bindings for project-examer, so editor extensions and build plugins
+//! can run the analyzer in-process instead of spawning the CLI and parsing its
+//! output. Build with `npm run build` in this directory.
+//!
+//! ```js
+//! const { analyze, renderReport, query } = require('project-examer');
+//!
+//! const report = await analyze('.', { skipLlm: true, indexDir: '.examer' },
+//!   (event) => console.log(event.event, event.pass ?? event.path ?? ''));
+//! const html = renderReport(report, 'html');
+//! const metrics = query('.examer/file_index.json', 'metrics', 'src/main.rs');
+//! ```
+
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi_derive::napi;
+use project_examer::config::Config;
+use project_examer::file_index::{FileIndex, QueryKind};
+use project_examer::progress::{AnalyzeOptions as RunOptions, CancellationToken, ProgressEvent};
+use project_examer::reporter::Report;
+use project_examer::{Analyzer, Reporter};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+#[napi(object)]
+#[derive(Default)]
+pub struct AnalyzeOptions {
+    /// A config file to use instead of the `.project-examer.toml` found from the project
+    pub config_path: Option<String>,
+    /// A profile from the config to apply
+    pub profile: Option<String>,
+    /// Run only the local passes
+    pub skip_llm: Option<bool>,
+    /// Write `file_index.json` to this directory, for `query`
+    pub index_dir: Option<String>,
+}
+
+/// Analyze the project at `path` and resolve to its report, the same JSON as
+/// `analyze --format json`. `onProgress` receives the analyzer's progress events.
+#[napi(ts_args_type = "path: string, options?: AnalyzeOptions, onProgress?: (event: any) => void")]
+pub async fn analyze(
+    path: String,
+    options: Option<AnalyzeOptions>,
+    on_progress: Option<ThreadsafeFunction<Value, ErrorStrategy::Fatal>>,
+) -> Result<Value> {
+    let options = options.unwrap_or_default();
+    let target = PathBuf::from(path);
+    let config = load_config(&options, &target)?;
+    let mut reporter = Reporter::with_scoring(config.analysis.scoring.clone())
+        .with_maintainability(config.analysis.maintainability.clone());
+    if config.report.deterministic {
+        reporter = reporter.with_fixed_timestamp(config.report.timestamp.clone());
+    }
+
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<ProgressEvent>();
+    let forward = on_progress.map(|callback| {
+        napi::tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                if let Ok(event) = serde_json::to_value(&event) {
+                    callback.call(event, ThreadsafeFunctionCallMode::NonBlocking);
+                }
+            }
+        })
+    });
+
+    let started = Instant::now();
+    let mut analyzer = Analyzer::new(config.clone(), false).map_err(to_napi)?;
+    let run = RunOptions { skip_llm: options.skip_llm.unwrap_or(false), quiet: true };
+    // Everything but the LLM stage blocks, so the run gets a thread of its own
+    // instead of a runtime worker. Dropping the analyzer at its end closes the
+    // channel, which ends the forwarding task.
+    let analysis = napi::tokio::task::spawn_blocking(move || {
+        napi::tokio::runtime::Handle::current().block_on(analyzer.analyze_project_with(run, CancellationToken::new(), sender))
+    })
+    .await
+    .map_err(to_napi)?;
+    if let Some(forward) = forward {
+        let _ = forward.await;
+    }
+    let analysis = analysis.map_err(to_napi)?;
+
+    let report = reporter.generate_report(&analysis, started.elapsed().as_millis(), config.llm.provider.name(), &config.llm.model);
+    if let Some(index_dir) = options.index_dir {
+        FileIndex::build(&analysis, &report.maintainability, &target)
+            .write(Path::new(&index_dir))
+            .map_err(to_napi)?;
+    }
+    serde_json::to_value(&report).map_err(to_napi)
+}
+
+/// Render a report returned by `analyze` as `html`, `markdown` or `json`
+#[napi]
+pub fn render_report(report: Value, format: String) -> Result<String> {
+    let report: Report = serde_json::from_value(report).map_err(to_napi)?;
+    let reporter = Reporter::new();
+    match format.as_str() {
+        "html" => reporter.generate_html_report(&report).map_err(to_napi),
+        "markdown" => reporter.generate_markdown(&report, true).map_err(to_napi),
+        "json" => serde_json::to_string_pretty(&report).map_err(to_napi),
+        other => Err(Error::new(Status::InvalidArg, format!("unknown report format `{}`; use html, markdown or json", other))),
+    }
+}
+
+/// Look up `metrics`, `importers`, `dependencies` or `findings` for `file` in
+/// the `file_index.json` written by `analyze` with `indexDir`
+#[napi]
+pub fn query(index_path: String, kind: String, file: String) -> Result<Value> {
+    let kind: QueryKind = serde_json::from_value(Value::String(kind.clone())).map_err(|_| {
+        Error::new(Status::InvalidArg, format!("unknown query `{}`; use metrics, importers, dependencies or findings", kind))
+    })?;
+    let index = FileIndex::load(Path::new(&index_path)).map_err(to_napi)?;
+    index.query(kind, Path::new(&file)).map_err(to_napi)
+}
+
+/// The project's config, resolved the way the CLI resolves `--config` and `--profile`
+fn load_config(options: &AnalyzeOptions, target: &Path) -> Result<Config> {
+    let mut config = match &options.config_path {
+        Some(config_path) => {
+            let mut config = Config::from_file(&PathBuf::from(config_path)).map_err(to_napi)?;
+            config.apply_env_overrides().map_err(to_napi)?;
+            config
+        }
+        None => Config::load_quietly_for(target).map_err(to_napi)?,
+    };
+    if let Some(profile) = &options.profile {
+        config.apply_profile(profile).map_err(to_napi)?;
+    }
+    config.target_directory = target.to_path_buf();
+    Ok(config)
+}
+
+fn to_napi(error: impl std::fmt::Display) -> Error {
+    Error::from_reason(error.to_string())
+}
@@ -0,0 +1,345 @@
+use crate::{
+    graph_export::escape_xml,
+    html_layout,
+    llm::Priority,
+    maintainability::MaintainabilityRating,
+    reporter::Report,
+    rules::Severity,
+};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// File name `analyze` gives its JSON report, looked for inside directories
+const REPORT_FILE: &str = "analysis_report.json";
+
+/// Headline numbers for one repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositorySummary {
+    pub name: String,
+    pub report: PathBuf,
+    pub generated_at: String,
+    pub files: usize,
+    pub lines_of_code: usize,
+    pub complexity_score: f64,
+    pub maintainability_score: f64,
+    /// Mean per-file maintainability index (0-100)
+    pub avg_maintainability_index: f64,
+    pub red_files: usize,
+    pub documentation_coverage: f64,
+    pub dependency_cycles: usize,
+    pub critical_recommendations: usize,
+    pub high_recommendations: usize,
+    pub rule_errors: usize,
+    pub architecture_violations: usize,
+}
+
+/// A language's share across every repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageTotal {
+    pub language: String,
+    pub repositories: usize,
+    pub files: usize,
+    pub lines_of_code: usize,
+    pub total_size: u64,
+}
+
+/// One of the least maintainable files across the organization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorstFile {
+    pub repository: String,
+    pub path: PathBuf,
+    pub index: f64,
+    pub rating: MaintainabilityRating,
+    pub lines_of_code: usize,
+    pub cyclomatic_complexity: usize,
+}
+
+/// A critical finding seen in more than one place, grouped by what it is
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommonFinding {
+    /// `recommendation`, `rule` or `architecture`
+    pub kind: String,
+    pub title: String,
+    /// Repositories it appears in, by name
+    pub repositories: Vec<String>,
+    pub occurrences: usize,
+}
+
+/// Organization-level view over many repositories' reports, from `project-examer aggregate`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Aggregate {
+    /// Least maintainable first
+    pub repositories: Vec<RepositorySummary>,
+    /// Most lines of code first
+    pub languages: Vec<LanguageTotal>,
+    pub worst_files: Vec<WorstFile>,
+    /// Findings in the most repositories first
+    pub common_findings: Vec<CommonFinding>,
+}
+
+/// Merges reports written by `analyze` in many repositories
+pub struct Aggregator {
+    worst_files: usize,
+}
+
+impl Aggregator {
+    /// `worst_files` caps the least-maintainable file list
+    pub fn new(worst_files: usize) -> Self {
+        Self { worst_files }
+    }
+
+    /// Report files named directly, plus every `analysis_report.json` below the named directories
+    pub fn find_reports(inputs: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        let mut reports = BTreeSet::new();
+        for input in inputs {
+            if input.is_dir() {
+                reports.extend(WalkDir::new(input).into_iter()
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.file_type().is_file() && entry.file_name() == REPORT_FILE)
+                    .map(|entry| entry.into_path()));
+            } else if input.is_file() {
+                reports.insert(input.clone());
+            } else {
+                return Err(Error::Discovery(format!("{} does not exist", input.display())));
+            }
+        }
+        Ok(reports.into_iter().collect())
+    }
+
+    pub fn load(path: &Path) -> Result<Report> {
+        let content = fs::read_to_string(path).map_err(|e| Error::parse(path, e))?;
+        serde_json::from_str(&content).map_err(|e| Error::parse(path, e))
+    }
+
+    pub fn aggregate(&self, reports: &[(PathBuf, Report)]) -> Aggregate {
+        let names = Self::names(reports);
+        let mut repositories = Vec::new();
+        let mut languages: BTreeMap<String, LanguageTotal> = BTreeMap::new();
+        let mut worst_files = Vec::new();
+        let mut findings: BTreeMap<(&'static str, String), CommonFinding> = BTreeMap::new();
+
+        for ((path, report), name) in reports.iter().zip(&names) {
+            let files = &report.maintainability;
+            repositories.push(RepositorySummary {
+                name: name.clone(),
+                report: path.clone(),
+                generated_at: report.metadata.generated_at.clone(),
+                files: report.metadata.total_files,
+                lines_of_code: files.iter().map(|file| file.lines_of_code).sum(),
+                complexity_score: report.executive_summary.complexity_score,
+                maintainability_score: report.executive_summary.maintainability_score,
+                avg_maintainability_index: if files.is_empty() {
+                    0.0
+                } else {
+                    files.iter().map(|file| file.index).sum::<f64>() / files.len() as f64
+                },
+                red_files: files.iter().filter(|file| file.rating == MaintainabilityRating::Red).count(),
+                documentation_coverage: report.executive_summary.documentation_coverage,
+                dependency_cycles: report.dependency_analysis.circular_dependencies.len(),
+                critical_recommendations: report.recommendations.iter().filter(|rec| matches!(rec.priority, Priority::Critical)).count(),
+                high_recommendations: report.recommendations.iter().filter(|rec| matches!(rec.priority, Priority::High)).count(),
+                rule_errors: report.rule_findings.iter().filter(|finding| finding.severity == Severity::Error).count(),
+                architecture_violations: report.architecture_violations.len(),
+            });
+
+            for stats in &report.file_analysis.language_breakdown {
+                let total = languages.entry(stats.language.clone()).or_insert_with(|| LanguageTotal {
+                    language: stats.language.clone(),
+                    repositories: 0,
+                    files: 0,
+                    lines_of_code: 0,
+                    total_size: 0,
+                });
+                total.repositories += 1;
+                total.files += stats.file_count;
+                total.lines_of_code += stats.lines_of_code;
+                total.total_size += stats.total_size;
+            }
+
+            worst_files.extend(files.iter().map(|file| WorstFile {
+                repository: name.clone(),
+                path: file.path.clone(),
+                index: file.index,
+                rating: file.rating,
+                lines_of_code: file.lines_of_code,
+                cyclomatic_complexity: file.cyclomatic_complexity,
+            }));
+
+            let mut add = |kind: &'static str, title: String| {
+                let finding = findings.entry((kind, title.to_lowercase())).or_insert_with(|| CommonFinding {
+                    kind: kind.to_string(),
+                    title,
+                    repositories: Vec::new(),
+                    occurrences: 0,
+                });
+                finding.occurrences += 1;
+                if finding.repositories.last() != Some(name) {
+                    finding.repositories.push(name.clone());
+                }
+            };
+            for rec in report.recommendations.iter().filter(|rec| matches!(rec.priority, Priority::Critical | Priority::High)) {
+                add("recommendation", format!("{}: {}", rec.category.name(), rec.title.split_whitespace().collect::<Vec<_>>().join(" ")));
+            }
+            for finding in report.rule_findings.iter().filter(|finding| finding.severity == Severity::Error) {
+                add("rule", finding.rule_id.clone());
+            }
+            for violation in &report.architecture_violations {
+                add("architecture", format!("{} → {}", violation.from_layer, violation.to_layer));
+            }
+        }
+
+        repositories.sort_by(|a, b| a.maintainability_score.total_cmp(&b.maintainability_score).then(a.name.cmp(&b.name)));
+        let mut languages: Vec<LanguageTotal> = languages.into_values().collect();
+        languages.sort_by_key(|language| std::cmp::Reverse(language.lines_of_code));
+        worst_files.sort_by(|a, b| a.index.total_cmp(&b.index));
+        worst_files.truncate(self.worst_files);
+        let mut common_findings: Vec<CommonFinding> = findings.into_values().collect();
+        common_findings.sort_by_key(|finding| (std::cmp::Reverse(finding.repositories.len()), std::cmp::Reverse(finding.occurrences)));
+
+        Aggregate { repositories, languages, worst_files, common_findings }
+    }
+
+    /// Project names, with the report's directory added where two share a name
+    fn names(reports: &[(PathBuf, Report)]) -> Vec<String> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for (_, report) in reports {
+            *counts.entry(&report.metadata.project_name).or_insert(0) += 1;
+        }
+        reports.iter().map(|(path, report)| {
+            let name = &report.metadata.project_name;
+            if counts[name.as_str()] > 1 {
+                format!("{} ({})", name, path.parent().unwrap_or(path).display())
+            } else {
+                name.clone()
+            }
+        }).collect()
+    }
+}
+
+impl Aggregate {
+    pub fn print(&self) {
+        println!("\n🏢 {} repositories, {} lines of code", self.repositories.len(),
+            self.repositories.iter().map(|repo| repo.lines_of_code).sum::<usize>());
+        for repo in &self.repositories {
+            println!("  {:<32} maintainability {:>4.1}/10  complexity {:>4.1}/10  {} critical, {} high",
+                repo.name, repo.maintainability_score, repo.complexity_score, repo.critical_recommendations, repo.high_recommendations);
+        }
+        let shared = self.common_findings.iter().filter(|finding| finding.repositories.len() > 1).count();
+        if shared > 0 {
+            println!("  🔁 {} critical finding(s) recur across repositories", shared);
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# Organization Overview\n\n{} repositories, {} files, {} lines of code.\n\n",
+            self.repositories.len(),
+            self.repositories.iter().map(|repo| repo.files).sum::<usize>(),
+            self.repositories.iter().map(|repo| repo.lines_of_code).sum::<usize>());
+
+        md.push_str("## Repositories\n\nLeast maintainable first.\n\n");
+        md.push_str("| Repository | Files | LOC | Maintainability | Complexity | Avg Index | Red Files | Docs | Cycles | Critical | High | Rule Errors |\n");
+        md.push_str("|------------|-------|-----|-----------------|------------|-----------|-----------|------|--------|----------|------|-------------|\n");
+        for repo in &self.repositories {
+            md.push_str(&format!("| {} | {} | {} | {:.1}/10 | {:.1}/10 | {:.1} | {} | {:.0}% | {} | {} | {} | {} |\n",
+                repo.name, repo.files, repo.lines_of_code, repo.maintainability_score, repo.complexity_score,
+                repo.avg_maintainability_index, repo.red_files, repo.documentation_coverage, repo.dependency_cycles,
+                repo.critical_recommendations, repo.high_recommendations, repo.rule_errors));
+        }
+
+        md.push_str("\n## Languages\n\n| Language | Repositories | Files | LOC |\n|----------|--------------|-------|-----|\n");
+        for language in &self.languages {
+            md.push_str(&format!("| {} | {} | {} | {} |\n", language.language, language.repositories, language.files, language.lines_of_code));
+        }
+
+        if !self.worst_files.is_empty() {
+            md.push_str("\n## Least Maintainable Files\n\n| Repository | File | Index | LOC | Cyclomatic |\n|------------|------|-------|-----|------------|\n");
+            for file in &self.worst_files {
+                md.push_str(&format!("| {} | `{}` | {:.1} | {} | {} |\n",
+                    file.repository, file.path.display(), file.index, file.lines_of_code, file.cyclomatic_complexity));
+            }
+        }
+
+        if !self.common_findings.is_empty() {
+            md.push_str("\n## Common Critical Findings\n\nCritical and high recommendations, error-level rule findings and architecture violations, by how many repositories share them.\n\n");
+            md.push_str("| Kind | Finding | Repositories | Occurrences |\n|------|---------|--------------|-------------|\n");
+            for finding in &self.common_findings {
+                md.push_str(&format!("| {} | {} | {} | {} |\n",
+                    finding.kind, finding.title.replace('|', "\\|"), finding.repositories.join(", "), finding.occurrences));
+            }
+        }
+        md
+    }
+
+    pub fn to_html(&self) -> String {
+        let sortable = |headers: &[&str]| -> String {
+            let cells: String = headers.iter().map(|header| format!("<th onclick=\"sortTable(this)\">{}</th>", header)).collect();
+            format!("<table class=\"sortable\"><tr>{}</tr>", cells)
+        };
+        let mut body = format!("<h1>Organization Overview</h1><p>{} repositories, {} files, {} lines of code. Click a column to sort.</p>",
+            self.repositories.len(),
+            self.repositories.iter().map(|repo| repo.files).sum::<usize>(),
+            self.repositories.iter().map(|repo| repo.lines_of_code).sum::<usize>());
+
+        body.push_str("<div class=\"section\"><h2>Repositories</h2>");
+        body.push_str(&sortable(&["Repository", "Files", "LOC", "Maintainability", "Complexity", "Avg Index", "Red Files", "Docs %", "Cycles", "Critical", "High", "Rule Errors"]));
+        for repo in &self.repositories {
+            body.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.1}</td><td>{:.1}</td><td>{:.1}</td><td>{}</td><td>{:.0}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_xml(&repo.name), repo.files, repo.lines_of_code, repo.maintainability_score, repo.complexity_score,
+                repo.avg_maintainability_index, repo.red_files, repo.documentation_coverage, repo.dependency_cycles,
+                repo.critical_recommendations, repo.high_recommendations, repo.rule_errors));
+        }
+        body.push_str("</table></div>");
+
+        body.push_str("<div class=\"section\"><h2>Languages</h2>");
+        body.push_str(&sortable(&["Language", "Repositories", "Files", "LOC"]));
+        for language in &self.languages {
+            body.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_xml(&language.language), language.repositories, language.files, language.lines_of_code));
+        }
+        body.push_str("</table></div>");
+
+        if !self.worst_files.is_empty() {
+            body.push_str("<div class=\"section\"><h2>Least Maintainable Files</h2>");
+            body.push_str(&sortable(&["Repository", "File", "Index", "LOC", "Cyclomatic"]));
+            for file in &self.worst_files {
+                body.push_str(&format!("<tr class=\"rating-{}\"><td>{}</td><td>{}</td><td>{:.1}</td><td>{}</td><td>{}</td></tr>",
+                    file.rating.name(), escape_xml(&file.repository), escape_xml(&file.path.to_string_lossy()),
+                    file.index, file.lines_of_code, file.cyclomatic_complexity));
+            }
+            body.push_str("</table></div>");
+        }
+
+        if !self.common_findings.is_empty() {
+            body.push_str("<div class=\"section\"><h2>Common Critical Findings</h2>");
+            body.push_str(&sortable(&["Kind", "Finding", "Repositories", "Occurrences"]));
+            for finding in &self.common_findings {
+                body.push_str(&format!("<tr><td>{}</td><td>{}</td><td title=\"{}\">{}</td><td>{}</td></tr>",
+                    finding.kind, escape_xml(&finding.title), escape_xml(&finding.repositories.join(", ")),
+                    finding.repositories.len(), finding.occurrences));
+            }
+            body.push_str("</table></div>");
+        }
+
+        html_layout::page("Organization Overview", &body)
+    }
+
+    /// Write `aggregate.json`, `aggregate.md` and `aggregate.html` to the output directory
+    pub fn export(&self, output_dir: &Path) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+
+        let json_path = output_dir.join("aggregate.json");
+        fs::write(&json_path, serde_json::to_string_pretty(self)?).map_err(|e| Error::report(&json_path, e))?;
+
+        let md_path = output_dir.join("aggregate.md");
+        fs::write(&md_path, self.to_markdown()).map_err(|e| Error::report(&md_path, e))?;
+
+        let html_path = output_dir.join("aggregate.html");
+        fs::write(&html_path, self.to_html()).map_err(|e| Error::report(&html_path, e))?;
+
+        Ok(vec![json_path, md_path, html_path])
+    }
+}
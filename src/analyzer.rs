@@ -1,158 +1,1192 @@
 use crate::{
-    config::Config,
-    dependency_graph::{DependencyGraph, GraphBuilder},
-    file_discovery::{FileDiscovery, FileInfo},
-    llm::{AnalysisRequest, AnalysisContext, AnalysisType, FileContext, DependencyContext, ProjectInfo, LLMClient, AnalysisResponse, DocumentationContext},
-    simple_parser::{SimpleParser, ParsedFile},
+    architecture::{ArchitectureChecker, ArchitectureViolation, LazyBoundary},
+    components::{ComponentAnalysis, ComponentMapper},
+    boundaries::{BoundaryChecker, BoundaryViolation},
+    bus_factor::{BusFactorAnalyzer, ModuleBusFactor},
+    change_coupling::{ChangeCouplingAnalysis, ChangeCouplingAnalyzer},
+    config::{Audience, Config},
+    dependency_graph::{FileDependency, GraphBuilder},
+    file_discovery::{Discovery, FileDiscovery, FileInfo, SkippedFile},
+    registry::RegistryCheck,
+    osv::VulnerabilityScan,
+    llm::{AnalysisRequest, AnalysisContext, AnalysisType, FileContext, DependencyContext, ProjectInfo, LLMClient, AnalysisResponse, EvidenceStatus, DocumentationContext, SourceExcerpt, estimate_tokens},
+    debt::{DebtCollector, DebtItem},
+    doc_coverage::DocCoverageAnalyzer,
+    doc_drift::{DocDrift, DocDriftAnalyzer},
+    concurrency::{ConcurrencyInventory, ConcurrencyScanner},
+    metaprogramming::{MetaprogrammingInventory, MetaprogrammingScanner},
+    data_access::{DataAccess, DataAccessAnalyzer},
+    perf_signals::{PerformanceSignal, PerformanceSignalDetector},
+    process_health::{ProcessHealth, ProcessHealthAnalyzer},
+    testing::{TestingAnalyzer, TestingSummary},
+    docker::{DockerImage, DockerfileAnalyzer},
+    infrastructure::{InfrastructureAnalyzer, InfrastructureInventory},
+    assets::{AssetAnalyzer, AssetInventory},
+    endpoints::{EndpointInventory, EndpointScanner},
+    contracts::{ContractAnalyzer, ContractInventory},
+    frontend_routes::{FrontendRouteInventory, FrontendRouteScanner},
+    i18n::{I18nAnalyzer, I18nReport},
+    explain::FileExplainer,
+    module_guide::{ModuleSummarizer, ModuleSummary},
+    onboarding::{OnboardingAnalyzer, OnboardingGuide},
+    ownership::{FileOwnership, OwnershipResolver},
+    proposed_changes::ProposedChangeValidator,
+    grounding::{DiscardedItem, FileResolver, Guardrails},
+    review::ReviewPass,
+    perf::{ParseSample, PerfRecorder, PerfReport},
+    pipeline::{DiscoveryArtifact, GraphArtifact, LlmFindings, LlmProgress, LocalFindings, ParseArtifact, Pipeline, Stage},
+    progress::{AnalyzeOptions, CancellationToken, ProgressEvent, ProgressSink},
+    parse_cache::{FileStamp, ParseCache},
+    redaction::{DataSentEntry, Redactor},
+    rules::{RuleEngine, RuleFinding},
+    run_stats::RunStats,
+    sampling::{FileSampler, SamplingSummary},
+    smells::{CodeSmell, SmellDetector},
+    cohesion::{ClassCohesion, CohesionAnalyzer},
+    simple_parser::{SimpleParser, ParsedFile, ParseError, ParseErrorKind},
+    sources::{IoLimiter, SourceEncoding, SourceSet},
 };
-use anyhow::Result;
+use crate::{Error, Result};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::mpsc::UnboundedSender;
+#[cfg(feature = "llm")]
+use crate::{manifests::ManifestScanner, osv::OsvScanner, registry::RegistryChecker};
+
+/// `println!` unless the analyzer is quiet
+macro_rules! say {
+    ($analyzer:expr) => {
+        if !$analyzer.quiet {
+            println!();
+        }
+    };
+    ($analyzer:expr, $($arg:tt)*) => {
+        if !$analyzer.quiet {
+            println!($($arg)*);
+        }
+    };
+}
 
 pub struct Analyzer {
     config: Config,
     file_discovery: FileDiscovery,
     llm_client: LLMClient,
+    rule_engine: RuleEngine,
+    architecture: ArchitectureChecker,
+    components: ComponentMapper,
+    pipeline: Pipeline,
+    perf: PerfRecorder,
+    /// Keeps sensitive files and values out of LLM context
+    redactor: Redactor,
+    /// Runs parsing and scanning with `analysis.threads` workers
+    pool: rayon::ThreadPool,
+    run_stats: RunStats,
+    /// Files that failed to parse since the last analysis was returned
+    parse_errors: Vec<ParseError>,
+    /// Parses kept across analyses when running inside the daemon
+    parse_cache: Option<Arc<ParseCache>>,
+    /// Print a line per parsed file
+    progress: bool,
+    /// Print nothing to stdout, for embedders following `events` instead
+    quiet: bool,
+    cancel: CancellationToken,
+    events: ProgressSink,
 }
 
 impl Analyzer {
-    pub fn new(config: Config, debug_llm: bool) -> Result<Self> {
+    pub fn new(mut config: Config, debug_llm: bool) -> Result<Self> {
+        config.enforce_offline()?;
         let file_discovery = FileDiscovery::new(config.clone());
-        let llm_client = LLMClient::new(config.llm.clone(), debug_llm);
+        let redactor = Redactor::new(&config.redaction)?;
+        let llm_client = LLMClient::new(config.llm.clone(), debug_llm)
+            .with_redactor(redactor.clone())
+            .with_offline(config.offline.enabled);
+        let rule_engine = RuleEngine::new(&config.target_directory, &config.rules)?;
+        let architecture = ArchitectureChecker::new(&config.target_directory, &config.architecture)?;
+        let components = ComponentMapper::new(&config.target_directory, &config.components)?;
+        let pipeline = Pipeline::new(&config.pipeline)?;
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(config.analysis.threads)
+            .build()?;
 
         Ok(Self {
             config,
             file_discovery,
             llm_client,
+            rule_engine,
+            architecture,
+            components,
+            pipeline,
+            perf: PerfRecorder::new(),
+            redactor,
+            pool,
+            run_stats: RunStats::new(),
+            parse_errors: Vec::new(),
+            parse_cache: None,
+            progress: true,
+            quiet: false,
+            cancel: CancellationToken::default(),
+            events: ProgressSink::default(),
         })
     }
 
-    pub async fn analyze_project(&mut self, skip_llm: bool) -> Result<ProjectAnalysis> {
-        println!("🔍 Discovering files...");
+    /// Reuse parses of unchanged files from earlier analyses and keep this run's parses
+    pub fn with_parse_cache(mut self, cache: Arc<ParseCache>) -> Self {
+        self.parse_cache = Some(cache);
+        self
+    }
+
+    /// Checkpoint each pipeline stage's output under `dir`; with `resume`,
+    /// restore the stages an interrupted run of the same configuration completed
+    pub fn with_checkpoints(mut self, dir: PathBuf, resume: bool) -> Result<Self> {
+        self.pipeline = self.pipeline.with_checkpoints(dir, &self.config, resume)?;
+        Ok(self)
+    }
+
+    pub fn pipeline(&self) -> &Pipeline {
+        &self.pipeline
+    }
+
+    /// Stop runs with `Error::Cancelled` at the next file, pass or LLM call once
+    /// `cancel` is cancelled, e.g. by a task watching for Ctrl-C or a deadline;
+    /// `analyze_project_with` uses its own token for the run it starts
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Parse without printing a line per file, for commands whose stdout is their result
+    pub fn without_progress(mut self) -> Self {
+        self.progress = false;
+        self
+    }
+
+    /// Stage timings and parse throughput recorded by the analyses run so far
+    pub fn perf_report(&self) -> PerfReport {
+        self.perf.report()
+    }
+
+    /// File, parse error and timing counts for the analyses run so far
+    pub fn run_stats(&self) -> RunStats {
+        let perf = self.perf.report();
+        RunStats {
+            stages: perf.stages,
+            total_ms: perf.total_ms,
+            ..self.run_stats.clone()
+        }
+    }
+
+    /// What an interrupted run got through, from its stage checkpoints: the
+    /// completed stages and the LLM analyses that succeeded. `None` until
+    /// discover, parse and graph have completed.
+    pub fn partial_analysis(&self) -> Option<(ProjectAnalysis, Vec<Stage>)> {
+        let llm: Option<LlmFindings> = self.pipeline.load(Stage::Llm);
+        let llm_completed = llm.is_some();
+        let state = PipelineState {
+            discovery: self.pipeline.load(Stage::Discover),
+            parse: self.pipeline.load(Stage::Parse),
+            graph: self.pipeline.load(Stage::Graph),
+            local: self.pipeline.load(Stage::LocalPasses),
+            llm: llm.or_else(|| {
+                self.pipeline.load_llm_progress().map(|progress| LlmFindings {
+                    llm_enabled: true,
+                    llm_analysis: progress.responses,
+                    data_sent: progress.data_sent,
+                    ..Default::default()
+                })
+            }),
+        };
+        if state.discovery.is_none() || state.parse.is_none() || state.graph.is_none() {
+            return None;
+        }
+        let completed = self.pipeline.stages().iter()
+            .filter(|stage| match stage {
+                Stage::LocalPasses => state.local.is_some(),
+                Stage::Llm => llm_completed,
+                Stage::Report => false,
+                _ => true,
+            })
+            .copied()
+            .collect();
+        Some((state.into_analysis(&self.config), completed))
+    }
+
+    /// Run discovery, parsing and graph building, then build every LLM request
+    /// that a full analysis would send, without making any network calls.
+    pub fn plan_llm_analysis(&mut self) -> Result<Vec<PlannedLLMCall>> {
+        say!(self, "🔍 Discovering files...");
         let files = self.file_discovery.discover_files()?;
         let stats = self.file_discovery.get_stats(&files);
-        stats.print_summary();
+        if !self.quiet {
+            stats.print_summary();
+        }
+        let DiscoveryArtifact { files, .. } = self.select_files(files, Vec::new(), &SourceSet::Disk);
 
-        println!("\n📝 Parsing files...");
-        let parsed_files = self.parse_files_parallel(&files)?;
+        say!(self, "\n📝 Parsing files...");
+        let parsed_files = self.parse_files_parallel(&files, &SourceSet::Disk)?;
 
-        println!("\n🕸️  Building dependency graph...");
-        let mut graph_builder = GraphBuilder::new();
-        let graph = graph_builder.build_graph(&parsed_files);
-        
-        // Clone the graph and get analysis before using in async function
-        let graph_copy = graph.clone();
-        let graph_analysis = graph_builder.analyze_dependencies();
-        graph_analysis.print_summary();
+        say!(self, "\n🕸️  Building dependency graph...");
+        let mut graph_builder = GraphBuilder::new()
+            .with_max_component_size(self.config.analysis.max_component_size);
+        graph_builder.build_graph(&parsed_files);
+
+        let file_dependencies = graph_builder.file_dependencies();
+
+        say!(self, "\n🔬 Running local passes...");
+        let local = self.run_offline_passes(&files, &parsed_files, &file_dependencies, &SourceSet::Disk)?;
+        let context = self.create_analysis_context(&parsed_files, &files, &local, &SourceSet::Disk);
+
+        let mut plan: Vec<PlannedLLMCall> = self.create_llm_requests(&context).into_iter().map(|(name, request)| {
+            let (system_prompt, user_prompt) = self.llm_client.build_prompts(&request);
+            self.planned_call(name.to_string(), system_prompt, user_prompt)
+        }).collect();
+
+        if self.config.llm.enable_review_pass {
+            if let Some((_, request)) = self.create_llm_requests(&context).first() {
+                let review = ReviewPass::new();
+                let context_prompt = self.llm_client.context_prompt(request);
+                plan.push(self.planned_call("Critic review".to_string(), review.system_prompt().to_string(), review.build_prompt(&[], &context_prompt)));
+            }
+        }
+
+        if self.config.analysis.summarize_modules {
+            let summarizer = ModuleSummarizer::new();
+            for (i, prompt) in summarizer.build_prompts(&parsed_files).into_iter().enumerate() {
+                plan.push(self.planned_call(format!("Module summary batch {}", i + 1), summarizer.system_prompt().to_string(), prompt));
+            }
+        }
+
+        Ok(plan)
+    }
+
+    fn planned_call(&self, name: String, system_prompt: String, user_prompt: String) -> PlannedLLMCall {
+        let DataSentEntry { system_prompt, user_prompt, .. } = self.llm_client.redact_prompts(&system_prompt, &user_prompt);
+        let input_tokens = estimate_tokens(&system_prompt) + estimate_tokens(&user_prompt);
+        PlannedLLMCall {
+            name,
+            system_prompt,
+            user_prompt,
+            input_tokens,
+            max_output_tokens: self.config.llm.max_tokens,
+        }
+    }
+
+    pub async fn analyze_project(&mut self, skip_llm: bool) -> Result<ProjectAnalysis> {
+        self.run_pipeline(PipelineState::default(), &SourceSet::Disk, skip_llm).await
+    }
+
+    /// `analyze_project` for GUIs and editors: `progress` receives an event per
+    /// stage, parsed file, local pass and LLM analysis, and cancelling `cancel`
+    /// stops the run with `Error::Cancelled` at the next file, pass or LLM call.
+    /// With checkpoints enabled, a cancelled run resumes where it stopped. The
+    /// sender is dropped when the run ends, which closes the receiver.
+    ///
+    /// Only the LLM stage awaits; discovery, parsing, the graph and the local
+    /// passes run on the calling thread (parsing fans out to the analyzer's
+    /// own thread pool) and block it until they finish. Call this from a
+    /// dedicated thread, or from `spawn_blocking` with `Handle::block_on`,
+    /// rather than from a task sharing an executor with a UI.
+    pub async fn analyze_project_with(
+        &mut self,
+        options: AnalyzeOptions,
+        cancel: CancellationToken,
+        progress: UnboundedSender<ProgressEvent>,
+    ) -> Result<ProjectAnalysis> {
+        let quiet = std::mem::replace(&mut self.quiet, options.quiet);
+        self.llm_client.set_quiet(options.quiet);
+        self.file_discovery.set_quiet(options.quiet);
+        let previous_cancel = std::mem::replace(&mut self.cancel, cancel);
+        self.events = ProgressSink::new(Some(progress));
+
+        let result = self.run_pipeline(PipelineState::default(), &SourceSet::Disk, options.skip_llm).await;
+
+        self.events = ProgressSink::default();
+        self.cancel = previous_cancel;
+        self.quiet = quiet;
+        self.llm_client.set_quiet(quiet);
+        self.file_discovery.set_quiet(quiet);
+        result
+    }
+
+    /// Discover and parse the target directory and stop there, for commands that
+    /// only need the parser's output
+    pub fn parse_project(&mut self) -> Result<Vec<ParsedFile>> {
+        let Discovery { files, .. } = self.file_discovery.discover()?;
+        self.run_stats.record_discovery(files.len(), &[]);
+        self.parse_files_parallel(&files, &SourceSet::Disk)
+    }
+
+    /// Ask the LLM for a detailed walkthrough of one file, sent in full with the
+    /// signatures of the files it imports directly. Returns the Markdown reply.
+    pub async fn explain_file(&mut self, path: &Path) -> Result<String> {
+        if !self.config.llm.is_enabled() {
+            return Err(Error::Config("explain needs an LLM provider; set llm.provider in the configuration".to_string()));
+        }
+        let Some(file) = self.file_discovery.files_from_list(&[path.to_path_buf()])?.pop() else {
+            return Err(Error::Discovery(format!("{} is not a file the analysis includes (see ignore_patterns and file_extensions)", path.display())));
+        };
+        if self.is_sensitive(&file.path) {
+            return Err(Error::Config(format!("{} matches redaction.sensitive_paths and is never sent to an LLM", path.display())));
+        }
+        let parsed = self.parse_files_parallel(std::slice::from_ref(&file), &SourceSet::Disk)?.pop()
+            .ok_or_else(|| Error::parse(path,
+                self.parse_errors.drain(..).map(|e| e.message).collect::<Vec<_>>().join("; ")))?;
+
+        let mut dependency_paths: Vec<PathBuf> = parsed.imports.iter()
+            .filter_map(|import| self.file_discovery.resolve_import(&file.path, &import.module))
+            .filter(|dependency| *dependency != file.path)
+            .collect();
+        dependency_paths.sort();
+        dependency_paths.dedup();
+        let dependencies: Vec<FileInfo> = self.file_discovery.files_from_list(&dependency_paths)?.into_iter()
+            .filter(|dependency| !self.is_sensitive(&dependency.path))
+            .collect();
+        let parsed_dependencies = self.parse_files_parallel(&dependencies, &SourceSet::Disk)?;
+        self.parse_errors.clear();
+
+        let relative = |path: &Path| path.strip_prefix(&self.config.target_directory).unwrap_or(path).to_path_buf();
+        let dependencies: Vec<(PathBuf, &ParsedFile)> = parsed_dependencies.iter()
+            .map(|pf| (relative(&pf.file_info.path), pf))
+            .collect();
+        let content = self.redactor.redact_source(&SourceSet::Disk.read(&file.path)?);
+        let explainer = FileExplainer::new();
+        let prompt = explainer.build_prompt(&parsed, &relative(&file.path), &content, &dependencies);
+        self.llm_client.complete(explainer.system_prompt(), &prompt).await
+    }
+
+    /// Analyze files whose contents are already in memory, skipping discovery.
+    /// Paths are taken as given (relative to the configured target directory);
+    /// nothing is read from disk and git blame is not consulted.
+    pub async fn analyze_files(&mut self, files: Vec<(PathBuf, String)>, skip_llm: bool) -> Result<ProjectAnalysis> {
+        let file_infos = files.iter()
+            .map(|(path, content)| self.file_discovery.describe_file(path, content.len() as u64))
+            .collect();
+        let sources = SourceSet::in_memory(files);
+
+        // The given files stand in for the discover stage
+        let discovery = self.select_files(file_infos, Vec::new(), &sources);
+        let state = PipelineState { discovery: Some(discovery), ..Default::default() };
+        self.run_pipeline(state, &sources, skip_llm).await
+    }
+
+    /// Analyze exactly the listed files plus the files they import directly,
+    /// without walking the target directory
+    pub async fn analyze_file_list(&mut self, paths: &[PathBuf], skip_llm: bool) -> Result<ProjectAnalysis> {
+        say!(self, "📋 Reading {} listed file(s)...", paths.len());
+        let mut files = self.file_discovery.files_from_list(paths)?;
+
+        say!(self, "\n📝 Parsing files...");
+        let mut parsed_files = self.parse_files_parallel(&files, &SourceSet::Disk)?;
+
+        let listed: std::collections::HashSet<PathBuf> = files.iter().map(|f| f.path.clone()).collect();
+        let mut dependency_paths: Vec<PathBuf> = parsed_files.iter()
+            .flat_map(|pf| pf.imports.iter().filter_map(|import| {
+                self.file_discovery.resolve_import(&pf.file_info.path, &import.module)
+            }))
+            .filter(|path| !listed.contains(path))
+            .collect();
+        dependency_paths.sort();
+        dependency_paths.dedup();
+
+        let dependencies = self.file_discovery.files_from_list(&dependency_paths)?;
+        say!(self, "\n🔗 Including {} direct dependenc{}", dependencies.len(), if dependencies.len() == 1 { "y" } else { "ies" });
+        parsed_files.extend(self.parse_files_parallel(&dependencies, &SourceSet::Disk)?);
+        files.extend(dependencies);
+        self.run_stats.record_discovery(files.len(), &[]);
+        self.run_stats.files_analyzed += files.len();
+
+        // Listing and parsing the files stand in for the discover and parse stages
+        let state = PipelineState {
+            discovery: Some(DiscoveryArtifact { files, ..Default::default() }),
+            parse: Some(ParseArtifact { parsed_files, parse_errors: std::mem::take(&mut self.parse_errors) }),
+            ..Default::default()
+        };
+        self.run_pipeline(state, &SourceSet::Disk, skip_llm).await
+    }
+
+    /// Run the `[pipeline]` stages in order, skipping those whose output `state`
+    /// already holds. The report stage is left to the caller.
+    async fn run_pipeline(&mut self, mut state: PipelineState, sources: &SourceSet, skip_llm: bool) -> Result<ProjectAnalysis> {
+        if let Some(notice) = self.pipeline.notice() {
+            say!(self, "{}", notice);
+        }
+        let llm_enabled = !skip_llm && !self.config.analysis.low_memory && self.config.llm.is_enabled();
+        let disabled: Vec<&str> = Stage::ALL.iter()
+            .filter(|stage| !self.pipeline.is_enabled(**stage))
+            .map(Stage::name)
+            .collect();
+        if !disabled.is_empty() {
+            say!(self, "⏭️  Pipeline stages disabled: {}", disabled.join(", "));
+        }
+
+        const ORDER: &str = "stage order is validated by Pipeline::new";
+        for stage in self.pipeline.stages().to_vec() {
+            self.check_cancelled()?;
+            match stage {
+                Stage::Discover if state.discovery.is_none() => {
+                    if let Some(discovery) = self.pipeline.restore::<DiscoveryArtifact>(stage) {
+                        say!(self, "♻️  {}: restored {} files from checkpoint", stage.name(), discovery.files.len());
+                        self.run_stats.record_discovery(discovery.files.len(), &discovery.skipped);
+                        self.run_stats.files_analyzed += discovery.files.len();
+                        state.discovery = Some(discovery);
+                        self.events.send(ProgressEvent::StageRestored { stage });
+                        continue;
+                    }
+                    let started = self.start_stage(stage);
+                    let discovery = self.discover()?;
+                    self.pipeline.save(stage, &discovery)?;
+                    self.finish_stage(stage, started);
+                    state.discovery = Some(discovery);
+                }
+                Stage::Parse if state.parse.is_none() => {
+                    if let Some(parse) = self.pipeline.restore(stage) {
+                        say!(self, "♻️  {}: restored from checkpoint", stage.name());
+                        state.parse = Some(parse);
+                        self.events.send(ProgressEvent::StageRestored { stage });
+                        continue;
+                    }
+                    let files = &state.discovery.as_ref().expect(ORDER).files;
+                    say!(self, "\n📝 Parsing files...");
+                    let started = self.start_stage(stage);
+                    let parsed_files = self.parse_files_parallel(files, sources)?;
+                    self.perf.record_stage("parse", started);
+                    let parse = ParseArtifact { parsed_files, parse_errors: std::mem::take(&mut self.parse_errors) };
+                    self.pipeline.save(stage, &parse)?;
+                    self.finish_stage(stage, started);
+                    state.parse = Some(parse);
+                }
+                Stage::Graph if state.graph.is_none() => {
+                    if let Some(graph) = self.pipeline.restore(stage) {
+                        say!(self, "♻️  {}: restored from checkpoint", stage.name());
+                        state.graph = Some(graph);
+                        self.events.send(ProgressEvent::StageRestored { stage });
+                        continue;
+                    }
+                    let started = self.start_stage(stage);
+                    let graph = self.build_graph(&state.parse.as_ref().expect(ORDER).parsed_files);
+                    self.pipeline.save(stage, &graph)?;
+                    self.finish_stage(stage, started);
+                    state.graph = Some(graph);
+                }
+                Stage::LocalPasses if state.local.is_none() => {
+                    if let Some(local) = self.pipeline.restore(stage) {
+                        say!(self, "♻️  {}: restored from checkpoint", stage.name());
+                        state.local = Some(local);
+                        self.events.send(ProgressEvent::StageRestored { stage });
+                        continue;
+                    }
+                    let started = self.start_stage(stage);
+                    let files = &state.discovery.as_ref().expect(ORDER).files;
+                    let parsed_files = &state.parse.as_ref().expect(ORDER).parsed_files;
+                    let file_dependencies = &state.graph.as_ref().expect(ORDER).file_dependencies;
+                    let local = self.run_local_passes(files, parsed_files, file_dependencies, sources).await?;
+                    self.pipeline.save(stage, &local)?;
+                    self.finish_stage(stage, started);
+                    state.local = Some(local);
+                }
+                Stage::Llm if state.llm.is_none() => {
+                    if let Some(llm) = self.pipeline.restore(stage) {
+                        say!(self, "♻️  {}: restored from checkpoint", stage.name());
+                        state.llm = Some(llm);
+                        self.events.send(ProgressEvent::StageRestored { stage });
+                        continue;
+                    }
+                    let started = self.start_stage(stage);
+                    let files = &state.discovery.as_ref().expect(ORDER).files;
+                    let parsed_files = &state.parse.as_ref().expect(ORDER).parsed_files;
+                    // The local-passes stage may be disabled, leaving its findings out of the prompts
+                    let no_findings = LocalFindings::default();
+                    let local = state.local.as_ref().unwrap_or(&no_findings);
+                    let llm = if llm_enabled {
+                        say!(self, "\n🤖 Analyzing with LLM...");
+                        self.run_llm(files, parsed_files, local, sources).await?
+                    } else {
+                        say!(self, "\n⚡ Skipping LLM analysis (local-only mode)");
+                        LlmFindings::default()
+                    };
+                    // Failed analysis types are retried on resume; the ones that
+                    // succeeded are already in the llm progress checkpoint
+                    if llm.failed_analyses.is_empty() {
+                        self.pipeline.save(stage, &llm)?;
+                    }
+                    self.finish_stage(stage, started);
+                    state.llm = Some(llm);
+                }
+                _ => {}
+            }
+        }
+
+        if self.config.analysis.low_memory {
+            if let Some(peak) = peak_memory_mb() {
+                say!(self, "\n🪶 Peak memory: {} MB", peak);
+            }
+        }
+        Ok(state.into_analysis(&self.config))
+    }
+
+    fn check_cancelled(&self) -> Result<()> {
+        if self.cancel.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+        Ok(())
+    }
+
+    fn start_stage(&self, stage: Stage) -> Instant {
+        self.events.send(ProgressEvent::StageStarted { stage });
+        Instant::now()
+    }
+
+    fn finish_stage(&self, stage: Stage, started: Instant) {
+        self.events.send(ProgressEvent::StageFinished { stage, duration_ms: started.elapsed().as_millis() as u64 });
+    }
+
+    /// A local pass is starting; stops the run first if it was cancelled
+    fn start_pass(&self, pass: &str) -> Result<()> {
+        self.check_cancelled()?;
+        self.events.send(ProgressEvent::PassStarted { pass: pass.to_string() });
+        Ok(())
+    }
+
+    /// The discover stage: walk the target directory and select the files to analyze
+    fn discover(&mut self) -> Result<DiscoveryArtifact> {
+        say!(self, "🔍 Discovering files...");
+        let started = Instant::now();
+        let Discovery { files, skipped } = self.file_discovery.discover_with(&self.cancel)?;
+        self.perf.record_stage("discovery", started);
+        self.run_stats.record_discovery(files.len(), &skipped);
+        let stats = self.file_discovery.get_stats(&files);
+        if !self.quiet {
+            stats.print_summary();
+        }
+
+        Ok(self.select_files(files, skipped, &SourceSet::Disk))
+    }
+
+    /// Order the files for deterministic runs and apply `analysis.max_files`,
+    /// keeping the files chosen by `analysis.sampling`
+    fn select_files(&mut self, mut files: Vec<FileInfo>, skipped: Vec<SkippedFile>, sources: &SourceSet) -> DiscoveryArtifact {
+        if self.config.report.deterministic {
+            files.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+        let analysis = &self.config.analysis;
+        let sampler = FileSampler::new(&self.config.target_directory, analysis.max_files, analysis.sampling);
+        let (files, sampling) = self.pool.install(|| sampler.sample(files, sources));
+        if let Some(ref summary) = sampling {
+            say!(self, "  📉 Sampling {} (analysis.max_files = {})", summary, analysis.max_files);
+        }
+        self.run_stats.files_analyzed += files.len();
+        DiscoveryArtifact { files, skipped, sampling }
+    }
+
+    /// The graph stage
+    fn build_graph(&mut self, parsed_files: &[ParsedFile]) -> GraphArtifact {
+        say!(self, "\n🕸️  Building dependency graph...");
+        let started = Instant::now();
+        let mut graph_builder = GraphBuilder::new()
+            .with_max_component_size(self.config.analysis.max_component_size);
+        graph_builder.build_graph(parsed_files);
+        let graph = GraphArtifact {
+            dependency_analysis: graph_builder.analyze_dependencies(),
+            file_dependencies: graph_builder.file_dependencies(),
+        };
+        if !self.quiet {
+            graph.dependency_analysis.print_summary();
+        }
+        self.perf.record_stage("graph", started);
+        graph
+    }
+
+    /// The local-passes stage: everything that needs no LLM
+    async fn run_local_passes(&mut self, files: &[FileInfo], parsed_files: &[ParsedFile], file_dependencies: &[FileDependency], sources: &SourceSet) -> Result<LocalFindings> {
+        let mut local = self.run_offline_passes(files, parsed_files, file_dependencies, sources)?;
+        (local.registry_check, local.vulnerability_scan) = self.check_dependencies(files, parsed_files, sources).await?;
+        self.start_pass("components")?;
+        // Rolled up last so every finding above is counted
+        local.components = self.components.analyze(parsed_files, file_dependencies, &local);
+        if !local.components.is_empty() {
+            say!(self, "🧩 Components: {} component(s), {} dependenc{} between them, {} file(s) unassigned",
+                local.components.components.len(), local.components.dependencies.len(),
+                if local.components.dependencies.len() == 1 { "y" } else { "ies" }, local.components.unassigned_files);
+        }
+        Ok(local)
+    }
 
-        let llm_analysis = if skip_llm {
-            println!("\n⚡ Skipping LLM analysis (local-only mode)");
+    /// Every local pass except the registry and OSV lookups and the component
+    /// rollup, which `run_local_passes` adds; the dry run plans prompts from these
+    fn run_offline_passes(&mut self, files: &[FileInfo], parsed_files: &[ParsedFile], file_dependencies: &[FileDependency], sources: &SourceSet) -> Result<LocalFindings> {
+        let low_memory = self.config.analysis.low_memory;
+        let started = Instant::now();
+        self.start_pass("ownership")?;
+        let file_ownership = if self.config.ownership.enabled {
+            let mut ownership = self.config.ownership.clone();
+            ownership.git_blame &= !low_memory;
+            OwnershipResolver::new(&self.config.target_directory, &ownership, sources).resolve(files)
+        } else {
+            Vec::new()
+        };
+        self.start_pass("bus factor")?;
+        let bus_factor = if self.config.ownership.bus_factor && !sources.is_in_memory() {
+            BusFactorAnalyzer::new(&self.config.target_directory, self.config.ownership.history_commits).analyze(parsed_files)
+        } else {
             Vec::new()
+        };
+        self.start_pass("process health")?;
+        let process_health = if self.config.process.enabled && !sources.is_in_memory() {
+            ProcessHealthAnalyzer::new(&self.config.target_directory, &self.config.process)
+                .with_reference_time(self.config.report.reference_time())
+                .analyze()
+        } else {
+            ProcessHealth::default()
+        };
+        let single_owner = bus_factor.iter().filter(|module| module.bus_factor == 1).count();
+        if single_owner > 0 {
+            say!(self, "🚌 Bus factor: {} of {} module(s) mostly written by one person", single_owner, bus_factor.len());
+        }
+        self.start_pass("change coupling")?;
+        let change_coupling = if self.config.change_coupling.enabled && !sources.is_in_memory() {
+            ChangeCouplingAnalyzer::new(&self.config.target_directory, &self.config.change_coupling).analyze(parsed_files, file_dependencies)
         } else {
-            println!("\n🤖 Analyzing with LLM...");
-            self.analyze_with_llm(&parsed_files, &graph_copy, &files).await?
+            ChangeCouplingAnalysis::default()
         };
+        if !change_coupling.is_empty() {
+            say!(self, "🔗 Change coupling: {} file pair(s) change together without importing each other", change_coupling.pairs.len());
+        }
 
-        Ok(ProjectAnalysis {
-            files: files.clone(),
-            parsed_files,
-            dependency_analysis: graph_analysis,
+        self.start_pass("debt markers")?;
+        let debt_markers = DebtCollector::new(&self.config.target_directory)
+            .with_git_blame(!sources.is_in_memory() && !low_memory)
+            .with_reference_time(self.config.report.reference_time())
+            .collect(parsed_files);
+        self.start_pass("doc drift")?;
+        let doc_drift = if low_memory {
+            Vec::new()
+        } else {
+            DocDriftAnalyzer::new(&self.config.target_directory).analyze(files, parsed_files, sources)
+        };
+        self.start_pass("code smells")?;
+        let code_smells = SmellDetector::new(self.config.analysis.smells.clone()).detect(parsed_files);
+        self.start_pass("class cohesion")?;
+        let class_cohesion = CohesionAnalyzer::new().analyze(parsed_files, sources);
+        self.start_pass("performance signals")?;
+        let performance_signals = PerformanceSignalDetector::new().detect(parsed_files, sources);
+        self.start_pass("testing")?;
+        let testing = TestingAnalyzer::new(&self.config.target_directory).analyze(parsed_files, sources);
+
+        self.start_pass("rules")?;
+        let rule_findings = self.rule_engine.evaluate(parsed_files, file_dependencies, sources);
+        if !self.rule_engine.is_empty() {
+            say!(self, "\n📏 Custom rules: {} finding(s)", rule_findings.len());
+        }
+
+        self.start_pass("architecture")?;
+        let architecture_violations = self.architecture.check(file_dependencies);
+        if !self.architecture.is_empty() {
+            say!(self, "🏛️  Architecture conformance: {} violation(s)", architecture_violations.len());
+        }
+        let lazy_boundaries = self.architecture.lazy_boundaries(file_dependencies);
+        if !lazy_boundaries.is_empty() {
+            say!(self, "💤 Lazy loading: {} dynamic import(s) across {} module boundary(ies)",
+                lazy_boundaries.iter().map(|boundary| boundary.imports).sum::<usize>(), lazy_boundaries.len());
+        }
+
+        self.start_pass("package boundaries")?;
+        let boundaries = BoundaryChecker::new(&self.config.target_directory, &self.config.boundaries, files, sources);
+        let boundary_violations = boundaries.check(file_dependencies);
+        if boundaries.packages().len() > 1 {
+            say!(self, "🧱 Package boundaries: {} violation(s) across {} workspace packages", boundary_violations.len(), boundaries.packages().len());
+        }
+
+        let onboarding = (self.config.analysis.audience == Audience::Onboarding)
+            .then(|| OnboardingAnalyzer::new().analyze(parsed_files, file_dependencies));
+
+        self.start_pass("dockerfiles")?;
+        let docker_images = DockerfileAnalyzer::new().analyze(files, sources);
+        for image in &docker_images {
+            say!(self, "🐳 {}: {} file(s) shipped, {} not copied", image.dockerfile.display(), image.shipped_files.len(), image.unshipped_files);
+        }
+
+        self.start_pass("infrastructure")?;
+        let infrastructure = InfrastructureAnalyzer::new(&self.config.target_directory).analyze(files, sources);
+        if !infrastructure.is_empty() {
+            say!(self, "🏗️  Infrastructure: {} resource(s), {} module reference(s), {} risky setting(s)",
+                infrastructure.resources.len(), infrastructure.modules.len(), infrastructure.risks.len());
+        }
+
+        self.start_pass("assets")?;
+        let assets = AssetAnalyzer::new(&self.config.target_directory).analyze(files, sources);
+        if !assets.is_empty() {
+            say!(self, "🗃️  Assets: {} file(s), {} ({:.0}% of the repository), {} of at least 256 KiB",
+                assets.asset_files(), AssetInventory::format_size(assets.asset_bytes), assets.asset_share() * 100.0, assets.large_assets.len());
+            if assets.asset_dominated {
+                say!(self, "  ⚠️  Non-code assets make up most of the repository's size");
+            }
+        }
+
+        self.start_pass("endpoints")?;
+        let endpoints = EndpointScanner::new(&self.config.target_directory).analyze(files, sources);
+        if !endpoints.endpoints.is_empty() {
+            say!(self, "🔌 API endpoints: {} from {} spec(s) and route declarations", endpoints.endpoints.len(), endpoints.specs.len());
+        }
+
+        self.start_pass("contracts")?;
+        let contracts = ContractAnalyzer::new(&self.config.target_directory).analyze(files, parsed_files, sources);
+        if !contracts.is_empty() {
+            say!(self, "📜 Contracts: {} IDL file(s) with {} RPC(s), used by {} module(s)",
+                contracts.contracts.len(), contracts.rpc_count(), contracts.modules.len());
+        }
+
+        self.start_pass("frontend routes")?;
+        let frontend_routes = FrontendRouteScanner::new(&self.config.target_directory).analyze(files, parsed_files, file_dependencies, sources);
+        if !frontend_routes.is_empty() {
+            say!(self, "🧭 Frontend routes: {} page(s) and route(s) ({})", frontend_routes.routes.len(),
+                frontend_routes.frameworks().iter().map(|framework| framework.name()).collect::<Vec<_>>().join(", "));
+        }
+
+        self.start_pass("i18n")?;
+        let i18n = I18nAnalyzer::new(&self.config.target_directory).analyze(files, sources);
+        if !i18n.is_empty() {
+            say!(self, "🌐 i18n: {} locale(s) in {} bundle(s); {} missing and {} unused key(s), {} hardcoded string(s)",
+                i18n.locales().len(), i18n.bundles.len(), i18n.missing_count(), i18n.unused_count(), i18n.hardcoded_strings.len());
+        }
+
+        self.start_pass("data access")?;
+        let data_access = DataAccessAnalyzer::new(&self.config.target_directory).analyze(files, sources);
+        if !data_access.is_empty() {
+            say!(self, "🗄️  Data access: {} table(s) in migrations, {} file(s) with SQL", data_access.tables.len(), data_access.files.len());
+        }
+
+        self.start_pass("concurrency")?;
+        let concurrency = ConcurrencyScanner::new(&self.config.target_directory).analyze(files, sources);
+        if !concurrency.is_empty() {
+            say!(self, "🧵 Concurrency: {} primitive(s) in {} module(s)", concurrency.sites.len(), concurrency.hotspots.len());
+        }
+
+        self.start_pass("metaprogramming")?;
+        let metaprogramming = MetaprogrammingScanner::new(&self.config.target_directory).analyze(files, sources);
+        if !metaprogramming.is_empty() {
+            say!(self, "🪄 Metaprogramming: {} site(s) in {} module(s)", metaprogramming.sites.len(), metaprogramming.modules.len());
+        }
+
+        self.perf.record_stage("local", started);
+
+        Ok(LocalFindings {
+            file_ownership,
+            bus_factor,
+            process_health,
+            change_coupling,
+            registry_check: RegistryCheck::default(),
+            vulnerability_scan: VulnerabilityScan::default(),
+            debt_markers,
+            doc_drift,
+            code_smells,
+            class_cohesion,
+            performance_signals,
+            testing,
+            rule_findings,
+            architecture_violations,
+            lazy_boundaries,
+            boundary_violations,
+            docker_images,
+            infrastructure,
+            assets,
+            endpoints,
+            contracts,
+            frontend_routes,
+            i18n,
+            data_access,
+            concurrency,
+            metaprogramming,
+            onboarding,
+            components: ComponentAnalysis::default(),
+        })
+    }
+
+    /// Look declared dependency versions up on package registries and OSV.dev,
+    /// as configured
+    #[cfg(feature = "llm")]
+    async fn check_dependencies(&mut self, files: &[FileInfo], parsed_files: &[ParsedFile], sources: &SourceSet) -> Result<(RegistryCheck, VulnerabilityScan)> {
+        let dependencies = if self.config.registry.enabled || self.config.osv.enabled {
+            ManifestScanner::new(&self.config.target_directory).scan(files, sources)
+        } else {
+            Vec::new()
+        };
+        self.start_pass("registry")?;
+        let registry_check = if self.config.registry.enabled {
+            say!(self, "\n📦 Checking dependency versions against package registries...");
+            let started = Instant::now();
+            let now = self.config.report.reference_time()
+                .and_then(|time| chrono::DateTime::from_timestamp(time, 0))
+                .unwrap_or_else(chrono::Utc::now);
+            let check = RegistryChecker::new(&self.config.registry).check(&dependencies, now).await?;
+            say!(self, "  {} of {} dependencies outdated or yanked{}", check.outdated.len(), check.checked,
+                if check.failures.is_empty() { String::new() } else { format!(", {} lookup(s) failed", check.failures.len()) });
+            self.perf.record_stage("registry", started);
+            check
+        } else {
+            RegistryCheck::default()
+        };
+
+        self.start_pass("osv")?;
+        let vulnerability_scan = if self.config.osv.enabled {
+            say!(self, "\n🛡️  Checking dependency versions against OSV.dev...");
+            let started = Instant::now();
+            let scan = OsvScanner::new(&self.config.osv)
+                .scan(&dependencies, parsed_files, &self.config.target_directory).await?;
+            say!(self, "  {} known vulnerabilit{} in {} dependencies checked", scan.vulnerabilities.len(),
+                if scan.vulnerabilities.len() == 1 { "y" } else { "ies" }, scan.checked);
+            self.perf.record_stage("osv", started);
+            scan
+        } else {
+            VulnerabilityScan::default()
+        };
+        Ok((registry_check, vulnerability_scan))
+    }
+
+    #[cfg(not(feature = "llm"))]
+    async fn check_dependencies(&mut self, _files: &[FileInfo], _parsed_files: &[ParsedFile], _sources: &SourceSet) -> Result<(RegistryCheck, VulnerabilityScan)> {
+        if self.config.registry.enabled || self.config.osv.enabled {
+            say!(self, "\n⚠️  Skipping registry and OSV checks: project-examer was built without the `llm` feature");
+        }
+        Ok((RegistryCheck::default(), VulnerabilityScan::default()))
+    }
+
+    /// The llm stage: analysis types, guardrails and module summaries
+    async fn run_llm(&mut self, files: &[FileInfo], parsed_files: &[ParsedFile], local: &LocalFindings, sources: &SourceSet) -> Result<LlmFindings> {
+        let started = Instant::now();
+        let previous = self.pipeline.restore_llm_progress();
+        let (mut llm_analysis, mut discarded_llm_output, failed_analyses) =
+            self.analyze_with_llm(parsed_files, files, local, sources, &previous).await?;
+
+        if !llm_analysis.is_empty() {
+            let guardrails = Guardrails::new(&self.config.target_directory, files, parsed_files, sources);
+            let (mut lowered, mut discarded) = (0, 0);
+            for response in &mut llm_analysis {
+                let outcome = guardrails.check(response);
+                lowered += outcome.lowered;
+                discarded += outcome.discarded.len();
+                discarded_llm_output.extend(outcome.discarded);
+            }
+            if lowered + discarded > 0 {
+                say!(self, "  🛡️  LLM output checked against the project: {} insight(s) discarded, {} with lowered confidence",
+                    discarded, lowered);
+            }
+        }
+
+        let module_summaries = if self.config.analysis.summarize_modules {
+            say!(self, "\n📚 Summarizing modules...");
+            self.summarize_modules(parsed_files).await
+        } else {
+            Vec::new()
+        };
+        self.check_cancelled()?;
+        self.perf.record_stage("llm", started);
+
+        let mut data_sent = previous.data_sent;
+        data_sent.extend(self.llm_client.data_sent());
+        Ok(LlmFindings {
+            llm_enabled: true,
+            failed_analyses,
             llm_analysis,
+            discarded_llm_output,
+            module_summaries,
+            data_sent,
         })
     }
 
-    fn parse_files_parallel(&mut self, files: &[FileInfo]) -> Result<Vec<ParsedFile>> {
-        let chunk_size = std::cmp::max(1, files.len() / rayon::current_num_threads());
-        
-        Ok(files
-            .par_chunks(chunk_size)
-            .map(|chunk| {
-                let local_parser = SimpleParser::new().unwrap();
-                let mut parsed_files = Vec::new();
-                
-                for file_info in chunk {
-                    match local_parser.parse_file(file_info) {
-                        Ok(parsed_file) => {
-                            println!("  ✓ {}", file_info.path.display());
-                            parsed_files.push(parsed_file);
+    /// Parse files on the analyzer's thread pool, one task per file so work
+    /// stealing balances skewed file sizes
+    fn parse_files_parallel(&mut self, files: &[FileInfo], sources: &SourceSet) -> Result<Vec<ParsedFile>> {
+        let low_memory = self.config.analysis.low_memory;
+        let progress = self.progress && !self.quiet;
+        let quiet = self.quiet;
+        let io = IoLimiter::new(self.config.analysis.io_concurrency);
+        let (cancel, events) = (&self.cancel, &self.events);
+        let (completed, total) = (AtomicUsize::new(0), files.len());
+        let max_line_length = self.config.analysis.max_line_length;
+
+        // A compacted parse is not a full one, and in-memory sources have no stamp
+        let cache = self.parse_cache.as_deref().filter(|_| !low_memory && !sources.is_in_memory());
+
+        let results: Vec<(std::result::Result<ParsedFile, ParseError>, Option<ParseSample>, bool)> = self.pool.install(|| {
+            files.par_iter()
+                .map_init(|| SimpleParser::new().unwrap().with_max_line_length(max_line_length), |parser, file_info| {
+                    // Files left when the run is cancelled are skipped, not read
+                    if cancel.is_cancelled() {
+                        return (Err(ParseError::new(file_info, ParseErrorKind::Read, Error::Cancelled)), None, false);
+                    }
+                    let stamp = cache.and(FileStamp::of(&file_info.path));
+                    if let Some(mut parsed_file) = cache.zip(stamp).and_then(|(cache, stamp)| cache.get(&file_info.path, stamp)) {
+                        parsed_file.file_info = file_info.clone();
+                        return (Ok(parsed_file), None, true);
+                    }
+                    let mut sample = None;
+                    let parsed = io.read(sources, &file_info.path)
+                        .map_err(|e| ParseError::from_io(file_info, &e))
+                        .and_then(|(content, encoding)| {
+                            let started = Instant::now();
+                            let parsed = parser.parse_source(file_info, &content);
+                            sample = Some(ParseSample {
+                                language: file_info.language.clone(),
+                                bytes: content.len() as u64,
+                                duration: started.elapsed(),
+                            });
+                            parsed
+                                .map(|parsed_file| ParsedFile {
+                                    encoding: Some(encoding).filter(|encoding| *encoding != SourceEncoding::Utf8),
+                                    ..parsed_file
+                                })
+                                .map_err(|e| ParseError::new(file_info, ParseErrorKind::Parse, e))
+                        });
+                    match parsed {
+                        Ok(mut parsed_file) => {
+                            if progress {
+                                println!("  ✓ {}", file_info.path.display());
+                            }
+                            if low_memory {
+                                parsed_file.compact();
+                            }
+                            if let Some((cache, stamp)) = cache.zip(stamp) {
+                                cache.insert(&file_info.path, stamp, parsed_file.clone());
+                            }
+                            (Ok(parsed_file), sample, false)
                         }
                         Err(e) => {
-                            eprintln!("  ✗ {}: {}", file_info.path.display(), e);
+                            if !quiet {
+                                eprintln!("  ✗ {}: {}", file_info.path.display(), e.message);
+                            }
+                            (Err(e), sample, false)
                         }
                     }
+                })
+                .inspect(|(parsed, _, _)| {
+                    if cancel.is_cancelled() {
+                        return;
+                    }
+                    let (path, ok) = match parsed {
+                        Ok(parsed_file) => (&parsed_file.file_info.path, true),
+                        Err(error) => (&error.path, false),
+                    };
+                    let completed = completed.fetch_add(1, Ordering::Relaxed) + 1;
+                    events.send(ProgressEvent::FileParsed { path: path.clone(), completed, total, ok });
+                })
+                .collect()
+        });
+
+        self.check_cancelled()?;
+        let mut parsed_files = Vec::with_capacity(results.len());
+        let mut samples = Vec::with_capacity(results.len());
+        let mut reused = 0;
+        for (parsed, sample, cached) in results {
+            reused += cached as usize;
+            match parsed {
+                Ok(parsed_file) => {
+                    let degradations = parsed_file.degradations();
+                    self.run_stats.files_degraded += !degradations.is_empty() as usize;
+                    self.parse_errors.extend(degradations);
+                    parsed_files.push(parsed_file);
                 }
-                
-                parsed_files
-            })
-            .reduce(Vec::new, |mut acc, mut chunk| {
-                acc.append(&mut chunk);
-                acc
-            }))
+                Err(error) => {
+                    self.run_stats.record_parse_error(error.language.as_deref());
+                    self.parse_errors.push(error);
+                }
+            }
+            samples.extend(sample);
+        }
+        self.perf.record_parse(samples, self.pool.current_num_threads(), self.config.analysis.io_concurrency);
+        self.run_stats.files_parsed += parsed_files.len() - reused;
+        self.run_stats.files_from_cache += reused;
+        if reused > 0 && self.progress {
+            say!(self, "  ♻️  {} unchanged file(s) reused from the daemon cache", reused);
+        }
+        Ok(parsed_files)
+    }
+
+    /// Batched LLM pass producing a short summary per module; failed batches are skipped
+    async fn summarize_modules(&self, parsed_files: &[ParsedFile]) -> Vec<ModuleSummary> {
+        let summarizer = ModuleSummarizer::new();
+        let visible: Vec<ParsedFile> = parsed_files.iter()
+            .filter(|pf| !self.is_sensitive(&pf.file_info.path))
+            .cloned()
+            .collect();
+        let prompts = summarizer.build_prompts(&visible);
+        let total = prompts.len();
+
+        let mut summaries = Vec::new();
+        for (i, prompt) in prompts.iter().enumerate() {
+            if self.cancel.is_cancelled() {
+                break;
+            }
+            say!(self, "  📝 Module batch {}/{}...", i + 1, total);
+            match self.llm_client.complete(summarizer.system_prompt(), prompt).await {
+                Ok(content) => summaries.extend(summarizer.parse_response(&content)),
+                Err(e) => say!(self, "    ⚠️  Module summary batch failed: {}", e),
+            }
+        }
+
+        say!(self, "  ✅ Summarized {} modules", summaries.len());
+        summaries
     }
 
     async fn analyze_with_llm(
         &self,
         parsed_files: &[ParsedFile],
-        _graph: &DependencyGraph,
         files: &[FileInfo],
-    ) -> Result<Vec<AnalysisResponse>> {
-        println!("  📊 Preparing analysis context...");
-        let context = self.create_analysis_context(parsed_files, _graph, files);
+        local: &LocalFindings,
+        sources: &SourceSet,
+        previous: &LlmProgress,
+    ) -> Result<(Vec<AnalysisResponse>, Vec<DiscardedItem>, Vec<String>)> {
+        say!(self, "  📊 Preparing analysis context...");
+        let context = self.create_analysis_context(parsed_files, files, local, sources);
         
-        let analysis_types = vec![
-            ("Overview", AnalysisType::Overview),
-            ("Architecture", AnalysisType::Architecture), 
-            ("Dependencies", AnalysisType::Dependencies),
-        ];
+        let requests = self.create_llm_requests(&context);
+        let total = requests.len();
+        let review_context = requests.first()
+            .filter(|_| self.config.llm.enable_review_pass)
+            .map(|(_, request)| self.llm_client.context_prompt(request));
 
-        println!("  🔄 Running {} analysis types...", analysis_types.len());
+        say!(self, "  🔄 Running {} analysis types...", total);
         
         let mut results = Vec::new();
-        for (i, (name, analysis_type)) in analysis_types.iter().enumerate() {
-            println!("  {} Analyzing {} ({}/{})...", 
+        let mut failed = Vec::new();
+        for (i, (name, request)) in requests.into_iter().enumerate() {
+            self.check_cancelled()?;
+            if let Some(response) = previous.responses.iter().find(|r| r.analysis_type.as_ref() == Some(&request.analysis_type)) {
+                say!(self, "  ♻️  {} analysis restored from checkpoint ({}/{})", name, i + 1, total);
+                results.push(response.clone());
+                self.events.send(ProgressEvent::LlmAnalysis { analysis: name.to_string(), completed: i + 1, total, ok: true });
+                continue;
+            }
+
+            say!(self, "  {} Analyzing {} ({}/{})...", 
                 if i == 0 { "🚀" } else { "📈" }, 
                 name, 
                 i + 1, 
-                analysis_types.len()
+                total
             );
-            
-            let prompt = self.create_prompt_for_type(analysis_type);
-            let request = AnalysisRequest {
-                prompt,
-                context: context.clone(),
-                analysis_type: analysis_type.clone(),
-            };
 
+            let analysis_type = request.analysis_type.clone();
             match self.llm_client.analyze(request).await {
-                Ok(response) => {
-                    println!("    ✅ {} analysis completed", name);
+                Ok(mut response) => {
+                    say!(self, "    ✅ {} analysis completed", name);
+                    if !response.proposed_changes.is_empty() {
+                        let validator = ProposedChangeValidator::new(&self.config.target_directory, files);
+                        let (accepted, rejected) = validator.validate(std::mem::take(&mut response.proposed_changes), sources);
+                        say!(self, "    🩹 {} proposed change(s) accepted, {} rejected", accepted.len(), rejected.len());
+                        for rejection in &rejected {
+                            say!(self, "      ⚠️  {}: {}", rejection.change.file, rejection.reason);
+                        }
+                        response.proposed_changes = accepted;
+                    }
+                    let resolver = FileResolver::new(&self.config.target_directory, files);
+                    let mut dropped = 0;
+                    for recommendation in &mut response.recommendations {
+                        dropped += recommendation.files.iter().filter(|file| resolver.resolve(file).is_none()).count();
+                        recommendation.files = resolver.resolve_all(&recommendation.files);
+                    }
+                    if dropped > 0 {
+                        say!(self, "    🔗 Dropped {} recommendation file reference(s) not found in the project", dropped);
+                    }
+                    let (mut verified, mut flagged) = (0, 0);
+                    for evidence in response.insights.iter_mut().flat_map(|insight| insight.evidence.iter_mut()) {
+                        resolver.anchor(evidence, sources);
+                        match evidence.status {
+                            EvidenceStatus::Verified => verified += 1,
+                            status if status.is_flagged() => flagged += 1,
+                            _ => {}
+                        }
+                    }
+                    if verified + flagged > 0 {
+                        say!(self, "    🧾 {} evidence quote(s) verified, {} not found in the named files", verified, flagged);
+                    }
+                    response.analysis_type = Some(analysis_type);
                     results.push(response);
+                    let mut data_sent = previous.data_sent.clone();
+                    data_sent.extend(self.llm_client.data_sent());
+                    self.pipeline.save_llm_progress(&LlmProgress { responses: results.clone(), data_sent })?;
+                    self.events.send(ProgressEvent::LlmAnalysis { analysis: name.to_string(), completed: i + 1, total, ok: true });
                 }
                 Err(e) => {
-                    println!("    ⚠️  {} analysis failed: {}", name, e);
+                    self.events.send(ProgressEvent::LlmAnalysis { analysis: name.to_string(), completed: i + 1, total, ok: false });
+                    say!(self, "    ⚠️  {} analysis failed: {}", name, e);
+                    failed.push(name.to_string());
                     // Continue with other analyses even if one fails
-                    println!("    📝 Continuing with remaining analyses...");
+                    say!(self, "    📝 Continuing with remaining analyses...");
                 }
             }
         }
 
         if results.is_empty() {
-            println!("  ⚠️  All LLM analyses failed, continuing with local analysis only");
+            say!(self, "  ⚠️  All LLM analyses failed, continuing with local analysis only");
         } else {
-            println!("  ✅ Completed {}/{} LLM analyses successfully", results.len(), analysis_types.len());
+            say!(self, "  ✅ Completed {}/{} LLM analyses successfully", results.len(), total);
         }
 
-        Ok(results)
+        let mut removed = Vec::new();
+        let findings = results.iter().map(|r| r.insights.len() + r.recommendations.len()).sum::<usize>();
+        self.check_cancelled()?;
+        if let (Some(review_context), true) = (review_context, findings > 0) {
+            say!(self, "  🧐 Reviewing {} finding(s) with a critic pass...", findings);
+            let review = ReviewPass::new();
+            match self.llm_client.complete(review.system_prompt(), &review.build_prompt(&results, &review_context)).await {
+                Ok(content) => {
+                    let outcome = review.apply(review.parse_response(&content), &mut results);
+                    say!(self, "    ✅ Review removed {} unsupported insight(s) and merged {} duplicate(s)", outcome.removed.len(), outcome.merged);
+                    removed = outcome.removed;
+                }
+                Err(e) => say!(self, "    ⚠️  Review pass failed, keeping the analyses as they are: {}", e),
+            }
+        }
+
+        Ok((results, removed, failed))
+    }
+
+    fn create_llm_requests(&self, context: &AnalysisContext) -> Vec<(&'static str, AnalysisRequest)> {
+        self.config.analysis.analysis_types.iter().map(|analysis_type| {
+            let mut prompt = self.create_prompt_for_type(analysis_type);
+            if self.config.analysis.audience == Audience::Onboarding {
+                prompt.push_str("\n\nThe audience is a developer who is new to this codebase. Explain rather than critique: describe what each part is for, how a request or command flows from the entry points through the main modules, and what to read first. Define domain terms from the glossary where they matter.");
+            }
+            let request = AnalysisRequest {
+                prompt,
+                context: context.clone(),
+                analysis_type: analysis_type.clone(),
+            };
+            (analysis_type.name(), request)
+        }).collect()
     }
 
+    /// Everything the prompts describe. Local-pass findings come from the
+    /// local-passes stage, so they're empty when it is disabled.
     fn create_analysis_context(
         &self,
         parsed_files: &[ParsedFile],
-        _graph: &DependencyGraph,
         files: &[FileInfo],
+        local: &LocalFindings,
+        sources: &SourceSet,
     ) -> AnalysisContext {
+        // Sensitive files are neither described nor quoted in prompts. The local
+        // findings cover every file, so lines naming a sensitive one are dropped.
+        let hidden: Vec<String> = files.iter()
+            .filter(|f| self.is_sensitive(&f.path))
+            .flat_map(|f| [
+                f.path.display().to_string(),
+                f.path.strip_prefix(&self.config.target_directory).unwrap_or(&f.path).display().to_string(),
+            ])
+            .collect();
+        let (visible_parsed, visible_files);
+        let (parsed_files, files) = if hidden.is_empty() {
+            (parsed_files, files)
+        } else {
+            visible_parsed = parsed_files.iter().filter(|pf| !self.is_sensitive(&pf.file_info.path)).cloned().collect::<Vec<_>>();
+            visible_files = files.iter().filter(|f| !self.is_sensitive(&f.path)).cloned().collect::<Vec<_>>();
+            (visible_parsed.as_slice(), visible_files.as_slice())
+        };
+
         let file_contexts: Vec<FileContext> = parsed_files.iter().map(|pf| {
             FileContext {
                 path: pf.file_info.path.to_string_lossy().to_string(),
@@ -162,8 +1196,10 @@ impl Analyzer {
                 functions: pf.functions.iter().map(|f| f.name.clone()).collect(),
                 classes: pf.classes.iter().map(|c| c.name.clone()).collect(),
                 imports: pf.imports.iter().map(|i| i.module.clone()).collect(),
+                function_signatures: Vec::new(),
             }
         }).collect();
+        let file_contexts = self.add_function_signatures(file_contexts, parsed_files);
 
         let dependency_contexts: Vec<DependencyContext> = parsed_files.iter().flat_map(|pf| {
             pf.imports.iter().map(|import| {
@@ -195,16 +1231,216 @@ impl Analyzer {
             architecture_patterns: Vec::new(), // Will be filled by analysis
         };
 
-        let documentation = self.extract_documentation_content(files);
+        let documentation = self.extract_documentation_content(files, sources);
+
+        let debt_markers = parsed_files.iter().flat_map(|pf| {
+            pf.debt_markers.iter().map(move |marker| {
+                format!("{}:{} {}: {}", pf.file_info.path.display(), marker.line_number, marker.kind, marker.text)
+            })
+        }).collect();
+
+        let wants = |analysis_type: AnalysisType| self.config.analysis.analysis_types.contains(&analysis_type);
+        let visible = |lines: Vec<String>| -> Vec<String> {
+            lines.into_iter().filter(|line| !hidden.iter().any(|path| line.contains(path.as_str()))).collect()
+        };
+
+        let smells: Vec<&CodeSmell> = local.code_smells.iter().filter(|smell| !self.is_sensitive(Path::new(&smell.file))).collect();
+        let code_smells = smells.iter().map(|smell| smell.to_string()).collect();
+        let cohesion: Vec<ClassCohesion> = if wants(AnalysisType::Refactoring) {
+            local.class_cohesion.iter().filter(|class| !self.is_sensitive(Path::new(&class.file))).cloned().collect()
+        } else {
+            Vec::new()
+        };
+        let class_cohesion = CohesionAnalyzer::context_lines(&cohesion);
+        let source_excerpts = if wants(AnalysisType::Refactoring) {
+            self.source_excerpts(smells.iter().map(|smell| smell.file.as_str()).chain(cohesion.iter().map(|class| class.file.as_str())), sources)
+        } else {
+            Vec::new()
+        };
+        let dockerfiles = if wants(AnalysisType::Security) {
+            let paths: Vec<String> = files.iter()
+                .filter(|file| DockerfileAnalyzer::is_dockerfile(file))
+                .map(|file| file.path.to_string_lossy().to_string())
+                .collect();
+            self.source_excerpts(paths.iter().map(String::as_str), sources)
+        } else {
+            Vec::new()
+        };
+
+        let (infrastructure, infrastructure_files) = if wants(AnalysisType::IacSecurity) {
+            let paths: Vec<String> = local.infrastructure.files().iter()
+                .map(|file| self.config.target_directory.join(file))
+                .filter(|path| !self.is_sensitive(path))
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+            (visible(local.infrastructure.context_lines()), self.source_excerpts(paths.iter().map(String::as_str), sources))
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        let (data_access, dynamic_sql) = if wants(AnalysisType::Architecture) || wants(AnalysisType::Security) {
+            let dynamic_sql = local.data_access.files.iter()
+                .filter(|file| !self.is_sensitive(&file.file))
+                .flat_map(|file| file.dynamic_queries.iter().map(move |line| format!("{}:{}", file.file.display(), line)))
+                .collect();
+            (visible(local.data_access.module_summaries()), dynamic_sql)
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        let performance_signals = if wants(AnalysisType::Performance) {
+            visible(local.performance_signals.iter().map(|signal| signal.to_string()).collect())
+        } else {
+            Vec::new()
+        };
+        let testing = if wants(AnalysisType::Testing) {
+            visible(local.testing.context_lines())
+        } else {
+            Vec::new()
+        };
+        let concurrency = if wants(AnalysisType::Concurrency) {
+            visible(local.concurrency.context_lines(&self.config.target_directory))
+        } else {
+            Vec::new()
+        };
+        let contracts = if wants(AnalysisType::Architecture) || wants(AnalysisType::Dependencies) {
+            visible(local.contracts.context_lines())
+        } else {
+            Vec::new()
+        };
+        let frontend_routes = if wants(AnalysisType::Architecture) {
+            visible(local.frontend_routes.context_lines())
+        } else {
+            Vec::new()
+        };
+        let metaprogramming = if wants(AnalysisType::Refactoring) || wants(AnalysisType::Documentation) {
+            visible(local.metaprogramming.context_lines())
+        } else {
+            Vec::new()
+        };
+        let change_coupling = if wants(AnalysisType::Architecture) {
+            visible(local.change_coupling.context_lines())
+        } else {
+            Vec::new()
+        };
+        let process_health = if wants(AnalysisType::Overview) {
+            local.process_health.context_lines()
+        } else {
+            Vec::new()
+        };
+
+        let undocumented_symbols = DocCoverageAnalyzer::new().analyze(parsed_files).undocumented.iter()
+            .map(|symbol| format!("{}:{} {}", symbol.file, symbol.line, symbol.name))
+            .collect();
+
+        let doc_drift = visible(local.doc_drift.iter()
+            .map(|drift| format!("{}:{} {:?} `{}`: {}", drift.doc_file, drift.line, drift.kind, drift.reference, drift.detail))
+            .collect());
+
+        let onboarding = match &local.onboarding {
+            Some(guide) if self.config.analysis.audience == Audience::Onboarding => visible(guide.main_flows.iter()
+                .map(|flow| format!("Flow from {}: {}", flow.entry_point.display(), flow.files.iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" -> ")))
+                .chain(guide.glossary.iter().map(|term| format!("Glossary term '{}' ({} identifiers, e.g. {})", term.term, term.occurrences, term.examples.join(", "))))
+                .collect()),
+            _ => Vec::new(),
+        };
 
         AnalysisContext {
             files: file_contexts,
             dependencies: dependency_contexts,
             project_info,
             documentation,
+            debt_markers,
+            code_smells,
+            class_cohesion,
+            source_excerpts,
+            dockerfiles,
+            infrastructure,
+            infrastructure_files,
+            contracts,
+            frontend_routes,
+            data_access,
+            dynamic_sql,
+            concurrency,
+            metaprogramming,
+            performance_signals,
+            testing,
+            undocumented_symbols,
+            doc_drift,
+            onboarding,
+            process_health,
+            change_coupling,
         }
     }
 
+    fn is_sensitive(&self, path: &std::path::Path) -> bool {
+        self.redactor.is_sensitive(path.strip_prefix(&self.config.target_directory).unwrap_or(path))
+    }
+
+    /// Fill in `function_signatures` file by file until the estimated tokens they
+    /// add reach `analysis.function_signature_tokens`
+    fn add_function_signatures(&self, mut file_contexts: Vec<FileContext>, parsed_files: &[ParsedFile]) -> Vec<FileContext> {
+        let analysis = &self.config.analysis;
+        if !analysis.function_signatures {
+            return file_contexts;
+        }
+        let mut remaining = analysis.function_signature_tokens;
+        let mut covered = 0;
+        for (context, pf) in file_contexts.iter_mut().zip(parsed_files) {
+            let methods = pf.classes.iter().flat_map(|class| class.methods.iter().map(move |method| (Some(&class.name), method)));
+            let classes = pf.classes.iter()
+                .filter_map(|class| Some(format!("class {} — {}", class.name, class.summary()?)));
+            let signatures: Vec<String> = classes.chain(pf.functions.iter().map(|function| (None, function))
+                .chain(methods)
+                .map(|(class, function)| {
+                    let name = class.map(|class| format!("{}.", class)).unwrap_or_default();
+                    match function.summary() {
+                        Some(summary) => format!("{}{} — {}", name, function.signature(), summary),
+                        None => format!("{}{}", name, function.signature()),
+                    }
+                }))
+                .collect();
+            // Replaces the names-only line, which costs about as much as the names
+            let cost = estimate_tokens(&signatures.join("\n")).saturating_sub(estimate_tokens(&context.functions.join(", ")));
+            if cost > remaining {
+                break;
+            }
+            remaining -= cost;
+            covered += 1;
+            context.function_signatures = signatures;
+        }
+        if covered < file_contexts.len() {
+            say!(self, "  📏 Function signatures for {} of {} files (analysis.function_signature_tokens = {})",
+                covered, file_contexts.len(), analysis.function_signature_tokens);
+        }
+        file_contexts
+    }
+
+    /// Source of the first few distinct files, relative to the target directory and
+    /// truncated to keep prompts bounded
+    fn source_excerpts<'a>(&self, files: impl Iterator<Item = &'a str>, sources: &SourceSet) -> Vec<SourceExcerpt> {
+        const MAX_FILES: usize = 3;
+        const MAX_LINES: usize = 300;
+
+        let mut seen = std::collections::HashSet::new();
+        files.filter(|file| seen.insert(*file))
+            .take(MAX_FILES)
+            .filter_map(|file| {
+                let path = std::path::Path::new(file);
+                let content = sources.read(path).ok()?;
+                let relative = path.strip_prefix(&self.config.target_directory).unwrap_or(path);
+                let mut excerpt = self.redactor.redact_source(&content.lines().take(MAX_LINES).collect::<Vec<_>>().join("\n"));
+                if content.lines().count() > MAX_LINES {
+                    excerpt.push_str(&format!("\n[... truncated after {} lines]", MAX_LINES));
+                }
+                Some(SourceExcerpt { path: relative.to_string_lossy().to_string(), content: excerpt })
+            })
+            .collect()
+    }
+
     fn safe_truncate<'a>(&self, s: &'a str, max_chars: usize) -> &'a str {
         if s.chars().count() <= max_chars {
             return s;
@@ -226,7 +1462,7 @@ impl Analyzer {
         }
     }
 
-    fn extract_documentation_content(&self, files: &[FileInfo]) -> Vec<DocumentationContext> {
+    fn extract_documentation_content(&self, files: &[FileInfo], sources: &SourceSet) -> Vec<DocumentationContext> {
         let mut documentation = Vec::new();
         
         for file in files {
@@ -235,7 +1471,7 @@ impl Analyzer {
                     "markdown" | "text" | "json" | "yaml" | "toml");
                 
                 if is_documentation {
-                    match fs::read_to_string(&file.path) {
+                    match sources.read(&file.path) {
                         Ok(content) => {
                             let summary = if content.chars().count() > 500 {
                                 format!("{}... ({} characters total)", 
@@ -263,8 +1499,10 @@ impl Analyzer {
                             });
                         }
                         Err(e) => {
-                            eprintln!("Warning: Could not read documentation file {}: {}", 
-                                file.path.display(), e);
+                            if !self.quiet {
+                                eprintln!("Warning: Could not read documentation file {}: {}", 
+                                    file.path.display(), e);
+                            }
                         }
                     }
                 }
@@ -289,8 +1527,7 @@ impl Analyzer {
       "category": "Architecture|Functionality|Technology|Implementation",
       "confidence": 0.8,
       "evidence": [
-        "Specific evidence from the codebase supporting this insight",
-        "Another piece of evidence"
+        {"file": "path/to/file.rs", "line": 42, "snippet": "the line of code quoted exactly"}
       ]
     }
   ],
@@ -301,6 +1538,8 @@ impl Analyzer {
       "priority": "High|Medium|Low",
       "effort": "High|Medium|Low", 
       "impact": "High|Medium|Low",
+      "category": "Architecture|Dependencies|Security|Testing|Performance|Documentation|Tooling",
+      "files": ["path/to/affected_file.rs"],
       "action_items": [
         "Specific actionable step",
         "Another specific step"
@@ -326,8 +1565,7 @@ Focus on describing what the software does, its main components, technology choi
       "category": "Architecture|Design Pattern|Structure|Organization",
       "confidence": 0.8,
       "evidence": [
-        "Specific evidence from the codebase supporting this insight",
-        "Another piece of evidence"
+        {"file": "path/to/file.rs", "line": 42, "snippet": "the line of code quoted exactly"}
       ]
     }
   ],
@@ -338,6 +1576,8 @@ Focus on describing what the software does, its main components, technology choi
       "priority": "High|Medium|Low",
       "effort": "High|Medium|Low", 
       "impact": "High|Medium|Low",
+      "category": "Architecture|Dependencies|Security|Testing|Performance|Documentation|Tooling",
+      "files": ["path/to/affected_file.rs"],
       "action_items": [
         "Specific actionable step",
         "Another specific step"
@@ -363,8 +1603,7 @@ Focus on identifying architectural patterns (MVC, microservices, layered, etc.),
       "category": "Coupling|Modularity|Dependencies|Structure",
       "confidence": 0.8,
       "evidence": [
-        "Specific evidence from the codebase supporting this insight",
-        "Another piece of evidence"
+        {"file": "path/to/file.rs", "line": 42, "snippet": "the line of code quoted exactly"}
       ]
     }
   ],
@@ -375,6 +1614,8 @@ Focus on identifying architectural patterns (MVC, microservices, layered, etc.),
       "priority": "High|Medium|Low",
       "effort": "High|Medium|Low", 
       "impact": "High|Medium|Low",
+      "category": "Architecture|Dependencies|Security|Testing|Performance|Documentation|Tooling",
+      "files": ["path/to/affected_file.rs"],
       "action_items": [
         "Specific actionable step to improve dependencies",
         "Another specific step"
@@ -388,13 +1629,248 @@ Focus on identifying architectural patterns (MVC, microservices, layered, etc.),
 Focus on identifying coupling issues, circular dependencies, modularity problems, dependency injection opportunities, and provide actionable recommendations for better dependency management. Consider the project's documentation to understand intended module relationships and design goals."#.to_string()
             }
             AnalysisType::Security => {
-                "Perform a security analysis of this codebase. Look for potential vulnerabilities, insecure patterns, and provide security recommendations.".to_string()
+                r#"Perform a security analysis of this codebase. Look for potential vulnerabilities, insecure patterns, and provide security recommendations. Respond in the following JSON format:
+
+```json
+{
+  "analysis": "Brief summary of the project's security posture and most serious risks in 2-3 sentences",
+  "insights": [
+    {
+      "title": "Vulnerability or Insecure Pattern",
+      "description": "What is exposed, how it could be exploited, and what data or access is at risk",
+      "category": "Security|Injection|Secrets|Configuration|Container",
+      "confidence": 0.8,
+      "evidence": [{"file": "path/to/file.rs", "line": 42, "snippet": "the line of code quoted exactly"}]
+    }
+  ],
+  "recommendations": [
+    {
+      "title": "Recommendation Title",
+      "description": "How to fix or mitigate the risk",
+      "priority": "High|Medium|Low",
+      "effort": "High|Medium|Low",
+      "impact": "High|Medium|Low",
+      "category": "Architecture|Dependencies|Security|Testing|Performance|Documentation|Tooling",
+      "files": ["path/to/affected_file.rs"],
+      "action_items": ["Specific actionable step"]
+    }
+  ],
+  "confidence": 0.8
+}
+```
+
+Check the Dockerfiles and the SQL built by concatenation or string formatting when they are provided, and cite the Dockerfile or source line as evidence for each finding."#.to_string()
             }
             AnalysisType::Refactoring => {
-                "Identify refactoring opportunities in this codebase. Look for code smells, duplication, and areas that could benefit from restructuring.".to_string()
+                r#"Identify refactoring opportunities in this codebase. Look for code smells, duplication, and areas that could benefit from restructuring. Respond in the following JSON format:
+
+```json
+{
+  "analysis": "Brief summary of the main refactoring opportunities in 2-3 sentences",
+  "insights": [
+    {
+      "title": "Code Smell or Problem Area",
+      "description": "What is wrong and why it hurts maintainability",
+      "category": "CodeQuality|Maintainability|Architecture",
+      "confidence": 0.8,
+      "evidence": [{"file": "path/to/file.rs", "line": 42, "snippet": "the line of code quoted exactly"}]
+    }
+  ],
+  "recommendations": [
+    {
+      "title": "Refactoring Title",
+      "description": "The refactoring and its benefit",
+      "priority": "High|Medium|Low",
+      "effort": "High|Medium|Low",
+      "impact": "High|Medium|Low",
+      "category": "Architecture|Dependencies|Security|Testing|Performance|Documentation|Tooling",
+      "files": ["path/to/affected_file.rs"],
+      "action_items": ["Specific actionable step"]
+    }
+  ],
+  "proposed_changes": [
+    {
+      "file": "path/to/file.rs",
+      "description": "What this change does",
+      "diff": "--- a/path/to/file.rs
++++ b/path/to/file.rs
+@@ -10,7 +10,7 @@
+ context line
+-removed line
++added line
+ context line"
+    }
+  ],
+  "confidence": 0.8
+}
+```
+
+Each proposed change must touch exactly one file from the provided file list, use a path relative to the project root, and be a valid unified diff whose context and removed lines match the source excerpts exactly. Only propose changes for code you have seen; leave "proposed_changes" empty rather than guess."#.to_string()
             }
             AnalysisType::Documentation => {
-                "Generate comprehensive documentation for this software project, explaining how it works, its components, and usage patterns.".to_string()
+                r#"Generate comprehensive documentation for this software project, explaining how it works, its components, and usage patterns. Respond in the following JSON format:
+
+```json
+{
+  "analysis": "The documentation: a high-level overview, the key components and how data flows between them",
+  "insights": [
+    {
+      "title": "Component or Documentation Gap",
+      "description": "What the component does, or what is missing or out of date in the documentation",
+      "category": "Documentation|Architecture|Usage",
+      "confidence": 0.8,
+      "evidence": [{"file": "path/to/file.rs", "line": 42, "snippet": "the line of code quoted exactly"}]
+    }
+  ],
+  "recommendations": [
+    {
+      "title": "Recommendation Title",
+      "description": "What to document or correct, and for whom",
+      "priority": "High|Medium|Low",
+      "effort": "High|Medium|Low",
+      "impact": "High|Medium|Low",
+      "category": "Architecture|Dependencies|Security|Testing|Performance|Documentation|Tooling",
+      "files": ["path/to/affected_file.rs"],
+      "action_items": ["Specific actionable step"]
+    }
+  ],
+  "confidence": 0.8
+}
+```
+
+Turn undocumented public symbols and README/CHANGELOG drift you can confirm against the code into recommendations, naming the affected files."#.to_string()
+            }
+            AnalysisType::Concurrency => {
+                r#"Review the concurrency of this codebase for race-condition risks, starting with the modules in the concurrency inventory that spawn work and share state. Respond in the following JSON format:
+
+```json
+{
+  "analysis": "Brief summary of how the project uses concurrency and its main risks in 2-3 sentences",
+  "insights": [
+    {
+      "title": "Race Condition or Concurrency Risk",
+      "description": "What can go wrong, under which interleaving, and what state is affected",
+      "category": "Concurrency|Safety|Performance",
+      "confidence": 0.8,
+      "evidence": [{"file": "path/to/file.rs", "line": 42, "snippet": "the line of code quoted exactly"}]
+    }
+  ],
+  "recommendations": [
+    {
+      "title": "Recommendation Title",
+      "description": "How to remove or contain the risk",
+      "priority": "High|Medium|Low",
+      "effort": "High|Medium|Low",
+      "impact": "High|Medium|Low",
+      "category": "Architecture|Dependencies|Security|Testing|Performance|Documentation|Tooling",
+      "files": ["path/to/affected_file.rs"],
+      "action_items": ["Specific actionable step"]
+    }
+  ],
+  "confidence": 0.8
+}
+```
+
+Cite the inventory's `path:line` sites as evidence. Only report a race when the shared state and the concurrent accessors are both visible in the inventory or file list; otherwise describe it as a question to verify."#.to_string()
+            }
+            AnalysisType::Performance => {
+                r#"Review the performance of this codebase, starting from the locally detected performance signals. Respond in the following JSON format:
+
+```json
+{
+  "analysis": "Brief summary of the main performance risks in 2-3 sentences",
+  "insights": [
+    {
+      "title": "Performance Problem",
+      "description": "What is slow or wasteful, and how cost grows with input size or load",
+      "category": "Performance|Scalability|Resource Usage",
+      "confidence": 0.8,
+      "evidence": [{"file": "path/to/file.rs", "line": 42, "snippet": "the line of code quoted exactly"}]
+    }
+  ],
+  "recommendations": [
+    {
+      "title": "Recommendation Title",
+      "description": "The change, e.g. batching, caching, streaming or moving IO off the async runtime",
+      "priority": "High|Medium|Low",
+      "effort": "High|Medium|Low",
+      "impact": "High|Medium|Low",
+      "category": "Architecture|Dependencies|Security|Testing|Performance|Documentation|Tooling",
+      "files": ["path/to/affected_file.rs"],
+      "action_items": ["Specific actionable step"]
+    }
+  ],
+  "confidence": 0.8
+}
+```
+
+Ground every insight in a signal or file from the context and cite its `path:line`. Signals are heuristics: dismiss ones the code shows to be harmless instead of repeating them."#.to_string()
+            }
+            AnalysisType::Testing => {
+                r#"Review the testing strategy of this codebase using the test suite summary. Respond in the following JSON format:
+
+```json
+{
+  "analysis": "Brief summary of the test suite and its main gaps in 2-3 sentences",
+  "insights": [
+    {
+      "title": "Coverage Gap or Test Architecture Issue",
+      "description": "What is untested or poorly tested, and the risk it leaves",
+      "category": "Testing|Coverage|Test Architecture",
+      "confidence": 0.8,
+      "evidence": [{"file": "path/to/file.rs", "line": 42, "snippet": "the line of code quoted exactly"}]
+    }
+  ],
+  "recommendations": [
+    {
+      "title": "Recommendation Title",
+      "description": "Which tests to add or restructure, and why first",
+      "priority": "High|Medium|Low",
+      "effort": "High|Medium|Low",
+      "impact": "High|Medium|Low",
+      "category": "Architecture|Dependencies|Security|Testing|Performance|Documentation|Tooling",
+      "files": ["path/to/affected_file.rs"],
+      "action_items": ["Specific actionable step"]
+    }
+  ],
+  "confidence": 0.8
+}
+```
+
+Prioritize untested files by complexity and by how central they are in the dependency relationships. Test-to-source matching is by file name, so a file listed as untested may be covered by tests with other names; say so when the file list suggests it."#.to_string()
+            }
+            AnalysisType::IacSecurity => {
+                r#"Review the security of this project's infrastructure-as-code, starting with the risky settings in the infrastructure inventory. Respond in the following JSON format:
+
+```json
+{
+  "analysis": "Brief summary of the infrastructure and its main security risks in 2-3 sentences",
+  "insights": [
+    {
+      "title": "Infrastructure Security Issue",
+      "description": "What is exposed or misconfigured, to whom, and what an attacker could reach",
+      "category": "Network Exposure|Data Protection|Identity and Access|Workload Isolation|Secrets",
+      "confidence": 0.8,
+      "evidence": [{"file": "path/to/main.tf", "line": 42, "snippet": "the line of code quoted exactly"}]
+    }
+  ],
+  "recommendations": [
+    {
+      "title": "Recommendation Title",
+      "description": "The configuration change and what it closes",
+      "priority": "High|Medium|Low",
+      "effort": "High|Medium|Low",
+      "impact": "High|Medium|Low",
+      "category": "Architecture|Dependencies|Security|Testing|Performance|Documentation|Tooling",
+      "files": ["path/to/main.tf"],
+      "action_items": ["Specific actionable step"]
+    }
+  ],
+  "confidence": 0.8
+}
+```
+
+Cite the `path:line` of each risky setting you confirm and dismiss the ones the surrounding configuration makes safe. Use the dependencies to explain what an exposed resource leads to."#.to_string()
             }
         }
     }
@@ -449,12 +1925,198 @@ Focus on identifying coupling issues, circular dependencies, modularity problems
     }
 }
 
+/// Outputs of the pipeline stages run so far
+#[derive(Default)]
+struct PipelineState {
+    discovery: Option<DiscoveryArtifact>,
+    parse: Option<ParseArtifact>,
+    graph: Option<GraphArtifact>,
+    local: Option<LocalFindings>,
+    llm: Option<LlmFindings>,
+}
+
+impl PipelineState {
+    /// Disabled stages contribute empty results
+    fn into_analysis(self, config: &Config) -> ProjectAnalysis {
+        const ORDER: &str = "discover, parse and graph always run";
+        let DiscoveryArtifact { files, skipped, sampling } = self.discovery.expect(ORDER);
+        let ParseArtifact { parsed_files, parse_errors } = self.parse.expect(ORDER);
+        let GraphArtifact { dependency_analysis, file_dependencies } = self.graph.expect(ORDER);
+        let local = self.local.unwrap_or_default();
+        let llm = self.llm.unwrap_or_default();
+
+        ProjectAnalysis {
+            files,
+            parsed_files,
+            dependency_analysis,
+            file_dependencies,
+            file_ownership: local.file_ownership,
+            bus_factor: local.bus_factor,
+            process_health: local.process_health,
+            change_coupling: local.change_coupling,
+            registry_check: local.registry_check,
+            vulnerability_scan: local.vulnerability_scan,
+            debt_markers: local.debt_markers,
+            doc_drift: local.doc_drift,
+            code_smells: local.code_smells,
+            class_cohesion: local.class_cohesion,
+            performance_signals: local.performance_signals,
+            testing: local.testing,
+            rule_findings: local.rule_findings,
+            architecture_violations: local.architecture_violations,
+            lazy_boundaries: local.lazy_boundaries,
+            components: local.components,
+            boundary_violations: local.boundary_violations,
+            docker_images: local.docker_images,
+            infrastructure: local.infrastructure,
+            assets: local.assets,
+            endpoints: local.endpoints,
+            contracts: local.contracts,
+            frontend_routes: local.frontend_routes,
+            i18n: local.i18n,
+            data_access: local.data_access,
+            concurrency: local.concurrency,
+            metaprogramming: local.metaprogramming,
+            scope: config.analysis.scope.clone(),
+            sampling,
+            excluded_files: skipped,
+            module_summaries: llm.module_summaries,
+            onboarding: local.onboarding,
+            llm_enabled: llm.llm_enabled,
+            llm_analysis: llm.llm_analysis,
+            discarded_llm_output: llm.discarded_llm_output,
+            data_sent: llm.data_sent,
+            offline: config.offline.enabled,
+            revision: None,
+            parse_errors,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProjectAnalysis {
     pub files: Vec<FileInfo>,
     pub parsed_files: Vec<ParsedFile>,
     pub dependency_analysis: crate::dependency_graph::DependencyAnalysis,
+    #[serde(default)]
+    pub file_dependencies: Vec<FileDependency>,
+    #[serde(default)]
+    pub file_ownership: Vec<FileOwnership>,
+    /// Commit authorship concentration per module, bus factor 1 first
+    #[serde(default)]
+    pub bus_factor: Vec<ModuleBusFactor>,
+    /// Commit conventions, commit size and long-lived branches from git history
+    #[serde(default)]
+    pub process_health: ProcessHealth,
+    /// File pairs that change together in git history without an import
+    /// between them, strongest first
+    #[serde(default)]
+    pub change_coupling: ChangeCouplingAnalysis,
+    /// Outdated and yanked dependencies, when run with `--check-registry`
+    #[serde(default)]
+    pub registry_check: RegistryCheck,
+    /// Published advisories affecting declared dependency versions, when run
+    /// with `--check-vulnerabilities`
+    #[serde(default)]
+    pub vulnerability_scan: VulnerabilityScan,
+    /// TODO/FIXME/HACK/XXX comments, oldest first
+    #[serde(default)]
+    pub debt_markers: Vec<DebtItem>,
+    /// README/CHANGELOG references that don't match the code
+    #[serde(default)]
+    pub doc_drift: Vec<DocDrift>,
+    /// Long functions, long parameter lists, deep nesting and god classes, worst first
+    #[serde(default)]
+    pub code_smells: Vec<CodeSmell>,
+    /// Classes whose methods split into groups sharing no fields (LCOM4 > 1), least cohesive first
+    #[serde(default)]
+    pub class_cohesion: Vec<ClassCohesion>,
+    /// Query loops, blocking IO in async functions, nested loops and large files
+    #[serde(default)]
+    pub performance_signals: Vec<PerformanceSignal>,
+    /// Test files, frameworks, assertion density and untested source files
+    #[serde(default)]
+    pub testing: TestingSummary,
+    /// Findings from `[[rules]]`, most severe first
+    #[serde(default)]
+    pub rule_findings: Vec<RuleFinding>,
+    /// Imports that break the declared `[architecture]` layer model
+    #[serde(default)]
+    pub architecture_violations: Vec<ArchitectureViolation>,
+    /// Dynamic imports grouped by the layers or directories they cross
+    #[serde(default)]
+    pub lazy_boundaries: Vec<LazyBoundary>,
+    /// Metrics, dependencies and findings rolled up to the `[components]` groups
+    #[serde(default)]
+    pub components: ComponentAnalysis,
+    /// Imports between workspace packages that bypass the target's entry point
+    #[serde(default)]
+    pub boundary_violations: Vec<BoundaryViolation>,
+    /// Dockerfiles with their base images, exposed ports and the source they ship
+    #[serde(default)]
+    pub docker_images: Vec<DockerImage>,
+    /// Terraform, CloudFormation and Kubernetes resources, their dependencies and risky settings
+    #[serde(default)]
+    pub infrastructure: InfrastructureInventory,
+    /// Images, models, archives and other binary files with their sizes, and whether they dominate the repository
+    #[serde(default)]
+    pub assets: AssetInventory,
+    /// Endpoints from OpenAPI/GraphQL specs and route declarations, with the files implementing them
+    #[serde(default)]
+    pub endpoints: EndpointInventory,
+    /// Protobuf, Thrift and Avro services and messages, and the modules using their generated code
+    #[serde(default)]
+    pub contracts: ContractInventory,
+    /// React Router, Next.js and Angular pages and routes with the component files they render
+    #[serde(default)]
+    pub frontend_routes: FrontendRouteInventory,
+    /// Translation bundles with missing and unused keys per locale, and hardcoded user-facing strings
+    #[serde(default)]
+    pub i18n: I18nReport,
+    /// Tables from SQL migrations and the tables each file and module queries
+    #[serde(default)]
+    pub data_access: DataAccess,
+    /// Threads, tasks, locks, channels, atomics and unsafe code, with per-module hotspots
+    #[serde(default)]
+    pub concurrency: ConcurrencyInventory,
+    /// Derives, macros, decorators, metaclasses and build-time codegen per module
+    #[serde(default)]
+    pub metaprogramming: MetaprogrammingInventory,
+    /// Paths or packages the analysis was restricted to; empty for the whole project
+    #[serde(default)]
+    pub scope: Vec<String>,
+    /// Set when `analysis.max_files` limited the run to a sample of the project
+    #[serde(default)]
+    pub sampling: Option<SamplingSummary>,
+    /// Files the directory walk found but excluded, with the rule responsible
+    #[serde(default)]
+    pub excluded_files: Vec<SkippedFile>,
+    /// LLM-written module descriptions for the module guide
+    #[serde(default)]
+    pub module_summaries: Vec<ModuleSummary>,
+    /// Guided tour for new developers, present when the audience is `Onboarding`
+    #[serde(default)]
+    pub onboarding: Option<OnboardingGuide>,
+    /// False when the run was local-only (`--skip-llm` or provider `None`)
+    #[serde(default)]
+    pub llm_enabled: bool,
     pub llm_analysis: Vec<AnalysisResponse>,
+    /// LLM insights dropped for naming files, functions or languages the project doesn't
+    /// have, or removed by the review pass
+    #[serde(default)]
+    pub discarded_llm_output: Vec<DiscardedItem>,
+    /// Every LLM request as sent, after redaction (written to `data_sent.json`)
+    #[serde(default)]
+    pub data_sent: Vec<DataSentEntry>,
+    /// Set when the run was made with `[offline]`, guaranteeing no network calls
+    #[serde(default)]
+    pub offline: bool,
+    /// Commit analyzed with `--rev`, instead of the working copy
+    #[serde(default)]
+    pub revision: Option<String>,
+    /// Files that were discovered but could not be read or parsed
+    #[serde(default)]
+    pub parse_errors: Vec<ParseError>,
 }
 
 impl ProjectAnalysis {
@@ -465,11 +2127,28 @@ impl ProjectAnalysis {
         println!("\n📁 Files:");
         println!("  Total files: {}", self.files.len());
         println!("  Successfully parsed: {}", self.parsed_files.len());
+        let degraded = self.parse_errors.iter().filter(|error| error.degraded).count();
+        if self.parse_errors.len() > degraded {
+            println!("  Parse errors: {} (listed in the report appendix)", self.parse_errors.len() - degraded);
+        }
+        if degraded > 0 {
+            println!("  Parsed with reduced fidelity: {} (listed in the report appendix)", degraded);
+        }
         
         println!("\n🔗 Dependencies:");
         self.dependency_analysis.print_summary();
+
+        println!("\n🧾 Technical debt markers: {}", self.debt_markers.len());
+        println!("👃 Code smells: {}", self.code_smells.len());
+        println!("🧬 Low-cohesion classes: {}", self.class_cohesion.len());
+        println!("🐢 Performance signals: {}", self.performance_signals.len());
+        println!("🧪 Tests: {} file(s), {} of {} source files matched to a test",
+            self.testing.test_files.len(), self.testing.tested_sources, self.testing.source_files);
         
         println!("\n🤖 LLM Analysis:");
+        if !self.llm_enabled {
+            println!("  Disabled (local-only mode)");
+        }
         for (i, analysis) in self.llm_analysis.iter().enumerate() {
             println!("  Analysis {}:", i + 1);
             println!("    Confidence: {:.2}", analysis.confidence);
@@ -483,12 +2162,22 @@ impl ProjectAnalysis {
     }
 }
 
+/// An LLM request that would be sent by a full analysis run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedLLMCall {
+    pub name: String,
+    pub system_prompt: String,
+    pub user_prompt: String,
+    pub input_tokens: usize,
+    pub max_output_tokens: usize,
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct FileSummary {
     pub total_files: usize,
     pub total_size: u64,
-    pub language_distribution: HashMap<String, usize>,
-    pub extension_distribution: HashMap<String, usize>,
+    pub language_distribution: BTreeMap<String, usize>,
+    pub extension_distribution: BTreeMap<String, usize>,
 }
 
 #[derive(Debug, Default)]
@@ -497,4 +2186,12 @@ pub struct FilterCriteria {
     pub min_size: Option<u64>,
     pub max_size: Option<u64>,
     pub path_contains: Option<String>,
-}
\ No newline at end of file
+}
+
+/// Peak resident set size from `/proc/self/status`; `None` off Linux
+fn peak_memory_mb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes / 1024)
+}
@@ -0,0 +1,125 @@
+use crate::{
+    analyzer::ProjectAnalysis,
+    module_rollup::ModuleAnalyzer,
+    simple_parser::ParsedFile,
+    symbols::SymbolKind,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Exported symbols grouped by module, with caller counts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiSurface {
+    pub modules: Vec<ApiModule>,
+    pub total_exported: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiModule {
+    pub module: String,
+    pub symbols: Vec<ApiSymbol>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub signature: String,
+    pub file: String,
+    pub line: usize,
+    /// Files in the same module that import the symbol or its defining file
+    pub internal_callers: usize,
+    /// Files in other modules that import the symbol or its defining file
+    pub external_callers: usize,
+}
+
+#[derive(Default)]
+pub struct ApiSurfaceAnalyzer;
+
+impl ApiSurfaceAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn analyze(&self, analysis: &ProjectAnalysis) -> ApiSurface {
+        let mut modules: BTreeMap<String, Vec<ApiSymbol>> = BTreeMap::new();
+
+        for pf in &analysis.parsed_files {
+            let exported: HashSet<&str> = pf.exports.iter().map(|e| e.name.as_str()).collect();
+            let module = ModuleAnalyzer::module_name(&pf.file_info.path);
+
+            let mut seen = HashSet::new();
+            let functions = pf.functions.iter()
+                .filter(|f| exported.contains(f.name.as_str()))
+                .map(|f| (f.name.as_str(), SymbolKind::Function, f.signature(), f.line_number));
+            let classes = pf.classes.iter()
+                .filter(|c| exported.contains(c.name.as_str()))
+                .map(|c| {
+                    let signature = match &c.extends {
+                        Some(base) => format!("{} : {}", c.name, base),
+                        None => c.name.clone(),
+                    };
+                    (c.name.as_str(), SymbolKind::Class, signature, c.line_number)
+                });
+
+            for (name, kind, signature, line) in functions.chain(classes) {
+                if !seen.insert(name) {
+                    continue;
+                }
+                let (internal_callers, external_callers) = Self::count_callers(analysis, pf, name, &module);
+                modules.entry(module.clone()).or_default().push(ApiSymbol {
+                    name: name.to_string(),
+                    kind,
+                    signature,
+                    file: pf.file_info.path.to_string_lossy().to_string(),
+                    line,
+                    internal_callers,
+                    external_callers,
+                });
+            }
+        }
+
+        let total_exported = modules.values().map(|symbols| symbols.len()).sum();
+        ApiSurface {
+            modules: modules.into_iter()
+                .map(|(module, symbols)| ApiModule { module, symbols })
+                .collect(),
+            total_exported,
+        }
+    }
+
+    /// Callers are estimated from imports: a file counts as a caller when one
+    /// of its imports names the symbol, or when it depends on the defining file.
+    fn count_callers(analysis: &ProjectAnalysis, defining: &ParsedFile, name: &str, module: &str) -> (usize, usize) {
+        let defining_path = defining.file_info.path.as_path();
+        let depends_on_defining: HashSet<&Path> = analysis.file_dependencies.iter()
+            .filter(|dep| dep.to == defining_path)
+            .map(|dep| dep.from.as_path())
+            .collect();
+
+        let mut internal = 0;
+        let mut external = 0;
+        for pf in &analysis.parsed_files {
+            let path = pf.file_info.path.as_path();
+            if path == defining_path {
+                continue;
+            }
+
+            let names_symbol = pf.imports.iter().any(|import| {
+                import.items.iter().any(|item| item == name)
+                    || import.module.split(|c: char| !c.is_alphanumeric() && c != '_').any(|part| part == name)
+            });
+
+            if names_symbol || depends_on_defining.contains(path) {
+                if ModuleAnalyzer::module_name(path) == module {
+                    internal += 1;
+                } else {
+                    external += 1;
+                }
+            }
+        }
+
+        (internal, external)
+    }
+}
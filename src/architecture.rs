@@ -0,0 +1,128 @@
+use crate::{config::ArchitectureConfig, dependency_graph::FileDependency};
+use crate::{Error, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// A dependency between layers that the declared model doesn't allow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchitectureViolation {
+    pub from_layer: String,
+    pub to_layer: String,
+    pub from_file: String,
+    pub to_file: String,
+    pub line: usize,
+}
+
+/// Dynamic imports from one module into another: where the code splits into
+/// parts loaded on demand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LazyBoundary {
+    /// Layer name, or the directory for files outside every layer
+    pub from_module: String,
+    pub to_module: String,
+    pub imports: usize,
+    /// Files loaded on demand, relative to the project root
+    pub loaded_files: Vec<String>,
+}
+
+struct Layer {
+    name: String,
+    paths: GlobSet,
+    may_depend_on: Vec<String>,
+}
+
+/// Verifies file dependencies against the `[architecture]` layer model
+pub struct ArchitectureChecker {
+    root: PathBuf,
+    layers: Vec<Layer>,
+}
+
+impl ArchitectureChecker {
+    pub fn new(root: &Path, config: &ArchitectureConfig) -> Result<Self> {
+        let layers = config.layers.iter()
+            .map(|layer| {
+                for allowed in &layer.may_depend_on {
+                    if !config.layers.iter().any(|l| &l.name == allowed) {
+                        return Err(Error::Config(format!("Layer '{}' may depend on unknown layer '{}'", layer.name, allowed)));
+                    }
+                }
+                let mut paths = GlobSetBuilder::new();
+                for pattern in &layer.paths {
+                    paths.add(Glob::new(pattern).map_err(|e| Error::Config(format!("Invalid path for layer '{}': {}", layer.name, e)))?);
+                }
+                Ok(Layer {
+                    name: layer.name.clone(),
+                    paths: paths.build()?,
+                    may_depend_on: layer.may_depend_on.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { root: root.to_path_buf(), layers })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Files outside every layer are not checked; dependencies within a layer
+    /// are always allowed.
+    pub fn check(&self, file_dependencies: &[FileDependency]) -> Vec<ArchitectureViolation> {
+        file_dependencies.iter()
+            .filter_map(|dep| {
+                let from = self.layer_of(&dep.from)?;
+                let to = self.layer_of(&dep.to)?;
+                if from.name == to.name || from.may_depend_on.contains(&to.name) {
+                    return None;
+                }
+                Some(ArchitectureViolation {
+                    from_layer: from.name.clone(),
+                    to_layer: to.name.clone(),
+                    from_file: dep.from.to_string_lossy().to_string(),
+                    to_file: dep.to.to_string_lossy().to_string(),
+                    line: dep.line_number,
+                })
+            })
+            .collect()
+    }
+
+    /// Dynamic imports grouped by the modules on either side, most imports first
+    pub fn lazy_boundaries(&self, file_dependencies: &[FileDependency]) -> Vec<LazyBoundary> {
+        let mut groups: BTreeMap<(String, String), (usize, BTreeSet<String>)> = BTreeMap::new();
+        for dep in file_dependencies.iter().filter(|dep| dep.is_dynamic) {
+            let (imports, loaded) = groups.entry((self.module_of(&dep.from), self.module_of(&dep.to))).or_default();
+            *imports += 1;
+            loaded.insert(dep.to.strip_prefix(&self.root).unwrap_or(&dep.to).to_string_lossy().to_string());
+        }
+
+        let mut boundaries: Vec<LazyBoundary> = groups.into_iter()
+            .map(|((from_module, to_module), (imports, loaded))| LazyBoundary {
+                from_module,
+                to_module,
+                imports,
+                loaded_files: loaded.into_iter().collect(),
+            })
+            .collect();
+        boundaries.sort_by_key(|boundary| std::cmp::Reverse(boundary.imports));
+        boundaries
+    }
+
+    fn module_of(&self, path: &Path) -> String {
+        if let Some(layer) = self.layer_of(path) {
+            return layer.name.clone();
+        }
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        match relative.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().to_string(),
+            _ => ".".to_string(),
+        }
+    }
+
+    /// First declared layer whose globs match the path
+    fn layer_of(&self, path: &Path) -> Option<&Layer> {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        self.layers.iter().find(|layer| layer.paths.is_match(relative))
+    }
+}
@@ -0,0 +1,211 @@
+use crate::{file_discovery::FileInfo, sources::SourceSet};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Assets at least this large are listed individually
+const LARGE_ASSET_BYTES: u64 = 256 * 1024;
+/// Large assets kept in the inventory, biggest first
+const MAX_LARGE_ASSETS: usize = 100;
+/// Share of the repository's bytes in assets above which the report warns
+const DOMINANT_ASSET_SHARE: f64 = 0.5;
+/// Below this many asset bytes the share isn't worth a warning
+const MIN_DOMINANT_ASSET_BYTES: u64 = 1024 * 1024;
+/// Bytes read from a file with an unknown extension to decide whether it's binary
+const SNIFF_BYTES: usize = 8000;
+
+/// What kind of non-code file an asset is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetKind {
+    Image,
+    Video,
+    Audio,
+    Font,
+    Model,
+    Archive,
+    Document,
+    Data,
+    Binary,
+}
+
+impl AssetKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AssetKind::Image => "image",
+            AssetKind::Video => "video",
+            AssetKind::Audio => "audio",
+            AssetKind::Font => "font",
+            AssetKind::Model => "model",
+            AssetKind::Archive => "archive",
+            AssetKind::Document => "document",
+            AssetKind::Data => "data",
+            AssetKind::Binary => "binary",
+        }
+    }
+
+    fn from_extension(extension: &str) -> Option<Self> {
+        let kind = match extension {
+            "png" | "jpg" | "jpeg" | "gif" | "bmp" | "ico" | "icns" | "webp" | "tif" | "tiff" | "avif" | "heic" | "svg" | "psd" | "xcf" => AssetKind::Image,
+            "mp4" | "mov" | "avi" | "mkv" | "webm" | "m4v" | "flv" | "wmv" => AssetKind::Video,
+            "mp3" | "wav" | "ogg" | "flac" | "aac" | "m4a" | "opus" | "mid" | "midi" => AssetKind::Audio,
+            "ttf" | "otf" | "woff" | "woff2" | "eot" => AssetKind::Font,
+            "onnx" | "pt" | "pth" | "h5" | "hdf5" | "pb" | "tflite" | "safetensors" | "ckpt" | "gguf" | "ggml" | "mlmodel" | "joblib" => AssetKind::Model,
+            "zip" | "tar" | "gz" | "tgz" | "bz2" | "xz" | "zst" | "7z" | "rar" | "jar" | "war" | "whl" | "nupkg" | "deb" | "rpm" | "dmg" | "iso" => AssetKind::Archive,
+            "pdf" | "doc" | "docx" | "xls" | "xlsx" | "ppt" | "pptx" | "sketch" | "fig" | "ai" => AssetKind::Document,
+            "sqlite" | "sqlite3" | "db" | "parquet" | "avro" | "arrow" | "feather" | "npy" | "npz" | "pkl" | "pickle" | "mat" => AssetKind::Data,
+            "exe" | "dll" | "so" | "dylib" | "a" | "lib" | "o" | "obj" | "class" | "wasm" | "bin" | "dat" => AssetKind::Binary,
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+/// A large non-code file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Asset {
+    /// Relative to the project root
+    pub path: PathBuf,
+    pub kind: AssetKind,
+    pub size: u64,
+}
+
+/// Number and total size of the assets of one kind
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetKindTotal {
+    pub kind: AssetKind,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Images, models, archives and other binary files in the repository, which are
+/// never parsed but can make up most of its size
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetInventory {
+    /// Size of every file the walk found, assets included
+    pub total_bytes: u64,
+    pub asset_bytes: u64,
+    /// Largest first
+    pub kinds: Vec<AssetKindTotal>,
+    /// Assets of at least 256 KiB, largest first
+    pub large_assets: Vec<Asset>,
+    /// Set when assets make up most of the repository's size
+    pub asset_dominated: bool,
+}
+
+impl AssetInventory {
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty()
+    }
+
+    pub fn asset_files(&self) -> usize {
+        self.kinds.iter().map(|kind| kind.files).sum()
+    }
+
+    /// Share of the repository's bytes taken up by assets, 0 to 1
+    pub fn asset_share(&self) -> f64 {
+        if self.total_bytes == 0 {
+            0.0
+        } else {
+            self.asset_bytes as f64 / self.total_bytes as f64
+        }
+    }
+
+    /// A size in the largest unit that keeps it above 1
+    pub fn format_size(bytes: u64) -> String {
+        const UNITS: &[&str] = &["KiB", "MiB", "GiB"];
+        if bytes < 1024 {
+            return format!("{} B", bytes);
+        }
+        let mut size = bytes as f64 / 1024.0;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+pub struct AssetAnalyzer {
+    root: PathBuf,
+}
+
+impl AssetAnalyzer {
+    pub fn new(root: &Path) -> Self {
+        Self { root: root.to_path_buf() }
+    }
+
+    pub fn analyze(&self, files: &[FileInfo], sources: &SourceSet) -> AssetInventory {
+        // Assets aren't among the analyzed extensions; an in-memory source set has
+        // only the analyzed files to go on
+        let candidates: Vec<(PathBuf, u64)> = if sources.is_in_memory() {
+            files.iter().map(|f| (f.path.clone(), f.size)).collect()
+        } else {
+            self.walk()
+        };
+
+        let analyzed: HashSet<&Path> = files.iter().map(|f| f.path.as_path()).collect();
+        let mut inventory = AssetInventory::default();
+        let mut kinds: BTreeMap<AssetKind, AssetKindTotal> = BTreeMap::new();
+        let mut large_assets = Vec::new();
+        for (path, size) in candidates {
+            inventory.total_bytes += size;
+            let Some(kind) = self.kind(&path, analyzed.contains(path.as_path()), sources) else { continue };
+            inventory.asset_bytes += size;
+            let total = kinds.entry(kind).or_insert(AssetKindTotal { kind, files: 0, bytes: 0 });
+            total.files += 1;
+            total.bytes += size;
+            if size >= LARGE_ASSET_BYTES {
+                let path = path.strip_prefix(&self.root).unwrap_or(&path).to_path_buf();
+                large_assets.push(Asset { path, kind, size });
+            }
+        }
+
+        large_assets.sort_by_key(|asset| std::cmp::Reverse(asset.size));
+        large_assets.truncate(MAX_LARGE_ASSETS);
+        inventory.large_assets = large_assets;
+        inventory.kinds = kinds.into_values().collect();
+        inventory.kinds.sort_by_key(|kind| std::cmp::Reverse(kind.bytes));
+        inventory.asset_dominated = inventory.asset_bytes >= MIN_DOMINANT_ASSET_BYTES
+            && inventory.asset_share() > DOMINANT_ASSET_SHARE;
+        inventory
+    }
+
+    /// Every file outside `.gitignore`d paths and `node_modules`, with its size
+    fn walk(&self) -> Vec<(PathBuf, u64)> {
+        let mut paths: Vec<(PathBuf, u64)> = WalkBuilder::new(&self.root)
+            .build()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+            .filter(|entry| !entry.path().components().any(|c| c.as_os_str() == "node_modules"))
+            .filter_map(|entry| {
+                let size = entry.metadata().ok()?.len();
+                Some((entry.into_path(), size))
+            })
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// The asset kind from the extension, or `Binary` for an unanalyzed file with
+    /// an unknown extension whose first bytes contain a NUL
+    fn kind(&self, path: &Path, analyzed: bool, sources: &SourceSet) -> Option<AssetKind> {
+        let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+        if let Some(kind) = extension.as_deref().and_then(AssetKind::from_extension) {
+            return Some(kind);
+        }
+        if analyzed || sources.is_in_memory() {
+            return None;
+        }
+        let mut buffer = vec![0; SNIFF_BYTES];
+        let read = File::open(path).and_then(|mut file| file.read(&mut buffer)).ok()?;
+        let buffer = &buffer[..read];
+        // UTF-16 text is full of NULs but starts with a byte order mark
+        let utf16 = buffer.starts_with(&[0xFF, 0xFE]) || buffer.starts_with(&[0xFE, 0xFF]);
+        (!utf16 && buffer.contains(&0)).then_some(AssetKind::Binary)
+    }
+}
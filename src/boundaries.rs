@@ -0,0 +1,267 @@
+use crate::{
+    config::BoundariesConfig,
+    dependency_graph::FileDependency,
+    file_discovery::FileInfo,
+    rules::Severity,
+    sources::SourceSet,
+};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+
+/// Index files a JavaScript package resolves to without a `main` field
+const INDEX_FILES: &[&str] = &["index.js", "index.mjs", "index.cjs", "index.jsx", "index.ts", "index.tsx", "index.d.ts"];
+
+/// A member package of a Cargo, npm/yarn, pnpm or lerna workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspacePackage {
+    pub name: String,
+    /// Relative to the project root
+    pub directory: PathBuf,
+    /// Files other packages may import, relative to the project root
+    pub entry_points: Vec<PathBuf>,
+}
+
+/// An import from one workspace package into another package's files that
+/// bypasses its public entry point
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoundaryViolation {
+    pub severity: Severity,
+    pub from_package: String,
+    pub to_package: String,
+    pub from_file: String,
+    pub to_file: String,
+    pub line: usize,
+    pub reason: String,
+}
+
+/// Detects workspace packages and checks file dependencies against their boundaries
+pub struct BoundaryChecker {
+    root: PathBuf,
+    packages: Vec<WorkspacePackage>,
+    internal_directories: Vec<String>,
+}
+
+impl BoundaryChecker {
+    /// Packages come from the workspace manifests at the project root; member
+    /// globs are matched against the directories of the discovered files
+    pub fn new(root: &Path, config: &BoundariesConfig, files: &[FileInfo], sources: &SourceSet) -> Self {
+        let mut checker = Self {
+            root: root.to_path_buf(),
+            packages: Vec::new(),
+            internal_directories: config.internal_directories.clone(),
+        };
+        if !config.enabled {
+            return checker;
+        }
+
+        let (members, excluded) = checker.member_patterns(sources);
+        let (Some(members), Some(excluded)) = (Self::glob_set(&members), Self::glob_set(&excluded)) else {
+            return checker;
+        };
+        if members.is_empty() {
+            return checker;
+        }
+
+        let mut directories = BTreeSet::new();
+        for file in files {
+            let relative = file.path.strip_prefix(root).unwrap_or(&file.path);
+            directories.extend(relative.ancestors().skip(1).filter(|d| !d.as_os_str().is_empty()).map(Path::to_path_buf));
+        }
+        checker.packages = directories.into_iter()
+            .filter(|directory| members.is_match(directory) && !excluded.is_match(directory))
+            .filter_map(|directory| checker.package(directory, sources))
+            .collect();
+        // Nested members belong to the innermost package, so match longest paths first
+        checker.packages.sort_by(|a, b| b.directory.components().count().cmp(&a.directory.components().count())
+            .then_with(|| a.directory.cmp(&b.directory)));
+        checker
+    }
+
+    /// Detected packages; a project with fewer than two isn't treated as a monorepo
+    pub fn packages(&self) -> &[WorkspacePackage] {
+        &self.packages
+    }
+
+    /// Imports between packages that target anything other than an entry point.
+    /// Targets inside an internal directory are errors, others warnings.
+    pub fn check(&self, file_dependencies: &[FileDependency]) -> Vec<BoundaryViolation> {
+        if self.packages.len() < 2 {
+            return Vec::new();
+        }
+
+        let mut violations: Vec<BoundaryViolation> = file_dependencies.iter()
+            .filter_map(|dep| {
+                let from_file = dep.from.strip_prefix(&self.root).unwrap_or(&dep.from);
+                // What the import statement names; a package's barrel entry point may forward elsewhere
+                let named = dep.via.as_ref().unwrap_or(&dep.to);
+                let to_file = named.strip_prefix(&self.root).unwrap_or(named);
+                let from = self.package_of(from_file)?;
+                let to = self.package_of(to_file)?;
+                if from.directory == to.directory || Self::is_entry_point(to, to_file) {
+                    return None;
+                }
+
+                let inside = to_file.strip_prefix(&to.directory).unwrap_or(to_file);
+                let internal = inside.parent().into_iter()
+                    .flat_map(Path::components)
+                    .filter_map(|component| match component {
+                        Component::Normal(name) => name.to_str(),
+                        _ => None,
+                    })
+                    .find(|name| self.internal_directories.iter().any(|internal| internal == name));
+                let (severity, reason) = match internal {
+                    Some(directory) => (Severity::Error, format!("reaches into {}'s internal `{}` directory", to.name, directory)),
+                    None => (Severity::Warning, format!("bypasses {}'s public entry point", to.name)),
+                };
+                Some(BoundaryViolation {
+                    severity,
+                    from_package: from.name.clone(),
+                    to_package: to.name.clone(),
+                    from_file: from_file.to_string_lossy().to_string(),
+                    to_file: to_file.to_string_lossy().to_string(),
+                    line: dep.line_number,
+                    reason,
+                })
+            })
+            .collect();
+        violations.sort_by(|a, b| b.severity.cmp(&a.severity)
+            .then_with(|| (&a.from_file, a.line).cmp(&(&b.from_file, b.line))));
+        violations
+    }
+
+    fn package_of(&self, relative: &Path) -> Option<&WorkspacePackage> {
+        self.packages.iter().find(|package| relative.starts_with(&package.directory))
+    }
+
+    /// Entry points are compared without extensions, so `src/index` matches
+    /// `src/index.ts`; `exports` subpath patterns like `./features/*.js` are globs
+    fn is_entry_point(package: &WorkspacePackage, file: &Path) -> bool {
+        let stem = file.with_extension("");
+        package.entry_points.iter().any(|entry| {
+            let pattern = entry.to_string_lossy();
+            if pattern.contains('*') {
+                return Glob::new(&pattern).map(|glob| glob.compile_matcher().is_match(file)).unwrap_or(false);
+            }
+            *entry == file || entry.with_extension("") == stem
+        })
+    }
+
+    /// Member and exclude globs from Cargo.toml, package.json, pnpm-workspace.yaml and lerna.json
+    fn member_patterns(&self, sources: &SourceSet) -> (Vec<String>, Vec<String>) {
+        let read = |name: &str| sources.read(&self.root.join(name)).ok();
+        let strings = |values: Option<&serde_json::Value>| -> Vec<String> {
+            values.and_then(|v| v.as_array()).into_iter().flatten()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        };
+        let mut members = Vec::new();
+        let mut excluded = Vec::new();
+
+        if let Some(workspace) = read("Cargo.toml")
+            .and_then(|content| toml::from_str::<toml::Value>(&content).ok())
+            .and_then(|document| document.get("workspace").cloned())
+        {
+            let list = |key: &str| -> Vec<String> {
+                workspace.get(key).and_then(|v| v.as_array()).into_iter().flatten()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            };
+            members.extend(list("members"));
+            excluded.extend(list("exclude"));
+        }
+
+        if let Some(document) = read("package.json").and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok()) {
+            // Either a list or yarn's `{ "packages": [...] }`
+            let workspaces = document.get("workspaces");
+            members.extend(strings(workspaces));
+            members.extend(strings(workspaces.and_then(|w| w.get("packages"))));
+        }
+
+        if let Some(document) = read("pnpm-workspace.yaml").and_then(|content| serde_yaml::from_str::<serde_json::Value>(&content).ok()) {
+            for pattern in strings(document.get("packages")) {
+                match pattern.strip_prefix('!') {
+                    Some(pattern) => excluded.push(pattern.to_string()),
+                    None => members.push(pattern),
+                }
+            }
+        }
+
+        if let Some(document) = read("lerna.json").and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok()) {
+            members.extend(strings(document.get("packages")));
+        }
+
+        let normalize = |pattern: String| pattern.trim_start_matches("./").trim_end_matches('/').to_string();
+        (members.into_iter().map(normalize).collect(), excluded.into_iter().map(normalize).collect())
+    }
+
+    fn glob_set(patterns: &[String]) -> Option<GlobSet> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            // `packages/*` must not match `packages/a/src`
+            builder.add(GlobBuilder::new(pattern).literal_separator(true).build().ok()?);
+        }
+        builder.build().ok()
+    }
+
+    /// The package declared by a manifest in the member directory
+    fn package(&self, directory: PathBuf, sources: &SourceSet) -> Option<WorkspacePackage> {
+        let absolute = self.root.join(&directory);
+        let fallback_name = directory.file_name()?.to_string_lossy().to_string();
+        let mut entry_points: Vec<PathBuf> = Vec::new();
+
+        let name = if let Ok(content) = sources.read(&absolute.join("package.json")) {
+            let document: serde_json::Value = serde_json::from_str(&content).ok()?;
+            for field in ["main", "module", "types", "typings", "browser", "source"] {
+                entry_points.extend(document.get(field).and_then(|v| v.as_str()).map(PathBuf::from));
+            }
+            Self::export_targets(document.get("exports"), &mut entry_points);
+            entry_points.extend(INDEX_FILES.iter().flat_map(|index| [PathBuf::from(index), Path::new("src").join(index)]));
+            document.get("name").and_then(|v| v.as_str()).map(String::from)
+        } else if let Ok(content) = sources.read(&absolute.join("Cargo.toml")) {
+            let document: toml::Value = toml::from_str(&content).ok()?;
+            let lib = document.get("lib").and_then(|lib| lib.get("path")).and_then(|path| path.as_str()).unwrap_or("src/lib.rs");
+            entry_points.push(PathBuf::from(lib));
+            document.get("package").and_then(|p| p.get("name")).and_then(|v| v.as_str()).map(String::from)
+        } else if let Ok(content) = sources.read(&absolute.join("pyproject.toml")) {
+            let document: toml::Value = toml::from_str(&content).ok()?;
+            let name = document.get("project").or_else(|| document.get("tool").and_then(|t| t.get("poetry")))
+                .and_then(|p| p.get("name")).and_then(|v| v.as_str()).map(String::from);
+            // Top-level `__init__.py` of flat and src layouts
+            let module = name.as_deref().unwrap_or(&fallback_name).replace('-', "_");
+            entry_points.push(Path::new(&module).join("__init__.py"));
+            entry_points.push(Path::new("src").join(&module).join("__init__.py"));
+            name
+        } else {
+            return None;
+        };
+
+        let entry_points = entry_points.into_iter()
+            .map(|entry| directory.join(entry.strip_prefix("./").unwrap_or(&entry)))
+            .collect();
+        Some(WorkspacePackage {
+            name: name.unwrap_or(fallback_name),
+            directory,
+            entry_points,
+        })
+    }
+
+    /// Every path string in a package.json `exports` map, including conditional ones
+    fn export_targets(exports: Option<&serde_json::Value>, entry_points: &mut Vec<PathBuf>) {
+        match exports {
+            Some(serde_json::Value::String(target)) => entry_points.push(PathBuf::from(target)),
+            Some(serde_json::Value::Object(map)) => {
+                for value in map.values() {
+                    Self::export_targets(Some(value), entry_points);
+                }
+            }
+            Some(serde_json::Value::Array(values)) => {
+                for value in values {
+                    Self::export_targets(Some(value), entry_points);
+                }
+            }
+            _ => {}
+        }
+    }
+}
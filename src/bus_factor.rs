@@ -0,0 +1,96 @@
+use crate::{git, module_rollup::ModuleAnalyzer, simple_parser::ParsedFile};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// How concentrated the knowledge of one module (directory, relative to the
+/// project root) is among its committers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleBusFactor {
+    pub module: String,
+    /// Non-merge commits touching the module's analyzed files
+    pub commits: usize,
+    /// Distinct commit authors
+    pub authors: usize,
+    pub top_author: String,
+    /// Percentage of the module's commits made by the top author
+    pub top_author_share: f64,
+    /// Fewest authors who together made more than half of the commits
+    pub bus_factor: usize,
+    /// Summed cyclomatic complexity of the module's files
+    pub complexity: usize,
+    /// Complexity weighted by the top author's share and divided by the bus factor;
+    /// higher means harder to maintain if people leave
+    pub knowledge_risk: f64,
+}
+
+/// Computes per-module bus factors from `git log` authorship
+pub struct BusFactorAnalyzer {
+    root: PathBuf,
+    history_commits: usize,
+}
+
+impl BusFactorAnalyzer {
+    pub fn new(root: &Path, history_commits: usize) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            history_commits,
+        }
+    }
+
+    /// Modules with git history, bus factor 1 first and then by knowledge risk;
+    /// empty when the project isn't a git repository
+    pub fn analyze(&self, parsed_files: &[ParsedFile]) -> Vec<ModuleBusFactor> {
+        let Some(file_authors) = git::commit_authors(&self.root, self.history_commits) else {
+            return Vec::new();
+        };
+
+        let mut modules: BTreeMap<String, (HashMap<&str, usize>, usize)> = BTreeMap::new();
+        for pf in parsed_files {
+            let path = &pf.file_info.path;
+            let module = ModuleAnalyzer::module_name(path.strip_prefix(&self.root).unwrap_or(path));
+            let (authors, complexity) = modules.entry(module).or_default();
+            *complexity += pf.cyclomatic_complexity;
+            for (author, commits) in file_authors.get(path).into_iter().flatten() {
+                *authors.entry(author.as_str()).or_insert(0) += commits;
+            }
+        }
+
+        let mut bus_factors: Vec<ModuleBusFactor> = modules.into_iter()
+            .filter(|(_, (authors, _))| !authors.is_empty())
+            .map(|(module, (authors, complexity))| {
+                let mut by_commits: Vec<(&str, usize)> = authors.into_iter().collect();
+                by_commits.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+                let commits: usize = by_commits.iter().map(|(_, count)| count).sum();
+
+                let mut covered = 0;
+                let bus_factor = by_commits.iter()
+                    .take_while(|(_, count)| {
+                        let short = covered * 2 <= commits;
+                        covered += count;
+                        short
+                    })
+                    .count();
+                let top_author_share = by_commits[0].1 as f64 / commits as f64 * 100.0;
+
+                ModuleBusFactor {
+                    module,
+                    commits,
+                    authors: by_commits.len(),
+                    top_author: by_commits[0].0.to_string(),
+                    top_author_share,
+                    bus_factor,
+                    complexity,
+                    knowledge_risk: complexity as f64 * top_author_share / 100.0 / bus_factor as f64,
+                }
+            })
+            .collect();
+
+        bus_factors.sort_by(|a, b| {
+            (a.bus_factor > 1).cmp(&(b.bus_factor > 1))
+                .then_with(|| b.knowledge_risk.total_cmp(&a.knowledge_risk))
+                .then_with(|| a.module.cmp(&b.module))
+        });
+        bus_factors
+    }
+}
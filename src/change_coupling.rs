@@ -0,0 +1,139 @@
+use crate::{config::ChangeCouplingConfig, dependency_graph::FileDependency, git, module_rollup::ModuleAnalyzer, simple_parser::ParsedFile};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Pairs kept in the report, strongest first
+const MAX_PAIRS: usize = 100;
+/// Pairs listed in the LLM context
+const MAX_CONTEXT_PAIRS: usize = 20;
+
+/// Two files that keep changing in the same commits although neither imports
+/// the other
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeCoupling {
+    /// Relative to the project root
+    pub file_a: PathBuf,
+    pub file_b: PathBuf,
+    /// Commits changing both files
+    pub shared_commits: usize,
+    pub commits_a: usize,
+    pub commits_b: usize,
+    /// Shared commits as a percentage of the two files' average commit count
+    pub degree: f64,
+    /// The files are in different modules, so the coupling crosses a boundary
+    pub cross_module: bool,
+}
+
+impl ChangeCoupling {
+    pub fn context_line(&self) -> String {
+        format!("{} <-> {}: changed together in {} commits ({:.0}% coupling){}",
+            self.file_a.display(), self.file_b.display(), self.shared_commits, self.degree,
+            if self.cross_module { ", across modules" } else { "" })
+    }
+}
+
+/// Hidden change coupling mined from recent git history
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChangeCouplingAnalysis {
+    /// Non-merge commits read, including skipped large ones
+    pub commits: usize,
+    /// Commits skipped for touching more than `max_files_per_commit` files
+    pub skipped_commits: usize,
+    /// Strongly coupled pairs that also have an import between them, which the
+    /// dependency graph already explains
+    pub explained_pairs: usize,
+    /// Strongest first
+    pub pairs: Vec<ChangeCoupling>,
+}
+
+impl ChangeCouplingAnalysis {
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// One line per pair for the Architecture analysis' LLM context
+    pub fn context_lines(&self) -> Vec<String> {
+        self.pairs.iter().take(MAX_CONTEXT_PAIRS).map(ChangeCoupling::context_line).collect()
+    }
+}
+
+/// Finds analyzed files that change together in `git log` without a static
+/// dependency edge between them
+pub struct ChangeCouplingAnalyzer {
+    root: PathBuf,
+    config: ChangeCouplingConfig,
+}
+
+impl ChangeCouplingAnalyzer {
+    pub fn new(root: &Path, config: &ChangeCouplingConfig) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            config: config.clone(),
+        }
+    }
+
+    /// Empty when the project isn't a git repository
+    pub fn analyze(&self, parsed_files: &[ParsedFile], file_dependencies: &[FileDependency]) -> ChangeCouplingAnalysis {
+        let Some(commits) = git::commit_files(&self.root, self.config.history_commits) else {
+            return ChangeCouplingAnalysis::default();
+        };
+
+        // Only files still in the tree and analyzed; renamed or deleted paths drop out
+        let analyzed: HashSet<&Path> = parsed_files.iter().map(|pf| pf.file_info.path.as_path()).collect();
+        let mut analysis = ChangeCouplingAnalysis { commits: commits.len(), ..Default::default() };
+        let mut file_commits: HashMap<&Path, usize> = HashMap::new();
+        let mut shared: BTreeMap<(&Path, &Path), usize> = BTreeMap::new();
+        for files in &commits {
+            if files.len() > self.config.max_files_per_commit {
+                analysis.skipped_commits += 1;
+                continue;
+            }
+            let mut files: Vec<&Path> = files.iter().map(PathBuf::as_path).filter(|path| analyzed.contains(path)).collect();
+            files.sort();
+            files.dedup();
+            for (i, a) in files.iter().enumerate() {
+                *file_commits.entry(a).or_default() += 1;
+                for b in &files[i + 1..] {
+                    *shared.entry((a, b)).or_default() += 1;
+                }
+            }
+        }
+
+        let linked: HashSet<(&Path, &Path)> = file_dependencies.iter()
+            .flat_map(|dependency| [(dependency.from.as_path(), dependency.to.as_path()), (dependency.to.as_path(), dependency.from.as_path())])
+            .collect();
+        let module = |path: &Path| ModuleAnalyzer::module_name(path.strip_prefix(&self.root).unwrap_or(path));
+        for ((a, b), shared_commits) in shared {
+            if shared_commits < self.config.min_shared_commits {
+                continue;
+            }
+            let (commits_a, commits_b) = (file_commits[a], file_commits[b]);
+            let degree = shared_commits as f64 * 200.0 / (commits_a + commits_b) as f64;
+            if degree < self.config.min_degree {
+                continue;
+            }
+            if linked.contains(&(a, b)) {
+                analysis.explained_pairs += 1;
+                continue;
+            }
+            analysis.pairs.push(ChangeCoupling {
+                file_a: a.strip_prefix(&self.root).unwrap_or(a).to_path_buf(),
+                file_b: b.strip_prefix(&self.root).unwrap_or(b).to_path_buf(),
+                shared_commits,
+                commits_a,
+                commits_b,
+                degree,
+                cross_module: module(a) != module(b),
+            });
+        }
+
+        analysis.pairs.sort_by(|a, b| {
+            b.degree.total_cmp(&a.degree)
+                .then(b.shared_commits.cmp(&a.shared_commits))
+                .then_with(|| (&a.file_a, &a.file_b).cmp(&(&b.file_a, &b.file_b)))
+        });
+        analysis.pairs.truncate(MAX_PAIRS);
+        analysis
+    }
+}
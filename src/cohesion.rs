@@ -0,0 +1,240 @@
+use crate::{simple_parser::{Function, ParsedFile}, sources::SourceSet};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Classes with fewer connected methods than this aren't worth splitting
+const MIN_METHODS: usize = 4;
+/// Classes listed in the LLM context
+const MAX_CONTEXT_CLASSES: usize = 20;
+
+/// Constructors touch every field, which would make any class look cohesive
+const CONSTRUCTORS: &[&str] = &["__init__", "__new__", "constructor", "new", "default"];
+
+/// Methods that share fields or call each other, and the fields they share
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MethodGroup {
+    pub methods: Vec<String>,
+    pub fields: Vec<String>,
+}
+
+/// A class whose methods fall into unrelated groups (LCOM4 > 1): a candidate
+/// for splitting along those groups
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassCohesion {
+    pub file: String,
+    pub class: String,
+    pub line: usize,
+    /// Methods using instance state, constructors excluded
+    pub methods: usize,
+    pub fields: usize,
+    /// Connected groups of methods (LCOM4); 1 means fully cohesive
+    pub lcom: usize,
+    /// Largest first
+    pub groups: Vec<MethodGroup>,
+    /// Methods touching no fields and no other methods, which could be free functions
+    pub stateless_methods: Vec<String>,
+}
+
+impl ClassCohesion {
+    pub fn context_line(&self) -> String {
+        format!("{}:{} {}: {} unrelated method groups ({})", self.file, self.line, self.class, self.lcom,
+            self.groups.iter()
+                .map(|group| format!("{} using {}", group.methods.join(", "),
+                    if group.fields.is_empty() { "no fields".to_string() } else { group.fields.join(", ") }))
+                .collect::<Vec<_>>()
+                .join(" | "))
+    }
+}
+
+/// Measures LCOM4 per class: methods are linked when they use the same field
+/// or one calls the other, and each connected group could be its own class.
+/// Fields and calls are found through the receiver (`self.x`, `this.x`,
+/// `Self::f()`), as in the languages the parser extracts classes from.
+pub struct CohesionAnalyzer {
+    receiver_access: Regex,
+    rust_impl: Regex,
+}
+
+impl Default for CohesionAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CohesionAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            receiver_access: Regex::new(r"\b(?:self|this)\s*(?:\.|->)\s*([A-Za-z_]\w*)(\s*\()?|\bSelf::([A-Za-z_]\w*)\s*(\()").unwrap(),
+            rust_impl: Regex::new(r"^\s*impl\b(?:\s*<[^{]*?>)?\s+(?:[\w:]+(?:<[^{]*?>)?\s+for\s+)?(?:\w+::)*(\w+)").unwrap(),
+        }
+    }
+
+    /// Low-cohesion classes across all files, least cohesive first
+    pub fn analyze(&self, parsed_files: &[ParsedFile], sources: &SourceSet) -> Vec<ClassCohesion> {
+        let mut classes = Vec::new();
+        for pf in parsed_files.iter().filter(|pf| !pf.classes.is_empty()) {
+            let Ok(content) = sources.read(&pf.file_info.path) else { continue };
+            let lines: Vec<&str> = content.lines().collect();
+            let language = pf.file_info.language.as_deref().unwrap_or_default();
+            let impls = if language == "rust" { self.rust_impls(&lines) } else { Vec::new() };
+
+            for class in &pf.classes {
+                let mut methods = Self::class_methods(pf, class.line_number, class.end_line, &class.methods, &class.name, &impls);
+                if language == "rust" {
+                    // Associated functions have no instance to share state through
+                    methods.retain(|method| method.parameters.first().is_some_and(|receiver| receiver.ends_with("self")));
+                }
+                if let Some(mut cohesion) = self.measure(&lines, &class.name, &methods) {
+                    cohesion.file = pf.file_info.path.to_string_lossy().to_string();
+                    cohesion.class = class.name.clone();
+                    cohesion.line = class.line_number;
+                    classes.push(cohesion);
+                }
+            }
+        }
+        classes.sort_by(|a, b| b.lcom.cmp(&a.lcom).then(b.methods.cmp(&a.methods)).then_with(|| (&a.file, a.line).cmp(&(&b.file, b.line))));
+        classes
+    }
+
+    pub fn context_lines(classes: &[ClassCohesion]) -> Vec<String> {
+        classes.iter().take(MAX_CONTEXT_CLASSES).map(ClassCohesion::context_line).collect()
+    }
+
+    /// Functions declared in the class body, or in Rust `impl` blocks for the
+    /// type; functions nested in another method are left out
+    fn class_methods<'a>(pf: &'a ParsedFile, start: usize, end: usize, declared: &'a [Function], name: &str, impls: &[(String, usize, usize)]) -> Vec<&'a Function> {
+        let inside = |function: &Function| {
+            (function.line_number > start && function.line_number <= end)
+                || impls.iter().any(|(ty, from, to)| ty == name && function.line_number > *from && function.line_number <= *to)
+        };
+        let candidates: Vec<&Function> = pf.functions.iter().filter(|f| inside(f)).chain(declared.iter()).collect();
+        candidates.iter()
+            .filter(|f| !candidates.iter().any(|outer| outer.line_number < f.line_number && outer.end_line >= f.end_line && outer.end_line > f.line_number))
+            .copied()
+            .collect()
+    }
+
+    /// `impl Type` and `impl Trait for Type` blocks as (type, first line, last line)
+    fn rust_impls(&self, lines: &[&str]) -> Vec<(String, usize, usize)> {
+        let mut impls = Vec::new();
+        for (index, line) in lines.iter().enumerate() {
+            let Some(captures) = self.rust_impl.captures(line) else { continue };
+            let mut depth = 0i32;
+            let mut opened = false;
+            let mut end = index;
+            'scan: for (offset, body) in lines[index..].iter().enumerate() {
+                for c in body.chars() {
+                    match c {
+                        '{' => {
+                            depth += 1;
+                            opened = true;
+                        }
+                        '}' => depth -= 1,
+                        _ => {}
+                    }
+                    if opened && depth == 0 {
+                        end = index + offset;
+                        break 'scan;
+                    }
+                }
+            }
+            impls.push((captures[1].to_string(), index + 1, end + 1));
+        }
+        impls
+    }
+
+    fn measure(&self, lines: &[&str], class: &str, methods: &[&Function]) -> Option<ClassCohesion> {
+        let names: BTreeSet<&str> = methods.iter().map(|m| m.name.as_str()).collect();
+        let mut uses: BTreeMap<&str, (BTreeSet<String>, BTreeSet<&str>)> = BTreeMap::new();
+        let constructor = |name: &str| name == class || CONSTRUCTORS.contains(&name);
+        for method in methods.iter().filter(|m| !constructor(&m.name) && m.end_line >= m.line_number) {
+            let body = lines.get(method.line_number.saturating_sub(1)..method.end_line.min(lines.len())).unwrap_or_default().join("\n");
+            let (fields, calls) = uses.entry(method.name.as_str()).or_default();
+            // A name followed by `(` is a call, so a getter named after its field is told apart
+            let mut used = |name: &str, call: bool| {
+                match names.get(name) {
+                    Some(&callee) if call && callee != method.name => {
+                        calls.insert(callee);
+                    }
+                    _ if !call => {
+                        fields.insert(name.to_string());
+                    }
+                    _ => {}
+                }
+            };
+            for captures in self.receiver_access.captures_iter(&body) {
+                let name = captures.get(1).or(captures.get(3)).map(|m| m.as_str()).unwrap_or_default();
+                used(name, captures.get(2).or(captures.get(4)).is_some());
+            }
+        }
+
+        let (stateful, stateless): (Vec<&str>, Vec<&str>) = uses.keys().copied()
+            .partition(|name| {
+                let (fields, calls) = &uses[name];
+                !fields.is_empty() || !calls.is_empty() || uses.values().any(|(_, calls)| calls.contains(name))
+            });
+        if stateful.len() < MIN_METHODS {
+            return None;
+        }
+
+        // Union-find over methods linked by a shared field or a call
+        let index: BTreeMap<&str, usize> = stateful.iter().enumerate().map(|(i, name)| (*name, i)).collect();
+        let mut parent: Vec<usize> = (0..stateful.len()).collect();
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        let mut union = |a: usize, b: usize| {
+            let (a, b) = (root(&mut parent, a), root(&mut parent, b));
+            parent[a] = b;
+        };
+        let mut field_owner: BTreeMap<&str, usize> = BTreeMap::new();
+        for (i, name) in stateful.iter().enumerate() {
+            let (fields, calls) = &uses[name];
+            for field in fields {
+                match field_owner.get(field.as_str()) {
+                    Some(&owner) => union(owner, i),
+                    None => {
+                        field_owner.insert(field.as_str(), i);
+                    }
+                }
+            }
+            for callee in calls.iter().filter_map(|callee| index.get(callee)) {
+                union(i, *callee);
+            }
+        }
+
+        let mut groups: BTreeMap<usize, MethodGroup> = BTreeMap::new();
+        for (i, name) in stateful.iter().enumerate() {
+            let group = groups.entry(root(&mut parent, i)).or_insert_with(|| MethodGroup { methods: Vec::new(), fields: Vec::new() });
+            group.methods.push(name.to_string());
+            group.fields.extend(uses[name].0.iter().cloned());
+        }
+        let mut groups: Vec<MethodGroup> = groups.into_values()
+            .map(|mut group| {
+                group.fields.sort();
+                group.fields.dedup();
+                group
+            })
+            .collect();
+        if groups.len() < 2 {
+            return None;
+        }
+        groups.sort_by_key(|group| std::cmp::Reverse(group.methods.len()));
+
+        Some(ClassCohesion {
+            file: String::new(),
+            class: String::new(),
+            line: 0,
+            methods: stateful.len(),
+            fields: field_owner.len(),
+            lcom: groups.len(),
+            groups,
+            stateless_methods: stateless.iter().map(|name| name.to_string()).collect(),
+        })
+    }
+}
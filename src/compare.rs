@@ -0,0 +1,249 @@
+use crate::{
+    analyzer::ProjectAnalysis,
+    config::MaintainabilityConfig,
+    maintainability::MaintainabilityAnalyzer,
+    module_rollup::ModuleAnalyzer,
+};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Size, complexity and dependency structure of one compared target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetSummary {
+    pub path: PathBuf,
+    pub files: usize,
+    pub total_size: u64,
+    pub lines_of_code: usize,
+    /// Files per language
+    pub languages: BTreeMap<String, usize>,
+    pub functions: usize,
+    pub classes: usize,
+    /// Mean cyclomatic complexity per file
+    pub avg_complexity: f64,
+    pub max_complexity: usize,
+    /// Mean maintainability index (0-100)
+    pub avg_maintainability: f64,
+    pub modules: usize,
+    pub dependency_edges: usize,
+    pub dependency_cycles: usize,
+    pub avg_degree: f64,
+}
+
+/// A module (directory, relative to its target) present in both targets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedModule {
+    pub module: String,
+    pub left_files: usize,
+    pub right_files: usize,
+    pub left_lines_of_code: usize,
+    pub right_lines_of_code: usize,
+}
+
+/// A file at the same relative path in both targets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedFile {
+    pub path: PathBuf,
+    /// Byte-for-byte identical; otherwise the copies have diverged
+    pub identical: bool,
+}
+
+/// Side-by-side comparison produced by `project-examer compare`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comparison {
+    pub left: TargetSummary,
+    pub right: TargetSummary,
+    pub shared_modules: Vec<SharedModule>,
+    pub left_only_modules: Vec<String>,
+    pub right_only_modules: Vec<String>,
+    pub shared_files: Vec<SharedFile>,
+}
+
+/// Compares the analyses of two targets, e.g. two services or a fork and its upstream
+pub struct Comparer {
+    maintainability: MaintainabilityConfig,
+}
+
+impl Comparer {
+    pub fn new(maintainability: MaintainabilityConfig) -> Self {
+        Self { maintainability }
+    }
+
+    pub fn compare(&self, left_root: &Path, left: &ProjectAnalysis, right_root: &Path, right: &ProjectAnalysis) -> Comparison {
+        let left_modules = Self::modules(left_root, left);
+        let right_modules = Self::modules(right_root, right);
+
+        let shared_modules = left_modules.iter()
+            .filter_map(|(module, &(left_files, left_loc))| {
+                let &(right_files, right_loc) = right_modules.get(module)?;
+                Some(SharedModule {
+                    module: module.clone(),
+                    left_files,
+                    right_files,
+                    left_lines_of_code: left_loc,
+                    right_lines_of_code: right_loc,
+                })
+            })
+            .collect();
+        let left_only_modules = left_modules.keys().filter(|m| !right_modules.contains_key(*m)).cloned().collect();
+        let right_only_modules = right_modules.keys().filter(|m| !left_modules.contains_key(*m)).cloned().collect();
+
+        let right_files: BTreeSet<PathBuf> = right.files.iter().map(|f| Self::relative(right_root, &f.path)).collect();
+        let shared_files = left.files.iter()
+            .map(|f| Self::relative(left_root, &f.path))
+            .filter(|path| right_files.contains(path))
+            .map(|path| {
+                let identical = match (fs::read(left_root.join(&path)), fs::read(right_root.join(&path))) {
+                    (Ok(a), Ok(b)) => a == b,
+                    _ => false,
+                };
+                SharedFile { path, identical }
+            })
+            .collect();
+
+        Comparison {
+            left: self.summarize(left_root, left, left_modules.len()),
+            right: self.summarize(right_root, right, right_modules.len()),
+            shared_modules,
+            left_only_modules,
+            right_only_modules,
+            shared_files,
+        }
+    }
+
+    fn summarize(&self, root: &Path, analysis: &ProjectAnalysis, modules: usize) -> TargetSummary {
+        let parsed = &analysis.parsed_files;
+        let mut languages = BTreeMap::new();
+        for file in &analysis.files {
+            *languages.entry(file.language.clone().unwrap_or_else(|| "unknown".to_string())).or_insert(0) += 1;
+        }
+        let maintainability = MaintainabilityAnalyzer::new(self.maintainability.clone()).analyze(parsed);
+        let mean = |total: f64, count: usize| if count == 0 { 0.0 } else { total / count as f64 };
+
+        TargetSummary {
+            path: root.to_path_buf(),
+            files: analysis.files.len(),
+            total_size: analysis.files.iter().map(|f| f.size).sum(),
+            lines_of_code: parsed.iter().map(|pf| pf.lines_of_code).sum(),
+            languages,
+            functions: parsed.iter().map(|pf| pf.functions.len()).sum(),
+            classes: parsed.iter().map(|pf| pf.classes.len()).sum(),
+            avg_complexity: mean(parsed.iter().map(|pf| pf.cyclomatic_complexity as f64).sum(), parsed.len()),
+            max_complexity: parsed.iter().map(|pf| pf.cyclomatic_complexity).max().unwrap_or(0),
+            avg_maintainability: mean(maintainability.iter().map(|m| m.index).sum(), maintainability.len()),
+            modules,
+            dependency_edges: analysis.file_dependencies.len(),
+            dependency_cycles: analysis.dependency_analysis.strongly_connected_components,
+            avg_degree: analysis.dependency_analysis.avg_degree,
+        }
+    }
+
+    /// Files and lines of code per module, keyed by directory relative to the target
+    fn modules(root: &Path, analysis: &ProjectAnalysis) -> BTreeMap<String, (usize, usize)> {
+        let mut modules: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+        for pf in &analysis.parsed_files {
+            let module = ModuleAnalyzer::module_name(&Self::relative(root, &pf.file_info.path));
+            let entry = modules.entry(module).or_default();
+            entry.0 += 1;
+            entry.1 += pf.lines_of_code;
+        }
+        modules
+    }
+
+    fn relative(root: &Path, path: &Path) -> PathBuf {
+        path.strip_prefix(root).unwrap_or(path).to_path_buf()
+    }
+}
+
+impl Comparison {
+    pub fn print(&self) {
+        println!("\n⚖️  Comparison");
+        println!("=============");
+        for (label, left, right) in self.rows() {
+            println!("  {:<24} {:>14} {:>14}", label, left, right);
+        }
+        println!("\n  Shared modules: {} ({} only in left, {} only in right)",
+            self.shared_modules.len(), self.left_only_modules.len(), self.right_only_modules.len());
+        println!("  Shared files: {} ({} identical)",
+            self.shared_files.len(), self.shared_files.iter().filter(|f| f.identical).count());
+    }
+
+    /// Metric rows as (label, left, right)
+    fn rows(&self) -> Vec<(&'static str, String, String)> {
+        let (l, r) = (&self.left, &self.right);
+        let mb = |bytes: u64| format!("{:.2} MB", bytes as f64 / (1024.0 * 1024.0));
+        vec![
+            ("Files", l.files.to_string(), r.files.to_string()),
+            ("Size", mb(l.total_size), mb(r.total_size)),
+            ("Lines of code", l.lines_of_code.to_string(), r.lines_of_code.to_string()),
+            ("Functions", l.functions.to_string(), r.functions.to_string()),
+            ("Classes", l.classes.to_string(), r.classes.to_string()),
+            ("Avg complexity / file", format!("{:.1}", l.avg_complexity), format!("{:.1}", r.avg_complexity)),
+            ("Max complexity", l.max_complexity.to_string(), r.max_complexity.to_string()),
+            ("Avg maintainability", format!("{:.1}", l.avg_maintainability), format!("{:.1}", r.avg_maintainability)),
+            ("Modules", l.modules.to_string(), r.modules.to_string()),
+            ("Dependency edges", l.dependency_edges.to_string(), r.dependency_edges.to_string()),
+            ("Dependency cycles", l.dependency_cycles.to_string(), r.dependency_cycles.to_string()),
+            ("Avg degree", format!("{:.2}", l.avg_degree), format!("{:.2}", r.avg_degree)),
+        ]
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("# Project Comparison\n\n");
+        md.push_str(&format!("| Metric | {} | {} |\n|--------|------|------|\n", self.left.path.display(), self.right.path.display()));
+        for (label, left, right) in self.rows() {
+            md.push_str(&format!("| {} | {} | {} |\n", label, left, right));
+        }
+
+        md.push_str("\n## Languages (files)\n\n| Language | Left | Right |\n|----------|------|-------|\n");
+        let languages: BTreeSet<&String> = self.left.languages.keys().chain(self.right.languages.keys()).collect();
+        for language in languages {
+            md.push_str(&format!("| {} | {} | {} |\n", language,
+                self.left.languages.get(language).copied().unwrap_or(0),
+                self.right.languages.get(language).copied().unwrap_or(0)));
+        }
+
+        md.push_str(&format!("\n## Shared Modules\n\n{} modules exist in both; {} only in left, {} only in right.\n\n",
+            self.shared_modules.len(), self.left_only_modules.len(), self.right_only_modules.len()));
+        if !self.shared_modules.is_empty() {
+            md.push_str("| Module | Left Files | Right Files | Left LOC | Right LOC |\n|--------|------------|-------------|----------|-----------|\n");
+            for module in &self.shared_modules {
+                md.push_str(&format!("| {} | {} | {} | {} | {} |\n", module.module,
+                    module.left_files, module.right_files, module.left_lines_of_code, module.right_lines_of_code));
+            }
+        }
+        for (title, modules) in [("Only in Left", &self.left_only_modules), ("Only in Right", &self.right_only_modules)] {
+            if !modules.is_empty() {
+                md.push_str(&format!("\n### {}\n\n", title));
+                for module in modules {
+                    md.push_str(&format!("- {}\n", module));
+                }
+            }
+        }
+
+        if !self.shared_files.is_empty() {
+            let identical = self.shared_files.iter().filter(|f| f.identical).count();
+            md.push_str(&format!("\n## Shared Files\n\n{} files exist at the same path in both; {} are identical and {} have diverged.\n\n",
+                self.shared_files.len(), identical, self.shared_files.len() - identical));
+            for file in self.shared_files.iter().filter(|f| !f.identical) {
+                md.push_str(&format!("- {} (diverged)\n", file.path.display()));
+            }
+        }
+        md
+    }
+
+    /// Write `comparison.json` and `comparison.md` to the output directory
+    pub fn export(&self, output_dir: &Path) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+
+        let json_path = output_dir.join("comparison.json");
+        fs::write(&json_path, serde_json::to_string_pretty(self)?).map_err(|e| Error::report(&json_path, e))?;
+
+        let md_path = output_dir.join("comparison.md");
+        fs::write(&md_path, self.to_markdown()).map_err(|e| Error::report(&md_path, e))?;
+
+        Ok(vec![json_path, md_path])
+    }
+}
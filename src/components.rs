@@ -0,0 +1,270 @@
+use crate::{
+    dependency_graph::FileDependency,
+    graph_export::escape_xml,
+    module_rollup::{main_sequence, SequenceZone},
+    pipeline::LocalFindings,
+    simple_parser::ParsedFile,
+};
+use crate::{Error, Result};
+use globset::{Glob, GlobMatcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::f64::consts::PI;
+use std::path::{Path, PathBuf};
+
+/// Metrics, coupling and findings for one `[components]` entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentMetrics {
+    pub name: String,
+    pub files: usize,
+    pub lines_of_code: usize,
+    pub functions: usize,
+    pub classes: usize,
+    /// Functions plus twice the classes, as for module rollups
+    pub complexity: usize,
+    /// Number of other components depending on this one (Ca)
+    pub afferent_coupling: usize,
+    /// Number of other components this one depends on (Ce)
+    pub efferent_coupling: usize,
+    /// Ce / (Ca + Ce)
+    pub instability: f64,
+    /// Abstract types / total types
+    #[serde(default)]
+    pub abstractness: f64,
+    /// |A + I - 1|
+    #[serde(default)]
+    pub distance: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<SequenceZone>,
+    pub code_smells: usize,
+    pub debt_markers: usize,
+    pub rule_findings: usize,
+    pub performance_signals: usize,
+    /// Architecture and package boundary violations starting in the component
+    pub boundary_violations: usize,
+}
+
+impl ComponentMetrics {
+    pub fn findings(&self) -> usize {
+        self.code_smells + self.debt_markers + self.rule_findings + self.performance_signals + self.boundary_violations
+    }
+}
+
+/// File-level imports from one component into another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentDependency {
+    pub from: String,
+    pub to: String,
+    pub imports: usize,
+}
+
+/// Files grouped into the named components from `[components]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ComponentAnalysis {
+    /// In declaration order of the config table, which is alphabetical
+    pub components: Vec<ComponentMetrics>,
+    /// Most imports first
+    pub dependencies: Vec<ComponentDependency>,
+    /// Parsed files no component's globs match
+    pub unassigned_files: usize,
+}
+
+impl ComponentAnalysis {
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Component dependency diagram as a Mermaid flowchart
+    pub fn mermaid(&self) -> String {
+        let id: HashMap<&str, usize> = self.components.iter().enumerate().map(|(i, c)| (c.name.as_str(), i)).collect();
+        let mut diagram = String::from("graph LR\n");
+        for (i, component) in self.components.iter().enumerate() {
+            diagram.push_str(&format!("    c{}[\"{} ({} files)\"]\n", i, component.name.replace('"', "#quot;"), component.files));
+        }
+        for dependency in &self.dependencies {
+            diagram.push_str(&format!("    c{} -->|{}| c{}\n", id[dependency.from.as_str()], dependency.imports, id[dependency.to.as_str()]));
+        }
+        diagram
+    }
+
+    /// Component dependency diagram as an inline SVG, components on a circle and
+    /// arrows as thick as their share of the imports
+    pub fn render_svg(&self) -> String {
+        const SIZE: f64 = 560.0;
+        const BOX_WIDTH: f64 = 130.0;
+        const BOX_HEIGHT: f64 = 34.0;
+        let count = self.components.len();
+        let radius = if count == 1 { 0.0 } else { SIZE / 2.0 - BOX_WIDTH / 2.0 - 10.0 };
+        let position = |i: usize| {
+            let angle = 2.0 * PI * i as f64 / count as f64 - PI / 2.0;
+            (SIZE / 2.0 + radius * angle.cos(), SIZE / 2.0 + radius * angle.sin())
+        };
+        let index: HashMap<&str, usize> = self.components.iter().enumerate().map(|(i, c)| (c.name.as_str(), i)).collect();
+        let max_imports = self.dependencies.iter().map(|d| d.imports).max().unwrap_or(1) as f64;
+
+        let mut svg = format!(
+            r##"<svg class="component-diagram" viewBox="0 0 {s} {s}" width="100%" style="max-width:{s}px" xmlns="http://www.w3.org/2000/svg"><defs><marker id="component-arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto-start-reverse"><path d="M0,0 L10,5 L0,10 z" fill="#555"/></marker></defs>"##,
+            s = SIZE
+        );
+        for dependency in &self.dependencies {
+            let (x1, y1) = position(index[dependency.from.as_str()]);
+            let (x2, y2) = position(index[dependency.to.as_str()]);
+            // Stop at the target's box edge so the arrowhead stays visible
+            let (dx, dy) = (x2 - x1, y2 - y1);
+            let scale = ((BOX_WIDTH / 2.0) / dx.abs().max(1e-9)).min((BOX_HEIGHT / 2.0) / dy.abs().max(1e-9));
+            let (end_x, end_y) = (x2 - dx * scale.min(1.0), y2 - dy * scale.min(1.0));
+            let width = 1.0 + 4.0 * dependency.imports as f64 / max_imports;
+            svg.push_str(&format!(
+                r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#555" stroke-opacity="0.6" stroke-width="{:.1}" marker-end="url(#component-arrow)"><title>{} → {}: {} import(s)</title></line>"##,
+                x1, y1, end_x, end_y, width, escape_xml(&dependency.from), escape_xml(&dependency.to), dependency.imports
+            ));
+        }
+        for (i, component) in self.components.iter().enumerate() {
+            let (x, y) = position(i);
+            svg.push_str(&format!(
+                r##"<rect x="{:.1}" y="{:.1}" width="{}" height="{}" rx="6" fill="#e8f0fe" stroke="#3b6fd4"><title>{}: {} files, {} LOC, {} finding(s)</title></rect><text x="{:.1}" y="{:.1}" font-size="12" text-anchor="middle" pointer-events="none">{}</text>"##,
+                x - BOX_WIDTH / 2.0, y - BOX_HEIGHT / 2.0, BOX_WIDTH, BOX_HEIGHT,
+                escape_xml(&component.name), component.files, component.lines_of_code, component.findings(),
+                x, y + 4.0, escape_xml(&component.name)
+            ));
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+struct Component {
+    name: String,
+    /// Each glob with its pattern length; the longest matching pattern wins
+    globs: Vec<(usize, GlobMatcher)>,
+}
+
+/// Maps files to the components declared in `[components]`
+pub struct ComponentMapper {
+    root: PathBuf,
+    components: Vec<Component>,
+}
+
+impl ComponentMapper {
+    pub fn new(root: &Path, components: &BTreeMap<String, Vec<String>>) -> Result<Self> {
+        let components = components.iter()
+            .map(|(name, patterns)| {
+                let globs = patterns.iter()
+                    .map(|pattern| {
+                        let glob = Glob::new(pattern).map_err(|e| Error::Config(format!("Invalid path for component '{}': {}", name, e)))?;
+                        Ok((pattern.len(), glob.compile_matcher()))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Component { name: name.clone(), globs })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { root: root.to_path_buf(), components })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// The component with the most specific (longest) glob matching the path, so
+    /// `src/payments/**` wins over `src/**`
+    pub fn component_of(&self, path: &Path) -> Option<usize> {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        self.components.iter().enumerate()
+            .filter_map(|(i, component)| {
+                component.globs.iter()
+                    .filter(|(_, glob)| glob.is_match(relative))
+                    .map(|(length, _)| *length)
+                    .max()
+                    .map(|length| (length, i))
+            })
+            .max_by_key(|(length, i)| (*length, std::cmp::Reverse(*i)))
+            .map(|(_, i)| i)
+    }
+
+    /// Roll parsed files, their dependencies and the local findings up to components
+    pub fn analyze(&self, parsed_files: &[ParsedFile], file_dependencies: &[FileDependency], findings: &LocalFindings) -> ComponentAnalysis {
+        if self.is_empty() {
+            return ComponentAnalysis::default();
+        }
+
+        let mut components: Vec<ComponentMetrics> = self.components.iter()
+            .map(|component| ComponentMetrics {
+                name: component.name.clone(),
+                files: 0,
+                lines_of_code: 0,
+                functions: 0,
+                classes: 0,
+                complexity: 0,
+                afferent_coupling: 0,
+                efferent_coupling: 0,
+                instability: 0.0,
+                abstractness: 0.0,
+                distance: 0.0,
+                zone: None,
+                code_smells: 0,
+                debt_markers: 0,
+                rule_findings: 0,
+                performance_signals: 0,
+                boundary_violations: 0,
+            })
+            .collect();
+        let mut abstract_classes = vec![0usize; components.len()];
+        let mut unassigned_files = 0;
+        for pf in parsed_files {
+            let Some(i) = self.component_of(&pf.file_info.path) else {
+                unassigned_files += 1;
+                continue;
+            };
+            let component = &mut components[i];
+            component.files += 1;
+            component.lines_of_code += pf.lines_of_code;
+            component.functions += pf.functions.len();
+            component.classes += pf.classes.len();
+            abstract_classes[i] += pf.classes.iter().filter(|c| c.is_abstract).count();
+            component.complexity += pf.functions.len() + pf.classes.len() * 2;
+        }
+
+        let mut imports: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        for dependency in file_dependencies {
+            if let (Some(from), Some(to)) = (self.component_of(&dependency.from), self.component_of(&dependency.to)) {
+                if from != to {
+                    *imports.entry((from, to)).or_default() += 1;
+                }
+            }
+        }
+        for &(from, to) in imports.keys() {
+            components[from].efferent_coupling += 1;
+            components[to].afferent_coupling += 1;
+        }
+        for (component, abstract_classes) in components.iter_mut().zip(abstract_classes) {
+            let coupling = component.afferent_coupling + component.efferent_coupling;
+            component.instability = if coupling == 0 { 0.0 } else { component.efferent_coupling as f64 / coupling as f64 };
+            component.abstractness = if component.classes == 0 { 0.0 } else { abstract_classes as f64 / component.classes as f64 };
+            (component.distance, component.zone) = main_sequence(component.abstractness, component.instability, component.afferent_coupling, component.classes);
+        }
+
+        let mut count = |file: &str, field: fn(&mut ComponentMetrics) -> &mut usize| {
+            if let Some(i) = self.component_of(Path::new(file)) {
+                *field(&mut components[i]) += 1;
+            }
+        };
+        findings.code_smells.iter().for_each(|smell| count(&smell.file, |c| &mut c.code_smells));
+        findings.debt_markers.iter().for_each(|marker| count(&marker.file, |c| &mut c.debt_markers));
+        findings.rule_findings.iter().for_each(|finding| count(&finding.file, |c| &mut c.rule_findings));
+        findings.performance_signals.iter().for_each(|signal| count(&signal.file, |c| &mut c.performance_signals));
+        findings.architecture_violations.iter().for_each(|violation| count(&violation.from_file, |c| &mut c.boundary_violations));
+        findings.boundary_violations.iter().for_each(|violation| count(&violation.from_file, |c| &mut c.boundary_violations));
+
+        let mut dependencies: Vec<ComponentDependency> = imports.into_iter()
+            .map(|((from, to), imports)| ComponentDependency {
+                from: components[from].name.clone(),
+                to: components[to].name.clone(),
+                imports,
+            })
+            .collect();
+        dependencies.sort_by_key(|dependency| std::cmp::Reverse(dependency.imports));
+
+        ComponentAnalysis { components, dependencies, unassigned_files }
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{file_discovery::FileInfo, module_rollup::ModuleAnalyzer, sources::SourceSet};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Sites listed per module in the LLM context
+const SITES_PER_MODULE: usize = 10;
+
+/// A kind of concurrency primitive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConcurrencyKind {
+    /// OS threads, goroutines and thread pools
+    Thread,
+    /// Spawned async tasks and futures
+    AsyncTask,
+    /// Mutexes, read-write locks, semaphores and `synchronized`
+    Lock,
+    /// Channels and concurrent queues
+    Channel,
+    /// Atomics and `volatile`
+    Atomic,
+    /// `unsafe` blocks, functions and impls
+    Unsafe,
+}
+
+impl ConcurrencyKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConcurrencyKind::Thread => "thread",
+            ConcurrencyKind::AsyncTask => "async task",
+            ConcurrencyKind::Lock => "lock",
+            ConcurrencyKind::Channel => "channel",
+            ConcurrencyKind::Atomic => "atomic",
+            ConcurrencyKind::Unsafe => "unsafe",
+        }
+    }
+
+    fn is_spawn(&self) -> bool {
+        matches!(self, ConcurrencyKind::Thread | ConcurrencyKind::AsyncTask)
+    }
+
+    fn is_shared_state(&self) -> bool {
+        matches!(self, ConcurrencyKind::Lock | ConcurrencyKind::Atomic)
+    }
+}
+
+/// One use of a concurrency primitive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConcurrencySite {
+    pub file: PathBuf,
+    pub line: usize,
+    pub kind: ConcurrencyKind,
+    /// The matched source line, trimmed
+    pub code: String,
+}
+
+/// Concurrency primitives used by one module (directory, relative to the project root)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConcurrencyHotspot {
+    pub module: String,
+    pub counts: BTreeMap<ConcurrencyKind, usize>,
+    pub total: usize,
+    /// Spawns threads or tasks and also guards shared state, where races are most likely
+    pub spawns_with_shared_state: bool,
+}
+
+/// Threads, tasks, locks, channels, atomics and unsafe code found in Rust, Go and Java/Kotlin
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConcurrencyInventory {
+    pub sites: Vec<ConcurrencySite>,
+    /// Modules that spawn work and share state first, then by number of sites
+    pub hotspots: Vec<ConcurrencyHotspot>,
+}
+
+impl ConcurrencyInventory {
+    pub fn is_empty(&self) -> bool {
+        self.sites.is_empty()
+    }
+
+    /// Hotspot summaries followed by their first sites, for the Concurrency LLM analysis
+    pub fn context_lines(&self, root: &Path) -> Vec<String> {
+        let mut lines = Vec::new();
+        for hotspot in &self.hotspots {
+            let counts = hotspot.counts.iter()
+                .map(|(kind, count)| format!("{} {}", count, kind.name()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            lines.push(format!("Module {}: {}{}", hotspot.module, counts,
+                if hotspot.spawns_with_shared_state { " (spawns work and shares state)" } else { "" }));
+            let sites = self.sites.iter()
+                .filter(|site| ModuleAnalyzer::module_name(site.file.strip_prefix(root).unwrap_or(&site.file)) == hotspot.module)
+                .take(SITES_PER_MODULE);
+            for site in sites {
+                lines.push(format!("  {}:{} [{}] {}", site.file.display(), site.line, site.kind.name(), site.code));
+            }
+        }
+        lines
+    }
+}
+
+/// Per-language patterns recognizing concurrency primitives line by line
+pub struct ConcurrencyScanner {
+    root: PathBuf,
+    patterns: Vec<(&'static [&'static str], ConcurrencyKind, Regex)>,
+}
+
+impl ConcurrencyScanner {
+    pub fn new(root: &Path) -> Self {
+        const RUST: &[&str] = &["rust"];
+        const GO: &[&str] = &["go"];
+        const JVM: &[&str] = &["java", "kotlin"];
+        let patterns: Vec<(&'static [&'static str], ConcurrencyKind, &str)> = vec![
+            (RUST, ConcurrencyKind::Thread, r"\bthread::(?:spawn|scope|Builder)\b|\brayon::|\.par_iter\(\)"),
+            (RUST, ConcurrencyKind::AsyncTask, r"\b(?:tokio|task|async_std::task)::spawn(?:_blocking|_local)?\b|\bJoinSet\b"),
+            (RUST, ConcurrencyKind::Lock, r"\b(?:Mutex|RwLock|Condvar|Semaphore|Barrier)\s*(?:<|::new)"),
+            (RUST, ConcurrencyKind::Channel, r"\b(?:mpsc|broadcast|watch|oneshot)::(?:channel|unbounded_channel|sync_channel)\b|\bcrossbeam(?:_channel)?::|\bflume::"),
+            (RUST, ConcurrencyKind::Atomic, r"\bAtomic(?:Bool|Usize|Isize|U8|U16|U32|U64|I8|I16|I32|I64|Ptr)\b"),
+            (RUST, ConcurrencyKind::Unsafe, r"\bunsafe\s*(?:\{|fn\b|impl\b)"),
+            (GO, ConcurrencyKind::Thread, r"^\s*go\s+(?:func\b|[\w.]+\()"),
+            (GO, ConcurrencyKind::AsyncTask, r"\bsync\.WaitGroup\b|\berrgroup\."),
+            (GO, ConcurrencyKind::Lock, r"\bsync\.(?:Mutex|RWMutex|Cond|Once)\b"),
+            (GO, ConcurrencyKind::Channel, r"\bmake\(\s*chan\b|\bchan\s*<-|<-\s*chan\b|\bselect\s*\{"),
+            (GO, ConcurrencyKind::Atomic, r"\batomic\.\w+"),
+            (GO, ConcurrencyKind::Unsafe, r"\bunsafe\.Pointer\b"),
+            (JVM, ConcurrencyKind::Thread, r"\bnew\s+Thread\s*\(|\bextends\s+Thread\b|\bExecutors\.\w+|\bExecutorService\b|\bForkJoinPool\b|\bparallelStream\(\)"),
+            (JVM, ConcurrencyKind::AsyncTask, r"\bCompletableFuture\b|@Async\b|\b(?:launch|async)\s*\{|\bGlobalScope\b"),
+            (JVM, ConcurrencyKind::Lock, r"\bsynchronized\b|\b(?:ReentrantLock|ReentrantReadWriteLock|StampedLock|Semaphore|CountDownLatch)\b|@Synchronized\b"),
+            (JVM, ConcurrencyKind::Channel, r"\b\w*BlockingQueue\b|\bConcurrentLinkedQueue\b|\bExchanger\b|\bChannel<"),
+            (JVM, ConcurrencyKind::Atomic, r"\bAtomic(?:Integer|Long|Boolean|Reference)\b|\bvolatile\b|@Volatile\b"),
+            (JVM, ConcurrencyKind::Unsafe, r"\bsun\.misc\.Unsafe\b"),
+        ];
+
+        Self {
+            root: root.to_path_buf(),
+            patterns: patterns.into_iter()
+                .map(|(languages, kind, pattern)| (languages, kind, Regex::new(pattern).expect("valid concurrency pattern")))
+                .collect(),
+        }
+    }
+
+    pub fn analyze(&self, files: &[FileInfo], sources: &SourceSet) -> ConcurrencyInventory {
+        let mut sites = Vec::new();
+        for file in files {
+            let Some(language) = file.language.as_deref() else { continue };
+            let patterns: Vec<_> = self.patterns.iter().filter(|(languages, _, _)| languages.contains(&language)).collect();
+            if patterns.is_empty() {
+                continue;
+            }
+            let Ok(content) = sources.read(&file.path) else { continue };
+
+            for (index, line) in content.lines().enumerate() {
+                let trimmed = line.trim();
+                if ["//", "/*", "*", "use ", "import "].iter().any(|prefix| trimmed.starts_with(prefix)) {
+                    continue;
+                }
+                // One site per kind and line, so `Arc<Mutex<..>>::new(Mutex::new(..))` counts once
+                let mut seen = Vec::new();
+                for (_, kind, regex) in &patterns {
+                    if !seen.contains(kind) && regex.is_match(line) {
+                        seen.push(*kind);
+                        sites.push(ConcurrencySite {
+                            file: file.path.clone(),
+                            line: index + 1,
+                            kind: *kind,
+                            code: trimmed.chars().take(160).collect(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut modules: BTreeMap<String, BTreeMap<ConcurrencyKind, usize>> = BTreeMap::new();
+        for site in &sites {
+            let module = ModuleAnalyzer::module_name(site.file.strip_prefix(&self.root).unwrap_or(&site.file));
+            *modules.entry(module).or_default().entry(site.kind).or_insert(0) += 1;
+        }
+        let mut hotspots: Vec<ConcurrencyHotspot> = modules.into_iter()
+            .map(|(module, counts)| ConcurrencyHotspot {
+                total: counts.values().sum(),
+                spawns_with_shared_state: counts.keys().any(|kind| kind.is_spawn()) && counts.keys().any(|kind| kind.is_shared_state()),
+                module,
+                counts,
+            })
+            .collect();
+        hotspots.sort_by_key(|hotspot| std::cmp::Reverse((hotspot.spawns_with_shared_state, hotspot.total)));
+
+        ConcurrencyInventory { sites, hotspots }
+    }
+}
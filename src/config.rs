@@ -1,5 +1,21 @@
+use crate::error::Error;
+use crate::llm::AnalysisType;
+use crate::pipeline::Stage;
+use crate::rules::{Rule, Severity};
+use crate::simple_parser::DEFAULT_MAX_LINE_LENGTH;
 use serde::{Deserialize, Serialize};
-use std::{env, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    env,
+    path::{Path, PathBuf},
+};
+
+/// File name used for both the home-directory and project-local config files
+pub const CONFIG_FILE_NAME: &str = ".project-examer.toml";
+
+/// Prefix for environment variables that override config keys; nested keys
+/// are separated by `__` (e.g. `PROJECT_EXAMER_LLM__MODEL`)
+pub const ENV_PREFIX: &str = "PROJECT_EXAMER_";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -9,6 +25,526 @@ pub struct Config {
     pub max_file_size: usize,
     pub llm: LLMConfig,
     pub analysis: AnalysisConfig,
+    #[serde(default)]
+    pub discovery: DiscoveryConfig,
+    #[serde(default)]
+    pub pipeline: PipelineConfig,
+    #[serde(default)]
+    pub report: ReportConfig,
+    #[serde(default)]
+    pub scorecard: ScorecardConfig,
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+    #[serde(default)]
+    pub offline: OfflineConfig,
+    #[serde(default)]
+    pub ownership: OwnershipConfig,
+    #[serde(default)]
+    pub process: ProcessConfig,
+    #[serde(default)]
+    pub change_coupling: ChangeCouplingConfig,
+    #[serde(default)]
+    pub registry: RegistryConfig,
+    #[serde(default)]
+    pub osv: OsvConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    #[serde(default)]
+    pub issues: IssuesConfig,
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
+    #[serde(default)]
+    pub boundaries: BoundariesConfig,
+    #[serde(default)]
+    pub architecture: ArchitectureConfig,
+    /// Named groups of files (`[components]`), each a list of path globs
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub components: BTreeMap<String, Vec<String>>,
+    /// Custom rules evaluated locally (`[[rules]]`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<Rule>,
+    /// Named overrides selectable with `--profile` (`[profile.<name>]`)
+    #[serde(default, rename = "profile", skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+/// File discovery policy (`[discovery]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiscoveryConfig {
+    /// Honor `linguist-vendored`, `linguist-generated` and `linguist-language`
+    /// from `.gitattributes` files
+    pub respect_gitattributes: bool,
+    /// File name globs included even when their extension is missing or not in
+    /// `file_extensions` (case-insensitive), e.g. `Makefile`, `Dockerfile.*`
+    pub include_filenames: Vec<String>,
+    /// Languages included regardless of extension; extension-less scripts are
+    /// detected from their shebang line
+    pub include_languages: Vec<String>,
+    /// Skip generated code: `@generated` markers, protobuf/grpc outputs,
+    /// `*.g.dart` and minified JavaScript/CSS
+    pub skip_generated: bool,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            respect_gitattributes: true,
+            include_filenames: ["Makefile", "GNUmakefile", "Dockerfile", "Dockerfile.*", "*.dockerfile", "Jenkinsfile"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            include_languages: Vec::new(),
+            skip_generated: true,
+        }
+    }
+}
+
+/// Analysis stages and their order (`[pipeline]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PipelineConfig {
+    pub stages: Vec<Stage>,
+    /// Keep the stage checkpoints in `<output>/pipeline/` after a successful run
+    pub persist_artifacts: bool,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            stages: Stage::ALL.to_vec(),
+            persist_artifacts: false,
+        }
+    }
+}
+
+/// Report output options (`[report]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportConfig {
+    /// Sort every collection and fix the timestamp so identical inputs produce
+    /// byte-identical JSON
+    pub deterministic: bool,
+    /// `generated_at` used in deterministic reports
+    pub timestamp: String,
+    /// Lowest severity reported as a failure in `junit.xml` (`--fail-on` overrides it)
+    pub junit_fail_on: Severity,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            deterministic: false,
+            timestamp: "1970-01-01T00:00:00Z".to_string(),
+            junit_fail_on: Severity::Warning,
+        }
+    }
+}
+
+impl ReportConfig {
+    /// The fixed timestamp as Unix time when deterministic, for measuring ages
+    pub fn reference_time(&self) -> Option<i64> {
+        if !self.deterministic {
+            return None;
+        }
+        chrono::DateTime::parse_from_rfc3339(&self.timestamp).ok().map(|time| time.timestamp())
+    }
+}
+
+/// Grade cutoffs and deductions for the scorecard (`[scorecard]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScorecardConfig {
+    /// Lowest 0-100 category score earning an A, B, C and D; anything lower is an F
+    pub grade_a: f64,
+    pub grade_b: f64,
+    pub grade_c: f64,
+    pub grade_d: f64,
+    /// Architecture points lost per layer violation
+    pub architecture_violation_penalty: f64,
+    /// Dependencies points lost per dependency cycle
+    pub dependency_cycle_penalty: f64,
+    /// Security points lost per Critical, High and Medium security recommendation
+    pub security_critical_penalty: f64,
+    pub security_high_penalty: f64,
+    pub security_medium_penalty: f64,
+    /// Security points lost per SQL query built by concatenation or formatting
+    pub dynamic_query_penalty: f64,
+}
+
+impl Default for ScorecardConfig {
+    fn default() -> Self {
+        Self {
+            grade_a: 90.0,
+            grade_b: 80.0,
+            grade_c: 70.0,
+            grade_d: 60.0,
+            architecture_violation_penalty: 5.0,
+            dependency_cycle_penalty: 10.0,
+            security_critical_penalty: 30.0,
+            security_high_penalty: 15.0,
+            security_medium_penalty: 5.0,
+            dynamic_query_penalty: 5.0,
+        }
+    }
+}
+
+/// Masking applied to everything sent to LLM providers (`[redaction]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    /// Replace string literals in source excerpts
+    pub strip_string_literals: bool,
+    pub mask_emails: bool,
+    /// Credential assignments, private keys and well-known token formats
+    pub mask_secrets: bool,
+    pub mask_ip_addresses: bool,
+    /// Globs (relative to the target directory) for files kept out of prompts entirely
+    pub sensitive_paths: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            strip_string_literals: false,
+            mask_emails: true,
+            mask_secrets: true,
+            mask_ip_addresses: true,
+            sensitive_paths: ["**/.env", "**/.env.*", "**/*.pem", "**/*.key", "**/secrets/**", "**/*credentials*"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Air-gapped runs with no network access (`[offline]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OfflineConfig {
+    pub enabled: bool,
+    /// Keep an Ollama provider whose `base_url` is a loopback address
+    pub allow_local_llm: bool,
+}
+
+/// Codebase metrics pushed to a Prometheus Pushgateway or OTLP collector (`[metrics]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// Pushgateway base URL; metrics are pushed under the job and labels
+    pub pushgateway_url: Option<String>,
+    /// OTLP/HTTP metrics endpoint (JSON encoding)
+    pub otlp_endpoint: Option<String>,
+    /// Pushgateway job and OTLP `service.name`
+    pub job: String,
+    pub timeout_seconds: u64,
+    /// Added to every metric; the project name is always included
+    pub labels: BTreeMap<String, String>,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            pushgateway_url: None,
+            otlp_endpoint: None,
+            job: "project-examer".to_string(),
+            timeout_seconds: 10,
+            labels: BTreeMap::new(),
+        }
+    }
+}
+
+impl MetricsConfig {
+    /// Whether any push target is configured
+    pub fn push_enabled(&self) -> bool {
+        self.pushgateway_url.is_some() || self.otlp_endpoint.is_some()
+    }
+}
+
+/// Chat notifications sent when an analysis completes (`[notifications]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    pub slack_webhook_url: Option<String>,
+    pub teams_webhook_url: Option<String>,
+    /// Link to the published report; the local HTML report when unset
+    pub report_url: Option<String>,
+    /// Critical findings listed in the message
+    pub max_findings: usize,
+    pub timeout_seconds: u64,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            slack_webhook_url: None,
+            teams_webhook_url: None,
+            report_url: None,
+            max_findings: 5,
+            timeout_seconds: 10,
+        }
+    }
+}
+
+impl NotificationsConfig {
+    /// Whether any webhook is configured
+    pub fn enabled(&self) -> bool {
+        self.slack_webhook_url.is_some() || self.teams_webhook_url.is_some()
+    }
+}
+
+/// Issue tracker used by `export-issues` (`[issues]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IssuesConfig {
+    pub tracker: IssueTrackerKind,
+    /// `owner/name`
+    pub github_repo: Option<String>,
+    pub github_api_url: String,
+    /// Falls back to `GITHUB_TOKEN`
+    pub github_token: Option<String>,
+    pub jira_url: Option<String>,
+    /// Project key, e.g. `ENG`
+    pub jira_project: Option<String>,
+    pub jira_email: Option<String>,
+    /// Falls back to `JIRA_API_TOKEN`
+    pub jira_token: Option<String>,
+    pub jira_issue_type: String,
+    /// Added to every issue; the first one finds issues filed earlier
+    pub labels: Vec<String>,
+    pub timeout_seconds: u64,
+}
+
+impl Default for IssuesConfig {
+    fn default() -> Self {
+        Self {
+            tracker: IssueTrackerKind::Github,
+            github_repo: None,
+            github_api_url: "https://api.github.com".to_string(),
+            github_token: None,
+            jira_url: None,
+            jira_project: None,
+            jira_email: None,
+            jira_token: None,
+            jira_issue_type: "Task".to_string(),
+            labels: vec!["project-examer".to_string()],
+            timeout_seconds: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum IssueTrackerKind {
+    #[default]
+    Github,
+    Jira,
+}
+
+/// Periodic re-analysis by `project-examer run` (`[schedule]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    /// Five-field cron expression in local time; `--schedule` overrides it
+    pub cron: Option<String>,
+    /// Newest run directories kept, 0 keeps all
+    pub keep_runs: usize,
+    /// Run directories older than this are deleted, 0 disables the age limit
+    pub max_age_days: u64,
+    /// Analyze once at startup instead of waiting for the first scheduled time
+    pub run_on_start: bool,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            cron: None,
+            keep_runs: 14,
+            max_age_days: 0,
+            run_on_start: false,
+        }
+    }
+}
+
+/// File ownership attribution (`[ownership]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OwnershipConfig {
+    /// Attribute files to owners using CODEOWNERS
+    pub enabled: bool,
+    /// Fall back to the top `git blame` author for files CODEOWNERS doesn't cover
+    pub git_blame: bool,
+    /// Compute each module's bus factor from git commit authorship
+    pub bus_factor: bool,
+    /// Most recent commits read for the bus factor; 0 reads the whole history
+    pub history_commits: usize,
+}
+
+impl Default for OwnershipConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            git_blame: false,
+            bus_factor: true,
+            history_commits: 2000,
+        }
+    }
+}
+
+/// Git history audit for the report's process health section (`[process]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessConfig {
+    pub enabled: bool,
+    /// Most recent non-merge commits audited; 0 reads the whole history
+    pub history_commits: usize,
+    /// Commits changing more lines than this count as large
+    pub large_commit_lines: usize,
+    /// Unmerged branches whose first commit is older than this are long-lived
+    pub long_lived_branch_days: u64,
+}
+
+impl Default for ProcessConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            history_commits: 200,
+            large_commit_lines: 500,
+            long_lived_branch_days: 30,
+        }
+    }
+}
+
+/// Files that change together in git history without importing each other
+/// (`[change_coupling]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChangeCouplingConfig {
+    pub enabled: bool,
+    /// Most recent non-merge commits read; 0 reads the whole history
+    pub history_commits: usize,
+    /// Pairs changed together in fewer commits than this are ignored
+    pub min_shared_commits: usize,
+    /// Smallest coupling degree reported, in percent: shared commits over the
+    /// average number of commits of the two files
+    pub min_degree: f64,
+    /// Commits touching more files than this (mass renames, formatting) are skipped
+    pub max_files_per_commit: usize,
+}
+
+impl Default for ChangeCouplingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            history_commits: 1000,
+            min_shared_commits: 3,
+            min_degree: 50.0,
+            max_files_per_commit: 30,
+        }
+    }
+}
+
+/// Dependency freshness lookups against package registries (`[registry]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegistryConfig {
+    /// Also turned on by `--check-registry`
+    pub enabled: bool,
+    /// Registry base URLs, replaceable with mirrors
+    pub crates_io_url: String,
+    pub npm_url: String,
+    pub pypi_url: String,
+    pub timeout_seconds: u64,
+    /// Lookups in flight at once
+    pub concurrency: usize,
+    /// Check development, test and build dependencies too
+    pub include_dev: bool,
+}
+
+impl Default for RegistryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            crates_io_url: "https://crates.io".to_string(),
+            npm_url: "https://registry.npmjs.org".to_string(),
+            pypi_url: "https://pypi.org".to_string(),
+            timeout_seconds: 10,
+            concurrency: 8,
+            include_dev: true,
+        }
+    }
+}
+
+/// Known-vulnerability lookups against OSV.dev (`[osv]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OsvConfig {
+    /// Also turned on by `--check-vulnerabilities`
+    pub enabled: bool,
+    /// OSV API base URL, replaceable with a self-hosted mirror
+    pub api_url: String,
+    pub timeout_seconds: u64,
+    /// Check development, test and build dependencies too
+    pub include_dev: bool,
+}
+
+impl Default for OsvConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            api_url: "https://api.osv.dev".to_string(),
+            timeout_seconds: 20,
+            include_dev: true,
+        }
+    }
+}
+
+/// Monorepo package boundary checks (`[boundaries]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BoundariesConfig {
+    pub enabled: bool,
+    /// Directory names whose files are private to their package
+    pub internal_directories: Vec<String>,
+}
+
+impl Default for BoundariesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            internal_directories: vec!["internal".to_string(), "private".to_string(), "_internal".to_string()],
+        }
+    }
+}
+
+/// Declared layer model checked against the dependency graph (`[architecture]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchitectureConfig {
+    pub layers: Vec<LayerConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerConfig {
+    pub name: String,
+    /// Globs relative to the analyzed directory
+    pub paths: Vec<String>,
+    /// Other layers this layer is allowed to import
+    #[serde(default)]
+    pub may_depend_on: Vec<String>,
+}
+
+/// Overrides applied on top of the base configuration when a profile is selected
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileConfig {
+    pub max_depth: Option<usize>,
+    pub analysis_types: Option<Vec<AnalysisType>>,
+    pub model: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,13 +556,63 @@ pub struct LLMConfig {
     pub max_tokens: usize,
     pub temperature: f32,
     pub timeout_seconds: u64,
+    /// Models tried in order when the primary model keeps failing
+    #[serde(default)]
+    pub fallback_models: Vec<String>,
+    /// Retries per model on rate limits (429), server errors (5xx), connection
+    /// failures and timeouts
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Run a critic call over the analyses before the report is generated
+    #[serde(default)]
+    pub enable_review_pass: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn default_max_retries() -> u32 {
+    2
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum LLMProvider {
     OpenAI,
     Ollama,
     Anthropic,
+    /// LLM analysis disabled; only local analysis runs
+    None,
+}
+
+impl LLMProvider {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LLMProvider::OpenAI => "OpenAI",
+            LLMProvider::Ollama => "Ollama",
+            LLMProvider::Anthropic => "Anthropic",
+            LLMProvider::None => "None",
+        }
+    }
+}
+
+impl LLMConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.provider != LLMProvider::None
+    }
+
+    /// Ollama on localhost / a loopback address; the only provider usable offline
+    pub fn is_local(&self) -> bool {
+        if self.provider != LLMProvider::Ollama {
+            return false;
+        }
+        let Some(ref base_url) = self.base_url else {
+            return true;
+        };
+        let host = base_url.split("://").nth(1).unwrap_or(base_url)
+            .split(['/', '?']).next().unwrap_or_default();
+        let host = match host.strip_prefix('[') {
+            Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+            None => host.rsplit_once(':').map_or(host, |(host, _)| host),
+        };
+        host == "localhost" || host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +622,177 @@ pub struct AnalysisConfig {
     pub include_architecture_patterns: bool,
     pub include_security_analysis: bool,
     pub max_depth: usize,
+    /// LLM analysis types to run, in order
+    #[serde(default = "default_analysis_types")]
+    pub analysis_types: Vec<AnalysisType>,
+    #[serde(default)]
+    pub scoring: ScoringConfig,
+    #[serde(default)]
+    pub maintainability: MaintainabilityConfig,
+    #[serde(default)]
+    pub smells: SmellConfig,
+    /// Dependency cycles spanning more files than this are flagged as architectural risks
+    #[serde(default = "default_max_component_size")]
+    pub max_component_size: usize,
+    /// Restrict analysis to these paths (relative to the target directory) or package names
+    #[serde(default)]
+    pub scope: Vec<String>,
+    /// Ask the LLM for a short summary of each module (adds one request per 8 modules)
+    #[serde(default)]
+    pub summarize_modules: bool,
+    /// Describe functions in the LLM context by signature and the first line of
+    /// their doc comment instead of by name alone
+    #[serde(default)]
+    pub function_signatures: bool,
+    /// Estimated prompt tokens `function_signatures` may add; files past the
+    /// budget keep the names-only listing
+    #[serde(default = "default_function_signature_tokens")]
+    pub function_signature_tokens: usize,
+    /// Who the report is written for; `Onboarding` adds a guided tour for new developers
+    #[serde(default)]
+    pub audience: Audience,
+    /// Analyze at most this many files, chosen by `sampling`; 0 means no limit
+    #[serde(default)]
+    pub max_files: usize,
+    /// How files are prioritized when the project has more than `max_files`
+    #[serde(default)]
+    pub sampling: SamplingStrategy,
+    /// Metrics-only runs for very large repositories: no LLM, no documentation
+    /// drift or git blame, and parsed files are compacted as they are read
+    #[serde(default)]
+    pub low_memory: bool,
+    /// Worker threads for parsing and scanning; 0 uses one per CPU core
+    #[serde(default)]
+    pub threads: usize,
+    /// Maximum files read from disk at once; 0 means no limit beyond `threads`
+    #[serde(default)]
+    pub io_concurrency: usize,
+    /// Files with a longer line, in bytes, such as minified bundles, get
+    /// size-only metrics instead of being parsed for symbols; 0 means no limit
+    #[serde(default = "default_max_line_length")]
+    pub max_line_length: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Audience {
+    /// Code health, risks and recommendations for the people maintaining the project
+    #[default]
+    Maintainer,
+    /// Entry points, main flows, reading order and a domain glossary for new team members
+    Onboarding,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SamplingStrategy {
+    /// Largest files first
+    Size,
+    /// Files imported by the most other files first
+    #[default]
+    Centrality,
+    /// Most recently committed (or modified) files first
+    Recency,
+}
+
+impl SamplingStrategy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SamplingStrategy::Size => "size",
+            SamplingStrategy::Centrality => "centrality",
+            SamplingStrategy::Recency => "recency",
+        }
+    }
+}
+
+fn default_max_line_length() -> usize {
+    DEFAULT_MAX_LINE_LENGTH
+}
+
+fn default_max_component_size() -> usize {
+    5
+}
+
+fn default_function_signature_tokens() -> usize {
+    8000
+}
+
+fn default_analysis_types() -> Vec<AnalysisType> {
+    vec![AnalysisType::Overview, AnalysisType::Architecture, AnalysisType::Dependencies]
+}
+
+/// Weights for the complexity/maintainability scoring model (`[analysis.scoring]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringConfig {
+    pub function_weight: f64,
+    pub class_weight: f64,
+    pub import_weight: f64,
+    pub loc_weight: f64,
+    pub halstead_weight: f64,
+    pub max_complexity: f64,
+    pub base_maintainability: f64,
+    pub complexity_penalty: f64,
+    pub coupling_penalty: f64,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self {
+            function_weight: 1.0,
+            class_weight: 2.0,
+            import_weight: 1.0,
+            loc_weight: 0.0,
+            halstead_weight: 0.0,
+            max_complexity: 10.0,
+            base_maintainability: 10.0,
+            complexity_penalty: 0.5,
+            coupling_penalty: 0.3,
+        }
+    }
+}
+
+/// Rating thresholds for the per-file maintainability index (`[analysis.maintainability]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaintainabilityConfig {
+    /// Files with an index below this (0-100) are rated yellow
+    pub yellow_below: f64,
+    /// Files with an index below this (0-100) are rated red
+    pub red_below: f64,
+}
+
+impl Default for MaintainabilityConfig {
+    fn default() -> Self {
+        Self {
+            yellow_below: 40.0,
+            red_below: 20.0,
+        }
+    }
+}
+
+/// Thresholds for local code smell detection (`[analysis.smells]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmellConfig {
+    /// Functions longer than this many lines are flagged
+    pub max_function_lines: usize,
+    /// Functions with more parameters than this are flagged
+    pub max_parameters: usize,
+    /// Functions whose blocks nest deeper than this are flagged
+    pub max_nesting: usize,
+    /// Classes declaring more methods than this in their body are flagged as god classes
+    pub max_class_methods: usize,
+}
+
+impl Default for SmellConfig {
+    fn default() -> Self {
+        Self {
+            max_function_lines: 60,
+            max_parameters: 5,
+            max_nesting: 4,
+            max_class_methods: 20,
+        }
+    }
 }
 
 impl Default for Config {
@@ -53,8 +810,6 @@ impl Default for Config {
                 ".env.*".to_string(),
                 "*.min.js".to_string(),
                 "*.map".to_string(),
-                "test-*".to_string(),
-                "test_*".to_string(),
             ],
             file_extensions: vec![
                 "rs".to_string(),
@@ -76,6 +831,11 @@ impl Default for Config {
                 "json".to_string(),
                 "html".to_string(),
                 "css".to_string(),
+                "sql".to_string(),
+                "sh".to_string(),
+                "bash".to_string(),
+                "tf".to_string(),
+                "hcl".to_string(),
             ],
             max_file_size: 1024 * 1024, // 1MB
             llm: LLMConfig {
@@ -86,6 +846,9 @@ impl Default for Config {
                 max_tokens: 4000,
                 temperature: 0.1,
                 timeout_seconds: 300,
+                fallback_models: Vec::new(),
+                max_retries: default_max_retries(),
+                enable_review_pass: false,
             },
             analysis: AnalysisConfig {
                 include_dependencies: true,
@@ -93,43 +856,450 @@ impl Default for Config {
                 include_architecture_patterns: true,
                 include_security_analysis: false,
                 max_depth: 10,
+                analysis_types: default_analysis_types(),
+                scoring: ScoringConfig::default(),
+                maintainability: MaintainabilityConfig::default(),
+                smells: SmellConfig::default(),
+                max_component_size: default_max_component_size(),
+                scope: Vec::new(),
+                summarize_modules: false,
+                function_signatures: false,
+                function_signature_tokens: default_function_signature_tokens(),
+                audience: Audience::default(),
+                max_files: 0,
+                sampling: SamplingStrategy::default(),
+                low_memory: false,
+                threads: 0,
+                io_concurrency: 0,
+                max_line_length: default_max_line_length(),
             },
+            discovery: DiscoveryConfig::default(),
+            pipeline: PipelineConfig::default(),
+            report: ReportConfig::default(),
+            scorecard: ScorecardConfig::default(),
+            redaction: RedactionConfig::default(),
+            offline: OfflineConfig::default(),
+            ownership: OwnershipConfig::default(),
+            process: ProcessConfig::default(),
+            change_coupling: ChangeCouplingConfig::default(),
+            registry: RegistryConfig::default(),
+            osv: OsvConfig::default(),
+            metrics: MetricsConfig::default(),
+            notifications: NotificationsConfig::default(),
+            issues: IssuesConfig::default(),
+            schedule: ScheduleConfig::default(),
+            boundaries: BoundariesConfig::default(),
+            architecture: ArchitectureConfig::default(),
+            components: BTreeMap::new(),
+            rules: Vec::new(),
+            profiles: BTreeMap::new(),
         }
     }
 }
 
+/// Builds a [`Config`] in code, starting from the defaults, for applications
+/// embedding the analyzer; created with [`Config::builder`]
+#[derive(Debug, Clone, Default)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    pub fn target_directory(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.target_directory = path.into();
+        self
+    }
+
+    /// LLM provider; `LLMProvider::None` runs the local analyses only
+    pub fn provider(mut self, provider: LLMProvider) -> Self {
+        self.config.llm.provider = provider;
+        self
+    }
+
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.config.llm.model = model.into();
+        self
+    }
+
+    /// API key; when unset, `OPENAI_API_KEY` or `ANTHROPIC_API_KEY` is used on build
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.config.llm.api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.config.llm.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Add to the default ignore patterns
+    pub fn add_ignore(mut self, pattern: impl Into<String>) -> Self {
+        self.config.ignore_patterns.push(pattern.into());
+        self
+    }
+
+    /// Replace the ignore patterns, defaults included
+    pub fn ignore_patterns<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.ignore_patterns = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Replace the analyzed file extensions; a leading `.` is dropped
+    pub fn extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.file_extensions = extensions.into_iter()
+            .map(|extension| extension.into().trim_start_matches('.').to_string())
+            .collect();
+        self
+    }
+
+    pub fn max_file_size(mut self, bytes: usize) -> Self {
+        self.config.max_file_size = bytes;
+        self
+    }
+
+    /// LLM analysis types to run, in order
+    pub fn analysis_types(mut self, analysis_types: impl IntoIterator<Item = AnalysisType>) -> Self {
+        self.config.analysis.analysis_types = analysis_types.into_iter().collect();
+        self
+    }
+
+    /// Restrict the analysis to a path (relative to the target directory) or package name
+    pub fn add_scope(mut self, scope: impl Into<String>) -> Self {
+        self.config.analysis.scope.push(scope.into());
+        self
+    }
+
+    /// Guarantee no network calls (see `[offline]`)
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.config.offline.enabled = offline;
+        self
+    }
+
+    /// Sorted output and a fixed timestamp (see `[report]`)
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.config.report.deterministic = deterministic;
+        self
+    }
+
+    /// Add a named component (see `[components]`)
+    pub fn component<I, S>(mut self, name: impl Into<String>, globs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.components.insert(name.into(), globs.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Add a custom rule (see `[[rules]]`)
+    pub fn add_rule(mut self, rule: Rule) -> Self {
+        self.config.rules.push(rule);
+        self
+    }
+
+    /// Change any other setting directly
+    pub fn configure(mut self, configure: impl FnOnce(&mut Config)) -> Self {
+        configure(&mut self.config);
+        self
+    }
+
+    /// Validate the configuration (see `Config::validate`) and fill in the
+    /// provider's API key from the environment when none is set
+    pub fn build(self) -> crate::Result<Config> {
+        let mut config = self.config;
+        config.validate()?;
+        config.apply_api_key_env(&Config::env_vars());
+        Ok(config)
+    }
+}
+
 impl Config {
+    /// Start from the defaults and set what differs, validated on `build()`:
+    ///
+    /// ```no_run
+    /// # use project_examer::{Config, config::LLMProvider};
+    /// let config = Config::builder()
+    ///     .target_directory("./my-project")
+    ///     .provider(LLMProvider::None)
+    ///     .extensions(["rs", "toml"])
+    ///     .add_ignore("fixtures")
+    ///     .build()?;
+    /// # Ok::<(), project_examer::Error>(())
+    /// ```
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
+    /// Fail on what the analyzer would reject later: invalid globs and regexes
+    /// in rules, components, architecture layers, redaction and the pipeline,
+    /// or settings that can't work
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.file_extensions.is_empty() || self.file_extensions.iter().any(|extension| extension.is_empty()) {
+            return Err(Error::Config("file_extensions must list at least one non-empty extension".to_string()));
+        }
+        if self.ignore_patterns.iter().any(|pattern| pattern.is_empty()) {
+            return Err(Error::Config("ignore_patterns must not contain an empty pattern".to_string()));
+        }
+        if self.max_file_size == 0 {
+            return Err(Error::Config("max_file_size must be greater than 0".to_string()));
+        }
+        if self.llm.is_enabled() {
+            if self.llm.model.is_empty() {
+                return Err(Error::Config(format!("llm.model must be set for the {} provider", self.llm.provider.name())));
+            }
+            if self.llm.max_tokens == 0 {
+                return Err(Error::Config("llm.max_tokens must be greater than 0".to_string()));
+            }
+            if !(0.0..=2.0).contains(&self.llm.temperature) {
+                return Err(Error::Config(format!("llm.temperature must be between 0 and 2, not {}", self.llm.temperature)));
+            }
+        }
+
+        crate::redaction::Redactor::new(&self.redaction)?;
+        crate::rules::RuleEngine::new(&self.target_directory, &self.rules)?;
+        crate::architecture::ArchitectureChecker::new(&self.target_directory, &self.architecture)?;
+        crate::components::ComponentMapper::new(&self.target_directory, &self.components)?;
+        crate::pipeline::Pipeline::new(&self.pipeline)?;
+        Ok(())
+    }
+
     /// Get the default config file path (~/.project-examer.toml)
     pub fn default_config_path() -> crate::Result<PathBuf> {
         let home_dir = env::var("HOME")
             .or_else(|_| env::var("USERPROFILE"))
-            .map_err(|_| anyhow::anyhow!("Could not determine home directory"))?;
-        Ok(PathBuf::from(home_dir).join(".project-examer.toml"))
+            .map_err(|_| Error::Config("Could not determine home directory".to_string()))?;
+        Ok(PathBuf::from(home_dir).join(CONFIG_FILE_NAME))
     }
 
-    /// Load config from file, falling back to defaults if file doesn't exist
+    /// Find a project-local `.project-examer.toml` in `start` or any of its ancestors
+    pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+        let start = start.canonicalize().unwrap_or_else(|_| start.to_path_buf());
+        start.ancestors()
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// Load config for the current directory, falling back to defaults if no file exists
     pub fn load() -> crate::Result<Self> {
-        let config_path = Self::default_config_path()?;
+        Self::load_for(Path::new("."))
+    }
+
+    /// Load config for a target directory: the nearest project-local config
+    /// file wins, then the home-directory config, then built-in defaults
+    pub fn load_for(target: &Path) -> crate::Result<Self> {
+        Self::load_reporting(target, &Self::env_vars(), false)
+    }
+
+    /// `load_for` without the notices on stderr about which file was used, for
+    /// embedders that own the process's output
+    pub fn load_quietly_for(target: &Path) -> crate::Result<Self> {
+        Self::load_reporting(target, &Self::env_vars(), true)
+    }
+
+    /// `load_for` with `vars`, as returned by `env_vars` in another process,
+    /// in place of this process's environment
+    pub fn load_for_env(target: &Path, vars: &BTreeMap<String, String>) -> crate::Result<Self> {
+        Self::load_reporting(target, vars, false)
+    }
+
+    /// The environment variables loading reads: `PROJECT_EXAMER_*` overrides
+    /// and the provider API keys
+    pub fn env_vars() -> BTreeMap<String, String> {
+        env::vars()
+            .filter(|(key, _)| key.starts_with(ENV_PREFIX) || key == "OPENAI_API_KEY" || key == "ANTHROPIC_API_KEY")
+            .collect()
+    }
+
+    fn load_reporting(target: &Path, vars: &BTreeMap<String, String>, quiet: bool) -> crate::Result<Self> {
+        let config_path = match Self::find_project_config(target) {
+            Some(project_config) => project_config,
+            None => Self::default_config_path()?,
+        };
         
+        // Notices go to stderr so commands that print results (`search --json`) stay parseable
         let mut config = if config_path.exists() {
-            println!("📝 Loading configuration from: {}", config_path.display());
+            if !quiet {
+                eprintln!("📝 Loading configuration from: {}", config_path.display());
+            }
             Self::from_file(&config_path)?
         } else {
-            println!("ℹ️  No config file found at {}, using defaults", config_path.display());
-            println!("💡 Run 'project-examer config' to create a default configuration file");
+            if !quiet {
+                eprintln!("ℹ️  No config file found at {}, using defaults", config_path.display());
+                eprintln!("💡 Run 'project-examer config' to create a default configuration file");
+            }
             Self::default()
         };
+
+        config.apply_overrides(vars.clone())?;
+        config.apply_api_key_env(vars);
         
-        // Override API key from environment variables if not set in config
-        if config.llm.api_key.is_none() {
-            config.llm.api_key = match config.llm.provider {
-                LLMProvider::OpenAI => env::var("OPENAI_API_KEY").ok(),
-                LLMProvider::Anthropic => env::var("ANTHROPIC_API_KEY").ok(),
+        Ok(config)
+    }
+
+    /// Take the provider's API key from `OPENAI_API_KEY` or `ANTHROPIC_API_KEY`
+    /// in `vars` when the config doesn't set one
+    pub fn apply_api_key_env(&mut self, vars: &BTreeMap<String, String>) {
+        if self.llm.api_key.is_none() {
+            self.llm.api_key = match self.llm.provider {
+                LLMProvider::OpenAI => vars.get("OPENAI_API_KEY").cloned(),
+                LLMProvider::Anthropic => vars.get("ANTHROPIC_API_KEY").cloned(),
                 LLMProvider::Ollama => None, // Ollama typically doesn't need API keys
+                LLMProvider::None => None,
             };
         }
-        
-        Ok(config)
+    }
+
+    /// Apply `PROJECT_EXAMER_*` overrides from the process environment
+    pub fn apply_env_overrides(&mut self) -> crate::Result<()> {
+        self.apply_overrides(env::vars())
+    }
+
+    /// Apply `PROJECT_EXAMER_*` style overrides from an arbitrary set of variables.
+    ///
+    /// Values are parsed as TOML (`true`, `42`, `["rs", "py"]`), falling back to
+    /// a plain string; a comma-separated value is accepted for list keys.
+    pub fn apply_overrides<I>(&mut self, vars: I) -> crate::Result<()>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut overrides: Vec<(Vec<String>, String)> = vars.into_iter()
+            .filter_map(|(key, value)| {
+                let path = key.strip_prefix(ENV_PREFIX)?;
+                let path: Vec<String> = path.split("__").map(|part| part.to_lowercase()).collect();
+                (!path.iter().any(|part| part.is_empty())).then_some((path, value))
+            })
+            .collect();
+
+        if overrides.is_empty() {
+            return Ok(());
+        }
+        overrides.sort();
+
+        let mut root = toml::Value::try_from(&*self)?;
+        for (path, raw) in &overrides {
+            let (last, parents) = path.split_last().expect("override path is never empty");
+            let mut table = root.as_table_mut().expect("config serializes to a table");
+            for part in parents {
+                table = table.entry(part.clone())
+                    .or_insert_with(|| toml::Value::Table(Default::default()))
+                    .as_table_mut()
+                    .ok_or_else(|| Error::Config(format!("{}{} does not refer to a config section", ENV_PREFIX, path.join("__").to_uppercase())))?;
+            }
+
+            let is_list = matches!(table.get(last), Some(toml::Value::Array(_)));
+            table.insert(last.clone(), Self::parse_override_value(raw, is_list));
+        }
+
+        *self = root.try_into().map_err(|e| Error::Config(format!("Invalid {}* override: {}", ENV_PREFIX, e)))?;
+        Ok(())
+    }
+
+    /// Overlay a partial config given as JSON, e.g. `{"llm": {"provider": "None"}}`;
+    /// objects merge key by key and any other value replaces the current one
+    pub fn apply_json(&mut self, overrides: &serde_json::Value) -> crate::Result<()> {
+        fn merge(base: &mut serde_json::Value, overlay: &serde_json::Value) {
+            match (base, overlay) {
+                (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+                    for (key, value) in overlay {
+                        merge(base.entry(key.clone()).or_insert(serde_json::Value::Null), value);
+                    }
+                }
+                (base, overlay) => *base = overlay.clone(),
+            }
+        }
+
+        let mut root = serde_json::to_value(&*self)?;
+        merge(&mut root, overrides);
+        *self = serde_json::from_value(root).map_err(|e| Error::Config(format!("Invalid config JSON: {}", e)))?;
+        Ok(())
+    }
+
+    fn parse_override_value(raw: &str, is_list: bool) -> toml::Value {
+        if let Ok(table) = toml::from_str::<toml::Table>(&format!("value = {}", raw)) {
+            if let Some(value) = table.get("value") {
+                if !is_list || value.is_array() {
+                    return value.clone();
+                }
+            }
+        }
+
+        if is_list {
+            toml::Value::Array(
+                raw.split(',')
+                    .map(|item| item.trim())
+                    .filter(|item| !item.is_empty())
+                    .map(|item| toml::Value::String(item.to_string()))
+                    .collect(),
+            )
+        } else {
+            toml::Value::String(raw.to_string())
+        }
+    }
+
+    /// Apply the overrides of a named `[profile.<name>]` section
+    pub fn apply_profile(&mut self, name: &str) -> crate::Result<()> {
+        let profile = self.profiles.get(name).cloned().ok_or_else(|| {
+            let available: Vec<&str> = self.profiles.keys().map(|k| k.as_str()).collect();
+            Error::Config(format!("Unknown profile '{}' (available: {})", name,
+                if available.is_empty() { "none".to_string() } else { available.join(", ") }))
+        })?;
+
+        if let Some(max_depth) = profile.max_depth {
+            self.analysis.max_depth = max_depth;
+        }
+        if let Some(analysis_types) = profile.analysis_types {
+            self.analysis.analysis_types = analysis_types;
+        }
+        if let Some(model) = profile.model {
+            self.llm.model = model;
+        }
+
+        Ok(())
+    }
+
+    /// Apply `[offline]`: cloud providers are switched off, and a local Ollama
+    /// is kept only when `allow_local_llm` is set. Fails if that Ollama
+    /// would be reached over the network. Returns the provider that was
+    /// switched off, if any, so the caller can say so.
+    pub fn enforce_offline(&mut self) -> crate::Result<Option<LLMProvider>> {
+        if !self.offline.enabled {
+            return Ok(None);
+        }
+        if self.metrics.push_enabled() {
+            return Err(Error::Config("Offline mode cannot push metrics; remove metrics.pushgateway_url and metrics.otlp_endpoint or use --format prometheus".to_string()));
+        }
+        if self.notifications.enabled() {
+            return Err(Error::Config("Offline mode cannot post notifications; remove the [notifications] webhook URLs".to_string()));
+        }
+        if self.registry.enabled {
+            return Err(Error::Config("Offline mode cannot query package registries; drop --check-registry or set registry.enabled = false".to_string()));
+        }
+        if self.osv.enabled {
+            return Err(Error::Config("Offline mode cannot query OSV.dev; drop --check-vulnerabilities or set osv.enabled = false".to_string()));
+        }
+
+        match self.llm.provider {
+            LLMProvider::None => {}
+            LLMProvider::Ollama if self.offline.allow_local_llm => {
+                if !self.llm.is_local() {
+                    return Err(Error::Config(format!(
+                        "Offline mode allows only a loopback Ollama, but base_url is {}",
+                        self.llm.base_url.as_deref().unwrap_or_default()
+                    )));
+                }
+            }
+            _ => return Ok(Some(std::mem::replace(&mut self.llm.provider, LLMProvider::None))),
+        }
+
+        Ok(None)
     }
 
     /// Load config from a specific file path
@@ -143,11 +1313,11 @@ impl Config {
     pub fn to_file(&self, path: &PathBuf) -> crate::Result<()> {
         // Create parent directories if they don't exist
         if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
+            std::fs::create_dir_all(parent).map_err(|e| Error::report(parent, e))?;
         }
         
         let content = toml::to_string_pretty(self)?;
-        std::fs::write(path, content)?;
+        std::fs::write(path, content).map_err(|e| Error::report(path, e))?;
         Ok(())
     }
 
@@ -159,7 +1329,7 @@ impl Config {
 
     /// Create a config file with all available options documented
     pub fn create_documented_config() -> String {
-        format!(r#"# Project Examer Configuration File
+        r#"# Project Examer Configuration File
 # This file configures how project-examer analyzes your codebase
 
 # Target directory to analyze (defaults to current directory)
@@ -184,14 +1354,14 @@ file_extensions = [
     "rs", "js", "ts", "tsx", "jsx", "py", "java", "go", 
     "cpp", "c", "h", "php", "rb", "cs", "swift", "kt",
     "scala", "clj", "hs", "ml", "elm", "ex", "erl", "dart",
-    "lua", "r", "pl", "sh", "sql", "html", "css", "scss"
+    "lua", "r", "pl", "sh", "bash", "sql", "html", "css", "scss", "tf", "hcl"
 ]
 
 # Maximum file size to analyze (in bytes, default 1MB)
 max_file_size = 1048576
 
 [llm]
-# LLM Provider: "OpenAI", "Ollama", or "Anthropic"
+# LLM Provider: "OpenAI", "Ollama", "Anthropic", or "None" to disable LLM analysis
 provider = "OpenAI"
 
 # API key for the provider (can also be set via environment variables)
@@ -214,6 +1384,17 @@ temperature = 0.1
 # Request timeout in seconds (default: 300 seconds / 5 minutes)
 timeout_seconds = 300
 
+# Retries per model when the provider returns 429 or 5xx (honors Retry-After),
+# can't be reached or times out
+max_retries = 2
+
+# Models to fall back to, in order, when the primary model keeps failing
+# fallback_models = ["gpt-4o-mini"]
+
+# Send the analyses to a second "critic" call that removes insights the context
+# doesn't support and merges duplicates across analysis types (one extra request)
+enable_review_pass = false
+
 [analysis]
 # Include dependency analysis
 include_dependencies = true
@@ -229,6 +1410,335 @@ include_security_analysis = false
 
 # Maximum depth for dependency traversal
 max_depth = 10
-"#)
+
+# Dependency cycles spanning more files than this are flagged as architectural risks
+max_component_size = 5
+
+# Restrict analysis to a subgraph: paths relative to the target directory or
+# package names from Cargo.toml, package.json or pyproject.toml (also `--scope`)
+scope = []
+
+# Ask the LLM for 2-3 sentence summaries of each module for the "Module Guide"
+# report section (one extra request per 8 modules)
+summarize_modules = false
+
+# List each function in the LLM context with its signature and the first line of
+# its doc comment or docstring (and each documented class with its summary), so analyses can reason about behavior rather than
+# names. Files are covered in order until the estimated tokens added reach
+# function_signature_tokens; the rest keep the names-only listing
+function_signatures = false
+function_signature_tokens = 8000
+
+# Who the report is written for: "Maintainer" or "Onboarding" (also `--audience`).
+# Onboarding adds a guided tour: entry points, main flows, where to start reading
+# and a glossary of domain terms, and asks the LLM to explain rather than critique
+audience = "Maintainer"
+
+# Cap on analyzed files for very large repositories (0 = no limit). When the
+# project has more files, a sample is chosen by `sampling` and the report says so.
+# Sampling: "Size" (largest first), "Centrality" (most imported first) or
+# "Recency" (most recently committed first)
+max_files = 0
+sampling = "Centrality"
+
+# Metrics-only mode for very large repositories (also `--low-memory`): skips LLM
+# analysis, documentation drift and git blame, and drops symbol details the
+# metrics don't need while files stream through the parser
+low_memory = false
+
+# Worker threads for parsing (0 = one per CPU core; also `--threads`) and the
+# maximum number of files read at once (0 = no extra limit), useful on network
+# filesystems or slow disks
+threads = 0
+io_concurrency = 0
+
+# Files with a line longer than this many bytes (minified bundles, generated
+# data) are counted but not parsed for symbols; 0 parses every file in full.
+# Files decoded from UTF-16 or Latin-1 and files cut to size-only metrics are
+# listed in the parse error appendix.
+max_line_length = 5000
+
+# LLM analysis types to run, in order
+# Options: "Overview", "Architecture", "Dependencies", "Security", "Refactoring", "Documentation", "Concurrency", "Performance", "Testing", "IacSecurity"
+analysis_types = ["Overview", "Architecture", "Dependencies"]
+
+[analysis.scoring]
+# Complexity = sum of (per-file average * weight), capped at max_complexity
+function_weight = 1.0
+class_weight = 2.0
+import_weight = 1.0
+# Lines of code are measured per 100 lines
+loc_weight = 0.0
+# Halstead volume (per 100) computed from operator and operand tokens
+halstead_weight = 0.0
+max_complexity = 10.0
+
+# Maintainability = base - complexity * complexity_penalty - avg_degree * coupling_penalty
+base_maintainability = 10.0
+complexity_penalty = 0.5
+coupling_penalty = 0.3
+
+[analysis.maintainability]
+# Per-file maintainability index (0-100) from lines of code, cyclomatic complexity,
+# comment ratio and Halstead volume; files are rated green/yellow/red
+yellow_below = 40.0
+red_below = 20.0
+
+[analysis.smells]
+# Code smell thresholds; smells are reported with locations and passed to the
+# Refactoring LLM analysis as evidence
+max_function_lines = 60
+max_parameters = 5
+max_nesting = 4
+# God classes: methods declared in the class body
+max_class_methods = 20
+
+[discovery]
+# Skip files marked linguist-vendored or linguist-generated in .gitattributes and
+# apply linguist-language overrides to language detection
+respect_gitattributes = true
+# A file is analyzed when its extension is in file_extensions, OR its name
+# matches include_filenames, OR its detected language is in include_languages.
+# Anything else, including extension-less files, is excluded
+include_filenames = ["Makefile", "GNUmakefile", "Dockerfile", "Dockerfile.*", "*.dockerfile", "Jenkinsfile"]
+# e.g. ["bash", "python"] to include extension-less scripts by their shebang
+include_languages = []
+# Skip generated code so metrics reflect human-written code: files with an
+# @generated or "Code generated ... DO NOT EDIT" header, protobuf/grpc outputs
+# (*.pb.go, *_pb2.py, ...), *.g.dart and minified JavaScript/CSS
+skip_generated = true
+
+[pipeline]
+# Stages run in this order: discover, parse, graph, local-passes, llm, report.
+# discover, parse and graph are required; leave out local-passes, llm or report
+# to skip them. The llm prompts include the local-passes findings, so without
+# that stage they describe only the parsed files
+stages = ["discover", "parse", "graph", "local-passes", "llm", "report"]
+# Each stage's output is checkpointed to <output>/pipeline/<stage>.json as it
+# completes, so `analyze --resume` can pick up an interrupted run; keep the files
+# after a successful run instead of removing them
+persist_artifacts = false
+
+[report]
+# Reproducible output for snapshot tests and diffs (also `--deterministic`): files
+# are walked and listed in path order, and the timestamp and duration are fixed.
+# Debt marker ages are measured from `timestamp` (RFC 3339) instead of now
+deterministic = false
+timestamp = "1970-01-01T00:00:00Z"
+# Lowest severity written as a failing test case in `junit.xml` (`--format junit`):
+# info, warning or error. `--fail-on` overrides it
+junit_fail_on = "warning"
+
+[scorecard]
+# Letter grades in scorecard.md / scorecard.html (`--format scorecard`). Each
+# category is scored 0-100: architecture from the maintainability score less
+# layer violations, dependencies from cycles, tests from the share of source files
+# with a matching test, docs from doc-comment coverage, and security from security
+# recommendations and SQL built by concatenation. These are the lowest scores
+# earning an A, B, C and D; anything lower is an F
+grade_a = 90
+grade_b = 80
+grade_c = 70
+grade_d = 60
+# Points deducted per finding
+architecture_violation_penalty = 5
+dependency_cycle_penalty = 10
+security_critical_penalty = 30
+security_high_penalty = 15
+security_medium_penalty = 5
+dynamic_query_penalty = 5
+
+[redaction]
+# Applied to everything sent to LLM providers; each request is recorded, after
+# redaction, in data_sent.json next to the report
+enabled = true
+# Mask credential assignments, private keys and well-known token formats
+mask_secrets = true
+mask_emails = true
+mask_ip_addresses = true
+# Replace string literals in source excerpts with "…"
+strip_string_literals = false
+# Files never described or quoted in prompts
+sensitive_paths = ["**/.env", "**/.env.*", "**/*.pem", "**/*.key", "**/secrets/**", "**/*credentials*"]
+
+[offline]
+# Guarantee zero network calls (also `--offline`): cloud LLM providers are
+# skipped and the report is marked offline. A local Ollama on a loopback
+# address may be kept with allow_local_llm (also `--allow-local-llm`)
+enabled = false
+allow_local_llm = false
+
+[ownership]
+# Attribute files to teams using CODEOWNERS (.github/, root, or docs/)
+enabled = true
+# Fall back to the top git blame author for files CODEOWNERS doesn't cover (slower)
+git_blame = false
+# Per-module bus factor from git history: distinct authors, the top author's
+# share of commits, and modules one person knows, ranked by complexity
+bus_factor = true
+# Most recent commits to read for the bus factor (0 = the whole history)
+history_commits = 2000
+
+[process]
+# Audit recent git history: conventional commit messages, commit size and
+# branches not merged into HEAD; the findings also inform LLM workflow advice
+enabled = true
+# Most recent non-merge commits to audit (0 = the whole history)
+history_commits = 200
+# Commits changing more lines than this are counted as large
+large_commit_lines = 500
+# Unmerged branches whose first commit is older than this are long-lived
+long_lived_branch_days = 30
+
+[change_coupling]
+# Mine git history for files that keep changing together although neither
+# imports the other: hidden coupling the dependency graph can't show
+enabled = true
+# Most recent non-merge commits to read (0 = the whole history)
+history_commits = 1000
+# Pairs changed together in fewer commits than this are ignored
+min_shared_commits = 3
+# Smallest coupling degree reported: shared commits as a percentage of the
+# average number of commits of the two files
+min_degree = 50.0
+# Commits touching more files than this (mass renames, formatting) are skipped
+max_files_per_commit = 30
+
+[registry]
+# Look declared dependency versions up on crates.io, npm and PyPI and report
+# outdated and yanked ones; needs network, so it is off unless --check-registry.
+# Refused in offline mode
+enabled = false
+crates_io_url = "https://crates.io"
+npm_url = "https://registry.npmjs.org"
+pypi_url = "https://pypi.org"
+timeout_seconds = 10
+# Lookups in flight at once
+concurrency = 8
+# Check dev, test and build dependencies too
+include_dev = true
+
+[osv]
+# Look declared dependency versions up in the OSV.dev vulnerability database and
+# list published advisories in the Security section, with the files importing
+# each affected package; off unless --check-vulnerabilities. Refused in offline mode
+enabled = false
+api_url = "https://api.osv.dev"
+timeout_seconds = 20
+# Check dev, test and build dependencies too
+include_dev = true
+
+[metrics]
+# Push codebase health metrics (files, LOC, complexity, cycles, maintainability)
+# after each analyze run so they can be graphed over time; `--format prometheus`
+# writes them to metrics.prom instead. Pushing is refused in offline mode
+# pushgateway_url = "http://localhost:9091"
+# OTLP/HTTP metrics endpoint, sent as JSON
+# otlp_endpoint = "http://localhost:4318/v1/metrics"
+# Pushgateway job and OTLP service.name
+job = "project-examer"
+timeout_seconds = 10
+# Extra labels (Prometheus) or resource attributes (OTLP) on every metric
+# [metrics.labels]
+# team = "platform"
+
+[notifications]
+# Post a run summary (scores, critical findings, report link) to chat when an
+# analyze run completes. Webhook URLs are secrets: prefer the environment, e.g.
+# PROJECT_EXAMER_NOTIFICATIONS__SLACK_WEBHOOK_URL. Refused in offline mode
+# slack_webhook_url = "https://hooks.slack.com/services/..."
+# teams_webhook_url = "https://example.webhook.office.com/..."
+# Link to the published report, e.g. a CI artifact URL (defaults to the local HTML file)
+# report_url = "https://ci.example.com/job/123/artifacts/analysis_report.html"
+# Critical findings listed before "… and N more"
+max_findings = 5
+timeout_seconds = 10
+
+[issues]
+# Where `project-examer export-issues` files Critical and High recommendations:
+# "github" or "jira". Issues already filed are recognized by a fingerprint in their body
+tracker = "github"
+# GitHub repository; the token comes from GITHUB_TOKEN or github_token
+# github_repo = "owner/name"
+github_api_url = "https://api.github.com"
+# Jira site and project key; authenticates as jira_email with JIRA_API_TOKEN or jira_token
+# jira_url = "https://example.atlassian.net"
+# jira_project = "ENG"
+# jira_email = "bot@example.com"
+jira_issue_type = "Task"
+# Added to every issue; the first label is also used to find earlier issues
+labels = ["project-examer"]
+timeout_seconds = 30
+
+[schedule]
+# `project-examer run` re-analyzes on this cron schedule (minute hour day-of-month
+# month day-of-week, local time; also `--schedule`). Each run writes to its own
+# run-YYYYMMDD-HHMMSS directory under --output, with `latest` linking the newest
+# cron = "0 3 * * *"
+# Newest runs kept (0 = all) and maximum age in days (0 = no limit)
+keep_runs = 14
+max_age_days = 0
+# Analyze once immediately instead of waiting for the first scheduled time
+run_on_start = false
+
+[boundaries]
+# In a workspace (Cargo `[workspace]`, package.json `workspaces`, pnpm-workspace.yaml
+# or lerna.json), flag imports that reach into another package's files instead
+# of its entry point (`main`/`exports`, index files, src/lib.rs, `__init__.py`)
+enabled = true
+# Reaching into these directories is an error; other bypasses are warnings
+internal_directories = ["internal", "private", "_internal"]
+
+# Intended architecture: files in a layer may only import their own layer and
+# the layers listed in `may_depend_on`; files outside every layer are not checked
+# [[architecture.layers]]
+# name = "ui"
+# paths = ["src/ui/**"]
+# may_depend_on = ["domain"]
+#
+# [[architecture.layers]]
+# name = "domain"
+# paths = ["src/domain/**"]
+# may_depend_on = []
+
+# Logical components: metrics, dependencies and findings are rolled up per
+# component and drawn as a component dependency diagram. A file belongs to the
+# component with the most specific matching glob; others are left unassigned.
+# [components]
+# "Payments" = ["src/payments/**"]
+# "Checkout" = ["src/checkout/**", "src/cart/**"]
+
+# Custom rules, evaluated locally; fail CI with `--fail-on <severity>`
+# [[rules]]
+# id = "ui-no-db"
+# description = "UI layer must not import the database layer"
+# severity = "error"            # info, warning or error
+# type = "forbidden_import"     # files matching `from` must not import `to`
+# from = "src/ui/**"
+# to = "src/db/**"
+#
+# [[rules]]
+# id = "no-unwrap"
+# type = "pattern"              # regex matched against each line
+# pattern = "\\.unwrap\\(\\)"
+# files = "src/**/*.rs"
+#
+# [[rules]]
+# id = "snake-case-functions"
+# type = "symbol_name"          # function/class names must match
+# pattern = "^[a-z_][a-z0-9_]*$|^[A-Z][A-Za-z0-9]*$"
+# files = "**/*.py"
+
+# Named profiles selectable with `--profile <name>`; each key is optional
+# [profile.quick]
+# max_depth = 3
+# analysis_types = ["Overview"]
+# model = "gpt-4o-mini"
+#
+# [profile.deep]
+# max_depth = 20
+# analysis_types = ["Overview", "Architecture", "Dependencies", "Security", "Refactoring"]
+# model = "gpt-4o"
+"#.to_string()
     }
 }
\ No newline at end of file
@@ -0,0 +1,632 @@
+use crate::{file_discovery::FileInfo, module_rollup::ModuleAnalyzer, simple_parser::ParsedFile, sources::SourceSet};
+use ignore::WalkBuilder;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// An interface definition language
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdlFormat {
+    /// `.proto`
+    Protobuf,
+    /// `.thrift`
+    Thrift,
+    /// `.avsc` schemas, `.avpr` protocols and `.avdl` IDL
+    Avro,
+}
+
+impl IdlFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            IdlFormat::Protobuf => "protobuf",
+            IdlFormat::Thrift => "thrift",
+            IdlFormat::Avro => "avro",
+        }
+    }
+
+    fn of(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("proto") => Some(IdlFormat::Protobuf),
+            Some("thrift") => Some(IdlFormat::Thrift),
+            Some("avsc" | "avpr" | "avdl") => Some(IdlFormat::Avro),
+            _ => None,
+        }
+    }
+}
+
+/// One RPC of a service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcMethod {
+    pub name: String,
+    /// Request message, or the parameter list for Thrift and Avro
+    pub request: String,
+    pub response: String,
+    pub client_streaming: bool,
+    pub server_streaming: bool,
+    pub line: usize,
+}
+
+impl RpcMethod {
+    /// `Name(Request) -> Response`, with `stream` where it applies
+    pub fn signature(&self) -> String {
+        let stream = |streaming: bool| if streaming { "stream " } else { "" };
+        format!("{}({}{}) -> {}{}", self.name, stream(self.client_streaming), self.request,
+            stream(self.server_streaming), self.response)
+    }
+}
+
+/// A service (Avro: protocol) and its RPCs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractService {
+    pub name: String,
+    pub line: usize,
+    pub methods: Vec<RpcMethod>,
+}
+
+/// A message, struct, record or enum
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractType {
+    /// Nested types are qualified by their parents, e.g. `Order.Item`
+    pub name: String,
+    /// `message`, `enum`, `struct`, `union`, `exception`, `record`, `error` or `fixed`
+    pub kind: String,
+    /// Fields, or values for enums
+    pub fields: usize,
+    pub line: usize,
+}
+
+/// One IDL file, the code generated from it and the files using that code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contract {
+    /// Relative to the project root
+    pub file: PathBuf,
+    pub format: IdlFormat,
+    /// Protobuf package, Avro namespace, or the first Thrift namespace
+    pub package: Option<String>,
+    /// Packages generated code is imported under: `go_package`, `java_package`,
+    /// `csharp_namespace` and every Thrift namespace
+    pub generated_packages: Vec<String>,
+    /// Other IDL files imported or included, as written
+    pub imports: Vec<String>,
+    pub services: Vec<ContractService>,
+    pub types: Vec<ContractType>,
+    /// Generated files checked into the project, relative to the project root
+    pub generated_files: Vec<PathBuf>,
+    /// Files importing the generated code, relative to the project root
+    pub consumers: Vec<PathBuf>,
+}
+
+impl Contract {
+    pub fn rpc_count(&self) -> usize {
+        self.services.iter().map(|service| service.methods.len()).sum()
+    }
+
+    /// Import paths and packages that name this contract's generated code
+    fn package_keys(&self) -> impl Iterator<Item = String> + '_ {
+        self.package.iter()
+            .chain(&self.generated_packages)
+            .map(|package| package.split(';').next().unwrap_or(package).replace('/', "."))
+    }
+
+    fn stem(&self) -> &str {
+        self.file.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default()
+    }
+}
+
+/// Contracts one module (directory, relative to the project root) depends on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractModule {
+    pub module: String,
+    pub contracts: Vec<PathBuf>,
+    /// Files of the module using generated code
+    pub files: usize,
+}
+
+/// Protobuf, Thrift and Avro contracts: their RPC surface and who depends on them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContractInventory {
+    pub contracts: Vec<Contract>,
+    /// Modules using the most contracts first
+    pub modules: Vec<ContractModule>,
+}
+
+impl ContractInventory {
+    pub fn is_empty(&self) -> bool {
+        self.contracts.is_empty()
+    }
+
+    pub fn rpc_count(&self) -> usize {
+        self.contracts.iter().map(Contract::rpc_count).sum()
+    }
+
+    /// One line per contract, for the Architecture and Dependencies LLM analyses
+    pub fn context_lines(&self) -> Vec<String> {
+        self.contracts.iter()
+            .map(|contract| {
+                let services = contract.services.iter()
+                    .map(|service| format!("{} ({})", service.name,
+                        service.methods.iter().map(RpcMethod::signature).collect::<Vec<_>>().join(", ")))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                let users: BTreeSet<String> = contract.consumers.iter().map(|file| ModuleAnalyzer::module_name(file)).collect();
+                format!("{} ({}{}): {}{} types; used by {}",
+                    contract.file.display(),
+                    contract.format.name(),
+                    contract.package.as_ref().map(|package| format!(", package {}", package)).unwrap_or_default(),
+                    if services.is_empty() { String::new() } else { format!("services {}; ", services) },
+                    contract.types.len(),
+                    if users.is_empty() { "no module".to_string() } else { users.into_iter().collect::<Vec<_>>().join(", ") })
+            })
+            .collect()
+    }
+}
+
+/// What a block opened on a line belongs to
+#[derive(Clone, Copy)]
+enum Scope {
+    Type(usize),
+    Service(usize),
+    Other,
+}
+
+/// Reads `.proto`, `.thrift` and Avro files and links the code generated from
+/// them to its importers. Generated code is recognized by protoc/grpc file
+/// naming (`orders_pb2.py`, `orders.pb.go`, `orders_pb.js`), by imports of the
+/// contract's package, and by Rust `include_proto!`.
+pub struct ContractAnalyzer {
+    root: PathBuf,
+    proto_package: Regex,
+    proto_import: Regex,
+    proto_option: Regex,
+    proto_block: Regex,
+    proto_rpc: Regex,
+    proto_field: Regex,
+    thrift_namespace: Regex,
+    thrift_include: Regex,
+    thrift_block: Regex,
+    thrift_method: Regex,
+    thrift_field: Regex,
+    avdl_namespace: Regex,
+    avdl_import: Regex,
+    avdl_block: Regex,
+    avdl_method: Regex,
+    enum_value: Regex,
+    generated_module: Regex,
+    generated_file: Regex,
+    rust_include: Regex,
+}
+
+impl ContractAnalyzer {
+    pub fn new(root: &Path) -> Self {
+        let regex = |pattern: &str| Regex::new(pattern).expect("valid contract pattern");
+        Self {
+            root: root.to_path_buf(),
+            proto_package: regex(r"^\s*package\s+([\w.]+)\s*;"),
+            proto_import: regex(r#"^\s*import\s+(?:public\s+|weak\s+)?"([^"]+)""#),
+            proto_option: regex(r#"^\s*option\s+(?:go_package|java_package|csharp_namespace)\s*=\s*"([^"]+)""#),
+            proto_block: regex(r"^\s*(message|enum|service|oneof|extend)\s+([\w.]+)"),
+            proto_rpc: regex(r"^\s*rpc\s+(\w+)\s*\(\s*(stream\s+)?([\w.]+)\s*\)\s*returns\s*\(\s*(stream\s+)?([\w.]+)\s*\)"),
+            proto_field: regex(r"=\s*-?\d+\s*[;\[]"),
+            thrift_namespace: regex(r"^\s*namespace\s+[\w.*]+\s+([\w.]+)"),
+            thrift_include: regex(r#"^\s*(?:include|cpp_include)\s+"([^"]+)""#),
+            thrift_block: regex(r"^\s*(struct|union|exception|enum|service)\s+(\w+)"),
+            thrift_method: regex(r"^\s*(oneway\s+)?([\w.]+(?:<[^>]*>)?)\s+(\w+)\s*\(([^)]*)"),
+            thrift_field: regex(r"^\s*-?\d+\s*:"),
+            avdl_namespace: regex(r#"@namespace\s*\(\s*"([^"]+)"\s*\)"#),
+            avdl_import: regex(r#"^\s*import\s+(?:idl|protocol|schema)\s+"([^"]+)""#),
+            avdl_block: regex(r"^\s*(?:@\w+\([^)]*\)\s*)*(protocol|record|error|enum|fixed)\s+(\w+)"),
+            avdl_method: regex(r"^\s*([\w.]+(?:<[^>]*>)?(?:\[\])?)\s+(\w+)\s*\(([^)]*)\)\s*(?:oneway|throws\s+[\w., ]+)?\s*;"),
+            enum_value: regex(r"^\s*[A-Za-z_]\w*\s*(?:=\s*-?\w+)?\s*[,;]?\s*$"),
+            generated_module: regex(r"(?:^|[^\w-])(?P<stem>[\w-]+?)(?:_pb2_grpc|_pb2|_grpc_pb|_pb|_grpc\.pb|\.grpc\.pb|\.pb|_connect|_twirp)\b"),
+            generated_file: regex(r"^(?P<stem>[\w-]+?)(?:_pb2_grpc|_pb2|_grpc_pb|_pb|_grpc\.pb|\.grpc\.pb|\.pb|_connect|_twirp|\.twirp)\.(?:py|pyi|go|js|mjs|cjs|ts|d\.ts|h|cc|rb|php|dart|swift)$"),
+            rust_include: regex(r#"include_proto!\s*\(\s*"([\w.]+)"|include!\s*\(\s*concat!\s*\(\s*env!\s*\(\s*"OUT_DIR"\s*\)\s*,\s*"/([\w.]+)\.rs""#),
+        }
+    }
+
+    pub fn analyze(&self, files: &[FileInfo], parsed_files: &[ParsedFile], sources: &SourceSet) -> ContractInventory {
+        // Discovery leaves out generated code, so walk for it along with the IDL files
+        let walked = if sources.is_in_memory() {
+            files.iter().map(|f| f.path.clone()).collect()
+        } else {
+            self.walk()
+        };
+
+        let mut contracts: Vec<Contract> = walked.iter()
+            .filter_map(|path| {
+                let format = IdlFormat::of(path)?;
+                let content = sources.read(path).ok()?;
+                let mut contract = Contract {
+                    file: self.relative(path),
+                    format,
+                    package: None,
+                    generated_packages: Vec::new(),
+                    imports: Vec::new(),
+                    services: Vec::new(),
+                    types: Vec::new(),
+                    generated_files: Vec::new(),
+                    consumers: Vec::new(),
+                };
+                match (format, path.extension().and_then(|e| e.to_str())) {
+                    (IdlFormat::Protobuf, _) => self.parse_protobuf(&content, &mut contract),
+                    (IdlFormat::Thrift, _) => self.parse_thrift(&content, &mut contract),
+                    (IdlFormat::Avro, Some("avdl")) => self.parse_avdl(&content, &mut contract),
+                    (IdlFormat::Avro, _) => Self::parse_avro_json(&content, &mut contract),
+                }
+                Some(contract)
+            })
+            .collect();
+        if contracts.is_empty() {
+            return ContractInventory::default();
+        }
+
+        for path in &walked {
+            let relative = self.relative(path);
+            for contract in &mut contracts {
+                if self.is_generated_from(&relative, contract) {
+                    contract.generated_files.push(relative.clone());
+                }
+            }
+        }
+        let generated: BTreeSet<PathBuf> = contracts.iter().flat_map(|c| c.generated_files.iter().cloned()).collect();
+
+        for parsed_file in parsed_files {
+            let relative = self.relative(&parsed_file.file_info.path);
+            if generated.contains(&relative) || IdlFormat::of(&relative).is_some() {
+                continue;
+            }
+            let Ok(content) = sources.read(&parsed_file.file_info.path) else { continue };
+            let lines: Vec<&str> = content.lines().collect();
+
+            // Python names generated modules in the import list (`from gen import orders_pb2`),
+            // so match the whole import line as well as the module
+            let mut stems = BTreeSet::new();
+            let mut modules = BTreeSet::new();
+            for import in &parsed_file.imports {
+                if let Some(line) = lines.get(import.line_number.saturating_sub(1)) {
+                    stems.extend(self.generated_module.captures_iter(line).map(|captures| captures["stem"].to_string()));
+                }
+                modules.insert(import.module.replace(['/', '\\'], ".").replace("::", "."));
+            }
+            if parsed_file.file_info.language.as_deref() == Some("rust") {
+                for captures in lines.iter().filter_map(|line| self.rust_include.captures(line)) {
+                    modules.extend(captures.get(1).or(captures.get(2)).map(|m| m.as_str().to_string()));
+                }
+            }
+
+            for contract in &mut contracts {
+                let by_stem = stems.contains(contract.stem());
+                // Single-segment packages are too generic to tell apart from ordinary modules
+                let by_package = contract.package_keys()
+                    .filter(|key| key.contains('.'))
+                    .any(|key| modules.iter().any(|module| *module == key || module.starts_with(&format!("{}.", key))));
+                if by_stem || by_package {
+                    contract.consumers.push(relative.clone());
+                }
+            }
+        }
+
+        let mut modules: BTreeMap<String, (BTreeSet<PathBuf>, BTreeSet<&PathBuf>)> = BTreeMap::new();
+        for contract in &contracts {
+            for consumer in &contract.consumers {
+                let (module_contracts, module_files) = modules.entry(ModuleAnalyzer::module_name(consumer)).or_default();
+                module_contracts.insert(contract.file.clone());
+                module_files.insert(consumer);
+            }
+        }
+        let mut modules: Vec<ContractModule> = modules.into_iter()
+            .map(|(module, (module_contracts, module_files))| ContractModule {
+                module,
+                contracts: module_contracts.into_iter().collect(),
+                files: module_files.len(),
+            })
+            .collect();
+        modules.sort_by_key(|module| std::cmp::Reverse(module.contracts.len()));
+
+        ContractInventory { contracts, modules }
+    }
+
+    fn relative(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.root).unwrap_or(path).to_path_buf()
+    }
+
+    /// Files anywhere under the target, honoring .gitignore; IDL files usually
+    /// aren't among the analyzed file extensions
+    fn walk(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = WalkBuilder::new(&self.root)
+            .build()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.into_path())
+            .filter(|path| path.is_file() && !path.components().any(|c| c.as_os_str() == "node_modules"))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// protoc naming (`orders_pb2.py`, `orders.pb.go`) or, for Thrift, a file
+    /// under a `gen-*` directory whose path spells a namespace
+    fn is_generated_from(&self, relative: &Path, contract: &Contract) -> bool {
+        let name = relative.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if self.generated_file.captures(name).is_some_and(|captures| &captures["stem"] == contract.stem()) {
+            return true;
+        }
+        if contract.format != IdlFormat::Thrift || !relative.components().any(|c| c.as_os_str().to_string_lossy().starts_with("gen-")) {
+            return false;
+        }
+        let directory = relative.parent().map(|p| p.to_string_lossy().replace(['/', '\\'], ".")).unwrap_or_default();
+        contract.generated_packages.iter().any(|package| directory.ends_with(package.as_str()))
+    }
+
+    /// `Outer.Inner` for types declared inside another type's block
+    fn qualified(types: &[ContractType], stack: &[Scope], name: &str) -> String {
+        match stack.iter().rev().find_map(|scope| match scope {
+            Scope::Type(index) => Some(*index),
+            _ => None,
+        }) {
+            Some(parent) => format!("{}.{}", types[parent].name, name),
+            None => name.to_string(),
+        }
+    }
+
+    /// Braces opened and closed on a line; the first block opened gets `pending`'s scope
+    fn track_braces(line: &str, stack: &mut Vec<Scope>, mut pending: Option<Scope>) {
+        for c in line.chars() {
+            match c {
+                '{' => stack.push(pending.take().unwrap_or(Scope::Other)),
+                '}' => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Fields declared on the line that opens a type's block, e.g. `message Id { string id = 1; }`
+    fn inline_fields(&self, format: IdlFormat, kind: &str, line: &str) -> usize {
+        let Some((_, body)) = line.split_once('{') else { return 0 };
+        let body = body.split('}').next().unwrap_or_default();
+        match format {
+            IdlFormat::Protobuf => self.proto_field.find_iter(body).count(),
+            _ if kind == "enum" => body.split(',').filter(|symbol| !symbol.trim().is_empty()).count(),
+            IdlFormat::Thrift => body.split([',', ';']).filter(|field| self.thrift_field.is_match(field)).count(),
+            IdlFormat::Avro => body.matches(';').count(),
+        }
+    }
+
+    fn parse_protobuf(&self, content: &str, contract: &mut Contract) {
+        let mut stack: Vec<Scope> = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.split("//").next().unwrap_or_default();
+            let mut pending = None;
+            if let Some(captures) = self.proto_package.captures(line) {
+                contract.package = Some(captures[1].to_string());
+            } else if let Some(captures) = self.proto_import.captures(line) {
+                contract.imports.push(captures[1].to_string());
+            } else if let Some(captures) = self.proto_option.captures(line) {
+                contract.generated_packages.push(captures[1].to_string());
+            } else if let Some(captures) = self.proto_rpc.captures(line) {
+                if let Some(Scope::Service(service)) = stack.last() {
+                    contract.services[*service].methods.push(RpcMethod {
+                        name: captures[1].to_string(),
+                        request: captures[3].to_string(),
+                        response: captures[5].to_string(),
+                        client_streaming: captures.get(2).is_some(),
+                        server_streaming: captures.get(4).is_some(),
+                        line: index + 1,
+                    });
+                }
+            } else if let Some(captures) = self.proto_block.captures(line) {
+                pending = Some(match &captures[1] {
+                    "service" => {
+                        contract.services.push(ContractService { name: captures[2].to_string(), line: index + 1, methods: Vec::new() });
+                        Scope::Service(contract.services.len() - 1)
+                    }
+                    kind @ ("message" | "enum") => {
+                        let name = Self::qualified(&contract.types, &stack, &captures[2]);
+                        let fields = self.inline_fields(IdlFormat::Protobuf, kind, line);
+                        contract.types.push(ContractType { name, kind: kind.to_string(), fields, line: index + 1 });
+                        Scope::Type(contract.types.len() - 1)
+                    }
+                    // A oneof's fields belong to the enclosing message
+                    "oneof" => stack.last().copied().unwrap_or(Scope::Other),
+                    _ => Scope::Other,
+                });
+            } else if let Some(Scope::Type(type_index)) = stack.last() {
+                contract.types[*type_index].fields += self.proto_field.find_iter(line).count();
+            }
+            Self::track_braces(line, &mut stack, pending);
+        }
+    }
+
+    fn parse_thrift(&self, content: &str, contract: &mut Contract) {
+        let mut stack: Vec<Scope> = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.split("//").next().unwrap_or_default();
+            let trimmed = line.trim_start();
+            if trimmed.starts_with('#') || trimmed.starts_with("/*") || trimmed.starts_with('*') {
+                continue;
+            }
+            let mut pending = None;
+            if let Some(captures) = self.thrift_namespace.captures(line) {
+                contract.package.get_or_insert_with(|| captures[1].to_string());
+                contract.generated_packages.push(captures[1].to_string());
+            } else if let Some(captures) = self.thrift_include.captures(line) {
+                contract.imports.push(captures[1].to_string());
+            } else if let Some(captures) = self.thrift_block.captures(line) {
+                pending = Some(if &captures[1] == "service" {
+                    contract.services.push(ContractService { name: captures[2].to_string(), line: index + 1, methods: Vec::new() });
+                    Scope::Service(contract.services.len() - 1)
+                } else {
+                    contract.types.push(ContractType { name: captures[2].to_string(), kind: captures[1].to_string(), fields: self.inline_fields(IdlFormat::Thrift, &captures[1], line), line: index + 1 });
+                    Scope::Type(contract.types.len() - 1)
+                });
+            } else {
+                match stack.last() {
+                    Some(Scope::Service(service)) => {
+                        if let Some(captures) = self.thrift_method.captures(line) {
+                            let oneway = captures.get(1).is_some();
+                            contract.services[*service].methods.push(RpcMethod {
+                                name: captures[3].to_string(),
+                                request: Self::parameter_types(&captures[4]),
+                                response: if oneway { "void".to_string() } else { captures[2].to_string() },
+                                client_streaming: false,
+                                server_streaming: false,
+                                line: index + 1,
+                            });
+                        }
+                    }
+                    Some(Scope::Type(type_index)) => {
+                        let field = if contract.types[*type_index].kind == "enum" { &self.enum_value } else { &self.thrift_field };
+                        if field.is_match(line) {
+                            contract.types[*type_index].fields += 1;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Self::track_braces(line, &mut stack, pending);
+        }
+    }
+
+    fn parse_avdl(&self, content: &str, contract: &mut Contract) {
+        let mut stack: Vec<Scope> = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.split("//").next().unwrap_or_default();
+            let mut pending = None;
+            if let Some(captures) = self.avdl_namespace.captures(line) {
+                contract.package.get_or_insert_with(|| captures[1].to_string());
+            }
+            if let Some(captures) = self.avdl_import.captures(line) {
+                contract.imports.push(captures[1].to_string());
+            } else if let Some(captures) = self.avdl_block.captures(line) {
+                pending = Some(if &captures[1] == "protocol" {
+                    contract.services.push(ContractService { name: captures[2].to_string(), line: index + 1, methods: Vec::new() });
+                    Scope::Service(contract.services.len() - 1)
+                } else {
+                    contract.types.push(ContractType { name: captures[2].to_string(), kind: captures[1].to_string(), fields: self.inline_fields(IdlFormat::Avro, &captures[1], line), line: index + 1 });
+                    Scope::Type(contract.types.len() - 1)
+                });
+            } else {
+                match stack.last() {
+                    Some(Scope::Service(service)) => {
+                        if let Some(captures) = self.avdl_method.captures(line) {
+                            contract.services[*service].methods.push(RpcMethod {
+                                name: captures[2].to_string(),
+                                request: Self::parameter_types(&captures[3]),
+                                response: captures[1].to_string(),
+                                client_streaming: false,
+                                server_streaming: false,
+                                line: index + 1,
+                            });
+                        }
+                    }
+                    Some(Scope::Type(type_index)) => {
+                        // Enum symbols are comma separated, often several to a line
+                        contract.types[*type_index].fields += if contract.types[*type_index].kind == "enum" {
+                            line.split(',').filter(|symbol| !symbol.trim().trim_matches('}').trim().is_empty()).count()
+                        } else {
+                            usize::from(line.trim_end().ends_with(';'))
+                        };
+                    }
+                    _ => {}
+                }
+            }
+            Self::track_braces(line, &mut stack, pending);
+        }
+    }
+
+    /// `.avsc` schemas (a named type or a union of them) and `.avpr` protocols
+    fn parse_avro_json(content: &str, contract: &mut Contract) {
+        let Ok(document) = serde_json::from_str::<serde_json::Value>(content) else { return };
+        let line_of = |name: &str| content.find(&format!("\"{}\"", name))
+            .map_or(1, |offset| content[..offset].matches('\n').count() + 1);
+        contract.package = document.get("namespace").and_then(|n| n.as_str()).map(String::from);
+
+        let Some(protocol) = document.get("protocol").and_then(|p| p.as_str()) else {
+            Self::avro_types(&document, &mut contract.types, &line_of);
+            if contract.package.is_none() {
+                contract.package = Self::avro_namespace(&document);
+            }
+            return;
+        };
+        for named in document.get("types").and_then(|t| t.as_array()).into_iter().flatten() {
+            Self::avro_types(named, &mut contract.types, &line_of);
+        }
+        let methods = document.get("messages").and_then(|m| m.as_object()).into_iter().flatten()
+            .map(|(name, message)| RpcMethod {
+                name: name.clone(),
+                request: message.get("request").and_then(|r| r.as_array()).into_iter().flatten()
+                    .map(|parameter| Self::avro_type_name(parameter.get("type").unwrap_or(&serde_json::Value::Null)))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                response: Self::avro_type_name(message.get("response").unwrap_or(&serde_json::Value::Null)),
+                client_streaming: false,
+                server_streaming: false,
+                line: line_of(name),
+            })
+            .collect();
+        contract.services.push(ContractService { name: protocol.to_string(), line: line_of(protocol), methods });
+    }
+
+    /// Named types (records, errors, enums, fixed) declared anywhere in a schema
+    fn avro_types(schema: &serde_json::Value, types: &mut Vec<ContractType>, line_of: &dyn Fn(&str) -> usize) {
+        match schema {
+            serde_json::Value::Array(union) => {
+                for member in union {
+                    Self::avro_types(member, types, line_of);
+                }
+            }
+            serde_json::Value::Object(object) => {
+                let kind = object.get("type").and_then(|t| t.as_str()).unwrap_or_default();
+                if let (true, Some(name)) = (matches!(kind, "record" | "error" | "enum" | "fixed"), object.get("name").and_then(|n| n.as_str())) {
+                    let fields = object.get("fields").or_else(|| object.get("symbols")).and_then(|f| f.as_array()).map_or(0, Vec::len);
+                    types.push(ContractType { name: name.to_string(), kind: kind.to_string(), fields, line: line_of(name) });
+                }
+                for field in object.get("fields").and_then(|f| f.as_array()).into_iter().flatten() {
+                    Self::avro_types(field.get("type").unwrap_or(&serde_json::Value::Null), types, line_of);
+                }
+                for nested in ["type", "items", "values"].iter().filter_map(|key| object.get(*key)).filter(|value| !value.is_string()) {
+                    Self::avro_types(nested, types, line_of);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn avro_namespace(schema: &serde_json::Value) -> Option<String> {
+        match schema {
+            serde_json::Value::Array(union) => union.iter().find_map(Self::avro_namespace),
+            serde_json::Value::Object(object) => object.get("namespace").and_then(|n| n.as_str()).map(String::from),
+            _ => None,
+        }
+    }
+
+    fn avro_type_name(schema: &serde_json::Value) -> String {
+        match schema {
+            serde_json::Value::String(name) => name.clone(),
+            serde_json::Value::Array(union) => union.iter().map(Self::avro_type_name).collect::<Vec<_>>().join(" | "),
+            serde_json::Value::Object(object) => object.get("name")
+                .or_else(|| object.get("type"))
+                .and_then(|t| t.as_str())
+                .unwrap_or("?")
+                .to_string(),
+            _ => "null".to_string(),
+        }
+    }
+
+    /// Types from a Thrift (`1: i64 id, 2: string name`) or Avro IDL (`long id`) parameter list
+    fn parameter_types(parameters: &str) -> String {
+        parameters.split(',')
+            .filter_map(|parameter| {
+                let parameter = parameter.split_once(':').map_or(parameter, |(_, rest)| rest).trim();
+                let mut words: Vec<&str> = parameter.split_whitespace().collect();
+                if words.len() > 1 {
+                    words.pop();
+                }
+                Some(words.join(" ")).filter(|t| !t.is_empty())
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
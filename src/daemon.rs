@@ -0,0 +1,296 @@
+use crate::{
+    file_index::{FileIndex, QueryKind},
+    parse_cache::ParseCache,
+};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{UnixListener, UnixStream};
+
+/// A request from the CLI, sent as one JSON line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DaemonRequest {
+    /// Run `analyze` inside the daemon
+    Analyze(AnalyzeRequest),
+    /// Look a file up in the last analysis
+    Query { kind: QueryKind, path: PathBuf },
+    Status,
+    Stop,
+}
+
+/// An `analyze` run as the client would have run it itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyzeRequest {
+    /// The client's working directory, which relative paths in `args` are taken from
+    pub cwd: PathBuf,
+    /// The client's `Config::env_vars`, used instead of the daemon's own
+    pub env: BTreeMap<String, String>,
+    /// The serialized `analyze` arguments
+    pub args: Value,
+}
+
+/// How a request failed, so the client can exit the way a local run would
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    #[default]
+    Error,
+    /// The run was cancelled by Ctrl-C
+    Interrupted,
+    /// The run exceeded `--max-duration`
+    TimedOut,
+}
+
+impl FailureKind {
+    /// 130 like a shell's SIGINT exit, 124 like `timeout(1)`
+    pub fn exit_code(self) -> i32 {
+        match self {
+            FailureKind::Error => 1,
+            FailureKind::Interrupted => 130,
+            FailureKind::TimedOut => 124,
+        }
+    }
+}
+
+/// A request the daemon failed to serve
+#[derive(Debug, Clone)]
+pub struct DaemonFailure {
+    pub kind: FailureKind,
+    pub message: String,
+}
+
+impl fmt::Display for DaemonFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for DaemonFailure {}
+
+impl From<Error> for DaemonFailure {
+    fn from(error: Error) -> Self {
+        Self { kind: FailureKind::Error, message: error.to_string() }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DaemonResponse {
+    ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kind: Option<FailureKind>,
+    #[serde(default)]
+    result: Value,
+}
+
+impl DaemonResponse {
+    fn from_result(result: std::result::Result<Value, DaemonFailure>) -> Self {
+        match result {
+            Ok(result) => Self { ok: true, error: None, kind: None, result },
+            Err(e) => Self { ok: false, error: Some(e.message), kind: Some(e.kind), result: Value::Null },
+        }
+    }
+}
+
+/// The socket a daemon serving `target` listens on, named after a hash of the
+/// target's absolute path so each project gets its own daemon. It lives in a
+/// per-user directory under the temp directory that only the user can enter.
+pub fn socket_path(target: &Path) -> PathBuf {
+    let target = target.canonicalize().unwrap_or_else(|_| target.to_path_buf());
+    let hash = target.to_string_lossy().bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    socket_dir().join(format!("{:016x}.sock", hash))
+}
+
+fn socket_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("project-examer-{}", current_uid()))
+}
+
+fn current_uid() -> u32 {
+    // SAFETY: getuid has no preconditions and cannot fail
+    unsafe { libc::getuid() }
+}
+
+/// Keeps parsed files and the last analysis's file index in memory and answers
+/// `analyze` and `query` requests from the CLI over a unix socket, one at a time
+pub struct Daemon {
+    socket: PathBuf,
+    cache: Arc<ParseCache>,
+    index: Option<FileIndex>,
+    started: Instant,
+    analyses: usize,
+}
+
+impl Daemon {
+    pub fn new(socket: &Path) -> Self {
+        Self {
+            socket: socket.to_path_buf(),
+            cache: Arc::new(ParseCache::new()),
+            index: None,
+            started: Instant::now(),
+            analyses: 0,
+        }
+    }
+
+    /// The parse cache to hand to each analysis run by the daemon
+    pub fn cache(&self) -> Arc<ParseCache> {
+        self.cache.clone()
+    }
+
+    /// Start from an index written by an earlier analysis, so queries work before the first run
+    pub fn with_index(mut self, index: FileIndex) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// Serve until a `stop` request or Ctrl-C. `analyze` runs one analysis for a
+    /// client and returns the summary for it and the new index.
+    pub async fn serve<F, Fut>(&mut self, mut analyze: F) -> Result<()>
+    where
+        F: FnMut(AnalyzeRequest) -> Fut,
+        Fut: Future<Output = std::result::Result<(Value, FileIndex), DaemonFailure>>,
+    {
+        let listener = self.bind()?;
+        loop {
+            let stream = tokio::select! {
+                accepted = listener.accept() => accepted?.0,
+                _ = tokio::signal::ctrl_c() => break,
+            };
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            // A client that disconnects mid-request only ends its own connection
+            while let Ok(Some(line)) = lines.next_line().await {
+                let request = serde_json::from_str::<DaemonRequest>(&line)
+                    .map_err(|e| Error::Protocol(format!("Invalid request: {}", e)));
+                let stop = matches!(request, Ok(DaemonRequest::Stop));
+                let result = match request {
+                    Ok(DaemonRequest::Analyze(request)) => self.analyze(&mut analyze, request).await,
+                    Ok(DaemonRequest::Query { kind, path }) => self.index.as_ref()
+                        .ok_or_else(|| Error::Protocol("No analysis yet; run `project-examer analyze` first".to_string()))
+                        .and_then(|index| index.query(kind, &path))
+                        .map_err(DaemonFailure::from),
+                    Ok(DaemonRequest::Status) => Ok(self.status()),
+                    Ok(DaemonRequest::Stop) => Ok(Value::Null),
+                    Err(e) => Err(e.into()),
+                };
+                let mut response = serde_json::to_vec(&DaemonResponse::from_result(result))?;
+                response.push(b'\n');
+                if writer.write_all(&response).await.is_err() {
+                    break;
+                }
+                if stop {
+                    let _ = std::fs::remove_file(&self.socket);
+                    return Ok(());
+                }
+            }
+        }
+        let _ = std::fs::remove_file(&self.socket);
+        Ok(())
+    }
+
+    async fn analyze<F, Fut>(&mut self, analyze: &mut F, request: AnalyzeRequest) -> std::result::Result<Value, DaemonFailure>
+    where
+        F: FnMut(AnalyzeRequest) -> Fut,
+        Fut: Future<Output = std::result::Result<(Value, FileIndex), DaemonFailure>>,
+    {
+        let (summary, index) = analyze(request).await?;
+        self.index = Some(index);
+        self.analyses += 1;
+        let pruned = self.cache.prune();
+        if pruned > 0 {
+            println!("🧹 Dropped {} deleted file(s) from the cache", pruned);
+        }
+        Ok(summary)
+    }
+
+    fn status(&self) -> Value {
+        json!({
+            "pid": std::process::id(),
+            "socket": self.socket,
+            "uptime_seconds": self.started.elapsed().as_secs(),
+            "analyses": self.analyses,
+            "cached_files": self.cache.len(),
+            "indexed_files": self.index.as_ref().map(|index| index.files.len()).unwrap_or(0),
+        })
+    }
+
+    /// Bind the socket, replacing one left behind by a daemon that didn't exit cleanly
+    fn bind(&self) -> Result<UnixListener> {
+        // Requests name arbitrary paths to read, so only the owner may reach the
+        // socket: its directory is created private, and one that isn't is refused
+        if let Some(dir) = self.socket.parent() {
+            match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+                Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => return Err(e.into()),
+                _ => {}
+            }
+            let metadata = std::fs::symlink_metadata(dir)?;
+            if !metadata.is_dir() || metadata.uid() != current_uid() || metadata.mode() & 0o077 != 0 {
+                return Err(Error::Protocol(format!(
+                    "{} must be a directory only you can access; remove it and try again",
+                    dir.display()
+                )));
+            }
+        }
+        if self.socket.exists() {
+            if std::os::unix::net::UnixStream::connect(&self.socket).is_ok() {
+                return Err(Error::Protocol(format!("A daemon is already listening on {}", self.socket.display())));
+            }
+            std::fs::remove_file(&self.socket)?;
+        }
+        let listener = UnixListener::bind(&self.socket)?;
+        std::fs::set_permissions(&self.socket, std::fs::Permissions::from_mode(0o600))?;
+        Ok(listener)
+    }
+}
+
+/// The CLI's side of the socket
+pub struct DaemonClient {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+}
+
+impl DaemonClient {
+    /// `None` when no daemon is listening on the socket
+    pub async fn connect(socket: &Path) -> Option<Self> {
+        let (reader, writer) = UnixStream::connect(socket).await.ok()?.into_split();
+        Some(Self { reader: BufReader::new(reader), writer })
+    }
+
+    pub async fn request(&mut self, request: &DaemonRequest) -> Result<Value> {
+        self.send(request).await?.map_err(|failure| Error::Protocol(failure.message))
+    }
+
+    /// Run an analysis in the daemon. The outer error is a failure to talk to
+    /// the daemon; the inner one is the analysis failing there.
+    pub async fn analyze(&mut self, request: AnalyzeRequest) -> Result<std::result::Result<Value, DaemonFailure>> {
+        self.send(&DaemonRequest::Analyze(request)).await
+    }
+
+    async fn send(&mut self, request: &DaemonRequest) -> Result<std::result::Result<Value, DaemonFailure>> {
+        let mut line = serde_json::to_vec(request)?;
+        line.push(b'\n');
+        self.writer.write_all(&line).await?;
+
+        let mut response = String::new();
+        if self.reader.read_line(&mut response).await? == 0 {
+            return Err(Error::Protocol("The daemon closed the connection".to_string()));
+        }
+        let response: DaemonResponse = serde_json::from_str(&response)?;
+        Ok(match response.error {
+            Some(message) if !response.ok => Err(DaemonFailure { kind: response.kind.unwrap_or_default(), message }),
+            _ => Ok(response.result),
+        })
+    }
+}
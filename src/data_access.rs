@@ -0,0 +1,365 @@
+use crate::{file_discovery::FileInfo, module_rollup::ModuleAnalyzer, sources::SourceSet};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Lines after a statement start searched for the rest of a multi-line query
+const STATEMENT_WINDOW: usize = 15;
+
+/// Words that follow FROM/JOIN/INTO/UPDATE but are not table names
+const NOT_TABLES: &[&str] = &["select", "where", "set", "values", "lateral", "unnest", "only", "dual", "if", "exists", "not", "table"];
+
+/// Languages whose files hold data or prose rather than code issuing queries
+const NON_CODE_LANGUAGES: &[&str] = &["json", "yaml", "toml", "xml", "markdown", "text", "html", "css", "scss", "latex"];
+
+/// Leading words of column definitions that are table constraints instead
+const CONSTRAINT_WORDS: &[&str] = &["primary", "foreign", "constraint", "unique", "check", "key", "index", "exclude"];
+
+/// How a file touches a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessKind {
+    /// `CREATE TABLE` / `ALTER TABLE` in a migration
+    Schema,
+    /// `SELECT`
+    Read,
+    /// `INSERT`, `UPDATE` or `DELETE`
+    Write,
+}
+
+impl AccessKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AccessKind::Schema => "schema",
+            AccessKind::Read => "read",
+            AccessKind::Write => "write",
+        }
+    }
+}
+
+/// A table defined by the project's SQL migrations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableSchema {
+    pub name: String,
+    pub columns: Vec<String>,
+    /// Migrations creating or altering the table
+    pub defined_in: Vec<PathBuf>,
+}
+
+/// One table referenced by a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableAccess {
+    pub table: String,
+    pub operations: BTreeSet<AccessKind>,
+    /// Columns named in the file's queries
+    pub columns: BTreeSet<String>,
+}
+
+/// SQL found in one file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDataAccess {
+    pub file: PathBuf,
+    /// Statements found, including DDL in migrations
+    pub queries: usize,
+    /// Lines building SQL by concatenation or string formatting instead of bind parameters
+    pub dynamic_queries: Vec<usize>,
+    pub tables: Vec<TableAccess>,
+}
+
+/// Tables touched by a module (directory, relative to the project root)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleDataAccess {
+    pub module: String,
+    pub tables: BTreeMap<String, BTreeSet<AccessKind>>,
+}
+
+/// Schema from SQL migrations and the tables each file and module touches
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DataAccess {
+    /// `.sql` files with `CREATE TABLE` or `ALTER TABLE`
+    pub migrations: Vec<PathBuf>,
+    pub tables: Vec<TableSchema>,
+    pub files: Vec<FileDataAccess>,
+    pub modules: Vec<ModuleDataAccess>,
+}
+
+impl DataAccess {
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty() && self.files.is_empty()
+    }
+
+    /// One line per module, e.g. `src/db: read orders; write users`, for LLM context
+    pub fn module_summaries(&self) -> Vec<String> {
+        self.modules.iter()
+            .map(|module| {
+                let tables = |kind: AccessKind| module.tables.iter()
+                    .filter(|(_, operations)| operations.contains(&kind))
+                    .map(|(table, _)| table.as_str())
+                    .collect::<Vec<_>>();
+                let parts: Vec<String> = [AccessKind::Read, AccessKind::Write, AccessKind::Schema].into_iter()
+                    .filter_map(|kind| {
+                        let tables = tables(kind);
+                        (!tables.is_empty()).then(|| format!("{} {}", kind.name(), tables.join(", ")))
+                    })
+                    .collect();
+                format!("{}: {}", module.module, parts.join("; "))
+            })
+            .collect()
+    }
+}
+
+/// Finds SQL migrations and inline SQL queries. Queries are recognized from string
+/// literals starting with `SELECT`/`INSERT`/`UPDATE`/`DELETE`/`WITH`; ORM calls and
+/// query builders are not followed.
+pub struct DataAccessAnalyzer {
+    root: PathBuf,
+    statement_start: Regex,
+    bare_statement: Regex,
+    write_statement: Regex,
+    table_reference: Regex,
+    create_table: Regex,
+    alter_table: Regex,
+    select_list: Regex,
+    insert_columns: Regex,
+    dynamic: Regex,
+}
+
+impl DataAccessAnalyzer {
+    pub fn new(root: &Path) -> Self {
+        let regex = |pattern: &str| Regex::new(pattern).expect("valid SQL pattern");
+        Self {
+            root: root.to_path_buf(),
+            statement_start: regex(r#"(?i)(?:["'`]|\bsql!?\(\s*|r#?")\s*(SELECT|INSERT\s+INTO|UPDATE|DELETE\s+FROM|WITH)\s"#),
+            bare_statement: regex(r"(?i)^\s*(SELECT|INSERT\s+INTO|UPDATE|DELETE\s+FROM|WITH)\s"),
+            write_statement: regex(r"(?i)^\s*(?:INSERT|UPDATE|DELETE)\b|\b(?:INSERT\s+INTO|DELETE\s+FROM)\b"),
+            table_reference: regex(r#"(?i)\b(?:FROM|JOIN|INTO)\s+[`"\[]?([A-Za-z_][\w.]*)|\bUPDATE\s+[`"\[]?([A-Za-z_][\w.]*)[`"\]]?\s+SET\b"#),
+            create_table: regex(r#"(?i)\bCREATE\s+(?:TEMP(?:ORARY)?\s+)?TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?[`"\[]?([A-Za-z_][\w.]*)[`"\]]?\s*\("#),
+            alter_table: regex(r#"(?i)\bALTER\s+TABLE\s+(?:IF\s+EXISTS\s+)?[`"\[]?([A-Za-z_][\w.]*)[`"\]]?\s+ADD\s+(?:COLUMN\s+)?(?:IF\s+NOT\s+EXISTS\s+)?[`"\[]?(\w+)"#),
+            select_list: regex(r"(?is)\bSELECT\s+(?:DISTINCT\s+)?(.+?)\s+FROM\b"),
+            insert_columns: regex(r"(?is)\bINSERT\s+INTO\s+\S+\s*\(([^)]*)\)"),
+            dynamic: regex(r#"(?i)\$\{|["'`]\s*\+|\+\s*["'`]|\.format\(|format!\(|\bf["'](?:\s*)(?:SELECT|INSERT|UPDATE|DELETE|WITH)\b|["']\s*%\s*[\w(]"#),
+        }
+    }
+
+    pub fn analyze(&self, files: &[FileInfo], sources: &SourceSet) -> DataAccess {
+        let contents: Vec<(&FileInfo, String)> = files.iter()
+            .filter_map(|file| sources.read(&file.path).ok().map(|content| (file, content)))
+            .collect();
+
+        let mut schema: BTreeMap<String, TableSchema> = BTreeMap::new();
+        let mut migrations = Vec::new();
+        let mut accesses = Vec::new();
+
+        for (file, content) in contents.iter().filter(|(file, _)| Self::is_sql(file)) {
+            let statements = self.schema_statements(&file.path, content, &mut schema);
+            if statements.is_empty() {
+                continue;
+            }
+            migrations.push(file.path.clone());
+            accesses.push(FileDataAccess {
+                file: file.path.clone(),
+                queries: statements.len(),
+                dynamic_queries: Vec::new(),
+                tables: statements.into_iter()
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .map(|table| TableAccess { table, operations: BTreeSet::from([AccessKind::Schema]), columns: BTreeSet::new() })
+                    .collect(),
+            });
+        }
+
+        for (file, content) in &contents {
+            if Self::is_sql(file) || file.language.as_deref().is_none_or(|language| NON_CODE_LANGUAGES.contains(&language)) {
+                continue;
+            }
+            if let Some(access) = self.queries_in(&file.path, content, &schema) {
+                accesses.push(access);
+            }
+        }
+        accesses.sort_by(|a, b| a.file.cmp(&b.file));
+
+        let mut modules: BTreeMap<String, BTreeMap<String, BTreeSet<AccessKind>>> = BTreeMap::new();
+        for access in &accesses {
+            let relative = access.file.strip_prefix(&self.root).unwrap_or(&access.file);
+            let module = modules.entry(ModuleAnalyzer::module_name(relative)).or_default();
+            for table in &access.tables {
+                module.entry(table.table.clone()).or_default().extend(table.operations.iter().copied());
+            }
+        }
+
+        DataAccess {
+            migrations,
+            tables: schema.into_values().collect(),
+            files: accesses,
+            modules: modules.into_iter()
+                .map(|(module, tables)| ModuleDataAccess { module, tables })
+                .collect(),
+        }
+    }
+
+    fn is_sql(file: &FileInfo) -> bool {
+        file.language.as_deref() == Some("sql")
+    }
+
+    /// `public.Users` and `users` name the same table
+    fn table_name(raw: &str) -> String {
+        raw.rsplit('.').next().unwrap_or(raw).trim_matches(['`', '"', '[', ']']).to_lowercase()
+    }
+
+    /// Record `CREATE TABLE` and `ALTER TABLE ... ADD` into the schema; returns the tables touched
+    fn schema_statements(&self, path: &Path, content: &str, schema: &mut BTreeMap<String, TableSchema>) -> Vec<String> {
+        let mut touched = Vec::new();
+        let table_entry = |name: &str, schema: &mut BTreeMap<String, TableSchema>| -> String {
+            let name = Self::table_name(name);
+            let table = schema.entry(name.clone()).or_insert_with(|| TableSchema {
+                name: name.clone(),
+                columns: Vec::new(),
+                defined_in: Vec::new(),
+            });
+            if !table.defined_in.iter().any(|p| p == path) {
+                table.defined_in.push(path.to_path_buf());
+            }
+            name
+        };
+
+        for captures in self.create_table.captures_iter(content) {
+            let name = table_entry(&captures[1], schema);
+            let body_start = captures.get(0).map(|m| m.end()).unwrap_or_default();
+            let columns = Self::column_definitions(&content[body_start..]);
+            if let Some(table) = schema.get_mut(&name) {
+                for column in columns {
+                    if !table.columns.contains(&column) {
+                        table.columns.push(column);
+                    }
+                }
+            }
+            touched.push(name);
+        }
+
+        for captures in self.alter_table.captures_iter(content) {
+            let name = table_entry(&captures[1], schema);
+            let column = captures[2].to_lowercase();
+            if let Some(table) = schema.get_mut(&name) {
+                if !CONSTRAINT_WORDS.contains(&column.as_str()) && !table.columns.contains(&column) {
+                    table.columns.push(column);
+                }
+            }
+            touched.push(name);
+        }
+        touched
+    }
+
+    /// Column names from the body of `CREATE TABLE (...)`, up to the matching parenthesis
+    fn column_definitions(body: &str) -> Vec<String> {
+        let mut depth = 0usize;
+        let mut definitions = vec![String::new()];
+        for ch in body.chars() {
+            match ch {
+                '(' => depth += 1,
+                ')' if depth == 0 => break,
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    definitions.push(String::new());
+                    continue;
+                }
+                _ => {}
+            }
+            if let Some(current) = definitions.last_mut() {
+                current.push(ch);
+            }
+        }
+
+        definitions.iter()
+            .filter_map(|definition| definition.split_whitespace().next())
+            .map(|word| word.trim_matches(['`', '"', '[', ']']).to_lowercase())
+            .filter(|word| !word.is_empty() && !CONSTRAINT_WORDS.contains(&word.as_str()))
+            .collect()
+    }
+
+    fn queries_in(&self, path: &Path, content: &str, schema: &BTreeMap<String, TableSchema>) -> Option<FileDataAccess> {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut queries = 0;
+        let mut dynamic_queries = Vec::new();
+        let mut tables: BTreeMap<String, TableAccess> = BTreeMap::new();
+
+        for (index, line) in lines.iter().enumerate() {
+            let trimmed = line.trim_start();
+            if ["//", "/*", "*", "--"].iter().any(|comment| trimmed.starts_with(comment)) || (trimmed.starts_with('#') && !trimmed.starts_with("#[")) {
+                continue;
+            }
+            // Multi-line literals put the statement on the line after the opening quote
+            let opens_literal = index > 0 && {
+                let previous = lines[index - 1].trim_end();
+                ["\"\"\"", "'''", "`", "r#\"", "\""].iter().any(|quote| previous.ends_with(quote))
+            };
+            let Some(start) = self.statement_start.captures(line)
+                .or_else(|| opens_literal.then(|| self.bare_statement.captures(line)).flatten())
+            else {
+                continue;
+            };
+
+            let statement = lines[index..(index + STATEMENT_WINDOW).min(lines.len())].join("\n");
+            let statement = &statement[start.get(1).map(|m| m.start()).unwrap_or_default()..];
+            let statement = Self::until_literal_end(statement);
+            let kind = if self.write_statement.is_match(statement) { AccessKind::Write } else { AccessKind::Read };
+
+            // Prose in strings ("... with the file") starts like a statement but names no table
+            let referenced: Vec<String> = self.table_reference.captures_iter(statement)
+                .filter_map(|captures| captures.get(1).or(captures.get(2)).map(|m| Self::table_name(m.as_str())))
+                .filter(|table| !NOT_TABLES.contains(&table.as_str()))
+                .collect();
+            if referenced.is_empty() {
+                continue;
+            }
+            queries += 1;
+            if self.dynamic.is_match(line) {
+                dynamic_queries.push(index + 1);
+            }
+
+            let mut named_columns = self.named_columns(statement);
+            for table in referenced {
+                let access = tables.entry(table.clone()).or_insert_with(|| TableAccess {
+                    table: table.clone(),
+                    operations: BTreeSet::new(),
+                    columns: BTreeSet::new(),
+                });
+                access.operations.insert(kind);
+                match schema.get(&table) {
+                    Some(known) => {
+                        let words: BTreeSet<String> = statement.split(|c: char| !c.is_alphanumeric() && c != '_').map(str::to_lowercase).collect();
+                        access.columns.extend(known.columns.iter().filter(|column| words.contains(*column)).cloned());
+                    }
+                    None => access.columns.append(&mut named_columns),
+                }
+            }
+        }
+
+        (queries > 0).then(|| FileDataAccess {
+            file: path.to_path_buf(),
+            queries,
+            dynamic_queries,
+            tables: tables.into_values().collect(),
+        })
+    }
+
+    /// The statement up to the closing quote or terminating semicolon
+    fn until_literal_end(statement: &str) -> &str {
+        let end = statement.find(['"', '\'', '`', ';']).unwrap_or(statement.len());
+        &statement[..end]
+    }
+
+    /// Plain column names from the select list or an `INSERT` column list
+    fn named_columns(&self, statement: &str) -> BTreeSet<String> {
+        self.select_list.captures(statement)
+            .into_iter()
+            .chain(self.insert_columns.captures(statement))
+            .flat_map(|captures| captures[1].split(',').map(str::to_string).collect::<Vec<_>>())
+            .filter_map(|item| {
+                let item = item.split_whitespace().next()?.to_string();
+                let column = item.rsplit('.').next()?.trim_matches(['`', '"', '[', ']']).to_lowercase();
+                (!column.is_empty() && column.chars().all(|c| c.is_alphanumeric() || c == '_')).then_some(column)
+            })
+            .collect()
+    }
+}
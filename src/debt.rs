@@ -0,0 +1,78 @@
+use crate::{git, simple_parser::ParsedFile};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A technical debt marker with its location and, when git history is
+/// available, who added it and how long ago.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebtItem {
+    pub kind: String,
+    pub text: String,
+    pub file: String,
+    pub line: usize,
+    pub author: Option<String>,
+    pub age_days: Option<u64>,
+}
+
+pub struct DebtCollector {
+    root: PathBuf,
+    git_blame: bool,
+    /// Unix time ages are measured from; the current time when unset
+    reference_time: Option<i64>,
+}
+
+impl DebtCollector {
+    pub fn new(root: &Path) -> Self {
+        Self { root: root.to_path_buf(), git_blame: true, reference_time: None }
+    }
+
+    /// Skip `git blame`, e.g. for in-memory file sets; author and age are left empty
+    pub fn with_git_blame(mut self, git_blame: bool) -> Self {
+        self.git_blame = git_blame;
+        self
+    }
+
+    /// Measure ages from a fixed Unix time instead of now (deterministic reports)
+    pub fn with_reference_time(mut self, reference_time: Option<i64>) -> Self {
+        self.reference_time = reference_time;
+        self
+    }
+
+    /// Collect markers from every parsed file, oldest first
+    pub fn collect(&self, parsed_files: &[ParsedFile]) -> Vec<DebtItem> {
+        let now = self.reference_time.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0)
+        });
+
+        let mut items = Vec::new();
+        for pf in parsed_files.iter().filter(|pf| !pf.debt_markers.is_empty()) {
+            // One blame per file rather than per marker
+            let blame: BTreeMap<usize, git::BlameLine> = self.git_blame.then(|| git::blame(&self.root, &pf.file_info.path))
+                .flatten()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|line| (line.line_number, line))
+                .collect();
+
+            for marker in &pf.debt_markers {
+                let blame_line = blame.get(&marker.line_number);
+                items.push(DebtItem {
+                    kind: marker.kind.clone(),
+                    text: marker.text.clone(),
+                    file: pf.file_info.path.to_string_lossy().to_string(),
+                    line: marker.line_number,
+                    author: blame_line.map(|b| b.author.clone()),
+                    age_days: blame_line.map(|b| (now - b.author_time).max(0) as u64 / 86_400),
+                });
+            }
+        }
+
+        items.sort_by(|a, b| b.age_days.cmp(&a.age_days).then_with(|| (&a.file, a.line).cmp(&(&b.file, b.line))));
+        items
+    }
+}
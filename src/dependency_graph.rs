@@ -1,11 +1,14 @@
 use crate::simple_parser::{ParsedFile, Function, Class};
 use petgraph::{Graph, Directed, graph::NodeIndex};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
 
 pub type DependencyGraph = Graph<Node, Edge, Directed>;
 
+/// Re-export chains longer than this are attributed to the file they stop at
+const MAX_REEXPORT_DEPTH: usize = 8;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Node {
     pub id: String,
@@ -55,6 +58,15 @@ pub enum EdgeType {
     Contains,
     References,
     DependsOn,
+    /// An import that loads its target at runtime
+    DynamicImport,
+}
+
+impl EdgeType {
+    /// Edges from an import to the file it resolves to
+    pub fn is_file_dependency(&self) -> bool {
+        matches!(self, EdgeType::DependsOn | EdgeType::DynamicImport)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,12 +74,23 @@ pub struct EdgeMetadata {
     pub call_count: usize,
     pub is_direct: bool,
     pub line_numbers: Vec<usize>,
+    /// The barrel file the import names, when the edge was followed through its re-exports
+    #[serde(default)]
+    pub via: Option<PathBuf>,
 }
 
 pub struct GraphBuilder {
     graph: DependencyGraph,
     node_map: HashMap<String, NodeIndex>,
     file_nodes: HashMap<PathBuf, NodeIndex>,
+    max_component_size: usize,
+    barrel_files: Vec<BarrelFile>,
+}
+
+impl Default for GraphBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GraphBuilder {
@@ -76,9 +99,17 @@ impl GraphBuilder {
             graph: Graph::new(),
             node_map: HashMap::new(),
             file_nodes: HashMap::new(),
+            max_component_size: 5,
+            barrel_files: Vec::new(),
         }
     }
 
+    /// Dependency cycles spanning more files than this are flagged as architectural risks
+    pub fn with_max_component_size(mut self, max_component_size: usize) -> Self {
+        self.max_component_size = max_component_size;
+        self
+    }
+
     pub fn build_graph(&mut self, parsed_files: &[ParsedFile]) -> &DependencyGraph {
         for parsed_file in parsed_files {
             self.add_file_node(parsed_file);
@@ -88,6 +119,7 @@ impl GraphBuilder {
         }
 
         self.add_call_relationships(parsed_files);
+        self.barrel_files = self.find_barrel_files(parsed_files);
         &self.graph
     }
 
@@ -155,6 +187,7 @@ impl GraphBuilder {
                     call_count: 1,
                     is_direct: true,
                     line_numbers: vec![import.line_number],
+                    via: None,
                 },
             };
 
@@ -182,7 +215,7 @@ impl GraphBuilder {
                     return_type: function.return_type.clone(),
                     is_async: function.is_async,
                     is_exported: self.is_function_exported(parsed_file, function),
-                    docstring: None,
+                    docstring: function.docstring.clone(),
                 },
             };
 
@@ -196,6 +229,7 @@ impl GraphBuilder {
                     call_count: 1,
                     is_direct: true,
                     line_numbers: vec![function.line_number],
+                    via: None,
                 },
             };
 
@@ -223,7 +257,7 @@ impl GraphBuilder {
                     return_type: None,
                     is_async: false,
                     is_exported: self.is_class_exported(parsed_file, class),
-                    docstring: None,
+                    docstring: class.docstring.clone(),
                 },
             };
 
@@ -237,6 +271,7 @@ impl GraphBuilder {
                     call_count: 1,
                     is_direct: true,
                     line_numbers: vec![class.line_number],
+                    via: None,
                 },
             };
 
@@ -259,7 +294,7 @@ impl GraphBuilder {
                         return_type: method.return_type.clone(),
                         is_async: method.is_async,
                         is_exported: false,
-                        docstring: None,
+                        docstring: method.docstring.clone(),
                     },
                 };
 
@@ -273,6 +308,7 @@ impl GraphBuilder {
                         call_count: 1,
                         is_direct: true,
                         line_numbers: vec![method.line_number],
+                        via: None,
                     },
                 };
 
@@ -281,38 +317,165 @@ impl GraphBuilder {
         }
     }
 
+    /// DependsOn (or, for lazy loads, lighter DynamicImport) edges from each import
+    /// to the files defining what it names, following `export ... from` and `pub use` re-exports
     fn add_call_relationships(&mut self, parsed_files: &[ParsedFile]) {
         for parsed_file in parsed_files {
             for import in &parsed_file.imports {
-                if let Some(target_file) = self.find_imported_file(parsed_files, &import.module) {
-                    if let Some(&import_node) = self.node_map.get(&format!("import:{}:{}", parsed_file.file_info.path.display(), import.module)) {
-                        if let Some(&target_node) = self.file_nodes.get(&target_file.file_info.path) {
-                            let edge = Edge {
-                                edge_type: EdgeType::DependsOn,
-                                weight: 1.0,
-                                metadata: EdgeMetadata {
-                                    call_count: 1,
-                                    is_direct: true,
-                                    line_numbers: vec![import.line_number],
-                                },
-                            };
-
-                            self.graph.add_edge(import_node, target_node, edge);
-                        }
-                    }
+                let Some(imported) = self.find_imported_file(parsed_files, parsed_file, &import.module) else { continue };
+                let Some(&import_node) = self.node_map.get(&format!("import:{}:{}", parsed_file.file_info.path.display(), import.module)) else { continue };
+
+                let mut targets: Vec<&Path> = import.names().iter()
+                    .map(|(name, _)| self.defining_file(parsed_files, imported, name, 0).unwrap_or(imported).file_info.path.as_path())
+                    .collect();
+                if targets.is_empty() {
+                    targets.push(&imported.file_info.path);
+                }
+                targets.sort();
+                targets.dedup();
+
+                for target in targets {
+                    let Some(&target_node) = self.file_nodes.get(target) else { continue };
+                    let edge = Edge {
+                        edge_type: if import.is_dynamic { EdgeType::DynamicImport } else { EdgeType::DependsOn },
+                        weight: if import.is_dynamic { 0.5 } else { 1.0 },
+                        metadata: EdgeMetadata {
+                            call_count: 1,
+                            is_direct: true,
+                            line_numbers: vec![import.line_number],
+                            via: (target != imported.file_info.path).then(|| imported.file_info.path.clone()),
+                        },
+                    };
+
+                    self.graph.add_edge(import_node, target_node, edge);
+                }
+            }
+        }
+    }
+
+    /// The file that defines `name` as exported by `file`: `file` itself, or the
+    /// end of the re-export chain it's forwarded through. None when neither
+    /// `file` nor its re-exports provide the name.
+    fn defining_file<'a>(&self, parsed_files: &'a [ParsedFile], file: &'a ParsedFile, name: &str, depth: usize) -> Option<&'a ParsedFile> {
+        if Self::defines(file, name) {
+            return Some(file);
+        }
+        if depth >= MAX_REEXPORT_DEPTH {
+            return None;
+        }
+
+        for reexport in file.imports.iter().filter(|import| import.is_reexport) {
+            let names = reexport.names();
+            let source = if names.is_empty() {
+                name
+            } else if let Some((source, _)) = names.iter().find(|(_, exported)| *exported == name) {
+                source
+            } else {
+                continue;
+            };
+            let Some(next) = self.find_imported_file(parsed_files, file, &reexport.module) else { continue };
+
+            if names.is_empty() {
+                // A glob only forwards the name if something behind it provides it
+                if let Some(defining) = self.defining_file(parsed_files, next, name, depth + 1) {
+                    return Some(defining);
                 }
+            } else if source == "*" {
+                // `export * as ns from`: the whole module
+                return Some(next);
+            } else {
+                return Some(self.defining_file(parsed_files, next, source, depth + 1).unwrap_or(next));
             }
         }
+        None
+    }
+
+    /// Functions, classes and (non re-exported) exports declared in the file itself
+    fn defines(file: &ParsedFile, name: &str) -> bool {
+        let reexport_lines: BTreeSet<usize> = file.imports.iter()
+            .filter(|import| import.is_reexport)
+            .map(|import| import.line_number)
+            .collect();
+        file.functions.iter().any(|function| function.name == name)
+            || file.classes.iter().any(|class| class.name == name)
+            || file.exports.iter()
+                .filter(|export| !reexport_lines.contains(&export.line_number))
+                .any(|export| export.name == name || (name == "default" && export.is_default))
     }
 
-    fn find_imported_file<'a>(&self, parsed_files: &'a [ParsedFile], module_name: &str) -> Option<&'a ParsedFile> {
-        parsed_files.iter().find(|f| {
-            f.file_info.path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .map(|s| s == module_name)
-                .unwrap_or(false)
-        })
+    /// Files that re-export from others without defining anything themselves,
+    /// most imported first
+    fn find_barrel_files(&self, parsed_files: &[ParsedFile]) -> Vec<BarrelFile> {
+        let mut barrels: Vec<BarrelFile> = parsed_files.iter()
+            .filter(|pf| pf.functions.is_empty() && pf.classes.is_empty())
+            .filter(|pf| pf.imports.iter().any(|import| import.is_reexport))
+            .map(|pf| {
+                let path = &pf.file_info.path;
+                let mut importers = BTreeSet::new();
+                let mut forwards_to = BTreeSet::new();
+                for edge in self.graph.edge_indices().filter(|&edge| self.graph[edge].edge_type.is_file_dependency()) {
+                    let Some((source, target)) = self.graph.edge_endpoints(edge) else { continue };
+                    let (from, to) = (&self.graph[source].file_path, &self.graph[target].file_path);
+                    if from == path {
+                        forwards_to.insert(to.clone());
+                    } else if to == path || self.graph[edge].metadata.via.as_ref() == Some(path) {
+                        importers.insert(from.clone());
+                    }
+                }
+                BarrelFile {
+                    path: path.clone(),
+                    re_exports: pf.imports.iter().filter(|import| import.is_reexport).count(),
+                    importers: importers.len(),
+                    forwards_to: forwards_to.into_iter().collect(),
+                }
+            })
+            .collect();
+        barrels.sort_by(|a, b| b.importers.cmp(&a.importers).then_with(|| a.path.cmp(&b.path)));
+        barrels
+    }
+
+    /// Resolve an import to a parsed file: relative paths (`./x`, `../y/z`) are
+    /// resolved against the importing file's directory, other modules match a
+    /// file stem against their path segments, last segment first.
+    fn find_imported_file<'a>(&self, parsed_files: &'a [ParsedFile], importer: &ParsedFile, module_name: &str) -> Option<&'a ParsedFile> {
+        let stem_of = |f: &ParsedFile| f.file_info.path.file_stem().and_then(|s| s.to_str()).map(String::from);
+        let candidates = || parsed_files.iter().filter(|f| f.file_info.path != importer.file_info.path);
+
+        if module_name.starts_with("./") || module_name.starts_with("../") {
+            let base = importer.file_info.path.parent()?;
+            let target = Self::normalize(&base.join(module_name));
+            return candidates().find(|f| {
+                let path = &f.file_info.path;
+                Self::normalize(&path.with_extension("")) == target
+                    || Self::normalize(path) == target
+                    || (stem_of(f).as_deref() == Some("index") && path.parent().is_some_and(|dir| Self::normalize(dir) == target))
+            });
+        }
+
+        module_name
+            .split(['/', ':', '.'])
+            .filter(|segment| !segment.is_empty() && !matches!(*segment, "crate" | "self" | "super" | "std" | "core" | "alloc"))
+            .rev()
+            .find_map(|segment| candidates().find(|f| match stem_of(f).as_deref() {
+                // `a/mod.rs` is module `a`
+                Some("mod") => f.file_info.path.parent().and_then(Path::file_name).is_some_and(|dir| dir == segment),
+                stem => stem == Some(segment),
+            }))
+    }
+
+    /// Lexically resolve `.` and `..` components
+    fn normalize(path: &Path) -> PathBuf {
+        let mut normalized = PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    normalized.pop();
+                }
+                other => normalized.push(other),
+            }
+        }
+        normalized
     }
 
     fn calculate_file_complexity(&self, parsed_file: &ParsedFile) -> usize {
@@ -343,13 +506,60 @@ impl GraphBuilder {
         &self.node_map
     }
 
+    /// Resolved file-to-file dependencies (one entry per resolved import)
+    pub fn file_dependencies(&self) -> Vec<FileDependency> {
+        self.graph.edge_indices()
+            .filter(|&edge| self.graph[edge].edge_type.is_file_dependency())
+            .filter_map(|edge| {
+                let (source, target) = self.graph.edge_endpoints(edge)?;
+                Some(FileDependency {
+                    from: self.graph[source].file_path.clone(),
+                    to: self.graph[target].file_path.clone(),
+                    line_number: self.graph[source].line_number,
+                    via: self.graph[edge].metadata.via.clone(),
+                    is_dynamic: matches!(self.graph[edge].edge_type, EdgeType::DynamicImport),
+                })
+            })
+            .collect()
+    }
+
+    /// File-level strongly connected components that contain a cycle, largest
+    /// first. Lazy imports are left out: they don't run at load time, which is
+    /// how cycles are usually broken.
+    fn cyclic_components(&self) -> Vec<Vec<PathBuf>> {
+        let mut file_graph: Graph<PathBuf, (), Directed> = Graph::new();
+        let mut indices: HashMap<PathBuf, NodeIndex> = HashMap::new();
+        let mut self_loops = std::collections::HashSet::new();
+
+        for dep in self.file_dependencies().into_iter().filter(|dep| !dep.is_dynamic) {
+            let mut index_of = |path: &PathBuf| *indices.entry(path.clone())
+                .or_insert_with(|| file_graph.add_node(path.clone()));
+            let from = index_of(&dep.from);
+            let to = index_of(&dep.to);
+            if from == to {
+                self_loops.insert(from);
+            }
+            file_graph.update_edge(from, to, ());
+        }
+
+        let mut components: Vec<Vec<PathBuf>> = petgraph::algo::tarjan_scc(&file_graph).into_iter()
+            .filter(|component| component.len() > 1 || self_loops.contains(&component[0]))
+            .map(|component| {
+                let mut files: Vec<PathBuf> = component.into_iter().map(|i| file_graph[i].clone()).collect();
+                files.sort();
+                files
+            })
+            .collect();
+        components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        components
+    }
+
     pub fn analyze_dependencies(&self) -> DependencyAnalysis {
         let total_nodes = self.graph.node_count();
         let total_edges = self.graph.edge_count();
         
-        let mut node_types = HashMap::new();
-        let mut edge_types = HashMap::new();
-        let strongly_connected_components = 0;
+        let mut node_types = BTreeMap::new();
+        let mut edge_types = BTreeMap::new();
         
         for node_weight in self.graph.node_weights() {
             *node_types.entry(format!("{:?}", node_weight.node_type)).or_insert(0) += 1;
@@ -359,25 +569,73 @@ impl GraphBuilder {
             *edge_types.entry(format!("{:?}", edge_weight.edge_type)).or_insert(0) += 1;
         }
 
+        let cyclic_components = self.cyclic_components();
+        let oversized_components = cyclic_components.iter()
+            .filter(|component| component.len() > self.max_component_size)
+            .cloned()
+            .collect();
+
         DependencyAnalysis {
             total_nodes,
             total_edges,
             node_types,
             edge_types,
-            strongly_connected_components,
+            strongly_connected_components: cyclic_components.len(),
+            largest_component: cyclic_components.first().cloned().unwrap_or_default(),
+            cyclic_components,
+            oversized_components,
             avg_degree: if total_nodes > 0 { total_edges as f64 / total_nodes as f64 } else { 0.0 },
+            barrel_files: self.barrel_files.clone(),
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDependency {
+    pub from: PathBuf,
+    /// The file defining what was imported, past any re-exports
+    pub to: PathBuf,
+    pub line_number: usize,
+    /// The barrel file the import statement names, when it differs from `to`
+    #[serde(default)]
+    pub via: Option<PathBuf>,
+    /// Loaded lazily at runtime
+    #[serde(default)]
+    pub is_dynamic: bool,
+}
+
+/// A file that only re-exports other files' symbols (`index.ts`, `mod.rs`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarrelFile {
+    pub path: PathBuf,
+    /// `export ... from` or `pub use` statements
+    pub re_exports: usize,
+    /// Files importing through it
+    pub importers: usize,
+    /// Files whose symbols it re-exports
+    pub forwards_to: Vec<PathBuf>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DependencyAnalysis {
     pub total_nodes: usize,
     pub total_edges: usize,
-    pub node_types: HashMap<String, usize>,
-    pub edge_types: HashMap<String, usize>,
+    pub node_types: BTreeMap<String, usize>,
+    pub edge_types: BTreeMap<String, usize>,
+    /// Number of file-level strongly connected components containing a cycle
     pub strongly_connected_components: usize,
+    #[serde(default)]
+    pub largest_component: Vec<PathBuf>,
+    /// Every cyclic component, largest first
+    #[serde(default)]
+    pub cyclic_components: Vec<Vec<PathBuf>>,
+    /// Cyclic components larger than the configured `max_component_size`
+    #[serde(default)]
+    pub oversized_components: Vec<Vec<PathBuf>>,
     pub avg_degree: f64,
+    /// Most imported first
+    #[serde(default)]
+    pub barrel_files: Vec<BarrelFile>,
 }
 
 impl DependencyAnalysis {
@@ -386,6 +644,16 @@ impl DependencyAnalysis {
         println!("  Total nodes: {}", self.total_nodes);
         println!("  Total edges: {}", self.total_edges);
         println!("  Average degree: {:.2}", self.avg_degree);
+        println!("  Dependency cycles (strongly connected components): {}", self.strongly_connected_components);
+        if !self.largest_component.is_empty() {
+            println!("  Largest cycle spans {} files", self.largest_component.len());
+        }
+        if !self.oversized_components.is_empty() {
+            println!("  ⚠️  {} cycle(s) exceed the configured size limit", self.oversized_components.len());
+        }
+        if !self.barrel_files.is_empty() {
+            println!("  Barrel files (re-export only): {}", self.barrel_files.len());
+        }
         
         println!("  Node types:");
         for (node_type, count) in &self.node_types {
@@ -0,0 +1,217 @@
+use crate::{
+    analyzer::ProjectAnalysis,
+    dependency_graph::FileDependency,
+    llm::AnalysisType,
+    module_rollup::{ModuleAnalysis, ModuleAnalyzer},
+    simple_parser::ParsedFile,
+};
+use crate::{Error, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File names conventionally used as program entry points
+const ENTRY_POINT_NAMES: &[&str] = &[
+    "main.rs", "lib.rs", "main.py", "__main__.py", "app.py", "manage.py",
+    "index.js", "index.ts", "main.js", "main.ts", "server.js", "app.js", "main.go",
+];
+
+/// Writes a multi-file Markdown documentation bundle for a project
+#[derive(Default)]
+pub struct DocBundleWriter;
+
+impl DocBundleWriter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Write `README.md`, `ARCHITECTURE.md`, `MODULES.md`, `DEPENDENCIES.md`
+    /// and `GETTING_STARTED.md` into `output_dir`
+    pub fn write(&self, analysis: &ProjectAnalysis, project_root: &Path, output_dir: &Path) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+        let modules = ModuleAnalyzer::new().analyze(analysis);
+
+        let documents = [
+            ("README.md", self.index(analysis, &modules)),
+            ("ARCHITECTURE.md", self.architecture(analysis, &modules)),
+            ("MODULES.md", self.modules(&modules)),
+            ("DEPENDENCIES.md", self.dependencies(analysis, &modules)),
+            ("GETTING_STARTED.md", self.getting_started(analysis, project_root)),
+        ];
+
+        let mut written = Vec::new();
+        for (name, content) in documents {
+            let path = output_dir.join(name);
+            fs::write(&path, content).map_err(|e| Error::report(&path, e))?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    fn index(&self, analysis: &ProjectAnalysis, modules: &ModuleAnalysis) -> String {
+        format!(
+            "# Project Documentation\n\n\
+             Generated by project-examer {} from {} files in {} modules.\n\n\
+             - [Architecture overview](ARCHITECTURE.md)\n\
+             - [Module descriptions](MODULES.md)\n\
+             - [Dependency diagrams](DEPENDENCIES.md)\n\
+             - [Getting started](GETTING_STARTED.md)\n",
+            env!("CARGO_PKG_VERSION"), analysis.parsed_files.len(), modules.modules.len()
+        )
+    }
+
+    fn architecture(&self, analysis: &ProjectAnalysis, modules: &ModuleAnalysis) -> String {
+        let mut md = String::from("# Architecture Overview\n\n");
+
+        let llm_text = analysis.llm_analysis.iter()
+            .filter(|r| matches!(r.analysis_type, Some(AnalysisType::Documentation) | Some(AnalysisType::Architecture)))
+            .map(|r| r.analysis.trim())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>();
+        if llm_text.is_empty() {
+            md.push_str("_No LLM documentation analysis was available; this overview is generated from static analysis only._\n\n");
+        } else {
+            md.push_str(&llm_text.join("\n\n"));
+            md.push_str("\n\n");
+        }
+
+        let mut languages: HashMap<&str, usize> = HashMap::new();
+        for file in &analysis.files {
+            if let Some(ref language) = file.language {
+                *languages.entry(language.as_str()).or_insert(0) += 1;
+            }
+        }
+        let mut languages: Vec<_> = languages.into_iter().collect();
+        languages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        md.push_str("## At a Glance\n\n");
+        md.push_str(&format!("- **Files:** {}\n", analysis.files.len()));
+        md.push_str(&format!("- **Modules:** {}\n", modules.modules.len()));
+        md.push_str(&format!("- **Languages:** {}\n", languages.iter()
+            .map(|(language, count)| format!("{} ({})", language, count))
+            .collect::<Vec<_>>()
+            .join(", ")));
+        md.push_str(&format!("- **Dependency cycles:** {}\n", analysis.dependency_analysis.strongly_connected_components));
+
+        if !analysis.components.is_empty() {
+            md.push_str("\n## Components\n\n");
+            md.push_str(&format!("```mermaid\n{}```\n", analysis.components.mermaid()));
+        }
+
+        let entry_points = find_entry_points(&analysis.parsed_files);
+        if !entry_points.is_empty() {
+            md.push_str("\n## Entry Points\n\n");
+            for path in entry_points {
+                md.push_str(&format!("- `{}`\n", path.display()));
+            }
+        }
+
+        md
+    }
+
+    fn modules(&self, modules: &ModuleAnalysis) -> String {
+        let mut md = String::from("# Modules\n");
+        for module in &modules.modules {
+            md.push_str(&format!("\n## {}\n\n", module.module));
+            if let Some(ref summary) = module.summary {
+                md.push_str(&format!("{}\n\n", summary));
+            }
+            md.push_str(&format!(
+                "- **Files:** {}\n- **Lines of code:** {}\n- **Depends on {} modules, used by {} modules** (instability {:.2})\n",
+                module.files, module.lines_of_code, module.efferent_coupling, module.afferent_coupling, module.instability
+            ));
+        }
+        md
+    }
+
+    fn dependencies(&self, analysis: &ProjectAnalysis, modules: &ModuleAnalysis) -> String {
+        let mut md = String::from("# Dependencies\n\n## Module Dependency Graph\n\n");
+        md.push_str(&mermaid_module_graph(modules));
+
+        if !analysis.dependency_analysis.cyclic_components.is_empty() {
+            md.push_str("\n## Dependency Cycles\n\n");
+            for component in &analysis.dependency_analysis.cyclic_components {
+                md.push_str(&format!("- {}\n", component.iter()
+                    .map(|p| format!("`{}`", p.display()))
+                    .collect::<Vec<_>>()
+                    .join(" ↔ ")));
+            }
+        }
+        md
+    }
+
+    fn getting_started(&self, analysis: &ProjectAnalysis, project_root: &Path) -> String {
+        let mut md = String::from("# Getting Started\n\n## Build and Run\n\n");
+
+        let commands: Vec<&str> = [
+            ("Cargo.toml", "cargo build\ncargo test"),
+            ("package.json", "npm install\nnpm test"),
+            ("pyproject.toml", "pip install -e ."),
+            ("requirements.txt", "pip install -r requirements.txt"),
+            ("go.mod", "go build ./...\ngo test ./..."),
+            ("Makefile", "make"),
+        ].iter()
+            .filter(|(manifest, _)| project_root.join(manifest).exists())
+            .map(|(_, commands)| *commands)
+            .collect();
+        if commands.is_empty() {
+            md.push_str("No build manifest was found at the project root.\n");
+        } else {
+            md.push_str(&format!("```bash\n{}\n```\n", commands.join("\n")));
+        }
+
+        md.push_str("\n## Where to Start Reading\n\n");
+        for path in find_entry_points(&analysis.parsed_files) {
+            md.push_str(&format!("- `{}` — entry point\n", path.display()));
+        }
+        for (path, dependents) in most_depended_on(&analysis.file_dependencies, 5) {
+            md.push_str(&format!("- `{}` — imported by {} files\n", path.display(), dependents));
+        }
+        md
+    }
+}
+
+/// Files whose names mark them as program or library entry points
+pub fn find_entry_points(parsed_files: &[ParsedFile]) -> Vec<PathBuf> {
+    let mut entry_points: Vec<PathBuf> = parsed_files.iter()
+        .map(|pf| &pf.file_info.path)
+        .filter(|path| path.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|name| ENTRY_POINT_NAMES.contains(&name)))
+        .cloned()
+        .collect();
+    entry_points.sort();
+    entry_points
+}
+
+/// The `limit` files with the most incoming file dependencies
+pub fn most_depended_on(file_dependencies: &[FileDependency], limit: usize) -> Vec<(PathBuf, usize)> {
+    let mut dependents: HashMap<&Path, usize> = HashMap::new();
+    for dep in file_dependencies {
+        *dependents.entry(dep.to.as_path()).or_insert(0) += 1;
+    }
+    let mut ranked: Vec<(PathBuf, usize)> = dependents.into_iter()
+        .map(|(path, count)| (path.to_path_buf(), count))
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(limit);
+    ranked
+}
+
+/// Mermaid flowchart of module-to-module dependencies, edges labelled with file-level counts
+pub fn mermaid_module_graph(modules: &ModuleAnalysis) -> String {
+    let matrix = &modules.dependency_matrix;
+    let mut mermaid = String::from("```mermaid\ngraph LR\n");
+    for (i, module) in matrix.modules.iter().enumerate() {
+        mermaid.push_str(&format!("    m{}[\"{}\"]\n", i, module.replace('"', "'")));
+    }
+    for (i, row) in matrix.cells.iter().enumerate() {
+        for (j, &count) in row.iter().enumerate() {
+            if i != j && count > 0 {
+                mermaid.push_str(&format!("    m{} -->|{}| m{}\n", i, count, j));
+            }
+        }
+    }
+    mermaid.push_str("```\n");
+    mermaid
+}
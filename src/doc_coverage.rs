@@ -0,0 +1,105 @@
+use crate::{module_rollup::ModuleAnalyzer, simple_parser::ParsedFile, symbols::SymbolKind};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Share of public functions and classes that carry doc comments
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocCoverage {
+    pub documented: usize,
+    pub total: usize,
+    /// Percentage (0-100); 100 when there are no public symbols
+    pub coverage: f64,
+    pub modules: Vec<ModuleDocCoverage>,
+    pub undocumented: Vec<UndocumentedSymbol>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleDocCoverage {
+    pub module: String,
+    pub documented: usize,
+    pub total: usize,
+    pub coverage: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndocumentedSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub file: String,
+    pub line: usize,
+}
+
+#[derive(Default)]
+pub struct DocCoverageAnalyzer;
+
+impl DocCoverageAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn analyze(&self, parsed_files: &[ParsedFile]) -> DocCoverage {
+        let mut modules: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+        let mut undocumented = Vec::new();
+
+        for pf in parsed_files {
+            let module = ModuleAnalyzer::module_name(&pf.file_info.path);
+            let functions = pf.functions.iter()
+                .map(|f| (f.name.as_str(), SymbolKind::Function, f.line_number, f.is_documented));
+            let classes = pf.classes.iter()
+                .map(|c| (c.name.as_str(), SymbolKind::Class, c.line_number, c.is_documented));
+
+            for (name, kind, line, is_documented) in functions.chain(classes) {
+                if !Self::is_public(pf, name) {
+                    continue;
+                }
+
+                let counts = modules.entry(module.clone()).or_default();
+                counts.1 += 1;
+                if is_documented {
+                    counts.0 += 1;
+                } else {
+                    undocumented.push(UndocumentedSymbol {
+                        name: name.to_string(),
+                        kind,
+                        file: pf.file_info.path.to_string_lossy().to_string(),
+                        line,
+                    });
+                }
+            }
+        }
+
+        let documented = modules.values().map(|(documented, _)| documented).sum();
+        let total = modules.values().map(|(_, total)| total).sum();
+        DocCoverage {
+            documented,
+            total,
+            coverage: Self::percentage(documented, total),
+            modules: modules.into_iter()
+                .map(|(module, (documented, total))| ModuleDocCoverage {
+                    module,
+                    documented,
+                    total,
+                    coverage: Self::percentage(documented, total),
+                })
+                .collect(),
+            undocumented,
+        }
+    }
+
+    /// Exported symbols are public; Python modules without `__all__` treat
+    /// every name without a leading underscore as public.
+    fn is_public(pf: &ParsedFile, name: &str) -> bool {
+        if pf.exports.iter().any(|e| e.name == name) {
+            return true;
+        }
+        pf.file_info.language.as_deref() == Some("python") && pf.exports.is_empty() && !name.starts_with('_')
+    }
+
+    fn percentage(documented: usize, total: usize) -> f64 {
+        if total == 0 {
+            100.0
+        } else {
+            documented as f64 * 100.0 / total as f64
+        }
+    }
+}
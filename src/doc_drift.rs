@@ -0,0 +1,226 @@
+use crate::{file_discovery::FileInfo, simple_parser::ParsedFile, sources::SourceSet};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// A README/CHANGELOG reference that no longer matches the code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocDrift {
+    pub doc_file: String,
+    pub line: usize,
+    pub kind: DriftKind,
+    pub reference: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DriftKind {
+    FilePath,
+    Symbol,
+    CliFlag,
+    ConfigKey,
+    ChangelogVersion,
+}
+
+struct Reference {
+    doc_file: String,
+    line: usize,
+    kind: DriftKind,
+    reference: String,
+}
+
+pub struct DocDriftAnalyzer {
+    root: PathBuf,
+    inline_code: Regex,
+    cli_flag: Regex,
+    config_key: Regex,
+    changelog_version: Regex,
+}
+
+impl DocDriftAnalyzer {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            inline_code: Regex::new(r"`([^`\s]+)`").unwrap(),
+            cli_flag: Regex::new(r"(?:^|\s)(--[a-z][a-z0-9-]+)").unwrap(),
+            config_key: Regex::new(r"^\s*#?\s*([a-z][a-z0-9_]*)\s*=").unwrap(),
+            changelog_version: Regex::new(r"^##\s*\[?v?(\d+\.\d+\.\d+[^\]\s]*)").unwrap(),
+        }
+    }
+
+    /// Check README and CHANGELOG files for paths, symbols, CLI flags and
+    /// config keys that don't exist in the analyzed sources.
+    pub fn analyze(&self, files: &[FileInfo], parsed_files: &[ParsedFile], sources: &SourceSet) -> Vec<DocDrift> {
+        let doc_files: Vec<&FileInfo> = files.iter().filter(|f| Self::is_project_doc(&f.path)).collect();
+        let mut drift = Vec::new();
+        let mut references = Vec::new();
+
+        for doc in &doc_files {
+            let Ok(content) = sources.read(&doc.path) else { continue };
+            let doc_file = doc.path.to_string_lossy().to_string();
+            references.extend(self.extract_references(&doc_file, &content));
+            if Self::file_name(&doc.path).starts_with("CHANGELOG") {
+                drift.extend(self.check_changelog_version(&doc_file, &content, sources));
+            }
+        }
+
+        let symbols: HashSet<&str> = parsed_files.iter().flat_map(|pf| {
+            pf.functions.iter().map(|f| f.name.as_str())
+                .chain(pf.classes.iter().flat_map(|c| {
+                    std::iter::once(c.name.as_str()).chain(c.methods.iter().map(|m| m.name.as_str()))
+                }))
+        }).collect();
+        let unresolved_text = self.unresolved_in_sources(&references, parsed_files, sources);
+
+        let mut seen = HashSet::new();
+        for reference in references {
+            if !seen.insert((reference.doc_file.clone(), reference.reference.clone())) {
+                continue;
+            }
+            let detail = match reference.kind {
+                DriftKind::FilePath if !self.path_exists(&reference.doc_file, &reference.reference) => "file does not exist",
+                DriftKind::Symbol if !symbols.contains(Self::symbol_name(&reference.reference)) => "no function or type with this name was found",
+                DriftKind::CliFlag if unresolved_text.contains(&reference.reference) => "flag is not defined in the sources",
+                DriftKind::ConfigKey if unresolved_text.contains(&reference.reference) => "config key is not read by the sources",
+                _ => continue,
+            };
+            drift.push(DocDrift {
+                doc_file: reference.doc_file,
+                line: reference.line,
+                kind: reference.kind,
+                reference: reference.reference,
+                detail: detail.to_string(),
+            });
+        }
+
+        drift
+    }
+
+    fn is_project_doc(path: &Path) -> bool {
+        let name = Self::file_name(path);
+        name.starts_with("README") || name.starts_with("CHANGELOG")
+    }
+
+    fn file_name(path: &Path) -> String {
+        path.file_name().map(|n| n.to_string_lossy().to_uppercase()).unwrap_or_default()
+    }
+
+    fn extract_references(&self, doc_file: &str, content: &str) -> Vec<Reference> {
+        let mut references = Vec::new();
+        let mut fence: Option<String> = None;
+
+        for (line_num, line) in content.lines().enumerate() {
+            let mut push = |kind, reference: &str| references.push(Reference {
+                doc_file: doc_file.to_string(),
+                line: line_num + 1,
+                kind,
+                reference: reference.to_string(),
+            });
+
+            if let Some(lang) = line.trim_start().strip_prefix("```") {
+                fence = match fence {
+                    Some(_) => None,
+                    None => Some(lang.trim().to_lowercase()),
+                };
+                continue;
+            }
+
+            match fence.as_deref() {
+                Some("toml") => {
+                    if let Some(captures) = self.config_key.captures(line) {
+                        push(DriftKind::ConfigKey, &captures[1]);
+                    }
+                }
+                Some(_) => {
+                    for captures in self.cli_flag.captures_iter(line) {
+                        push(DriftKind::CliFlag, &captures[1]);
+                    }
+                }
+                None => {
+                    for captures in self.inline_code.captures_iter(line) {
+                        let code = &captures[1];
+                        if let Some(flag) = self.cli_flag.captures(code) {
+                            push(DriftKind::CliFlag, &flag[1]);
+                        } else if Self::looks_like_path(code) {
+                            push(DriftKind::FilePath, code);
+                        } else if code.contains("::") || code.ends_with("()") {
+                            push(DriftKind::Symbol, code);
+                        }
+                    }
+                }
+            }
+        }
+
+        references
+    }
+
+    fn looks_like_path(code: &str) -> bool {
+        code.contains('/')
+            && !code.contains("://")
+            && !code.starts_with('~')
+            && code.rsplit('/').next().is_some_and(|name| name.contains('.') && !name.ends_with('.'))
+    }
+
+    fn symbol_name(reference: &str) -> &str {
+        reference.trim_end_matches("()").rsplit("::").next().unwrap_or(reference)
+    }
+
+    fn path_exists(&self, doc_file: &str, path: &str) -> bool {
+        let relative_to_doc = Path::new(doc_file).parent().map(|dir| dir.join(path));
+        self.root.join(path).exists() || relative_to_doc.is_some_and(|p| p.exists())
+    }
+
+    /// Flags and config keys that appear nowhere in the parsed source code
+    /// (docs and config files excluded), in either kebab-case or snake_case form
+    fn unresolved_in_sources(&self, references: &[Reference], parsed_files: &[ParsedFile], sources: &SourceSet) -> HashSet<String> {
+        let mut unresolved: BTreeMap<String, Vec<String>> = references.iter()
+            .filter(|r| matches!(r.kind, DriftKind::CliFlag | DriftKind::ConfigKey))
+            .map(|r| {
+                let name = r.reference.trim_start_matches('-');
+                (r.reference.clone(), vec![name.to_string(), name.replace('-', "_")])
+            })
+            .collect();
+
+        let is_source = |pf: &&ParsedFile| !matches!(pf.file_info.language.as_deref(),
+            Some("markdown" | "text" | "json" | "yaml" | "toml"));
+
+        for pf in parsed_files.iter().filter(is_source) {
+            if unresolved.is_empty() {
+                break;
+            }
+            let Ok(content) = sources.read(&pf.file_info.path) else { continue };
+            unresolved.retain(|_, forms| !forms.iter().any(|form| content.contains(form.as_str())));
+        }
+
+        unresolved.into_keys().collect()
+    }
+
+    fn check_changelog_version(&self, doc_file: &str, content: &str, sources: &SourceSet) -> Option<DocDrift> {
+        let manifest_version = self.manifest_version(sources)?;
+        let (line, latest) = content.lines().enumerate()
+            .find_map(|(i, line)| self.changelog_version.captures(line).map(|c| (i + 1, c[1].to_string())))?;
+
+        if latest == manifest_version {
+            return None;
+        }
+        Some(DocDrift {
+            doc_file: doc_file.to_string(),
+            line,
+            kind: DriftKind::ChangelogVersion,
+            reference: latest,
+            detail: format!("latest changelog entry does not match manifest version {}", manifest_version),
+        })
+    }
+
+    /// Version declared in Cargo.toml or package.json at the project root
+    fn manifest_version(&self, sources: &SourceSet) -> Option<String> {
+        if let Ok(content) = sources.read(&self.root.join("Cargo.toml")) {
+            let manifest: toml::Value = toml::from_str(&content).ok()?;
+            return manifest.get("package")?.get("version")?.as_str().map(String::from);
+        }
+        let content = sources.read(&self.root.join("package.json")).ok()?;
+        let manifest: serde_json::Value = serde_json::from_str(&content).ok()?;
+        manifest.get("version")?.as_str().map(String::from)
+    }
+}
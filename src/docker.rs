@@ -0,0 +1,211 @@
+use crate::{file_discovery::FileInfo, sources::SourceSet};
+use globset::Glob;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// What a Dockerfile builds from and which discovered source ends up in the image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DockerImage {
+    pub dockerfile: PathBuf,
+    /// External images named in `FROM`; references to earlier build stages are left out
+    pub base_images: Vec<String>,
+    /// Sources of `COPY`/`ADD` from the build context, as written
+    pub copied_paths: Vec<String>,
+    pub exposed_ports: Vec<String>,
+    /// Analyzed files copied into the image, after `.dockerignore`
+    pub shipped_files: Vec<PathBuf>,
+    /// Analyzed files in the build context that are not copied
+    pub unshipped_files: usize,
+}
+
+/// Instructions of one Dockerfile that matter for source correlation
+#[derive(Debug, Default)]
+struct ParsedDockerfile {
+    base_images: Vec<String>,
+    copied_paths: Vec<String>,
+    exposed_ports: Vec<String>,
+}
+
+/// Parses Dockerfiles and correlates `COPY`/`ADD` with the discovered source.
+/// The Dockerfile's directory is taken as the build context.
+pub struct DockerfileAnalyzer;
+
+impl Default for DockerfileAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DockerfileAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn is_dockerfile(file: &FileInfo) -> bool {
+        file.language.as_deref() == Some("dockerfile")
+    }
+
+    pub fn analyze(&self, files: &[FileInfo], sources: &SourceSet) -> Vec<DockerImage> {
+        files.iter()
+            .filter(|file| Self::is_dockerfile(file))
+            .filter_map(|file| {
+                let content = sources.read(&file.path).ok()?;
+                Some(self.correlate(&file.path, Self::parse(&content), files, sources))
+            })
+            .collect()
+    }
+
+    fn parse(content: &str) -> ParsedDockerfile {
+        let mut parsed = ParsedDockerfile::default();
+        let mut stages: Vec<String> = Vec::new();
+
+        for instruction in Self::instructions(content) {
+            let mut words = instruction.split_whitespace();
+            let Some(keyword) = words.next() else { continue };
+            let args: Vec<&str> = words.collect();
+
+            match keyword.to_uppercase().as_str() {
+                "FROM" => {
+                    let mut args = args.iter().filter(|arg| !arg.starts_with("--"));
+                    let Some(image) = args.next() else { continue };
+                    if !stages.iter().any(|stage| stage.eq_ignore_ascii_case(image)) && !parsed.base_images.contains(&image.to_string()) {
+                        parsed.base_images.push(image.to_string());
+                    }
+                    if let (Some(as_keyword), Some(name)) = (args.next(), args.next()) {
+                        if as_keyword.eq_ignore_ascii_case("as") {
+                            stages.push(name.to_string());
+                        }
+                    }
+                }
+                "COPY" | "ADD" => {
+                    // `--from` copies out of another stage or image, not the build context
+                    if args.iter().any(|arg| arg.starts_with("--from")) {
+                        continue;
+                    }
+                    let operands = Self::operands(&instruction[keyword.len()..]);
+                    if let Some((_, copied)) = operands.split_last() {
+                        parsed.copied_paths.extend(copied.iter().cloned());
+                    }
+                }
+                "EXPOSE" => parsed.exposed_ports.extend(args.iter().map(|port| port.to_string())),
+                _ => {}
+            }
+        }
+        parsed
+    }
+
+    /// Instructions with `\` line continuations joined and comments removed
+    fn instructions(content: &str) -> Vec<String> {
+        let mut instructions = Vec::new();
+        let mut current = String::new();
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('#') || (trimmed.is_empty() && current.is_empty()) {
+                continue;
+            }
+            match trimmed.strip_suffix('\\') {
+                Some(continued) => {
+                    current.push_str(continued);
+                    current.push(' ');
+                }
+                None => {
+                    current.push_str(trimmed);
+                    instructions.push(std::mem::take(&mut current));
+                }
+            }
+        }
+        if !current.trim().is_empty() {
+            instructions.push(current);
+        }
+        instructions
+    }
+
+    /// Operands of `COPY`/`ADD` in shell form or JSON array form, without flags
+    fn operands(rest: &str) -> Vec<String> {
+        let rest = rest.trim();
+        let without_flags: Vec<&str> = rest.split_whitespace().skip_while(|word| word.starts_with("--")).collect();
+        let joined = without_flags.join(" ");
+        if joined.starts_with('[') {
+            if let Ok(values) = serde_json::from_str::<Vec<String>>(&joined) {
+                return values;
+            }
+        }
+        without_flags.iter().map(|word| word.to_string()).collect()
+    }
+
+    fn correlate(&self, dockerfile: &Path, parsed: ParsedDockerfile, files: &[FileInfo], sources: &SourceSet) -> DockerImage {
+        let context = dockerfile.parent().unwrap_or(Path::new(""));
+        let dockerignore = Self::load_dockerignore(context, sources);
+        let matchers: Vec<CopySource> = parsed.copied_paths.iter()
+            .filter(|path| !path.contains("://"))
+            .map(|path| CopySource::new(path))
+            .collect();
+
+        let mut shipped_files = Vec::new();
+        let mut unshipped_files = 0;
+        for file in files {
+            let Ok(relative) = file.path.strip_prefix(context) else { continue };
+            if file.path == dockerfile {
+                continue;
+            }
+            let ignored = dockerignore.as_ref()
+                .is_some_and(|ignore| ignore.matched_path_or_any_parents(&file.path, false).is_ignore());
+            if !ignored && matchers.iter().any(|source| source.matches(relative)) {
+                shipped_files.push(file.path.clone());
+            } else {
+                unshipped_files += 1;
+            }
+        }
+
+        DockerImage {
+            dockerfile: dockerfile.to_path_buf(),
+            base_images: parsed.base_images,
+            copied_paths: parsed.copied_paths,
+            exposed_ports: parsed.exposed_ports,
+            shipped_files,
+            unshipped_files,
+        }
+    }
+
+    /// `.dockerignore` patterns, read with gitignore semantics
+    fn load_dockerignore(context: &Path, sources: &SourceSet) -> Option<Gitignore> {
+        let content = sources.read(&context.join(".dockerignore")).ok()?;
+        let mut builder = GitignoreBuilder::new(context);
+        for line in content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            builder.add_line(None, line).ok()?;
+        }
+        builder.build().ok()
+    }
+}
+
+/// One `COPY`/`ADD` source: the whole context, a path prefix, or a glob
+enum CopySource {
+    All,
+    Prefix(PathBuf),
+    Glob(globset::GlobMatcher),
+}
+
+impl CopySource {
+    fn new(path: &str) -> Self {
+        let path = path.trim_start_matches("./").trim_start_matches('/').trim_end_matches('/');
+        if path.is_empty() || path == "." {
+            return CopySource::All;
+        }
+        if path.contains(['*', '?', '[']) {
+            if let Ok(glob) = Glob::new(path) {
+                return CopySource::Glob(glob.compile_matcher());
+            }
+        }
+        CopySource::Prefix(PathBuf::from(path))
+    }
+
+    /// A glob copies the matching entries with everything below them
+    fn matches(&self, relative: &Path) -> bool {
+        match self {
+            CopySource::All => true,
+            CopySource::Prefix(prefix) => relative.starts_with(prefix),
+            CopySource::Glob(glob) => relative.ancestors().any(|ancestor| !ancestor.as_os_str().is_empty() && glob.is_match(ancestor)),
+        }
+    }
+}
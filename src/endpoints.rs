@@ -0,0 +1,288 @@
+use crate::{file_discovery::FileInfo, sources::SourceSet};
+use ignore::WalkBuilder;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const HTTP_METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Where an endpoint was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndpointKind {
+    /// HTTP operation; from an OpenAPI/Swagger spec and/or a route declaration
+    Http,
+    /// Field of the GraphQL `Query`, `Mutation` or `Subscription` type
+    GraphQl,
+}
+
+/// A route declaration in code, e.g. `app.get("/users", ...)` or `#[get("/users")]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteLocation {
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// One operation of the project's external API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Endpoint {
+    pub kind: EndpointKind,
+    /// HTTP method, or `QUERY`/`MUTATION`/`SUBSCRIPTION` for GraphQL
+    pub method: String,
+    /// URL path, or field name for GraphQL
+    pub path: String,
+    /// Spec files declaring the endpoint
+    pub declared_in: Vec<PathBuf>,
+    /// Route declarations implementing the endpoint
+    pub implemented_in: Vec<RouteLocation>,
+}
+
+/// API specs found in the project and the endpoints they and the code declare
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EndpointInventory {
+    /// OpenAPI/Swagger and GraphQL schema files
+    pub specs: Vec<PathBuf>,
+    pub endpoints: Vec<Endpoint>,
+}
+
+impl EndpointInventory {
+    /// Spec endpoints no route declaration was matched to
+    pub fn unimplemented(&self) -> impl Iterator<Item = &Endpoint> {
+        self.endpoints.iter().filter(|e| e.kind == EndpointKind::Http && !e.declared_in.is_empty() && e.implemented_in.is_empty())
+    }
+}
+
+/// Route declaration patterns: (languages, regex with `method` and `path` groups)
+struct RoutePattern {
+    languages: &'static [&'static str],
+    regex: Regex,
+}
+
+/// Finds OpenAPI/Swagger specs, GraphQL schemas and Express, Actix, FastAPI and
+/// Flask route declarations. Router prefixes (`app.use("/api", router)`,
+/// `APIRouter(prefix=...)`, `web::scope`) are not resolved.
+pub struct EndpointScanner {
+    root: PathBuf,
+    routes: Vec<RoutePattern>,
+    actix_route: Regex,
+    flask_route: Regex,
+    graphql_type: Regex,
+    graphql_field: Regex,
+    path_parameter: Regex,
+}
+
+impl EndpointScanner {
+    pub fn new(root: &Path) -> Self {
+        let pattern = |languages, regex: &str| RoutePattern { languages, regex: Regex::new(regex).expect("valid route pattern") };
+        Self {
+            root: root.to_path_buf(),
+            routes: vec![
+                // Express / Koa / Fastify: app.get("/users", ...)
+                pattern(&["javascript", "typescript"],
+                    r#"\b(?:app|router|server|api|route[rs]?)\s*\.\s*(?P<method>get|post|put|patch|delete|options|head|all)\s*\(\s*['"`](?P<path>/[^'"`]*)['"`]"#),
+                // Actix / Rocket attribute macros: #[get("/users")]
+                pattern(&["rust"], r#"#\[\s*(?P<method>get|post|put|patch|delete|head|options)\s*\(\s*"(?P<path>/[^"]*)""#),
+                // FastAPI: @app.get("/users")
+                pattern(&["python"],
+                    r#"@\w+\s*\.\s*(?P<method>get|post|put|patch|delete|options|head)\s*\(\s*['"](?P<path>/[^'"]*)['"]"#),
+            ],
+            actix_route: Regex::new(r#"\.route\(\s*"(?P<path>/[^"]*)"\s*,\s*web::(?P<method>get|post|put|patch|delete|head)\(\)"#).expect("valid route pattern"),
+            flask_route: Regex::new(r#"@\w+\s*\.\s*route\(\s*['"](?P<path>/[^'"]*)['"](?:[^)]*methods\s*=\s*\[(?P<methods>[^\]]*)\])?"#).expect("valid route pattern"),
+            graphql_type: Regex::new(r"^\s*(?:extend\s+)?type\s+(Query|Mutation|Subscription)\b").expect("valid graphql pattern"),
+            graphql_field: Regex::new(r"^\s*(\w+)\s*[(:]").expect("valid graphql pattern"),
+            path_parameter: Regex::new(r"\{[^}]*\}|:\w+|<[^>]*>").expect("valid parameter pattern"),
+        }
+    }
+
+    pub fn analyze(&self, files: &[FileInfo], sources: &SourceSet) -> EndpointInventory {
+        let mut endpoints: BTreeMap<(EndpointKind, String, String), Endpoint> = BTreeMap::new();
+        let specs = if sources.is_in_memory() {
+            files.iter().map(|f| f.path.clone()).filter(|path| Self::is_spec_candidate(path)).collect()
+        } else {
+            self.find_specs()
+        };
+
+        let mut found_specs = Vec::new();
+        for spec in specs {
+            let Ok(content) = sources.read(&spec) else { continue };
+            let declared = if Self::is_graphql(&spec) {
+                self.graphql_operations(&content)
+            } else {
+                Self::openapi_operations(&content)
+            };
+            if declared.is_empty() {
+                continue;
+            }
+            for (kind, method, path) in declared {
+                let key = (kind, method.clone(), self.normalize(&path));
+                let endpoint = endpoints.entry(key).or_insert_with(|| Endpoint {
+                    kind,
+                    method,
+                    path,
+                    declared_in: Vec::new(),
+                    implemented_in: Vec::new(),
+                });
+                if !endpoint.declared_in.contains(&spec) {
+                    endpoint.declared_in.push(spec.clone());
+                }
+            }
+            found_specs.push(spec);
+        }
+
+        for file in files {
+            let Some(language) = file.language.as_deref() else { continue };
+            if !matches!(language, "javascript" | "typescript" | "rust" | "python") {
+                continue;
+            }
+            let Ok(content) = sources.read(&file.path) else { continue };
+            for (method, path, line) in self.routes_in(language, &content) {
+                let key = (EndpointKind::Http, method.clone(), self.normalize(&path));
+                endpoints.entry(key)
+                    .or_insert_with(|| Endpoint {
+                        kind: EndpointKind::Http,
+                        method,
+                        path,
+                        declared_in: Vec::new(),
+                        implemented_in: Vec::new(),
+                    })
+                    .implemented_in
+                    .push(RouteLocation { file: file.path.clone(), line });
+            }
+        }
+
+        EndpointInventory {
+            specs: found_specs,
+            endpoints: endpoints.into_values().collect(),
+        }
+    }
+
+    /// `/users/{id}`, `/users/:id` and `/users/<id>` name the same endpoint
+    fn normalize(&self, path: &str) -> String {
+        let path = self.path_parameter.replace_all(path, "{}");
+        let trimmed = path.trim_end_matches('/');
+        if trimmed.is_empty() { "/".to_string() } else { trimmed.to_string() }
+    }
+
+    /// Spec-looking files anywhere under the target, honoring .gitignore
+    fn find_specs(&self) -> Vec<PathBuf> {
+        let mut specs: Vec<PathBuf> = WalkBuilder::new(&self.root)
+            .build()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.into_path())
+            .filter(|path| path.is_file() && !path.components().any(|c| c.as_os_str() == "node_modules"))
+            .filter(|path| Self::is_spec_candidate(path))
+            .collect();
+        specs.sort();
+        specs
+    }
+
+    fn is_graphql(path: &Path) -> bool {
+        matches!(path.extension().and_then(|e| e.to_str()), Some("graphql" | "gql" | "graphqls"))
+    }
+
+    /// GraphQL schemas, and YAML/JSON files named like a spec or starting with an `openapi`/`swagger` key
+    fn is_spec_candidate(path: &Path) -> bool {
+        if Self::is_graphql(path) {
+            return true;
+        }
+        if !matches!(path.extension().and_then(|e| e.to_str()), Some("yaml" | "yml" | "json")) {
+            return false;
+        }
+        let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+        if name.contains("openapi") || name.contains("swagger") {
+            return true;
+        }
+
+        let mut head = [0u8; 1024];
+        let Ok(read) = fs::File::open(path).and_then(|mut file| file.read(&mut head)) else { return false };
+        let head = String::from_utf8_lossy(&head[..read]);
+        head.lines().any(|line| {
+            let line = line.trim_start_matches(['{', ' ', '\t']);
+            ["openapi", "swagger"].iter().any(|key| line.starts_with(&format!("{}:", key)) || line.starts_with(&format!("\"{}\"", key)))
+        })
+    }
+
+    /// Operations under `paths` in an OpenAPI 3 or Swagger 2 document (YAML or JSON)
+    fn openapi_operations(content: &str) -> Vec<(EndpointKind, String, String)> {
+        let Ok(document) = serde_yaml::from_str::<serde_yaml::Value>(content) else { return Vec::new() };
+        if document.get("openapi").is_none() && document.get("swagger").is_none() {
+            return Vec::new();
+        }
+        let Some(paths) = document.get("paths").and_then(|p| p.as_mapping()) else { return Vec::new() };
+
+        paths.iter()
+            .filter_map(|(path, item)| Some((path.as_str()?, item.as_mapping()?)))
+            .flat_map(|(path, item)| {
+                item.keys()
+                    .filter_map(|method| method.as_str())
+                    .filter(|method| HTTP_METHODS.contains(&method.to_lowercase().as_str()))
+                    .map(move |method| (EndpointKind::Http, method.to_uppercase(), path.to_string()))
+            })
+            .collect()
+    }
+
+    /// Fields of the root operation types in a GraphQL schema
+    fn graphql_operations(&self, content: &str) -> Vec<(EndpointKind, String, String)> {
+        let mut operations = Vec::new();
+        let mut current: Option<String> = None;
+        let mut depth = 0usize;
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            if current.is_none() {
+                if let Some(captures) = self.graphql_type.captures(line) {
+                    current = Some(captures[1].to_uppercase());
+                    depth = 0;
+                }
+            } else if depth == 1 {
+                if let Some(captures) = self.graphql_field.captures(line) {
+                    operations.push((EndpointKind::GraphQl, current.clone().unwrap_or_default(), captures[1].to_string()));
+                }
+            }
+
+            if current.is_some() {
+                depth += line.matches(['{', '(']).count();
+                depth = depth.saturating_sub(line.matches(['}', ')']).count());
+                if depth == 0 && line.contains('}') {
+                    current = None;
+                }
+            }
+        }
+        operations
+    }
+
+    /// (method, path, line) of every route declaration in a source file
+    fn routes_in(&self, language: &str, content: &str) -> Vec<(String, String, usize)> {
+        let mut routes = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            if ["//", "/*", "*"].iter().any(|comment| trimmed.starts_with(comment)) || (trimmed.starts_with('#') && !trimmed.starts_with("#[")) {
+                continue;
+            }
+            for pattern in self.routes.iter().filter(|p| p.languages.contains(&language)) {
+                for captures in pattern.regex.captures_iter(line) {
+                    routes.push((captures["method"].to_uppercase(), captures["path"].to_string(), index + 1));
+                }
+            }
+            if language == "rust" {
+                for captures in self.actix_route.captures_iter(line) {
+                    routes.push((captures["method"].to_uppercase(), captures["path"].to_string(), index + 1));
+                }
+            }
+            if language == "python" {
+                if let Some(captures) = self.flask_route.captures(line) {
+                    let methods: Vec<String> = captures.name("methods")
+                        .map(|m| m.as_str().split(',').map(|s| s.trim().trim_matches(['\'', '"']).to_uppercase()).filter(|s| !s.is_empty()).collect())
+                        .unwrap_or_else(|| vec!["GET".to_string()]);
+                    for method in methods {
+                        routes.push((method, captures["path"].to_string(), index + 1));
+                    }
+                }
+            }
+        }
+        routes
+    }
+}
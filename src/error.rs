@@ -0,0 +1,107 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Everything the library can fail with, so embedding applications can match on
+/// the cause; the messages are the ones the CLI prints
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Invalid settings: config files, overrides, profiles, globs, regexes and
+    /// cron expressions
+    #[error("{0}")]
+    Config(String),
+    /// The target directory, a scope or a listed file can't be analyzed
+    #[error("{0}")]
+    Discovery(String),
+    /// A source file, saved report or index couldn't be read or parsed
+    #[error("{}: {message}", path.display())]
+    Parse { path: PathBuf, message: String },
+    /// An LLM request failed; `status` is the HTTP status when the provider answered
+    #[error("{provider} API error{}: {message}", status_suffix(*.status))]
+    Llm { provider: String, status: Option<u16>, message: String },
+    /// A report, export or checkpoint couldn't be written
+    #[error("Writing {}: {source}", path.display())]
+    Report { path: PathBuf, source: io::Error },
+    /// A request to a package registry, OSV, an issue tracker, a webhook or a
+    /// metrics endpoint failed
+    #[error("{service} request failed{}: {message}", status_suffix(*.status))]
+    Service { service: String, status: Option<u16>, message: String },
+    /// A git command failed or the target isn't in a repository
+    #[error("{0}")]
+    Git(String),
+    /// A malformed daemon or language server message, or a request the daemon
+    /// failed to serve
+    #[error("{0}")]
+    Protocol(String),
+    /// The run was stopped through its `CancellationToken`
+    #[error("Analysis cancelled")]
+    Cancelled,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "llm")]
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+fn status_suffix(status: Option<u16>) -> String {
+    status.map(|status| format!(" ({})", status)).unwrap_or_default()
+}
+
+impl Error {
+    pub fn parse(path: &Path, message: impl ToString) -> Self {
+        Error::Parse { path: path.to_path_buf(), message: message.to_string() }
+    }
+
+    pub fn report(path: &Path, source: io::Error) -> Self {
+        Error::Report { path: path.to_path_buf(), source }
+    }
+
+    #[cfg(feature = "llm")]
+    /// A failed HTTP request to `service`, with the status when the server answered
+    pub fn service(service: impl Into<String>, error: reqwest::Error) -> Self {
+        Error::Service {
+            service: service.into(),
+            status: error.status().map(|status| status.as_u16()),
+            message: error.without_url().to_string(),
+        }
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(error: toml::de::Error) -> Self {
+        Error::Config(error.to_string())
+    }
+}
+
+impl From<toml::ser::Error> for Error {
+    fn from(error: toml::ser::Error) -> Self {
+        Error::Config(error.to_string())
+    }
+}
+
+impl From<regex::Error> for Error {
+    fn from(error: regex::Error) -> Self {
+        Error::Config(error.to_string())
+    }
+}
+
+impl From<globset::Error> for Error {
+    fn from(error: globset::Error) -> Self {
+        Error::Config(error.to_string())
+    }
+}
+
+impl From<ignore::Error> for Error {
+    fn from(error: ignore::Error) -> Self {
+        Error::Discovery(error.to_string())
+    }
+}
+
+impl From<rayon::ThreadPoolBuildError> for Error {
+    fn from(error: rayon::ThreadPoolBuildError) -> Self {
+        Error::Config(format!("Cannot start the worker pool: {}", error))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
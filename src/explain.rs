@@ -0,0 +1,75 @@
+use crate::simple_parser::ParsedFile;
+use std::path::{Path, PathBuf};
+
+/// Dependencies described in the prompt
+const MAX_DEPENDENCIES: usize = 20;
+/// Functions and classes listed per dependency
+const MAX_SYMBOLS: usize = 25;
+
+const SYSTEM_PROMPT: &str = "You are a senior engineer walking a colleague through one source file in detail. \
+You are given the file with line numbers and the signatures of the files it imports. \
+Respond in Markdown with these sections: \
+## Purpose (what the file is for and where it sits in the project), \
+## Walkthrough (one ### subsection per function, class or method in file order: what it does, its inputs and outputs, and anything non-obvious), \
+## Risks (bugs, edge cases, error handling gaps and security concerns, each citing a line), \
+## Suggested Tests (concrete test cases with the inputs and expected behavior to check). \
+Refer to code by name and line number. Describe dependencies only from their listed signatures; do not guess at their implementation.";
+
+/// Builds the `explain` request for one file: its full content plus the
+/// signatures of the files it imports directly
+#[derive(Default)]
+pub struct FileExplainer;
+
+impl FileExplainer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn system_prompt(&self) -> &'static str {
+        SYSTEM_PROMPT
+    }
+
+    /// `content` goes into the prompt numbered line by line; `dependencies` are
+    /// the imported files with their paths relative to the target directory
+    pub fn build_prompt(&self, file: &ParsedFile, path: &Path, content: &str, dependencies: &[(PathBuf, &ParsedFile)]) -> String {
+        let language = file.file_info.language.as_deref().unwrap_or("unknown");
+        let mut prompt = format!("Explain this file.\n\nFile: {} ({}, {} lines)\n\n```{}\n",
+            path.display(), language, content.lines().count(), language.to_lowercase());
+        for (number, line) in content.lines().enumerate() {
+            prompt.push_str(&format!("{:>5} | {}\n", number + 1, line));
+        }
+        prompt.push_str("```\n");
+
+        if dependencies.is_empty() {
+            prompt.push_str("\nThe file imports no other files of the project.\n");
+            return prompt;
+        }
+        prompt.push_str("\nFiles it imports:\n");
+        for (path, dependency) in dependencies.iter().take(MAX_DEPENDENCIES) {
+            prompt.push_str(&format!("\n{}\n", path.display()));
+            for class in dependency.classes.iter().take(MAX_SYMBOLS) {
+                let methods: Vec<&str> = class.methods.iter().take(MAX_SYMBOLS).map(|m| m.name.as_str()).collect();
+                match class.summary() {
+                    Some(summary) => prompt.push_str(&format!("  class {} {{ {} }} — {}\n", class.name, methods.join(", "), summary)),
+                    None => prompt.push_str(&format!("  class {} {{ {} }}\n", class.name, methods.join(", "))),
+                }
+            }
+            for function in dependency.functions.iter().take(MAX_SYMBOLS) {
+                match function.summary() {
+                    Some(summary) => prompt.push_str(&format!("  {} — {}\n", function.signature(), summary)),
+                    None => prompt.push_str(&format!("  {}\n", function.signature())),
+                }
+            }
+        }
+        if dependencies.len() > MAX_DEPENDENCIES {
+            prompt.push_str(&format!("\n…and {} more\n", dependencies.len() - MAX_DEPENDENCIES));
+        }
+        prompt
+    }
+
+    /// The explanation as a standalone Markdown document for `--output`
+    pub fn to_markdown(&self, path: &Path, explanation: &str, provider: &str, model: &str) -> String {
+        format!("# {}\n\n_Explained by {} ({}) with project-examer {}_\n\n{}\n",
+            path.display(), provider, model, env!("CARGO_PKG_VERSION"), explanation.trim())
+    }
+}
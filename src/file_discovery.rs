@@ -1,7 +1,11 @@
 use crate::config::Config;
+use crate::generated::GeneratedCodeDetector;
+use crate::gitattributes::LinguistAttributes;
+use crate::progress::CancellationToken;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use ignore::WalkBuilder;
 use serde::{Deserialize, Serialize};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::fs;
 use regex;
 
@@ -13,31 +17,142 @@ pub struct FileInfo {
     pub language: Option<String>,
 }
 
+/// Why discovery left a file out of the analysis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    IgnorePattern,
+    MaxFileSize,
+    Extension,
+    Vendored,
+    Generated,
+}
+
+impl SkipReason {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SkipReason::IgnorePattern => "ignore_pattern",
+            SkipReason::MaxFileSize => "max_file_size",
+            SkipReason::Extension => "extension",
+            SkipReason::Vendored => "vendored",
+            SkipReason::Generated => "generated",
+        }
+    }
+}
+
+/// A file found by the directory walk but excluded from the analysis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+    /// The setting or pattern that excluded the file, e.g. `ignore_patterns: *.lock`
+    #[serde(default)]
+    pub rule: String,
+}
+
+impl SkippedFile {
+    fn new(path: &Path, reason: SkipReason, rule: impl Into<String>) -> Self {
+        Self { path: path.to_path_buf(), reason, rule: rule.into() }
+    }
+}
+
+/// Files selected for analysis, plus those the walk found but excluded
+#[derive(Debug, Default)]
+pub struct Discovery {
+    pub files: Vec<FileInfo>,
+    pub skipped: Vec<SkippedFile>,
+}
+
 pub struct FileDiscovery {
     config: Config,
+    /// Rules from the target directory's `.gitattributes`; nested files are
+    /// picked up during the directory walk
+    attributes: LinguistAttributes,
+    /// `discovery.include_filenames`, matched against file names
+    include_filenames: GlobSet,
+    /// Present when `discovery.skip_generated` is set
+    generated: Option<GeneratedCodeDetector>,
+    /// `include_filenames` patterns that didn't compile, reported by each walk
+    invalid_patterns: Vec<String>,
+    /// Print nothing to stdout
+    quiet: bool,
 }
 
 impl FileDiscovery {
     pub fn new(config: Config) -> Self {
-        Self { config }
+        let attributes = if config.discovery.respect_gitattributes {
+            LinguistAttributes::load(&config.target_directory)
+        } else {
+            LinguistAttributes::default()
+        };
+        let (include_filenames, invalid_patterns) = Self::build_filename_globs(&config.discovery.include_filenames);
+        let generated = config.discovery.skip_generated.then(GeneratedCodeDetector::new);
+        Self { config, attributes, include_filenames, generated, invalid_patterns, quiet: false }
+    }
+
+    /// Keep discovery's notices off stdout, for embedders that own it
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.quiet = quiet;
+    }
+
+    /// The compiled patterns, and a description of each one that didn't compile
+    fn build_filename_globs(patterns: &[String]) -> (GlobSet, Vec<String>) {
+        let mut builder = GlobSetBuilder::new();
+        let mut invalid = Vec::new();
+        for pattern in patterns {
+            match GlobBuilder::new(pattern).case_insensitive(true).build() {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(e) => invalid.push(format!("'{}': {}", pattern, e)),
+            }
+        }
+        (builder.build().unwrap_or_else(|_| GlobSet::empty()), invalid)
     }
 
     pub fn discover_files(&self) -> crate::Result<Vec<FileInfo>> {
-        let mut files = Vec::new();
+        Ok(self.discover()?.files)
+    }
+
+    /// Walk the target directory, recording why each excluded file was left out
+    pub fn discover(&self) -> crate::Result<Discovery> {
+        self.discover_with(&CancellationToken::default())
+    }
+
+    /// `discover`, stopping with `Error::Cancelled` at the next file once `cancel` is cancelled
+    pub fn discover_with(&self, cancel: &CancellationToken) -> crate::Result<Discovery> {
+        let mut discovery = Discovery::default();
+        if !self.quiet {
+            for invalid in &self.invalid_patterns {
+                println!("  ⚠️  Ignoring discovery.include_filenames pattern {}", invalid);
+            }
+        }
         
-        let mut walker_builder = WalkBuilder::new(&self.config.target_directory);
+        let roots = self.scope_roots()?;
+        let mut walker_builder = WalkBuilder::new(&roots[0]);
+        for root in &roots[1..] {
+            walker_builder.add(root);
+        }
         walker_builder
             .standard_filters(true)  // This enables .gitignore support
             .hidden(false)           // Show hidden files except those in .gitignore
             .git_ignore(true)        // Explicitly enable .gitignore parsing
             .git_global(true)        // Respect global git ignore
             .git_exclude(true);      // Respect .git/info/exclude
+        if self.config.report.deterministic {
+            walker_builder.sort_by_file_name(|a, b| a.cmp(b));
+        }
             
         // The ignore patterns will be handled in the file processing logic
         
         let walker = walker_builder.build();
+        let root_attributes = self.config.target_directory.join(".gitattributes");
+        let mut nested_attributes = Vec::new();
 
         for result in walker {
+            if cancel.is_cancelled() {
+                return Err(crate::Error::Cancelled);
+            }
             let entry = result?;
             let path = entry.path();
             
@@ -45,30 +160,196 @@ impl FileDiscovery {
                 continue;
             }
 
+            if path.file_name().is_some_and(|name| name == ".gitattributes") && path != root_attributes {
+                nested_attributes.push(path.to_path_buf());
+            }
+
             // Check if file matches any ignore patterns
-            if self.should_ignore_file(path) {
-                continue;
+            let processed = match self.matching_ignore_pattern(path) {
+                Some(pattern) => Err(SkippedFile::new(path, SkipReason::IgnorePattern, format!("ignore_patterns: {}", pattern))),
+                None => self.process_file(path)?,
+            };
+            match processed {
+                Ok(file_info) => discovery.files.push(file_info),
+                Err(skipped) => discovery.skipped.push(skipped),
             }
+        }
 
-            if let Some(file_info) = self.process_file(path)? {
-                files.push(file_info);
+        if self.config.discovery.respect_gitattributes {
+            self.apply_attributes(&mut discovery, nested_attributes);
+        }
+
+        Ok(discovery)
+    }
+
+    /// Drop vendored/generated files and apply language overrides using the root
+    /// `.gitattributes` plus any found below it, shallowest first so deeper rules win
+    fn apply_attributes(&self, discovery: &mut Discovery, mut nested: Vec<PathBuf>) {
+        nested.sort_by_key(|path| path.components().count());
+        let mut attributes = LinguistAttributes::load(&self.config.target_directory);
+        for path in &nested {
+            attributes.add_file(path);
+        }
+        if attributes.is_empty() {
+            return;
+        }
+
+        let before = discovery.skipped.len();
+        let skipped = &mut discovery.skipped;
+        discovery.files.retain_mut(|file| {
+            let overrides = attributes.lookup(&file.path);
+            if overrides.language.is_some() {
+                file.language = overrides.language.clone();
+            }
+            if overrides.is_excluded() {
+                let (reason, rule) = if overrides.vendored {
+                    (SkipReason::Vendored, ".gitattributes: linguist-vendored")
+                } else {
+                    (SkipReason::Generated, ".gitattributes: linguist-generated")
+                };
+                skipped.push(SkippedFile::new(&file.path, reason, rule));
             }
+            !overrides.is_excluded()
+        });
+        if discovery.skipped.len() > before && !self.quiet {
+            println!("  Skipped {} vendored/generated file(s) per .gitattributes", discovery.skipped.len() - before);
         }
+    }
 
+    /// Describe an explicit list of files instead of walking the target directory.
+    /// Relative paths are taken relative to the target directory; missing, ignored
+    /// or filtered-out files are skipped.
+    pub fn files_from_list(&self, paths: &[PathBuf]) -> crate::Result<Vec<FileInfo>> {
+        let mut files = Vec::new();
+        for path in paths {
+            let target = &self.config.target_directory;
+            let path = if path.is_relative() && !path.starts_with(target) { target.join(path) } else { path.clone() };
+            if !path.is_file() {
+                if !self.quiet {
+                    println!("  ⚠️  Skipping {} (not a file)", path.display());
+                }
+                continue;
+            }
+            if self.should_ignore_file(&path) || self.attributes.lookup(&path).is_excluded() {
+                continue;
+            }
+            if let Ok(file_info) = self.process_file(&path)? {
+                files.push(file_info);
+            }
+        }
         Ok(files)
     }
 
+    /// Find the file an import refers to by probing the filesystem, without a
+    /// directory walk: relative imports resolve against the importing file's
+    /// directory; module paths (`crate::a::b`, `pkg.mod`, `lib/x`) are tried
+    /// against the importer's directory, `src/` and the target directory.
+    pub fn resolve_import(&self, importer: &Path, module: &str) -> Option<PathBuf> {
+        let base = importer.parent()?;
+        let extensions = &self.config.file_extensions;
+        let probe = |stem: &Path| -> Option<PathBuf> {
+            extensions.iter()
+                .map(|ext| stem.with_extension(ext))
+                .chain(extensions.iter().map(|ext| stem.join(format!("index.{}", ext))))
+                .chain([stem.join("mod.rs"), stem.join("__init__.py")])
+                .chain(std::iter::once(stem.to_path_buf()))
+                .find(|candidate| candidate.is_file() && candidate != importer)
+        };
+
+        if module.starts_with("./") || module.starts_with("../") {
+            // Resolve `..` lexically so the result matches discovered paths
+            let mut stem = base.to_path_buf();
+            for component in Path::new(module).components() {
+                match component {
+                    Component::CurDir => {}
+                    Component::ParentDir => {
+                        stem.pop();
+                    }
+                    other => stem.push(other),
+                }
+            }
+            return probe(&stem);
+        }
+
+        let segments: Vec<&str> = module.split(['/', ':', '.'])
+            .filter(|segment| !segment.is_empty() && !matches!(*segment, "crate" | "self" | "super" | "std" | "core" | "alloc"))
+            .collect();
+        let target = &self.config.target_directory;
+        let roots = [base.to_path_buf(), target.join("src"), target.clone()];
+
+        // Longest module path first: `a::b::Item` tries a/b/Item, a/b, a, then drops
+        // the leading segment (often a crate or package name) and tries b/Item, b, ...
+        (0..segments.len()).find_map(|start| {
+            (start + 1..=segments.len()).rev().find_map(|end| {
+                let relative: PathBuf = segments[start..end].iter().collect();
+                roots.iter().find_map(|root| probe(&root.join(&relative)))
+            })
+        })
+    }
+
+    /// Directories to walk: the target directory, or each `analysis.scope`
+    /// entry resolved as a path or package name
+    pub fn scope_roots(&self) -> crate::Result<Vec<PathBuf>> {
+        let target = &self.config.target_directory;
+        if self.config.analysis.scope.is_empty() {
+            return Ok(vec![target.clone()]);
+        }
+
+        self.config.analysis.scope.iter()
+            .map(|scope| {
+                let path = target.join(scope);
+                if path.exists() {
+                    return Ok(path);
+                }
+                self.find_package(scope)?
+                    .ok_or_else(|| crate::Error::Discovery(format!("Scope '{}' matches no path or package under {}", scope, target.display())))
+            })
+            .collect()
+    }
+
+    /// Directory of the Cargo.toml, package.json or pyproject.toml declaring `name`
+    fn find_package(&self, name: &str) -> crate::Result<Option<PathBuf>> {
+        for entry in WalkBuilder::new(&self.config.target_directory).build() {
+            let entry = entry?;
+            let path = entry.path();
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if !matches!(file_name, "Cargo.toml" | "package.json" | "pyproject.toml") {
+                continue;
+            }
+
+            let Ok(content) = fs::read_to_string(path) else { continue };
+            let package_name = if file_name == "package.json" {
+                serde_json::from_str::<serde_json::Value>(&content).ok()
+                    .and_then(|v| v.get("name")?.as_str().map(String::from))
+            } else {
+                toml::from_str::<toml::Value>(&content).ok().and_then(|v| {
+                    let table = v.get("package").or_else(|| v.get("project"))?;
+                    table.get("name")?.as_str().map(String::from)
+                })
+            };
+
+            if package_name.as_deref() == Some(name) {
+                return Ok(path.parent().map(Path::to_path_buf));
+            }
+        }
+        Ok(None)
+    }
+
     fn should_ignore_file(&self, path: &Path) -> bool {
+        self.matching_ignore_pattern(path).is_some()
+    }
+
+    /// The first `ignore_patterns` entry that excludes the path
+    fn matching_ignore_pattern(&self, path: &Path) -> Option<&str> {
         let path_str = path.to_string_lossy();
         
         for pattern in &self.config.ignore_patterns {
             // Handle simple glob patterns (*.ext)
-            if pattern.starts_with("*.") {
+            if let Some(ext) = pattern.strip_prefix("*.") {
                 if let Some(filename) = path.file_name() {
                     let filename_str = filename.to_string_lossy();
-                    let ext = &pattern[2..]; // Remove "*."
                     if filename_str.ends_with(&format!(".{}", ext)) {
-                        return true;
+                        return Some(pattern);
                     }
                 }
             } else if pattern.contains('*') {
@@ -76,57 +357,110 @@ impl FileDiscovery {
                 let regex_pattern = pattern.replace('*', ".*");
                 if let Ok(re) = regex::Regex::new(&regex_pattern) {
                     if re.is_match(&path_str) {
-                        return true;
+                        return Some(pattern);
                     }
                     if let Some(filename) = path.file_name() {
                         if re.is_match(&filename.to_string_lossy()) {
-                            return true;
+                            return Some(pattern);
                         }
                     }
                 }
             } else {
                 // Handle exact matches and directory names
                 if path_str.contains(pattern) {
-                    return true;
+                    return Some(pattern);
                 }
                 // Check if any component of the path matches
                 for component in path.components() {
                     if component.as_os_str().to_string_lossy() == *pattern {
-                        return true;
+                        return Some(pattern);
                     }
                 }
             }
         }
         
-        false
+        None
     }
 
-    fn process_file(&self, path: &Path) -> crate::Result<Option<FileInfo>> {
+    /// Describe a file, or say why size or extension rules exclude it
+    fn process_file(&self, path: &Path) -> crate::Result<Result<FileInfo, SkippedFile>> {
         let metadata = fs::metadata(path)?;
         let size = metadata.len();
 
         if size > self.config.max_file_size as u64 {
-            return Ok(None);
+            return Ok(Err(SkippedFile::new(path, SkipReason::MaxFileSize,
+                format!("max_file_size: {} bytes", self.config.max_file_size))));
         }
 
-        let extension = path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|s| s.to_lowercase());
+        let mut file_info = self.describe_file(path, size);
+        if file_info.extension.is_none() && file_info.language.is_none() {
+            file_info.language = Self::detect_shebang(path);
+        }
 
-        if let Some(ref ext) = extension {
-            if !self.config.file_extensions.contains(ext) {
-                return Ok(None);
-            }
+        if !self.is_included(&file_info) {
+            let rule = match file_info.extension {
+                Some(ref ext) => format!("file_extensions: .{} not listed", ext),
+                None => "file_extensions: no extension".to_string(),
+            };
+            return Ok(Err(SkippedFile::new(path, SkipReason::Extension, rule)));
+        }
+
+        if let Some(kind) = self.generated.as_ref().and_then(|detector| detector.detect(path)) {
+            return Ok(Err(SkippedFile::new(path, SkipReason::Generated, format!("skip_generated: {}", kind))));
+        }
+
+        Ok(Ok(file_info))
+    }
+
+    /// Inclusion policy: a listed extension, a `discovery.include_filenames`
+    /// match, or a language in `discovery.include_languages`
+    fn is_included(&self, file_info: &FileInfo) -> bool {
+        file_info.extension.as_ref().is_some_and(|ext| self.config.file_extensions.contains(ext))
+            || file_info.path.file_name().is_some_and(|name| self.include_filenames.is_match(name))
+            || file_info.language.as_ref().is_some_and(|language| self.config.discovery.include_languages.contains(language))
+    }
+
+    /// Interpreter named on a `#!` first line, for extension-less scripts
+    fn detect_shebang(path: &Path) -> Option<String> {
+        use std::io::Read;
+        let mut head = [0u8; 128];
+        let read = fs::File::open(path).and_then(|mut file| file.read(&mut head)).ok()?;
+        let first_line = std::str::from_utf8(&head[..read]).ok()?.lines().next()?;
+        let command = first_line.strip_prefix("#!")?;
+
+        // `#!/usr/bin/env python3` names the interpreter after env
+        let mut words = command.split_whitespace();
+        let mut interpreter = Path::new(words.next()?).file_name()?.to_str()?;
+        if interpreter == "env" {
+            interpreter = words.find(|word| !word.starts_with('-'))?;
+        }
+        let interpreter = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+        match interpreter {
+            "sh" | "bash" | "zsh" | "dash" | "ksh" => Some("bash".to_string()),
+            "python" => Some("python".to_string()),
+            "node" | "deno" | "bun" => Some("javascript".to_string()),
+            "ruby" => Some("ruby".to_string()),
+            "perl" => Some("perl".to_string()),
+            "php" => Some("php".to_string()),
+            "lua" => Some("lua".to_string()),
+            _ => None,
         }
+    }
 
-        let language = self.detect_language(path, &extension);
+    /// Build a `FileInfo` with extension and detected language, without touching the filesystem
+    pub fn describe_file(&self, path: &Path, size: u64) -> FileInfo {
+        let extension = path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|s| s.to_lowercase());
+        let language = self.attributes.lookup(path).language
+            .or_else(|| self.detect_language(path, &extension));
 
-        Ok(Some(FileInfo {
+        FileInfo {
             path: path.to_path_buf(),
             size,
             extension,
             language,
-        }))
+        }
     }
 
     fn detect_language(&self, path: &Path, extension: &Option<String>) -> Option<String> {
@@ -136,12 +470,17 @@ impl FileDiscovery {
                 let filename_lower = filename.to_string_lossy().to_lowercase();
                 match filename_lower.as_str() {
                     "readme" | "license" | "changelog" | "contributing" | "authors" | 
-                    "install" | "usage" | "todo" | "news" | "history" | "acknowledgments" |
-                    "makefile" | "dockerfile" => return Some("text".to_string()),
+                    "install" | "usage" | "todo" | "news" | "history" | "acknowledgments" => return Some("text".to_string()),
+                    "makefile" | "gnumakefile" => return Some("makefile".to_string()),
+                    "dockerfile" => return Some("dockerfile".to_string()),
                     _ => {}
                 }
             }
         }
+        // `Dockerfile.prod`, `Dockerfile.dev`
+        if path.file_name().is_some_and(|name| name.to_string_lossy().to_lowercase().starts_with("dockerfile.")) {
+            return Some("dockerfile".to_string());
+        }
         
         match extension.as_deref() {
             Some("rs") => Some("rust".to_string()),
@@ -183,6 +522,7 @@ impl FileDiscovery {
             Some("json") => Some("json".to_string()),
             Some("yaml") | Some("yml") => Some("yaml".to_string()),
             Some("toml") => Some("toml".to_string()),
+            Some("tf") | Some("tfvars") | Some("hcl") => Some("terraform".to_string()),
             Some("md") => Some("markdown".to_string()),
             Some("txt") => Some("text".to_string()),
             Some("tex") => Some("latex".to_string()),
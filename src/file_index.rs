@@ -0,0 +1,222 @@
+use crate::{
+    analyzer::ProjectAnalysis,
+    maintainability::{FileMaintainability, MaintainabilityRating},
+    rules::Severity,
+};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A file and the line that links it to another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileLink {
+    pub path: PathBuf,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionMetrics {
+    pub name: String,
+    pub line: usize,
+    pub end_line: usize,
+    pub parameters: usize,
+    pub max_nesting: usize,
+}
+
+/// A finding located in a file, from any of the local analyses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileFinding {
+    pub line: usize,
+    pub severity: Severity,
+    /// The analysis that reported it, e.g. `rule:no-unwrap` or `code-smell`
+    pub source: String,
+    pub message: String,
+}
+
+/// Everything the index knows about one file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub language: Option<String>,
+    pub lines_of_code: usize,
+    pub cyclomatic_complexity: usize,
+    pub maintainability_index: Option<f64>,
+    pub maintainability_rating: Option<MaintainabilityRating>,
+    pub functions: Vec<FunctionMetrics>,
+    /// Files this file imports
+    pub imports: Vec<FileLink>,
+    /// Files importing this file
+    pub importers: Vec<FileLink>,
+    pub findings: Vec<FileFinding>,
+}
+
+impl FileEntry {
+    /// The function whose body contains the (1-based) line
+    pub fn function_at(&self, line: usize) -> Option<&FunctionMetrics> {
+        self.functions.iter()
+            .filter(|function| function.line <= line && line <= function.end_line.max(function.line))
+            .max_by_key(|function| function.line)
+    }
+}
+
+/// What `query` looks up for a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum QueryKind {
+    /// Size, complexity, maintainability and functions
+    Metrics,
+    /// Files importing the file
+    Importers,
+    /// Files the file imports
+    Dependencies,
+    /// Rule findings, smells, violations and other findings
+    Findings,
+}
+
+/// Per-file metrics, dependency links and findings from an analysis, keyed by absolute
+/// path and written as `file_index.json` so editor integrations (`lsp`) can answer
+/// lookups without re-running the analysis
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileIndex {
+    pub version: String,
+    pub root: PathBuf,
+    pub files: BTreeMap<PathBuf, FileEntry>,
+}
+
+impl FileIndex {
+    pub fn build(analysis: &ProjectAnalysis, maintainability: &[FileMaintainability], root: &Path) -> Self {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let absolute = |path: &Path| -> PathBuf {
+            let path = if path.is_absolute() { path.to_path_buf() } else { root.join(path) };
+            path.canonicalize().unwrap_or(path)
+        };
+        let mut files: BTreeMap<PathBuf, FileEntry> = BTreeMap::new();
+
+        for pf in &analysis.parsed_files {
+            files.insert(absolute(&pf.file_info.path), FileEntry {
+                language: pf.file_info.language.clone(),
+                lines_of_code: pf.lines_of_code,
+                cyclomatic_complexity: pf.cyclomatic_complexity,
+                functions: pf.functions.iter().map(|function| FunctionMetrics {
+                    name: function.name.clone(),
+                    line: function.line_number,
+                    end_line: function.end_line,
+                    parameters: function.parameters.len(),
+                    max_nesting: function.max_nesting,
+                }).collect(),
+                ..FileEntry::default()
+            });
+        }
+        for file in maintainability {
+            if let Some(entry) = files.get_mut(&absolute(&file.path)) {
+                entry.maintainability_index = Some(file.index);
+                entry.maintainability_rating = Some(file.rating);
+            }
+        }
+        for dependency in &analysis.file_dependencies {
+            let (from, to) = (absolute(&dependency.from), absolute(&dependency.to));
+            files.entry(from.clone()).or_default().imports.push(FileLink { path: to.clone(), line: dependency.line_number });
+            files.entry(to).or_default().importers.push(FileLink { path: from, line: dependency.line_number });
+        }
+
+        let mut findings: Vec<(PathBuf, FileFinding)> = Vec::new();
+        let mut add = |file: &str, line: usize, severity: Severity, source: String, message: String| {
+            findings.push((absolute(Path::new(file)), FileFinding { line, severity, source, message }));
+        };
+        for finding in &analysis.rule_findings {
+            add(&finding.file, finding.line, finding.severity, format!("rule:{}", finding.rule_id), finding.description.clone());
+        }
+        for violation in &analysis.architecture_violations {
+            add(&violation.from_file, violation.line, Severity::Error, "architecture".to_string(),
+                format!("Layer {} may not depend on {} ({})", violation.from_layer, violation.to_layer, violation.to_file));
+        }
+        for smell in &analysis.code_smells {
+            add(&smell.file, smell.line, Severity::Warning, "code-smell".to_string(),
+                format!("{} `{}`: {} {} (limit {})", smell.kind.name(), smell.symbol, smell.value, smell.kind.unit(), smell.threshold));
+        }
+        for signal in &analysis.performance_signals {
+            add(&signal.file, signal.line, Severity::Info, "performance".to_string(),
+                format!("{} `{}`: {}", signal.kind.name(), signal.symbol, signal.detail));
+        }
+        for item in &analysis.debt_markers {
+            add(&item.file, item.line, Severity::Info, "debt".to_string(), format!("{}: {}", item.kind, item.text));
+        }
+        for component in &analysis.dependency_analysis.cyclic_components {
+            for file in component {
+                add(&file.to_string_lossy(), 1, Severity::Warning, "dependency-cycle".to_string(),
+                    format!("Part of a dependency cycle of {} files", component.len()));
+            }
+        }
+        for (path, finding) in findings {
+            files.entry(path).or_default().findings.push(finding);
+        }
+        for entry in files.values_mut() {
+            entry.findings.sort_by_key(|finding| (finding.line, std::cmp::Reverse(finding.severity)));
+        }
+
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            root,
+            files,
+        }
+    }
+
+    /// Move every path under `from` to the same place under `to`, for an index
+    /// built from a temporary checkout that should point at the working copy
+    pub fn rebase(mut self, from: &Path, to: &Path) -> Self {
+        let from = from.canonicalize().unwrap_or_else(|_| from.to_path_buf());
+        let to = to.canonicalize().unwrap_or_else(|_| to.to_path_buf());
+        let moved = |path: &Path| match path.strip_prefix(&from) {
+            Ok(relative) if relative.as_os_str().is_empty() => to.clone(),
+            Ok(relative) => to.join(relative),
+            Err(_) => path.to_path_buf(),
+        };
+        self.root = moved(&self.root);
+        self.files = std::mem::take(&mut self.files).into_iter()
+            .map(|(path, mut entry)| {
+                for link in entry.imports.iter_mut().chain(entry.importers.iter_mut()) {
+                    link.path = moved(&link.path);
+                }
+                (moved(&path), entry)
+            })
+            .collect();
+        self
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&FileEntry> {
+        self.files.get(path).or_else(|| self.files.get(&path.canonicalize().ok()?))
+    }
+
+    pub fn query(&self, kind: QueryKind, path: &Path) -> Result<Value> {
+        let entry = self.get(path).ok_or_else(|| Error::Discovery(format!("{} is not in the analysis index", path.display())))?;
+        Ok(match kind {
+            QueryKind::Metrics => json!({
+                "language": entry.language,
+                "lines_of_code": entry.lines_of_code,
+                "cyclomatic_complexity": entry.cyclomatic_complexity,
+                "maintainability_index": entry.maintainability_index,
+                "maintainability_rating": entry.maintainability_rating,
+                "functions": entry.functions,
+            }),
+            QueryKind::Importers => json!(entry.importers),
+            QueryKind::Dependencies => json!(entry.imports),
+            QueryKind::Findings => json!(entry.findings),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(|e| Error::parse(path, e))?;
+        serde_json::from_str(&content).map_err(|e| Error::parse(path, e))
+    }
+
+    /// Write `file_index.json` to the output directory
+    pub fn write(&self, output_dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+        let path = output_dir.join("file_index.json");
+        fs::write(&path, serde_json::to_string(self)?).map_err(|e| Error::report(&path, e))?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,381 @@
+use crate::{dependency_graph::FileDependency, file_discovery::FileInfo, simple_parser::{Import, ParsedFile}, sources::SourceSet};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Characters after a route object's `path:` searched for its component
+const ROUTE_OBJECT_WINDOW: usize = 400;
+
+/// Next.js files under `pages/` that wrap every page instead of being one
+const NEXT_SPECIAL_PAGES: &[&str] = &["_app", "_document", "_error", "_middleware"];
+
+/// The router a route was declared for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteFramework {
+    /// `<Route>` elements and route objects passed to `createBrowserRouter`/`useRoutes`
+    ReactRouter,
+    /// Files under `pages/` and `page.*` files under `app/`
+    NextJs,
+    /// `Routes` arrays given to `RouterModule`
+    Angular,
+}
+
+impl RouteFramework {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RouteFramework::ReactRouter => "React Router",
+            RouteFramework::NextJs => "Next.js",
+            RouteFramework::Angular => "Angular",
+        }
+    }
+}
+
+/// One user-facing page or route
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrontendRoute {
+    pub framework: RouteFramework,
+    /// As the router matches it; nested `<Route>` paths are joined to their parents'
+    pub path: String,
+    /// Component, Angular module or Next.js default export rendered for the route
+    pub component: Option<String>,
+    /// File defining the component, relative to the project root
+    pub component_file: Option<PathBuf>,
+    /// File declaring the route (the page file itself for Next.js), relative to the project root
+    pub declared_in: PathBuf,
+    pub line: usize,
+    /// The component is loaded with a dynamic `import()`
+    pub lazy: bool,
+    /// Target of a redirect route
+    pub redirect_to: Option<String>,
+}
+
+/// Pages and routes of React Router, Next.js and Angular apps
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FrontendRouteInventory {
+    pub routes: Vec<FrontendRoute>,
+}
+
+impl FrontendRouteInventory {
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    pub fn frameworks(&self) -> BTreeSet<RouteFramework> {
+        self.routes.iter().map(|route| route.framework).collect()
+    }
+
+    /// One line per route, for the Architecture LLM analysis
+    pub fn context_lines(&self) -> Vec<String> {
+        self.routes.iter()
+            .map(|route| {
+                let target = match (&route.redirect_to, &route.component, &route.component_file) {
+                    (Some(redirect), _, _) => format!("redirect to {}", redirect),
+                    (None, Some(component), Some(file)) => format!("{} ({})", component, file.display()),
+                    (None, Some(component), None) => component.clone(),
+                    (None, None, Some(file)) => file.display().to_string(),
+                    (None, None, None) => "?".to_string(),
+                };
+                format!("{} {} -> {}{}", route.framework.name(), route.path, target, if route.lazy { " [lazy]" } else { "" })
+            })
+            .collect()
+    }
+}
+
+/// Finds route declarations and file-based pages. Route objects nested under
+/// `children` keep their own relative paths; Next.js API routes are left to
+/// the endpoint inventory.
+pub struct FrontendRouteScanner {
+    root: PathBuf,
+    route_tag: Regex,
+    closing_route_tag: Regex,
+    jsx_path: Regex,
+    jsx_component: Regex,
+    object_path: Regex,
+    object_component: Regex,
+    object_lazy: Regex,
+    lazy_member: Regex,
+    redirect: Regex,
+    lazy_binding: Regex,
+}
+
+impl FrontendRouteScanner {
+    pub fn new(root: &Path) -> Self {
+        let regex = |pattern: &str| Regex::new(pattern).expect("valid route pattern");
+        Self {
+            root: root.to_path_buf(),
+            route_tag: regex(r"<Route\b"),
+            closing_route_tag: regex(r"</Route\s*>"),
+            jsx_path: regex(r#"\bpath\s*=\s*\{?\s*["'`]([^"'`]*)["'`]"#),
+            jsx_component: regex(r"\b(?:element\s*=\s*\{\s*<\s*([A-Z][\w.]*)|(?:component|Component)\s*=\s*\{\s*([A-Z][\w.]*))"),
+            object_path: regex(r#"\bpath\s*:\s*["'`]([^"'`]*)["'`]"#),
+            object_component: regex(r"\b(?:element\s*:\s*<\s*([A-Z][\w.]*)|(?:component|Component)\s*:\s*([A-Z][\w.]*))"),
+            object_lazy: regex(r#"\b(?:lazy|loadChildren|loadComponent)\s*:\s*(?:async\s*)?\(\s*\)\s*=>\s*import\s*\(\s*["'`]([^"'`]+)["'`]\s*\)"#),
+            lazy_member: regex(r"^\s*\.then\s*\(\s*\(?\s*\w+\s*\)?\s*=>\s*\w+\.(\w+)"),
+            redirect: regex(r#"(?:\bredirectTo\s*:\s*|<Navigate\s+to\s*=\s*\{?)["'`]([^"'`]*)["'`]"#),
+            lazy_binding: regex(r#"\b(?:const|let|var)\s+([A-Z]\w*)\s*=\s*(?:React\.)?lazy\s*\(\s*\(\s*\)\s*=>\s*import\s*\(\s*["'`]([^"'`]+)["'`]"#),
+        }
+    }
+
+    pub fn analyze(&self, files: &[FileInfo], parsed_files: &[ParsedFile], file_dependencies: &[FileDependency], sources: &SourceSet) -> FrontendRouteInventory {
+        let mut routes = Vec::new();
+        let next_js = self.is_next_js(files, sources);
+
+        for parsed_file in parsed_files {
+            if !matches!(parsed_file.file_info.language.as_deref(), Some("javascript" | "typescript")) {
+                continue;
+            }
+            let relative = self.relative(&parsed_file.file_info.path);
+            if next_js {
+                routes.extend(self.next_js_page(&relative, parsed_file));
+            }
+
+            let Ok(content) = sources.read(&parsed_file.file_info.path) else { continue };
+            let framework = if content.contains("react-router") {
+                RouteFramework::ReactRouter
+            } else if content.contains("@angular/router") {
+                RouteFramework::Angular
+            } else {
+                continue;
+            };
+            let resolver = ComponentResolver { scanner: self, parsed_file, parsed_files, file_dependencies, content: &content };
+            if framework == RouteFramework::ReactRouter {
+                routes.extend(self.jsx_routes(&resolver));
+            }
+            routes.extend(self.object_routes(framework, &resolver));
+        }
+
+        routes.sort_by(|a, b| (a.framework, &a.path, &a.declared_in).cmp(&(b.framework, &b.path, &b.declared_in)));
+        FrontendRouteInventory { routes }
+    }
+
+    fn relative(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.root).unwrap_or(path).to_path_buf()
+    }
+
+    /// A `next` dependency in the root package.json, or a `next.config.*` file
+    fn is_next_js(&self, files: &[FileInfo], sources: &SourceSet) -> bool {
+        let config = files.iter().any(|file| file.path.file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with("next.config.")));
+        config || sources.read(&self.root.join("package.json")).ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .is_some_and(|manifest| ["dependencies", "devDependencies"].iter()
+                .any(|section| manifest.get(section).and_then(|deps| deps.get("next")).is_some()))
+    }
+
+    /// The route of a file under `pages/` (`pages/users/[id].tsx` is `/users/[id]`)
+    /// or of a `page.*` file under `app/`, without route groups and parallel slots
+    fn next_js_page(&self, relative: &Path, parsed_file: &ParsedFile) -> Option<FrontendRoute> {
+        let segments: Vec<String> = relative.iter().map(|s| s.to_string_lossy().to_string()).collect();
+        let router = segments.iter().position(|segment| segment == "pages" || segment == "app")?;
+        if segments[..router].iter().any(|segment| segment != "src") {
+            return None;
+        }
+        let stem = relative.file_stem()?.to_string_lossy().to_string();
+        let directories = &segments[router + 1..segments.len() - 1];
+        let route_segments: Vec<&str> = if segments[router] == "pages" {
+            if directories.first().is_some_and(|d| d == "api") || NEXT_SPECIAL_PAGES.contains(&stem.as_str()) {
+                return None;
+            }
+            directories.iter().map(String::as_str)
+                .chain(Some(stem.as_str()).filter(|stem| *stem != "index"))
+                .collect()
+        } else {
+            if stem != "page" {
+                return None;
+            }
+            directories.iter().map(String::as_str)
+                .filter(|segment| !(segment.starts_with('@') || (segment.starts_with('(') && segment.ends_with(')'))))
+                .collect()
+        };
+
+        Some(FrontendRoute {
+            framework: RouteFramework::NextJs,
+            path: format!("/{}", route_segments.join("/")),
+            component: Self::default_export(parsed_file),
+            component_file: Some(relative.to_path_buf()),
+            declared_in: relative.to_path_buf(),
+            line: 1,
+            lazy: false,
+            redirect_to: None,
+        })
+    }
+
+    /// Name of the default export; `export default function Page()` is named by the function
+    fn default_export(parsed_file: &ParsedFile) -> Option<String> {
+        let export = parsed_file.exports.iter().find(|export| export.is_default)?;
+        if !matches!(export.name.as_str(), "function" | "class" | "async") {
+            return Some(export.name.clone());
+        }
+        parsed_file.functions.iter().find(|function| function.line_number == export.line_number).map(|function| function.name.clone())
+            .or_else(|| parsed_file.classes.iter().find(|class| class.line_number == export.line_number).map(|class| class.name.clone()))
+    }
+
+    /// `<Route>` elements in document order; a route's path is joined to the
+    /// paths of the `<Route>` elements it's nested in
+    fn jsx_routes(&self, resolver: &ComponentResolver) -> Vec<FrontendRoute> {
+        let content = resolver.content;
+        let mut tokens: Vec<(usize, bool)> = self.route_tag.find_iter(content).map(|m| (m.start(), true))
+            .chain(self.closing_route_tag.find_iter(content).map(|m| (m.start(), false)))
+            .collect();
+        tokens.sort();
+
+        let mut parents: Vec<String> = Vec::new();
+        let mut routes = Vec::new();
+        for (start, opening) in tokens {
+            if !opening {
+                parents.pop();
+                continue;
+            }
+            let Some((tag, self_closing)) = Self::tag_at(&content[start..]) else { continue };
+            let path = self.jsx_path.captures(tag).map(|captures| captures[1].to_string());
+            let full_path = Self::join_route(parents.last().map(String::as_str), path.as_deref().unwrap_or(""));
+            if !self_closing {
+                parents.push(full_path.clone());
+            }
+            let component = self.jsx_component.captures(tag)
+                .and_then(|captures| captures.get(1).or(captures.get(2)))
+                .map(|m| m.as_str().to_string());
+            let redirect_to = self.redirect.captures(tag).map(|captures| captures[1].to_string());
+            if component.is_none() && redirect_to.is_none() && path.is_none() {
+                continue;
+            }
+            routes.push(resolver.route(RouteFramework::ReactRouter, full_path, RouteTarget { component, lazy_module: None, redirect_to }, start));
+        }
+        routes
+    }
+
+    /// `{ path: '...', component: X }` objects, as used by `createBrowserRouter`,
+    /// `useRoutes` and Angular `Routes`
+    fn object_routes(&self, framework: RouteFramework, resolver: &ComponentResolver) -> Vec<FrontendRoute> {
+        let content = resolver.content;
+        let starts: Vec<(usize, String)> = self.object_path.captures_iter(content)
+            .map(|captures| (captures.get(0).map_or(0, |m| m.start()), captures[1].to_string()))
+            .collect();
+        starts.iter().enumerate()
+            .map(|(index, (start, path))| {
+                let mut end = starts.get(index + 1).map_or(content.len(), |(next, _)| *next).min(start + ROUTE_OBJECT_WINDOW);
+                while !content.is_char_boundary(end) {
+                    end -= 1;
+                }
+                // The object's properties may come before `path`, e.g. `{ element: <Home />, path: '/' }`
+                let mut begin = content[..*start].rfind('{').map_or(*start, |brace| brace + 1);
+                if index > 0 && begin < starts[index - 1].0 {
+                    begin = *start;
+                }
+                let body = &content[begin..end];
+                let component = self.object_component.captures(body)
+                    .and_then(|captures| captures.get(1).or(captures.get(2)))
+                    .map(|m| m.as_str().to_string());
+                let lazy = self.object_lazy.captures(body).map(|captures| {
+                    let after = &body[captures.get(0).map_or(0, |m| m.end())..];
+                    (captures[1].to_string(), self.lazy_member.captures(after).map(|member| member[1].to_string()))
+                });
+                let redirect_to = self.redirect.captures(body).map(|captures| captures[1].to_string());
+                // Angular paths are relative to the application root
+                let absolute = |path: &str| if framework == RouteFramework::Angular && !path.starts_with('/') { format!("/{}", path) } else { path.to_string() };
+                let path = absolute(path);
+                let redirect_to = redirect_to.as_deref().map(absolute);
+                let (component, lazy_module) = match lazy {
+                    Some((module, member)) => (component.or(member), Some(module)),
+                    None => (component, None),
+                };
+                resolver.route(framework, path, RouteTarget { component, lazy_module, redirect_to }, *start)
+            })
+            .filter(|route| route.component.is_some() || route.component_file.is_some() || route.redirect_to.is_some())
+            .collect()
+    }
+
+    /// Text of the JSX tag starting at `text`, ignoring `>` inside `{...}`
+    /// expressions, and whether it closes itself
+    fn tag_at(text: &str) -> Option<(&str, bool)> {
+        let mut depth = 0usize;
+        let mut previous = ' ';
+        for (index, c) in text.char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                '>' if depth == 0 => return Some((&text[..index], previous == '/')),
+                _ => {}
+            }
+            if !c.is_whitespace() {
+                previous = c;
+            }
+        }
+        None
+    }
+
+    fn join_route(parent: Option<&str>, path: &str) -> String {
+        match parent {
+            _ if path.starts_with('/') => path.to_string(),
+            Some(parent) if !path.is_empty() => format!("{}/{}", parent.trim_end_matches('/'), path),
+            Some(parent) => parent.to_string(),
+            None => format!("/{}", path),
+        }
+    }
+}
+
+/// What a route renders, as written in its declaration
+struct RouteTarget {
+    component: Option<String>,
+    /// Module of a dynamic `import()` loading the component
+    lazy_module: Option<String>,
+    redirect_to: Option<String>,
+}
+
+/// Finds the file defining a route's component in one declaring file
+struct ComponentResolver<'a> {
+    scanner: &'a FrontendRouteScanner,
+    parsed_file: &'a ParsedFile,
+    parsed_files: &'a [ParsedFile],
+    file_dependencies: &'a [FileDependency],
+    content: &'a str,
+}
+
+impl ComponentResolver<'_> {
+    fn route(&self, framework: RouteFramework, path: String, target: RouteTarget, offset: usize) -> FrontendRoute {
+        let RouteTarget { component, lazy_module, redirect_to } = target;
+        // `const Users = lazy(() => import("./Users"))` makes a component lazy too
+        let lazy_module = lazy_module.or_else(|| component.as_ref().and_then(|component| {
+            self.scanner.lazy_binding.captures_iter(self.content)
+                .find(|captures| &captures[1] == component)
+                .map(|captures| captures[2].to_string())
+        }));
+        let component_file = match &lazy_module {
+            Some(module) => self.import_target(|import| import.is_dynamic && import.module == *module),
+            None => component.as_deref().and_then(|component| {
+                let root = component.split('.').next().unwrap_or(component);
+                self.import_target(|import| import.names().iter().any(|(_, bound)| *bound == root))
+                    .or_else(|| self.defining_file(root))
+            }),
+        };
+        FrontendRoute {
+            framework,
+            path,
+            component,
+            component_file: component_file.map(|file| self.scanner.relative(&file)),
+            declared_in: self.scanner.relative(&self.parsed_file.file_info.path),
+            line: self.content[..offset].matches('\n').count() + 1,
+            lazy: lazy_module.is_some(),
+            redirect_to,
+        }
+    }
+
+    /// Where the first matching import of the declaring file resolves to
+    fn import_target(&self, matches: impl Fn(&Import) -> bool) -> Option<PathBuf> {
+        let import = self.parsed_file.imports.iter().find(|import| matches(import))?;
+        self.file_dependencies.iter()
+            .find(|dep| dep.from == self.parsed_file.file_info.path && dep.line_number == import.line_number)
+            .map(|dep| dep.to.clone())
+    }
+
+    /// The only file declaring a function or class with this name
+    fn defining_file(&self, name: &str) -> Option<PathBuf> {
+        let mut defining = self.parsed_files.iter().filter(|pf| {
+            pf.functions.iter().any(|f| f.name == name) || pf.classes.iter().any(|c| c.name == name)
+        });
+        let file = defining.next()?;
+        defining.next().is_none().then(|| file.file_info.path.clone())
+    }
+}
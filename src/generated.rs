@@ -0,0 +1,86 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Output file names of common code generators
+const GENERATED_FILE_PATTERNS: &[(&str, &str)] = &[
+    ("*.pb.go", "protobuf output"),
+    ("*.pb.gw.go", "grpc-gateway output"),
+    ("*.pb.cc", "protobuf output"),
+    ("*.pb.h", "protobuf output"),
+    ("*_pb2.py", "protobuf output"),
+    ("*_pb2.pyi", "protobuf output"),
+    ("*_pb2_grpc.py", "grpc output"),
+    ("*_pb.js", "protobuf output"),
+    ("*_pb.d.ts", "protobuf output"),
+    ("*_grpc_pb.js", "grpc output"),
+    ("*.g.dart", "Dart build_runner output"),
+    ("*.freezed.dart", "Dart build_runner output"),
+    ("*.designer.cs", "designer output"),
+    ("*.min.js", "minified"),
+    ("*.min.css", "minified"),
+];
+
+/// Bytes read from the start of a file when looking for generator markers
+const HEAD_BYTES: usize = 4096;
+
+/// Average line length above which JavaScript/CSS is treated as minified
+const MINIFIED_LINE_LENGTH: usize = 500;
+
+/// Recognizes generated code by file name, generator markers in the header, or
+/// minification, so metrics describe human-written code (`discovery.skip_generated`)
+pub struct GeneratedCodeDetector {
+    patterns: GlobSet,
+}
+
+impl Default for GeneratedCodeDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GeneratedCodeDetector {
+    pub fn new() -> Self {
+        let mut builder = GlobSetBuilder::new();
+        for (pattern, _) in GENERATED_FILE_PATTERNS {
+            builder.add(Glob::new(pattern).expect("built-in generated file pattern is valid"));
+        }
+        Self {
+            patterns: builder.build().unwrap_or_else(|_| GlobSet::empty()),
+        }
+    }
+
+    /// Why the file looks generated, or `None` for human-written code
+    pub fn detect(&self, path: &Path) -> Option<String> {
+        let name = path.file_name()?;
+        if let Some(index) = self.patterns.matches(name).first() {
+            let (pattern, kind) = GENERATED_FILE_PATTERNS[*index];
+            return Some(format!("{} ({})", kind, pattern));
+        }
+
+        let mut head = Vec::with_capacity(HEAD_BYTES);
+        fs::File::open(path).ok()?.take(HEAD_BYTES as u64).read_to_end(&mut head).ok()?;
+        let head = String::from_utf8_lossy(&head);
+        self.detect_content(path, &head)
+    }
+
+    fn detect_content(&self, path: &Path, head: &str) -> Option<String> {
+        // `@generated` (Facebook/Buck convention) and Go's `Code generated ... DO NOT EDIT.`
+        if head.lines().take(20).any(|line| {
+            line.contains("@generated") || (line.contains("Code generated") && line.contains("DO NOT EDIT"))
+        }) {
+            return Some("@generated marker".to_string());
+        }
+
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+        if matches!(extension, "js" | "mjs" | "cjs" | "css") && !head.is_empty() {
+            let lines = head.lines().count().max(1);
+            if head.len() / lines > MINIFIED_LINE_LENGTH {
+                return Some("minified".to_string());
+            }
+        }
+
+        None
+    }
+}
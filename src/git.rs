@@ -0,0 +1,304 @@
+use crate::{Error, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Authorship of a single line as reported by `git blame`
+#[derive(Debug, Clone)]
+pub struct BlameLine {
+    pub line_number: usize,
+    pub author: String,
+    /// Unix timestamp of the commit that last touched the line
+    pub author_time: i64,
+}
+
+/// Run `git blame` on a file inside `root`; `None` when git is unavailable or
+/// the file isn't tracked.
+pub fn blame(root: &Path, path: &Path) -> Option<Vec<BlameLine>> {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let output = Command::new("git")
+        .arg("-C").arg(root)
+        .args(["blame", "--line-porcelain", "--"])
+        .arg(relative)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let mut lines = Vec::new();
+    let mut line_number = 0;
+    let mut author = String::new();
+    let mut author_time = 0;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if line.starts_with('\t') {
+            lines.push(BlameLine { line_number, author: std::mem::take(&mut author), author_time });
+        } else if let Some(value) = line.strip_prefix("author ") {
+            author = value.to_string();
+        } else if let Some(value) = line.strip_prefix("author-time ") {
+            author_time = value.parse().unwrap_or(0);
+        } else if let Some(final_line) = header_final_line(line) {
+            line_number = final_line;
+        }
+    }
+
+    Some(lines)
+}
+
+/// Parse the final line number from a porcelain header (`<sha> <orig> <final> [<count>]`)
+fn header_final_line(line: &str) -> Option<usize> {
+    let mut parts = line.split(' ');
+    let sha = parts.next()?;
+    if sha.len() != 40 || !sha.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    parts.nth(1)?.parse().ok()
+}
+
+/// Commits per author for each file under `root`, over the most recent
+/// `max_commits` commits (all when 0), keyed by `root.join(<relative path>)`;
+/// `None` when git is unavailable.
+pub fn commit_authors(root: &Path, max_commits: usize) -> Option<HashMap<PathBuf, HashMap<String, usize>>> {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(root)
+        .args(["log", "--no-merges", "--format=%x00%an", "--name-only", "--relative"]);
+    if max_commits > 0 {
+        command.arg(format!("--max-count={}", max_commits));
+    }
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let mut authors: HashMap<PathBuf, HashMap<String, usize>> = HashMap::new();
+    let mut author = String::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(name) = line.strip_prefix('\0') {
+            author = name.to_string();
+        } else if !line.is_empty() {
+            *authors.entry(root.join(line)).or_default().entry(author.clone()).or_insert(0) += 1;
+        }
+    }
+
+    Some(authors)
+}
+
+/// Unix timestamp of the last commit touching each file under `root`, keyed by
+/// `root.join(<relative path>)`; `None` when git is unavailable.
+pub fn last_commit_times(root: &Path) -> Option<HashMap<PathBuf, i64>> {
+    let output = Command::new("git")
+        .arg("-C").arg(root)
+        .args(["log", "--format=%x00%ct", "--name-only", "--relative"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // Log is newest first, so the first time seen for a path is its latest commit
+    let mut times = HashMap::new();
+    let mut commit_time = 0;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(timestamp) = line.strip_prefix('\0') {
+            commit_time = timestamp.parse().unwrap_or(0);
+        } else if !line.is_empty() {
+            times.entry(root.join(line)).or_insert(commit_time);
+        }
+    }
+
+    Some(times)
+}
+
+/// Files changed by each of the most recent `max_commits` non-merge commits
+/// (all when 0), newest first, keyed by `root.join(<relative path>)`; `None`
+/// when git is unavailable.
+pub fn commit_files(root: &Path, max_commits: usize) -> Option<Vec<Vec<PathBuf>>> {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(root)
+        .args(["log", "--no-merges", "--format=%x00", "--name-only", "--relative"]);
+    if max_commits > 0 {
+        command.arg(format!("--max-count={}", max_commits));
+    }
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let mut commits: Vec<Vec<PathBuf>> = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if line == "\0" {
+            commits.push(Vec::new());
+        } else if let Some(files) = commits.last_mut().filter(|_| !line.is_empty()) {
+            files.push(root.join(line));
+        }
+    }
+
+    Some(commits)
+}
+
+/// A commit from `git log`, with its size from `--numstat`
+#[derive(Debug, Clone)]
+pub struct CommitSummary {
+    pub subject: String,
+    /// Unix timestamp of the commit
+    pub commit_time: i64,
+    pub files_changed: usize,
+    /// Lines added plus lines deleted; binary files count as zero
+    pub lines_changed: usize,
+}
+
+/// The most recent non-merge commits reachable from HEAD, newest first; `None`
+/// when git is unavailable or `root` isn't in a repository.
+pub fn recent_commits(root: &Path, max_commits: usize) -> Option<Vec<CommitSummary>> {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(root)
+        .args(["log", "--no-merges", "--format=%x00%ct%x09%s", "--numstat"]);
+    if max_commits > 0 {
+        command.arg(format!("--max-count={}", max_commits));
+    }
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let mut commits: Vec<CommitSummary> = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(header) = line.strip_prefix('\0') {
+            let (time, subject) = header.split_once('\t').unwrap_or((header, ""));
+            commits.push(CommitSummary {
+                subject: subject.to_string(),
+                commit_time: time.parse().unwrap_or(0),
+                files_changed: 0,
+                lines_changed: 0,
+            });
+        } else if let Some(commit) = commits.last_mut().filter(|_| !line.is_empty()) {
+            let mut counts = line.split('\t');
+            let added: usize = counts.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+            let deleted: usize = counts.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+            commit.files_changed += 1;
+            commit.lines_changed += added + deleted;
+        }
+    }
+
+    Some(commits)
+}
+
+/// A branch with commits that HEAD doesn't have
+#[derive(Debug, Clone)]
+pub struct UnmergedBranch {
+    /// Short ref name, e.g. `feature/x` or `origin/feature/x`
+    pub name: String,
+    /// Commits on the branch but not on HEAD
+    pub ahead: usize,
+    /// Unix timestamp of the oldest of those commits
+    pub first_commit_time: i64,
+    /// Unix timestamp of the branch tip
+    pub last_commit_time: i64,
+}
+
+/// Local and remote-tracking branches not merged into HEAD; `None` when git is
+/// unavailable or `root` isn't in a repository.
+pub fn unmerged_branches(root: &Path) -> Option<Vec<UnmergedBranch>> {
+    let output = Command::new("git")
+        .arg("-C").arg(root)
+        .args(["for-each-ref", "--no-merged=HEAD", "--format=%(refname:short)%09%(symref)", "refs/heads", "refs/remotes"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let mut branches = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        // `origin/HEAD` is a symbolic ref to another branch
+        let Some((name, "")) = line.split_once('\t') else { continue };
+        let output = Command::new("git")
+            .arg("-C").arg(root)
+            .args(["log", "--format=%ct"])
+            .arg(format!("HEAD..{}", name))
+            .arg("--")
+            .output()
+            .ok()?;
+        let times: Vec<i64> = String::from_utf8_lossy(&output.stdout).lines()
+            .filter_map(|time| time.parse().ok())
+            .collect();
+        if let (Some(&last_commit_time), Some(&first_commit_time)) = (times.first(), times.last()) {
+            branches.push(UnmergedBranch {
+                name: name.to_string(),
+                ahead: times.len(),
+                first_commit_time,
+                last_commit_time,
+            });
+        }
+    }
+
+    Some(branches)
+}
+
+/// A detached worktree of one revision in a temporary directory, removed again
+/// when dropped; the working copy and its index are never touched
+#[derive(Debug)]
+pub struct RevisionWorktree {
+    repository: PathBuf,
+    directory: PathBuf,
+    /// Where the analyzed directory lies inside the worktree
+    pub target: PathBuf,
+    /// Full commit hash the revision resolved to
+    pub commit: String,
+}
+
+impl RevisionWorktree {
+    /// Check out `revision` (a commit, tag or branch) of the repository
+    /// containing `target`
+    pub fn checkout(target: &Path, revision: &str) -> Result<Self> {
+        let git = |args: &[&str]| -> Result<String> {
+            let output = Command::new("git").arg("-C").arg(target).args(args).output()
+                .map_err(|e| Error::Git(format!("git is not available: {}", e)))?;
+            if !output.status.success() {
+                return Err(Error::Git(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        };
+        let repository = PathBuf::from(git(&["rev-parse", "--show-toplevel"])
+            .map_err(|e| Error::Git(format!("{} is not in a git repository: {}", target.display(), e)))?);
+        let prefix = git(&["rev-parse", "--show-prefix"])?;
+        let commit = git(&["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", revision)])
+            .map_err(|_| Error::Git(format!("unknown revision '{}'", revision)))?;
+
+        // Named after the repository so the report's project name stays the same
+        let name = repository.file_name().map(|n| n.to_os_string()).unwrap_or_else(|| "repository".into());
+        let directory = std::env::temp_dir()
+            .join(format!("project-examer-rev-{}-{}", &commit[..12], std::process::id()))
+            .join(name);
+        let output = Command::new("git")
+            .arg("-C").arg(&repository)
+            .args(["worktree", "add", "--detach", "--quiet"])
+            .arg(&directory)
+            .arg(&commit)
+            .output()?;
+        if !output.status.success() {
+            return Err(Error::Git(format!("could not check out {}: {}", revision, String::from_utf8_lossy(&output.stderr).trim())));
+        }
+
+        let target = if prefix.is_empty() { directory.clone() } else { directory.join(prefix.trim_end_matches('/')) };
+        Ok(Self { target, repository, directory, commit })
+    }
+}
+
+impl Drop for RevisionWorktree {
+    fn drop(&mut self) {
+        let removed = Command::new("git")
+            .arg("-C").arg(&self.repository)
+            .args(["worktree", "remove", "--force"])
+            .arg(&self.directory)
+            .status()
+            .is_ok_and(|status| status.success());
+        if !removed {
+            let _ = std::fs::remove_dir_all(&self.directory);
+            let _ = Command::new("git").arg("-C").arg(&self.repository).args(["worktree", "prune"]).status();
+        }
+        if let Some(parent) = self.directory.parent() {
+            let _ = std::fs::remove_dir(parent);
+        }
+    }
+}
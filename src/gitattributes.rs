@@ -0,0 +1,140 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Linguist attributes assigned to a path by `.gitattributes`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinguistOverrides {
+    pub vendored: bool,
+    pub generated: bool,
+    pub language: Option<String>,
+}
+
+impl LinguistOverrides {
+    /// Vendored and generated files are left out of the analysis
+    pub fn is_excluded(&self) -> bool {
+        self.vendored || self.generated
+    }
+}
+
+/// One `.gitattributes` line; `None` leaves the attribute as set by earlier lines
+struct AttributeRule {
+    base: PathBuf,
+    matcher: Gitignore,
+    vendored: Option<bool>,
+    generated: Option<bool>,
+    /// `Some(None)` resets an earlier `linguist-language`
+    language: Option<Option<String>>,
+}
+
+/// Linguist rules collected from one or more `.gitattributes` files. Rules from
+/// deeper directories are added later and override shallower ones, as in git.
+#[derive(Default)]
+pub struct LinguistAttributes {
+    rules: Vec<AttributeRule>,
+}
+
+impl LinguistAttributes {
+    /// Load the `.gitattributes` at the root of the target directory, if any
+    pub fn load(root: &Path) -> Self {
+        let mut attributes = Self::default();
+        attributes.add_file(&root.join(".gitattributes"));
+        attributes
+    }
+
+    pub fn add_file(&mut self, path: &Path) {
+        let Ok(content) = fs::read_to_string(path) else { return };
+        let base = path.parent().unwrap_or(Path::new(""));
+        self.rules.extend(Self::parse(base, &content));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    fn parse(base: &Path, content: &str) -> Vec<AttributeRule> {
+        content.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let pattern = parts.next()?;
+                // Attribute macros and negative patterns don't apply to paths
+                if pattern.starts_with('[') || pattern.starts_with('!') {
+                    return None;
+                }
+
+                let mut rule = AttributeRule {
+                    base: base.to_path_buf(),
+                    matcher: Gitignore::empty(),
+                    vendored: None,
+                    generated: None,
+                    language: None,
+                };
+                for attribute in parts {
+                    let (name, state) = match attribute.split_once('=') {
+                        Some((name, value)) => (name, Some(value)),
+                        None => match attribute.strip_prefix('-') {
+                            Some(name) => (name, Some("false")),
+                            None => match attribute.strip_prefix('!') {
+                                Some(name) => (name, None),
+                                None => (attribute, Some("true")),
+                            },
+                        },
+                    };
+                    match name {
+                        "linguist-vendored" => rule.vendored = Some(state == Some("true")),
+                        "linguist-generated" => rule.generated = Some(state == Some("true")),
+                        "linguist-language" => {
+                            rule.language = Some(state.filter(|v| *v != "true" && *v != "false").map(Self::language_id));
+                        }
+                        _ => {}
+                    }
+                }
+                if rule.vendored.is_none() && rule.generated.is_none() && rule.language.is_none() {
+                    return None;
+                }
+
+                let mut builder = GitignoreBuilder::new(base);
+                builder.add_line(None, pattern).ok()?;
+                rule.matcher = builder.build().ok()?;
+                Some(rule)
+            })
+            .collect()
+    }
+
+    /// Map a Linguist language name onto the identifiers used by language detection
+    fn language_id(name: &str) -> String {
+        let name = name.to_lowercase();
+        match name.as_str() {
+            "c++" => "cpp".to_string(),
+            "c#" => "csharp".to_string(),
+            "shell" | "sh" | "zsh" => "bash".to_string(),
+            "tsx" => "typescript".to_string(),
+            "jsx" => "javascript".to_string(),
+            "tex" => "latex".to_string(),
+            _ => name,
+        }
+    }
+
+    /// Resolve the linguist attributes for a path; later rules take precedence
+    pub fn lookup(&self, path: &Path) -> LinguistOverrides {
+        let mut overrides = LinguistOverrides::default();
+        for rule in &self.rules {
+            // Rules only apply below the directory of their .gitattributes
+            if !path.starts_with(&rule.base) || !rule.matcher.matched_path_or_any_parents(path, false).is_ignore() {
+                continue;
+            }
+            if let Some(vendored) = rule.vendored {
+                overrides.vendored = vendored;
+            }
+            if let Some(generated) = rule.generated {
+                overrides.generated = generated;
+            }
+            if let Some(ref language) = rule.language {
+                overrides.language = language.clone();
+            }
+        }
+        overrides
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{
+    dependency_graph::{DependencyGraph, GraphBuilder, Node, NodeType},
+    simple_parser::ParsedFile,
+};
+use crate::{Error, Result};
+use petgraph::visit::EdgeRef;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Node attributes shared by both formats, as (key, value) in a fixed order
+type Attributes = Vec<(&'static str, String)>;
+
+/// Writes the dependency graph as GraphML (yEd, Neo4j) or GEXF (Gephi)
+pub struct GraphExporter {
+    graph: DependencyGraph,
+    lines_of_code: HashMap<PathBuf, usize>,
+}
+
+impl GraphExporter {
+    pub fn from_parsed_files(parsed_files: &[ParsedFile]) -> Self {
+        let mut builder = GraphBuilder::new();
+        builder.build_graph(parsed_files);
+
+        Self {
+            graph: builder.get_graph().clone(),
+            lines_of_code: parsed_files.iter()
+                .map(|pf| (pf.file_info.path.clone(), pf.lines_of_code))
+                .collect(),
+        }
+    }
+
+    fn node_attributes(&self, node: &Node) -> Attributes {
+        let loc = match node.node_type {
+            NodeType::File => self.lines_of_code.get(&node.file_path).copied().unwrap_or(0),
+            _ => 0,
+        };
+        vec![
+            ("type", format!("{:?}", node.node_type)),
+            ("path", node.file_path.to_string_lossy().to_string()),
+            ("line", node.line_number.to_string()),
+            ("language", node.metadata.language.clone().unwrap_or_default()),
+            ("complexity", node.metadata.complexity.unwrap_or(0).to_string()),
+            ("loc", loc.to_string()),
+            // First line only; graph tools show attributes in single-line cells
+            ("doc", node.metadata.docstring.as_deref().and_then(|doc| doc.lines().next()).unwrap_or_default().to_string()),
+        ]
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+            "  <key id=\"type\" for=\"node\" attr.name=\"type\" attr.type=\"string\"/>\n",
+            "  <key id=\"path\" for=\"node\" attr.name=\"path\" attr.type=\"string\"/>\n",
+            "  <key id=\"line\" for=\"node\" attr.name=\"line\" attr.type=\"int\"/>\n",
+            "  <key id=\"language\" for=\"node\" attr.name=\"language\" attr.type=\"string\"/>\n",
+            "  <key id=\"complexity\" for=\"node\" attr.name=\"complexity\" attr.type=\"int\"/>\n",
+            "  <key id=\"loc\" for=\"node\" attr.name=\"loc\" attr.type=\"int\"/>\n",
+            "  <key id=\"doc\" for=\"node\" attr.name=\"doc\" attr.type=\"string\"/>\n",
+            "  <key id=\"edge_type\" for=\"edge\" attr.name=\"type\" attr.type=\"string\"/>\n",
+            "  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>\n",
+            "  <graph id=\"dependencies\" edgedefault=\"directed\">\n",
+        ));
+
+        for index in self.graph.node_indices() {
+            let node = &self.graph[index];
+            xml.push_str(&format!("    <node id=\"n{}\">\n      <data key=\"label\">{}</data>\n",
+                index.index(), escape_xml(&node.metadata.name)));
+            for (key, value) in self.node_attributes(node) {
+                xml.push_str(&format!("      <data key=\"{}\">{}</data>\n", key, escape_xml(&value)));
+            }
+            xml.push_str("    </node>\n");
+        }
+
+        for edge in self.graph.edge_references() {
+            xml.push_str(&format!(
+                "    <edge id=\"e{}\" source=\"n{}\" target=\"n{}\">\n      <data key=\"edge_type\">{:?}</data>\n      <data key=\"weight\">{}</data>\n    </edge>\n",
+                edge.id().index(), edge.source().index(), edge.target().index(), edge.weight().edge_type, edge.weight().weight));
+        }
+
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+
+    pub fn to_gexf(&self) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n",
+            "  <meta><creator>project-examer</creator></meta>\n",
+            "  <graph defaultedgetype=\"directed\">\n",
+            "    <attributes class=\"node\">\n",
+        ));
+        let attribute_names = ["type", "path", "line", "language", "complexity", "loc", "doc"];
+        let attribute_types = ["string", "string", "integer", "string", "integer", "integer", "string"];
+        for (i, (name, kind)) in attribute_names.iter().zip(attribute_types).enumerate() {
+            xml.push_str(&format!("      <attribute id=\"{}\" title=\"{}\" type=\"{}\"/>\n", i, name, kind));
+        }
+        xml.push_str("    </attributes>\n    <attributes class=\"edge\">\n      <attribute id=\"0\" title=\"type\" type=\"string\"/>\n    </attributes>\n    <nodes>\n");
+
+        for index in self.graph.node_indices() {
+            let node = &self.graph[index];
+            xml.push_str(&format!("      <node id=\"n{}\" label=\"{}\">\n        <attvalues>\n",
+                index.index(), escape_xml(&node.metadata.name)));
+            for (i, (_, value)) in self.node_attributes(node).into_iter().enumerate() {
+                xml.push_str(&format!("          <attvalue for=\"{}\" value=\"{}\"/>\n", i, escape_xml(&value)));
+            }
+            xml.push_str("        </attvalues>\n      </node>\n");
+        }
+
+        xml.push_str("    </nodes>\n    <edges>\n");
+        for edge in self.graph.edge_references() {
+            xml.push_str(&format!(
+                "      <edge id=\"e{}\" source=\"n{}\" target=\"n{}\" weight=\"{}\">\n        <attvalues><attvalue for=\"0\" value=\"{:?}\"/></attvalues>\n      </edge>\n",
+                edge.id().index(), edge.source().index(), edge.target().index(), edge.weight().weight, edge.weight().edge_type));
+        }
+
+        xml.push_str("    </edges>\n  </graph>\n</gexf>\n");
+        xml
+    }
+
+    pub fn export_graphml(&self, output_dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+        let path = output_dir.join("dependency_graph.graphml");
+        fs::write(&path, self.to_graphml()).map_err(|e| Error::report(&path, e))?;
+        Ok(path)
+    }
+
+    pub fn export_gexf(&self, output_dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+        let path = output_dir.join("dependency_graph.gexf");
+        fs::write(&path, self.to_gexf()).map_err(|e| Error::report(&path, e))?;
+        Ok(path)
+    }
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,349 @@
+use crate::{
+    file_discovery::FileInfo,
+    llm::{AnalysisResponse, AnalysisType, Evidence, EvidenceStatus},
+    simple_parser::ParsedFile,
+    sources::SourceSet,
+};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Share of a snippet's words a line must contain to count as the quoted line
+const SNIPPET_WORD_MATCH: f64 = 0.8;
+
+/// Confidence taken off an insight for each reference that doesn't check out
+const CONFIDENCE_PENALTY: f64 = 0.2;
+
+/// Insights whose confidence falls below this are discarded
+const MIN_CONFIDENCE: f64 = 0.25;
+
+/// Languages recognized in LLM text: display name, pattern, and the detected
+/// languages that count as the project having it
+const LANGUAGES: &[(&str, &str, &[&str])] = &[
+    ("Rust", r"\bRust\b", &["rust"]),
+    ("Python", r"\bPython\b", &["python"]),
+    ("JavaScript", r"\bJavaScript\b", &["javascript", "typescript"]),
+    ("TypeScript", r"\bTypeScript\b", &["typescript"]),
+    ("Java", r"\bJava\b", &["java"]),
+    ("Kotlin", r"\bKotlin\b", &["kotlin"]),
+    ("Ruby", r"\bRuby\b", &["ruby"]),
+    ("PHP", r"\bPHP\b", &["php"]),
+    ("Scala", r"\bScala\b", &["scala"]),
+    ("Elixir", r"\bElixir\b", &["elixir"]),
+    ("Dart", r"\bDart\b", &["dart"]),
+    ("Golang", r"\bGolang\b", &["go"]),
+    ("C#", r"\bC#", &["csharp"]),
+    ("C++", r"\bC\+\+", &["cpp", "c"]),
+];
+
+/// An LLM insight left out of the report, with why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscardedItem {
+    pub analysis_type: Option<AnalysisType>,
+    pub title: String,
+    pub reasons: Vec<String>,
+}
+
+/// Result of checking one analysis response
+#[derive(Debug, Default)]
+pub struct GuardrailOutcome {
+    /// Insights kept with a lowered confidence
+    pub lowered: usize,
+    pub discarded: Vec<DiscardedItem>,
+}
+
+/// Cross-checks the files, functions and languages LLM insights name against the
+/// analyzed project. Each reference that doesn't check out lowers an insight's
+/// confidence; insights whose references all fail are discarded. Recommendations are
+/// not checked, since they may propose files and functions that don't exist yet.
+pub struct Guardrails {
+    resolver: FileResolver,
+    /// Every identifier in the analyzed sources, so calls into libraries count too
+    identifiers: HashSet<String>,
+    languages: HashSet<String>,
+    function_ref: Regex,
+    file_ref: Regex,
+    language_refs: Vec<(&'static str, &'static [&'static str], Regex)>,
+}
+
+impl Guardrails {
+    pub fn new(root: &Path, files: &[FileInfo], parsed_files: &[ParsedFile], sources: &SourceSet) -> Self {
+        let identifier = Regex::new(r"[A-Za-z_][A-Za-z0-9_]*").expect("valid identifier pattern");
+        let mut identifiers = HashSet::new();
+        for pf in parsed_files {
+            identifiers.extend(pf.functions.iter().map(|f| f.name.clone()));
+            for class in &pf.classes {
+                identifiers.insert(class.name.clone());
+                identifiers.extend(class.methods.iter().map(|m| m.name.clone()));
+            }
+            if let Ok(content) = sources.read(&pf.file_info.path) {
+                identifiers.extend(identifier.find_iter(&content).map(|m| m.as_str().to_string()));
+            }
+        }
+
+        Self {
+            resolver: FileResolver::new(root, files),
+            identifiers,
+            languages: files.iter().filter_map(|f| f.language.clone()).collect(),
+            // `name()`, `module::name()` or `object.name()` with empty parentheses, as used in prose
+            function_ref: Regex::new(r"\b[A-Za-z_][A-Za-z0-9_]*(?:(?:::|\.)[A-Za-z_][A-Za-z0-9_]*)*\(\)").expect("valid function pattern"),
+            file_ref: Regex::new(r"(?:[\w.-]+/)*[\w-]+\.(?:rs|py|js|jsx|ts|tsx|go|java|kt|rb|php|cs|swift|scala|ex|exs|dart|c|h|cpp|hpp|sql)\b").expect("valid file pattern"),
+            language_refs: LANGUAGES.iter()
+                .map(|(name, pattern, ids)| (*name, *ids, Regex::new(pattern).expect("valid language pattern")))
+                .collect(),
+        }
+    }
+
+    pub fn check(&self, response: &mut AnalysisResponse) -> GuardrailOutcome {
+        let mut outcome = GuardrailOutcome::default();
+        let analysis_type = response.analysis_type.clone();
+
+        response.insights.retain_mut(|insight| {
+            let text = format!("{}\n{}", insight.title, insight.description);
+            let (mut checked, mut reasons) = self.text_problems(&text);
+            for evidence in &insight.evidence {
+                let location = evidence.file.as_deref().map(|file| match evidence.line {
+                    Some(line) => format!("{}:{}", file, line),
+                    None => file.to_string(),
+                });
+                match (evidence.status, location) {
+                    (EvidenceStatus::Verified, _) => checked += 1,
+                    (EvidenceStatus::UnknownFile, Some(location)) => {
+                        checked += 1;
+                        reasons.push(format!("evidence file {} is not in the project", location));
+                    }
+                    (EvidenceStatus::SnippetNotFound, Some(location)) => {
+                        checked += 1;
+                        reasons.push(format!("evidence quote not found at {}", location));
+                    }
+                    _ => {}
+                }
+            }
+            if reasons.is_empty() {
+                return true;
+            }
+
+            insight.confidence = (insight.confidence - CONFIDENCE_PENALTY * reasons.len() as f64).max(0.0);
+            if reasons.len() == checked || insight.confidence < MIN_CONFIDENCE {
+                outcome.discarded.push(DiscardedItem {
+                    analysis_type: analysis_type.clone(),
+                    title: insight.title.clone(),
+                    reasons,
+                });
+                false
+            } else {
+                outcome.lowered += 1;
+                true
+            }
+        });
+
+        outcome
+    }
+
+    /// Files, functions and languages named in free text: how many were checked, and
+    /// a reason for each one the project doesn't have
+    fn text_problems(&self, text: &str) -> (usize, Vec<String>) {
+        let mut checked = 0;
+        let mut reasons: Vec<String> = Vec::new();
+        let mut report = |reason: String| {
+            if !reasons.contains(&reason) {
+                reasons.push(reason);
+            }
+        };
+
+        for file in self.file_ref.find_iter(text).map(|m| m.as_str()) {
+            // Framework names such as `Node.js` and `Vue.js` read like file names
+            if !file.contains('/') && file.starts_with(char::is_uppercase) && file.ends_with(".js") {
+                continue;
+            }
+            checked += 1;
+            if self.resolver.resolve(file).is_none() {
+                report(format!("names file {}, which is not in the project", file));
+            }
+        }
+
+        for function in self.function_ref.find_iter(text).map(|m| m.as_str().trim_end_matches("()")) {
+            let name = function.rsplit([':', '.']).next().unwrap_or(function);
+            checked += 1;
+            if !self.identifiers.contains(name) {
+                report(format!("names function {}(), which does not appear in the source", function));
+            }
+        }
+
+        for (name, ids, regex) in &self.language_refs {
+            if regex.is_match(text) {
+                checked += 1;
+                if !ids.iter().any(|id| self.languages.contains(*id)) {
+                    report(format!("mentions {}, which the project does not use", name));
+                }
+            }
+        }
+
+        (checked, reasons)
+    }
+}
+
+/// Resolves file references written by the LLM to analyzed files, so reports only
+/// link to files that exist
+pub struct FileResolver {
+    root: PathBuf,
+    /// Analyzed files keyed by path relative to the project root
+    by_relative: HashMap<String, PathBuf>,
+    /// Analyzed files keyed by file name
+    by_name: HashMap<String, Vec<PathBuf>>,
+}
+
+impl FileResolver {
+    pub fn new(root: &Path, files: &[FileInfo]) -> Self {
+        let mut by_relative = HashMap::new();
+        let mut by_name: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for file in files {
+            let relative = file.path.strip_prefix(root).unwrap_or(&file.path);
+            by_relative.insert(normalize(&relative.to_string_lossy()), file.path.clone());
+            by_relative.insert(normalize(&file.path.to_string_lossy()), file.path.clone());
+            if let Some(name) = file.path.file_name() {
+                by_name.entry(name.to_string_lossy().to_string()).or_default().push(file.path.clone());
+            }
+        }
+        Self { root: root.to_path_buf(), by_relative, by_name }
+    }
+
+    /// The analyzed file a reference names. Accepts absolute paths, paths relative to
+    /// the project root, `./` and `a/` prefixes, a trailing `:line`, and bare file names
+    /// that are unique in the project.
+    pub fn resolve(&self, reference: &str) -> Option<&Path> {
+        let reference = normalize(strip_line_suffix(reference.trim().trim_matches(['`', '"', '\''])));
+        if reference.is_empty() {
+            return None;
+        }
+        if let Some(path) = self.by_relative.get(&reference) {
+            return Some(path);
+        }
+        let root = normalize(&self.root.to_string_lossy());
+        if let Some(path) = reference.strip_prefix(&root).and_then(|rest| self.by_relative.get(rest.trim_start_matches('/'))) {
+            return Some(path);
+        }
+        match self.by_name.get(&reference).map(Vec::as_slice) {
+            Some([only]) => Some(only),
+            _ => None,
+        }
+    }
+
+    /// Resolve each reference, keeping resolved files once each, in order, as display strings
+    pub fn resolve_all(&self, references: &[String]) -> Vec<String> {
+        let mut resolved: Vec<String> = Vec::new();
+        for reference in references {
+            if let Some(path) = self.resolve(reference) {
+                let path = path.to_string_lossy().to_string();
+                if !resolved.contains(&path) {
+                    resolved.push(path);
+                }
+            }
+        }
+        resolved
+    }
+
+    /// Check evidence against the file it names. The file must be analyzed and contain
+    /// the snippet, compared with whitespace collapsed and tolerating small edits; the
+    /// line is moved to the nearest matching line. Text evidence starting with
+    /// `path:line` is anchored the same way; other text is left unanchored.
+    pub fn anchor(&self, evidence: &mut Evidence, sources: &SourceSet) {
+        if evidence.file.is_none() {
+            let text = evidence.snippet.trim().to_string();
+            let (location, rest) = text.split_once(char::is_whitespace).unwrap_or((&text, ""));
+            let path = strip_line_suffix(location);
+            let looks_like_path = path != location && (path.contains('/') || path.contains('.'));
+            if !looks_like_path && self.resolve(location).is_none() {
+                evidence.status = EvidenceStatus::Unanchored;
+                return;
+            }
+            evidence.line = location[path.len()..].trim_start_matches(':').split(':').next().and_then(|line| line.parse().ok());
+            evidence.file = Some(path.to_string());
+            evidence.snippet = rest.trim().to_string();
+        }
+
+        let Some(path) = evidence.file.as_deref().and_then(|file| self.resolve(file)) else {
+            evidence.status = EvidenceStatus::UnknownFile;
+            return;
+        };
+        evidence.file = Some(path.to_string_lossy().to_string());
+        let Ok(content) = sources.read(path) else {
+            evidence.status = EvidenceStatus::Unanchored;
+            return;
+        };
+        let lines: Vec<&str> = content.lines().collect();
+
+        let snippet = evidence.snippet.lines()
+            .map(|line| squash(line.trim_matches('`')))
+            .find(|line| !line.is_empty())
+            .unwrap_or_default();
+        if snippet.is_empty() {
+            evidence.status = if evidence.line.is_none_or(|line| line >= 1 && line <= lines.len()) {
+                EvidenceStatus::Verified
+            } else {
+                EvidenceStatus::SnippetNotFound
+            };
+            return;
+        }
+
+        let claimed = evidence.line.unwrap_or(1);
+        let found = lines.iter().enumerate()
+            .filter(|(_, line)| quotes(line, &snippet))
+            .map(|(index, _)| index + 1)
+            .min_by_key(|line| line.abs_diff(claimed));
+        match found {
+            Some(line) => {
+                evidence.line = Some(line);
+                evidence.status = EvidenceStatus::Verified;
+            }
+            None => evidence.status = EvidenceStatus::SnippetNotFound,
+        }
+    }
+}
+
+/// Whether a source line is the one a snippet quotes
+fn quotes(line: &str, snippet: &str) -> bool {
+    let line = squash(line);
+    if line.is_empty() {
+        return false;
+    }
+    if line.contains(snippet) || (line.len() >= 12 && snippet.contains(&line)) {
+        return true;
+    }
+    let words = |text: &str| text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    let snippet_words = words(snippet);
+    if snippet_words.len() < 3 {
+        return false;
+    }
+    let line_words = words(&line);
+    let shared = snippet_words.iter().filter(|word| line_words.contains(word)).count();
+    shared as f64 / snippet_words.len() as f64 >= SNIPPET_WORD_MATCH
+}
+
+/// Whitespace runs collapsed to single spaces
+fn squash(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Drop a trailing `:42` or `:42:7` location
+fn strip_line_suffix(reference: &str) -> &str {
+    let mut reference = reference;
+    for _ in 0..2 {
+        match reference.rsplit_once(':') {
+            Some((path, suffix)) if !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit()) => reference = path,
+            _ => break,
+        }
+    }
+    reference
+}
+
+/// Forward slashes, without `./` or the `a/`/`b/` prefixes used in diff headers
+fn normalize(path: &str) -> String {
+    let path = path.trim().replace('\\', "/");
+    let path = path.strip_prefix("a/").or_else(|| path.strip_prefix("b/")).unwrap_or(&path);
+    path.trim_start_matches("./").to_string()
+}
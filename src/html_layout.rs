@@ -0,0 +1,269 @@
+use crate::graph_export::escape_xml;
+use regex::Regex;
+use std::collections::HashSet;
+
+/// Stylesheet embedded in every HTML report. Colors are CSS variables so the light
+/// and dark themes only swap the palette; fonts come from the system font stack.
+const STYLE: &str = r#"
+:root {
+    --bg: #ffffff; --fg: #1f2328; --muted: #59636e; --border: #d1d9e0;
+    --surface: #f6f8fa; --surface-strong: #eef1f4; --accent: #0969da;
+    --insight: #e8f4f8; --insights-head: #e3f2fd; --recommendations-head: #f3e5f5;
+    --high: #d1242f; --high-bg: #ffebee; --medium: #bc4c00; --medium-bg: #fff3e0;
+    --low: #1a7f37; --low-bg: #f1f8e9; --yellow-bg: #fff8e1;
+    color-scheme: light;
+}
+@media (prefers-color-scheme: dark) {
+    :root:not([data-theme="light"]) {
+        --bg: #0d1117; --fg: #e6edf3; --muted: #9198a1; --border: #3d444d;
+        --surface: #151b23; --surface-strong: #212830; --accent: #4493f8;
+        --insight: #132233; --insights-head: #16263a; --recommendations-head: #2a1d33;
+        --high: #f85149; --high-bg: #3a1618; --medium: #db6d28; --medium-bg: #3a2412;
+        --low: #3fb950; --low-bg: #12301b; --yellow-bg: #35300f;
+        color-scheme: dark;
+    }
+}
+:root[data-theme="dark"] {
+    --bg: #0d1117; --fg: #e6edf3; --muted: #9198a1; --border: #3d444d;
+    --surface: #151b23; --surface-strong: #212830; --accent: #4493f8;
+    --insight: #132233; --insights-head: #16263a; --recommendations-head: #2a1d33;
+    --high: #f85149; --high-bg: #3a1618; --medium: #db6d28; --medium-bg: #3a2412;
+    --low: #3fb950; --low-bg: #12301b; --yellow-bg: #35300f;
+    color-scheme: dark;
+}
+* { box-sizing: border-box; }
+body {
+    margin: 0; background: var(--bg); color: var(--fg); line-height: 1.6;
+    font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Roboto, "Helvetica Neue", Arial, sans-serif;
+}
+code, pre { font-family: ui-monospace, SFMono-Regular, "SF Mono", Menlo, Consolas, "Liberation Mono", monospace; font-size: 0.9em; }
+a { color: var(--accent); }
+.toc {
+    position: fixed; top: 0; left: 0; bottom: 0; width: 260px; overflow-y: auto;
+    padding: 20px; background: var(--surface); border-right: 1px solid var(--border); font-size: 0.9em;
+}
+.toc h2 { font-size: 1em; margin: 0 0 10px; }
+.toc ol { list-style: none; padding: 0; margin: 10px 0; }
+.toc li { margin: 2px 0; line-height: 1.3; }
+.toc a { display: block; padding: 4px 8px; border-radius: 4px; color: var(--fg); text-decoration: none; }
+.toc a:hover { background: var(--surface-strong); }
+.toc-controls { display: flex; flex-wrap: wrap; gap: 6px; }
+.toc-controls button {
+    padding: 4px 8px; border: 1px solid var(--border); border-radius: 4px;
+    background: var(--bg); color: var(--fg); cursor: pointer; font: inherit;
+}
+main { margin-left: 260px; padding: 40px; max-width: 1400px; }
+@media (max-width: 900px) {
+    .toc { position: static; width: auto; border-right: none; border-bottom: 1px solid var(--border); }
+    main { margin-left: 0; padding: 20px; }
+}
+.header { border-bottom: 2px solid var(--border); padding-bottom: 20px; }
+.section { margin: 30px 0; scroll-margin-top: 20px; }
+details.section > summary { cursor: pointer; list-style: none; }
+details.section > summary::-webkit-details-marker { display: none; }
+details.section > summary h2 { display: inline; }
+details.section > summary::before { content: "▸"; display: inline-block; width: 1.2em; color: var(--muted); transition: transform 0.15s; }
+details.section[open] > summary::before { transform: rotate(90deg); }
+.metric { display: inline-block; margin: 10px 20px 10px 0; padding: 10px; background: var(--surface); border-radius: 5px; }
+.recommendation { margin: 15px 0; padding: 15px; border-left: 4px solid var(--accent); background: var(--surface); }
+.recommendation.priority-high { border-left-color: var(--high); }
+.recommendation.priority-medium { border-left-color: var(--medium); }
+.recommendation.priority-low { border-left-color: var(--low); }
+.insight { margin: 10px 0; padding: 10px; background: var(--insight); border-radius: 5px; }
+.insight-title { font-weight: bold; }
+.insight-category { color: var(--muted); font-size: 0.9em; text-transform: uppercase; }
+.evidence { margin: 5px 0; font-style: italic; color: var(--muted); }
+.evidence-quote { margin: 5px 0; padding: 5px 10px; border-left: 3px solid var(--low); background: var(--surface); }
+.evidence-quote pre { margin: 5px 0 0; white-space: pre-wrap; }
+.evidence-flagged { border-left-color: var(--high); }
+.evidence-flag { color: var(--high); font-size: 0.9em; }
+.llm-analysis { margin: 20px 0; padding: 20px; background: var(--surface); border-radius: 8px; }
+.analysis-type { font-weight: bold; margin-bottom: 10px; }
+.analysis-summary { margin: 10px 0; padding: 15px; background: var(--bg); border-radius: 5px; line-height: 1.6; }
+.insights-table, .recommendations-table { margin: 15px 0; }
+.insights-table th { background-color: var(--insights-head); }
+.recommendations-table th { background-color: var(--recommendations-head); }
+table { border-collapse: collapse; width: 100%; margin: 10px 0; }
+th, td { border: 1px solid var(--border); padding: 12px; text-align: left; vertical-align: top; }
+th { background-color: var(--surface-strong); font-weight: bold; }
+tr.priority-high, td.priority-high { background-color: var(--high-bg); }
+tr.priority-medium, td.priority-medium { background-color: var(--medium-bg); }
+tr.priority-low, td.priority-low { background-color: var(--low-bg); }
+.confidence-high { color: var(--low); font-weight: bold; }
+.confidence-medium { color: var(--medium); font-weight: bold; }
+.confidence-low { color: var(--high); font-weight: bold; }
+main ol { list-style-type: decimal; padding-left: 25px; margin: 10px 0; }
+main ul { list-style-type: disc; padding-left: 25px; margin: 10px 0; }
+main li { margin: 8px 0; line-height: 1.4; }
+.analysis-summary li { margin: 6px 0; padding-left: 5px; }
+.analysis-summary h3 { margin: 25px 0 15px 0; }
+.analysis-summary h4 { margin: 20px 0 10px 0; }
+.analysis-summary p { margin: 12px 0; }
+.treemap { border: 1px solid var(--border); background: var(--surface); }
+.sortable th { cursor: pointer; }
+.rating-green { background-color: var(--low-bg); }
+.rating-yellow { background-color: var(--yellow-bg); }
+.rating-red { background-color: var(--high-bg); }
+.grade { display: inline-block; min-width: 2em; padding: 2px 10px; border-radius: 6px; font-size: 1.5em; font-weight: bold; text-align: center; }
+.grade-a, .grade-b { background-color: var(--low-bg); color: var(--low); }
+.grade-c { background-color: var(--yellow-bg); color: var(--medium); }
+.grade-d, .grade-f { background-color: var(--high-bg); color: var(--high); }
+.grade-na { background-color: var(--surface-strong); color: var(--muted); }
+@media print {
+    .toc { display: none; }
+    main { margin-left: 0; }
+    details.section > summary::before { display: none; }
+}
+"#;
+
+/// Applies the saved theme before first paint so dark mode doesn't flash
+const THEME_SCRIPT: &str = r#"
+(function () {
+    try {
+        var theme = localStorage.getItem('project-examer-theme');
+        if (theme) document.documentElement.dataset.theme = theme;
+    } catch (e) {}
+})();
+"#;
+
+/// Table sorting, section toggles and the theme switch. The report reads fine
+/// without it: sections start open and the theme follows the system setting.
+const SCRIPT: &str = r#"
+function sortTable(header) {
+    const table = header.closest('table');
+    const column = Array.from(header.parentNode.children).indexOf(header);
+    const ascending = header.dataset.order !== 'asc';
+    header.dataset.order = ascending ? 'asc' : 'desc';
+    const rows = Array.from(table.querySelectorAll('tr')).slice(1);
+    rows.sort((a, b) => {
+        const x = a.children[column].textContent;
+        const y = b.children[column].textContent;
+        const cmp = isNaN(x) || isNaN(y) ? x.localeCompare(y) : Number(x) - Number(y);
+        return ascending ? cmp : -cmp;
+    });
+    rows.forEach(row => table.appendChild(row));
+}
+function setSections(open) {
+    document.querySelectorAll('details.section').forEach(section => section.open = open);
+}
+function toggleTheme() {
+    const root = document.documentElement;
+    const dark = root.dataset.theme
+        ? root.dataset.theme === 'dark'
+        : window.matchMedia('(prefers-color-scheme: dark)').matches;
+    root.dataset.theme = dark ? 'light' : 'dark';
+    try { localStorage.setItem('project-examer-theme', root.dataset.theme); } catch (e) {}
+}
+document.querySelectorAll('.toc a').forEach(link => link.addEventListener('click', () => {
+    const section = document.getElementById(link.getAttribute('href').slice(1));
+    if (section) section.open = true;
+}));
+window.addEventListener('beforeprint', () => setSections(true));
+"#;
+
+/// Wraps a report body in a single self-contained page: embedded stylesheet and
+/// scripts, light and dark themes, collapsible sections and a table of contents.
+/// Nothing is loaded from outside the file, so it survives being emailed around.
+/// `title` is plain text and is escaped here.
+pub fn page(title: &str, body: &str) -> String {
+    let (body, sections) = collapsible_sections(body);
+    let toc: String = sections.iter()
+        .map(|(id, title)| format!("<li><a href=\"#{}\">{}</a></li>", id, escape_xml(title)))
+        .collect();
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{}</title>
+    <script>{}</script>
+    <style>{}</style>
+</head>
+<body>
+<nav class="toc">
+    <h2>Contents</h2>
+    <div class="toc-controls">
+        <button type="button" onclick="setSections(true)">Expand all</button>
+        <button type="button" onclick="setSections(false)">Collapse all</button>
+        <button type="button" onclick="toggleTheme()">Toggle theme</button>
+    </div>
+    <ol>{}</ol>
+</nav>
+<main>
+{}
+</main>
+<script>{}</script>
+</body>
+</html>"#,
+        escape_xml(title), THEME_SCRIPT, STYLE, toc, body, SCRIPT
+    )
+}
+
+/// Turns each `<div class="section"><h2>…</h2>` into an open `<details>` with the
+/// heading as its summary and an id for the table of contents, returning the new
+/// body and the (id, plain-text title) of every section in order
+fn collapsible_sections(body: &str) -> (String, Vec<(String, String)>) {
+    let tokens = Regex::new(r#"<div class="section">\s*<h2>(.*?)</h2>|<div\b|</div>"#).expect("valid section pattern");
+    let tags = Regex::new(r"<[^>]*>").expect("valid tag pattern");
+
+    let mut out = String::with_capacity(body.len() + 4096);
+    let mut sections = Vec::new();
+    let mut ids = HashSet::new();
+    // Whether each open div is a section, so its close becomes `</details>`
+    let mut open: Vec<bool> = Vec::new();
+    let mut last = 0;
+    for token in tokens.captures_iter(body) {
+        let whole = token.get(0).expect("match");
+        out.push_str(&body[last..whole.start()]);
+        last = whole.end();
+        if let Some(heading) = token.get(1) {
+            let title = unescape(tags.replace_all(heading.as_str(), "").trim());
+            let id = unique_slug(&title, &mut ids);
+            out.push_str(&format!(
+                "<details class=\"section\" id=\"{}\" open>\n        <summary><h2>{}</h2></summary>",
+                id, heading.as_str()
+            ));
+            sections.push((id, title));
+            open.push(true);
+        } else if whole.as_str() == "</div>" {
+            out.push_str(if open.pop().unwrap_or(false) { "</details>" } else { "</div>" });
+        } else {
+            out.push_str(whole.as_str());
+            open.push(false);
+        }
+    }
+    out.push_str(&body[last..]);
+    (out, sections)
+}
+
+/// Decodes the entities the report writers produce, so headings become plain text
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn unique_slug(title: &str, taken: &mut HashSet<String>) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = match slug.trim_end_matches('-') {
+        "" => "section".to_string(),
+        slug => slug.to_string(),
+    };
+    let mut candidate = slug.clone();
+    let mut n = 2;
+    while !taken.insert(candidate.clone()) {
+        candidate = format!("{}-{}", slug, n);
+        n += 1;
+    }
+    candidate
+}
@@ -0,0 +1,402 @@
+use crate::{file_discovery::FileInfo, sources::SourceSet};
+use ignore::WalkBuilder;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Directories whose JSON files are taken for translation resources
+const RESOURCE_DIRECTORIES: &[&str] = &["locales", "locale", "i18n", "lang", "langs", "translations", "messages", "l10n", "intl"];
+
+/// Languages searched for translation key references and hardcoded strings
+const CODE_LANGUAGES: &[&str] = &["javascript", "typescript", "python", "rust", "go", "java", "kotlin", "swift", "dart", "ruby", "php", "csharp", "html"];
+
+/// Locale name given to gettext `.pot` templates
+const TEMPLATE_LOCALE: &str = "template";
+
+/// How translations are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceFormat {
+    /// Nested JSON objects, as used by i18next, vue-i18n and react-intl
+    Json,
+    /// gettext `.po` catalogs and `.pot` templates
+    Po,
+    /// Flutter Application Resource Bundles
+    Arb,
+}
+
+impl ResourceFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResourceFormat::Json => "JSON",
+            ResourceFormat::Po => "gettext",
+            ResourceFormat::Arb => "ARB",
+        }
+    }
+}
+
+/// How completely one locale translates a bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocaleCoverage {
+    pub locale: String,
+    /// Relative to the project root
+    pub file: PathBuf,
+    pub keys: usize,
+    /// Keys other locales of the bundle have and this one lacks
+    pub missing: Vec<String>,
+    /// Keys present with an empty translation
+    pub untranslated: Vec<String>,
+}
+
+/// The same set of messages translated into several locales, e.g.
+/// `locales/*/common.json` or `lib/l10n/app_*.arb`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationBundle {
+    /// Resource path with the locale replaced by `*`
+    pub name: String,
+    pub format: ResourceFormat,
+    /// Locale the others are read against: the gettext template, English, or the largest
+    pub reference_locale: String,
+    pub locales: Vec<LocaleCoverage>,
+    /// Keys no string literal in the code mentions; keys built at runtime can't be seen
+    pub unused_keys: Vec<String>,
+}
+
+/// Text shown to users that bypasses the translation layer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardcodedString {
+    /// Relative to the project root
+    pub file: PathBuf,
+    pub line: usize,
+    pub text: String,
+}
+
+/// Translation resources compared across locales, plus user-facing strings
+/// left in the code of a project that has them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct I18nReport {
+    pub bundles: Vec<TranslationBundle>,
+    pub hardcoded_strings: Vec<HardcodedString>,
+}
+
+impl I18nReport {
+    pub fn is_empty(&self) -> bool {
+        self.bundles.is_empty()
+    }
+
+    pub fn locales(&self) -> BTreeSet<&str> {
+        self.bundles.iter().flat_map(|bundle| bundle.locales.iter().map(|locale| locale.locale.as_str())).collect()
+    }
+
+    /// Missing translations across all bundles and locales
+    pub fn missing_count(&self) -> usize {
+        self.bundles.iter().flat_map(|bundle| &bundle.locales).map(|locale| locale.missing.len()).sum()
+    }
+
+    pub fn unused_count(&self) -> usize {
+        self.bundles.iter().map(|bundle| bundle.unused_keys.len()).sum()
+    }
+}
+
+/// One resource file's messages; the flag is set when the message has a translation
+struct Resource {
+    bundle: String,
+    format: ResourceFormat,
+    locale: String,
+    file: PathBuf,
+    entries: BTreeMap<String, bool>,
+}
+
+/// Finds JSON, gettext and ARB translation resources, compares their keys
+/// across locales and looks for keys and user-facing text in the code
+pub struct I18nAnalyzer {
+    root: PathBuf,
+    locale: Regex,
+    arb_locale: Regex,
+    string_literal: Regex,
+    template_prefix: Regex,
+    member: Regex,
+    markup_text: Regex,
+    text_attribute: Regex,
+}
+
+impl I18nAnalyzer {
+    pub fn new(root: &Path) -> Self {
+        let regex = |pattern: &str| Regex::new(pattern).expect("valid i18n pattern");
+        Self {
+            root: root.to_path_buf(),
+            locale: regex(r"^[a-z]{2}(?:[-_](?:[A-Z]{2}|[A-Z][a-z]{3}|\d{3}))*$"),
+            arb_locale: regex(r"_([a-z]{2}(?:_(?:[A-Z]{2}|[A-Z][a-z]{3}))*)$"),
+            string_literal: regex(r#""((?:[^"\\]|\\.)*)"|'((?:[^'\\]|\\.)*)'"#),
+            template_prefix: regex(r"`([\w.:-]+)\$\{"),
+            member: regex(r"\.(\w+)"),
+            markup_text: regex(r">([^<>{}]*[A-Za-z][^<>{}]*)<"),
+            text_attribute: regex(r#"\b(?:placeholder|title|alt|aria-label|label)\s*=\s*"([^"{}]*[A-Za-z][^"{}]*)""#),
+        }
+    }
+
+    pub fn analyze(&self, files: &[FileInfo], sources: &SourceSet) -> I18nReport {
+        // .po and .arb files aren't among the analyzed extensions
+        let candidates = if sources.is_in_memory() {
+            files.iter().map(|f| f.path.clone()).collect()
+        } else {
+            self.walk()
+        };
+        let mut resources: Vec<Resource> = candidates.iter()
+            .filter_map(|path| self.resource(path, sources))
+            .collect();
+        if resources.is_empty() {
+            return I18nReport::default();
+        }
+
+        // A `.pot` template joins the catalogs beside it: `locale/messages.pot` goes
+        // with `locale/*/LC_MESSAGES/messages.po` or `locale/*.po`
+        let catalogs: BTreeSet<String> = resources.iter()
+            .filter(|r| r.format == ResourceFormat::Po && r.locale != TEMPLATE_LOCALE)
+            .map(|r| r.bundle.clone())
+            .collect();
+        for template in resources.iter_mut().filter(|r| r.locale == TEMPLATE_LOCALE) {
+            let directory = template.file.parent().unwrap_or(Path::new("")).join("*");
+            let stem = template.file.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+            let beside = |catalog: &&String| Path::new(catalog.as_str()).starts_with(&directory);
+            if let Some(catalog) = catalogs.iter().filter(beside).find(|catalog| Path::new(catalog.as_str()).ends_with(&stem))
+                .or_else(|| catalogs.iter().find(|catalog| Path::new(catalog.as_str()) == directory)) {
+                template.bundle = catalog.clone();
+            }
+        }
+        let resource_files: BTreeSet<PathBuf> = resources.iter().map(|r| self.root.join(&r.file)).collect();
+
+        let code_files: Vec<&FileInfo> = files.iter()
+            .filter(|file| file.language.as_deref().is_some_and(|language| CODE_LANGUAGES.contains(&language)))
+            .filter(|file| !resource_files.contains(&file.path))
+            .collect();
+        let mut literals = BTreeSet::new();
+        let mut prefixes = BTreeSet::new();
+        let mut members = BTreeSet::new();
+        let mut hardcoded_strings = Vec::new();
+        for file in &code_files {
+            let Ok(content) = sources.read(&file.path) else { continue };
+            // Line by line, so an apostrophe in a comment can't pair quotes across lines
+            for captures in content.lines().flat_map(|line| self.string_literal.captures_iter(line)) {
+                literals.extend(captures.get(1).or(captures.get(2)).map(|m| m.as_str().to_string()));
+            }
+            prefixes.extend(self.template_prefix.captures_iter(&content).map(|captures| captures[1].to_string()));
+            if file.language.as_deref() == Some("dart") {
+                members.extend(self.member.captures_iter(&content).map(|captures| captures[1].to_string()));
+            }
+            hardcoded_strings.extend(self.hardcoded_strings(file, &content));
+        }
+
+        let mut grouped: BTreeMap<(String, ResourceFormat), Vec<Resource>> = BTreeMap::new();
+        for resource in resources {
+            grouped.entry((resource.bundle.clone(), resource.format)).or_default().push(resource);
+        }
+        let bundles = grouped.into_iter()
+            .map(|((name, format), mut resources)| {
+                resources.sort_by(|a, b| a.locale.cmp(&b.locale));
+                let all_keys: BTreeSet<&String> = resources.iter().flat_map(|r| r.entries.keys()).collect();
+                let reference_locale = resources.iter().find(|r| r.locale == TEMPLATE_LOCALE)
+                    .or_else(|| resources.iter().find(|r| r.locale == "en" || r.locale.starts_with("en-") || r.locale.starts_with("en_")))
+                    .or_else(|| resources.iter().max_by_key(|r| r.entries.len()))
+                    .map(|r| r.locale.clone())
+                    .unwrap_or_default();
+                let namespace = Path::new(&name).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                // ARB keys are only read from Dart; don't blame them on a JavaScript front end
+                let consumers_scanned = match format {
+                    ResourceFormat::Arb => code_files.iter().any(|file| file.language.as_deref() == Some("dart")),
+                    _ => !code_files.is_empty(),
+                };
+                let unused_keys = if !consumers_scanned {
+                    Vec::new()
+                } else {
+                    let used = |key: &str| literals.contains(key)
+                        || literals.contains(&format!("{}:{}", namespace, key))
+                        || prefixes.iter().any(|prefix| key.starts_with(prefix.rsplit(':').next().unwrap_or(prefix)))
+                        || (format == ResourceFormat::Arb && members.contains(key));
+                    all_keys.iter()
+                        .filter(|key| !used(key.as_str()))
+                        .map(|key| key.to_string())
+                        .collect()
+                };
+                let locales = resources.iter()
+                    .map(|resource| LocaleCoverage {
+                        locale: resource.locale.clone(),
+                        file: resource.file.clone(),
+                        keys: resource.entries.len(),
+                        missing: all_keys.iter().filter(|key| !resource.entries.contains_key(key.as_str())).map(|key| key.to_string()).collect(),
+                        // A template's msgstrs are empty by design
+                        untranslated: if resource.locale == TEMPLATE_LOCALE {
+                            Vec::new()
+                        } else {
+                            resource.entries.iter().filter(|(_, translated)| !**translated).map(|(key, _)| key.clone()).collect()
+                        },
+                    })
+                    .collect();
+                TranslationBundle { name, format, reference_locale, locales, unused_keys }
+            })
+            .collect();
+
+        I18nReport { bundles, hardcoded_strings }
+    }
+
+    fn walk(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = WalkBuilder::new(&self.root)
+            .build()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.into_path())
+            .filter(|path| path.is_file() && !path.components().any(|c| c.as_os_str() == "node_modules"))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// Read a translation resource; the locale comes from the file name or a
+    /// directory above it, and is replaced by `*` in the bundle name
+    fn resource(&self, path: &Path, sources: &SourceSet) -> Option<Resource> {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path).to_path_buf();
+        let extension = relative.extension()?.to_str()?;
+        let stem = relative.file_stem()?.to_string_lossy().to_string();
+        let mut components: Vec<String> = relative.with_extension("").iter().map(|c| c.to_string_lossy().to_string()).collect();
+
+        let (format, locale) = match extension {
+            "arb" => {
+                let locale = self.arb_locale.captures(&stem).map(|captures| captures[1].to_string())?;
+                let last = components.last_mut()?;
+                *last = format!("{}*", &stem[..stem.len() - locale.len()]);
+                (ResourceFormat::Arb, locale)
+            }
+            "po" | "pot" | "json" => {
+                let format = if extension == "json" { ResourceFormat::Json } else { ResourceFormat::Po };
+                if format == ResourceFormat::Json && !components.iter().any(|c| RESOURCE_DIRECTORIES.contains(&c.as_str())) {
+                    return None;
+                }
+                if extension == "pot" {
+                    (format, TEMPLATE_LOCALE.to_string())
+                } else {
+                    // Nearest component naming a locale: `fr.json`, `locales/fr/common.json`, `fr/LC_MESSAGES/app.po`
+                    let index = components.iter().rposition(|c| self.locale.is_match(c))?;
+                    let locale = std::mem::replace(&mut components[index], "*".to_string());
+                    (format, locale)
+                }
+            }
+            _ => return None,
+        };
+
+        let content = sources.read(path).ok()?;
+        let entries = match format {
+            ResourceFormat::Json => {
+                let document: serde_json::Value = serde_json::from_str(&content).ok()?;
+                let mut entries = BTreeMap::new();
+                Self::flatten(&document, "", &mut entries);
+                entries
+            }
+            ResourceFormat::Arb => {
+                let document: serde_json::Value = serde_json::from_str(&content).ok()?;
+                document.as_object()?.iter()
+                    .filter(|(key, _)| !key.starts_with('@'))
+                    .map(|(key, value)| (key.clone(), value.as_str().is_some_and(|text| !text.is_empty())))
+                    .collect()
+            }
+            ResourceFormat::Po => Self::po_entries(&content),
+        };
+        if entries.is_empty() {
+            return None;
+        }
+        let bundle: PathBuf = components.iter().collect();
+        Some(Resource { bundle: bundle.to_string_lossy().to_string(), format, locale, file: relative, entries })
+    }
+
+    /// Nested keys joined with `.`; the flag is set for non-empty strings
+    fn flatten(value: &serde_json::Value, prefix: &str, entries: &mut BTreeMap<String, bool>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, nested) in map {
+                    let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                    Self::flatten(nested, &key, entries);
+                }
+            }
+            serde_json::Value::String(text) if !prefix.is_empty() => {
+                entries.insert(prefix.to_string(), !text.is_empty());
+            }
+            _ if !prefix.is_empty() => {
+                entries.insert(prefix.to_string(), true);
+            }
+            _ => {}
+        }
+    }
+
+    /// `msgid` to whether any `msgstr` is non-empty; strings continue on
+    /// following quoted lines, and the header entry (empty `msgid`) is skipped
+    fn po_entries(content: &str) -> BTreeMap<String, bool> {
+        #[derive(PartialEq)]
+        enum Part { Id, Translation, Other }
+        let unquote = |text: &str| text.trim().trim_start_matches('"').trim_end_matches('"').to_string();
+
+        let mut entries = BTreeMap::new();
+        let mut id: Option<String> = None;
+        let mut translation = String::new();
+        let mut part = Part::Other;
+        let mut flush = |id: &mut Option<String>, translation: &mut String| {
+            if let Some(id) = id.take().filter(|id| !id.is_empty()) {
+                entries.insert(id, !translation.is_empty());
+            }
+            translation.clear();
+        };
+        for line in content.lines().map(str::trim) {
+            if line.starts_with('#') || line.is_empty() {
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("msgid ") {
+                flush(&mut id, &mut translation);
+                id = Some(unquote(rest));
+                part = Part::Id;
+            } else if line.starts_with("msgid_plural") || line.starts_with("msgctxt") {
+                part = Part::Other;
+            } else if line.starts_with("msgstr") {
+                translation.push_str(&unquote(line.split_once(' ').map_or("", |(_, rest)| rest)));
+                part = Part::Translation;
+            } else if line.starts_with('"') {
+                match part {
+                    Part::Id => id.get_or_insert_with(String::new).push_str(&unquote(line)),
+                    Part::Translation => translation.push_str(&unquote(line)),
+                    Part::Other => {}
+                }
+            }
+        }
+        flush(&mut id, &mut translation);
+        entries
+    }
+
+    /// Text between markup tags and in text attributes (`placeholder`, `title`,
+    /// `alt`, `aria-label`, `label`) of JSX, TSX and HTML files; single words
+    /// are skipped as likely identifiers or icon names
+    fn hardcoded_strings(&self, file: &FileInfo, content: &str) -> Vec<HardcodedString> {
+        let extension = file.path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+        if !matches!(extension, "jsx" | "tsx" | "html" | "htm" | "vue" | "svelte") {
+            return Vec::new();
+        }
+        let relative = file.path.strip_prefix(&self.root).unwrap_or(&file.path);
+        let path = relative.to_string_lossy();
+        if path.contains("test") || path.contains("spec") || path.contains("stories") {
+            return Vec::new();
+        }
+
+        let mut strings = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("//") || trimmed.starts_with("<!--") || trimmed.starts_with('*') {
+                continue;
+            }
+            let markup = line.contains("</").then(|| self.markup_text.captures_iter(line)).into_iter().flatten();
+            for captures in markup.chain(self.text_attribute.captures_iter(line)) {
+                let text = captures[1].trim();
+                let words = text.split_whitespace().filter(|word| word.chars().any(char::is_alphabetic)).count();
+                if words >= 2 && !text.contains(['=', ';', '(', '&', '|']) {
+                    strings.push(HardcodedString { file: relative.to_path_buf(), line: index + 1, text: text.to_string() });
+                }
+            }
+        }
+        strings
+    }
+}
@@ -0,0 +1,592 @@
+use crate::{file_discovery::FileInfo, sources::SourceSet};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Resources and dependencies listed in the LLM context
+const MAX_CONTEXT_ITEMS: usize = 60;
+
+/// Workload kinds whose pod template labels a Service selector can match
+const WORKLOAD_KINDS: &[&str] = &["Deployment", "StatefulSet", "DaemonSet", "ReplicaSet", "Job", "CronJob", "Pod"];
+
+/// Settings that commonly expose infrastructure, as (rule, pattern, what it means)
+const RISK_PATTERNS: &[(&str, &str, &str)] = &[
+    ("open-ingress", r"0\.0\.0\.0/0|::/0", "allows traffic from any address"),
+    ("public-bucket", r#"(?i)\bacl"?\s*[=:]\s*"?public-read|AccessControl"?\s*:\s*"?PublicRead"#, "grants public read access"),
+    ("unencrypted-storage", r#"(?i)\b(?:storage_)?encrypted"?\s*[=:]\s*"?false\b"#, "storage is not encrypted at rest"),
+    ("publicly-accessible", r#"(?i)\bpublicly_?accessible"?\s*[=:]\s*"?true\b"#, "the database is reachable from outside its network"),
+    ("privileged-container", r#"\b(?:privileged|allowPrivilegeEscalation|hostNetwork|hostPID)"?\s*:\s*true\b"#, "the container can escape its isolation"),
+    ("root-container", r#"\brunAsUser"?\s*:\s*0\b|\brunAsNonRoot"?\s*:\s*false\b"#, "the container runs as root"),
+    ("hardcoded-secret", r#"(?i)\b(?:password|secret|secret_key|access_key)"?\s*[=:]\s*"[^"${]{4,}""#, "a credential is written in the file"),
+];
+
+/// Kind of infrastructure-as-code file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IacFormat {
+    Terraform,
+    CloudFormation,
+    Kubernetes,
+}
+
+impl IacFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            IacFormat::Terraform => "Terraform",
+            IacFormat::CloudFormation => "CloudFormation",
+            IacFormat::Kubernetes => "Kubernetes",
+        }
+    }
+}
+
+/// A resource, data source or object declared in an IaC file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IacResource {
+    pub format: IacFormat,
+    /// Terraform `aws_s3_bucket.logs` or `data.aws_ami.ubuntu`, a CloudFormation
+    /// logical ID, or Kubernetes `Kind/name`
+    pub address: String,
+    /// `aws_s3_bucket`, `AWS::S3::Bucket` or `Deployment`
+    pub kind: String,
+    /// Relative to the project root
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// A Terraform module call or CloudFormation nested stack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IacModuleReference {
+    pub format: IacFormat,
+    pub name: String,
+    /// Registry address, path, git URL or template URL, as written
+    pub source: String,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// One resource referring to another: a Terraform reference or `depends_on`,
+/// CloudFormation `Ref`, `Fn::GetAtt`, `Fn::Sub` or `DependsOn`, or a Kubernetes
+/// object named in another's spec or matched by a Service selector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IacDependency {
+    pub from: String,
+    pub to: String,
+    pub file: PathBuf,
+}
+
+/// A setting that commonly exposes infrastructure, found by line patterns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IacRisk {
+    pub file: PathBuf,
+    pub line: usize,
+    pub rule: String,
+    pub detail: String,
+}
+
+/// Resources, providers, module references and dependencies declared by
+/// Terraform, CloudFormation and Kubernetes files
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InfrastructureInventory {
+    pub resources: Vec<IacResource>,
+    /// Provider names, e.g. `aws` or `kubernetes`, with the files using them
+    pub providers: BTreeMap<String, Vec<PathBuf>>,
+    pub modules: Vec<IacModuleReference>,
+    pub dependencies: Vec<IacDependency>,
+    pub risks: Vec<IacRisk>,
+}
+
+impl InfrastructureInventory {
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty() && self.modules.is_empty()
+    }
+
+    /// IaC files, risky settings first
+    pub fn files(&self) -> Vec<&Path> {
+        let mut files: Vec<&Path> = Vec::new();
+        let declared = self.resources.iter().map(|r| &r.file).chain(self.modules.iter().map(|m| &m.file));
+        for file in self.risks.iter().map(|risk| &risk.file).chain(declared) {
+            if !files.contains(&file.as_path()) {
+                files.push(file);
+            }
+        }
+        files
+    }
+
+    /// Resource counts by kind, most first, as (format, kind, count)
+    pub fn kinds(&self) -> Vec<(IacFormat, &str, usize)> {
+        let mut kinds: BTreeMap<(IacFormat, &str), usize> = BTreeMap::new();
+        for resource in &self.resources {
+            *kinds.entry((resource.format, resource.kind.as_str())).or_insert(0) += 1;
+        }
+        let mut kinds: Vec<(IacFormat, &str, usize)> = kinds.into_iter().map(|((format, kind), count)| (format, kind, count)).collect();
+        kinds.sort_by_key(|(_, _, count)| std::cmp::Reverse(*count));
+        kinds
+    }
+
+    /// For the IacSecurity LLM analysis
+    pub fn context_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (name, files) in &self.providers {
+            lines.push(format!("Provider {} used in {}", name, files.iter().map(|f| f.display().to_string()).collect::<Vec<_>>().join(", ")));
+        }
+        for module in &self.modules {
+            lines.push(format!("Module {} from {} ({}:{})", module.name, module.source, module.file.display(), module.line));
+        }
+        for risk in &self.risks {
+            lines.push(format!("Risk {}:{} [{}] {}", risk.file.display(), risk.line, risk.rule, risk.detail));
+        }
+        for resource in self.resources.iter().take(MAX_CONTEXT_ITEMS) {
+            lines.push(format!("{} resource {} ({}) at {}:{}", resource.format.name(), resource.address, resource.kind, resource.file.display(), resource.line));
+        }
+        if self.resources.len() > MAX_CONTEXT_ITEMS {
+            lines.push(format!("…and {} more resources", self.resources.len() - MAX_CONTEXT_ITEMS));
+        }
+        for dependency in self.dependencies.iter().take(MAX_CONTEXT_ITEMS) {
+            lines.push(format!("Dependency {} -> {} ({})", dependency.from, dependency.to, dependency.file.display()));
+        }
+        if self.dependencies.len() > MAX_CONTEXT_ITEMS {
+            lines.push(format!("…and {} more dependencies", self.dependencies.len() - MAX_CONTEXT_ITEMS));
+        }
+        lines
+    }
+}
+
+/// A Kubernetes object's labels or Service selector, for matching after every file is read
+struct KubernetesLabels {
+    address: String,
+    file: PathBuf,
+    labels: BTreeMap<String, String>,
+}
+
+/// Parses Terraform/HCL, CloudFormation and Kubernetes manifests. Terraform is
+/// read line by line; YAML and JSON templates are parsed as documents.
+pub struct InfrastructureAnalyzer {
+    root: PathBuf,
+    terraform_block: Regex,
+    terraform_source: Regex,
+    terraform_provider: Regex,
+    terraform_reference: Regex,
+    substitution: Regex,
+    risks: Vec<(&'static str, Regex, &'static str)>,
+}
+
+impl InfrastructureAnalyzer {
+    pub fn new(root: &Path) -> Self {
+        let regex = |pattern: &str| Regex::new(pattern).expect("valid infrastructure pattern");
+        Self {
+            root: root.to_path_buf(),
+            terraform_block: regex(r#"^\s*(resource|data|module|provider|terraform)\s*(?:"([^"]+)")?\s*(?:"([^"]+)")?\s*\{"#),
+            terraform_source: regex(r#"^\s*source\s*=\s*"([^"]+)""#),
+            terraform_provider: regex(r"^\s*([\w-]+)\s*=\s*\{"),
+            terraform_reference: regex(r"\b(data\.[\w-]+\.[\w-]+|module\.[\w-]+|[a-z][a-z0-9]*_[\w-]+\.[\w-]+)"),
+            substitution: regex(r"\$\{([\w]+)"),
+            risks: RISK_PATTERNS.iter().map(|(rule, pattern, detail)| (*rule, regex(pattern), *detail)).collect(),
+        }
+    }
+
+    pub fn analyze(&self, files: &[FileInfo], sources: &SourceSet) -> InfrastructureInventory {
+        let mut inventory = InfrastructureInventory::default();
+        // Terraform references resolve within a module directory, CloudFormation ones within a template
+        let mut terraform_references: Vec<(PathBuf, String, String)> = Vec::new();
+        let mut labels = Vec::new();
+        let mut selectors = Vec::new();
+
+        for file in files {
+            let language = file.language.as_deref();
+            if !matches!(language, Some("terraform" | "yaml" | "json")) {
+                continue;
+            }
+            let Ok(content) = sources.read(&file.path) else { continue };
+            let relative = file.path.strip_prefix(&self.root).unwrap_or(&file.path).to_path_buf();
+
+            let recognized = match language {
+                Some("terraform") => {
+                    terraform_references.extend(self.terraform(&relative, &content, &mut inventory));
+                    true
+                }
+                _ => self.cloudformation(&relative, &content, &mut inventory)
+                    || (language == Some("yaml") && self.kubernetes(&relative, &content, &mut inventory, &mut labels, &mut selectors)),
+            };
+            if recognized {
+                self.find_risks(&relative, &content, &mut inventory.risks);
+            }
+        }
+
+        let declared: HashSet<(PathBuf, &str)> = inventory.resources.iter()
+            .filter(|resource| resource.format == IacFormat::Terraform)
+            .map(|resource| (Self::directory(&resource.file), resource.address.as_str()))
+            .collect();
+        let modules: HashSet<(PathBuf, String)> = inventory.modules.iter()
+            .filter(|module| module.format == IacFormat::Terraform)
+            .map(|module| (Self::directory(&module.file), format!("module.{}", module.name)))
+            .collect();
+        let terraform_dependencies: Vec<IacDependency> = terraform_references.into_iter()
+            .filter(|(file, _, to)| declared.contains(&(Self::directory(file), to.as_str()))
+                || modules.contains(&(Self::directory(file), to.clone())))
+            .map(|(file, from, to)| IacDependency { from, to, file })
+            .collect();
+        inventory.dependencies.extend(terraform_dependencies);
+
+        for selector in &selectors {
+            for workload in &labels {
+                if selector.labels.iter().all(|(key, value)| workload.labels.get(key) == Some(value)) {
+                    inventory.dependencies.push(IacDependency {
+                        from: selector.address.clone(),
+                        to: workload.address.clone(),
+                        file: selector.file.clone(),
+                    });
+                }
+            }
+        }
+
+        let mut seen = HashSet::new();
+        inventory.dependencies.retain(|dependency| dependency.from != dependency.to
+            && seen.insert((dependency.from.clone(), dependency.to.clone(), dependency.file.clone())));
+        inventory.risks.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+        inventory
+    }
+
+    fn directory(file: &Path) -> PathBuf {
+        file.parent().map(Path::to_path_buf).unwrap_or_default()
+    }
+
+    /// Blocks are tracked by brace depth; returns (file, from, to) for every
+    /// reference-shaped token inside a resource, data or module block
+    fn terraform(&self, file: &Path, content: &str, inventory: &mut InfrastructureInventory) -> Vec<(PathBuf, String, String)> {
+        let mut references = Vec::new();
+        let mut depth: i64 = 0;
+        // (block type, address, line)
+        let mut block: Option<(String, String, usize)> = None;
+        let mut module_source: Option<String> = None;
+        let mut in_required_providers = false;
+
+        for (index, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with('#') || trimmed.starts_with("//") {
+                continue;
+            }
+            if depth == 0 {
+                if let Some(captures) = self.terraform_block.captures(line) {
+                    let kind = captures[1].to_string();
+                    let first = captures.get(2).map(|m| m.as_str().to_string());
+                    let second = captures.get(3).map(|m| m.as_str().to_string());
+                    let address = match (kind.as_str(), &first, &second) {
+                        ("resource", Some(kind), Some(name)) => format!("{}.{}", kind, name),
+                        ("data", Some(kind), Some(name)) => format!("data.{}.{}", kind, name),
+                        ("module", Some(name), _) => format!("module.{}", name),
+                        _ => String::new(),
+                    };
+                    match (kind.as_str(), &first) {
+                        ("resource" | "data", Some(resource_kind)) if !address.is_empty() => {
+                            inventory.resources.push(IacResource {
+                                format: IacFormat::Terraform,
+                                address: address.clone(),
+                                kind: resource_kind.clone(),
+                                file: file.to_path_buf(),
+                                line: index + 1,
+                            });
+                            let provider = resource_kind.split('_').next().unwrap_or(resource_kind);
+                            Self::add_provider(inventory, provider, file);
+                        }
+                        ("provider", Some(provider)) => Self::add_provider(inventory, provider, file),
+                        _ => {}
+                    }
+                    block = Some((kind, address, index + 1));
+                    module_source = None;
+                }
+            } else if let Some((kind, address, _)) = &block {
+                match kind.as_str() {
+                    "module" if depth == 1 => {
+                        if let Some(captures) = self.terraform_source.captures(line) {
+                            module_source = Some(captures[1].to_string());
+                        }
+                    }
+                    "terraform" => {
+                        if depth == 1 && trimmed.starts_with("required_providers") {
+                            in_required_providers = true;
+                        } else if in_required_providers && depth == 2 {
+                            if let Some(captures) = self.terraform_provider.captures(line) {
+                                Self::add_provider(inventory, &captures[1], file);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                if !address.is_empty() {
+                    for captures in self.terraform_reference.captures_iter(line) {
+                        let reference = &captures[1];
+                        // `aws_s3_bucket.logs.arn` names `aws_s3_bucket.logs`
+                        let parts = if reference.starts_with("data.") { 3 } else { 2 };
+                        let target = reference.splitn(parts + 1, '.').take(parts).collect::<Vec<_>>().join(".");
+                        if target != *address {
+                            references.push((file.to_path_buf(), address.clone(), target));
+                        }
+                    }
+                }
+            }
+
+            depth += line.matches('{').count() as i64 - line.matches('}').count() as i64;
+            if depth <= 1 {
+                in_required_providers = false;
+            }
+            if depth <= 0 {
+                depth = 0;
+                if let Some((kind, address, line)) = block.take() {
+                    if kind == "module" {
+                        inventory.modules.push(IacModuleReference {
+                            format: IacFormat::Terraform,
+                            name: address.trim_start_matches("module.").to_string(),
+                            source: module_source.take().unwrap_or_default(),
+                            file: file.to_path_buf(),
+                            line,
+                        });
+                    }
+                }
+            }
+        }
+        references
+    }
+
+    /// A YAML or JSON template with AWS resource types; false for other files
+    fn cloudformation(&self, file: &Path, content: &str, inventory: &mut InfrastructureInventory) -> bool {
+        if !content.contains("AWSTemplateFormatVersion") && !content.contains("AWS::") {
+            return false;
+        }
+        let Ok(document) = serde_yaml::from_str::<Value>(content) else { return false };
+        let Some(resources) = document.get("Resources").and_then(Value::as_mapping) else { return false };
+        let declared: HashMap<&str, &Value> = resources.iter()
+            .filter_map(|(id, resource)| Some((id.as_str()?, resource)))
+            .filter(|(_, resource)| resource.get("Type").and_then(Value::as_str).is_some_and(|kind| kind.contains("::")))
+            .collect();
+        if declared.is_empty() {
+            return false;
+        }
+
+        let mut ids: Vec<&&str> = declared.keys().collect();
+        ids.sort();
+        for id in ids {
+            let resource = declared[*id];
+            let kind = resource.get("Type").and_then(Value::as_str).unwrap_or_default();
+            let line = Self::line_of(content, id);
+            inventory.resources.push(IacResource {
+                format: IacFormat::CloudFormation,
+                address: id.to_string(),
+                kind: kind.to_string(),
+                file: file.to_path_buf(),
+                line,
+            });
+            if let Some(provider) = kind.split("::").next() {
+                Self::add_provider(inventory, &provider.to_lowercase(), file);
+            }
+            let nested = match kind {
+                "AWS::CloudFormation::Stack" => resource.get("Properties").and_then(|p| p.get("TemplateURL")),
+                "AWS::Serverless::Application" => resource.get("Properties").and_then(|p| p.get("Location")),
+                _ => None,
+            };
+            if let Some(source) = nested {
+                inventory.modules.push(IacModuleReference {
+                    format: IacFormat::CloudFormation,
+                    name: id.to_string(),
+                    source: source.as_str().map(String::from).unwrap_or_else(|| "(computed)".to_string()),
+                    file: file.to_path_buf(),
+                    line,
+                });
+            }
+
+            let mut targets = Vec::new();
+            if let Some(depends_on) = resource.get("DependsOn") {
+                targets.extend(depends_on.as_str().map(String::from));
+                targets.extend(depends_on.as_sequence().into_iter().flatten().filter_map(|v| v.as_str().map(String::from)));
+            }
+            self.cloudformation_references(resource, &mut targets);
+            for target in targets {
+                if declared.contains_key(target.as_str()) {
+                    inventory.dependencies.push(IacDependency { from: id.to_string(), to: target, file: file.to_path_buf() });
+                }
+            }
+        }
+        true
+    }
+
+    /// Logical IDs named by `Ref`, `Fn::GetAtt` and `Fn::Sub`, in long or short (`!Ref`) form
+    fn cloudformation_references(&self, value: &Value, targets: &mut Vec<String>) {
+        let mut reference = |function: &str, argument: &Value| match function {
+            "Ref" => targets.extend(argument.as_str().map(String::from)),
+            "Fn::GetAtt" | "GetAtt" => {
+                let target = argument.as_str().and_then(|a| a.split('.').next())
+                    .or_else(|| argument.as_sequence().and_then(|s| s.first()).and_then(Value::as_str));
+                targets.extend(target.map(String::from));
+            }
+            "Fn::Sub" | "Sub" => {
+                let template = argument.as_str().or_else(|| argument.as_sequence().and_then(|s| s.first()).and_then(Value::as_str));
+                for captures in template.into_iter().flat_map(|t| self.substitution.captures_iter(t)) {
+                    targets.push(captures[1].to_string());
+                }
+            }
+            _ => {}
+        };
+        match value {
+            Value::Mapping(mapping) => {
+                for (key, child) in mapping {
+                    if let Some(key) = key.as_str() {
+                        reference(key, child);
+                    }
+                }
+                for child in mapping.values() {
+                    self.cloudformation_references(child, targets);
+                }
+            }
+            Value::Sequence(sequence) => {
+                for child in sequence {
+                    self.cloudformation_references(child, targets);
+                }
+            }
+            Value::Tagged(tagged) => {
+                let tag = tagged.tag.to_string();
+                reference(tag.trim_start_matches('!'), &tagged.value);
+                self.cloudformation_references(&tagged.value, targets);
+            }
+            _ => {}
+        }
+    }
+
+    /// Every `---`-separated document with `apiVersion`, `kind` and `metadata.name`;
+    /// false when the file has none, e.g. CI workflows or Helm templates
+    fn kubernetes(&self, file: &Path, content: &str, inventory: &mut InfrastructureInventory,
+        labels: &mut Vec<KubernetesLabels>, selectors: &mut Vec<KubernetesLabels>) -> bool {
+        if !content.contains("apiVersion") || !content.contains("kind") {
+            return false;
+        }
+        let mut found = false;
+        let mut start = 0;
+        let lines: Vec<&str> = content.lines().collect();
+        for end in (0..=lines.len()).filter(|&i| i == lines.len() || lines[i].starts_with("---")) {
+            let document = lines[start..end].join("\n");
+            let offset = start;
+            start = end + 1;
+            let Ok(value) = serde_yaml::from_str::<Value>(&document) else { continue };
+            let (Some(kind), Some(name)) = (value.get("kind").and_then(Value::as_str),
+                value.get("metadata").and_then(|m| m.get("name")).and_then(Value::as_str)) else { continue };
+            if value.get("apiVersion").is_none() {
+                continue;
+            }
+            found = true;
+            let address = format!("{}/{}", kind, name);
+            inventory.resources.push(IacResource {
+                format: IacFormat::Kubernetes,
+                address: address.clone(),
+                kind: kind.to_string(),
+                file: file.to_path_buf(),
+                line: offset + Self::line_of(&document, "kind"),
+            });
+            Self::add_provider(inventory, "kubernetes", file);
+
+            let mut targets = Vec::new();
+            Self::kubernetes_references(&value, &mut targets);
+            for target in targets {
+                inventory.dependencies.push(IacDependency { from: address.clone(), to: target, file: file.to_path_buf() });
+            }
+
+            let spec = value.get("spec");
+            let pod_labels = if kind == "Pod" {
+                value.get("metadata").and_then(|m| m.get("labels"))
+            } else if kind == "CronJob" {
+                spec.and_then(|s| s.get("jobTemplate")).and_then(|j| j.get("spec")).and_then(|s| s.get("template"))
+                    .and_then(|t| t.get("metadata")).and_then(|m| m.get("labels"))
+            } else {
+                spec.and_then(|s| s.get("template")).and_then(|t| t.get("metadata")).and_then(|m| m.get("labels"))
+            };
+            if WORKLOAD_KINDS.contains(&kind) {
+                if let Some(map) = pod_labels.and_then(Self::string_map) {
+                    labels.push(KubernetesLabels { address: address.clone(), file: file.to_path_buf(), labels: map });
+                }
+            }
+            if kind == "Service" {
+                if let Some(map) = spec.and_then(|s| s.get("selector")).and_then(Self::string_map).filter(|m| !m.is_empty()) {
+                    selectors.push(KubernetesLabels { address, file: file.to_path_buf(), labels: map });
+                }
+            }
+        }
+        found
+    }
+
+    /// Objects a spec names: ConfigMaps, Secrets, ServiceAccounts, volume claims,
+    /// Ingress backends and autoscaler targets, as `Kind/name`
+    fn kubernetes_references(value: &Value, targets: &mut Vec<String>) {
+        let name = |value: &Value, key: &str| value.get(key).and_then(Value::as_str).map(String::from);
+        match value {
+            Value::Mapping(mapping) => {
+                for (key, child) in mapping {
+                    let target = match key.as_str().unwrap_or_default() {
+                        "configMapKeyRef" | "configMapRef" | "configMap" => name(child, "name").map(|n| format!("ConfigMap/{}", n)),
+                        "secretKeyRef" | "secretRef" => name(child, "name").map(|n| format!("Secret/{}", n)),
+                        "secret" => name(child, "secretName").map(|n| format!("Secret/{}", n)),
+                        "serviceAccountName" => child.as_str().map(|n| format!("ServiceAccount/{}", n)),
+                        "persistentVolumeClaim" => name(child, "claimName").map(|n| format!("PersistentVolumeClaim/{}", n)),
+                        "backend" => child.get("service").and_then(|s| name(s, "name")).or_else(|| name(child, "serviceName"))
+                            .map(|n| format!("Service/{}", n)),
+                        "scaleTargetRef" => name(child, "kind").zip(name(child, "name")).map(|(kind, n)| format!("{}/{}", kind, n)),
+                        _ => None,
+                    };
+                    targets.extend(target);
+                    Self::kubernetes_references(child, targets);
+                }
+            }
+            Value::Sequence(sequence) => {
+                for child in sequence {
+                    Self::kubernetes_references(child, targets);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn string_map(value: &Value) -> Option<BTreeMap<String, String>> {
+        Some(value.as_mapping()?.iter()
+            .filter_map(|(key, value)| Some((key.as_str()?.to_string(), match value {
+                Value::String(s) => s.clone(),
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                _ => return None,
+            })))
+            .collect())
+    }
+
+    fn find_risks(&self, file: &Path, content: &str, risks: &mut Vec<IacRisk>) {
+        for (index, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with('#') || trimmed.starts_with("//") {
+                continue;
+            }
+            for (rule, pattern, detail) in &self.risks {
+                if pattern.is_match(line) {
+                    risks.push(IacRisk {
+                        file: file.to_path_buf(),
+                        line: index + 1,
+                        rule: rule.to_string(),
+                        detail: detail.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// First line declaring `key:` (YAML) or `"key":` (JSON), 1-based; 1 when not found
+    fn line_of(content: &str, key: &str) -> usize {
+        content.lines()
+            .position(|line| {
+                let line = line.trim_start().trim_start_matches('"');
+                line.strip_prefix(key).is_some_and(|rest| rest.trim_start_matches('"').trim_start().starts_with(':'))
+            })
+            .map_or(1, |index| index + 1)
+    }
+
+    fn add_provider(inventory: &mut InfrastructureInventory, provider: &str, file: &Path) {
+        let files = inventory.providers.entry(provider.to_string()).or_default();
+        if !files.iter().any(|f| f == file) {
+            files.push(file.to_path_buf());
+        }
+    }
+}
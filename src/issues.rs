@@ -0,0 +1,232 @@
+use crate::{
+    config::{IssueTrackerKind, IssuesConfig},
+    llm::Priority,
+    reporter::{PrioritizedRecommendation, Report},
+};
+use crate::{Error, Result};
+use regex::Regex;
+use reqwest::{Client, RequestBuilder};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// Issues fetched per request when looking for earlier fingerprints
+const PAGE_SIZE: usize = 100;
+
+/// Marker written into each issue body so later exports can recognize it
+const FINGERPRINT_MARKER: &str = "project-examer:fingerprint=";
+
+/// An issue to file for one recommendation
+#[derive(Debug, Clone)]
+pub struct DraftIssue {
+    pub title: String,
+    pub body: String,
+    pub labels: Vec<String>,
+    /// Stable hash of the recommendation's category and title
+    pub fingerprint: String,
+}
+
+/// Turns Critical and High recommendations into GitHub or Jira issues, skipping
+/// those an earlier export already filed (matched by the fingerprint in the body)
+pub struct IssueExporter {
+    config: IssuesConfig,
+    client: Client,
+}
+
+impl IssueExporter {
+    pub fn new(config: &IssuesConfig) -> Result<Self> {
+        Ok(Self {
+            config: config.clone(),
+            client: Client::builder()
+                .timeout(Duration::from_secs(config.timeout_seconds))
+                .user_agent(concat!("project-examer/", env!("CARGO_PKG_VERSION")))
+                .build()?,
+        })
+    }
+
+    /// Drafts for every Critical and High recommendation, most urgent first
+    pub fn drafts(&self, report: &Report) -> Vec<DraftIssue> {
+        let mut seen = HashSet::new();
+        report.recommendations.iter()
+            .filter(|rec| matches!(rec.priority, Priority::Critical | Priority::High))
+            .map(|rec| self.draft(rec, &report.metadata.project_name))
+            .filter(|draft| seen.insert(draft.fingerprint.clone()))
+            .collect()
+    }
+
+    fn draft(&self, rec: &PrioritizedRecommendation, project: &str) -> DraftIssue {
+        let fingerprint = fingerprint(rec);
+        let markdown = self.config.tracker == IssueTrackerKind::Github;
+        let (heading, bullet, code) = if markdown { ("### ", "- ", "`") } else { ("h3. ", "* ", "{{") };
+        let close = if markdown { "`" } else { "}}" };
+
+        let mut body = format!("{}\n\n", rec.description);
+        body.push_str(&format!("{}Priority: {:?}\n{}Category: {}\n{}Effort: {}\n{}Impact: {}\n",
+            bullet, rec.priority, bullet, rec.category.name(), bullet, rec.estimated_effort, bullet, rec.potential_impact));
+        if !rec.affected_files.is_empty() {
+            body.push_str(&format!("\n{}Affected files\n\n", heading));
+            for file in &rec.affected_files {
+                body.push_str(&format!("{}{}{}{}\n", bullet, code, file, close));
+            }
+        }
+        if !rec.action_items.is_empty() {
+            body.push_str(&format!("\n{}Action items\n\n", heading));
+            for item in &rec.action_items {
+                body.push_str(&format!("{}{}\n", if markdown { "- [ ] " } else { bullet }, item));
+            }
+        }
+        body.push_str(&format!("\nFiled by project-examer from the analysis of {}.\n", project));
+        body.push_str(&if markdown {
+            format!("<!-- {}{} -->\n", FINGERPRINT_MARKER, fingerprint)
+        } else {
+            format!("{}{}\n", FINGERPRINT_MARKER, fingerprint)
+        });
+
+        DraftIssue {
+            title: rec.title.clone(),
+            body,
+            labels: self.config.labels.clone(),
+            fingerprint,
+        }
+    }
+
+    /// Fingerprints found in issues that carry the first configured label
+    pub async fn existing_fingerprints(&self) -> Result<HashSet<String>> {
+        let pattern = Regex::new(&format!(r"{}([0-9a-f]{{16}})", regex::escape(FINGERPRINT_MARKER)))?;
+        let mut fingerprints = HashSet::new();
+        let mut offset = 0;
+        loop {
+            let bodies = match self.config.tracker {
+                IssueTrackerKind::Github => self.github_bodies(offset / PAGE_SIZE + 1).await?,
+                IssueTrackerKind::Jira => self.jira_bodies(offset).await?,
+            };
+            for body in &bodies {
+                fingerprints.extend(pattern.captures_iter(body).map(|c| c[1].to_string()));
+            }
+            if bodies.len() < PAGE_SIZE {
+                return Ok(fingerprints);
+            }
+            offset += bodies.len();
+        }
+    }
+
+    /// Create the issue, returning its URL or key
+    pub async fn create(&self, draft: &DraftIssue) -> Result<String> {
+        match self.config.tracker {
+            IssueTrackerKind::Github => {
+                let url = format!("{}/repos/{}/issues", self.github_api(), self.github_repo()?);
+                let response: Value = self.github(self.client.post(&url))?
+                    .json(&json!({"title": draft.title, "body": draft.body, "labels": draft.labels}))
+                    .send().await
+                    .and_then(|response| response.error_for_status())
+                    .map_err(|e| Error::service("GitHub", e))?
+                    .json().await?;
+                Ok(response["html_url"].as_str().unwrap_or_default().to_string())
+            }
+            IssueTrackerKind::Jira => {
+                let url = format!("{}/rest/api/2/issue", self.jira_url()?);
+                let response: Value = self.jira(self.client.post(&url))?
+                    .json(&json!({"fields": {
+                        "project": {"key": self.jira_project()?},
+                        "summary": draft.title,
+                        "description": draft.body,
+                        "issuetype": {"name": self.config.jira_issue_type},
+                        "labels": draft.labels,
+                    }}))
+                    .send().await
+                    .and_then(|response| response.error_for_status())
+                    .map_err(|e| Error::service("Jira", e))?
+                    .json().await?;
+                Ok(response["key"].as_str().unwrap_or_default().to_string())
+            }
+        }
+    }
+
+    async fn github_bodies(&self, page: usize) -> Result<Vec<String>> {
+        let url = format!("{}/repos/{}/issues", self.github_api(), self.github_repo()?);
+        let mut query = vec![
+            ("state", "all".to_string()),
+            ("per_page", PAGE_SIZE.to_string()),
+            ("page", page.to_string()),
+        ];
+        if let Some(label) = self.config.labels.first() {
+            query.push(("labels", label.clone()));
+        }
+        let issues: Vec<Value> = self.github(self.client.get(&url))?
+            .query(&query)
+            .send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| Error::service("GitHub", e))?
+            .json().await?;
+        Ok(issues.iter().map(|issue| issue["body"].as_str().unwrap_or_default().to_string()).collect())
+    }
+
+    async fn jira_bodies(&self, start_at: usize) -> Result<Vec<String>> {
+        let url = format!("{}/rest/api/2/search", self.jira_url()?);
+        let mut jql = format!("project = \"{}\"", self.jira_project()?);
+        if let Some(label) = self.config.labels.first() {
+            jql.push_str(&format!(" AND labels = \"{}\"", label));
+        }
+        let response: Value = self.jira(self.client.get(&url))?
+            .query(&[
+                ("jql", jql),
+                ("fields", "description".to_string()),
+                ("maxResults", PAGE_SIZE.to_string()),
+                ("startAt", start_at.to_string()),
+            ])
+            .send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| Error::service("Jira", e))?
+            .json().await?;
+        Ok(response["issues"].as_array().map(|issues| issues.iter()
+            .map(|issue| issue["fields"]["description"].as_str().unwrap_or_default().to_string())
+            .collect()).unwrap_or_default())
+    }
+
+    fn github(&self, request: RequestBuilder) -> Result<RequestBuilder> {
+        let token = self.config.github_token.clone()
+            .or_else(|| std::env::var("GITHUB_TOKEN").ok())
+            .ok_or_else(|| Error::Config("Set GITHUB_TOKEN or issues.github_token to create GitHub issues".to_string()))?;
+        Ok(request.bearer_auth(token).header("Accept", "application/vnd.github+json"))
+    }
+
+    fn jira(&self, request: RequestBuilder) -> Result<RequestBuilder> {
+        let email = self.config.jira_email.clone()
+            .ok_or_else(|| Error::Config("Set issues.jira_email to create Jira issues".to_string()))?;
+        let token = self.config.jira_token.clone()
+            .or_else(|| std::env::var("JIRA_API_TOKEN").ok())
+            .ok_or_else(|| Error::Config("Set JIRA_API_TOKEN or issues.jira_token to create Jira issues".to_string()))?;
+        Ok(request.basic_auth(email, Some(token)))
+    }
+
+    fn github_api(&self) -> &str {
+        self.config.github_api_url.trim_end_matches('/')
+    }
+
+    fn github_repo(&self) -> Result<&str> {
+        self.config.github_repo.as_deref().ok_or_else(|| Error::Config("Set issues.github_repo (owner/name)".to_string()))
+    }
+
+    fn jira_url(&self) -> Result<&str> {
+        self.config.jira_url.as_deref()
+            .map(|url| url.trim_end_matches('/'))
+            .ok_or_else(|| Error::Config("Set issues.jira_url".to_string()))
+    }
+
+    fn jira_project(&self) -> Result<&str> {
+        self.config.jira_project.as_deref().ok_or_else(|| Error::Config("Set issues.jira_project".to_string()))
+    }
+}
+
+/// FNV-1a over the category and the lowercased title words, so rewording whitespace
+/// or case doesn't file a second issue
+fn fingerprint(rec: &PrioritizedRecommendation) -> String {
+    let key = format!("{}:{}", rec.category.name(), rec.title.split_whitespace()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" "));
+    let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
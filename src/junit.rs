@@ -0,0 +1,170 @@
+use crate::{
+    graph_export::escape_xml,
+    maintainability::MaintainabilityRating,
+    reporter::Report,
+    rules::Severity,
+};
+use crate::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A finding as a JUnit test case; it fails when its severity reaches the threshold
+struct TestCase {
+    name: String,
+    /// File and line, shown as the class name by most CI viewers
+    location: String,
+    /// `None` for checks that passed
+    severity: Option<Severity>,
+    message: String,
+    detail: String,
+}
+
+/// Writes findings as JUnit XML (`junit.xml`) so CI pipelines can show them as test
+/// results: one suite per analysis, one case per finding, failures at or above a severity
+pub struct JunitWriter {
+    fail_on: Severity,
+}
+
+impl JunitWriter {
+    pub fn new(fail_on: Severity) -> Self {
+        Self { fail_on }
+    }
+
+    pub fn to_xml(&self, report: &Report) -> String {
+        let suites = [
+            ("rules", Self::rule_cases(report)),
+            ("architecture", Self::architecture_cases(report)),
+            ("package-boundaries", Self::boundary_cases(report)),
+            ("dependency-cycles", Self::cycle_cases(report)),
+            ("code-smells", Self::smell_cases(report)),
+            ("maintainability", Self::maintainability_cases(report)),
+            ("performance-signals", Self::performance_cases(report)),
+        ];
+
+        let total: usize = suites.iter().map(|(_, cases)| cases.len()).sum();
+        let failures: usize = suites.iter().map(|(_, cases)| self.failures(cases)).sum();
+        let mut xml = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"project-examer\" tests=\"{}\" failures=\"{}\" errors=\"0\">\n",
+            total, failures
+        );
+        for (name, cases) in &suites {
+            xml.push_str(&format!(
+                "  <testsuite name=\"project-examer.{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\" timestamp=\"{}\">\n",
+                name, cases.len(), self.failures(cases), escape_xml(&report.metadata.generated_at)
+            ));
+            for case in cases {
+                xml.push_str(&format!(
+                    "    <testcase name=\"{}\" classname=\"{}\">\n",
+                    escape_xml(&case.name), escape_xml(&case.location)
+                ));
+                match case.severity {
+                    Some(severity) if severity >= self.fail_on => xml.push_str(&format!(
+                        "      <failure message=\"{}\" type=\"{:?}\">{}</failure>\n",
+                        escape_xml(&case.message), severity, escape_xml(&case.detail)
+                    )),
+                    Some(severity) => xml.push_str(&format!(
+                        "      <system-out>{:?}: {}</system-out>\n", severity, escape_xml(&case.message)
+                    )),
+                    None => xml.push_str(&format!("      <system-out>{}</system-out>\n", escape_xml(&case.message))),
+                }
+                xml.push_str("    </testcase>\n");
+            }
+            xml.push_str("  </testsuite>\n");
+        }
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+
+    pub fn write(&self, report: &Report, output_dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+        let path = output_dir.join("junit.xml");
+        fs::write(&path, self.to_xml(report)).map_err(|e| Error::report(&path, e))?;
+        Ok(path)
+    }
+
+    fn failures(&self, cases: &[TestCase]) -> usize {
+        cases.iter().filter(|case| case.severity.is_some_and(|severity| severity >= self.fail_on)).count()
+    }
+
+    fn rule_cases(report: &Report) -> Vec<TestCase> {
+        report.rule_findings.iter().map(|finding| TestCase {
+            name: format!("{} at {}:{}", finding.rule_id, finding.file, finding.line),
+            location: format!("{}:{}", finding.file, finding.line),
+            severity: Some(finding.severity),
+            message: if finding.description.is_empty() { finding.evidence.clone() } else { finding.description.clone() },
+            detail: finding.evidence.clone(),
+        }).collect()
+    }
+
+    /// Layer violations break a rule the project declared, so they are errors
+    fn architecture_cases(report: &Report) -> Vec<TestCase> {
+        report.architecture_violations.iter().map(|violation| TestCase {
+            name: format!("{} → {} at {}:{}", violation.from_layer, violation.to_layer, violation.from_file, violation.line),
+            location: format!("{}:{}", violation.from_file, violation.line),
+            severity: Some(Severity::Error),
+            message: format!("Layer {} may not depend on {}", violation.from_layer, violation.to_layer),
+            detail: format!("{}:{} imports {}", violation.from_file, violation.line, violation.to_file),
+        }).collect()
+    }
+
+    fn boundary_cases(report: &Report) -> Vec<TestCase> {
+        report.boundary_violations.iter().map(|violation| TestCase {
+            name: format!("{} → {} at {}:{}", violation.from_package, violation.to_package, violation.from_file, violation.line),
+            location: format!("{}:{}", violation.from_file, violation.line),
+            severity: Some(violation.severity),
+            message: format!("{} {}", violation.from_package, violation.reason),
+            detail: format!("{}:{} imports {}", violation.from_file, violation.line, violation.to_file),
+        }).collect()
+    }
+
+    /// Cycles larger than `analysis.max_component_size` are errors, smaller ones warnings
+    fn cycle_cases(report: &Report) -> Vec<TestCase> {
+        report.dependency_analysis.circular_dependencies.iter().map(|cycle| TestCase {
+            name: format!("Dependency cycle through {}", cycle.files.first().map(String::as_str).unwrap_or_default()),
+            location: cycle.files.first().cloned().unwrap_or_default(),
+            severity: Some(if cycle.severity == "High" { Severity::Error } else { Severity::Warning }),
+            message: format!("{} files depend on each other in a cycle", cycle.files.len()),
+            detail: cycle.files.join("\n"),
+        }).collect()
+    }
+
+    fn smell_cases(report: &Report) -> Vec<TestCase> {
+        report.code_smells.iter().map(|smell| TestCase {
+            name: format!("{} in {} at {}:{}", smell.kind.name(), smell.symbol, smell.file, smell.line),
+            location: format!("{}:{}", smell.file, smell.line),
+            severity: Some(Severity::Warning),
+            message: format!("{} {} (limit {})", smell.value, smell.kind.unit(), smell.threshold),
+            detail: format!("{} {} at {}:{}", smell.kind.name(), smell.symbol, smell.file, smell.line),
+        }).collect()
+    }
+
+    /// One case per file: red files are warnings, yellow ones informational, green ones pass
+    fn maintainability_cases(report: &Report) -> Vec<TestCase> {
+        report.maintainability.iter().map(|file| {
+            let path = file.path.to_string_lossy().to_string();
+            TestCase {
+                name: format!("Maintainability of {}", path),
+                location: path.clone(),
+                severity: match file.rating {
+                    MaintainabilityRating::Red => Some(Severity::Warning),
+                    MaintainabilityRating::Yellow => Some(Severity::Info),
+                    MaintainabilityRating::Green => None,
+                },
+                message: format!("Maintainability index {:.1} ({})", file.index, file.rating.name()),
+                detail: format!("{} lines, cyclomatic complexity {}, comment ratio {:.2}",
+                    file.lines_of_code, file.cyclomatic_complexity, file.comment_ratio),
+            }
+        }).collect()
+    }
+
+    /// Heuristic leads rather than violations, so informational
+    fn performance_cases(report: &Report) -> Vec<TestCase> {
+        report.performance_signals.iter().map(|signal| TestCase {
+            name: format!("{} in {} at {}:{}", signal.kind.name(), signal.symbol, signal.file, signal.line),
+            location: format!("{}:{}", signal.file, signal.line),
+            severity: Some(Severity::Info),
+            message: signal.detail.clone(),
+            detail: signal.detail.clone(),
+        }).collect()
+    }
+}
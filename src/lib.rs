@@ -1,10 +1,88 @@
+pub mod error;
 pub mod config;
 pub mod file_discovery;
+pub mod gitattributes;
+pub mod generated;
 pub mod simple_parser;
 pub mod dependency_graph;
 pub mod llm;
 pub mod analyzer;
+#[cfg(feature = "reports")]
 pub mod reporter;
+pub mod module_rollup;
+pub mod scoring;
+#[cfg(feature = "reports")]
+pub mod treemap;
+pub mod symbols;
+pub mod api_surface;
+pub mod ownership;
+pub mod git;
+pub mod debt;
+pub mod doc_coverage;
+pub mod doc_drift;
+pub mod rules;
+pub mod architecture;
+pub mod components;
+pub mod boundaries;
+pub mod pipeline;
+pub mod progress;
+pub mod bus_factor;
+pub mod graph_export;
+pub mod module_guide;
+pub mod explain;
+pub mod doc_bundle;
+pub mod onboarding;
+pub mod maintainability;
+pub mod smells;
+pub mod cohesion;
+pub mod proposed_changes;
+pub mod sources;
+pub mod sampling;
+pub mod perf;
+pub mod redaction;
+pub mod run_stats;
+pub mod compare;
+pub mod docker;
+pub mod infrastructure;
+pub mod assets;
+pub mod endpoints;
+pub mod contracts;
+pub mod frontend_routes;
+pub mod i18n;
+pub mod data_access;
+pub mod concurrency;
+pub mod metaprogramming;
+pub mod perf_signals;
+pub mod process_health;
+pub mod change_coupling;
+pub mod manifests;
+pub mod registry;
+pub mod osv;
+pub mod testing;
+pub mod grounding;
+pub mod review;
+#[cfg(feature = "reports")]
+pub mod html_layout;
+#[cfg(feature = "reports")]
+pub mod junit;
+#[cfg(all(feature = "llm", feature = "reports"))]
+pub mod metrics_export;
+#[cfg(all(feature = "llm", feature = "reports"))]
+pub mod notifications;
+#[cfg(all(feature = "llm", feature = "reports"))]
+pub mod issues;
+pub mod file_index;
+pub mod lsp;
+pub mod parse_cache;
+#[cfg(all(unix, feature = "llm"))]
+pub mod daemon;
+pub mod schedule;
+#[cfg(feature = "reports")]
+pub mod aggregate;
+#[cfg(feature = "reports")]
+pub mod scorecard;
+#[cfg(feature = "test-support")]
+pub mod test_support;
 
 pub use config::Config;
 pub use file_discovery::FileDiscovery;
@@ -12,6 +90,8 @@ pub use simple_parser::SimpleParser;
 pub use dependency_graph::DependencyGraph;
 pub use llm::LLMClient;
 pub use analyzer::Analyzer;
+#[cfg(feature = "reports")]
 pub use reporter::Reporter;
+pub use scoring::ScoringModel;
 
-pub type Result<T> = anyhow::Result<T>;
\ No newline at end of file
+pub use error::{Error, Result};
\ No newline at end of file
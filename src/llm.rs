@@ -1,8 +1,16 @@
 use crate::config::{LLMConfig, LLMProvider};
-use anyhow::{anyhow, Result};
-use reqwest::Client;
+use crate::redaction::{DataSentEntry, RedactionCounts, Redactor};
+use crate::{Error, Result};
+#[cfg(feature = "llm")]
+use reqwest::{header::HeaderMap, Client, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::sync::Mutex;
+#[cfg(feature = "llm")]
+use std::{future::Future, time::Duration};
+
+#[cfg(feature = "llm")]
+/// Upper bound on a single retry wait, regardless of what the provider asks for
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(60);
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AnalysisRequest {
@@ -17,6 +25,73 @@ pub struct AnalysisContext {
     pub dependencies: Vec<DependencyContext>,
     pub project_info: ProjectInfo,
     pub documentation: Vec<DocumentationContext>,
+    /// TODO/FIXME/HACK/XXX comments as `path:line KIND: text`
+    #[serde(default)]
+    pub debt_markers: Vec<String>,
+    /// Locally detected code smells as `path:line Kind `symbol`: value (limit)`
+    #[serde(default)]
+    pub code_smells: Vec<String>,
+    /// Classes whose methods form unrelated groups, as `path:line Class: N unrelated method groups (...)`
+    #[serde(default)]
+    pub class_cohesion: Vec<String>,
+    /// Numbered source of the files with the worst smells, for concrete refactoring diffs
+    #[serde(default)]
+    pub source_excerpts: Vec<SourceExcerpt>,
+    /// Dockerfile contents for the Security analysis
+    #[serde(default)]
+    pub dockerfiles: Vec<SourceExcerpt>,
+    /// Providers, module references, risky settings, resources and dependencies of IaC files
+    #[serde(default)]
+    pub infrastructure: Vec<String>,
+    /// IaC file contents for the IacSecurity analysis, files with risky settings first
+    #[serde(default)]
+    pub infrastructure_files: Vec<SourceExcerpt>,
+    /// One line per Protobuf/Thrift/Avro contract: services and RPCs, type count and the modules using it
+    #[serde(default)]
+    pub contracts: Vec<String>,
+    /// One line per page or route, as `Framework /path -> Component (file)`
+    #[serde(default)]
+    pub frontend_routes: Vec<String>,
+    /// Tables each module reads, writes or defines, as `module: read a, b; write c`
+    #[serde(default)]
+    pub data_access: Vec<String>,
+    /// SQL built by concatenation or formatting, as `path:line`
+    #[serde(default)]
+    pub dynamic_sql: Vec<String>,
+    /// Concurrency hotspots per module followed by their sites as `path:line [kind] code`
+    #[serde(default)]
+    pub concurrency: Vec<String>,
+    /// Metaprogramming per module, as `Module name: counts (most used: names)`
+    #[serde(default)]
+    pub metaprogramming: Vec<String>,
+    /// Locally detected performance signals as `path:line Kind `symbol`: detail`
+    #[serde(default)]
+    pub performance_signals: Vec<String>,
+    /// Test frameworks, test-to-source mapping, assertion density and untested files
+    #[serde(default)]
+    pub testing: Vec<String>,
+    /// Public symbols without doc comments as `path:line name`
+    #[serde(default)]
+    pub undocumented_symbols: Vec<String>,
+    /// README/CHANGELOG references flagged by local heuristics
+    #[serde(default)]
+    pub doc_drift: Vec<String>,
+    /// Main flows and glossary terms, present when the audience is onboarding
+    #[serde(default)]
+    pub onboarding: Vec<String>,
+    /// Commit conventions, commit size and long-lived branches from git history
+    #[serde(default)]
+    pub process_health: Vec<String>,
+    /// File pairs that change together in git history without importing each other
+    #[serde(default)]
+    pub change_coupling: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceExcerpt {
+    /// Path relative to the project root
+    pub path: String,
+    pub content: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +102,9 @@ pub struct FileContext {
     pub functions: Vec<String>,
     pub classes: Vec<String>,
     pub imports: Vec<String>,
+    /// `signature — first doc line` per function and method, when `analysis.function_signatures` is on
+    #[serde(default)]
+    pub function_signatures: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,7 +132,7 @@ pub struct ProjectInfo {
     pub architecture_patterns: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AnalysisType {
     Overview,
     Architecture,
@@ -62,14 +140,51 @@ pub enum AnalysisType {
     Security,
     Refactoring,
     Documentation,
+    Concurrency,
+    Performance,
+    Testing,
+    /// Security review of Terraform, CloudFormation and Kubernetes files
+    IacSecurity,
+}
+
+impl AnalysisType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnalysisType::Overview => "Overview",
+            AnalysisType::Architecture => "Architecture",
+            AnalysisType::Dependencies => "Dependencies",
+            AnalysisType::Security => "Security",
+            AnalysisType::Refactoring => "Refactoring",
+            AnalysisType::Documentation => "Documentation",
+            AnalysisType::Concurrency => "Concurrency",
+            AnalysisType::Performance => "Performance",
+            AnalysisType::Testing => "Testing",
+            AnalysisType::IacSecurity => "IacSecurity",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisResponse {
+    /// Set by the analyzer after the response is received
+    #[serde(default)]
+    pub analysis_type: Option<AnalysisType>,
     pub analysis: String,
     pub insights: Vec<Insight>,
     pub recommendations: Vec<Recommendation>,
     pub confidence: f64,
+    /// File-scoped unified diffs proposed by the Refactoring analysis
+    #[serde(default)]
+    pub proposed_changes: Vec<ProposedChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposedChange {
+    /// Path relative to the project root
+    pub file: String,
+    pub description: String,
+    /// Unified diff (`--- a/path`, `+++ b/path`, `@@` hunks) touching only `file`
+    pub diff: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,7 +193,74 @@ pub struct Insight {
     pub description: String,
     pub category: InsightCategory,
     pub confidence: f64,
-    pub evidence: Vec<String>,
+    pub evidence: Vec<Evidence>,
+}
+
+/// A quote from the codebase supporting an insight. Plain strings are accepted as
+/// evidence without a location.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "EvidenceInput")]
+pub struct Evidence {
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub snippet: String,
+    /// Set by the analyzer after checking the quote against the file
+    pub status: EvidenceStatus,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EvidenceStatus {
+    /// No file was named, or the evidence has not been checked yet
+    #[default]
+    Unanchored,
+    /// The snippet was found in the file, at `line`
+    Verified,
+    /// The file exists but the snippet is not in it
+    SnippetNotFound,
+    /// The named file is not part of the analyzed project
+    UnknownFile,
+}
+
+impl EvidenceStatus {
+    /// Whether the evidence names a location that could not be confirmed
+    pub fn is_flagged(&self) -> bool {
+        matches!(self, EvidenceStatus::SnippetNotFound | EvidenceStatus::UnknownFile)
+    }
+}
+
+impl std::fmt::Display for Evidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{}:{} {}", file, line, self.snippet),
+            (Some(file), None) => write!(f, "{} {}", file, self.snippet),
+            _ => write!(f, "{}", self.snippet),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EvidenceInput {
+    Text(String),
+    Quote {
+        #[serde(default)]
+        file: Option<String>,
+        #[serde(default)]
+        line: Option<usize>,
+        #[serde(default, alias = "quote", alias = "code")]
+        snippet: String,
+        #[serde(default)]
+        status: EvidenceStatus,
+    },
+}
+
+impl From<EvidenceInput> for Evidence {
+    fn from(input: EvidenceInput) -> Self {
+        match input {
+            EvidenceInput::Text(snippet) => Evidence { snippet, ..Default::default() },
+            EvidenceInput::Quote { file, line, snippet, status } => Evidence { file, line, snippet, status },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -98,9 +280,91 @@ pub struct Recommendation {
     pub priority: Priority,
     pub effort: Effort,
     pub impact: Impact,
+    /// Files the recommendation applies to, validated against the analyzed files
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Category assigned by the LLM; unknown values are dropped and inferred locally
+    #[serde(default, deserialize_with = "lenient_category")]
+    pub category: Option<RecommendationCategory>,
     pub action_items: Vec<String>,
 }
 
+/// What area of the project a recommendation is about, used to group recommendations in reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RecommendationCategory {
+    Architecture,
+    Dependencies,
+    Security,
+    Testing,
+    Performance,
+    Documentation,
+    /// Build, CI, linting and developer tooling
+    Tooling,
+}
+
+impl RecommendationCategory {
+    pub const ALL: [RecommendationCategory; 7] = [
+        RecommendationCategory::Architecture,
+        RecommendationCategory::Dependencies,
+        RecommendationCategory::Security,
+        RecommendationCategory::Testing,
+        RecommendationCategory::Performance,
+        RecommendationCategory::Documentation,
+        RecommendationCategory::Tooling,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RecommendationCategory::Architecture => "Architecture",
+            RecommendationCategory::Dependencies => "Dependencies",
+            RecommendationCategory::Security => "Security",
+            RecommendationCategory::Testing => "Testing",
+            RecommendationCategory::Performance => "Performance",
+            RecommendationCategory::Documentation => "Documentation",
+            RecommendationCategory::Tooling => "Tooling",
+        }
+    }
+
+    /// Case-insensitive match on the category name
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::ALL.into_iter().find(|category| category.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Guess the category from a recommendation's wording, falling back to the analysis
+    /// that produced it
+    pub fn infer(text: &str, analysis_type: Option<&AnalysisType>) -> Self {
+        // Word prefixes; keywords of three letters or fewer must match a whole word
+        const KEYWORDS: &[(RecommendationCategory, &[&str])] = &[
+            (RecommendationCategory::Security, &["security", "vulnerab", "injection", "secret", "credential", "authenticat", "authoriz", "saniti", "xss", "csrf", "encrypt"]),
+            (RecommendationCategory::Testing, &["test", "coverage", "assertion", "mock", "fixture"]),
+            (RecommendationCategory::Performance, &["performance", "latency", "throughput", "cache", "caching", "n+1", "slow", "memory", "allocation", "blocking", "batch"]),
+            (RecommendationCategory::Documentation, &["document", "readme", "docstring", "comment", "changelog", "onboarding"]),
+            (RecommendationCategory::Tooling, &["ci", "pipeline", "lint", "clippy", "formatter", "pre-commit", "tooling", "makefile", "workflow"]),
+            (RecommendationCategory::Dependencies, &["dependency", "dependencies", "coupling", "circular", "imports", "package", "version", "upgrade", "crate"]),
+        ];
+        let text = text.to_lowercase();
+        let words: Vec<&str> = text.split(|c: char| !c.is_alphanumeric() && c != '+' && c != '-').collect();
+        let matches = |keyword: &str| words.iter().any(|word| *word == keyword || (keyword.len() > 3 && word.starts_with(keyword)));
+        if let Some((category, _)) = KEYWORDS.iter().find(|(_, keywords)| keywords.iter().any(|keyword| matches(keyword))) {
+            return *category;
+        }
+        match analysis_type {
+            Some(AnalysisType::Security) | Some(AnalysisType::IacSecurity) => RecommendationCategory::Security,
+            Some(AnalysisType::Dependencies) => RecommendationCategory::Dependencies,
+            Some(AnalysisType::Documentation) => RecommendationCategory::Documentation,
+            Some(AnalysisType::Performance) | Some(AnalysisType::Concurrency) => RecommendationCategory::Performance,
+            Some(AnalysisType::Testing) => RecommendationCategory::Testing,
+            _ => RecommendationCategory::Architecture,
+        }
+    }
+}
+
+fn lenient_category<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<RecommendationCategory>, D::Error> {
+    let name = Option::<String>::deserialize(deserializer)?;
+    Ok(name.as_deref().and_then(RecommendationCategory::parse))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Priority {
     Low,
@@ -123,250 +387,216 @@ pub enum Impact {
     High,
 }
 
-pub struct LLMClient {
-    config: LLMConfig,
-    client: Client,
-    debug: bool,
+/// Published per-million-token prices (USD) used for dry-run cost estimates
+#[derive(Debug, Clone)]
+pub struct ModelPricing {
+    pub provider: LLMProvider,
+    pub model: &'static str,
+    pub input_per_million: f64,
+    pub output_per_million: f64,
 }
 
-impl LLMClient {
-    pub fn new(config: LLMConfig, debug: bool) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(config.timeout_seconds))
-            .build()
-            .unwrap();
+pub const MODEL_PRICING: &[ModelPricing] = &[
+    ModelPricing { provider: LLMProvider::OpenAI, model: "gpt-4", input_per_million: 30.0, output_per_million: 60.0 },
+    ModelPricing { provider: LLMProvider::OpenAI, model: "gpt-4o", input_per_million: 2.5, output_per_million: 10.0 },
+    ModelPricing { provider: LLMProvider::OpenAI, model: "gpt-4o-mini", input_per_million: 0.15, output_per_million: 0.6 },
+    ModelPricing { provider: LLMProvider::Anthropic, model: "claude-3-5-sonnet", input_per_million: 3.0, output_per_million: 15.0 },
+    ModelPricing { provider: LLMProvider::Anthropic, model: "claude-3-haiku", input_per_million: 0.25, output_per_million: 1.25 },
+    ModelPricing { provider: LLMProvider::Ollama, model: "local", input_per_million: 0.0, output_per_million: 0.0 },
+];
 
-        Self { config, client, debug }
+impl ModelPricing {
+    pub fn cost(&self, input_tokens: usize, output_tokens: usize) -> f64 {
+        (input_tokens as f64 * self.input_per_million + output_tokens as f64 * self.output_per_million) / 1_000_000.0
     }
 
-    pub async fn analyze(&self, request: AnalysisRequest) -> Result<AnalysisResponse> {
-        match self.config.provider {
-            LLMProvider::OpenAI => self.analyze_with_openai(request).await,
-            LLMProvider::Ollama => self.analyze_with_ollama(request).await,
-            LLMProvider::Anthropic => self.analyze_with_anthropic(request).await,
+    /// Find pricing for a configured model, matching by model-name prefix
+    pub fn lookup(provider: &LLMProvider, model: &str) -> Option<&'static ModelPricing> {
+        if matches!(provider, LLMProvider::None) {
+            return None;
         }
-    }
-
-    async fn analyze_with_openai(&self, request: AnalysisRequest) -> Result<AnalysisResponse> {
-        let api_key = self.config.api_key.as_ref()
-            .ok_or_else(|| anyhow!("OpenAI API key not provided"))?;
-
-        let system_prompt = self.create_system_prompt(&request.analysis_type);
-        let user_prompt = self.create_user_prompt(&request);
-
-        let payload = serde_json::json!({
-            "model": self.config.model,
-            "messages": [
-                {
-                    "role": "system",
-                    "content": system_prompt
-                },
-                {
-                    "role": "user",
-                    "content": user_prompt
-                }
-            ],
-            "max_completion_tokens": self.config.max_tokens,
-            "temperature": self.config.temperature
-        });
-
-        if self.debug {
-            println!("\n🔍 LLM Debug - OpenAI Request:");
-            println!("Model: {}", self.config.model);
-            println!("System prompt: {}", system_prompt);
-            println!("User prompt: {}", user_prompt);
-            println!("Payload: {}", serde_json::to_string_pretty(&payload).unwrap_or_else(|_| "Failed to serialize".to_string()));
+        if matches!(provider, LLMProvider::Ollama) {
+            return MODEL_PRICING.iter().find(|p| matches!(p.provider, LLMProvider::Ollama));
         }
+        MODEL_PRICING.iter()
+            .filter(|p| std::mem::discriminant(&p.provider) == std::mem::discriminant(provider))
+            .filter(|p| model.starts_with(p.model))
+            .max_by_key(|p| p.model.len())
+    }
+}
 
-        let response = self.client
-            .post("https://api.openai.com/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", api_key))
-            .header("Content-Type", "application/json")
-            .json(&payload)
-            .send()
-            .await?;
+/// Rough token estimate (~4 characters per token)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
 
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            return Err(anyhow!("OpenAI API error: {}", error_text));
-        }
+/// Non-success response from a provider API, or a request that never got one
+/// because the connection failed or timed out (no `status`)
+#[cfg(feature = "llm")]
+#[derive(Debug)]
+struct ApiFailure {
+    status: Option<StatusCode>,
+    retry_after: Option<Duration>,
+    body: String,
+}
 
-        let response_json: serde_json::Value = response.json().await?;
-        
-        if self.debug {
-            println!("\n🔍 LLM Debug - OpenAI Response:");
-            println!("Raw response: {}", serde_json::to_string_pretty(&response_json).unwrap_or_else(|_| "Failed to serialize".to_string()));
+#[cfg(feature = "llm")]
+impl ApiFailure {
+    fn is_retryable(&self) -> bool {
+        match self.status {
+            Some(status) => status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error(),
+            None => true,
         }
-        
-        let content = response_json["choices"][0]["message"]["content"]
-            .as_str()
-            .ok_or_else(|| anyhow!("Invalid response format from OpenAI"))?;
+    }
 
-        if self.debug {
-            println!("Content: {}", content);
-        }
-
-        // Try to parse as JSON, but provide fallback for non-JSON responses
-        match serde_json::from_str::<AnalysisResponse>(content) {
-            Ok(analysis_response) => Ok(analysis_response),
-            Err(_) => {
-                // Fallback: create a basic response from plain text
-                Ok(AnalysisResponse {
-                    analysis: content.to_string(),
-                    insights: Vec::new(),
-                    recommendations: Vec::new(),
-                    confidence: 0.5,
-                })
-            }
+    /// "returned 503 Service Unavailable", or why the request failed
+    fn describe(&self) -> String {
+        match self.status {
+            Some(status) => format!("returned {}", status),
+            None => format!("request failed ({})", self.body),
         }
     }
+}
 
-    async fn analyze_with_ollama(&self, request: AnalysisRequest) -> Result<AnalysisResponse> {
-        let default_url = "http://localhost:11434".to_string();
-        let base_url = self.config.base_url.as_ref().unwrap_or(&default_url);
+#[cfg(feature = "llm")]
+fn api_error(provider: &str, status: Option<StatusCode>, message: impl Into<String>) -> Error {
+    Error::Llm {
+        provider: provider.to_string(),
+        status: status.map(|status| status.as_u16()),
+        message: message.into(),
+    }
+}
 
-        let system_prompt = self.create_system_prompt(&request.analysis_type);
-        let user_prompt = self.create_user_prompt(&request);
+/// The HTTP side of the client
+#[cfg(feature = "llm")]
+struct Transport {
+    client: Client,
+    debug: bool,
+    /// Don't print retries and model fallbacks
+    quiet: bool,
+}
 
-    let payload = serde_json::json!({
-        "model": self.config.model,
-        "prompt": format!("System: {}\n\nUser: {}", system_prompt, user_prompt),
-        "stream": false,
-        "format": "json",
-        "options": {
-            "temperature": self.config.temperature,
-            "num_predict": self.config.max_tokens
-        }
-    });
+#[cfg(feature = "llm")]
+impl Transport {
+    fn new(config: &LLMConfig, debug: bool) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()
+            .unwrap();
+        Self { client, debug, quiet: false }
+    }
 
-        if self.debug {
-            println!("\n🔍 LLM Debug - Ollama Request:");
-            println!("Model: {}", self.config.model);
-            println!("Base URL: {}", base_url);
-            println!("System prompt: {}", system_prompt);
-            println!("User prompt: {}", user_prompt);
-            println!("Payload: {}", serde_json::to_string_pretty(&payload).unwrap_or_else(|_| "Failed to serialize".to_string()));
-        }
+    fn set_quiet(&mut self, quiet: bool) {
+        self.quiet = quiet;
+    }
+}
 
-        let response = self.client
-            .post(&format!("{}/api/generate", base_url))
-            .header("Content-Type", "application/json")
-            .json(&payload)
-            .send()
-            .await?;
+/// Built without the `llm` feature, so requests fail instead of being sent
+#[cfg(not(feature = "llm"))]
+struct Transport;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            return Err(anyhow!("Ollama API error: {}", error_text));
-        }
+#[cfg(not(feature = "llm"))]
+impl Transport {
+    fn new(_config: &LLMConfig, _debug: bool) -> Self {
+        Transport
+    }
 
-        let response_json: serde_json::Value = response.json().await?;
-        
-        if self.debug {
-            println!("\n🔍 LLM Debug - Ollama Response:");
-            println!("Raw response: {}", serde_json::to_string_pretty(&response_json).unwrap_or_else(|_| "Failed to serialize".to_string()));
-        }
-        
-        let content = response_json["response"]
-            .as_str()
-            .ok_or_else(|| anyhow!("Invalid response format from Ollama"))?;
+    fn set_quiet(&mut self, _quiet: bool) {}
+}
 
-        if self.debug {
-            println!("Content: {}", content);
-        }
+pub struct LLMClient {
+    config: LLMConfig,
+    transport: Transport,
+    redactor: Option<Redactor>,
+    /// Every request sent, after redaction, for the data-sent manifest
+    sent: Mutex<Vec<DataSentEntry>>,
+    /// Refuse any request that would leave the machine
+    offline: bool,
+}
 
-        // Try to parse as JSON, but provide fallback for non-JSON responses
-        match serde_json::from_str::<AnalysisResponse>(content) {
-            Ok(analysis_response) => Ok(analysis_response),
-            Err(_) => {
-                // Fallback: create a basic response from plain text
-                Ok(AnalysisResponse {
-                    analysis: content.to_string(),
-                    insights: Vec::new(),
-                    recommendations: Vec::new(),
-                    confidence: 0.5,
-                })
-            }
-        }
+impl LLMClient {
+    pub fn new(config: LLMConfig, debug: bool) -> Self {
+        let transport = Transport::new(&config, debug);
+        Self { config, transport, redactor: None, sent: Mutex::new(Vec::new()), offline: false }
     }
 
-    async fn analyze_with_anthropic(&self, request: AnalysisRequest) -> Result<AnalysisResponse> {
-        let api_key = self.config.api_key.as_ref()
-            .ok_or_else(|| anyhow!("Anthropic API key not provided"))?;
+    /// Mask secrets, emails and IP addresses in every prompt before it is sent
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
 
-        let system_prompt = self.create_system_prompt(&request.analysis_type);
-        let user_prompt = self.create_user_prompt(&request);
+    /// Only allow requests to a loopback Ollama
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
 
-        let payload = serde_json::json!({
-            "model": self.config.model,
-            "max_tokens": self.config.max_tokens,
-            "system": system_prompt,
-            "messages": [
-                {
-                    "role": "user",
-                    "content": user_prompt
-                }
-            ]
-        });
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.transport.set_quiet(quiet);
+    }
 
-        if self.debug {
-            println!("\n🔍 LLM Debug - Anthropic Request:");
-            println!("Model: {}", self.config.model);
-            println!("System prompt: {}", system_prompt);
-            println!("User prompt: {}", user_prompt);
-            println!("Payload: {}", serde_json::to_string_pretty(&payload).unwrap_or_else(|_| "Failed to serialize".to_string()));
+    /// Apply redaction to a prompt pair exactly as `complete` would
+    pub fn redact_prompts(&self, system_prompt: &str, user_prompt: &str) -> DataSentEntry {
+        match self.redactor {
+            Some(ref redactor) => redactor.prepare(self.config.provider.name(), &self.config.model, system_prompt, user_prompt),
+            None => DataSentEntry::new(self.config.provider.name(), &self.config.model,
+                system_prompt.to_string(), user_prompt.to_string(), RedactionCounts::default()),
         }
+    }
 
-        let response = self.client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", api_key)
-            .header("Content-Type", "application/json")
-            .header("anthropic-version", "2023-06-01")
-            .json(&payload)
-            .send()
-            .await?;
+    /// Requests sent so far, as they left the machine
+    pub fn data_sent(&self) -> Vec<DataSentEntry> {
+        self.sent.lock().map(|sent| sent.clone()).unwrap_or_default()
+    }
 
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            return Err(anyhow!("Anthropic API error: {}", error_text));
-        }
+    pub async fn analyze(&self, request: AnalysisRequest) -> Result<AnalysisResponse> {
+        let (system_prompt, user_prompt) = self.build_prompts(&request);
+        let content = self.complete(&system_prompt, &user_prompt).await?;
+        Ok(Self::parse_response_content(&content))
+    }
 
-        let response_json: serde_json::Value = response.json().await?;
-        
-        if self.debug {
-            println!("\n🔍 LLM Debug - Anthropic Response:");
-            println!("Raw response: {}", serde_json::to_string_pretty(&response_json).unwrap_or_else(|_| "Failed to serialize".to_string()));
+    /// Send a raw prompt pair to the configured provider and return the text reply
+    pub async fn complete(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        if self.config.provider == LLMProvider::None {
+            return Err(Error::Config("LLM analysis is disabled (provider = \"None\")".to_string()));
         }
-        
-        let content = response_json["content"][0]["text"]
-            .as_str()
-            .ok_or_else(|| anyhow!("Invalid response format from Anthropic"))?;
-
-        if self.debug {
-            println!("Content: {}", content);
+        if self.offline && !self.config.is_local() {
+            return Err(Error::Config(format!("Offline mode: refusing to send a request to {}", self.config.provider.name())));
         }
 
+        self.send(self.redact_prompts(system_prompt, user_prompt)).await
+    }
+
+    fn parse_response_content(content: &str) -> AnalysisResponse {
         // Try to parse as JSON, but provide fallback for non-JSON responses
         match serde_json::from_str::<AnalysisResponse>(content) {
-            Ok(analysis_response) => Ok(analysis_response),
+            Ok(analysis_response) => analysis_response,
             Err(_) => {
                 // Fallback: create a basic response from plain text
-                Ok(AnalysisResponse {
+                AnalysisResponse {
+                    analysis_type: None,
                     analysis: content.to_string(),
                     insights: Vec::new(),
                     recommendations: Vec::new(),
                     confidence: 0.5,
-                })
+                    proposed_changes: Vec::new(),
+                }
             }
         }
     }
 
+    /// Build the system and user prompts for a request without sending it
+    pub fn build_prompts(&self, request: &AnalysisRequest) -> (String, String) {
+        (self.create_system_prompt(&request.analysis_type), self.create_user_prompt(request))
+    }
+
     fn create_system_prompt(&self, analysis_type: &AnalysisType) -> String {
         match analysis_type {
             AnalysisType::Overview => {
                 "You are a senior software architect analyzing a codebase. Provide a comprehensive overview of the software architecture, including key components, patterns used, and overall design philosophy. 
 
-If possible, return your response as JSON with this structure: {\"analysis\": \"detailed overview\", \"insights\": [{\"title\": \"...\", \"description\": \"...\", \"category\": \"Architecture\", \"confidence\": 0.8, \"evidence\": [\"...\"]}], \"recommendations\": [{\"title\": \"...\", \"description\": \"...\", \"priority\": \"High\", \"effort\": \"Medium\", \"impact\": \"High\", \"action_items\": [\"...\"]}], \"confidence\": 0.8}
+If possible, return your response as JSON with this structure: {\"analysis\": \"detailed overview\", \"insights\": [{\"title\": \"...\", \"description\": \"...\", \"category\": \"Architecture\", \"confidence\": 0.8, \"evidence\": [{\"file\": \"path/to/file.rs\", \"line\": 42, \"snippet\": \"...\"}]}], \"recommendations\": [{\"title\": \"...\", \"description\": \"...\", \"priority\": \"High\", \"effort\": \"Medium\", \"impact\": \"High\", \"category\": \"Architecture\", \"files\": [\"path/to/file.rs\"], \"action_items\": [\"...\"]}], \"confidence\": 0.8}
 
 If JSON formatting is not working, provide a well-structured text response with clear sections for analysis, insights, and recommendations.".to_string()
             }
@@ -398,7 +628,9 @@ Cover these areas:
 - Insecure coding patterns
 - Data handling and validation issues
 - Authentication and authorization concerns
-- Recommendations and best practices".to_string()
+- Recommendations and best practices
+
+If possible, return your response as JSON with this structure: {\"analysis\": \"security summary\", \"insights\": [{\"title\": \"...\", \"description\": \"...\", \"category\": \"Security\", \"confidence\": 0.8, \"evidence\": [{\"file\": \"path/to/file.rs\", \"line\": 42, \"snippet\": \"...\"}]}], \"recommendations\": [{\"title\": \"...\", \"description\": \"...\", \"priority\": \"High\", \"effort\": \"Medium\", \"impact\": \"High\", \"category\": \"Security\", \"files\": [\"path/to/file.rs\"], \"action_items\": [\"...\"]}], \"confidence\": 0.8}".to_string()
             }
             AnalysisType::Refactoring => {
                 "You are a code quality expert. Identify opportunities for refactoring, code smells, and suggest improvements for maintainability and readability.
@@ -418,15 +650,66 @@ Provide:
 - Key components and their purposes
 - Data flow and interactions
 - Usage examples
-- Setup and configuration guidance".to_string()
+- Setup and configuration guidance
+
+If possible, return your response as JSON with this structure: {\"analysis\": \"the documentation\", \"insights\": [{\"title\": \"...\", \"description\": \"...\", \"category\": \"Documentation\", \"confidence\": 0.8, \"evidence\": [{\"file\": \"path/to/file.rs\", \"line\": 42, \"snippet\": \"...\"}]}], \"recommendations\": [{\"title\": \"...\", \"description\": \"...\", \"priority\": \"Medium\", \"effort\": \"Low\", \"impact\": \"Medium\", \"category\": \"Documentation\", \"files\": [\"path/to/file.rs\"], \"action_items\": [\"...\"]}], \"confidence\": 0.8}".to_string()
+            }
+            AnalysisType::Concurrency => {
+                "You are a concurrency expert reviewing threads, async tasks, locks, channels, atomics and unsafe code for correctness.
+
+Analyze:
+- Shared mutable state and how it is protected
+- Race conditions and data races
+- Deadlock and lock-ordering risks
+- Blocking calls inside async code
+- Soundness of unsafe code
+- Recommendations to simplify or harden concurrent code".to_string()
+            }
+            AnalysisType::Performance => {
+                "You are a performance engineer reviewing a codebase for latency, throughput and resource-usage problems.
+
+Analyze:
+- Queries or remote calls issued per item in a loop (N+1 patterns)
+- Blocking IO on async runtimes
+- Algorithmic complexity of hot loops
+- Oversized files and modules that are expensive to load or build
+- Caching, batching and streaming opportunities".to_string()
+            }
+            AnalysisType::Testing => {
+                "You are a test engineering expert reviewing a project's test suite and testing strategy.
+
+Analyze:
+- Coverage gaps, weighted by the complexity of untested code
+- Test architecture: unit, integration and end-to-end balance, and where tests live
+- Framework choices and consistency
+- Assertion quality and density
+- Recommendations for the highest-value tests to add".to_string()
+            }
+            AnalysisType::IacSecurity => {
+                "You are a cloud security engineer reviewing infrastructure-as-code: Terraform, CloudFormation and Kubernetes manifests.
+
+Analyze:
+- Network exposure: open ingress, public endpoints and load balancers
+- Data protection: encryption at rest and in transit, public storage
+- Identity and access: overly broad IAM policies, roles and service accounts
+- Workload isolation: privileged containers, root users, host access
+- Secrets written into templates or variables
+- Module and provider sources that aren't pinned to a version".to_string()
             }
         }
     }
 
     fn create_user_prompt(&self, request: &AnalysisRequest) -> String {
         let mut prompt = format!("Analyze this codebase:\n\n{}\n\n", request.prompt);
+        prompt.push_str(&self.context_prompt(request));
+        prompt.push_str("\nPlease provide a detailed analysis with specific insights and actionable recommendations. Quote evidence exactly from the source excerpts, with the file path relative to the project root and the line number; quotes that cannot be found in the named file are flagged in the report.");
+        prompt
+    }
 
-        prompt.push_str("Project Information:\n");
+    /// The project context of a request (files, dependencies, excerpts and the sections
+    /// its analysis type adds), without the task or response format
+    pub fn context_prompt(&self, request: &AnalysisRequest) -> String {
+        let mut prompt = String::from("Project Information:\n");
         prompt.push_str(&format!("- Name: {}\n", request.context.project_info.name));
         prompt.push_str(&format!("- Total files: {}\n", request.context.project_info.total_files));
         prompt.push_str(&format!("- Languages: {}\n", request.context.project_info.languages.join(", ")));
@@ -435,7 +718,14 @@ Provide:
             prompt.push_str("\nFile Structure:\n");
             for file in &request.context.files {
                 prompt.push_str(&format!("- {} ({})\n", file.path, file.language));
-                prompt.push_str(&format!("  Functions: {}\n", file.functions.join(", ")));
+                if file.function_signatures.is_empty() {
+                    prompt.push_str(&format!("  Functions: {}\n", file.functions.join(", ")));
+                } else {
+                    prompt.push_str("  Functions:\n");
+                    for signature in &file.function_signatures {
+                        prompt.push_str(&format!("    - {}\n", signature));
+                    }
+                }
                 if !file.classes.is_empty() {
                     prompt.push_str(&format!("  Classes: {}\n", file.classes.join(", ")));
                 }
@@ -453,10 +743,439 @@ Provide:
             }
         }
 
-        prompt.push_str("\nPlease provide a detailed analysis with specific insights and actionable recommendations.");
+        if request.analysis_type == AnalysisType::Refactoring && !request.context.debt_markers.is_empty() {
+            prompt.push_str("\nTechnical Debt Markers (TODO/FIXME/HACK/XXX comments):\n");
+            for marker in &request.context.debt_markers {
+                prompt.push_str(&format!("- {}\n", marker));
+            }
+        }
+
+        if request.analysis_type == AnalysisType::Refactoring && !request.context.code_smells.is_empty() {
+            prompt.push_str("\nCode Smells (detected locally with configured thresholds):\n");
+            for smell in &request.context.code_smells {
+                prompt.push_str(&format!("- {}\n", smell));
+            }
+        }
+
+        if request.analysis_type == AnalysisType::Refactoring && !request.context.class_cohesion.is_empty() {
+            prompt.push_str("\nLow-Cohesion Classes (methods in different groups share no fields or calls; consider splitting along the groups):\n");
+            for class in &request.context.class_cohesion {
+                prompt.push_str(&format!("- {}\n", class));
+            }
+        }
+
+        if request.analysis_type == AnalysisType::Refactoring && !request.context.source_excerpts.is_empty() {
+            prompt.push_str("\nSource Excerpts (base proposed diffs on these exact lines):\n");
+            for excerpt in &request.context.source_excerpts {
+                prompt.push_str(&format!("\n--- {} ---\n{}\n", excerpt.path, excerpt.content));
+            }
+        }
+
+        if request.analysis_type == AnalysisType::Security && !request.context.dockerfiles.is_empty() {
+            prompt.push_str("\nDockerfiles (check base images, exposed ports, the user the image runs as, and secrets in build args or copied files):\n");
+            for dockerfile in &request.context.dockerfiles {
+                prompt.push_str(&format!("\n--- {} ---\n{}\n", dockerfile.path, dockerfile.content));
+            }
+        }
+
+        if request.analysis_type == AnalysisType::IacSecurity && !request.context.infrastructure.is_empty() {
+            prompt.push_str("\nInfrastructure (risky settings are line-pattern matches; confirm each against the file):\n");
+            for line in &request.context.infrastructure {
+                prompt.push_str(&format!("- {}\n", line));
+            }
+            for file in &request.context.infrastructure_files {
+                prompt.push_str(&format!("\n--- {} ---\n{}\n", file.path, file.content));
+            }
+        }
+
+        let data_access_relevant = matches!(request.analysis_type, AnalysisType::Architecture | AnalysisType::Security);
+        if data_access_relevant && !request.context.data_access.is_empty() {
+            prompt.push_str("\nData Access (database tables each module reads, writes or defines):\n");
+            for module in &request.context.data_access {
+                prompt.push_str(&format!("- {}\n", module));
+            }
+        }
+
+        if request.analysis_type == AnalysisType::Security && !request.context.dynamic_sql.is_empty() {
+            prompt.push_str("\nSQL Built by Concatenation or String Formatting (check each for injection):\n");
+            for location in &request.context.dynamic_sql {
+                prompt.push_str(&format!("- {}\n", location));
+            }
+        }
+
+        if request.analysis_type == AnalysisType::Concurrency && !request.context.concurrency.is_empty() {
+            prompt.push_str("\nConcurrency Inventory (modules that spawn work and share state first):\n");
+            for line in &request.context.concurrency {
+                prompt.push_str(&format!("{}\n", line));
+            }
+        }
+
+        let contracts_relevant = matches!(request.analysis_type, AnalysisType::Architecture | AnalysisType::Dependencies);
+        if contracts_relevant && !request.context.contracts.is_empty() {
+            prompt.push_str("\nService Contracts (Protobuf, Thrift and Avro IDL; modules using the generated code depend on the contract):\n");
+            for contract in &request.context.contracts {
+                prompt.push_str(&format!("- {}\n", contract));
+            }
+        }
+
+        if request.analysis_type == AnalysisType::Architecture && !request.context.frontend_routes.is_empty() {
+            prompt.push_str("\nPages and Routes (the app's user-facing structure; describe it alongside the code structure):\n");
+            for route in &request.context.frontend_routes {
+                prompt.push_str(&format!("- {}\n", route));
+            }
+        }
+
+        let metaprogramming_relevant = matches!(request.analysis_type, AnalysisType::Refactoring | AnalysisType::Documentation);
+        if metaprogramming_relevant && !request.context.metaprogramming.is_empty() {
+            prompt.push_str("\nMetaprogramming (derives, macros, decorators and generated code; the code they expand to isn't in the source):\n");
+            for module in &request.context.metaprogramming {
+                prompt.push_str(&format!("- {}\n", module));
+            }
+        }
+
+        if request.analysis_type == AnalysisType::Performance && !request.context.performance_signals.is_empty() {
+            prompt.push_str("\nPerformance Signals (found by local heuristics; confirm each against the code before recommending a fix):\n");
+            for signal in &request.context.performance_signals {
+                prompt.push_str(&format!("- {}\n", signal));
+            }
+        }
+
+        if request.analysis_type == AnalysisType::Testing && !request.context.testing.is_empty() {
+            prompt.push_str("\nTest Suite (test files are matched to source files by name):\n");
+            for line in &request.context.testing {
+                prompt.push_str(&format!("- {}\n", line));
+            }
+        }
+
+        if request.analysis_type == AnalysisType::Documentation && !request.context.undocumented_symbols.is_empty() {
+            prompt.push_str("\nUndocumented Public Symbols:\n");
+            for symbol in &request.context.undocumented_symbols {
+                prompt.push_str(&format!("- {}\n", symbol));
+            }
+        }
+
+        if request.analysis_type == AnalysisType::Documentation && !request.context.doc_drift.is_empty() {
+            prompt.push_str("\nPossible README/CHANGELOG Drift (found by heuristics; verify each against the code and flag real drift):\n");
+            for drift in &request.context.doc_drift {
+                prompt.push_str(&format!("- {}\n", drift));
+            }
+        }
+
+        if request.analysis_type == AnalysisType::Architecture && !request.context.change_coupling.is_empty() {
+            prompt.push_str("\nHidden Change Coupling (files that keep changing in the same commits without either importing the other; point out the implicit contracts, duplicated logic or missing abstractions that could explain each pair):\n");
+            for line in &request.context.change_coupling {
+                prompt.push_str(&format!("- {}\n", line));
+            }
+        }
+
+        if request.analysis_type == AnalysisType::Overview && !request.context.process_health.is_empty() {
+            prompt.push_str("\nProcess Health (recent git history; recommend workflow changes under the Tooling category where these numbers warrant it):\n");
+            for line in &request.context.process_health {
+                prompt.push_str(&format!("- {}\n", line));
+            }
+        }
+
+        if !request.context.onboarding.is_empty() {
+            prompt.push_str("\nOnboarding Notes (entry-point flows and recurring domain terms):\n");
+            for note in &request.context.onboarding {
+                prompt.push_str(&format!("- {}\n", note));
+            }
+        }
+
         prompt
     }
 
+
+}
+
+#[cfg(feature = "llm")]
+impl LLMClient {
+    /// Record `entry` in the data-sent manifest and send it to the configured provider
+    async fn send(&self, entry: DataSentEntry) -> Result<String> {
+        if let Ok(mut sent) = self.sent.lock() {
+            sent.push(entry.clone());
+        }
+        let (system_prompt, user_prompt) = (entry.system_prompt.as_str(), entry.user_prompt.as_str());
+        match self.config.provider {
+            LLMProvider::OpenAI => self.complete_with_openai(system_prompt, user_prompt).await,
+            LLMProvider::Ollama => self.complete_with_ollama(system_prompt, user_prompt).await,
+            LLMProvider::Anthropic => self.complete_with_anthropic(system_prompt, user_prompt).await,
+            LLMProvider::None => Err(Error::Config("LLM analysis is disabled (provider = \"None\")".to_string())),
+        }
+    }
+
+    async fn complete_with_openai(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        let api_key = self.config.api_key.as_ref()
+            .ok_or_else(|| Error::Config("OpenAI API key not provided".to_string()))?;
+        self.with_retries("OpenAI", |model| self.send_openai_request(api_key, model, system_prompt, user_prompt)).await
+    }
+
+    /// Try the configured model, then each fallback model, retrying rate limits,
+    /// server errors, connection failures and timeouts up to `max_retries`
+    /// times per model
+    async fn with_retries<'a, F, Fut>(&'a self, provider: &str, mut send: F) -> Result<String>
+    where
+        F: FnMut(&'a str) -> Fut,
+        Fut: Future<Output = Result<std::result::Result<String, ApiFailure>>>,
+    {
+        let models: Vec<&String> = std::iter::once(&self.config.model)
+            .chain(self.config.fallback_models.iter())
+            .collect();
+
+        let mut last_failure = None;
+        for (model_index, model) in models.iter().enumerate() {
+            if model_index > 0 && !self.transport.quiet {
+                println!("    ↪️  Falling back to {} model {}", provider, model);
+            }
+
+            for attempt in 0..=self.config.max_retries {
+                match send(model.as_str()).await? {
+                    Ok(content) => return Ok(content),
+                    Err(failure) => {
+                        if !failure.is_retryable() {
+                            return Err(api_error(provider, failure.status, format!("model {}: {}", model, failure.body)));
+                        }
+
+                        let wait = failure.retry_after.unwrap_or_else(|| Duration::from_secs(2u64.pow(attempt)));
+                        let retry_note = match (failure.retry_after, failure.status) {
+                            (Some(retry_after), _) => format!(" (retry-after {:.1}s)", retry_after.as_secs_f64()),
+                            (None, Some(_)) => " (no retry-after header)".to_string(),
+                            (None, None) => String::new(),
+                        };
+
+                        if attempt < self.config.max_retries {
+                            let wait = wait.min(MAX_RETRY_WAIT);
+                            if !self.transport.quiet {
+                                println!("    ⏳ {} {} for model {}{}, retrying in {:.1}s (attempt {}/{})",
+                                    provider, failure.describe(), model, retry_note, wait.as_secs_f64(), attempt + 1, self.config.max_retries);
+                            }
+                            tokio::time::sleep(wait).await;
+                        } else if !self.transport.quiet {
+                            println!("    ⚠️  {} {} for model {}{}, giving up on this model",
+                                provider, failure.describe(), model, retry_note);
+                        }
+                        last_failure = Some((model.to_string(), failure));
+                    }
+                }
+            }
+        }
+
+        match last_failure {
+            Some((model, failure)) => Err(api_error(provider, failure.status,
+                format!("after trying {} model(s), last model {}: {}", models.len(), model, failure.body))),
+            None => Err(Error::Config(format!("No {} model configured", provider))),
+        }
+    }
+
+    /// Send a request, returning the response when it succeeded and the failure
+    /// when it is one worth retrying; other transport errors are returned as is
+    async fn send_request(request: RequestBuilder) -> Result<std::result::Result<Response, ApiFailure>> {
+        match request.send().await {
+            Ok(response) if response.status().is_success() => Ok(Ok(response)),
+            Ok(response) => {
+                let status = response.status();
+                let retry_after = Self::parse_retry_after(response.headers());
+                let body = response.text().await?;
+                Ok(Err(ApiFailure { status: Some(status), retry_after, body }))
+            }
+            Err(e) if e.is_connect() || e.is_timeout() => {
+                Ok(Err(ApiFailure { status: None, retry_after: None, body: e.without_url().to_string() }))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Send a single chat completion request. Returns the message content, or
+    /// the failure details for non-success statuses, connection failures and
+    /// timeouts.
+    async fn send_openai_request(
+        &self,
+        api_key: &str,
+        model: &str,
+        system_prompt: &str,
+        user_prompt: &str,
+    ) -> Result<std::result::Result<String, ApiFailure>> {
+        let payload = serde_json::json!({
+            "model": model,
+            "messages": [
+                {
+                    "role": "system",
+                    "content": system_prompt
+                },
+                {
+                    "role": "user",
+                    "content": user_prompt
+                }
+            ],
+            "max_completion_tokens": self.config.max_tokens,
+            "temperature": self.config.temperature
+        });
+
+        if self.transport.debug {
+            println!("\n🔍 LLM Debug - OpenAI Request:");
+            println!("Model: {}", model);
+            println!("System prompt: {}", system_prompt);
+            println!("User prompt: {}", user_prompt);
+            println!("Payload: {}", serde_json::to_string_pretty(&payload).unwrap_or_else(|_| "Failed to serialize".to_string()));
+        }
+
+        let request = self.transport.client
+            .post("https://api.openai.com/v1/chat/completions")
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&payload);
+        let response = match Self::send_request(request).await? {
+            Ok(response) => response,
+            Err(failure) => return Ok(Err(failure)),
+        };
+
+        let response_json: serde_json::Value = response.json().await?;
+        
+        if self.transport.debug {
+            println!("\n🔍 LLM Debug - OpenAI Response:");
+            println!("Raw response: {}", serde_json::to_string_pretty(&response_json).unwrap_or_else(|_| "Failed to serialize".to_string()));
+        }
+        
+        let content = response_json["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or_else(|| api_error("OpenAI", None, "invalid response format"))?;
+
+        if self.transport.debug {
+            println!("Content: {}", content);
+        }
+
+        Ok(Ok(content.to_string()))
+    }
+
+    /// Read `retry-after-ms` or `retry-after` (seconds) from a rate-limited response
+    fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+        let header_value = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+        if let Some(ms) = header_value("retry-after-ms").and_then(|v| v.trim().parse::<f64>().ok()) {
+            return Some(Duration::from_secs_f64(ms.max(0.0) / 1000.0));
+        }
+        header_value("retry-after")
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .map(|secs| Duration::from_secs_f64(secs.max(0.0)))
+    }
+
+    async fn complete_with_ollama(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        self.with_retries("Ollama", |model| self.send_ollama_request(model, system_prompt, user_prompt)).await
+    }
+
+    async fn send_ollama_request(&self, model: &str, system_prompt: &str, user_prompt: &str) -> Result<std::result::Result<String, ApiFailure>> {
+        let default_url = "http://localhost:11434".to_string();
+        let base_url = self.config.base_url.as_ref().unwrap_or(&default_url);
+
+    let payload = serde_json::json!({
+        "model": model,
+        "prompt": format!("System: {}\n\nUser: {}", system_prompt, user_prompt),
+        "stream": false,
+        "format": "json",
+        "options": {
+            "temperature": self.config.temperature,
+            "num_predict": self.config.max_tokens
+        }
+    });
+
+        if self.transport.debug {
+            println!("\n🔍 LLM Debug - Ollama Request:");
+            println!("Model: {}", model);
+            println!("Base URL: {}", base_url);
+            println!("System prompt: {}", system_prompt);
+            println!("User prompt: {}", user_prompt);
+            println!("Payload: {}", serde_json::to_string_pretty(&payload).unwrap_or_else(|_| "Failed to serialize".to_string()));
+        }
+
+        let request = self.transport.client
+            .post(format!("{}/api/generate", base_url))
+            .header("Content-Type", "application/json")
+            .json(&payload);
+        let response = match Self::send_request(request).await? {
+            Ok(response) => response,
+            Err(failure) => return Ok(Err(failure)),
+        };
+
+        let response_json: serde_json::Value = response.json().await?;
+        
+        if self.transport.debug {
+            println!("\n🔍 LLM Debug - Ollama Response:");
+            println!("Raw response: {}", serde_json::to_string_pretty(&response_json).unwrap_or_else(|_| "Failed to serialize".to_string()));
+        }
+        
+        let content = response_json["response"]
+            .as_str()
+            .ok_or_else(|| api_error("Ollama", None, "invalid response format"))?;
+
+        if self.transport.debug {
+            println!("Content: {}", content);
+        }
+
+        Ok(Ok(content.to_string()))
+    }
+
+    async fn complete_with_anthropic(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        let api_key = self.config.api_key.as_ref()
+            .ok_or_else(|| Error::Config("Anthropic API key not provided".to_string()))?;
+        self.with_retries("Anthropic", |model| self.send_anthropic_request(api_key, model, system_prompt, user_prompt)).await
+    }
+
+    async fn send_anthropic_request(
+        &self,
+        api_key: &str,
+        model: &str,
+        system_prompt: &str,
+        user_prompt: &str,
+    ) -> Result<std::result::Result<String, ApiFailure>> {
+        let payload = serde_json::json!({
+            "model": model,
+            "max_tokens": self.config.max_tokens,
+            "system": system_prompt,
+            "messages": [
+                {
+                    "role": "user",
+                    "content": user_prompt
+                }
+            ]
+        });
+
+        if self.transport.debug {
+            println!("\n🔍 LLM Debug - Anthropic Request:");
+            println!("Model: {}", model);
+            println!("System prompt: {}", system_prompt);
+            println!("User prompt: {}", user_prompt);
+            println!("Payload: {}", serde_json::to_string_pretty(&payload).unwrap_or_else(|_| "Failed to serialize".to_string()));
+        }
+
+        let request = self.transport.client
+            .post("https://api.anthropic.com/v1/messages")
+            .header("x-api-key", api_key)
+            .header("Content-Type", "application/json")
+            .header("anthropic-version", "2023-06-01")
+            .json(&payload);
+        let response = match Self::send_request(request).await? {
+            Ok(response) => response,
+            Err(failure) => return Ok(Err(failure)),
+        };
+
+        let response_json: serde_json::Value = response.json().await?;
+        
+        if self.transport.debug {
+            println!("\n🔍 LLM Debug - Anthropic Response:");
+            println!("Raw response: {}", serde_json::to_string_pretty(&response_json).unwrap_or_else(|_| "Failed to serialize".to_string()));
+        }
+        
+        let content = response_json["content"][0]["text"]
+            .as_str()
+            .ok_or_else(|| api_error("Anthropic", None, "invalid response format"))?;
+
+        if self.transport.debug {
+            println!("Content: {}", content);
+        }
+
+        Ok(Ok(content.to_string()))
+    }
+
     pub async fn batch_analyze(&self, requests: Vec<AnalysisRequest>) -> Result<Vec<AnalysisResponse>> {
         let mut responses = Vec::new();
         
@@ -469,4 +1188,12 @@ Provide:
         
         Ok(responses)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(not(feature = "llm"))]
+impl LLMClient {
+    async fn send(&self, _entry: DataSentEntry) -> Result<String> {
+        Err(Error::Config(format!("Cannot send a request to {}: project-examer was built without the `llm` feature",
+            self.config.provider.name())))
+    }
+}
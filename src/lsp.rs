@@ -0,0 +1,238 @@
+use crate::{
+    file_index::{FileEntry, FileIndex, FileLink},
+    rules::Severity,
+};
+use crate::{Error, Result};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Importers listed in a hover before "… and N more"
+const HOVER_IMPORTERS: usize = 10;
+
+/// A minimal language server over stdio answering from `file_index.json`: hovers with
+/// file and function metrics and "who imports this", diagnostics for findings, and
+/// `projectExamer/*` requests for editor extensions. The index is reloaded when a new
+/// analysis rewrites it.
+pub struct LspServer {
+    index_path: PathBuf,
+    index: FileIndex,
+    loaded_at: Option<SystemTime>,
+    shutdown: bool,
+}
+
+impl LspServer {
+    pub fn new(index_path: &Path) -> Result<Self> {
+        let index = FileIndex::load(index_path)
+            .map_err(|e| Error::Discovery(format!("No analysis index; run `project-examer analyze` first or pass --index: {}", e)))?;
+        Ok(Self {
+            index_path: index_path.to_path_buf(),
+            index,
+            loaded_at: Self::modified(index_path),
+            shutdown: false,
+        })
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+    }
+
+    fn reload_if_changed(&mut self) {
+        let modified = Self::modified(&self.index_path);
+        if modified.is_some() && modified != self.loaded_at {
+            match FileIndex::load(&self.index_path) {
+                Ok(index) => {
+                    self.index = index;
+                    self.loaded_at = modified;
+                    eprintln!("project-examer: reloaded {}", self.index_path.display());
+                }
+                Err(e) => eprintln!("project-examer: keeping the previous index: {:#}", e),
+            }
+        }
+    }
+
+    /// Serve JSON-RPC messages with LSP `Content-Length` framing until `exit`
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> Result<()> {
+        let mut input = input;
+        while let Some(message) = read_message(&mut input)? {
+            self.reload_if_changed();
+            let method = message["method"].as_str().unwrap_or_default().to_string();
+            if method == "exit" {
+                break;
+            }
+            let id = message.get("id").cloned();
+            let params = &message["params"];
+
+            if method == "textDocument/didOpen" {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                write_message(&mut output, &json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": {"uri": uri, "diagnostics": self.diagnostics(uri)},
+                }))?;
+                continue;
+            }
+            // Other notifications need no answer
+            let Some(id) = id else { continue };
+
+            let response = match self.handle(&method, params) {
+                Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                Err((code, message)) => json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}}),
+            };
+            write_message(&mut output, &response)?;
+        }
+        if !self.shutdown {
+            eprintln!("project-examer: exit without shutdown");
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, method: &str, params: &Value) -> std::result::Result<Value, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().or_else(|| params["uri"].as_str());
+        let entry = || -> std::result::Result<&FileEntry, (i64, String)> {
+            let uri = uri.ok_or((-32602, "Missing uri".to_string()))?;
+            self.entry(uri).ok_or((-32602, format!("{} is not in the analysis index", uri)))
+        };
+
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "hoverProvider": true,
+                    "textDocumentSync": {"openClose": true, "change": 0},
+                },
+                "serverInfo": {"name": "project-examer", "version": env!("CARGO_PKG_VERSION")},
+            })),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/hover" => {
+                let Some(entry) = uri.and_then(|uri| self.entry(uri)) else { return Ok(Value::Null) };
+                let line = params["position"]["line"].as_u64().unwrap_or(0) as usize + 1;
+                Ok(json!({"contents": {"kind": "markdown", "value": self.hover(entry, line)}}))
+            }
+            "projectExamer/fileMetrics" => Ok(json!(entry()?)),
+            "projectExamer/importers" => Ok(links(&entry()?.importers)),
+            "projectExamer/dependencies" => Ok(links(&entry()?.imports)),
+            "projectExamer/findings" => match uri {
+                Some(_) => Ok(json!(entry()?.findings)),
+                None => Ok(Value::Object(self.index.files.iter()
+                    .filter(|(_, entry)| !entry.findings.is_empty())
+                    .map(|(path, entry)| (path_to_uri(path), json!(entry.findings)))
+                    .collect())),
+            },
+            _ => Err((-32601, format!("Unsupported method {}", method))),
+        }
+    }
+
+    fn entry(&self, uri: &str) -> Option<&FileEntry> {
+        self.index.get(&uri_to_path(uri)?)
+    }
+
+    fn hover(&self, entry: &FileEntry, line: usize) -> String {
+        let mut text = format!("**project-examer** · {} lines · cyclomatic complexity {}",
+            entry.lines_of_code, entry.cyclomatic_complexity);
+        if let (Some(index), Some(rating)) = (entry.maintainability_index, entry.maintainability_rating) {
+            text.push_str(&format!(" · maintainability {:.0} ({})", index, rating.name()));
+        }
+        if let Some(function) = entry.function_at(line) {
+            text.push_str(&format!("\n\n`{}`: {} lines, {} parameters, nesting depth {}",
+                function.name, function.end_line.saturating_sub(function.line) + 1, function.parameters, function.max_nesting));
+        }
+        for finding in entry.findings.iter().filter(|finding| finding.line == line) {
+            text.push_str(&format!("\n\n⚠️ {:?} ({}): {}", finding.severity, finding.source, finding.message));
+        }
+        if entry.importers.is_empty() {
+            text.push_str("\n\nNo analyzed file imports this file.");
+        } else {
+            text.push_str(&format!("\n\nImported by {} file(s):", entry.importers.len()));
+            for importer in entry.importers.iter().take(HOVER_IMPORTERS) {
+                let shown = importer.path.strip_prefix(&self.index.root).unwrap_or(&importer.path);
+                text.push_str(&format!("\n- [{}:{}]({}#L{})", shown.display(), importer.line, path_to_uri(&importer.path), importer.line));
+            }
+            if entry.importers.len() > HOVER_IMPORTERS {
+                text.push_str(&format!("\n- … and {} more", entry.importers.len() - HOVER_IMPORTERS));
+            }
+        }
+        text
+    }
+
+    fn diagnostics(&self, uri: &str) -> Vec<Value> {
+        let Some(entry) = self.entry(uri) else { return Vec::new() };
+        entry.findings.iter().map(|finding| {
+            let line = finding.line.saturating_sub(1);
+            json!({
+                "range": {"start": {"line": line, "character": 0}, "end": {"line": line, "character": 1000}},
+                "severity": match finding.severity {
+                    Severity::Error => 1,
+                    Severity::Warning => 2,
+                    Severity::Info => 3,
+                },
+                "source": "project-examer",
+                "code": finding.source,
+                "message": finding.message,
+            })
+        }).collect()
+    }
+}
+
+fn links(links: &[FileLink]) -> Value {
+    json!(links.iter().map(|link| json!({"uri": path_to_uri(&link.path), "line": link.line})).collect::<Vec<_>>())
+}
+
+fn read_message(input: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = Some(value.trim().parse::<usize>().map_err(|e| Error::Protocol(format!("Invalid Content-Length: {}", e)))?);
+        }
+    }
+    let mut body = vec![0; length.ok_or_else(|| Error::Protocol("Message without Content-Length".to_string()))?];
+    input.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> Result<()> {
+    let body = serde_json::to_string(message)?;
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()?;
+    Ok(())
+}
+
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|hex| std::str::from_utf8(hex).ok());
+        if let (b'%', Some(Ok(byte))) = (bytes[i], hex.map(|hex| u8::from_str_radix(hex, 16))) {
+            decoded.push(byte);
+            i += 3;
+            continue;
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    Some(PathBuf::from(String::from_utf8(decoded).ok()?))
+}
+
+fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
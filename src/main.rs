@@ -1,7 +1,41 @@
-use project_examer::{Config, Analyzer, Reporter, config::LLMProvider};
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
-use std::time::Instant;
+use project_examer::{Config, Analyzer, Reporter, config::{Audience, IssueTrackerKind, LLMProvider}};
+use project_examer::aggregate::Aggregator;
+use project_examer::analyzer::PlannedLLMCall;
+use project_examer::compare::Comparer;
+use project_examer::doc_bundle::DocBundleWriter;
+use project_examer::explain::FileExplainer;
+#[cfg(unix)]
+use project_examer::daemon::{self, AnalyzeRequest, Daemon, DaemonClient, DaemonFailure, DaemonRequest, FailureKind};
+use project_examer::file_index::{FileIndex, QueryKind};
+use project_examer::file_discovery::SkippedFile;
+use project_examer::git::RevisionWorktree;
+use project_examer::llm::AnalysisType;
+use project_examer::llm::{ModelPricing, MODEL_PRICING};
+use project_examer::lsp::LspServer;
+use project_examer::graph_export::GraphExporter;
+use project_examer::issues::IssueExporter;
+use project_examer::junit::JunitWriter;
+use project_examer::metrics_export::MetricsExporter;
+use project_examer::notifications::Notifier;
+use project_examer::parse_cache::ParseCache;
+use project_examer::pipeline::Stage;
+use project_examer::progress::CancellationToken;
+use project_examer::proposed_changes::ProposedChangeWriter;
+use project_examer::redaction;
+use project_examer::run_stats::RunStats;
+use project_examer::schedule::{CronSchedule, RunRotation};
+use project_examer::scorecard::Scorecard;
+use project_examer::rules::Severity;
+use project_examer::symbols::{SymbolIndex, SymbolKind, SymbolQuery};
+use project_examer::reporter::Report;
+use anyhow::Context;
+use clap::{Args, Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::fmt;
+use std::time::{Duration, Instant};
 
 #[derive(Parser)]
 #[command(name = "project-examer")]
@@ -15,31 +49,30 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Analyze a project directory
-    Analyze {
-        /// Target directory to analyze
-        #[arg(short, long, default_value = ".")]
-        path: PathBuf,
-        
-        /// Configuration file path
-        #[arg(short, long)]
-        config: Option<PathBuf>,
-        
-        /// Output directory for reports
-        #[arg(short, long, default_value = "./analysis-output")]
-        output: PathBuf,
-        
-        /// Skip LLM analysis (faster, local-only analysis)
-        #[arg(long)]
-        skip_llm: bool,
-        
-        /// Show debug information for LLM requests and responses
-        #[arg(long)]
-        debug_llm: bool,
-        
-        /// Generate only specific report format
-        #[arg(long, value_enum)]
-        format: Option<ReportFormat>,
-    },
+    Analyze(AnalyzeArgs),
+    /// Generate a Markdown documentation bundle (architecture, modules, dependencies, getting started)
+    Document(DocumentArgs),
+    /// Walk through one file with the LLM: function-by-function explanation, risks and suggested tests
+    Explain(ExplainArgs),
+    /// Analyze a project with per-stage timing and per-language parse throughput
+    Bench(BenchArgs),
+    /// Analyze two directories and compare size, complexity, dependency structure and shared modules
+    Compare(CompareArgs),
+    /// Merge reports from many repositories into an organization-level overview
+    Aggregate(AggregateArgs),
+    /// File Critical and High recommendations from a report as GitHub or Jira issues
+    ExportIssues(ExportIssuesArgs),
+    /// Serve per-file metrics, importers and findings from the last analysis to editors over LSP (stdio)
+    Lsp(LspArgs),
+    /// Keep parsed files and the last analysis in memory; `analyze` and `query` in the target directory then run here
+    #[cfg(unix)]
+    Daemon(DaemonArgs),
+    /// Re-analyze on a cron schedule, each run in its own directory under --output (see `[schedule]`)
+    Run(RunArgs),
+    /// Look up a file's metrics, importers, dependencies or findings from the daemon or the last analysis
+    Query(QueryArgs),
+    /// Find functions, classes, methods and imports by name across the project
+    Search(SearchArgs),
     /// Generate a default configuration file
     Config {
         /// Output path for the config file (defaults to ~/.project-examer.toml)
@@ -48,11 +81,397 @@ enum Commands {
     },
 }
 
-#[derive(clap::ValueEnum, Clone)]
+#[derive(Args, Clone, Serialize, Deserialize)]
+struct AnalyzeArgs {
+    /// Target directory to analyze
+    #[arg(short, long, default_value = ".")]
+    path: PathBuf,
+    
+    /// Configuration file path
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Named configuration profile to apply (e.g. quick, deep)
+    #[arg(long)]
+    profile: Option<String>,
+    
+    /// Output directory for reports
+    #[arg(short, long, default_value = "./analysis-output")]
+    output: PathBuf,
+    
+    /// Skip LLM analysis (faster, local-only analysis)
+    #[arg(long)]
+    skip_llm: bool,
+    
+    /// Show debug information for LLM requests and responses
+    #[arg(long)]
+    debug_llm: bool,
+    
+    /// Generate only specific report format
+    #[arg(long, value_enum)]
+    format: Option<ReportFormat>,
+
+    /// Print the LLM calls that would be made, with token and cost estimates, without sending them
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Restrict analysis to a path (relative to --path) or package name; repeatable
+    #[arg(long)]
+    scope: Vec<String>,
+
+    /// Exit with an error when any custom rule finding is at or above this severity
+    #[arg(long, value_enum)]
+    fail_on: Option<Severity>,
+
+    /// Analyze only the newline-separated paths in this file (`-` for stdin) plus their direct dependencies
+    #[arg(long, value_name = "FILE")]
+    files_from: Option<PathBuf>,
+
+    /// Tailor prompts and report structure to an audience (e.g. onboarding for new developers)
+    #[arg(long, value_enum)]
+    audience: Option<Audience>,
+
+    /// Metrics-only run that keeps as little in memory as possible (implies --skip-llm)
+    #[arg(long)]
+    low_memory: bool,
+
+    /// Reproducible output: files in path order, fixed timestamp (see `[report]`)
+    #[arg(long)]
+    deterministic: bool,
+
+    /// Worker threads for parsing (overrides `analysis.threads`; 0 = one per CPU core)
+    #[arg(long)]
+    threads: Option<usize>,
+
+    /// Maximum files read from disk at once (overrides `analysis.io_concurrency`; 0 = no limit)
+    #[arg(long)]
+    io_concurrency: Option<usize>,
+
+    /// List every file left out by ignore patterns, size, extension or .gitattributes, with the rule responsible
+    #[arg(long)]
+    explain_excludes: bool,
+
+    /// Guarantee no network calls: cloud LLM providers are skipped (see `[offline]`)
+    #[arg(long)]
+    offline: bool,
+
+    /// With --offline, keep an Ollama provider running on a loopback address
+    #[arg(long)]
+    allow_local_llm: bool,
+
+    /// Run in this process even when a daemon is serving the target directory
+    #[arg(long)]
+    no_daemon: bool,
+
+    /// Look dependency versions up on crates.io, npm and PyPI and report outdated or yanked ones (needs network)
+    #[arg(long)]
+    check_registry: bool,
+
+    /// Look dependency versions up in the OSV.dev vulnerability database (needs network)
+    #[arg(long)]
+    check_vulnerabilities: bool,
+
+    /// Continue an interrupted run from the stage checkpoints in the output directory,
+    /// retrying only the LLM analysis types that didn't complete
+    #[arg(long)]
+    resume: bool,
+
+    /// Stop after this long (e.g. `900`, `90s`, `15m`, `2h`), write partial_report.json
+    /// and exit with code 124; Ctrl-C does the same with code 130
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    max_duration: Option<Duration>,
+
+    /// Analyze this git revision (commit, tag or branch) from a temporary worktree,
+    /// leaving the working copy untouched
+    #[arg(long, value_name = "REV")]
+    rev: Option<String>,
+}
+
+impl AnalyzeArgs {
+    /// Take relative paths from `cwd`, for runs in a daemon started elsewhere
+    #[cfg(unix)]
+    fn resolved_from(mut self, cwd: &Path) -> Self {
+        self.path = cwd.join(&self.path);
+        self.output = cwd.join(&self.output);
+        self.config = self.config.map(|config| cwd.join(config));
+        self.files_from = self.files_from.map(|list| cwd.join(list));
+        self
+    }
+}
+
+#[derive(Args)]
+struct DocumentArgs {
+    /// Target directory to document
+    #[arg(short, long, default_value = ".")]
+    path: PathBuf,
+
+    /// Configuration file path
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Named configuration profile to apply (e.g. quick, deep)
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Output directory for the documentation bundle
+    #[arg(short, long, default_value = "./docs/generated")]
+    output: PathBuf,
+
+    /// Skip LLM analysis; the bundle is built from static analysis only
+    #[arg(long)]
+    skip_llm: bool,
+
+    /// Show debug information for LLM requests and responses
+    #[arg(long)]
+    debug_llm: bool,
+
+    /// Guarantee no network calls: cloud LLM providers are skipped (see `[offline]`)
+    #[arg(long)]
+    offline: bool,
+
+    /// With --offline, keep an Ollama provider running on a loopback address
+    #[arg(long)]
+    allow_local_llm: bool,
+}
+
+#[derive(Args)]
+struct ExplainArgs {
+    /// File to explain
+    file: PathBuf,
+
+    /// Target directory the file belongs to
+    #[arg(short, long, default_value = ".")]
+    path: PathBuf,
+
+    /// Configuration file path
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Named configuration profile to apply (e.g. quick, deep)
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Save the explanation as Markdown instead of printing it
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Show debug information for LLM requests and responses
+    #[arg(long)]
+    debug_llm: bool,
+
+    /// Guarantee no network calls: only a loopback Ollama with --allow-local-llm can be used (see `[offline]`)
+    #[arg(long)]
+    offline: bool,
+
+    /// With --offline, keep an Ollama provider running on a loopback address
+    #[arg(long)]
+    allow_local_llm: bool,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// Target directory to benchmark
+    #[arg(short, long, default_value = ".")]
+    path: PathBuf,
+
+    /// Configuration file path
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Named configuration profile to apply (e.g. quick, deep)
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Directory to write perf_report.json and perf_report.md to
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Include the LLM stage (sends real requests); by default only local stages are timed
+    #[arg(long)]
+    with_llm: bool,
+
+    /// Worker threads for parsing (overrides `analysis.threads`; 0 = one per CPU core)
+    #[arg(long)]
+    threads: Option<usize>,
+
+    /// Maximum files read from disk at once (overrides `analysis.io_concurrency`; 0 = no limit)
+    #[arg(long)]
+    io_concurrency: Option<usize>,
+}
+
+#[derive(Args)]
+struct CompareArgs {
+    /// First directory (e.g. a fork or one service)
+    left: PathBuf,
+
+    /// Second directory (e.g. the upstream or another service)
+    right: PathBuf,
+
+    /// Configuration file path, applied to both directories
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Named configuration profile to apply (e.g. quick, deep)
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Directory to write comparison.json and comparison.md to
+    #[arg(short, long, default_value = "./comparison-output")]
+    output: PathBuf,
+}
+
+#[derive(Args)]
+struct AggregateArgs {
+    /// Report files, or directories searched for analysis_report.json
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+
+    /// Directory to write aggregate.json, aggregate.md and aggregate.html to
+    #[arg(short, long, default_value = "./aggregate-output")]
+    output: PathBuf,
+
+    /// Least maintainable files listed across all repositories
+    #[arg(long, default_value_t = 25)]
+    worst_files: usize,
+}
+
+#[derive(Args)]
+struct ExportIssuesArgs {
+    /// Report written by `analyze`
+    #[arg(short, long, default_value = "./analysis-output/analysis_report.json")]
+    report: PathBuf,
+
+    /// Configuration file path (see `[issues]`)
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Issue tracker to file into (overrides `issues.tracker`)
+    #[arg(long, value_enum)]
+    tracker: Option<IssueTrackerKind>,
+
+    /// Print the issues that would be filed without contacting the tracker
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Args)]
+struct LspArgs {
+    /// File index written by `analyze`
+    #[arg(short, long, default_value = "./analysis-output/file_index.json")]
+    index: PathBuf,
+}
+
+#[derive(Args)]
+struct DaemonArgs {
+    /// Target directory whose `analyze` and `query` runs the daemon serves
+    #[arg(short, long, default_value = ".")]
+    path: PathBuf,
+
+    /// File index to answer queries from until the first analysis
+    #[arg(long, default_value = "./analysis-output/file_index.json")]
+    index: PathBuf,
+
+    /// Print the running daemon's status
+    #[arg(long)]
+    status: bool,
+
+    /// Stop the running daemon
+    #[arg(long)]
+    stop: bool,
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Cron expression in local time, e.g. "0 3 * * *" (overrides `schedule.cron`)
+    #[arg(long)]
+    schedule: Option<String>,
+
+    #[command(flatten)]
+    analyze: AnalyzeArgs,
+}
+
+#[derive(Args)]
+struct QueryArgs {
+    /// What to look up
+    #[arg(value_enum)]
+    kind: QueryKind,
+
+    /// File to look up
+    file: PathBuf,
+
+    /// Target directory, used to find its daemon
+    #[arg(short, long, default_value = ".")]
+    path: PathBuf,
+
+    /// File index to read when no daemon is running
+    #[arg(long, default_value = "./analysis-output/file_index.json")]
+    index: PathBuf,
+}
+
+#[derive(Args)]
+struct SearchArgs {
+    /// Regular expression matched against symbol names (`Class.method` for methods)
+    pattern: String,
+
+    /// Target directory to search
+    #[arg(short, long, default_value = ".")]
+    path: PathBuf,
+
+    /// Configuration file path
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Named configuration profile to apply (e.g. quick, deep)
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Only these kinds of symbol (repeatable)
+    #[arg(short, long, value_enum)]
+    kind: Vec<SymbolKind>,
+
+    /// Only files in these languages, e.g. rust, typescript (repeatable)
+    #[arg(short, long)]
+    language: Vec<String>,
+
+    /// Only files matching these globs, relative to the target directory (repeatable)
+    #[arg(short = 'g', long = "glob")]
+    globs: Vec<String>,
+
+    /// Only symbols exported from their module
+    #[arg(long)]
+    exported: bool,
+
+    /// Match the pattern case-insensitively
+    #[arg(short, long)]
+    ignore_case: bool,
+
+    /// Print the matches as JSON instead of `path:line: kind name`
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::ValueEnum, Clone, Serialize, Deserialize)]
 enum ReportFormat {
     Json,
     Html,
+    /// Markdown summary (`analysis_summary.md`)
     Markdown,
+    /// Complete Markdown report (`analysis_report.md`) for committing into a docs folder
+    MarkdownReport,
+    /// Per-file maintainability index table
+    Csv,
+    /// ctags `tags` file plus a JSON symbol index
+    Symbols,
+    /// Dependency graph as GraphML (yEd, Neo4j)
+    Graphml,
+    /// Dependency graph as GEXF (Gephi)
+    Gexf,
+    /// Findings as JUnit XML test results for CI pipelines
+    Junit,
+    /// Codebase health metrics in Prometheus text format (`metrics.prom`)
+    Prometheus,
+    /// One-page letter-grade scorecard (`scorecard.md`, `scorecard.html`)
+    Scorecard,
     All,
 }
 
@@ -61,8 +480,42 @@ async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Analyze { path, config, output, skip_llm, debug_llm, format } => {
-            analyze_project(path, config, output, skip_llm, debug_llm, format).await?;
+        Commands::Analyze(args) => {
+            analyze_project(args).await?;
+        }
+        Commands::Document(args) => {
+            document_project(args).await?;
+        }
+        Commands::Explain(args) => {
+            explain_file(args).await?;
+        }
+        Commands::Bench(args) => {
+            bench_project(args).await?;
+        }
+        Commands::Compare(args) => {
+            compare_projects(args).await?;
+        }
+        Commands::Aggregate(args) => {
+            aggregate_reports(args)?;
+        }
+        Commands::ExportIssues(args) => {
+            export_issues(args).await?;
+        }
+        Commands::Lsp(args) => {
+            serve_lsp(args)?;
+        }
+        #[cfg(unix)]
+        Commands::Daemon(args) => {
+            serve_daemon(args).await?;
+        }
+        Commands::Run(args) => {
+            run_scheduled(args).await?;
+        }
+        Commands::Query(args) => {
+            query_file(args).await?;
+        }
+        Commands::Search(args) => {
+            search_symbols(args)?;
         }
         Commands::Config { output } => {
             generate_config(output)?;
@@ -72,36 +525,192 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn analyze_project(
-    target_path: PathBuf,
-    config_path: Option<PathBuf>,
-    output_path: PathBuf,
-    skip_llm: bool,
-    debug_llm: bool,
-    _format: Option<ReportFormat>,
-) -> anyhow::Result<()> {
+/// Wait for Ctrl-C or the end of `max_duration`, then cancel the run
+async fn watch_for_cancellation(cancel: CancellationToken, max_duration: Option<Duration>) -> Cancelled {
+    let deadline = tokio::time::Instant::now() + max_duration.unwrap_or_default();
+    let cancelled = tokio::select! {
+        _ = tokio::signal::ctrl_c() => Cancelled::Interrupted,
+        _ = tokio::time::sleep_until(deadline), if max_duration.is_some() => Cancelled::TimedOut(max_duration.unwrap_or_default()),
+    };
+    cancel.cancel();
+    cancelled
+}
+
+/// Why an analysis stopped before finishing
+#[derive(Debug)]
+enum Cancelled {
+    /// Ctrl-C
+    Interrupted,
+    /// `--max-duration`
+    TimedOut(Duration),
+}
+
+impl Cancelled {
+    /// 130 like a shell's SIGINT exit, 124 like `timeout(1)`
+    fn exit_code(&self) -> i32 {
+        match self {
+            Cancelled::Interrupted => 130,
+            Cancelled::TimedOut(_) => 124,
+        }
+    }
+
+    /// How the daemon reports this to its client
+    #[cfg(unix)]
+    fn kind(&self) -> FailureKind {
+        match self {
+            Cancelled::Interrupted => FailureKind::Interrupted,
+            Cancelled::TimedOut(_) => FailureKind::TimedOut,
+        }
+    }
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cancelled::Interrupted => write!(f, "Analysis interrupted"),
+            Cancelled::TimedOut(limit) => write!(f, "Analysis exceeded --max-duration of {}s", limit.as_secs()),
+        }
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// What an analysis left behind, for the daemon to report back to its client
+struct AnalysisRun {
+    exported_files: Vec<PathBuf>,
+    run_stats: RunStats,
+    index: FileIndex,
+    duration_secs: f64,
+    /// Set when `--fail-on` should fail the command
+    rule_failure: Option<String>,
+}
+
+async fn analyze_project(args: AnalyzeArgs) -> anyhow::Result<()> {
+    // Dry runs and file lists on stdin need this process's terminal
+    #[cfg(unix)]
+    if !args.no_daemon && !args.dry_run && args.files_from.as_deref() != Some(Path::new("-")) {
+        let socket = daemon::socket_path(&args.path);
+        if let Some(client) = DaemonClient::connect(&socket).await {
+            return analyze_in_daemon(client, &socket, args).await;
+        }
+    }
+
+    let run = match run_analysis(args, None, &Config::env_vars()).await {
+        Ok(run) => run,
+        Err(e) => match e.downcast_ref::<Cancelled>() {
+            Some(cancelled) => std::process::exit(cancelled.exit_code()),
+            None => return Err(e),
+        },
+    };
+    match run.and_then(|run| run.rule_failure) {
+        Some(failure) => anyhow::bail!(failure),
+        None => Ok(()),
+    }
+}
+
+#[cfg(unix)]
+async fn analyze_in_daemon(mut client: DaemonClient, socket: &Path, args: AnalyzeArgs) -> anyhow::Result<()> {
+    println!("📡 Analyzing in the daemon on {} (--no-daemon to run here)", socket.display());
+    let outcome = client.analyze(AnalyzeRequest {
+        cwd: std::env::current_dir()?,
+        env: Config::env_vars(),
+        args: serde_json::to_value(&args)?,
+    }).await?;
+    let summary = match outcome {
+        Ok(summary) => summary,
+        Err(DaemonFailure { kind: FailureKind::Error, message }) => anyhow::bail!(message),
+        Err(failure) => {
+            println!("\n🛑 {}", failure);
+            std::process::exit(failure.kind.exit_code());
+        }
+    };
+
+    println!("\n✅ Analysis completed in {:.2}s: {} file(s) parsed, {} reused from the cache",
+        summary["duration_secs"].as_f64().unwrap_or_default(),
+        summary["files_parsed"],
+        summary["files_from_cache"]);
+    println!("📁 Reports exported to:");
+    for file in summary["exported_files"].as_array().into_iter().flatten() {
+        println!("   - {}", file.as_str().unwrap_or_default());
+    }
+    match summary["rule_failure"].as_str() {
+        Some(failure) => anyhow::bail!("{}", failure),
+        None => Ok(()),
+    }
+}
+
+/// Run an analysis and export its reports; `None` for a dry run
+/// `env` stands in for the process environment when loading the config
+async fn run_analysis(args: AnalyzeArgs, parse_cache: Option<Arc<ParseCache>>, env: &BTreeMap<String, String>) -> anyhow::Result<Option<AnalysisRun>> {
+    let AnalyzeArgs {
+        path: target_path,
+        config: config_path,
+        profile,
+        output: output_path,
+        skip_llm,
+        debug_llm,
+        format,
+        dry_run,
+        scope,
+        fail_on,
+        audience,
+        files_from,
+        low_memory,
+        deterministic,
+        threads,
+        io_concurrency,
+        explain_excludes,
+        offline,
+        allow_local_llm,
+        no_daemon: _,
+        check_registry,
+        check_vulnerabilities,
+        resume,
+        max_duration,
+        rev,
+    } = args;
+
     println!("🚀 Starting Project Examer Analysis");
     println!("====================================");
     
     let start_time = Instant::now();
     
-    // Load configuration
-    let mut config = if let Some(config_path) = config_path {
-        Config::from_file(&config_path)?
-    } else {
-        Config::load()?
-    };
-    
-    // Override target directory
-    config.target_directory = target_path.clone();
+    let mut config = load_config_with_env(config_path, profile, &target_path, env)?;
     
     println!("🎯 Target directory: {}", target_path.display());
+    // Removed when the run ends
+    let worktree = rev.as_deref().map(|rev| RevisionWorktree::checkout(&target_path, rev)).transpose()?;
+    if let Some(worktree) = &worktree {
+        println!("🕰️  Revision: {} (checked out in {})", worktree.commit, worktree.target.display());
+        config.target_directory = worktree.target.clone();
+    }
+    config.analysis.scope.extend(scope);
+    if !config.analysis.scope.is_empty() {
+        println!("🔭 Scope: {}", config.analysis.scope.join(", "));
+    }
+    if let Some(audience) = audience {
+        config.analysis.audience = audience;
+    }
+    if config.analysis.audience == Audience::Onboarding {
+        println!("🧭 Audience: onboarding (guided tour for new developers)");
+    }
     println!("📤 Output directory: {}", output_path.display());
+
+    apply_concurrency(&mut config, threads, io_concurrency);
+    config.analysis.low_memory |= low_memory;
+    config.report.deterministic |= deterministic;
+    let skip_llm = skip_llm || config.analysis.low_memory;
+    if config.analysis.low_memory {
+        println!("🪶 Low-memory mode: metrics only, parsed files are compacted as they stream in");
+    }
     
     if skip_llm {
         println!("⚡ Skipping LLM analysis (local-only mode)");
-        config.llm.provider = project_examer::config::LLMProvider::OpenAI; // Will be unused
+        config.llm.provider = LLMProvider::None;
     }
+    config.registry.enabled |= check_registry;
+    config.osv.enabled |= check_vulnerabilities;
+    apply_offline(&mut config, offline, allow_local_llm)?;
     
     if debug_llm {
         println!("🔍 LLM debug mode enabled - will show detailed request/response information");
@@ -110,38 +719,672 @@ async fn analyze_project(
     // Save LLM configuration before moving config
     let llm_provider = config.llm.provider.clone();
     let llm_model = config.llm.model.clone();
+    let scoring_config = config.analysis.scoring.clone();
+    let maintainability_config = config.analysis.maintainability.clone();
+    let report_config = config.report.clone();
+    let metrics_config = config.metrics.clone();
+    let notifications_config = config.notifications.clone();
+    let scorecard_config = config.scorecard.clone();
+    let persist_artifacts = config.pipeline.persist_artifacts;
 
     // Initialize analyzer
     let mut analyzer = Analyzer::new(config, debug_llm)?;
+    if let Some(cache) = parse_cache {
+        analyzer = analyzer.with_parse_cache(cache);
+    }
+
+    if dry_run {
+        println!("🧪 Dry run - no LLM requests will be sent");
+        let plan = analyzer.plan_llm_analysis()?;
+        print_dry_run(&plan, &llm_provider, &llm_model);
+        return Ok(None);
+    }
+    analyzer = analyzer.with_checkpoints(output_path.join("pipeline"), resume)?;
+    let mut reporter = Reporter::with_scoring(scoring_config).with_maintainability(maintainability_config);
+    if report_config.deterministic {
+        reporter = reporter.with_fixed_timestamp(report_config.timestamp);
+    }
     
-    // Run analysis
-    let analysis = analyzer.analyze_project(skip_llm).await?;
+    // Ctrl-C and the deadline cancel the run through its token, which the
+    // synchronous stages check between files and passes; dropping the analysis
+    // when the watcher fires also abandons in-flight LLM requests
+    let file_list = files_from.as_deref().map(read_file_list).transpose()?;
+    let cancel = CancellationToken::new();
+    let mut watcher = tokio::spawn(watch_for_cancellation(cancel.clone(), max_duration));
+    analyzer = analyzer.with_cancellation(cancel);
+    let finished = tokio::select! {
+        analysis = async {
+            match file_list {
+                Some(list) => analyzer.analyze_file_list(&list, skip_llm).await,
+                None => analyzer.analyze_project(skip_llm).await,
+            }
+        } => Ok(analysis),
+        cancelled = &mut watcher => Err(cancelled),
+    };
+    let outcome = match finished {
+        Ok(Ok(analysis)) => Ok(analysis),
+        // Only the watcher cancels the token, and it has already finished or is about to
+        Ok(Err(project_examer::Error::Cancelled)) => Err((&mut watcher).await?),
+        Ok(Err(e)) => return Err(e.into()),
+        Err(cancelled) => Err(cancelled?),
+    };
+    watcher.abort();
+    let mut analysis = match outcome {
+        Ok(analysis) => analysis,
+        Err(cancelled) => {
+            println!("\n🛑 {}", cancelled);
+            let report = analyzer.partial_analysis().map(|(analysis, completed)| {
+                let names: Vec<&str> = completed.iter().map(Stage::name).collect();
+                println!("🧩 Completed stages: {}", names.join(", "));
+                reporter.generate_report(&analysis, start_time.elapsed().as_millis(), llm_provider.name(), &llm_model)
+            });
+            match report {
+                Some(report) => {
+                    let path = output_path.join("partial_report.json");
+                    std::fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+                    println!("📄 Partial results written to {}", path.display());
+                }
+                None => println!("📄 No partial results: discover, parse and graph hadn't completed"),
+            }
+            println!("♻️  Rerun with --resume to continue from the checkpoints in {}", output_path.join("pipeline").display());
+            return Err(cancelled.into());
+        }
+    };
+    analysis.revision = worktree.as_ref().map(|worktree| worktree.commit.clone());
+    // Left by an earlier interrupted run this one has now completed
+    let _ = std::fs::remove_file(output_path.join("partial_report.json"));
     
     let duration = start_time.elapsed();
     
     // Print summary
     analysis.print_summary();
+    if explain_excludes {
+        print_exclusions(&analysis.excluded_files);
+    }
     
     // Generate reports
-    println!("\n📊 Generating reports...");
-    let reporter = Reporter::new();
-    let provider_str = match llm_provider {
-        LLMProvider::OpenAI => "OpenAI",
-        LLMProvider::Ollama => "Ollama", 
-        LLMProvider::Anthropic => "Anthropic",
+    let export = analyzer.pipeline().is_enabled(Stage::Report);
+    if export {
+        println!("\n📊 Generating reports...");
+    }
+    let report = reporter.generate_report(&analysis, duration.as_millis(), llm_provider.name(), &llm_model);
+    let junit = JunitWriter::new(fail_on.unwrap_or(report_config.junit_fail_on));
+    let metrics = MetricsExporter::new(&metrics_config, &report);
+    let mut exported_files = match format {
+        _ if !export => Vec::new(),
+        None => reporter.export_report(&report, &output_path)?,
+        Some(ReportFormat::Json) => vec![reporter.export_json(&report, &output_path)?],
+        Some(ReportFormat::Html) => vec![reporter.export_html(&report, &output_path)?],
+        Some(ReportFormat::Markdown) => vec![reporter.export_markdown(&report, &output_path)?],
+        Some(ReportFormat::MarkdownReport) => vec![reporter.export_markdown_report(&report, &output_path)?],
+        Some(ReportFormat::Csv) => vec![reporter.export_csv(&report, &output_path)?],
+        Some(ReportFormat::Symbols) => SymbolIndex::from_parsed_files(&analysis.parsed_files).export(&output_path)?,
+        Some(ReportFormat::Graphml) => vec![GraphExporter::from_parsed_files(&analysis.parsed_files).export_graphml(&output_path)?],
+        Some(ReportFormat::Gexf) => vec![GraphExporter::from_parsed_files(&analysis.parsed_files).export_gexf(&output_path)?],
+        Some(ReportFormat::Junit) => vec![junit.write(&report, &output_path)?],
+        Some(ReportFormat::Prometheus) => vec![metrics.export(&output_path)?],
+        Some(ReportFormat::Scorecard) => Scorecard::from_report(&report, &scorecard_config).export(&output_path)?,
+        Some(ReportFormat::All) => {
+            let mut files = reporter.export_report(&report, &output_path)?;
+            files.extend(SymbolIndex::from_parsed_files(&analysis.parsed_files).export(&output_path)?);
+            let graph_exporter = GraphExporter::from_parsed_files(&analysis.parsed_files);
+            files.push(graph_exporter.export_graphml(&output_path)?);
+            files.push(graph_exporter.export_gexf(&output_path)?);
+            files.push(junit.write(&report, &output_path)?);
+            files.push(metrics.export(&output_path)?);
+            files.extend(Scorecard::from_report(&report, &scorecard_config).export(&output_path)?);
+            files
+        }
     };
-    let report = reporter.generate_report(&analysis, duration.as_millis(), provider_str, &llm_model);
-    let exported_files = reporter.export_report(&report, &output_path)?;
+    if export {
+        exported_files.extend(ProposedChangeWriter::new().write(&analysis, &output_path)?);
+        exported_files.extend(redaction::write_manifest(&analysis.data_sent, &output_path)?);
+    }
+    let checkpoint_dir = analyzer.pipeline().checkpoint_dir().map(Path::to_path_buf).unwrap_or_default();
+    if analyzer.pipeline().finish(persist_artifacts)? {
+        println!("♻️  Some LLM analyses failed; rerun with --resume to retry only those (checkpoints in {})", checkpoint_dir.display());
+    } else if persist_artifacts {
+        println!("🧩 Pipeline artifacts written to {}", checkpoint_dir.display());
+    }
+    let run_stats = analyzer.run_stats();
+    exported_files.push(run_stats.write(&output_path)?);
+    // Queries name files in the working copy, not the temporary worktree
+    let index = match &worktree {
+        Some(worktree) => FileIndex::build(&analysis, &report.maintainability, &worktree.target).rebase(&worktree.target, &target_path),
+        None => FileIndex::build(&analysis, &report.maintainability, &target_path),
+    };
+    exported_files.push(index.write(&output_path)?);
+
+    if export && metrics_config.push_enabled() {
+        match metrics.push().await {
+            Ok(targets) => {
+                for target in targets {
+                    println!("📈 Metrics pushed to {}", target);
+                }
+            }
+            Err(e) => println!("⚠️  Metrics push failed: {:#}", e),
+        }
+    }
+
+    if export && notifications_config.enabled() {
+        let notifier = Notifier::new(&notifications_config);
+        let local_report = output_path.join("analysis_report.html");
+        let local_report = local_report.exists()
+            .then(|| local_report.canonicalize().ok())
+            .flatten()
+            .map(|path| format!("file://{}", path.display()));
+        match notifier.notify(&notifier.summarize(&report, local_report)).await {
+            Ok(services) => {
+                for service in services {
+                    println!("💬 Run summary posted to {}", service);
+                }
+            }
+            Err(e) => println!("⚠️  Notification failed: {:#}", e),
+        }
+    }
     
     println!("\n✅ Analysis completed in {:.2}s", duration.as_secs_f64());
     println!("📁 Reports exported to:");
-    for file in exported_files {
+    for file in &exported_files {
         println!("   - {}", file.display());
     }
-    
+
+    let rule_failure = fail_on.and_then(|threshold| {
+        let failing = analysis.rule_findings.iter().filter(|f| f.severity >= threshold).count();
+        (failing > 0).then(|| format!("{} rule finding(s) at or above {:?} severity", failing, threshold))
+    });
+
+    Ok(Some(AnalysisRun {
+        exported_files,
+        run_stats,
+        index,
+        duration_secs: duration.as_secs_f64(),
+        rule_failure,
+    }))
+}
+
+async fn document_project(args: DocumentArgs) -> anyhow::Result<()> {
+    let DocumentArgs {
+        path: target_path,
+        config: config_path,
+        profile,
+        output: output_path,
+        skip_llm,
+        debug_llm,
+        offline,
+        allow_local_llm,
+    } = args;
+
+    println!("📚 Generating documentation bundle for {}", target_path.display());
+
+    let mut config = load_config(config_path, profile, &target_path)?;
+    config.analysis.analysis_types = vec![AnalysisType::Documentation];
+    config.analysis.summarize_modules = true;
+    if skip_llm {
+        println!("⚡ Skipping LLM analysis (local-only mode)");
+        config.llm.provider = LLMProvider::None;
+    }
+    apply_offline(&mut config, offline, allow_local_llm)?;
+
+    let mut analyzer = Analyzer::new(config, debug_llm)?;
+    let analysis = analyzer.analyze_project(skip_llm).await?;
+
+    let mut written = DocBundleWriter::new().write(&analysis, &target_path, &output_path)?;
+    written.extend(redaction::write_manifest(&analysis.data_sent, &output_path)?);
+    println!("\n✅ Documentation bundle written to:");
+    for file in written {
+        println!("   - {}", file.display());
+    }
+
+    Ok(())
+}
+
+async fn explain_file(args: ExplainArgs) -> anyhow::Result<()> {
+    let ExplainArgs {
+        file,
+        path: target_path,
+        config: config_path,
+        profile,
+        output,
+        debug_llm,
+        offline,
+        allow_local_llm,
+    } = args;
+
+    let mut config = load_config(config_path, profile, &target_path)?;
+    apply_offline(&mut config, offline, allow_local_llm)?;
+    let (provider, model) = (config.llm.provider.clone(), config.llm.model.clone());
+
+    // Progress goes to stderr: without --output, stdout is the explanation
+    eprintln!("🔎 Explaining {} with {} ({})...", file.display(), provider.name(), model);
+    let mut analyzer = Analyzer::new(config, debug_llm)?.without_progress();
+    let explanation = analyzer.explain_file(&file).await?;
+
+    match output {
+        Some(output) => {
+            let markdown = FileExplainer::new().to_markdown(&file, &explanation, provider.name(), &model);
+            if let Some(parent) = output.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&output, markdown)?;
+            eprintln!("📄 Explanation written to {}", output.display());
+        }
+        None => println!("{}", explanation.trim()),
+    }
+    Ok(())
+}
+
+async fn bench_project(args: BenchArgs) -> anyhow::Result<()> {
+    let BenchArgs {
+        path: target_path,
+        config: config_path,
+        profile,
+        output,
+        with_llm,
+        threads,
+        io_concurrency,
+    } = args;
+
+    println!("⏱️  Benchmarking {}", target_path.display());
+
+    let mut config = load_config(config_path, profile, &target_path)?;
+    apply_concurrency(&mut config, threads, io_concurrency);
+    if !with_llm {
+        config.llm.provider = LLMProvider::None;
+    }
+
+    let mut analyzer = Analyzer::new(config, false)?;
+    analyzer.analyze_project(!with_llm).await?;
+
+    let report = analyzer.perf_report();
+    report.print();
+    if let Some(output) = output {
+        println!();
+        for file in report.export(&output)? {
+            println!("📄 Wrote {}", file.display());
+        }
+    }
+
+    Ok(())
+}
+
+async fn compare_projects(args: CompareArgs) -> anyhow::Result<()> {
+    let CompareArgs {
+        left,
+        right,
+        config: config_path,
+        profile,
+        output,
+    } = args;
+
+    println!("⚖️  Comparing {} with {}", left.display(), right.display());
+
+    let mut analyses = Vec::new();
+    let mut maintainability = None;
+    for target in [&left, &right] {
+        println!("\n🎯 Analyzing {}", target.display());
+        let mut config = load_config(config_path.clone(), profile.clone(), target)?;
+        config.llm.provider = LLMProvider::None;
+        maintainability.get_or_insert_with(|| config.analysis.maintainability.clone());
+        let mut analyzer = Analyzer::new(config, false)?;
+        analyses.push(analyzer.analyze_project(true).await?);
+    }
+
+    let comparer = Comparer::new(maintainability.unwrap_or_default());
+    let comparison = comparer.compare(&left, &analyses[0], &right, &analyses[1]);
+    comparison.print();
+
+    println!();
+    for file in comparison.export(&output)? {
+        println!("📄 Wrote {}", file.display());
+    }
+
+    Ok(())
+}
+
+fn aggregate_reports(args: AggregateArgs) -> anyhow::Result<()> {
+    let paths = Aggregator::find_reports(&args.inputs)?;
+    if paths.is_empty() {
+        anyhow::bail!("No analysis_report.json found in the given paths");
+    }
+    println!("🏢 Aggregating {} report(s)", paths.len());
+
+    let mut reports = Vec::new();
+    for path in paths {
+        match Aggregator::load(&path) {
+            Ok(report) => reports.push((path, report)),
+            Err(e) => println!("⚠️  Skipping {:#}", e),
+        }
+    }
+    let aggregate = Aggregator::new(args.worst_files).aggregate(&reports);
+    aggregate.print();
+
+    println!();
+    for file in aggregate.export(&args.output)? {
+        println!("📄 Wrote {}", file.display());
+    }
+    Ok(())
+}
+
+async fn export_issues(args: ExportIssuesArgs) -> anyhow::Result<()> {
+    let ExportIssuesArgs {
+        report: report_path,
+        config: config_path,
+        tracker,
+        dry_run,
+    } = args;
+
+    let mut config = load_config(config_path, None, Path::new("."))?;
+    if let Some(tracker) = tracker {
+        config.issues.tracker = tracker;
+    }
+    if config.offline.enabled && !dry_run {
+        anyhow::bail!("Offline mode cannot create issues; use --dry-run to preview them");
+    }
+
+    let report: Report = serde_json::from_str(&std::fs::read_to_string(&report_path)
+        .with_context(|| format!("Reading {}", report_path.display()))?)
+        .with_context(|| format!("Parsing {}", report_path.display()))?;
+    let exporter = IssueExporter::new(&config.issues)?;
+    let drafts = exporter.drafts(&report);
+    println!("🎫 {} Critical/High recommendation(s) in {}", drafts.len(), report_path.display());
+
+    if dry_run {
+        for draft in &drafts {
+            println!("\n── {} [{}]\n{}", draft.title, draft.fingerprint, draft.body);
+        }
+        return Ok(());
+    }
+
+    let existing = exporter.existing_fingerprints().await?;
+    let mut created = 0;
+    for draft in &drafts {
+        if existing.contains(&draft.fingerprint) {
+            println!("   ⏭️  Already filed: {}", draft.title);
+            continue;
+        }
+        let issue = exporter.create(draft).await?;
+        println!("   ✅ {} → {}", draft.title, issue);
+        created += 1;
+    }
+    println!("\n🎫 Filed {} new issue(s), {} already existed", created, drafts.len() - created);
+
+    Ok(())
+}
+
+/// Stdout carries the protocol, so everything else goes to stderr
+fn serve_lsp(args: LspArgs) -> anyhow::Result<()> {
+    let mut server = LspServer::new(&args.index)?;
+    eprintln!("project-examer: serving {} over stdio", args.index.display());
+    Ok(server.run(std::io::stdin().lock(), std::io::stdout().lock())?)
+}
+
+#[cfg(unix)]
+async fn serve_daemon(args: DaemonArgs) -> anyhow::Result<()> {
+    let socket = daemon::socket_path(&args.path);
+    if args.status || args.stop {
+        let mut client = DaemonClient::connect(&socket).await
+            .ok_or_else(|| anyhow::anyhow!("No daemon is serving {}", args.path.display()))?;
+        if args.stop {
+            client.request(&DaemonRequest::Stop).await?;
+            println!("🛑 Daemon stopped");
+        } else {
+            println!("{}", serde_json::to_string_pretty(&client.request(&DaemonRequest::Status).await?)?);
+        }
+        return Ok(());
+    }
+
+    let mut daemon = Daemon::new(&socket);
+    if let Ok(index) = FileIndex::load(&args.index) {
+        daemon = daemon.with_index(index);
+    }
+    let cache = daemon.cache();
+    println!("👂 Daemon for {} listening on {}", args.path.display(), socket.display());
+    println!("   `analyze` and `query` there now run here; stop with Ctrl-C or `project-examer daemon --stop`");
+
+    daemon.serve(|request| {
+        let cache = cache.clone();
+        async move {
+            let run = async {
+                let args: AnalyzeArgs = serde_json::from_value(request.args)?;
+                run_analysis(args.resolved_from(&request.cwd), Some(cache), &request.env).await?
+                    .ok_or_else(|| anyhow::anyhow!("Dry runs are not sent to the daemon"))
+            };
+            // The whole chain, as the client would have printed it
+            let run = run.await.map_err(|e| DaemonFailure {
+                kind: e.downcast_ref::<Cancelled>().map_or(FailureKind::Error, Cancelled::kind),
+                message: format!("{:#}", e),
+            })?;
+            let summary = serde_json::json!({
+                "duration_secs": run.duration_secs,
+                "files_parsed": run.run_stats.files_parsed,
+                "files_from_cache": run.run_stats.files_from_cache,
+                "exported_files": run.exported_files,
+                "rule_failure": run.rule_failure,
+            });
+            Ok((summary, run.index))
+        }
+    }).await?;
+    println!("👋 Daemon stopped");
+    Ok(())
+}
+
+async fn run_scheduled(args: RunArgs) -> anyhow::Result<()> {
+    let RunArgs { schedule, analyze } = args;
+    if analyze.dry_run || analyze.files_from.as_deref() == Some(Path::new("-")) {
+        anyhow::bail!("Scheduled runs can't use --dry-run or read --files-from from stdin");
+    }
+    let config = load_config(analyze.config.clone(), analyze.profile.clone(), &analyze.path)?.schedule;
+    let expression = schedule.or(config.cron.clone())
+        .ok_or_else(|| anyhow::anyhow!("Set a schedule with --schedule or schedule.cron"))?;
+    let cron = CronSchedule::parse(&expression)?;
+    let rotation = RunRotation::new(&analyze.output, &config);
+    // Unchanged files are parsed once for the whole life of the scheduler
+    let cache = Arc::new(ParseCache::new());
+
+    println!("⏰ Analyzing {} on \"{}\"; runs are written under {}", analyze.path.display(), expression, analyze.output.display());
+    let mut run_now = config.run_on_start;
+    loop {
+        if !run_now {
+            let now = chrono::Local::now();
+            let next = cron.next_after(&now)
+                .ok_or_else(|| anyhow::anyhow!("Schedule \"{}\" never fires", expression))?;
+            println!("⏳ Next run at {}", next.format("%Y-%m-%d %H:%M %:z"));
+            tokio::select! {
+                _ = tokio::time::sleep((next - now).to_std().unwrap_or_default()) => {}
+                _ = tokio::signal::ctrl_c() => {
+                    println!("👋 Scheduler stopped");
+                    return Ok(());
+                }
+            }
+        }
+        run_now = false;
+
+        let started = chrono::Local::now().naive_local();
+        let run_dir = rotation.run_dir(started);
+        let mut args = analyze.clone();
+        args.output = run_dir.clone();
+        match run_analysis(args, Some(cache.clone()), &Config::env_vars()).await {
+            Ok(run) => {
+                if let Some(failure) = run.and_then(|run| run.rule_failure) {
+                    println!("⚠️  {}", failure);
+                }
+                if let Err(e) = rotation.mark_latest(&run_dir) {
+                    println!("⚠️  Could not update the latest link: {:#}", e);
+                }
+            }
+            Err(e) if matches!(e.downcast_ref::<Cancelled>(), Some(Cancelled::Interrupted)) => {
+                println!("👋 Scheduler stopped");
+                return Ok(());
+            }
+            Err(e) => println!("⚠️  Scheduled run failed: {:#}", e),
+        }
+        match rotation.prune(started) {
+            Ok(removed) => {
+                for dir in removed {
+                    println!("🗑️  Removed old run {}", dir.display());
+                }
+            }
+            Err(e) => println!("⚠️  Could not apply the retention policy: {:#}", e),
+        }
+    }
+}
+
+async fn query_file(args: QueryArgs) -> anyhow::Result<()> {
+    let file = args.file.canonicalize().with_context(|| format!("Resolving {}", args.file.display()))?;
+
+    #[cfg(unix)]
+    if let Some(mut client) = DaemonClient::connect(&daemon::socket_path(&args.path)).await {
+        let result = client.request(&DaemonRequest::Query { kind: args.kind, path: file }).await?;
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    let result = FileIndex::load(&args.index)?.query(args.kind, &file)?;
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    Ok(())
+}
+
+fn search_symbols(args: SearchArgs) -> anyhow::Result<()> {
+    let query = SymbolQuery::new(&args.pattern, args.ignore_case, &args.path)?
+        .with_kinds(args.kind)
+        .with_languages(args.language)
+        .with_paths(&args.globs)?
+        .exported_only(args.exported);
+
+    let mut config = load_config(args.config, args.profile, &args.path)?;
+    config.llm.provider = LLMProvider::None;
+    let mut analyzer = Analyzer::new(config, false)?.without_progress();
+    let index = SymbolIndex::from_parsed_files(&analyzer.parse_project()?);
+    let matches = index.search(&query);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&matches)?);
+    } else {
+        for symbol in &matches {
+            let kind = format!("{:?}", symbol.kind).to_lowercase();
+            match symbol.container {
+                Some(ref container) => println!("{}:{}: {} {}.{}", symbol.path, symbol.line, kind, container, symbol.name),
+                None => println!("{}:{}: {} {}", symbol.path, symbol.line, kind, symbol.name),
+            }
+        }
+    }
+    if matches.is_empty() {
+        // Like grep, no match is a non-zero exit
+        std::process::exit(1);
+    }
     Ok(())
 }
 
+/// Apply `--threads` / `--io-concurrency` over the configured values
+fn apply_concurrency(config: &mut Config, threads: Option<usize>, io_concurrency: Option<usize>) {
+    if let Some(threads) = threads {
+        config.analysis.threads = threads;
+    }
+    if let Some(io_concurrency) = io_concurrency {
+        config.analysis.io_concurrency = io_concurrency;
+    }
+}
+
+/// Apply `--offline` / `--allow-local-llm` and switch off any provider that
+/// would need the network
+fn apply_offline(config: &mut Config, offline: bool, allow_local_llm: bool) -> anyhow::Result<()> {
+    config.offline.enabled |= offline;
+    config.offline.allow_local_llm |= allow_local_llm;
+    if config.offline.enabled {
+        println!("🔒 Offline mode: no network calls will be made");
+    }
+    if let Some(provider) = config.enforce_offline()? {
+        println!("🔒 Offline: skipping {} LLM analysis", provider.name());
+    }
+    Ok(())
+}
+
+/// Load configuration from `--config` or the project/user config, then apply
+/// the profile and point it at the target directory
+fn load_config(config_path: Option<PathBuf>, profile: Option<String>, target_path: &Path) -> anyhow::Result<Config> {
+    load_config_with_env(config_path, profile, target_path, &Config::env_vars())
+}
+
+/// `load_config` reading overrides and API keys from `env` rather than this
+/// process's environment
+fn load_config_with_env(config_path: Option<PathBuf>, profile: Option<String>, target_path: &Path, env: &BTreeMap<String, String>) -> anyhow::Result<Config> {
+    let mut config = if let Some(config_path) = config_path {
+        let mut config = Config::from_file(&config_path)?;
+        config.apply_overrides(env.clone())?;
+        config
+    } else {
+        Config::load_for_env(target_path, env)?
+    };
+
+    if let Some(profile) = profile {
+        eprintln!("🎛️  Using profile: {}", profile);
+        config.apply_profile(&profile)?;
+    }
+
+    // Override target directory
+    config.target_directory = target_path.to_path_buf();
+    Ok(config)
+}
+
+/// Seconds, optionally suffixed with `s`, `m` or `h`
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let (number, unit) = match value.trim().char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((position, _)) => value.trim().split_at(position),
+        None => (value.trim(), "s"),
+    };
+    let number: u64 = number.parse().map_err(|_| format!("`{}` is not a duration like 900, 90s, 15m or 2h", value))?;
+    match unit {
+        "s" => Ok(Duration::from_secs(number)),
+        "m" => Ok(Duration::from_secs(number * 60)),
+        "h" => Ok(Duration::from_secs(number * 3600)),
+        _ => Err(format!("unknown unit `{}` in `{}`; use s, m or h", unit, value)),
+    }
+}
+
+/// Newline-separated paths from a file, or from stdin when `list` is `-`
+fn read_file_list(list: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let content = if list == Path::new("-") {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        std::fs::read_to_string(list)?
+    };
+    Ok(content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+fn print_exclusions(excluded_files: &[SkippedFile]) {
+    println!("\n🚫 Excluded files: {}", excluded_files.len());
+    for file in excluded_files {
+        println!("  {} ({})", file.path.display(), file.rule);
+    }
+    println!("  Files matched by .gitignore are skipped during the walk and not listed.");
+}
+
+fn print_dry_run(plan: &[PlannedLLMCall], provider: &LLMProvider, model: &str) {
+    for call in plan {
+        println!("\n📨 {} analysis (~{} input tokens, up to {} output tokens)", call.name, call.input_tokens, call.max_output_tokens);
+        println!("---- system prompt ----\n{}", call.system_prompt);
+        println!("---- user prompt ----\n{}", call.user_prompt);
+    }
+
+    let input_tokens: usize = plan.iter().map(|c| c.input_tokens).sum();
+    let output_tokens: usize = plan.iter().map(|c| c.max_output_tokens).sum();
+
+    println!("\n💰 Estimated usage for {} call(s): ~{} input tokens, up to {} output tokens", plan.len(), input_tokens, output_tokens);
+    match ModelPricing::lookup(provider, model) {
+        Some(pricing) => println!("   Configured model {:?}/{}: up to ${:.4}", provider, model, pricing.cost(input_tokens, output_tokens)),
+        None => println!("   Configured model {:?}/{}: no pricing data", provider, model),
+    }
+    println!("   Other providers:");
+    for pricing in MODEL_PRICING {
+        println!("     {:?}/{}: up to ${:.4}", pricing.provider, pricing.model, pricing.cost(input_tokens, output_tokens));
+    }
+}
+
 fn generate_config(output_path: Option<PathBuf>) -> anyhow::Result<()> {
     let config_path = output_path.unwrap_or_else(|| {
         Config::default_config_path().unwrap_or_else(|_| PathBuf::from("project-examer.toml"))
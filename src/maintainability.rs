@@ -0,0 +1,90 @@
+use crate::{
+    config::MaintainabilityConfig,
+    scoring::ScoringModel,
+    simple_parser::ParsedFile,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileMaintainability {
+    pub path: PathBuf,
+    pub lines_of_code: usize,
+    pub cyclomatic_complexity: usize,
+    /// Comment lines as a share of non-blank lines (0.0-1.0)
+    pub comment_ratio: f64,
+    /// Maintainability index normalized to 0-100; higher is easier to maintain
+    pub index: f64,
+    pub rating: MaintainabilityRating,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MaintainabilityRating {
+    Green,
+    Yellow,
+    Red,
+}
+
+impl MaintainabilityRating {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MaintainabilityRating::Green => "green",
+            MaintainabilityRating::Yellow => "yellow",
+            MaintainabilityRating::Red => "red",
+        }
+    }
+}
+
+/// Per-file maintainability index using the classic formula
+/// `171 - 5.2 ln(V) - 0.23 CC - 16.2 ln(LOC) + 50 sin(sqrt(2.4 CM))`,
+/// rescaled to 0-100, where `V` is the Halstead volume, `CC` the
+/// cyclomatic complexity and `CM` the comment ratio.
+pub struct MaintainabilityAnalyzer {
+    config: MaintainabilityConfig,
+}
+
+impl MaintainabilityAnalyzer {
+    pub fn new(config: MaintainabilityConfig) -> Self {
+        Self { config }
+    }
+
+    /// Every parsed source file, least maintainable first; documentation and data files are skipped
+    pub fn analyze(&self, parsed_files: &[ParsedFile]) -> Vec<FileMaintainability> {
+        let mut files: Vec<FileMaintainability> = parsed_files.iter()
+            .filter(|pf| pf.lines_of_code > 0)
+            .filter(|pf| !matches!(pf.file_info.language.as_deref(),
+                Some("markdown" | "text" | "json" | "yaml" | "toml")))
+            .map(|pf| self.measure(pf))
+            .collect();
+        files.sort_by(|a, b| a.index.total_cmp(&b.index).then_with(|| a.path.cmp(&b.path)));
+        files
+    }
+
+    pub fn measure(&self, parsed_file: &ParsedFile) -> FileMaintainability {
+        let loc = parsed_file.lines_of_code.max(1) as f64;
+        let volume = ScoringModel::halstead_volume(parsed_file).max(1.0);
+        let cyclomatic = parsed_file.cyclomatic_complexity.max(1);
+        let comment_ratio = (parsed_file.comment_lines as f64 / loc).min(1.0);
+
+        let raw = 171.0 - 5.2 * volume.ln() - 0.23 * cyclomatic as f64 - 16.2 * loc.ln()
+            + 50.0 * (2.4 * comment_ratio).sqrt().sin();
+        let index = (raw * 100.0 / 171.0).clamp(0.0, 100.0);
+
+        let rating = if index < self.config.red_below {
+            MaintainabilityRating::Red
+        } else if index < self.config.yellow_below {
+            MaintainabilityRating::Yellow
+        } else {
+            MaintainabilityRating::Green
+        };
+
+        FileMaintainability {
+            path: parsed_file.file_info.path.clone(),
+            lines_of_code: parsed_file.lines_of_code,
+            cyclomatic_complexity: cyclomatic,
+            comment_ratio,
+            index,
+            rating,
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use crate::{file_discovery::FileInfo, sources::SourceSet};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_NAMES: &[&str] = &["Cargo.toml", "package.json", "pyproject.toml", "requirements.txt", "requirements-dev.txt"];
+
+/// A package registry a manifest's dependencies come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    PyPI,
+}
+
+impl Ecosystem {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ecosystem::Cargo => "crates.io",
+            Ecosystem::Npm => "npm",
+            Ecosystem::PyPI => "PyPI",
+        }
+    }
+}
+
+/// A dependency declared with a registry version in a manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeclaredDependency {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    /// The requirement as written, e.g. `^1.2`, `>=2.0,<3`, `0.4`
+    pub requirement: String,
+    /// Lowest version the requirement allows, e.g. `1.2` for `^1.2`
+    pub version: Option<String>,
+    pub manifest: PathBuf,
+    /// Development, test or build-only dependency
+    pub dev: bool,
+}
+
+/// A dotted version compared by its first three numeric components
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    /// Missing components count as zero; pre-release and build suffixes are ignored
+    pub fn parse(text: &str) -> Option<Self> {
+        let core = text.trim().trim_start_matches(['v', 'V']);
+        let core = core.split(['-', '+']).next()?;
+        let mut parts = core.split('.').map(|part| part.parse::<u64>());
+        Some(Self {
+            major: parts.next()?.ok()?,
+            minor: parts.next().and_then(Result::ok).unwrap_or(0),
+            patch: parts.next().and_then(Result::ok).unwrap_or(0),
+        })
+    }
+
+    pub fn is_prerelease(text: &str) -> bool {
+        text.contains('-') || text.chars().any(|c| c.is_ascii_alphabetic() && !matches!(c, 'v' | 'V'))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Finds Cargo.toml, package.json, pyproject.toml and requirements files in the
+/// directories of the discovered files and lists their registry dependencies
+pub struct ManifestScanner {
+    root: PathBuf,
+    version: Regex,
+    requirement_line: Regex,
+}
+
+impl ManifestScanner {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            version: Regex::new(r"\d+(\.\d+)*").unwrap(),
+            // PEP 508: name, optional extras, then the version specifier up to a marker
+            requirement_line: Regex::new(r"^([A-Za-z0-9][A-Za-z0-9._-]*)\s*(\[[^\]]*\])?\s*\(?([^;()]*)").unwrap(),
+        }
+    }
+
+    pub fn scan(&self, files: &[FileInfo], sources: &SourceSet) -> Vec<DeclaredDependency> {
+        let mut directories = BTreeSet::new();
+        directories.insert(self.root.clone());
+        for file in files {
+            for directory in file.path.ancestors().skip(1) {
+                if !directory.starts_with(&self.root) || !directories.insert(directory.to_path_buf()) {
+                    break;
+                }
+            }
+        }
+
+        let mut dependencies = Vec::new();
+        for directory in directories {
+            for name in MANIFEST_NAMES {
+                let manifest = directory.join(name);
+                let Ok(content) = sources.read(&manifest) else { continue };
+                let relative = manifest.strip_prefix(&self.root).unwrap_or(&manifest).to_path_buf();
+                match *name {
+                    "Cargo.toml" => self.cargo(&content, &relative, &mut dependencies),
+                    "package.json" => self.npm(&content, &relative, &mut dependencies),
+                    "pyproject.toml" => self.pyproject(&content, &relative, &mut dependencies),
+                    _ => {
+                        let dev = name.contains("dev");
+                        for line in content.lines() {
+                            self.push_pep508(line, &relative, dev, &mut dependencies);
+                        }
+                    }
+                }
+            }
+        }
+        dependencies
+    }
+
+    /// The first version not bounding the requirement from above (`<2`, `!=1.3`)
+    fn lowest_version(&self, requirement: &str) -> Option<String> {
+        requirement.split([',', ' '])
+            .map(str::trim)
+            .filter(|clause| !clause.starts_with(['<', '!']))
+            .find_map(|clause| self.version.find(clause))
+            .map(|m| m.as_str().to_string())
+    }
+
+    fn push(&self, ecosystem: Ecosystem, name: &str, requirement: &str, manifest: &Path, dev: bool, dependencies: &mut Vec<DeclaredDependency>) {
+        let requirement = requirement.trim();
+        dependencies.push(DeclaredDependency {
+            ecosystem,
+            name: name.to_string(),
+            requirement: requirement.to_string(),
+            version: self.lowest_version(requirement),
+            manifest: manifest.to_path_buf(),
+            dev,
+        });
+    }
+
+    fn cargo(&self, content: &str, manifest: &Path, dependencies: &mut Vec<DeclaredDependency>) {
+        let Ok(toml::Value::Table(document)) = toml::from_str::<toml::Value>(content) else { return };
+        let mut tables: Vec<(&toml::Value, bool)> = Vec::new();
+        for (table, dev) in [("dependencies", false), ("dev-dependencies", true), ("build-dependencies", true)] {
+            tables.extend(document.get(table).map(|t| (t, dev)));
+            tables.extend(document.get("workspace").and_then(|w| w.get(table)).map(|t| (t, dev)));
+            if let Some(toml::Value::Table(targets)) = document.get("target") {
+                tables.extend(targets.values().filter_map(|target| target.get(table)).map(|t| (t, dev)));
+            }
+        }
+
+        for (table, dev) in tables {
+            let Some(table) = table.as_table() else { continue };
+            for (key, spec) in table {
+                // Path, git and `workspace = true` dependencies carry no registry version
+                let (requirement, name) = match spec {
+                    toml::Value::String(requirement) => (requirement.as_str(), key.as_str()),
+                    toml::Value::Table(spec) if spec.get("path").is_none() && spec.get("git").is_none() => {
+                        let Some(requirement) = spec.get("version").and_then(|v| v.as_str()) else { continue };
+                        (requirement, spec.get("package").and_then(|p| p.as_str()).unwrap_or(key))
+                    }
+                    _ => continue,
+                };
+                self.push(Ecosystem::Cargo, name, requirement, manifest, dev, dependencies);
+            }
+        }
+    }
+
+    fn npm(&self, content: &str, manifest: &Path, dependencies: &mut Vec<DeclaredDependency>) {
+        let Ok(document) = serde_json::from_str::<serde_json::Value>(content) else { return };
+        for (section, dev) in [("dependencies", false), ("optionalDependencies", false), ("devDependencies", true)] {
+            let Some(section) = document.get(section).and_then(|s| s.as_object()) else { continue };
+            for (name, requirement) in section {
+                let Some(requirement) = requirement.as_str() else { continue };
+                // Local, git, workspace and alias specifiers, and tags like `latest`
+                if requirement.contains(':') || requirement.contains('/') || self.lowest_version(requirement).is_none() {
+                    continue;
+                }
+                self.push(Ecosystem::Npm, name, requirement, manifest, dev, dependencies);
+            }
+        }
+    }
+
+    fn pyproject(&self, content: &str, manifest: &Path, dependencies: &mut Vec<DeclaredDependency>) {
+        let Ok(document) = toml::from_str::<toml::Value>(content) else { return };
+
+        let project = document.get("project");
+        for requirement in project.and_then(|p| p.get("dependencies")).and_then(|d| d.as_array()).into_iter().flatten() {
+            if let Some(requirement) = requirement.as_str() {
+                self.push_pep508(requirement, manifest, false, dependencies);
+            }
+        }
+        let optional = project.and_then(|p| p.get("optional-dependencies")).and_then(|o| o.as_table());
+        for group in optional.into_iter().flat_map(|groups| groups.values()) {
+            for requirement in group.as_array().into_iter().flatten().filter_map(|r| r.as_str()) {
+                self.push_pep508(requirement, manifest, true, dependencies);
+            }
+        }
+
+        let Some(poetry) = document.get("tool").and_then(|t| t.get("poetry")) else { return };
+        let mut tables: Vec<(&toml::Value, bool)> = Vec::new();
+        tables.extend(poetry.get("dependencies").map(|t| (t, false)));
+        tables.extend(poetry.get("dev-dependencies").map(|t| (t, true)));
+        if let Some(groups) = poetry.get("group").and_then(|g| g.as_table()) {
+            tables.extend(groups.values().filter_map(|group| group.get("dependencies")).map(|t| (t, true)));
+        }
+        for (table, dev) in tables {
+            for (name, spec) in table.as_table().into_iter().flatten() {
+                let requirement = match spec {
+                    toml::Value::String(requirement) => requirement.as_str(),
+                    toml::Value::Table(spec) => match spec.get("version").and_then(|v| v.as_str()) {
+                        Some(requirement) => requirement,
+                        None => continue,
+                    },
+                    _ => continue,
+                };
+                if name != "python" && self.lowest_version(requirement).is_some() {
+                    self.push(Ecosystem::PyPI, name, requirement, manifest, dev, dependencies);
+                }
+            }
+        }
+    }
+
+    /// One requirements.txt line or pyproject requirement string
+    fn push_pep508(&self, line: &str, manifest: &Path, dev: bool, dependencies: &mut Vec<DeclaredDependency>) {
+        let line = line.split(" #").next().unwrap_or(line).trim();
+        if line.is_empty() || line.starts_with(['#', '-']) || line.contains("://") || line.contains(" @ ") {
+            return;
+        }
+        let Some(captures) = self.requirement_line.captures(line) else { return };
+        let requirement = captures[3].trim();
+        if self.lowest_version(requirement).is_some() {
+            self.push(Ecosystem::PyPI, &captures[1], requirement, manifest, dev, dependencies);
+        }
+    }
+}
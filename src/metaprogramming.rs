@@ -0,0 +1,239 @@
+use crate::{file_discovery::FileInfo, module_rollup::ModuleAnalyzer, sources::SourceSet};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Most used macros and decorators listed per module
+const TOP_NAMES: usize = 5;
+
+/// Built-in Rust attributes and derive helper attributes, which aren't macro invocations
+const RUST_ATTRIBUTES: &[&str] = &[
+    "allow", "warn", "deny", "forbid", "expect", "cfg", "cfg_attr", "test", "ignore", "should_panic", "doc",
+    "inline", "cold", "must_use", "repr", "non_exhaustive", "deprecated", "path", "macro_export", "macro_use",
+    "automatically_derived", "track_caller", "no_mangle", "link", "export_name", "used", "global_allocator",
+    "rustfmt", "clippy", "derive", "default", "serde", "error", "from", "source", "backtrace", "arg", "command",
+    "value", "clap", "sqlx", "schemars", "strum", "proc_macro", "proc_macro_derive", "proc_macro_attribute",
+];
+
+/// Python decorators that only change how a method binds
+const PYTHON_DECORATORS: &[&str] = &["property", "staticmethod", "classmethod", "abstractmethod", "override", "setter", "getter", "deleter"];
+
+/// A kind of metaprogramming
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetaKind {
+    /// `#[derive(...)]`, one site per derived trait
+    Derive,
+    /// Rust attribute macros such as `#[tokio::main]`
+    AttributeMacro,
+    /// `macro_rules!` and procedural macro definitions
+    MacroDefinition,
+    /// TypeScript/JavaScript and Python decorators
+    Decorator,
+    /// Python metaclasses, `__init_subclass__` and classes built with `type()`
+    Metaclass,
+    /// Build scripts and code generated at build time
+    Codegen,
+}
+
+impl MetaKind {
+    pub const ALL: [MetaKind; 6] = [MetaKind::Derive, MetaKind::AttributeMacro, MetaKind::MacroDefinition,
+        MetaKind::Decorator, MetaKind::Metaclass, MetaKind::Codegen];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MetaKind::Derive => "derive",
+            MetaKind::AttributeMacro => "attribute macro",
+            MetaKind::MacroDefinition => "macro definition",
+            MetaKind::Decorator => "decorator",
+            MetaKind::Metaclass => "metaclass",
+            MetaKind::Codegen => "codegen",
+        }
+    }
+}
+
+/// One use of metaprogramming
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetaSite {
+    pub file: PathBuf,
+    pub line: usize,
+    pub kind: MetaKind,
+    /// The derived trait, macro, decorator or generator, e.g. `Serialize` or `tokio::main`
+    pub name: String,
+}
+
+/// Metaprogramming used by one module (directory, relative to the project root)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetaModule {
+    pub module: String,
+    pub counts: BTreeMap<MetaKind, usize>,
+    pub total: usize,
+    /// Most used names first, as (name, uses)
+    pub top_names: Vec<(String, usize)>,
+    /// Relies on code that is generated or defines its own macros, so reading the
+    /// source alone doesn't show everything that runs
+    pub generates_code: bool,
+}
+
+/// Derives, macros, decorators, metaclasses and build-time code generation in
+/// Rust, TypeScript/JavaScript and Python
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetaprogrammingInventory {
+    pub sites: Vec<MetaSite>,
+    /// Modules that generate code first, then by number of sites
+    pub modules: Vec<MetaModule>,
+}
+
+impl MetaprogrammingInventory {
+    pub fn is_empty(&self) -> bool {
+        self.sites.is_empty()
+    }
+
+    /// One line per module, for the Refactoring and Documentation LLM analyses
+    pub fn context_lines(&self) -> Vec<String> {
+        self.modules.iter()
+            .map(|module| {
+                let counts = module.counts.iter()
+                    .map(|(kind, count)| format!("{} {}", count, kind.name()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let names = module.top_names.iter()
+                    .map(|(name, uses)| format!("{} ×{}", name, uses))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("Module {}: {} (most used: {}){}", module.module, counts, names,
+                    if module.generates_code { "; relies on generated code or its own macros" } else { "" })
+            })
+            .collect()
+    }
+}
+
+/// Line patterns recognizing metaprogramming per language
+pub struct MetaprogrammingScanner {
+    root: PathBuf,
+    rust_derive: Regex,
+    rust_attribute: Regex,
+    rust_macro_rules: Regex,
+    rust_codegen: Regex,
+    decorator: Regex,
+    python_metaclass: Regex,
+    generated_import: Regex,
+}
+
+impl MetaprogrammingScanner {
+    pub fn new(root: &Path) -> Self {
+        let regex = |pattern: &str| Regex::new(pattern).expect("valid metaprogramming pattern");
+        Self {
+            root: root.to_path_buf(),
+            rust_derive: regex(r"#\[derive\(([^\]]*)\)\]"),
+            rust_attribute: regex(r"^\s*#!?\[\s*([\w:]+)"),
+            rust_macro_rules: regex(r"\bmacro_rules!\s*(\w+)|#\[(proc_macro(?:_derive|_attribute)?)\b"),
+            rust_codegen: regex(r#"\b(include_proto!|include!\s*\(\s*concat!\s*\(\s*env!\s*\(\s*"OUT_DIR")"#),
+            decorator: regex(r"^\s*@([\w.]+)"),
+            python_metaclass: regex(r"\b(metaclass)\s*=|\bdef\s+(__init_subclass__)\b|\b(type)\s*\(\s*['\w]+\s*,\s*\(|^\s*class\s+\w+\s*\(\s*(type)\s*\)"),
+            generated_import: regex(r#"(?:from|import|require\s*\()\s*['"]?([\w./@-]*(?:_pb2(?:_grpc)?|\.pb|__generated__|\.generated|\.gen)\b[\w./-]*)"#),
+        }
+    }
+
+    pub fn analyze(&self, files: &[FileInfo], sources: &SourceSet) -> MetaprogrammingInventory {
+        let mut sites = Vec::new();
+        for file in files {
+            let Some(language) = file.language.as_deref() else { continue };
+            if !matches!(language, "rust" | "typescript" | "javascript" | "python") {
+                continue;
+            }
+            if language == "rust" && file.path.file_name().is_some_and(|name| name == "build.rs") {
+                sites.push(MetaSite { file: file.path.clone(), line: 1, kind: MetaKind::Codegen, name: "build.rs".to_string() });
+            }
+            let Ok(content) = sources.read(&file.path) else { continue };
+
+            for (index, line) in content.lines().enumerate() {
+                if line.trim_start().starts_with("//") {
+                    continue;
+                }
+                let mut site = |kind: MetaKind, name: &str| sites.push(MetaSite {
+                    file: file.path.clone(),
+                    line: index + 1,
+                    kind,
+                    name: name.to_string(),
+                });
+
+                if let Some(captures) = self.generated_import.captures(line) {
+                    site(MetaKind::Codegen, &captures[1]);
+                }
+                match language {
+                    "rust" => {
+                        if let Some(captures) = self.rust_derive.captures(line) {
+                            for derived in captures[1].split(',').map(str::trim).filter(|name| !name.is_empty()) {
+                                site(MetaKind::Derive, derived);
+                            }
+                        } else if let Some(captures) = self.rust_macro_rules.captures(line) {
+                            site(MetaKind::MacroDefinition, captures.get(1).or(captures.get(2)).map_or("", |m| m.as_str()));
+                        } else if let Some(captures) = self.rust_attribute.captures(line) {
+                            let name = &captures[1];
+                            if !RUST_ATTRIBUTES.contains(&name) {
+                                site(MetaKind::AttributeMacro, name);
+                            }
+                        }
+                        if let Some(captures) = self.rust_codegen.captures(line) {
+                            site(MetaKind::Codegen, if captures[1].starts_with("include_proto") { "include_proto!" } else { "include!(OUT_DIR)" });
+                        }
+                    }
+                    "python" => {
+                        if let Some(captures) = self.decorator.captures(line) {
+                            let name = &captures[1];
+                            if !PYTHON_DECORATORS.contains(&name.rsplit('.').next().unwrap_or(name)) {
+                                site(MetaKind::Decorator, name);
+                            }
+                        } else if let Some(captures) = self.python_metaclass.captures(line) {
+                            let name = (1..=4).find_map(|group| captures.get(group)).map_or("", |m| m.as_str());
+                            site(MetaKind::Metaclass, name);
+                        }
+                    }
+                    _ => {
+                        if let Some(captures) = self.decorator.captures(line) {
+                            site(MetaKind::Decorator, &captures[1]);
+                        }
+                    }
+                }
+            }
+        }
+
+        // The default ignore patterns leave a root build script out of discovery
+        let build_script = self.root.join("build.rs");
+        if !sites.iter().any(|site| site.file == build_script) && sources.read(&build_script).is_ok() {
+            sites.push(MetaSite { file: build_script, line: 1, kind: MetaKind::Codegen, name: "build.rs".to_string() });
+        }
+
+        let mut modules: BTreeMap<String, Vec<&MetaSite>> = BTreeMap::new();
+        for site in &sites {
+            modules.entry(ModuleAnalyzer::module_name(site.file.strip_prefix(&self.root).unwrap_or(&site.file)))
+                .or_default()
+                .push(site);
+        }
+        let mut modules: Vec<MetaModule> = modules.into_iter()
+            .map(|(module, module_sites)| {
+                let mut counts = BTreeMap::new();
+                let mut names: BTreeMap<&str, usize> = BTreeMap::new();
+                for site in &module_sites {
+                    *counts.entry(site.kind).or_insert(0) += 1;
+                    *names.entry(site.name.as_str()).or_insert(0) += 1;
+                }
+                let mut top_names: Vec<(String, usize)> = names.into_iter().map(|(name, uses)| (name.to_string(), uses)).collect();
+                top_names.sort_by_key(|(_, uses)| std::cmp::Reverse(*uses));
+                top_names.truncate(TOP_NAMES);
+                MetaModule {
+                    total: module_sites.len(),
+                    generates_code: counts.contains_key(&MetaKind::Codegen) || counts.contains_key(&MetaKind::MacroDefinition),
+                    module,
+                    counts,
+                    top_names,
+                }
+            })
+            .collect();
+        modules.sort_by_key(|module| std::cmp::Reverse((module.generates_code, module.total)));
+
+        MetaprogrammingInventory { sites, modules }
+    }
+}
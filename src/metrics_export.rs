@@ -0,0 +1,212 @@
+use crate::{
+    config::MetricsConfig,
+    maintainability::MaintainabilityRating,
+    reporter::Report,
+    rules::Severity,
+};
+use crate::{Error, Result};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Prefix of every metric name (`project_examer_files` in Prometheus, `project_examer.files` in OTLP)
+const PREFIX: &str = "project_examer";
+
+/// A gauge reading, optionally split by one label
+struct Metric {
+    name: &'static str,
+    help: &'static str,
+    unit: &'static str,
+    points: Vec<(Option<(&'static str, String)>, f64)>,
+}
+
+impl Metric {
+    fn single(name: &'static str, help: &'static str, unit: &'static str, value: f64) -> Self {
+        Self { name, help, unit, points: vec![(None, value)] }
+    }
+
+    fn split(name: &'static str, help: &'static str, label: &'static str, values: impl IntoIterator<Item = (String, f64)>) -> Self {
+        Self {
+            name,
+            help,
+            unit: "1",
+            points: values.into_iter().map(|(value, count)| (Some((label, value)), count)).collect(),
+        }
+    }
+}
+
+/// Key codebase health numbers from a report, as Prometheus text or OTLP metrics, so
+/// platform teams can graph them over time next to runtime metrics
+pub struct MetricsExporter {
+    config: MetricsConfig,
+    /// The project name plus `metrics.labels`
+    labels: BTreeMap<String, String>,
+    metrics: Vec<Metric>,
+    time_unix_nano: i64,
+}
+
+impl MetricsExporter {
+    pub fn new(config: &MetricsConfig, report: &Report) -> Self {
+        let mut labels = config.labels.clone();
+        labels.insert("project".to_string(), report.metadata.project_name.clone());
+        let time = chrono::DateTime::parse_from_rfc3339(&report.metadata.generated_at)
+            .map(|time| time.with_timezone(&chrono::Utc))
+            .unwrap_or_else(|_| chrono::Utc::now());
+        Self {
+            config: config.clone(),
+            labels,
+            metrics: Self::collect(report),
+            time_unix_nano: time.timestamp_nanos_opt().unwrap_or_default(),
+        }
+    }
+
+    fn collect(report: &Report) -> Vec<Metric> {
+        let files = &report.maintainability;
+        let average_index = if files.is_empty() {
+            0.0
+        } else {
+            files.iter().map(|file| file.index).sum::<f64>() / files.len() as f64
+        };
+        let ratings = [MaintainabilityRating::Green, MaintainabilityRating::Yellow, MaintainabilityRating::Red];
+        let severities = [Severity::Info, Severity::Warning, Severity::Error];
+
+        vec![
+            Metric::single("files", "Files analyzed", "1", report.metadata.total_files as f64),
+            Metric::single("size_bytes", "Total size of the analyzed files", "By", report.metadata.total_size as f64),
+            Metric::single("lines_of_code", "Lines of code across analyzed files", "1",
+                files.iter().map(|file| file.lines_of_code).sum::<usize>() as f64),
+            Metric::single("cyclomatic_complexity", "Summed cyclomatic complexity of all files", "1",
+                files.iter().map(|file| file.cyclomatic_complexity).sum::<usize>() as f64),
+            Metric::single("complexity_score", "Complexity score (0-10, lower is simpler)", "1", report.executive_summary.complexity_score),
+            Metric::single("maintainability_score", "Maintainability score (0-10, higher is better)", "1", report.executive_summary.maintainability_score),
+            Metric::single("maintainability_index_average", "Average per-file maintainability index (0-100)", "1", average_index),
+            Metric::split("files_by_maintainability", "Files per maintainability rating", "rating",
+                ratings.iter().map(|rating| (rating.name().to_string(), files.iter().filter(|file| file.rating == *rating).count() as f64))),
+            Metric::single("dependency_cycles", "Groups of files that depend on each other in a cycle", "1",
+                report.dependency_analysis.circular_dependencies.len() as f64),
+            Metric::single("documentation_coverage_percent", "Public functions and classes with doc comments", "%",
+                report.executive_summary.documentation_coverage),
+            Metric::single("tested_source_files", "Source files matched to a test by name", "1", report.testing.tested_sources as f64),
+            Metric::single("source_files", "Source files that could have tests", "1", report.testing.source_files as f64),
+            Metric::split("rule_findings", "Custom rule findings per severity", "severity",
+                severities.iter().map(|severity| (format!("{:?}", severity).to_lowercase(),
+                    report.rule_findings.iter().filter(|finding| finding.severity == *severity).count() as f64))),
+            Metric::single("architecture_violations", "Imports that break the declared layers", "1", report.architecture_violations.len() as f64),
+            Metric::single("code_smells", "Functions and classes over a smell threshold", "1", report.code_smells.len() as f64),
+            Metric::single("debt_markers", "TODO, FIXME and HACK comments", "1", report.debt_markers.len() as f64),
+            Metric::split("files_by_language", "Analyzed files per language", "language",
+                report.file_analysis.language_breakdown.iter().map(|language| (language.language.clone(), language.file_count as f64))),
+        ]
+    }
+
+    /// Prometheus text exposition format. Pushes leave the job and labels to the URL's
+    /// grouping key, so `with_labels` is only set for files.
+    pub fn to_prometheus(&self, with_labels: bool) -> String {
+        let mut text = String::new();
+        for metric in &self.metrics {
+            let name = format!("{}_{}", PREFIX, metric.name);
+            text.push_str(&format!("# HELP {} {}\n# TYPE {} gauge\n", name, metric.help, name));
+            for (label, value) in &metric.points {
+                let mut labels: Vec<String> = Vec::new();
+                if with_labels {
+                    labels.extend(self.labels.iter().map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value))));
+                }
+                if let Some((key, value)) = label {
+                    labels.push(format!("{}=\"{}\"", key, escape_label(value)));
+                }
+                if labels.is_empty() {
+                    text.push_str(&format!("{} {}\n", name, value));
+                } else {
+                    text.push_str(&format!("{}{{{}}} {}\n", name, labels.join(","), value));
+                }
+            }
+        }
+        text
+    }
+
+    /// OTLP `ExportMetricsServiceRequest` in its JSON encoding, one gauge per metric
+    pub fn to_otlp(&self) -> Value {
+        let attribute = |key: &str, value: &str| json!({"key": key, "value": {"stringValue": value}});
+        let mut resource = vec![attribute("service.name", &self.config.job)];
+        resource.extend(self.labels.iter().map(|(key, value)| attribute(key, value)));
+
+        let metrics: Vec<Value> = self.metrics.iter().map(|metric| json!({
+            "name": format!("{}.{}", PREFIX, metric.name),
+            "description": metric.help,
+            "unit": metric.unit,
+            "gauge": {
+                "dataPoints": metric.points.iter().map(|(label, value)| json!({
+                    "asDouble": value,
+                    "timeUnixNano": self.time_unix_nano.to_string(),
+                    "attributes": label.iter().map(|(key, value)| attribute(key, value)).collect::<Vec<_>>(),
+                })).collect::<Vec<_>>(),
+            },
+        })).collect();
+
+        json!({
+            "resourceMetrics": [{
+                "resource": {"attributes": resource},
+                "scopeMetrics": [{
+                    "scope": {"name": "project-examer", "version": env!("CARGO_PKG_VERSION")},
+                    "metrics": metrics,
+                }],
+            }],
+        })
+    }
+
+    /// `metrics.prom`, for the node_exporter textfile collector or a later push
+    pub fn export(&self, output_dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+        let path = output_dir.join("metrics.prom");
+        fs::write(&path, self.to_prometheus(true)).map_err(|e| Error::report(&path, e))?;
+        Ok(path)
+    }
+
+    /// Push to every configured target, returning where the metrics went
+    pub async fn push(&self) -> Result<Vec<String>> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(self.config.timeout_seconds))
+            .build()?;
+        let mut pushed = Vec::new();
+
+        if let Some(ref base) = self.config.pushgateway_url {
+            // The job and every label form the grouping key, so each project keeps its own series
+            let mut url = format!("{}/metrics/job/{}", base.trim_end_matches('/'), encode_path_segment(&self.config.job));
+            for (key, value) in &self.labels {
+                url.push_str(&format!("/{}/{}", key, encode_path_segment(value)));
+            }
+            client.put(&url)
+                .header("Content-Type", "text/plain; version=0.0.4")
+                .body(self.to_prometheus(false))
+                .send().await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| Error::service(format!("Pushgateway {}", base), e))?;
+            pushed.push(url);
+        }
+
+        if let Some(ref endpoint) = self.config.otlp_endpoint {
+            client.post(endpoint)
+                .json(&self.to_otlp())
+                .send().await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| Error::service(format!("OTLP endpoint {}", endpoint), e))?;
+            pushed.push(endpoint.clone());
+        }
+
+        Ok(pushed)
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn encode_path_segment(value: &str) -> String {
+    value.bytes().map(|byte| match byte {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+        _ => format!("%{:02X}", byte),
+    }).collect()
+}
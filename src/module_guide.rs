@@ -0,0 +1,78 @@
+use crate::{module_rollup::ModuleAnalyzer, simple_parser::ParsedFile};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Modules described per LLM request
+const BATCH_SIZE: usize = 8;
+/// Files and symbols listed per module in the prompt
+const MAX_LISTED: usize = 15;
+
+const SYSTEM_PROMPT: &str = "You are a senior engineer writing an architecture guide for a codebase. \
+For each module you are given, write a 2-3 sentence summary of its responsibility and how it fits into the system. \
+Respond with JSON only: {\"summaries\": [{\"module\": \"<module name exactly as given>\", \"summary\": \"...\"}]}";
+
+/// LLM-written description of one module
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleSummary {
+    pub module: String,
+    pub summary: String,
+}
+
+#[derive(Deserialize)]
+struct SummaryBatch {
+    summaries: Vec<ModuleSummary>,
+}
+
+#[derive(Default)]
+pub struct ModuleSummarizer;
+
+impl ModuleSummarizer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn system_prompt(&self) -> &'static str {
+        SYSTEM_PROMPT
+    }
+
+    /// One user prompt per batch of modules, listing each module's files and symbols
+    pub fn build_prompts(&self, parsed_files: &[ParsedFile]) -> Vec<String> {
+        let mut modules: BTreeMap<String, Vec<&ParsedFile>> = BTreeMap::new();
+        for pf in parsed_files {
+            modules.entry(ModuleAnalyzer::module_name(&pf.file_info.path)).or_default().push(pf);
+        }
+
+        let modules: Vec<(String, Vec<&ParsedFile>)> = modules.into_iter().collect();
+        modules.chunks(BATCH_SIZE).map(|batch| {
+            let mut prompt = String::from("Summarize each of these modules:\n");
+            for (module, files) in batch {
+                prompt.push_str(&format!("\nModule: {}\n", module));
+                let file_names: Vec<String> = files.iter()
+                    .take(MAX_LISTED)
+                    .filter_map(|pf| pf.file_info.path.file_name().map(|n| n.to_string_lossy().to_string()))
+                    .collect();
+                prompt.push_str(&format!("  Files ({}): {}\n", files.len(), file_names.join(", ")));
+
+                let symbols: Vec<&str> = files.iter()
+                    .flat_map(|pf| pf.classes.iter().map(|c| c.name.as_str()).chain(pf.functions.iter().map(|f| f.name.as_str())))
+                    .take(MAX_LISTED)
+                    .collect();
+                if !symbols.is_empty() {
+                    prompt.push_str(&format!("  Key symbols: {}\n", symbols.join(", ")));
+                }
+            }
+            prompt
+        }).collect()
+    }
+
+    /// Parse a batch reply, tolerating prose or code fences around the JSON
+    pub fn parse_response(&self, content: &str) -> Vec<ModuleSummary> {
+        let json = match (content.find('{'), content.rfind('}')) {
+            (Some(start), Some(end)) if start < end => &content[start..=end],
+            _ => return Vec::new(),
+        };
+        serde_json::from_str::<SummaryBatch>(json)
+            .map(|batch| batch.summaries)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{analyzer::ProjectAnalysis, graph_export::escape_xml, simple_parser::ParsedFile};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Distance from the main sequence at which a module counts as being in a zone
+const ZONE_DISTANCE: f64 = 0.5;
+
+/// The two corners of the abstractness/instability chart far from the main sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SequenceZone {
+    /// Concrete and depended upon: hard to change without breaking its dependents
+    Pain,
+    /// Abstract with no dependents: abstractions nobody uses
+    Uselessness,
+}
+
+impl SequenceZone {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SequenceZone::Pain => "zone of pain",
+            SequenceZone::Uselessness => "zone of uselessness",
+        }
+    }
+}
+
+/// Distance from the main sequence `A + I = 1`, and the zone a module with
+/// these metrics falls in. Abstractness means nothing for a module without
+/// types, so those are never put in a zone, and a module must be depended on
+/// to be in the zone of pain.
+pub fn main_sequence(abstractness: f64, instability: f64, afferent_coupling: usize, types: usize) -> (f64, Option<SequenceZone>) {
+    let distance = (abstractness + instability - 1.0).abs();
+    let zone = if distance < ZONE_DISTANCE || types == 0 {
+        None
+    } else if abstractness + instability < 1.0 {
+        (afferent_coupling > 0).then_some(SequenceZone::Pain)
+    } else {
+        Some(SequenceZone::Uselessness)
+    };
+    (distance, zone)
+}
+
+/// Abstractness against instability for each named point, with the main
+/// sequence and both zones drawn in
+pub fn render_main_sequence_svg(points: &[(&str, f64, f64, Option<SequenceZone>)]) -> String {
+    const SIZE: f64 = 360.0;
+    const MARGIN: f64 = 40.0;
+    let plot = SIZE - 2.0 * MARGIN;
+    let x = |instability: f64| MARGIN + instability * plot;
+    let y = |abstractness: f64| SIZE - MARGIN - abstractness * plot;
+
+    let mut svg = format!(
+        r##"<svg class="main-sequence" viewBox="0 0 {s} {s}" width="100%" style="max-width:{s}px" xmlns="http://www.w3.org/2000/svg">"##,
+        s = SIZE
+    );
+    // Zones: the corners beyond distance 0.5 from the main sequence
+    svg.push_str(&format!(r##"<polygon points="{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}" fill="#f8d7da"><title>Zone of pain</title></polygon>"##,
+        x(0.0), y(0.0), x(0.5), y(0.0), x(0.0), y(0.5)));
+    svg.push_str(&format!(r##"<polygon points="{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}" fill="#fff3cd"><title>Zone of uselessness</title></polygon>"##,
+        x(1.0), y(1.0), x(0.5), y(1.0), x(1.0), y(0.5)));
+    svg.push_str(&format!(r##"<rect x="{m}" y="{m}" width="{p}" height="{p}" fill="none" stroke="#999"/>"##, m = MARGIN, p = plot));
+    svg.push_str(&format!(r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#3b6fd4" stroke-dasharray="6 4"><title>Main sequence: A + I = 1</title></line>"##,
+        x(0.0), y(1.0), x(1.0), y(0.0)));
+    svg.push_str(&format!(r##"<text x="{:.1}" y="{:.1}" font-size="12" text-anchor="middle">Instability (I)</text>"##, SIZE / 2.0, SIZE - 8.0));
+    svg.push_str(&format!(r##"<text x="14" y="{:.1}" font-size="12" text-anchor="middle" transform="rotate(-90 14 {:.1})">Abstractness (A)</text>"##, SIZE / 2.0, SIZE / 2.0));
+    for (value, label) in [(0.0, "0"), (1.0, "1")] {
+        svg.push_str(&format!(r##"<text x="{:.1}" y="{:.1}" font-size="10" text-anchor="middle">{}</text>"##, x(value), SIZE - MARGIN + 14.0, label));
+        svg.push_str(&format!(r##"<text x="{:.1}" y="{:.1}" font-size="10" text-anchor="end">{}</text>"##, MARGIN - 4.0, y(value) + 4.0, label));
+    }
+    for (name, instability, abstractness, zone) in points {
+        let fill = match zone {
+            Some(SequenceZone::Pain) => "#c0392b",
+            Some(SequenceZone::Uselessness) => "#d4a017",
+            None => "#3b6fd4",
+        };
+        svg.push_str(&format!(r##"<circle cx="{:.1}" cy="{:.1}" r="5" fill="{}" fill-opacity="0.75"><title>{}: I = {:.2}, A = {:.2}</title></circle>"##,
+            x(*instability), y(*abstractness), fill, escape_xml(name), instability, abstractness));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Directory-level aggregation of file metrics and dependencies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleAnalysis {
+    pub modules: Vec<ModuleRollup>,
+    pub dependency_matrix: ModuleMatrix,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleRollup {
+    pub module: String,
+    pub files: usize,
+    pub lines_of_code: usize,
+    pub complexity: usize,
+    pub internal_dependencies: usize,
+    pub external_dependencies: usize,
+    /// Number of other modules depending on this module (Ca)
+    pub afferent_coupling: usize,
+    /// Number of other modules this module depends on (Ce)
+    pub efferent_coupling: usize,
+    /// Ce / (Ca + Ce)
+    pub instability: f64,
+    /// Abstract types / total types
+    pub abstractness: f64,
+    /// |A + I - 1|: how far the module is from the balance between the two
+    #[serde(default)]
+    pub distance: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<SequenceZone>,
+    /// LLM-written description, when `analysis.summarize_modules` is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// Dependency structure matrix: `cells[i][j]` is the number of file-level
+/// dependencies from `modules[i]` to `modules[j]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleMatrix {
+    pub modules: Vec<String>,
+    pub cells: Vec<Vec<usize>>,
+}
+
+#[derive(Default)]
+pub struct ModuleAnalyzer;
+
+impl ModuleAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn analyze(&self, analysis: &ProjectAnalysis) -> ModuleAnalysis {
+        let mut files_by_module: BTreeMap<String, Vec<&ParsedFile>> = BTreeMap::new();
+        let mut module_of: HashMap<&Path, String> = HashMap::new();
+
+        for pf in &analysis.parsed_files {
+            let module = Self::module_name(&pf.file_info.path);
+            module_of.insert(pf.file_info.path.as_path(), module.clone());
+            files_by_module.entry(module).or_default().push(pf);
+        }
+
+        let modules: Vec<String> = files_by_module.keys().cloned().collect();
+        let index: HashMap<&str, usize> = modules.iter().enumerate().map(|(i, m)| (m.as_str(), i)).collect();
+        let mut cells = vec![vec![0usize; modules.len()]; modules.len()];
+
+        for dep in &analysis.file_dependencies {
+            let (Some(from), Some(to)) = (module_of.get(dep.from.as_path()), module_of.get(dep.to.as_path())) else {
+                continue;
+            };
+            cells[index[from.as_str()]][index[to.as_str()]] += 1;
+        }
+
+        let rollups = modules.iter().enumerate().map(|(i, module)| {
+            let files = &files_by_module[module];
+            let outgoing: BTreeSet<usize> = (0..modules.len()).filter(|&j| j != i && cells[i][j] > 0).collect();
+            let incoming: BTreeSet<usize> = (0..modules.len()).filter(|&j| j != i && cells[j][i] > 0).collect();
+            let external_dependencies = (0..modules.len()).filter(|&j| j != i).map(|j| cells[i][j]).sum();
+
+            let total_types: usize = files.iter().map(|pf| pf.classes.len()).sum();
+            let abstract_types: usize = files.iter()
+                .map(|pf| pf.classes.iter().filter(|c| c.is_abstract).count())
+                .sum();
+
+            let afferent = incoming.len();
+            let efferent = outgoing.len();
+            let instability = if afferent + efferent == 0 { 0.0 } else { efferent as f64 / (afferent + efferent) as f64 };
+            let abstractness = if total_types == 0 { 0.0 } else { abstract_types as f64 / total_types as f64 };
+            let (distance, zone) = main_sequence(abstractness, instability, afferent, total_types);
+
+            ModuleRollup {
+                module: module.clone(),
+                files: files.len(),
+                lines_of_code: files.iter().map(|pf| pf.lines_of_code).sum(),
+                complexity: files.iter().map(|pf| pf.functions.len() + pf.classes.len() * 2).sum(),
+                internal_dependencies: cells[i][i],
+                external_dependencies,
+                afferent_coupling: afferent,
+                efferent_coupling: efferent,
+                instability,
+                abstractness,
+                distance,
+                zone,
+                summary: analysis.module_summaries.iter()
+                    .find(|s| &s.module == module)
+                    .map(|s| s.summary.clone()),
+            }
+        }).collect();
+
+        ModuleAnalysis {
+            modules: rollups,
+            dependency_matrix: ModuleMatrix { modules, cells },
+        }
+    }
+
+    /// A file's module is the directory containing it
+    pub fn module_name(path: &Path) -> String {
+        match path.parent().map(PathBuf::from) {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().to_string(),
+            _ => ".".to_string(),
+        }
+    }
+}
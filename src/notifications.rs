@@ -0,0 +1,158 @@
+use crate::{
+    config::NotificationsConfig,
+    llm::Priority,
+    reporter::Report,
+    rules::Severity,
+};
+use crate::{Error, Result};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// The compact run summary posted to chat
+pub struct RunSummary {
+    pub title: String,
+    /// (label, value) pairs shown side by side
+    pub facts: Vec<(String, String)>,
+    /// Critical and high-priority recommendations, error-severity rule findings,
+    /// architecture violations and oversized cycles, most severe first
+    pub critical: Vec<String>,
+    /// Critical findings beyond `max_findings`
+    pub more: usize,
+    pub report_url: Option<String>,
+}
+
+impl RunSummary {
+    pub fn from_report(report: &Report, max_findings: usize, report_url: Option<String>) -> Self {
+        let mut critical: Vec<String> = Vec::new();
+        critical.extend(report.recommendations.iter()
+            .filter(|rec| matches!(rec.priority, Priority::Critical))
+            .map(|rec| format!("Critical: {}", rec.title)));
+        critical.extend(report.rule_findings.iter()
+            .filter(|finding| finding.severity == Severity::Error)
+            .map(|finding| format!("Rule {} at {}:{}", finding.rule_id, finding.file, finding.line)));
+        critical.extend(report.architecture_violations.iter()
+            .map(|violation| format!("{} → {} at {}:{}", violation.from_layer, violation.to_layer, violation.from_file, violation.line)));
+        critical.extend(report.dependency_analysis.circular_dependencies.iter()
+            .filter(|cycle| cycle.severity == "High")
+            .map(|cycle| format!("Dependency cycle of {} files", cycle.files.len())));
+        critical.extend(report.recommendations.iter()
+            .filter(|rec| matches!(rec.priority, Priority::High))
+            .map(|rec| format!("High: {}", rec.title)));
+
+        let more = critical.len().saturating_sub(max_findings);
+        critical.truncate(max_findings);
+
+        let summary = &report.executive_summary;
+        Self {
+            title: format!("Project analysis: {}", report.metadata.project_name),
+            facts: vec![
+                ("Complexity".to_string(), format!("{:.1}/10", summary.complexity_score)),
+                ("Maintainability".to_string(), format!("{:.1}/10", summary.maintainability_score)),
+                ("Documentation".to_string(), format!("{:.0}%", summary.documentation_coverage)),
+                ("Files".to_string(), report.metadata.total_files.to_string()),
+                ("Dependency cycles".to_string(), report.dependency_analysis.circular_dependencies.len().to_string()),
+                ("Rule findings".to_string(), report.rule_findings.len().to_string()),
+            ],
+            critical,
+            more,
+            report_url,
+        }
+    }
+
+    fn critical_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.critical.iter().map(|item| format!("• {}", item)).collect();
+        if self.more > 0 {
+            lines.push(format!("… and {} more in the report", self.more));
+        }
+        lines
+    }
+
+    /// Slack incoming-webhook message with Block Kit sections and a plain-text fallback
+    pub fn slack_payload(&self) -> Value {
+        let mut blocks = vec![
+            json!({"type": "header", "text": {"type": "plain_text", "text": self.title}}),
+            json!({"type": "section", "fields": self.facts.iter()
+                .map(|(label, value)| json!({"type": "mrkdwn", "text": format!("*{}*\n{}", label, value)}))
+                .collect::<Vec<_>>()}),
+        ];
+        if !self.critical.is_empty() {
+            blocks.push(json!({"type": "section", "text": {"type": "mrkdwn",
+                "text": format!("*Critical findings*\n{}", self.critical_lines().join("\n"))}}));
+        }
+        if let Some(ref url) = self.report_url {
+            blocks.push(json!({"type": "context", "elements": [{"type": "mrkdwn", "text": format!("<{}|Full report>", url)}]}));
+        }
+        json!({"text": self.title, "blocks": blocks})
+    }
+
+    /// Microsoft Teams webhook message carrying an Adaptive Card
+    pub fn teams_payload(&self) -> Value {
+        let mut body = vec![
+            json!({"type": "TextBlock", "text": self.title, "weight": "Bolder", "size": "Medium", "wrap": true}),
+            json!({"type": "FactSet", "facts": self.facts.iter()
+                .map(|(label, value)| json!({"title": label, "value": value}))
+                .collect::<Vec<_>>()}),
+        ];
+        if !self.critical.is_empty() {
+            body.push(json!({"type": "TextBlock", "text": "Critical findings", "weight": "Bolder", "wrap": true}));
+            body.push(json!({"type": "TextBlock", "text": self.critical_lines().join("\n\n"), "wrap": true}));
+        }
+        let actions: Vec<Value> = self.report_url.iter()
+            .map(|url| json!({"type": "Action.OpenUrl", "title": "Full report", "url": url}))
+            .collect();
+        json!({
+            "type": "message",
+            "attachments": [{
+                "contentType": "application/vnd.microsoft.card.adaptive",
+                "content": {
+                    "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                    "type": "AdaptiveCard",
+                    "version": "1.4",
+                    "body": body,
+                    "actions": actions,
+                },
+            }],
+        })
+    }
+}
+
+/// Posts the run summary to the Slack and Teams webhooks in `[notifications]`
+pub struct Notifier {
+    config: NotificationsConfig,
+}
+
+impl Notifier {
+    pub fn new(config: &NotificationsConfig) -> Self {
+        Self { config: config.clone() }
+    }
+
+    /// `report_url` is the configured link, falling back to the given local report
+    pub fn summarize(&self, report: &Report, local_report: Option<String>) -> RunSummary {
+        RunSummary::from_report(report, self.config.max_findings, self.config.report_url.clone().or(local_report))
+    }
+
+    /// Post to every configured webhook, returning the services notified
+    pub async fn notify(&self, summary: &RunSummary) -> Result<Vec<&'static str>> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(self.config.timeout_seconds))
+            .build()?;
+        let targets = [
+            ("Slack", &self.config.slack_webhook_url, summary.slack_payload()),
+            ("Teams", &self.config.teams_webhook_url, summary.teams_payload()),
+        ];
+
+        let mut notified = Vec::new();
+        for (service, url, payload) in targets {
+            let Some(url) = url else { continue };
+            client.post(url)
+                .json(&payload)
+                .send().await
+                .and_then(|response| response.error_for_status())
+                // Webhook URLs embed their secret, so keep them out of the error
+                .map_err(|e| Error::service(service, e))?;
+            notified.push(service);
+        }
+        Ok(notified)
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    dependency_graph::FileDependency,
+    doc_bundle::{find_entry_points, most_depended_on},
+    simple_parser::ParsedFile,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// Maximum number of files listed per main flow
+const MAX_FLOW_LENGTH: usize = 8;
+const MAX_GLOSSARY_TERMS: usize = 30;
+
+/// Generic programming words that say nothing about the domain
+const GLOSSARY_STOPWORDS: &[&str] = &[
+    "get", "set", "new", "init", "the", "and", "for", "from", "with", "into", "to", "is", "has",
+    "add", "remove", "delete", "update", "create", "make", "build", "run", "handle", "handler",
+    "process", "generate", "extract", "calculate", "compute", "convert", "format", "load", "save", "read", "write", "parse", "find", "check", "test", "tests", "main",
+    "self", "default", "value", "data", "item", "items", "list", "map", "all", "by", "of", "on",
+    "impl", "fn", "func", "util", "utils", "helper", "helpers", "manager", "service",
+    "string", "str", "int", "bool", "result", "error", "err", "config", "options", "args",
+];
+
+/// Guided tour of the codebase for new team members
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OnboardingGuide {
+    pub entry_points: Vec<PathBuf>,
+    /// Files reachable from each entry point, in breadth-first import order
+    pub main_flows: Vec<MainFlow>,
+    /// Suggested reading order: entry points first, then the most imported files
+    pub reading_order: Vec<ReadingStep>,
+    pub glossary: Vec<GlossaryTerm>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MainFlow {
+    pub entry_point: PathBuf,
+    pub files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadingStep {
+    pub file: PathBuf,
+    pub reason: String,
+}
+
+/// A domain term that recurs across identifiers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlossaryTerm {
+    pub term: String,
+    /// Number of functions and classes whose name contains the term
+    pub occurrences: usize,
+    pub examples: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct OnboardingAnalyzer;
+
+impl OnboardingAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn analyze(&self, parsed_files: &[ParsedFile], file_dependencies: &[FileDependency]) -> OnboardingGuide {
+        let entry_points = find_entry_points(parsed_files);
+
+        let main_flows = entry_points.iter()
+            .map(|entry_point| MainFlow {
+                entry_point: entry_point.clone(),
+                files: Self::flow_from(entry_point, file_dependencies),
+            })
+            .filter(|flow| !flow.files.is_empty())
+            .collect();
+
+        let mut reading_order: Vec<ReadingStep> = entry_points.iter()
+            .map(|path| ReadingStep { file: path.clone(), reason: "entry point".to_string() })
+            .collect();
+        for (path, dependents) in most_depended_on(file_dependencies, 10) {
+            if reading_order.iter().all(|step| step.file != path) {
+                reading_order.push(ReadingStep { file: path, reason: format!("imported by {} files", dependents) });
+            }
+        }
+
+        OnboardingGuide {
+            entry_points,
+            main_flows,
+            reading_order,
+            glossary: Self::glossary(parsed_files),
+        }
+    }
+
+    /// Breadth-first walk of the file dependency graph from an entry point
+    fn flow_from(entry_point: &Path, file_dependencies: &[FileDependency]) -> Vec<PathBuf> {
+        let mut visited: HashSet<&Path> = HashSet::from([entry_point]);
+        let mut queue = VecDeque::from([entry_point]);
+        let mut flow = Vec::new();
+
+        while let Some(current) = queue.pop_front() {
+            let mut next: Vec<&Path> = file_dependencies.iter()
+                .filter(|dep| dep.from == current)
+                .map(|dep| dep.to.as_path())
+                .collect();
+            next.sort();
+            for path in next {
+                if flow.len() >= MAX_FLOW_LENGTH {
+                    return flow;
+                }
+                if visited.insert(path) {
+                    flow.push(path.to_path_buf());
+                    queue.push_back(path);
+                }
+            }
+        }
+        flow
+    }
+
+    /// Terms shared by at least two function or class names, most frequent first
+    fn glossary(parsed_files: &[ParsedFile]) -> Vec<GlossaryTerm> {
+        let mut terms: HashMap<String, BTreeSet<String>> = HashMap::new();
+        let identifiers = parsed_files.iter().flat_map(|pf| {
+            pf.functions.iter().map(|f| f.name.as_str())
+                .chain(pf.classes.iter().map(|c| c.name.as_str()))
+                .chain(pf.classes.iter().flat_map(|c| c.methods.iter().map(|m| m.name.as_str())))
+        });
+
+        for identifier in identifiers {
+            for word in split_identifier(identifier) {
+                if word.len() >= 3 && !GLOSSARY_STOPWORDS.contains(&word.as_str()) && !word.chars().all(|c| c.is_ascii_digit()) {
+                    terms.entry(word).or_default().insert(identifier.to_string());
+                }
+            }
+        }
+
+        let mut glossary: Vec<GlossaryTerm> = terms.into_iter()
+            .filter(|(_, identifiers)| identifiers.len() >= 2)
+            .map(|(term, identifiers)| GlossaryTerm {
+                term,
+                occurrences: identifiers.len(),
+                examples: identifiers.into_iter().take(3).collect(),
+            })
+            .collect();
+        glossary.sort_by(|a, b| b.occurrences.cmp(&a.occurrences).then_with(|| a.term.cmp(&b.term)));
+        glossary.truncate(MAX_GLOSSARY_TERMS);
+        glossary
+    }
+}
+
+/// Split `camelCase`, `PascalCase` and `snake_case` identifiers into lowercase words
+pub fn split_identifier(identifier: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = identifier.chars().collect();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        // Break before an uppercase letter that starts a new word: `parseHTTPRequest` -> parse, http, request
+        let starts_word = c.is_uppercase() && !current.is_empty() && (
+            chars[i - 1].is_lowercase()
+                || chars.get(i + 1).is_some_and(|next| next.is_lowercase())
+        );
+        if starts_word {
+            words.push(std::mem::take(&mut current));
+        }
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
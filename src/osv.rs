@@ -0,0 +1,206 @@
+use crate::{llm::Priority, manifests::Ecosystem};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+// Queries need the `llm` feature's HTTP client
+#[cfg(feature = "llm")]
+use {
+    crate::{config::OsvConfig, manifests::{DeclaredDependency, Version}, simple_parser::ParsedFile, Error, Result},
+    reqwest::Client,
+    serde_json::{json, Value},
+    std::collections::BTreeMap,
+    std::path::Path,
+    std::time::Duration,
+};
+
+/// The OSV batch endpoint accepts at most this many queries per request
+#[cfg(feature = "llm")]
+const BATCH_SIZE: usize = 1000;
+
+/// A published advisory affecting a declared dependency version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vulnerability {
+    /// OSV identifier, e.g. `GHSA-…` or `RUSTSEC-…`
+    pub id: String,
+    /// CVE and other identifiers for the same advisory
+    pub aliases: Vec<String>,
+    pub summary: String,
+    /// As rated by the advisory database, or `unrated`
+    pub severity: String,
+    pub priority: Priority,
+    pub ecosystem: Ecosystem,
+    pub package: String,
+    /// The version checked: the lowest the manifest's requirement allows
+    pub version: String,
+    /// Lowest release with the fix, when the advisory names one
+    pub fixed: Option<String>,
+    pub url: String,
+    pub manifest: PathBuf,
+    /// Files importing the package
+    pub affected_files: Vec<String>,
+}
+
+/// Result of `--check-vulnerabilities`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VulnerabilityScan {
+    /// Dependencies looked up
+    pub checked: usize,
+    /// Most severe first
+    pub vulnerabilities: Vec<Vulnerability>,
+}
+
+impl VulnerabilityScan {
+    pub fn is_empty(&self) -> bool {
+        self.checked == 0
+    }
+}
+
+/// Checks declared dependency versions against the OSV.dev vulnerability database
+#[cfg(feature = "llm")]
+pub struct OsvScanner {
+    config: OsvConfig,
+}
+
+#[cfg(feature = "llm")]
+impl OsvScanner {
+    pub fn new(config: &OsvConfig) -> Self {
+        Self { config: config.clone() }
+    }
+
+    pub async fn scan(&self, dependencies: &[DeclaredDependency], parsed_files: &[ParsedFile], root: &Path) -> Result<VulnerabilityScan> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(self.config.timeout_seconds))
+            .build()?;
+        let api = self.config.api_url.trim_end_matches('/');
+
+        let queried: Vec<(&DeclaredDependency, Version)> = dependencies.iter()
+            .filter(|dependency| self.config.include_dev || !dependency.dev)
+            .filter_map(|dependency| Some((dependency, dependency.version.as_deref().and_then(Version::parse)?)))
+            .collect();
+
+        let mut matches: Vec<(&DeclaredDependency, Version, String)> = Vec::new();
+        for batch in queried.chunks(BATCH_SIZE) {
+            let queries: Vec<Value> = batch.iter()
+                .map(|(dependency, version)| json!({
+                    "package": {"name": dependency.name, "ecosystem": dependency.ecosystem.name()},
+                    "version": version.to_string(),
+                }))
+                .collect();
+            let response: Value = Self::send(client.post(format!("{}/v1/querybatch", api)).json(&json!({"queries": queries}))).await?;
+            // Results are in query order
+            for ((dependency, version), result) in batch.iter().zip(response["results"].as_array().into_iter().flatten()) {
+                for vuln in result["vulns"].as_array().into_iter().flatten() {
+                    if let Some(id) = vuln["id"].as_str() {
+                        matches.push((dependency, *version, id.to_string()));
+                    }
+                }
+            }
+        }
+
+        let mut advisories: BTreeMap<String, Value> = BTreeMap::new();
+        let mut vulnerabilities = Vec::new();
+        for (dependency, version, id) in matches {
+            if !advisories.contains_key(&id) {
+                let advisory = Self::send(client.get(format!("{}/v1/vulns/{}", api, id))).await?;
+                advisories.insert(id.clone(), advisory);
+            }
+            let advisory = &advisories[&id];
+            let (severity, priority) = Self::severity(advisory);
+            vulnerabilities.push(Vulnerability {
+                aliases: advisory["aliases"].as_array().into_iter().flatten()
+                    .filter_map(|alias| alias.as_str().map(String::from))
+                    .collect(),
+                summary: advisory["summary"].as_str()
+                    .or_else(|| advisory["details"].as_str().and_then(|details| details.lines().next()))
+                    .unwrap_or_default()
+                    .to_string(),
+                severity,
+                priority,
+                ecosystem: dependency.ecosystem,
+                package: dependency.name.clone(),
+                version: version.to_string(),
+                fixed: Self::fixed_version(advisory, dependency, version),
+                url: advisory["references"].as_array().into_iter().flatten()
+                    .find(|reference| reference["type"] == "ADVISORY")
+                    .and_then(|reference| reference["url"].as_str())
+                    .map(String::from)
+                    .unwrap_or_else(|| format!("https://osv.dev/vulnerability/{}", id)),
+                manifest: dependency.manifest.clone(),
+                affected_files: Self::importers(dependency, parsed_files, root),
+                id,
+            });
+        }
+
+        vulnerabilities.sort_by(|a, b| {
+            Self::priority_rank(&a.priority).cmp(&Self::priority_rank(&b.priority))
+                .then_with(|| (&a.package, &a.id).cmp(&(&b.package, &b.id)))
+        });
+        Ok(VulnerabilityScan { checked: queried.len(), vulnerabilities })
+    }
+
+    async fn send(request: reqwest::RequestBuilder) -> Result<Value> {
+        Ok(request.send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| Error::service("OSV", e))?
+            .json().await?)
+    }
+
+    fn priority_rank(priority: &Priority) -> u8 {
+        match priority {
+            Priority::Critical => 0,
+            Priority::High => 1,
+            Priority::Medium => 2,
+            Priority::Low => 3,
+        }
+    }
+
+    /// The database's rating (GitHub advisories carry one); unrated advisories count as Medium
+    fn severity(advisory: &Value) -> (String, Priority) {
+        let rating = advisory["database_specific"]["severity"].as_str()
+            .or_else(|| advisory["affected"].as_array().into_iter().flatten()
+                .find_map(|affected| affected["database_specific"]["severity"].as_str()));
+        let priority = match rating.map(str::to_ascii_uppercase).as_deref() {
+            Some("CRITICAL") => Priority::Critical,
+            Some("HIGH") => Priority::High,
+            Some("LOW") => Priority::Low,
+            _ => Priority::Medium,
+        };
+        (rating.unwrap_or("unrated").to_string(), priority)
+    }
+
+    /// The lowest `fixed` event above the checked version for this package
+    fn fixed_version(advisory: &Value, dependency: &DeclaredDependency, version: Version) -> Option<String> {
+        advisory["affected"].as_array().into_iter().flatten()
+            .filter(|affected| affected["package"]["name"].as_str() == Some(dependency.name.as_str()))
+            .flat_map(|affected| affected["ranges"].as_array().into_iter().flatten())
+            .flat_map(|range| range["events"].as_array().into_iter().flatten())
+            .filter_map(|event| event["fixed"].as_str())
+            .filter_map(|fixed| Some((Version::parse(fixed)?, fixed)))
+            .filter(|(fixed, _)| *fixed > version)
+            .min()
+            .map(|(_, fixed)| fixed.to_string())
+    }
+
+    /// Files whose imports name the package. Python import names often differ from
+    /// distribution names, so some importers may be missed.
+    fn importers(dependency: &DeclaredDependency, parsed_files: &[ParsedFile], root: &Path) -> Vec<String> {
+        let normalize = |name: &str| name.to_lowercase().replace('-', "_");
+        let package = normalize(&dependency.name);
+        let root_module = |module: &str| -> String {
+            let module = module.trim_start_matches("::");
+            match dependency.ecosystem {
+                Ecosystem::Cargo => module.split("::").next().unwrap_or(module).to_string(),
+                Ecosystem::Npm if module.starts_with('@') => module.splitn(3, '/').take(2).collect::<Vec<_>>().join("/"),
+                Ecosystem::Npm => module.split('/').next().unwrap_or(module).to_string(),
+                Ecosystem::PyPI => module.split('.').next().unwrap_or(module).to_string(),
+            }
+        };
+
+        parsed_files.iter()
+            .filter(|pf| pf.imports.iter().any(|import| normalize(&root_module(&import.module)) == package))
+            .map(|pf| {
+                let path = &pf.file_info.path;
+                path.strip_prefix(root).unwrap_or(path).to_string_lossy().to_string()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{config::OwnershipConfig, file_discovery::FileInfo, git, sources::SourceSet};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Standard CODEOWNERS locations, in GitHub's lookup order
+const CODEOWNERS_LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileOwnership {
+    pub path: PathBuf,
+    pub owners: Vec<String>,
+    pub source: OwnershipSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OwnershipSource {
+    Codeowners,
+    GitBlame,
+}
+
+struct CodeownersRule {
+    matcher: Gitignore,
+    owners: Vec<String>,
+}
+
+pub struct OwnershipResolver {
+    root: PathBuf,
+    rules: Vec<CodeownersRule>,
+    use_git_blame: bool,
+}
+
+impl OwnershipResolver {
+    /// Git blame is only consulted for files on disk
+    pub fn new(root: &Path, config: &OwnershipConfig, sources: &SourceSet) -> Self {
+        let rules = CODEOWNERS_LOCATIONS.iter()
+            .find_map(|location| sources.read(&root.join(location)).ok())
+            .map(|content| Self::parse_codeowners(root, &content))
+            .unwrap_or_default();
+
+        Self {
+            root: root.to_path_buf(),
+            rules,
+            use_git_blame: config.git_blame && !sources.is_in_memory(),
+        }
+    }
+
+    fn parse_codeowners(root: &Path, content: &str) -> Vec<CodeownersRule> {
+        content.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let pattern = parts.next()?;
+                let owners: Vec<String> = parts.take_while(|p| !p.starts_with('#')).map(String::from).collect();
+
+                let mut builder = GitignoreBuilder::new(root);
+                builder.add_line(None, pattern).ok()?;
+                Some(CodeownersRule { matcher: builder.build().ok()?, owners })
+            })
+            .collect()
+    }
+
+    pub fn has_codeowners(&self) -> bool {
+        !self.rules.is_empty()
+    }
+
+    /// Resolve owners for every file; files without any owner are omitted
+    pub fn resolve(&self, files: &[FileInfo]) -> Vec<FileOwnership> {
+        files.iter()
+            .filter_map(|file| self.owners_for(&file.path))
+            .collect()
+    }
+
+    pub fn owners_for(&self, path: &Path) -> Option<FileOwnership> {
+        // Later CODEOWNERS rules take precedence over earlier ones
+        let codeowners = self.rules.iter().rev().find(|rule| {
+            rule.matcher.matched_path_or_any_parents(path, false).is_ignore()
+        });
+
+        match codeowners {
+            // A matching rule with no owners explicitly leaves the file unowned
+            Some(rule) if rule.owners.is_empty() => None,
+            Some(rule) => Some(FileOwnership {
+                path: path.to_path_buf(),
+                owners: rule.owners.clone(),
+                source: OwnershipSource::Codeowners,
+            }),
+            None if self.use_git_blame => self.top_blame_author(path).map(|author| FileOwnership {
+                path: path.to_path_buf(),
+                owners: vec![author],
+                source: OwnershipSource::GitBlame,
+            }),
+            None => None,
+        }
+    }
+
+    /// Author with the most lines in `git blame`
+    fn top_blame_author(&self, path: &Path) -> Option<String> {
+        let mut lines_by_author: HashMap<String, usize> = HashMap::new();
+        for line in git::blame(&self.root, path)? {
+            *lines_by_author.entry(line.author).or_insert(0) += 1;
+        }
+
+        lines_by_author.into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+            .map(|(author, _)| author)
+    }
+}
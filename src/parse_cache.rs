@@ -0,0 +1,67 @@
+use crate::simple_parser::ParsedFile;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Size and modification time a cached parse was taken at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    size: u64,
+    modified: SystemTime,
+}
+
+impl FileStamp {
+    pub fn of(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            size: metadata.len(),
+            modified: metadata.modified().ok()?,
+        })
+    }
+}
+
+/// Parsed files kept between analyses by the daemon. An entry is reused while the
+/// file's size and modification time are unchanged, so repeat runs only parse edits.
+#[derive(Debug, Default)]
+pub struct ParseCache {
+    entries: Mutex<HashMap<PathBuf, (FileStamp, ParsedFile)>>,
+}
+
+impl ParseCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, path: &Path, stamp: FileStamp) -> Option<ParsedFile> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(&Self::key(path))
+            .filter(|(cached, _)| *cached == stamp)
+            .map(|(_, parsed)| parsed.clone())
+    }
+
+    pub fn insert(&self, path: &Path, stamp: FileStamp, parsed: ParsedFile) {
+        self.entries.lock().unwrap().insert(Self::key(path), (stamp, parsed));
+    }
+
+    /// Clients may name the same file relative to different working directories
+    fn key(path: &Path) -> PathBuf {
+        std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    /// Drop entries for files that no longer exist
+    pub fn prune(&self) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|path, _| path.exists());
+        before - entries.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
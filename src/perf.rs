@@ -0,0 +1,164 @@
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Wall-clock time spent in one pipeline stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageTiming {
+    pub stage: String,
+    pub duration_ms: f64,
+}
+
+/// Parse throughput for one language; time is summed per file, so it is CPU
+/// time across all worker threads rather than wall-clock time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageThroughput {
+    pub language: String,
+    pub files: usize,
+    pub bytes: u64,
+    pub parse_ms: f64,
+    pub files_per_second: f64,
+    pub mb_per_second: f64,
+}
+
+/// Timing report produced by `project-examer bench`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerfReport {
+    pub threads: usize,
+    /// Maximum concurrent file reads; 0 means unlimited
+    pub io_concurrency: usize,
+    pub total_files: usize,
+    pub total_ms: f64,
+    pub stages: Vec<StageTiming>,
+    pub languages: Vec<LanguageThroughput>,
+}
+
+/// Time taken to parse a single file
+#[derive(Debug, Clone)]
+pub struct ParseSample {
+    pub language: Option<String>,
+    pub bytes: u64,
+    pub duration: Duration,
+}
+
+/// Collects stage timings and per-file parse samples during an analysis
+#[derive(Debug, Default)]
+pub struct PerfRecorder {
+    stages: Vec<StageTiming>,
+    samples: Vec<ParseSample>,
+    threads: usize,
+    io_concurrency: usize,
+}
+
+impl PerfRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_stage(&mut self, stage: &str, started: Instant) {
+        self.stages.push(StageTiming {
+            stage: stage.to_string(),
+            duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        });
+    }
+
+    pub fn record_parse(&mut self, samples: Vec<ParseSample>, threads: usize, io_concurrency: usize) {
+        self.samples.extend(samples);
+        self.threads = threads;
+        self.io_concurrency = io_concurrency;
+    }
+
+    pub fn report(&self) -> PerfReport {
+        let mut by_language: BTreeMap<String, (usize, u64, Duration)> = BTreeMap::new();
+        for sample in &self.samples {
+            let language = sample.language.clone().unwrap_or_else(|| "unknown".to_string());
+            let entry = by_language.entry(language).or_default();
+            entry.0 += 1;
+            entry.1 += sample.bytes;
+            entry.2 += sample.duration;
+        }
+
+        let mut languages: Vec<LanguageThroughput> = by_language.into_iter()
+            .map(|(language, (files, bytes, duration))| {
+                let seconds = duration.as_secs_f64().max(f64::EPSILON);
+                LanguageThroughput {
+                    language,
+                    files,
+                    bytes,
+                    parse_ms: duration.as_secs_f64() * 1000.0,
+                    files_per_second: files as f64 / seconds,
+                    mb_per_second: bytes as f64 / (1024.0 * 1024.0) / seconds,
+                }
+            })
+            .collect();
+        languages.sort_by(|a, b| b.parse_ms.total_cmp(&a.parse_ms));
+
+        PerfReport {
+            threads: self.threads,
+            io_concurrency: self.io_concurrency,
+            total_files: self.samples.len(),
+            total_ms: self.stages.iter().map(|s| s.duration_ms).sum(),
+            stages: self.stages.clone(),
+            languages,
+        }
+    }
+}
+
+impl PerfReport {
+    pub fn print(&self) {
+        println!("\n⏱️  Performance Report");
+        println!("====================");
+        println!("Threads: {}  IO concurrency: {}  Files parsed: {}", self.threads, Self::io_label(self.io_concurrency), self.total_files);
+
+        println!("\nStages:");
+        for stage in &self.stages {
+            let share = if self.total_ms > 0.0 { stage.duration_ms * 100.0 / self.total_ms } else { 0.0 };
+            println!("  {:<12} {:>10.1} ms  {:>5.1}%", stage.stage, stage.duration_ms, share);
+        }
+        println!("  {:<12} {:>10.1} ms", "total", self.total_ms);
+
+        println!("\nParse throughput by language (CPU time):");
+        for language in &self.languages {
+            println!("  {:<12} {:>6} files  {:>10.1} ms  {:>9.1} files/s  {:>7.2} MB/s",
+                language.language, language.files, language.parse_ms, language.files_per_second, language.mb_per_second);
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("# Performance Report\n\n");
+        md.push_str(&format!("- **Threads:** {}\n- **IO concurrency:** {}\n- **Files parsed:** {}\n- **Total:** {:.1} ms\n\n",
+            self.threads, Self::io_label(self.io_concurrency), self.total_files, self.total_ms));
+
+        md.push_str("## Stages\n\n| Stage | Duration (ms) |\n|-------|---------------|\n");
+        for stage in &self.stages {
+            md.push_str(&format!("| {} | {:.1} |\n", stage.stage, stage.duration_ms));
+        }
+
+        md.push_str("\n## Parse Throughput (CPU time)\n\n| Language | Files | Bytes | Parse (ms) | Files/s | MB/s |\n|----------|-------|-------|------------|---------|------|\n");
+        for language in &self.languages {
+            md.push_str(&format!("| {} | {} | {} | {:.1} | {:.0} | {:.2} |\n",
+                language.language, language.files, language.bytes, language.parse_ms, language.files_per_second, language.mb_per_second));
+        }
+        md
+    }
+
+    fn io_label(io_concurrency: usize) -> String {
+        if io_concurrency == 0 { "unlimited".to_string() } else { io_concurrency.to_string() }
+    }
+
+    /// Write `perf_report.json` and `perf_report.md` to the output directory
+    pub fn export(&self, output_dir: &Path) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+
+        let json_path = output_dir.join("perf_report.json");
+        fs::write(&json_path, serde_json::to_string_pretty(self)?).map_err(|e| Error::report(&json_path, e))?;
+
+        let md_path = output_dir.join("perf_report.md");
+        fs::write(&md_path, self.to_markdown()).map_err(|e| Error::report(&md_path, e))?;
+
+        Ok(vec![json_path, md_path])
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{simple_parser::ParsedFile, sources::SourceSet};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Lines of code above which a file is reported as large
+const LARGE_FILE_LINES: usize = 1000;
+
+/// Loop depth at which a function is reported for nested loops
+const NESTED_LOOP_DEPTH: usize = 3;
+
+/// A local heuristic hinting at a performance problem, given to the Performance analysis as evidence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceSignal {
+    pub kind: PerformanceSignalKind,
+    pub file: String,
+    pub line: usize,
+    /// Function the signal was found in, or the file name for large files
+    pub symbol: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PerformanceSignalKind {
+    /// A database query issued inside a loop (N+1 pattern)
+    QueryInLoop,
+    /// Blocking file, network or sleep call inside an async function
+    BlockingIoInAsync,
+    NestedLoops,
+    LargeFile,
+}
+
+impl PerformanceSignalKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PerformanceSignalKind::QueryInLoop => "Query in loop",
+            PerformanceSignalKind::BlockingIoInAsync => "Blocking IO in async",
+            PerformanceSignalKind::NestedLoops => "Nested loops",
+            PerformanceSignalKind::LargeFile => "Large file",
+        }
+    }
+}
+
+impl std::fmt::Display for PerformanceSignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{} {} `{}`: {}", self.file, self.line, self.kind.name(), self.symbol, self.detail)
+    }
+}
+
+/// Finds N+1-looking query loops, blocking IO in async functions, nested loops and
+/// large files. Loop bodies are recognized by indentation, so the results assume
+/// conventionally formatted code.
+pub struct PerformanceSignalDetector {
+    loop_start: Regex,
+    query_call: Regex,
+    blocking_call: Regex,
+}
+
+impl Default for PerformanceSignalDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PerformanceSignalDetector {
+    pub fn new() -> Self {
+        let regex = |pattern: &str| Regex::new(pattern).expect("valid performance pattern");
+        Self {
+            loop_start: regex(r"^\s*(?:(?:\w+\s*:\s*)?(?:for|while|loop)\b|.*\.(?:forEach|each|for_each)\s*[({])"),
+            query_call: regex(r#"\.(?:query|query_as|query_one|execute|exec|executemany|fetch_one|fetch_all|fetch_optional|findOne|findById|findAll|find_by|find_by_id|first_or_create)\s*[(!<]|\bobjects\.(?:get|filter)\(|["'`]\s*(?:SELECT|INSERT\s+INTO|UPDATE|DELETE\s+FROM)\s"#),
+            blocking_call: regex(r"\bstd::fs::|\bfs::(?:read|read_to_string|write|create_dir_all|metadata|remove_file|copy)\(|\bFile::(?:open|create)\(|\bstd::thread::sleep\b|\breqwest::blocking\b|\btime\.sleep\(|\brequests\.(?:get|post|put|patch|delete|request)\(|\bopen\(|\b\w+Sync\("),
+        }
+    }
+
+    /// Signals across all files, grouped by file in input order
+    pub fn detect(&self, parsed_files: &[ParsedFile], sources: &SourceSet) -> Vec<PerformanceSignal> {
+        let mut signals = Vec::new();
+
+        for pf in parsed_files {
+            let file = pf.file_info.path.to_string_lossy().to_string();
+            if pf.lines_of_code > LARGE_FILE_LINES {
+                signals.push(PerformanceSignal {
+                    kind: PerformanceSignalKind::LargeFile,
+                    file: file.clone(),
+                    line: 1,
+                    symbol: pf.file_info.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+                    detail: format!("{} lines of code (limit {})", pf.lines_of_code, LARGE_FILE_LINES),
+                });
+            }
+            if pf.functions.is_empty() {
+                continue;
+            }
+            let Ok(content) = sources.read(&pf.file_info.path) else { continue };
+            let lines: Vec<&str> = content.lines().collect();
+
+            for function in &pf.functions {
+                let start = function.line_number.saturating_sub(1);
+                let end = function.end_line.max(function.line_number).min(lines.len());
+                if start >= end {
+                    continue;
+                }
+                let body = &lines[start..end];
+                let mut flag = |kind, offset: usize, detail: String| {
+                    signals.push(PerformanceSignal { kind, file: file.clone(), line: start + offset + 1, symbol: function.name.clone(), detail });
+                };
+
+                let mut deepest: Option<(usize, usize)> = None;
+                let mut query_flagged = false;
+                // Indentation of the enclosing loops
+                let mut loops: Vec<usize> = Vec::new();
+                for (offset, line) in body.iter().enumerate().skip(1) {
+                    let trimmed = line.trim_start();
+                    if trimmed.is_empty() || ["//", "#", "*", "/*"].iter().any(|comment| trimmed.starts_with(comment)) {
+                        continue;
+                    }
+                    let indent = line.len() - trimmed.len();
+                    while loops.last().is_some_and(|&loop_indent| indent <= loop_indent && !trimmed.starts_with(['}', ')', ']'])) {
+                        loops.pop();
+                    }
+
+                    if !loops.is_empty() && !query_flagged && self.query_call.is_match(line) {
+                        query_flagged = true;
+                        flag(PerformanceSignalKind::QueryInLoop, offset, format!("query inside a loop: `{}`", trimmed.chars().take(100).collect::<String>()));
+                    }
+                    if function.is_async && self.blocking_call.is_match(line) && !line.contains("await") && !line.contains("tokio::") {
+                        flag(PerformanceSignalKind::BlockingIoInAsync, offset, format!("blocking call in async function: `{}`", trimmed.chars().take(100).collect::<String>()));
+                    }
+
+                    if self.loop_start.is_match(line) {
+                        loops.push(indent);
+                        if loops.len() >= NESTED_LOOP_DEPTH && deepest.is_none_or(|(depth, _)| loops.len() > depth) {
+                            deepest = Some((loops.len(), offset));
+                        }
+                    }
+                }
+
+                if let Some((depth, offset)) = deepest {
+                    flag(PerformanceSignalKind::NestedLoops, offset, format!("loops nested {} deep", depth));
+                }
+            }
+        }
+
+        // Function ranges overlap when braces in strings confuse the parser; keep the
+        // signal from the innermost (last-starting) function
+        let mut seen = HashSet::new();
+        signals.reverse();
+        signals.retain(|signal| seen.insert((signal.kind, signal.file.clone(), signal.line)));
+        signals.reverse();
+        signals
+    }
+}
@@ -0,0 +1,376 @@
+use crate::{
+    architecture::{ArchitectureViolation, LazyBoundary},
+    assets::AssetInventory,
+    boundaries::BoundaryViolation,
+    bus_factor::ModuleBusFactor,
+    change_coupling::ChangeCouplingAnalysis,
+    cohesion::ClassCohesion,
+    components::ComponentAnalysis,
+    concurrency::ConcurrencyInventory,
+    config::{Config, PipelineConfig},
+    contracts::ContractInventory,
+    data_access::DataAccess,
+    debt::DebtItem,
+    dependency_graph::{DependencyAnalysis, FileDependency},
+    doc_drift::DocDrift,
+    docker::DockerImage,
+    endpoints::EndpointInventory,
+    file_discovery::{FileInfo, SkippedFile},
+    frontend_routes::FrontendRouteInventory,
+    i18n::I18nReport,
+    grounding::DiscardedItem,
+    infrastructure::InfrastructureInventory,
+    llm::AnalysisResponse,
+    metaprogramming::MetaprogrammingInventory,
+    module_guide::ModuleSummary,
+    onboarding::OnboardingGuide,
+    osv::VulnerabilityScan,
+    ownership::FileOwnership,
+    perf_signals::PerformanceSignal,
+    process_health::ProcessHealth,
+    redaction::DataSentEntry,
+    registry::RegistryCheck,
+    rules::RuleFinding,
+    sampling::SamplingSummary,
+    simple_parser::{ParseError, ParsedFile},
+    smells::CodeSmell,
+    testing::TestingSummary,
+};
+use crate::{Error, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Identifies the run the checkpoints in a directory belong to
+const MANIFEST: &str = "manifest.json";
+
+/// Successful LLM analysis types of an unfinished llm stage
+const LLM_PROGRESS: &str = "llm-progress";
+
+/// One step of an analysis run, in default order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Stage {
+    /// Walk the target directory and select the files to analyze
+    Discover,
+    Parse,
+    /// Dependency graph, cycles and file-to-file dependencies
+    Graph,
+    /// Every analysis that runs without a network call to an LLM
+    LocalPasses,
+    Llm,
+    /// Build the report and export it in the requested formats
+    Report,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 6] = [Stage::Discover, Stage::Parse, Stage::Graph, Stage::LocalPasses, Stage::Llm, Stage::Report];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Discover => "discover",
+            Stage::Parse => "parse",
+            Stage::Graph => "graph",
+            Stage::LocalPasses => "local-passes",
+            Stage::Llm => "llm",
+            Stage::Report => "report",
+        }
+    }
+
+    /// Stages every run needs; the others can be left out
+    pub fn is_required(&self) -> bool {
+        matches!(self, Stage::Discover | Stage::Parse | Stage::Graph)
+    }
+
+    /// Stages whose output this one reads, when they are enabled
+    fn inputs(&self) -> &'static [Stage] {
+        match self {
+            Stage::Discover => &[],
+            Stage::Parse => &[Stage::Discover],
+            Stage::Graph => &[Stage::Parse],
+            Stage::LocalPasses => &[Stage::Graph],
+            Stage::Llm => &[Stage::Graph, Stage::LocalPasses],
+            Stage::Report => &[Stage::Graph, Stage::LocalPasses, Stage::Llm],
+        }
+    }
+}
+
+/// The validated stage order from `[pipeline]`, and where stage checkpoints go
+#[derive(Debug, Clone)]
+pub struct Pipeline {
+    stages: Vec<Stage>,
+    checkpoint_dir: Option<PathBuf>,
+    /// Checkpoints in `checkpoint_dir` belong to this configuration and may be restored
+    resuming: bool,
+    /// What `with_checkpoints` made of the checkpoints it found, for the
+    /// analyzer to print unless it is quiet
+    notice: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct Manifest {
+    version: String,
+    fingerprint: String,
+}
+
+impl Pipeline {
+    /// Fails when a required stage is missing, a stage is listed twice or a
+    /// stage comes before one whose output it reads
+    pub fn new(config: &PipelineConfig) -> Result<Self> {
+        let stages = &config.stages;
+        for stage in Stage::ALL.iter().filter(|stage| stage.is_required()) {
+            if !stages.contains(stage) {
+                return Err(Error::Config(format!("pipeline.stages must include `{}`", stage.name())));
+            }
+        }
+        for (position, stage) in stages.iter().enumerate() {
+            if stages[..position].contains(stage) {
+                return Err(Error::Config(format!("pipeline.stages lists `{}` more than once", stage.name())));
+            }
+            if let Some(input) = stage.inputs().iter().find(|input| stages[position..].contains(input)) {
+                return Err(Error::Config(format!("pipeline.stages runs `{}` before `{}`, whose output it needs", stage.name(), input.name())));
+            }
+        }
+        Ok(Self {
+            stages: stages.clone(),
+            checkpoint_dir: None,
+            resuming: false,
+            notice: None,
+        })
+    }
+
+    /// Write each stage's output to `<dir>/<stage>.json` as it completes. With
+    /// `resume`, checkpoints an earlier run of the same configuration left in
+    /// `dir` are restored instead of running their stages again; otherwise, or
+    /// when the configuration changed, they are discarded. Only checkpoint files
+    /// are ever deleted, and a non-empty `dir` without a manifest is refused.
+    pub fn with_checkpoints(mut self, dir: PathBuf, config: &Config, resume: bool) -> Result<Self> {
+        let manifest = Manifest {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            fingerprint: Self::fingerprint(config)?,
+        };
+        let previous = fs::read(dir.join(MANIFEST)).ok()
+            .and_then(|content| serde_json::from_slice::<Manifest>(&content).ok());
+        self.resuming = resume && previous.as_ref() == Some(&manifest);
+        if resume {
+            self.notice = Some(match previous {
+                Some(_) if self.resuming => format!("♻️  Resuming from checkpoints in {}", dir.display()),
+                Some(_) => format!("♻️  Checkpoints in {} are from a different configuration or version; starting over", dir.display()),
+                None => format!("♻️  No checkpoints in {}; starting from the beginning", dir.display()),
+            });
+        }
+        if previous.is_none() && fs::read_dir(&dir).is_ok_and(|mut entries| entries.next().is_some()) {
+            return Err(Error::Config(format!(
+                "{} has files but no checkpoint manifest; it wasn't created by project-examer, so choose another output directory",
+                dir.display()
+            )));
+        }
+        if !self.resuming {
+            Self::remove_checkpoints(&dir)?;
+        }
+        fs::create_dir_all(&dir).map_err(|e| Error::report(&dir, e))?;
+        let manifest_path = dir.join(MANIFEST);
+        fs::write(&manifest_path, serde_json::to_vec(&manifest)?).map_err(|e| Error::report(&manifest_path, e))?;
+        self.checkpoint_dir = Some(dir);
+        Ok(self)
+    }
+
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    pub fn is_enabled(&self, stage: Stage) -> bool {
+        self.stages.contains(&stage)
+    }
+
+    pub fn checkpoint_dir(&self) -> Option<&Path> {
+        self.checkpoint_dir.as_deref()
+    }
+
+    /// Whether checkpoints are being resumed, when `with_checkpoints` was asked to
+    pub fn notice(&self) -> Option<&str> {
+        self.notice.as_deref()
+    }
+
+    /// No-op without a checkpoint directory
+    pub fn save<T: Serialize>(&self, stage: Stage, artifact: &T) -> Result<()> {
+        self.save_checkpoint(stage.name(), artifact)
+    }
+
+    /// A stage's output from the run being resumed
+    pub fn restore<T: DeserializeOwned>(&self, stage: Stage) -> Option<T> {
+        self.restore_checkpoint(stage.name())
+    }
+
+    /// A stage's checkpoint from this run, including restored ones
+    pub fn load<T: DeserializeOwned>(&self, stage: Stage) -> Option<T> {
+        self.read_checkpoint(stage.name())
+    }
+
+    /// Record the LLM analyses that have succeeded so far
+    pub fn save_llm_progress(&self, progress: &LlmProgress) -> Result<()> {
+        self.save_checkpoint(LLM_PROGRESS, progress)
+    }
+
+    /// LLM analyses that succeeded in the run being resumed
+    pub fn restore_llm_progress(&self) -> LlmProgress {
+        self.restore_checkpoint(LLM_PROGRESS).unwrap_or_default()
+    }
+
+    /// LLM analyses that have succeeded in this run, including restored ones
+    pub fn load_llm_progress(&self) -> Option<LlmProgress> {
+        self.read_checkpoint(LLM_PROGRESS)
+    }
+
+    /// Called once the run's report is written. Checkpoints are removed unless
+    /// kept as artifacts, or the llm stage has analysis types left to retry, in
+    /// which case this returns true.
+    pub fn finish(&self, keep_artifacts: bool) -> Result<bool> {
+        let Some(dir) = &self.checkpoint_dir else { return Ok(false) };
+        let llm = dir.join(format!("{}.json", Stage::Llm.name()));
+        if self.is_enabled(Stage::Llm) && !llm.exists() {
+            return Ok(true);
+        }
+        if keep_artifacts {
+            let _ = fs::remove_file(dir.join(format!("{}.json", LLM_PROGRESS)));
+        } else {
+            Self::remove_checkpoints(dir)?;
+            // Left in place when something else was put there
+            let _ = fs::remove_dir(dir);
+        }
+        Ok(false)
+    }
+
+    /// Delete only the files checkpointing writes, leaving anything else in `dir`
+    fn remove_checkpoints(dir: &Path) -> Result<()> {
+        let names = Stage::ALL.iter().map(Stage::name).chain([LLM_PROGRESS]);
+        let files = names
+            .flat_map(|name| [format!("{}.json", name), format!("{}.json.partial", name)])
+            .chain([MANIFEST.to_string()]);
+        for file in files {
+            match fs::remove_file(dir.join(file)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn save_checkpoint<T: Serialize>(&self, name: &str, artifact: &T) -> Result<()> {
+        let Some(dir) = &self.checkpoint_dir else { return Ok(()) };
+        // Write then rename, so an interrupted write never leaves a truncated checkpoint
+        let path = dir.join(format!("{}.json", name));
+        let partial = dir.join(format!("{}.json.partial", name));
+        fs::write(&partial, serde_json::to_vec(artifact)?).map_err(|e| Error::report(&partial, e))?;
+        fs::rename(&partial, &path)?;
+        Ok(())
+    }
+
+    fn restore_checkpoint<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        self.read_checkpoint(name).filter(|_| self.resuming)
+    }
+
+    fn read_checkpoint<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        let content = fs::read(self.checkpoint_dir.as_ref()?.join(format!("{}.json", name))).ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    /// Everything except `[pipeline]` itself, which may change between a run and its resumption
+    fn fingerprint(config: &Config) -> Result<String> {
+        let mut value = serde_json::to_value(config)?;
+        if let Some(config) = value.as_object_mut() {
+            config.remove("pipeline");
+        }
+        let mut hasher = DefaultHasher::new();
+        value.to_string().hash(&mut hasher);
+        Ok(format!("{:016x}", hasher.finish()))
+    }
+}
+
+/// Output of the discover stage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiscoveryArtifact {
+    pub files: Vec<FileInfo>,
+    /// Files the walk found but excluded
+    pub skipped: Vec<SkippedFile>,
+    pub sampling: Option<SamplingSummary>,
+}
+
+/// Output of the parse stage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParseArtifact {
+    pub parsed_files: Vec<ParsedFile>,
+    pub parse_errors: Vec<ParseError>,
+}
+
+/// Output of the graph stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphArtifact {
+    pub dependency_analysis: DependencyAnalysis,
+    pub file_dependencies: Vec<FileDependency>,
+}
+
+/// Output of the local-passes stage; empty when the stage is disabled
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LocalFindings {
+    pub file_ownership: Vec<FileOwnership>,
+    pub bus_factor: Vec<ModuleBusFactor>,
+    pub process_health: ProcessHealth,
+    #[serde(default)]
+    pub change_coupling: ChangeCouplingAnalysis,
+    pub registry_check: RegistryCheck,
+    pub vulnerability_scan: VulnerabilityScan,
+    pub debt_markers: Vec<DebtItem>,
+    pub doc_drift: Vec<DocDrift>,
+    pub code_smells: Vec<CodeSmell>,
+    #[serde(default)]
+    pub class_cohesion: Vec<ClassCohesion>,
+    pub performance_signals: Vec<PerformanceSignal>,
+    pub testing: TestingSummary,
+    pub rule_findings: Vec<RuleFinding>,
+    pub architecture_violations: Vec<ArchitectureViolation>,
+    #[serde(default)]
+    pub lazy_boundaries: Vec<LazyBoundary>,
+    pub boundary_violations: Vec<BoundaryViolation>,
+    pub docker_images: Vec<DockerImage>,
+    #[serde(default)]
+    pub infrastructure: InfrastructureInventory,
+    #[serde(default)]
+    pub assets: AssetInventory,
+    pub endpoints: EndpointInventory,
+    #[serde(default)]
+    pub contracts: ContractInventory,
+    #[serde(default)]
+    pub frontend_routes: FrontendRouteInventory,
+    #[serde(default)]
+    pub i18n: I18nReport,
+    pub data_access: DataAccess,
+    pub concurrency: ConcurrencyInventory,
+    #[serde(default)]
+    pub metaprogramming: MetaprogrammingInventory,
+    pub onboarding: Option<OnboardingGuide>,
+    #[serde(default)]
+    pub components: ComponentAnalysis,
+}
+
+/// Output of the llm stage; empty when the stage is disabled or the run is local-only
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LlmFindings {
+    pub llm_enabled: bool,
+    /// Analysis types that failed; a resumed run retries them
+    #[serde(default)]
+    pub failed_analyses: Vec<String>,
+    pub llm_analysis: Vec<AnalysisResponse>,
+    pub discarded_llm_output: Vec<DiscardedItem>,
+    pub module_summaries: Vec<ModuleSummary>,
+    pub data_sent: Vec<DataSentEntry>,
+}
+
+/// LLM analyses completed so far in the llm stage, with the requests sent for them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LlmProgress {
+    pub responses: Vec<AnalysisResponse>,
+    pub data_sent: Vec<DataSentEntry>,
+}
@@ -0,0 +1,169 @@
+use crate::{config::ProcessConfig, git};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Subjects longer than this are flagged; git tooling truncates them
+const MAX_SUBJECT_LENGTH: usize = 72;
+
+/// Non-conventional subjects quoted as examples
+const EXAMPLE_SUBJECTS: usize = 5;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Commit message, commit size and branching habits from recent git history
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessHealth {
+    /// Non-merge commits audited
+    pub commits: usize,
+    /// Commits following Conventional Commits (`type(scope): subject`)
+    pub conventional_commits: usize,
+    /// Percentage of audited commits that are conventional
+    pub conventional_share: f64,
+    /// Commit count per conventional type (`feat`, `fix`, ...), most used first
+    pub commit_types: Vec<(String, usize)>,
+    /// A few recent subjects that don't follow the convention
+    pub nonconforming_examples: Vec<String>,
+    /// Subjects longer than 72 characters
+    pub long_subjects: usize,
+    pub average_lines_changed: f64,
+    pub median_lines_changed: usize,
+    pub average_files_changed: f64,
+    /// Commits changing more than `large_commit_lines` lines
+    pub large_commits: usize,
+    /// Commits per week over the audited period
+    pub commits_per_week: f64,
+    /// Unmerged branches older than `long_lived_branch_days`, oldest first
+    pub long_lived_branches: Vec<BranchAge>,
+    /// All branches with commits HEAD doesn't have
+    pub unmerged_branches: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchAge {
+    pub name: String,
+    /// Days since the branch's first unmerged commit
+    pub age_days: u64,
+    /// Days since its last commit
+    pub idle_days: u64,
+    /// Commits not on HEAD
+    pub ahead: usize,
+}
+
+impl ProcessHealth {
+    pub fn is_empty(&self) -> bool {
+        self.commits == 0
+    }
+
+    /// One line per finding for the Overview analysis' LLM context
+    pub fn context_lines(&self) -> Vec<String> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let mut lines = vec![
+            format!("{} recent commits, {:.1} per week; {:.0}% follow Conventional Commits", self.commits, self.commits_per_week, self.conventional_share),
+            format!("Commit size: {:.0} lines changed on average (median {}) across {:.1} files; {} large commit(s); {} subject(s) over {} characters",
+                self.average_lines_changed, self.median_lines_changed, self.average_files_changed, self.large_commits, self.long_subjects, MAX_SUBJECT_LENGTH),
+            format!("{} unmerged branch(es), {} long-lived", self.unmerged_branches, self.long_lived_branches.len()),
+        ];
+        lines.extend(self.nonconforming_examples.iter().map(|subject| format!("Non-conventional subject: {}", subject)));
+        lines.extend(self.long_lived_branches.iter().map(|branch| format!("Long-lived branch {}: {} commit(s) ahead, first commit {} days ago, idle {} days",
+            branch.name, branch.ahead, branch.age_days, branch.idle_days)));
+        lines
+    }
+}
+
+/// Audits recent `git log` history and unmerged branches of the repository
+/// containing the target directory
+pub struct ProcessHealthAnalyzer {
+    root: PathBuf,
+    config: ProcessConfig,
+    reference_time: Option<i64>,
+    conventional: Regex,
+}
+
+impl ProcessHealthAnalyzer {
+    pub fn new(root: &Path, config: &ProcessConfig) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            config: config.clone(),
+            reference_time: None,
+            conventional: Regex::new(r"^([a-z]+)(\([^)]*\))?!?: \S").unwrap(),
+        }
+    }
+
+    /// Measure branch ages from this Unix time instead of now (deterministic reports)
+    pub fn with_reference_time(mut self, reference_time: Option<i64>) -> Self {
+        self.reference_time = reference_time;
+        self
+    }
+
+    /// Empty when the target isn't in a git repository or has no commits
+    pub fn analyze(&self) -> ProcessHealth {
+        let Some(commits) = git::recent_commits(&self.root, self.config.history_commits).filter(|commits| !commits.is_empty()) else {
+            return ProcessHealth::default();
+        };
+        let now = self.reference_time.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0)
+        });
+
+        let mut commit_types: Vec<(String, usize)> = Vec::new();
+        let mut nonconforming_examples = Vec::new();
+        let mut conventional_commits = 0;
+        for commit in &commits {
+            match self.conventional.captures(&commit.subject) {
+                Some(captures) => {
+                    conventional_commits += 1;
+                    let kind = captures[1].to_string();
+                    match commit_types.iter_mut().find(|(existing, _)| *existing == kind) {
+                        Some((_, count)) => *count += 1,
+                        None => commit_types.push((kind, 1)),
+                    }
+                }
+                None if nonconforming_examples.len() < EXAMPLE_SUBJECTS => nonconforming_examples.push(commit.subject.clone()),
+                None => {}
+            }
+        }
+        commit_types.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let mut sizes: Vec<usize> = commits.iter().map(|commit| commit.lines_changed).collect();
+        sizes.sort_unstable();
+        let count = commits.len() as f64;
+        // Log is newest first
+        let span_days = (commits[0].commit_time - commits[commits.len() - 1].commit_time) as f64 / SECONDS_PER_DAY as f64;
+
+        let branches = git::unmerged_branches(&self.root).unwrap_or_default();
+        let days_since = |time: i64| ((now - time).max(0) / SECONDS_PER_DAY) as u64;
+        let mut long_lived_branches: Vec<BranchAge> = branches.iter()
+            .map(|branch| BranchAge {
+                name: branch.name.clone(),
+                age_days: days_since(branch.first_commit_time),
+                idle_days: days_since(branch.last_commit_time),
+                ahead: branch.ahead,
+            })
+            .filter(|branch| branch.age_days > self.config.long_lived_branch_days)
+            .collect();
+        long_lived_branches.sort_by(|a, b| b.age_days.cmp(&a.age_days).then_with(|| a.name.cmp(&b.name)));
+
+        ProcessHealth {
+            commits: commits.len(),
+            conventional_commits,
+            conventional_share: conventional_commits as f64 / count * 100.0,
+            commit_types,
+            nonconforming_examples,
+            long_subjects: commits.iter().filter(|commit| commit.subject.chars().count() > MAX_SUBJECT_LENGTH).count(),
+            average_lines_changed: sizes.iter().sum::<usize>() as f64 / count,
+            median_lines_changed: sizes[sizes.len() / 2],
+            average_files_changed: commits.iter().map(|commit| commit.files_changed).sum::<usize>() as f64 / count,
+            large_commits: sizes.iter().filter(|&&size| size > self.config.large_commit_lines).count(),
+            // A single day of history counts as one week rather than extrapolating
+            commits_per_week: count / (span_days / 7.0).max(1.0),
+            long_lived_branches,
+            unmerged_branches: branches.len(),
+        }
+    }
+}
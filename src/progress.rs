@@ -0,0 +1,70 @@
+use crate::pipeline::Stage;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Settings for `Analyzer::analyze_project_with`
+#[derive(Debug, Clone, Default)]
+pub struct AnalyzeOptions {
+    /// Run only the local passes
+    pub skip_llm: bool,
+    /// Print nothing to stdout; progress arrives as events only
+    pub quiet: bool,
+}
+
+/// Stops a running analysis at the next file, pass or LLM call. Clones share
+/// the same flag, so keep one to cancel from another task or thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// What a running analysis is doing, for progress bars and status lines
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum ProgressEvent {
+    StageStarted { stage: Stage },
+    /// The stage's output came from a checkpoint instead of being recomputed
+    StageRestored { stage: Stage },
+    StageFinished { stage: Stage, duration_ms: u64 },
+    /// A file was parsed, or failed to parse when `ok` is false
+    FileParsed { path: PathBuf, completed: usize, total: usize, ok: bool },
+    /// A local pass (ownership, rules, smells, ...) is starting
+    PassStarted { pass: String },
+    /// An LLM analysis type got its reply, or failed when `ok` is false
+    LlmAnalysis { analysis: String, completed: usize, total: usize, ok: bool },
+}
+
+/// Where an analyzer sends its events; a dropped receiver just stops them
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ProgressSink {
+    sender: Option<UnboundedSender<ProgressEvent>>,
+}
+
+impl ProgressSink {
+    pub(crate) fn new(sender: Option<UnboundedSender<ProgressEvent>>) -> Self {
+        Self { sender }
+    }
+
+    pub(crate) fn send(&self, event: ProgressEvent) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(event);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    analyzer::ProjectAnalysis,
+    file_discovery::FileInfo,
+    llm::ProposedChange,
+    sources::SourceSet,
+};
+use crate::{Error, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A proposed change that failed validation and was not written
+#[derive(Debug, Clone)]
+pub struct RejectedChange {
+    pub change: ProposedChange,
+    pub reason: String,
+}
+
+/// Checks LLM-proposed diffs against the analyzed files: the target must be a
+/// real file, the diff headers must name only that file, and every context or
+/// removed line must exist in it.
+pub struct ProposedChangeValidator {
+    /// Discovered files keyed by path relative to the project root
+    files: HashMap<String, PathBuf>,
+}
+
+impl ProposedChangeValidator {
+    pub fn new(root: &Path, files: &[FileInfo]) -> Self {
+        let files = files.iter()
+            .map(|file| {
+                let relative = file.path.strip_prefix(root).unwrap_or(&file.path);
+                (normalize(&relative.to_string_lossy()), file.path.clone())
+            })
+            .collect();
+        Self { files }
+    }
+
+    pub fn validate(&self, changes: Vec<ProposedChange>, sources: &SourceSet) -> (Vec<ProposedChange>, Vec<RejectedChange>) {
+        let mut accepted = Vec::new();
+        let mut rejected = Vec::new();
+
+        for mut change in changes {
+            match self.check(&change, sources) {
+                Ok(()) => {
+                    change.file = normalize(&change.file);
+                    accepted.push(change);
+                }
+                Err(reason) => rejected.push(RejectedChange { change, reason }),
+            }
+        }
+
+        (accepted, rejected)
+    }
+
+    fn check(&self, change: &ProposedChange, sources: &SourceSet) -> std::result::Result<(), String> {
+        let file = normalize(&change.file);
+        let path = self.files.get(&file)
+            .ok_or_else(|| format!("`{}` is not an analyzed file", change.file))?;
+
+        let headers: Vec<&str> = change.diff.lines()
+            .filter(|line| line.starts_with("--- ") || line.starts_with("+++ "))
+            .map(|line| line[4..].split('\t').next().unwrap_or_default().trim())
+            .collect();
+        if headers.is_empty() {
+            return Err("diff has no ---/+++ file headers".to_string());
+        }
+        if let Some(other) = headers.iter().find(|header| **header != "/dev/null" && normalize(header) != file) {
+            return Err(format!("diff touches `{}`, not only `{}`", other, change.file));
+        }
+        if !change.diff.lines().any(|line| line.starts_with("@@")) {
+            return Err("diff has no @@ hunks".to_string());
+        }
+
+        let content = sources.read(path).map_err(|e| format!("cannot read `{}`: {}", change.file, e))?;
+        let source: Vec<&str> = content.lines().map(str::trim_end).collect();
+        let missing = change.diff.lines()
+            .skip_while(|line| !line.starts_with("@@"))
+            .filter(|line| !line.starts_with("---") && (line.starts_with(' ') || line.starts_with('-')))
+            .map(|line| line[1..].trim_end())
+            .find(|line| !line.trim().is_empty() && !source.contains(line));
+        if let Some(line) = missing {
+            return Err(format!("context line not found in file: `{}`", line.trim()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Strip `./` and the `a/`/`b/` prefixes used in diff headers
+fn normalize(path: &str) -> String {
+    let path = path.trim().replace('\\', "/");
+    let path = path.strip_prefix("a/").or_else(|| path.strip_prefix("b/")).unwrap_or(&path);
+    path.trim_start_matches("./").to_string()
+}
+
+/// Writes validated proposals as one `.patch` file each plus a `README.md` index
+#[derive(Default)]
+pub struct ProposedChangeWriter;
+
+impl ProposedChangeWriter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Write `proposed-changes/` under `output_dir`; nothing is written when no changes were proposed
+    pub fn write(&self, analysis: &ProjectAnalysis, output_dir: &Path) -> Result<Vec<PathBuf>> {
+        let changes: Vec<&ProposedChange> = analysis.llm_analysis.iter()
+            .flat_map(|response| &response.proposed_changes)
+            .collect();
+        if changes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let dir = output_dir.join("proposed-changes");
+        fs::create_dir_all(&dir).map_err(|e| Error::report(&dir, e))?;
+
+        let mut written = Vec::new();
+        let mut index = String::from("# Proposed Changes\n\nLLM-proposed refactorings, validated against the analyzed files. Review each patch before applying it with `git apply <patch>`.\n\n");
+        for (i, change) in changes.iter().enumerate() {
+            let name = format!("{:03}-{}.patch", i + 1, change.file.replace(['/', '\\'], "-"));
+            let path = dir.join(&name);
+            let mut diff = change.diff.clone();
+            if !diff.ends_with('\n') {
+                diff.push('\n');
+            }
+            fs::write(&path, diff).map_err(|e| Error::report(&path, e))?;
+            index.push_str(&format!("- [{}]({}) — `{}`: {}\n", name, name, change.file, change.description));
+            written.push(path);
+        }
+
+        let index_path = dir.join("README.md");
+        fs::write(&index_path, index).map_err(|e| Error::report(&index_path, e))?;
+        written.push(index_path);
+        Ok(written)
+    }
+}
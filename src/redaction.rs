@@ -0,0 +1,172 @@
+use crate::config::RedactionConfig;
+use crate::{Error, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Number of values masked in one prompt, by kind
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactionCounts {
+    pub emails: usize,
+    pub secrets: usize,
+    pub ip_addresses: usize,
+}
+
+impl RedactionCounts {
+    pub fn total(&self) -> usize {
+        self.emails + self.secrets + self.ip_addresses
+    }
+
+    fn add(&mut self, other: &RedactionCounts) {
+        self.emails += other.emails;
+        self.secrets += other.secrets;
+        self.ip_addresses += other.ip_addresses;
+    }
+}
+
+/// One request as it left the machine, after redaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataSentEntry {
+    pub provider: String,
+    pub model: String,
+    pub system_prompt: String,
+    pub user_prompt: String,
+    pub bytes: usize,
+    pub redactions: RedactionCounts,
+}
+
+impl DataSentEntry {
+    pub fn new(provider: &str, model: &str, system_prompt: String, user_prompt: String, redactions: RedactionCounts) -> Self {
+        Self {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            bytes: system_prompt.len() + user_prompt.len(),
+            system_prompt,
+            user_prompt,
+            redactions,
+        }
+    }
+}
+
+/// Masks sensitive values in text sent to LLM providers and identifies files
+/// whose contents and names must not be sent at all (`[redaction]`)
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    enabled: bool,
+    strip_string_literals: bool,
+    sensitive_paths: GlobSet,
+    email: Option<Regex>,
+    secrets: Vec<Regex>,
+    ip_address: Option<Regex>,
+    string_literal: Regex,
+}
+
+impl Redactor {
+    pub fn new(config: &RedactionConfig) -> Result<Self> {
+        let mut sensitive_paths = GlobSetBuilder::new();
+        for pattern in &config.sensitive_paths {
+            sensitive_paths.add(Glob::new(pattern)?);
+        }
+
+        let secrets = if config.mask_secrets {
+            vec![
+                // key = "value" / key: value assignments for credential-like names
+                Regex::new(r#"(?i)((?:api[_-]?key|secret|token|password|passwd|pwd|auth[_-]?token|credential)[\w-]*["']?\s*[:=]\s*)["']?[^\s"',;]{8,}["']?"#)?,
+                // Well-known token formats: AWS access keys, GitHub, Slack, OpenAI/Anthropic style keys, JWTs
+                Regex::new(r"\b(?:AKIA[0-9A-Z]{16}|gh[pousr]_[A-Za-z0-9]{36,}|xox[abposr]-[A-Za-z0-9-]{10,}|sk-[A-Za-z0-9_-]{20,}|eyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,})\b")?,
+                Regex::new(r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----")?,
+            ]
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            enabled: config.enabled,
+            strip_string_literals: config.strip_string_literals,
+            sensitive_paths: sensitive_paths.build()?,
+            email: config.mask_emails
+                .then(|| Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b"))
+                .transpose()?,
+            secrets,
+            ip_address: config.mask_ip_addresses
+                .then(|| Regex::new(r"\b(?:(?:25[0-5]|2[0-4]\d|1?\d?\d)\.){3}(?:25[0-5]|2[0-4]\d|1?\d?\d)\b"))
+                .transpose()?,
+            string_literal: Regex::new(r#""(?:[^"\\\n]|\\.)*"|'(?:[^'\\\n]|\\.)*'"#)?,
+        })
+    }
+
+    /// Files matching `sensitive_paths` are left out of LLM context entirely
+    pub fn is_sensitive(&self, path: &Path) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let relative: PathBuf = path.components()
+            .filter(|c| !matches!(c, std::path::Component::CurDir))
+            .collect();
+        self.sensitive_paths.is_match(&relative)
+    }
+
+    /// Replace string literals in source code with `"…"` when configured
+    pub fn redact_source(&self, code: &str) -> String {
+        if !self.enabled || !self.strip_string_literals {
+            return code.to_string();
+        }
+        self.string_literal.replace_all(code, |caps: &regex::Captures| {
+            let quote = &caps[0][..1];
+            format!("{}…{}", quote, quote)
+        }).into_owned()
+    }
+
+    /// Mask secrets, email addresses and IP addresses
+    pub fn redact(&self, text: &str) -> (String, RedactionCounts) {
+        let mut counts = RedactionCounts::default();
+        if !self.enabled {
+            return (text.to_string(), counts);
+        }
+
+        let mut text = text.to_string();
+        for secret in &self.secrets {
+            text = secret.replace_all(&text, |caps: &regex::Captures| {
+                counts.secrets += 1;
+                match caps.get(1) {
+                    Some(prefix) => format!("{}[REDACTED]", prefix.as_str()),
+                    None => "[REDACTED]".to_string(),
+                }
+            }).into_owned();
+        }
+        if let Some(ref email) = self.email {
+            text = email.replace_all(&text, |_: &regex::Captures| {
+                counts.emails += 1;
+                "[EMAIL]"
+            }).into_owned();
+        }
+        if let Some(ref ip_address) = self.ip_address {
+            text = ip_address.replace_all(&text, |_: &regex::Captures| {
+                counts.ip_addresses += 1;
+                "[IP]"
+            }).into_owned();
+        }
+        (text, counts)
+    }
+
+    /// Redact a prompt pair and describe it for the data-sent manifest
+    pub fn prepare(&self, provider: &str, model: &str, system_prompt: &str, user_prompt: &str) -> DataSentEntry {
+        let (system_prompt, system_counts) = self.redact(system_prompt);
+        let (user_prompt, mut redactions) = self.redact(user_prompt);
+        redactions.add(&system_counts);
+        DataSentEntry::new(provider, model, system_prompt, user_prompt, redactions)
+    }
+}
+
+/// Write `data_sent.json`, listing every request sent to an LLM provider
+pub fn write_manifest(entries: &[DataSentEntry], output_dir: &Path) -> Result<Option<PathBuf>> {
+    if entries.is_empty() {
+        return Ok(None);
+    }
+    fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+    let path = output_dir.join("data_sent.json");
+    fs::write(&path, serde_json::to_string_pretty(entries)?).map_err(|e| Error::report(&path, e))?;
+    Ok(Some(path))
+}
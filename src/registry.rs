@@ -0,0 +1,251 @@
+use crate::manifests::{Ecosystem, Version};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+// The lookups need the `llm` feature's HTTP client and runtime
+#[cfg(feature = "llm")]
+use {
+    crate::{config::RegistryConfig, manifests::DeclaredDependency, Error, Result},
+    chrono::{DateTime, Utc},
+    reqwest::Client,
+    serde_json::Value,
+    std::sync::Arc,
+    std::time::Duration,
+    tokio::{sync::Semaphore, task::JoinSet},
+};
+
+/// How far a declared version is behind the latest release
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SemverDistance {
+    Current,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl SemverDistance {
+    pub fn between(declared: Version, latest: Version) -> Self {
+        if declared >= latest {
+            SemverDistance::Current
+        } else if declared.major != latest.major {
+            SemverDistance::Major
+        } else if declared.minor != latest.minor {
+            SemverDistance::Minor
+        } else {
+            SemverDistance::Patch
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SemverDistance::Current => "current",
+            SemverDistance::Patch => "patch",
+            SemverDistance::Minor => "minor",
+            SemverDistance::Major => "major",
+        }
+    }
+}
+
+/// A dependency whose declared version is behind the registry or was withdrawn
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutdatedDependency {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    pub requirement: String,
+    pub declared: String,
+    pub latest: String,
+    pub distance: SemverDistance,
+    /// Days since the declared version was published
+    pub age_days: Option<u64>,
+    /// Days between the declared and the latest release
+    pub behind_days: Option<u64>,
+    /// Yanked on crates.io or PyPI, or deprecated on npm
+    pub yanked: bool,
+    pub manifest: PathBuf,
+    pub dev: bool,
+}
+
+/// Result of `--check-registry`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegistryCheck {
+    /// Dependencies looked up
+    pub checked: usize,
+    /// Yanked first, then by semver distance and age
+    pub outdated: Vec<OutdatedDependency>,
+    /// Lookups that failed, as `ecosystem name: error`
+    pub failures: Vec<String>,
+}
+
+impl OutdatedDependency {
+    /// How the registry marks a withdrawn release, when the declared one is
+    pub fn withdrawn(&self) -> Option<&'static str> {
+        match (self.yanked, self.ecosystem) {
+            (false, _) => None,
+            (true, Ecosystem::Npm) => Some("deprecated"),
+            (true, _) => Some("yanked"),
+        }
+    }
+}
+
+impl RegistryCheck {
+    pub fn is_empty(&self) -> bool {
+        self.checked == 0 && self.failures.is_empty()
+    }
+}
+
+/// What the registry knows about one package
+#[cfg(feature = "llm")]
+struct PackageInfo {
+    latest: String,
+    /// (version, published, yanked or deprecated)
+    releases: Vec<(String, Option<DateTime<Utc>>, bool)>,
+}
+
+#[cfg(feature = "llm")]
+impl PackageInfo {
+    fn release(&self, version: Version) -> Option<&(String, Option<DateTime<Utc>>, bool)> {
+        self.releases.iter().find(|(number, _, _)| Version::parse(number) == Some(version) && !Version::is_prerelease(number))
+    }
+}
+
+/// Looks declared dependency versions up on crates.io, npm and PyPI
+#[cfg(feature = "llm")]
+pub struct RegistryChecker {
+    config: RegistryConfig,
+}
+
+#[cfg(feature = "llm")]
+impl RegistryChecker {
+    pub fn new(config: &RegistryConfig) -> Self {
+        Self { config: config.clone() }
+    }
+
+    pub async fn check(&self, dependencies: &[DeclaredDependency], now: DateTime<Utc>) -> Result<RegistryCheck> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(self.config.timeout_seconds))
+            // crates.io rejects requests without an identifying user agent
+            .user_agent(concat!("project-examer/", env!("CARGO_PKG_VERSION"), " (https://github.com/codyaverett/project-examer)"))
+            .build()?;
+        let permits = Arc::new(Semaphore::new(self.config.concurrency.max(1)));
+
+        let mut lookups = JoinSet::new();
+        let mut checked = 0;
+        for dependency in dependencies.iter().filter(|dependency| self.config.include_dev || !dependency.dev) {
+            let Some(declared) = dependency.version.as_deref().and_then(Version::parse) else { continue };
+            checked += 1;
+            let (client, permits, dependency) = (client.clone(), permits.clone(), dependency.clone());
+            let url = self.package_url(&dependency);
+            lookups.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                let info = Self::fetch(&client, &url, dependency.ecosystem).await;
+                (dependency, declared, info)
+            });
+        }
+
+        let mut check = RegistryCheck { checked, ..Default::default() };
+        while let Some(joined) = lookups.join_next().await {
+            let (dependency, declared, info) = joined.map_err(|e| Error::Service { service: "registry".to_string(), status: None, message: e.to_string() })?;
+            let info = match info {
+                Ok(info) => info,
+                Err(e) => {
+                    check.failures.push(format!("{} {}: {}", dependency.ecosystem.name(), dependency.name, e));
+                    continue;
+                }
+            };
+            let Some(latest) = Version::parse(&info.latest) else { continue };
+            let release = info.release(declared);
+            let published = release.and_then(|(_, published, _)| *published);
+            let latest_published = info.release(latest).and_then(|(_, published, _)| *published);
+            let yanked = release.map(|(_, _, yanked)| *yanked).unwrap_or(false);
+            let distance = SemverDistance::between(declared, latest);
+            if distance == SemverDistance::Current && !yanked {
+                continue;
+            }
+            let days = |from: DateTime<Utc>, to: DateTime<Utc>| (to - from).num_days().max(0) as u64;
+            check.outdated.push(OutdatedDependency {
+                ecosystem: dependency.ecosystem,
+                declared: release.map(|(number, _, _)| number.clone()).unwrap_or_else(|| declared.to_string()),
+                latest: info.latest,
+                distance,
+                age_days: published.map(|published| days(published, now)),
+                behind_days: published.zip(latest_published).map(|(published, latest)| days(published, latest)),
+                yanked,
+                name: dependency.name,
+                requirement: dependency.requirement,
+                manifest: dependency.manifest,
+                dev: dependency.dev,
+            });
+        }
+
+        check.outdated.sort_by(|a, b| {
+            b.yanked.cmp(&a.yanked)
+                .then_with(|| b.distance.cmp(&a.distance))
+                .then_with(|| b.age_days.cmp(&a.age_days))
+                .then_with(|| (a.ecosystem, &a.name).cmp(&(b.ecosystem, &b.name)))
+        });
+        check.failures.sort();
+        Ok(check)
+    }
+
+    fn package_url(&self, dependency: &DeclaredDependency) -> String {
+        match dependency.ecosystem {
+            Ecosystem::Cargo => format!("{}/api/v1/crates/{}", self.config.crates_io_url.trim_end_matches('/'), dependency.name),
+            // Scoped packages keep their `@` but escape the slash
+            Ecosystem::Npm => format!("{}/{}", self.config.npm_url.trim_end_matches('/'), dependency.name.replace('/', "%2F")),
+            Ecosystem::PyPI => format!("{}/pypi/{}/json", self.config.pypi_url.trim_end_matches('/'), dependency.name),
+        }
+    }
+
+    async fn fetch(client: &Client, url: &str, ecosystem: Ecosystem) -> Result<PackageInfo> {
+        let service = format!("{} registry", ecosystem.name());
+        let document: Value = client.get(url)
+            .send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| Error::service(&service, e))?
+            .json().await?;
+        let missing = |message: &str| Error::Service { service: service.clone(), status: None, message: message.to_string() };
+        let parse_time = |value: &Value| value.as_str()
+            .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+            .map(|time| time.with_timezone(&Utc));
+
+        let info = match ecosystem {
+            Ecosystem::Cargo => PackageInfo {
+                latest: document["crate"]["max_stable_version"].as_str()
+                    .or_else(|| document["crate"]["max_version"].as_str())
+                    .ok_or_else(|| missing("no published version"))?
+                    .to_string(),
+                releases: document["versions"].as_array().into_iter().flatten()
+                    .filter_map(|version| Some((
+                        version["num"].as_str()?.to_string(),
+                        parse_time(&version["created_at"]),
+                        version["yanked"].as_bool().unwrap_or(false),
+                    )))
+                    .collect(),
+            },
+            Ecosystem::Npm => PackageInfo {
+                latest: document["dist-tags"]["latest"].as_str().ok_or_else(|| missing("no latest tag"))?.to_string(),
+                releases: document["versions"].as_object().into_iter().flatten()
+                    .map(|(number, version)| (
+                        number.clone(),
+                        parse_time(&document["time"][number]),
+                        version.get("deprecated").is_some(),
+                    ))
+                    .collect(),
+            },
+            Ecosystem::PyPI => PackageInfo {
+                latest: document["info"]["version"].as_str().ok_or_else(|| missing("no published version"))?.to_string(),
+                releases: document["releases"].as_object().into_iter().flatten()
+                    .map(|(number, files)| {
+                        let files = files.as_array().map(Vec::as_slice).unwrap_or_default();
+                        (
+                            number.clone(),
+                            files.first().and_then(|file| parse_time(&file["upload_time_iso_8601"])),
+                            !files.is_empty() && files.iter().all(|file| file["yanked"].as_bool().unwrap_or(false)),
+                        )
+                    })
+                    .collect(),
+            },
+        };
+        Ok(info)
+    }
+}
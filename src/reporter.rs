@@ -1,23 +1,162 @@
 use crate::{
     analyzer::{ProjectAnalysis, FileSummary},
-    dependency_graph::DependencyAnalysis,
-    llm::{AnalysisResponse, Priority},
+    api_surface::{ApiSurface, ApiSurfaceAnalyzer},
+    architecture::{ArchitectureViolation, LazyBoundary},
+    boundaries::BoundaryViolation,
+    bus_factor::ModuleBusFactor,
+    change_coupling::ChangeCouplingAnalysis,
+    dependency_graph::{BarrelFile, DependencyAnalysis},
+    llm::{AnalysisResponse, Evidence, EvidenceStatus, Priority, RecommendationCategory},
+    config::{MaintainabilityConfig, ScoringConfig},
+    debt::DebtItem,
+    doc_coverage::{DocCoverage, DocCoverageAnalyzer},
+    doc_drift::DocDrift,
+    concurrency::{ConcurrencyInventory, ConcurrencyKind},
+    metaprogramming::{MetaKind, MetaprogrammingInventory},
+    data_access::{AccessKind, DataAccess},
+    perf_signals::PerformanceSignal,
+    process_health::ProcessHealth,
+    registry::RegistryCheck,
+    manifests::Version,
+    osv::{Vulnerability, VulnerabilityScan},
+    grounding::DiscardedItem,
+    testing::TestingSummary,
+    docker::DockerImage,
+    infrastructure::InfrastructureInventory,
+    assets::AssetInventory,
+    endpoints::EndpointInventory,
+    contracts::ContractInventory,
+    frontend_routes::FrontendRouteInventory,
+    i18n::{I18nReport, TranslationBundle},
+    file_discovery::{SkipReason, SkippedFile},
+    maintainability::{FileMaintainability, MaintainabilityAnalyzer, MaintainabilityRating},
+    rules::{RuleFinding, Severity},
+    sampling::SamplingSummary,
+    simple_parser::ParseError,
+    smells::CodeSmell,
+    cohesion::ClassCohesion,
+    module_rollup::{render_main_sequence_svg, ModuleAnalysis, ModuleAnalyzer, SequenceZone},
+    components::ComponentAnalysis,
+    onboarding::OnboardingGuide,
+    scoring::{ScoreFactor, ScoringModel},
+    treemap::TreemapRenderer,
+    html_layout,
 };
-use anyhow::Result;
+use crate::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::{BTreeMap, BTreeSet},
     fs,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
+/// Share of the shorter title's significant words the other title must contain for
+/// two recommendations to be merged
+const RECOMMENDATION_TITLE_SIMILARITY: f64 = 0.75;
+/// Share of significant words two recommendations' titles and descriptions must have in common to be merged
+const RECOMMENDATION_TEXT_SIMILARITY: f64 = 0.5;
+/// Hardcoded strings listed in the HTML and markdown reports; the JSON report has them all
+const MAX_LISTED_STRINGS: usize = 50;
+/// Translation keys listed per table cell
+const MAX_LISTED_KEYS: usize = 10;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Report {
     pub metadata: ReportMetadata,
     pub executive_summary: ExecutiveSummary,
     pub file_analysis: FileAnalysisReport,
     pub dependency_analysis: DependencyAnalysisReport,
+    /// Per-file maintainability index, least maintainable first
+    #[serde(default)]
+    pub maintainability: Vec<FileMaintainability>,
+    pub module_analysis: ModuleAnalysis,
+    /// Metrics, dependencies and findings per `[components]` group
+    #[serde(default)]
+    pub components: ComponentAnalysis,
+    pub api_surface: ApiSurface,
+    pub documentation: DocCoverage,
+    pub doc_drift: Vec<DocDrift>,
+    pub ownership: Vec<OwnerSummary>,
+    pub rule_findings: Vec<RuleFinding>,
+    pub architecture_violations: Vec<ArchitectureViolation>,
+    /// Dynamic imports grouped by the layers or directories they cross
+    #[serde(default)]
+    pub lazy_boundaries: Vec<LazyBoundary>,
+    /// Imports that bypass another workspace package's entry point
+    #[serde(default)]
+    pub boundary_violations: Vec<BoundaryViolation>,
+    pub debt_markers: Vec<DebtItem>,
+    #[serde(default)]
+    pub code_smells: Vec<CodeSmell>,
+    #[serde(default)]
+    pub class_cohesion: Vec<ClassCohesion>,
+    #[serde(default)]
+    pub performance_signals: Vec<PerformanceSignal>,
+    /// Test files, frameworks and untested source
+    #[serde(default)]
+    pub testing: TestingSummary,
+    /// Commit authorship concentration per module, bus factor 1 first
+    #[serde(default)]
+    pub bus_factor: Vec<ModuleBusFactor>,
+    /// Commit conventions, commit size and long-lived branches
+    #[serde(default)]
+    pub process_health: ProcessHealth,
+    /// File pairs changing together without an import between them
+    #[serde(default)]
+    pub change_coupling: ChangeCouplingAnalysis,
+    /// Outdated and yanked dependencies (`--check-registry`)
+    #[serde(default)]
+    pub registry_check: RegistryCheck,
+    /// Published advisories affecting declared dependencies (`--check-vulnerabilities`)
+    #[serde(default)]
+    pub vulnerability_scan: VulnerabilityScan,
+    /// Guided tour for new developers (`--audience onboarding`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub onboarding: Option<OnboardingGuide>,
     pub llm_insights: Vec<AnalysisResponse>,
     pub recommendations: Vec<PrioritizedRecommendation>,
+    /// Dockerfiles and the source each image ships
+    #[serde(default)]
+    pub docker_images: Vec<DockerImage>,
+    /// Terraform, CloudFormation and Kubernetes resources and how they depend on each other
+    #[serde(default)]
+    pub infrastructure: InfrastructureInventory,
+    /// Images, models, archives and other binary files, and how much of the repository they take up
+    #[serde(default)]
+    pub assets: AssetInventory,
+    /// API endpoints declared in specs or route declarations
+    #[serde(default)]
+    pub endpoints: EndpointInventory,
+    /// Protobuf, Thrift and Avro contracts and the modules depending on them
+    #[serde(default)]
+    pub contracts: ContractInventory,
+    /// Pages and routes of the frontend and the components they render
+    #[serde(default)]
+    pub frontend_routes: FrontendRouteInventory,
+    /// Translation completeness across locales and hardcoded user-facing strings
+    #[serde(default)]
+    pub i18n: I18nReport,
+    /// Database tables from migrations and the tables each module touches
+    #[serde(default)]
+    pub data_access: DataAccess,
+    /// Concurrency primitives per module
+    #[serde(default)]
+    pub concurrency: ConcurrencyInventory,
+    /// Derives, macros, decorators, metaclasses and build-time codegen per module
+    #[serde(default)]
+    pub metaprogramming: MetaprogrammingInventory,
+    /// Files left out by discovery, grouped by rule, most files first
+    #[serde(default)]
+    pub exclusions: Vec<ExclusionGroup>,
+    /// Files that could not be read or parsed, listed in the appendix
+    #[serde(default)]
+    pub parse_errors: Vec<ParseError>,
+    /// LLM output that named things the project doesn't have, listed in the appendix
+    #[serde(default)]
+    pub discarded_llm_output: Vec<DiscardedItem>,
+    /// Pre-rendered treemap heatmap for the HTML report
+    #[serde(skip)]
+    pub treemap_svg: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -30,6 +169,19 @@ pub struct ReportMetadata {
     pub version: String,
     pub llm_provider: String,
     pub llm_model: String,
+    #[serde(default)]
+    pub llm_enabled: bool,
+    #[serde(default)]
+    pub scope: Vec<String>,
+    /// Present when only a sample of the project was analyzed
+    #[serde(default)]
+    pub sampling: Option<SamplingSummary>,
+    /// Set when the run made no network calls (`--offline`)
+    #[serde(default)]
+    pub offline: bool,
+    /// Commit analyzed with `--rev`, instead of the working copy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -40,6 +192,10 @@ pub struct ExecutiveSummary {
     pub architecture_style: String,
     pub complexity_score: f64,
     pub maintainability_score: f64,
+    /// Percentage of public functions and classes with doc comments
+    #[serde(default)]
+    pub documentation_coverage: f64,
+    pub score_breakdown: Vec<ScoreFactor>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -57,6 +213,9 @@ pub struct LanguageStats {
     pub total_size: u64,
     pub avg_file_size: f64,
     pub percentage: f64,
+    /// Lines of code in the language's parsed files
+    #[serde(default)]
+    pub lines_of_code: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -112,39 +271,133 @@ pub struct DepthBucket {
     pub count: usize,
 }
 
+/// Files excluded by the same discovery rule
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExclusionGroup {
+    pub reason: SkipReason,
+    pub rule: String,
+    pub files: Vec<String>,
+}
+
+/// Files and action items attributed to one owner
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OwnerSummary {
+    pub owner: String,
+    pub files: Vec<String>,
+    pub lines_of_code: usize,
+    pub recommendations: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PrioritizedRecommendation {
     pub title: String,
     pub description: String,
     pub priority: Priority,
-    pub category: String,
+    pub category: RecommendationCategory,
     pub estimated_effort: String,
     pub potential_impact: String,
     pub action_items: Vec<String>,
     pub affected_files: Vec<String>,
 }
 
-pub struct Reporter;
+pub struct Reporter {
+    scoring: ScoringModel,
+    maintainability: MaintainabilityConfig,
+    /// Fixed `generated_at` for deterministic reports; the duration is zeroed too
+    fixed_timestamp: Option<String>,
+}
+
+impl Default for Reporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl Reporter {
     pub fn new() -> Self {
-        Self
+        Self {
+            scoring: ScoringModel::default(),
+            maintainability: MaintainabilityConfig::default(),
+            fixed_timestamp: None,
+        }
+    }
+
+    /// Create a reporter that uses the given `[analysis.scoring]` weights
+    pub fn with_scoring(scoring: ScoringConfig) -> Self {
+        Self {
+            scoring: ScoringModel::new(scoring),
+            maintainability: MaintainabilityConfig::default(),
+            fixed_timestamp: None,
+        }
+    }
+
+    /// Use the given `[analysis.maintainability]` rating thresholds
+    pub fn with_maintainability(mut self, maintainability: MaintainabilityConfig) -> Self {
+        self.maintainability = maintainability;
+        self
+    }
+
+    /// Stamp reports with a fixed time and zero duration so they can be diffed
+    pub fn with_fixed_timestamp(mut self, timestamp: impl Into<String>) -> Self {
+        self.fixed_timestamp = Some(timestamp.into());
+        self
     }
 
     pub fn generate_report(&self, analysis: &ProjectAnalysis, duration_ms: u128, llm_provider: &str, llm_model: &str) -> Report {
         let metadata = self.create_metadata(analysis, duration_ms, llm_provider, llm_model);
-        let executive_summary = self.create_executive_summary(analysis);
+        let documentation = DocCoverageAnalyzer::new().analyze(&analysis.parsed_files);
+        let executive_summary = self.create_executive_summary(analysis, &documentation);
         let file_analysis = self.create_file_analysis_report(analysis);
         let dependency_analysis = self.create_dependency_analysis_report(analysis);
+        let maintainability = MaintainabilityAnalyzer::new(self.maintainability.clone()).analyze(&analysis.parsed_files);
+        let module_analysis = ModuleAnalyzer::new().analyze(analysis);
+        let api_surface = ApiSurfaceAnalyzer::new().analyze(analysis);
         let recommendations = self.prioritize_recommendations(analysis);
+        let ownership = self.create_ownership_summary(analysis, &recommendations);
 
         Report {
             metadata,
             executive_summary,
             file_analysis,
             dependency_analysis,
+            maintainability,
+            module_analysis,
+            components: analysis.components.clone(),
+            api_surface,
+            documentation,
+            doc_drift: analysis.doc_drift.clone(),
+            ownership,
+            rule_findings: analysis.rule_findings.clone(),
+            architecture_violations: analysis.architecture_violations.clone(),
+            lazy_boundaries: analysis.lazy_boundaries.clone(),
+            boundary_violations: analysis.boundary_violations.clone(),
+            debt_markers: analysis.debt_markers.clone(),
+            code_smells: analysis.code_smells.clone(),
+            class_cohesion: analysis.class_cohesion.clone(),
+            performance_signals: analysis.performance_signals.clone(),
+            testing: analysis.testing.clone(),
+            bus_factor: analysis.bus_factor.clone(),
+            process_health: analysis.process_health.clone(),
+            change_coupling: analysis.change_coupling.clone(),
+            registry_check: analysis.registry_check.clone(),
+            vulnerability_scan: analysis.vulnerability_scan.clone(),
+            onboarding: analysis.onboarding.clone(),
             llm_insights: analysis.llm_analysis.clone(),
             recommendations,
+            docker_images: analysis.docker_images.clone(),
+            infrastructure: analysis.infrastructure.clone(),
+            assets: analysis.assets.clone(),
+            endpoints: analysis.endpoints.clone(),
+            contracts: analysis.contracts.clone(),
+            frontend_routes: analysis.frontend_routes.clone(),
+            i18n: analysis.i18n.clone(),
+            data_access: analysis.data_access.clone(),
+            concurrency: analysis.concurrency.clone(),
+            metaprogramming: analysis.metaprogramming.clone(),
+            exclusions: Self::group_exclusions(&analysis.excluded_files),
+            parse_errors: analysis.parse_errors.clone(),
+            discarded_llm_output: analysis.discarded_llm_output.clone(),
+            treemap_svg: TreemapRenderer::default().render_svg(&analysis.parsed_files),
         }
     }
 
@@ -158,18 +411,23 @@ impl Reporter {
             .to_string();
 
         ReportMetadata {
-            generated_at: chrono::Utc::now().to_rfc3339(),
+            generated_at: self.fixed_timestamp.clone().unwrap_or_else(|| chrono::Utc::now().to_rfc3339()),
             project_name,
             total_files: analysis.files.len(),
             total_size,
-            analysis_duration_ms: duration_ms,
+            analysis_duration_ms: if self.fixed_timestamp.is_some() { 0 } else { duration_ms },
             version: env!("CARGO_PKG_VERSION").to_string(),
             llm_provider: llm_provider.to_string(),
             llm_model: llm_model.to_string(),
+            llm_enabled: analysis.llm_enabled,
+            scope: analysis.scope.clone(),
+            sampling: analysis.sampling.clone(),
+            offline: analysis.offline,
+            revision: analysis.revision.clone(),
         }
     }
 
-    fn create_executive_summary(&self, analysis: &ProjectAnalysis) -> ExecutiveSummary {
+    fn create_executive_summary(&self, analysis: &ProjectAnalysis, documentation: &DocCoverage) -> ExecutiveSummary {
         let mut key_findings = Vec::new();
         let mut critical_issues = Vec::new();
 
@@ -187,27 +445,30 @@ impl Reporter {
 
         let overview = if let Some(first_analysis) = analysis.llm_analysis.first() {
             first_analysis.analysis.clone()
+        } else if !analysis.llm_enabled {
+            "LLM analysis was disabled for this run (local-only mode)".to_string()
         } else {
             "No LLM analysis available".to_string()
         };
 
-        let complexity_score = self.calculate_complexity_score(analysis);
-        let maintainability_score = self.calculate_maintainability_score(analysis);
+        let scores = self.scoring.score(analysis);
 
         ExecutiveSummary {
             overview,
             key_findings,
             critical_issues,
             architecture_style: "Unknown".to_string(), // Could be inferred from analysis
-            complexity_score,
-            maintainability_score,
+            complexity_score: scores.complexity_score,
+            maintainability_score: scores.maintainability_score,
+            documentation_coverage: documentation.coverage,
+            score_breakdown: scores.factors,
         }
     }
 
     fn create_file_analysis_report(&self, analysis: &ProjectAnalysis) -> FileAnalysisReport {
         let total_size: u64 = analysis.files.iter().map(|f| f.size).sum();
         
-        let mut language_stats: std::collections::HashMap<String, (usize, u64)> = std::collections::HashMap::new();
+        let mut language_stats: std::collections::BTreeMap<String, (usize, u64)> = std::collections::BTreeMap::new();
         for file in &analysis.files {
             if let Some(ref lang) = file.language {
                 let entry = language_stats.entry(lang.clone()).or_insert((0, 0));
@@ -216,9 +477,17 @@ impl Reporter {
             }
         }
 
+        let mut language_loc: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+        for pf in &analysis.parsed_files {
+            if let Some(ref lang) = pf.file_info.language {
+                *language_loc.entry(lang).or_insert(0) += pf.lines_of_code;
+            }
+        }
+
         let language_breakdown: Vec<LanguageStats> = language_stats
             .into_iter()
             .map(|(lang, (count, size))| LanguageStats {
+                lines_of_code: language_loc.get(lang.as_str()).copied().unwrap_or(0),
                 language: lang,
                 file_count: count,
                 total_size: size,
@@ -239,7 +508,7 @@ impl Reporter {
             })
             .collect();
 
-        file_stats.sort_by(|a, b| b.size.cmp(&a.size));
+        file_stats.sort_by_key(|f| std::cmp::Reverse(f.size));
         let largest_files = file_stats.into_iter().take(10).collect();
 
         let complexity_distribution = self.calculate_complexity_distribution(analysis);
@@ -248,8 +517,8 @@ impl Reporter {
             summary: FileSummary {
                 total_files: analysis.files.len(),
                 total_size,
-                language_distribution: std::collections::HashMap::new(),
-                extension_distribution: std::collections::HashMap::new(),
+                language_distribution: std::collections::BTreeMap::new(),
+                extension_distribution: std::collections::BTreeMap::new(),
             },
             language_breakdown,
             largest_files,
@@ -257,10 +526,32 @@ impl Reporter {
         }
     }
 
+    fn group_exclusions(excluded_files: &[SkippedFile]) -> Vec<ExclusionGroup> {
+        let mut groups: BTreeMap<(SkipReason, &str), Vec<String>> = BTreeMap::new();
+        for file in excluded_files {
+            groups.entry((file.reason, file.rule.as_str())).or_default().push(file.path.display().to_string());
+        }
+
+        let mut exclusions: Vec<ExclusionGroup> = groups.into_iter()
+            .map(|((reason, rule), files)| ExclusionGroup { reason, rule: rule.to_string(), files })
+            .collect();
+        exclusions.sort_by_key(|group| std::cmp::Reverse(group.files.len()));
+        exclusions
+    }
+
     fn create_dependency_analysis_report(&self, analysis: &ProjectAnalysis) -> DependencyAnalysisReport {
         DependencyAnalysisReport {
             graph_metrics: analysis.dependency_analysis.clone(),
-            circular_dependencies: Vec::new(), // TODO: Implement circular dependency detection
+            circular_dependencies: analysis.dependency_analysis.cyclic_components.iter().map(|component| {
+                CircularDependency {
+                    files: component.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+                    severity: if analysis.dependency_analysis.oversized_components.contains(component) {
+                        "High".to_string()
+                    } else {
+                        "Medium".to_string()
+                    },
+                }
+            }).collect(),
             highly_coupled_files: Vec::new(),   // TODO: Implement coupling analysis
             orphaned_files: Vec::new(),         // TODO: Implement orphan detection
             dependency_depth: DependencyDepthInfo {
@@ -271,60 +562,185 @@ impl Reporter {
         }
     }
 
+    fn create_ownership_summary(&self, analysis: &ProjectAnalysis, recommendations: &[PrioritizedRecommendation]) -> Vec<OwnerSummary> {
+        let loc_by_path: std::collections::HashMap<&std::path::Path, usize> = analysis.parsed_files.iter()
+            .map(|pf| (pf.file_info.path.as_path(), pf.lines_of_code))
+            .collect();
+
+        let mut by_owner: std::collections::BTreeMap<&str, OwnerSummary> = std::collections::BTreeMap::new();
+        for ownership in &analysis.file_ownership {
+            let path = ownership.path.to_string_lossy().to_string();
+            for owner in &ownership.owners {
+                let summary = by_owner.entry(owner.as_str()).or_insert_with(|| OwnerSummary {
+                    owner: owner.clone(),
+                    files: Vec::new(),
+                    lines_of_code: 0,
+                    recommendations: Vec::new(),
+                });
+                summary.lines_of_code += loc_by_path.get(ownership.path.as_path()).copied().unwrap_or(0);
+                summary.files.push(path.clone());
+            }
+        }
+
+        for summary in by_owner.values_mut() {
+            summary.recommendations = recommendations.iter()
+                .filter(|rec| rec.affected_files.iter().any(|f| summary.files.contains(f)))
+                .map(|rec| rec.title.clone())
+                .collect();
+        }
+
+        by_owner.into_values().collect()
+    }
+
     fn prioritize_recommendations(&self, analysis: &ProjectAnalysis) -> Vec<PrioritizedRecommendation> {
         let mut recommendations = Vec::new();
 
         for analysis_result in &analysis.llm_analysis {
             for rec in &analysis_result.recommendations {
-                recommendations.push(PrioritizedRecommendation {
+                let rec = PrioritizedRecommendation {
                     title: rec.title.clone(),
                     description: rec.description.clone(),
                     priority: rec.priority.clone(),
-                    category: "General".to_string(),
+                    category: rec.category.unwrap_or_else(|| RecommendationCategory::infer(
+                        &format!("{} {}", rec.title, rec.description), analysis_result.analysis_type.as_ref())),
                     estimated_effort: format!("{:?}", rec.effort),
                     potential_impact: format!("{:?}", rec.impact),
                     action_items: rec.action_items.clone(),
-                    affected_files: Vec::new(),
-                });
+                    affected_files: rec.files.clone(),
+                };
+                // Analysis types often repeat each other's advice; fold near-duplicates together
+                match recommendations.iter_mut().find(|existing| Self::similar_recommendations(existing, &rec)) {
+                    Some(existing) => Self::merge_recommendation(existing, rec),
+                    None => recommendations.push(rec),
+                }
             }
         }
 
-        recommendations.sort_by(|a, b| {
-            use Priority::*;
-            let priority_order = |p: &Priority| match p {
-                Critical => 0,
-                High => 1,
-                Medium => 2,
-                Low => 3,
-            };
-            priority_order(&a.priority).cmp(&priority_order(&b.priority))
-        });
+        for component in &analysis.dependency_analysis.oversized_components {
+            recommendations.push(PrioritizedRecommendation {
+                title: format!("Break up dependency cycle spanning {} files", component.len()),
+                description: "These files all depend on each other, directly or transitively, so none of them can be changed, tested or reused in isolation.".to_string(),
+                priority: Priority::High,
+                category: RecommendationCategory::Architecture,
+                estimated_effort: "Medium".to_string(),
+                potential_impact: "High".to_string(),
+                action_items: vec![
+                    "Identify the weakest edge in the cycle and invert it behind an interface".to_string(),
+                    "Move shared types into a module the cycle members can all depend on".to_string(),
+                ],
+                affected_files: component.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+            });
+        }
+
+        // One recommendation per package whose internals others import
+        let mut bypassed: BTreeMap<&str, Vec<&BoundaryViolation>> = BTreeMap::new();
+        for violation in &analysis.boundary_violations {
+            bypassed.entry(&violation.to_package).or_default().push(violation);
+        }
+        for (package, violations) in bypassed {
+            let internal = violations.iter().any(|v| v.severity == Severity::Error);
+            let mut affected_files: Vec<String> = violations.iter().map(|v| v.from_file.clone()).collect();
+            affected_files.sort();
+            affected_files.dedup();
+            recommendations.push(PrioritizedRecommendation {
+                title: format!("Import {} through its public entry point", package),
+                description: format!("{} import(s) from other workspace packages reach into {}'s files{}, so its internals can't change without breaking them.",
+                    violations.len(), package, if internal { ", including its internal directories" } else { "" }),
+                priority: if internal { Priority::High } else { Priority::Medium },
+                category: RecommendationCategory::Architecture,
+                estimated_effort: "Low".to_string(),
+                potential_impact: "Medium".to_string(),
+                action_items: vec![
+                    format!("Export what other packages need from {}'s entry point and import the package by name", package),
+                    "Move code that several packages share into its own package".to_string(),
+                ],
+                affected_files,
+            });
+        }
+
+        // One recommendation per vulnerable dependency, at its most severe advisory's priority
+        let mut vulnerable: Vec<(&str, &str, Vec<&Vulnerability>)> = Vec::new();
+        for vulnerability in &analysis.vulnerability_scan.vulnerabilities {
+            match vulnerable.iter_mut().find(|(package, version, _)| *package == vulnerability.package && *version == vulnerability.version) {
+                Some((_, _, advisories)) => advisories.push(vulnerability),
+                None => vulnerable.push((&vulnerability.package, &vulnerability.version, vec![vulnerability])),
+            }
+        }
+        for (package, version, advisories) in vulnerable {
+            let fixed = advisories.iter().filter_map(|v| v.fixed.as_deref()).max_by_key(|fixed| Version::parse(fixed));
+            let mut action_items = vec![match fixed {
+                Some(fixed) => format!("Upgrade {} to {} or later in {}", package, fixed, advisories[0].manifest.display()),
+                None => format!("No fixed release is published; replace {} or mitigate the advisories", package),
+            }];
+            action_items.extend(advisories.iter().map(|v| format!("Review {} ({}): {}", v.id, v.severity, v.url)));
+            recommendations.push(PrioritizedRecommendation {
+                title: format!("Upgrade vulnerable dependency {} {}", package, version),
+                description: format!("{} {} has {} published advisor{}: {}", package, version, advisories.len(),
+                    if advisories.len() == 1 { "y" } else { "ies" },
+                    advisories.iter().map(|v| format!("{} {}", v.id, v.summary)).collect::<Vec<_>>().join("; ")),
+                // Sorted most severe first
+                priority: advisories[0].priority.clone(),
+                category: RecommendationCategory::Security,
+                estimated_effort: "Low".to_string(),
+                potential_impact: "High".to_string(),
+                action_items,
+                affected_files: advisories[0].affected_files.clone(),
+            });
+        }
+
+        recommendations.sort_by_key(|rec| Self::priority_rank(&rec.priority));
 
         recommendations
     }
 
-    fn calculate_complexity_score(&self, analysis: &ProjectAnalysis) -> f64 {
-        if analysis.parsed_files.is_empty() {
-            return 0.0;
+    /// Most urgent first
+    fn priority_rank(priority: &Priority) -> u8 {
+        match priority {
+            Priority::Critical => 0,
+            Priority::High => 1,
+            Priority::Medium => 2,
+            Priority::Low => 3,
         }
+    }
 
-        let total_complexity: usize = analysis.parsed_files
-            .iter()
-            .map(|pf| pf.functions.len() + pf.classes.len() * 2 + pf.imports.len())
-            .sum();
-
-        (total_complexity as f64 / analysis.parsed_files.len() as f64).min(10.0)
+    /// Whether two recommendations give the same advice: one title's significant words
+    /// are nearly all in the other's, or their titles and descriptions largely overlap
+    fn similar_recommendations(a: &PrioritizedRecommendation, b: &PrioritizedRecommendation) -> bool {
+        const STOP_WORDS: &[&str] = &["the", "and", "for", "into", "with", "from", "that", "this", "more", "use"];
+        let words = |text: &str| -> BTreeSet<String> {
+            text.split(|c: char| !c.is_alphanumeric())
+                .map(|word| word.to_lowercase())
+                .filter(|word| word.len() > 2 && !STOP_WORDS.contains(&word.as_str()))
+                .collect()
+        };
+        let (title_a, title_b) = (words(&a.title), words(&b.title));
+        let shorter = title_a.len().min(title_b.len());
+        if shorter >= 2 && title_a.intersection(&title_b).count() as f64 / shorter as f64 >= RECOMMENDATION_TITLE_SIMILARITY {
+            return true;
+        }
+        let (text_a, text_b) = (words(&format!("{} {}", a.title, a.description)), words(&format!("{} {}", b.title, b.description)));
+        let union = text_a.union(&text_b).count();
+        union > 0 && text_a.intersection(&text_b).count() as f64 / union as f64 >= RECOMMENDATION_TEXT_SIMILARITY
     }
 
-    fn calculate_maintainability_score(&self, analysis: &ProjectAnalysis) -> f64 {
-        let complexity = self.calculate_complexity_score(analysis);
-        let coupling = analysis.dependency_analysis.avg_degree;
-        
-        let base_score = 10.0;
-        let complexity_penalty = complexity * 0.5;
-        let coupling_penalty = coupling * 0.3;
-        
-        (base_score - complexity_penalty - coupling_penalty).max(0.0)
+    /// Fold a duplicate into a recommendation, keeping the higher priority (and that
+    /// recommendation's wording) and combining files and action items
+    fn merge_recommendation(existing: &mut PrioritizedRecommendation, duplicate: PrioritizedRecommendation) {
+        let other = if Self::priority_rank(&duplicate.priority) < Self::priority_rank(&existing.priority) {
+            std::mem::replace(existing, duplicate)
+        } else {
+            duplicate
+        };
+        for file in other.affected_files {
+            if !existing.affected_files.contains(&file) {
+                existing.affected_files.push(file);
+            }
+        }
+        for item in other.action_items {
+            if !existing.action_items.contains(&item) {
+                existing.action_items.push(item);
+            }
+        }
     }
 
     fn calculate_complexity_distribution(&self, analysis: &ProjectAnalysis) -> Vec<ComplexityBucket> {
@@ -354,344 +770,81 @@ impl Reporter {
     }
 
     pub fn export_report(&self, report: &Report, output_dir: &PathBuf) -> Result<Vec<PathBuf>> {
-        fs::create_dir_all(output_dir)?;
-        let mut exported_files = Vec::new();
+        Ok(vec![
+            self.export_json(report, output_dir)?,
+            self.export_html(report, output_dir)?,
+            self.export_markdown(report, output_dir)?,
+            self.export_markdown_report(report, output_dir)?,
+            self.export_csv(report, output_dir)?,
+        ])
+    }
+
+    /// Per-file maintainability table as `maintainability.csv`
+    pub fn export_csv(&self, report: &Report, output_dir: &PathBuf) -> Result<PathBuf> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+        let csv_path = output_dir.join("maintainability.csv");
+        let mut csv = String::from("path,lines_of_code,cyclomatic_complexity,comment_ratio,maintainability_index,rating\n");
+        for file in &report.maintainability {
+            csv.push_str(&format!("{},{},{},{:.3},{:.1},{}\n",
+                Self::escape_csv(&file.path.to_string_lossy()), file.lines_of_code, file.cyclomatic_complexity,
+                file.comment_ratio, file.index, file.rating.name()));
+        }
+        fs::write(&csv_path, csv).map_err(|e| Error::report(&csv_path, e))?;
+        Ok(csv_path)
+    }
 
-        // Export JSON report
+    fn escape_csv(field: &str) -> String {
+        if field.contains([',', '"', '\n']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+
+    pub fn export_json(&self, report: &Report, output_dir: &PathBuf) -> Result<PathBuf> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
         let json_path = output_dir.join("analysis_report.json");
         let json_content = serde_json::to_string_pretty(report)?;
-        fs::write(&json_path, json_content)?;
-        exported_files.push(json_path);
+        fs::write(&json_path, json_content).map_err(|e| Error::report(&json_path, e))?;
+        Ok(json_path)
+    }
 
-        // Export HTML report
+    pub fn export_html(&self, report: &Report, output_dir: &PathBuf) -> Result<PathBuf> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
         let html_path = output_dir.join("analysis_report.html");
         let html_content = self.generate_html_report(report)?;
-        fs::write(&html_path, html_content)?;
-        exported_files.push(html_path);
+        fs::write(&html_path, html_content).map_err(|e| Error::report(&html_path, e))?;
+        Ok(html_path)
+    }
 
-        // Export Markdown summary
+    pub fn export_markdown(&self, report: &Report, output_dir: &PathBuf) -> Result<PathBuf> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
         let md_path = output_dir.join("analysis_summary.md");
-        let md_content = self.generate_markdown_summary(report)?;
-        fs::write(&md_path, md_content)?;
-        exported_files.push(md_path);
-
-        Ok(exported_files)
-    }
-
-    fn generate_html_report(&self, report: &Report) -> Result<String> {
-        let html = format!(
-            r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Project Analysis Report - {}</title>
-    <style>
-        body {{ font-family: Arial, sans-serif; margin: 40px; line-height: 1.6; }}
-        .header {{ border-bottom: 2px solid #333; padding-bottom: 20px; }}
-        .section {{ margin: 30px 0; }}
-        .metric {{ display: inline-block; margin: 10px 20px 10px 0; padding: 10px; background: #f5f5f5; border-radius: 5px; }}
-        .recommendation {{ margin: 15px 0; padding: 15px; border-left: 4px solid #007acc; background: #f9f9f9; }}
-        .priority-high {{ border-left-color: #ff6b6b; }}
-        .priority-medium {{ border-left-color: #ffa500; }}
-        .priority-low {{ border-left-color: #28a745; }}
-        .insight {{ margin: 10px 0; padding: 10px; background: #e8f4f8; border-radius: 5px; }}
-        .insight-title {{ font-weight: bold; color: #2c3e50; }}
-        .insight-category {{ color: #7f8c8d; font-size: 0.9em; text-transform: uppercase; }}
-        .evidence {{ margin: 5px 0; font-style: italic; color: #555; }}
-        .llm-analysis {{ margin: 20px 0; padding: 20px; background: #f8f9fa; border-radius: 8px; }}
-        .analysis-type {{ font-weight: bold; color: #495057; margin-bottom: 10px; }}
-        .analysis-summary {{ margin: 10px 0; padding: 15px; background: #fff; border-radius: 5px; line-height: 1.6; }}
-        .insights-table, .recommendations-table {{ margin: 15px 0; }}
-        .insights-table th {{ background-color: #e3f2fd; }}
-        .recommendations-table th {{ background-color: #f3e5f5; }}
-        table {{ border-collapse: collapse; width: 100%; margin: 10px 0; }}
-        th, td {{ border: 1px solid #ddd; padding: 12px; text-align: left; vertical-align: top; }}
-        th {{ background-color: #f2f2f2; font-weight: bold; }}
-        .priority-high {{ background-color: #ffebee; }}
-        .priority-medium {{ background-color: #fff3e0; }}
-        .priority-low {{ background-color: #f1f8e9; }}
-        .confidence-high {{ color: #2e7d32; font-weight: bold; }}
-        .confidence-medium {{ color: #f57c00; font-weight: bold; }}
-        .confidence-low {{ color: #d32f2f; font-weight: bold; }}
-        ol {{ list-style-type: decimal; padding-left: 25px; margin: 10px 0; }}
-        ul {{ list-style-type: disc; padding-left: 25px; margin: 10px 0; }}
-        li {{ margin: 8px 0; line-height: 1.4; }}
-        .analysis-summary ul {{ margin: 15px 0; }}
-        .analysis-summary ol {{ margin: 15px 0; }}
-        .analysis-summary li {{ margin: 6px 0; padding-left: 5px; }}
-        .analysis-summary h4 {{ margin: 20px 0 10px 0; color: #2c3e50; }}
-        .analysis-summary h3 {{ margin: 25px 0 15px 0; color: #34495e; }}
-        .analysis-summary p {{ margin: 12px 0; line-height: 1.6; }}
-    </style>
-    <script>
-        function parseJsonContent(jsonText) {{
-            try {{
-                const data = JSON.parse(jsonText);
-                let html = '';
-                
-                // Analysis summary
-                if (data.analysis) {{
-                    html += `<div class="analysis-summary">${{data.analysis}}</div>`;
-                }}
-                
-                // Insights table
-                if (data.insights && data.insights.length > 0) {{
-                    html += `
-                    <h4>Key Insights</h4>
-                    <table class="insights-table">
-                        <thead>
-                            <tr>
-                                <th>Insight</th>
-                                <th>Category</th>
-                                <th>Description</th>
-                                <th>Confidence</th>
-                                <th>Evidence</th>
-                            </tr>
-                        </thead>
-                        <tbody>`;
-                    
-                    data.insights.forEach(insight => {{
-                        const confidenceClass = insight.confidence >= 0.8 ? 'confidence-high' : 
-                                               insight.confidence >= 0.6 ? 'confidence-medium' : 'confidence-low';
-                        const evidence = insight.evidence && insight.evidence.length > 0 ? 
-                                        '• ' + insight.evidence.join('<br>• ') : 'No specific evidence';
-                        
-                        html += `
-                        <tr>
-                            <td><strong>${{insight.title}}</strong></td>
-                            <td>${{insight.category}}</td>
-                            <td>${{insight.description}}</td>
-                            <td class="${{confidenceClass}}">${{Math.round(insight.confidence * 100)}}%</td>
-                            <td>${{evidence}}</td>
-                        </tr>`;
-                    }});
-                    
-                    html += '</tbody></table>';
-                }}
-                
-                // Recommendations table
-                if (data.recommendations && data.recommendations.length > 0) {{
-                    html += `
-                    <h4>Recommendations</h4>
-                    <table class="recommendations-table">
-                        <thead>
-                            <tr>
-                                <th>Title</th>
-                                <th>Description</th>
-                                <th>Priority</th>
-                                <th>Effort</th>
-                                <th>Impact</th>
-                                <th>Action Items</th>
-                            </tr>
-                        </thead>
-                        <tbody>`;
-                    
-                    data.recommendations.forEach(rec => {{
-                        const priorityClass = rec.priority === 'High' || rec.priority === 'Critical' ? 'priority-high' :
-                                             rec.priority === 'Medium' ? 'priority-medium' : 'priority-low';
-                        const actionItems = rec.action_items && rec.action_items.length > 0 ? 
-                                           '• ' + rec.action_items.join('<br>• ') : 'No specific actions';
-                        
-                        html += `
-                        <tr class="${{priorityClass}}">
-                            <td><strong>${{rec.title}}</strong></td>
-                            <td>${{rec.description}}</td>
-                            <td>${{rec.priority}}</td>
-                            <td>${{rec.effort}}</td>
-                            <td>${{rec.impact}}</td>
-                            <td>${{actionItems}}</td>
-                        </tr>`;
-                    }});
-                    
-                    html += '</tbody></table>';
-                }}
-                
-                return html;
-            }} catch (e) {{
-                return `<p>Error parsing JSON content: ${{e.message}}</p>`;
-            }}
-        }}
-        
-        function parseMarkdownContent(markdown) {{
-            let html = markdown;
-            
-            // Convert headers first
-            html = html.replace(/^#### (.+)$/gm, '<h4>$1</h4>');
-            html = html.replace(/^### (.+)$/gm, '<h3>$1</h3>');
-            html = html.replace(/^## (.+)$/gm, '<h2>$1</h2>');
-            html = html.replace(/^# (.+)$/gm, '<h1>$1</h1>');
-            
-            // Convert bold text
-            html = html.replace(/\*\*(.*?)\*\*/g, '<strong>$1</strong>');
-            
-            // Process line by line for better list handling
-            let lines = html.split('\n');
-            let processedLines = [];
-            let inUnorderedList = false;
-            let inOrderedList = false;
-            
-            for (let i = 0; i < lines.length; i++) {{
-                let line = lines[i];
-                let trimmedLine = line.trim();
-                
-                // Look ahead to see if there are more list items coming
-                function hasMoreListItems(startIndex, listType) {{
-                    for (let j = startIndex + 1; j < lines.length; j++) {{
-                        let nextTrimmed = lines[j].trim();
-                        if (nextTrimmed === '') continue; // Skip empty lines
-                        
-                        if (listType === 'ordered' && nextTrimmed.match(/^\d+\.\s+/)) {{
-                            return true;
-                        }}
-                        if (listType === 'unordered' && nextTrimmed.match(/^[-*]\s+/)) {{
-                            return true;
-                        }}
-                        
-                        // Stop looking if we hit a header or substantial content
-                        if (nextTrimmed.match(/^<h[1-6]>/) || 
-                            nextTrimmed.match(/^### /) || 
-                            nextTrimmed.match(/^## /) ||
-                            nextTrimmed.match(/^#### /) ||
-                            (nextTrimmed.length > 0 && !nextTrimmed.match(/^[-*\d]\s*/) && !nextTrimmed.match(/^\d+\.\s+/))) {{
-                            break;
-                        }}
-                    }}
-                    return false;
-                }}
-                
-                // Handle unordered list items
-                if (trimmedLine.match(/^[-*]\s+/)) {{
-                    if (!inUnorderedList) {{
-                        if (inOrderedList) {{
-                            processedLines.push('</ol>');
-                            inOrderedList = false;
-                        }}
-                        processedLines.push('<ul>');
-                        inUnorderedList = true;
-                    }}
-                    let content = trimmedLine.replace(/^[-*]\s+/, '');
-                    processedLines.push(`<li>${{content}}</li>`);
-                    
-                    // Only close if no more unordered items are coming
-                    if (!hasMoreListItems(i, 'unordered')) {{
-                        processedLines.push('</ul>');
-                        inUnorderedList = false;
-                    }}
-                }}
-                // Handle ordered list items (1. 2. 3. etc.)
-                else if (trimmedLine.match(/^\d+\.\s+/)) {{
-                    if (!inOrderedList) {{
-                        if (inUnorderedList) {{
-                            processedLines.push('</ul>');
-                            inUnorderedList = false;
-                        }}
-                        processedLines.push('<ol>');
-                        inOrderedList = true;
-                    }}
-                    let content = trimmedLine.replace(/^\d+\.\s+/, '');
-                    processedLines.push(`<li>${{content}}</li>`);
-                    
-                    // Only close if no more ordered items are coming
-                    if (!hasMoreListItems(i, 'ordered')) {{
-                        processedLines.push('</ol>');
-                        inOrderedList = false;
-                    }}
-                }}
-                // Handle regular content
-                else {{
-                    // Close lists when we encounter headers or substantial content
-                    if (trimmedLine && (trimmedLine.startsWith('<h') || 
-                        trimmedLine.match(/^### /) || 
-                        trimmedLine.match(/^## /) ||
-                        trimmedLine.match(/^#### /))) {{
-                        // Close any open lists when we hit headers
-                        if (inUnorderedList) {{
-                            processedLines.push('</ul>');
-                            inUnorderedList = false;
-                        }}
-                        if (inOrderedList) {{
-                            processedLines.push('</ol>');
-                            inOrderedList = false;
-                        }}
-                        processedLines.push(line);
-                    }} else if (trimmedLine && !trimmedLine.startsWith('<ul') && !trimmedLine.startsWith('<ol') && !trimmedLine.startsWith('</')) {{
-                        // Close lists for substantial paragraph content
-                        if (inUnorderedList) {{
-                            processedLines.push('</ul>');
-                            inUnorderedList = false;
-                        }}
-                        if (inOrderedList) {{
-                            processedLines.push('</ol>');
-                            inOrderedList = false;
-                        }}
-                        processedLines.push(`<p>${{line}}</p>`);
-                    }} else {{
-                        // Empty lines and HTML elements - keep them without closing lists
-                        processedLines.push(line);
-                    }}
-                }}
-            }}
-            
-            // Close any remaining open lists
-            if (inUnorderedList) {{
-                processedLines.push('</ul>');
-            }}
-            if (inOrderedList) {{
-                processedLines.push('</ol>');
-            }}
-            
-            return processedLines.join('\n');
-        }}
-        
-        document.addEventListener('DOMContentLoaded', function() {{
-            // Process JSON content in any element that contains JSON
-            function processElementForJson(element) {{
-                const text = element.textContent || element.innerText;
-                if (text.trim().startsWith('```json') && text.trim().endsWith('```')) {{
-                    const jsonContent = text.trim().slice(7, -3); // Remove ```json and ```
-                    const processedHtml = parseJsonContent(jsonContent);
-                    element.innerHTML = processedHtml;
-                    element.style.whiteSpace = 'normal';
-                    return true;
-                }} else if (text.trim().startsWith('{{') && text.trim().endsWith('}}')) {{
-                    // Direct JSON content without markdown code blocks
-                    try {{
-                        const processedHtml = parseJsonContent(text.trim());
-                        element.innerHTML = processedHtml;
-                        element.style.whiteSpace = 'normal';
-                        return true;
-                    }} catch (e) {{
-                        // Not valid JSON, continue to markdown processing
-                    }}
-                }}
-                return false;
-            }}
-            
-            // Process all potential JSON containers
-            document.querySelectorAll('p, div, .analysis-summary, .llm-analysis div').forEach(element => {{
-                if (processElementForJson(element)) {{
-                    return; // Successfully processed as JSON
-                }}
-                
-                // If not JSON, try markdown processing
-                const text = element.textContent || element.innerText;
-                if (text.includes('###') || text.includes('**') || text.includes('- ') || text.includes('#### ')) {{
-                    const processedHtml = parseMarkdownContent(text);
-                    element.innerHTML = processedHtml;
-                    element.style.whiteSpace = 'normal';
-                }}
-            }});
-        }});
-    </script>
-</head>
-<body>
-    <div class="header">
+        let md_content = self.generate_markdown(report, false)?;
+        fs::write(&md_path, md_content).map_err(|e| Error::report(&md_path, e))?;
+        Ok(md_path)
+    }
+
+    /// Complete Markdown report as `analysis_report.md`, with every finding, insight and
+    /// recommendation, for committing into a project's docs
+    pub fn export_markdown_report(&self, report: &Report, output_dir: &PathBuf) -> Result<PathBuf> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+        let md_path = output_dir.join("analysis_report.md");
+        let md_content = self.generate_markdown(report, true)?;
+        fs::write(&md_path, md_content).map_err(|e| Error::report(&md_path, e))?;
+        Ok(md_path)
+    }
+
+    /// The self-contained HTML report as written to `analysis_report.html`
+    pub fn generate_html_report(&self, report: &Report) -> Result<String> {
+        let body = format!(
+            r#"    <div class="header">
         <h1>Project Analysis Report</h1>
         <p><strong>Project:</strong> {}</p>
         <p><strong>Generated:</strong> {}</p>
+        <p><strong>Scope:</strong> {}</p>
         <p><strong>Analysis Duration:</strong> {}ms</p>
-        <p><strong>LLM Model:</strong> {} ({})</p>
+        <p><strong>LLM Model:</strong> {}</p>
     </div>
     
     <div class="section">
@@ -702,6 +855,9 @@ impl Reporter {
         <div class="metric">
             <strong>Maintainability Score:</strong> {:.2}
         </div>
+        <div class="metric">
+            <strong>Documentation Coverage:</strong> {:.1}%
+        </div>
         <div class="metric">
             <strong>Total Files:</strong> {}
         </div>
@@ -709,11 +865,17 @@ impl Reporter {
             <strong>Total Size:</strong> {:.2} MB
         </div>
         <p>{}</p>
+        <h3>Score Breakdown</h3>
+        <table>
+            <tr><th>Factor</th><th>Score</th><th>Raw Value</th><th>Weight</th><th>Contribution</th></tr>
+            {}
+        </table>
     </div>
-
+{}
     <div class="section">
         <h2>Key Recommendations</h2>
         {}
+        {}
     </div>
 
     <div class="section">
@@ -730,36 +892,252 @@ impl Reporter {
         </table>
     </div>
 
-</body>
-</html>"#,
-            report.metadata.project_name,
+    <div class="section">
+        <h2>Maintainability Index</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Module Guide</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Module Analysis</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Components</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Public API Surface</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>API Endpoints</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Contracts</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Pages &amp; Routes</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Internationalization</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Data Access</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Concurrency</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Metaprogramming</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Documentation Coverage</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Ownership</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Process Health</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Change Coupling</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Dependency Cycles</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Barrel Files</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Outdated Dependencies</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Known Vulnerabilities</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Architecture Conformance</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Package Boundaries</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Rule Findings</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Code Smells</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Class Cohesion</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Performance Signals</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Testing</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Technical Debt Markers</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Container Images</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Infrastructure</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Assets</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Exclusions</h2>
+        {}
+    </div>
+
+    <div class="section">
+        <h2>Codebase Heatmap</h2>
+        <p>Rectangle area is lines of code; color is complexity per 100 lines (green = low, red = high).</p>
+        {}
+    </div>
+{}
+{}
+"#,
             report.metadata.project_name,
             report.metadata.generated_at,
+            Self::scope_label(&report.metadata),
             report.metadata.analysis_duration_ms,
-            report.metadata.llm_model,
-            report.metadata.llm_provider,
+            Self::html_llm_label(&report.metadata),
             report.executive_summary.complexity_score,
             report.executive_summary.maintainability_score,
+            report.executive_summary.documentation_coverage,
             report.metadata.total_files,
             report.metadata.total_size as f64 / (1024.0 * 1024.0),
             report.executive_summary.overview,
+            report.executive_summary.score_breakdown.iter().map(|f| {
+                format!("<tr><td title=\"{}\">{}</td><td>{:?}</td><td>{:.2}</td><td>{:.2}</td><td>{:+.2}</td></tr>",
+                    Self::escape_attr(&f.description), Self::escape_html(&f.name), f.score, f.raw_value, f.weight, f.contribution)
+            }).collect::<Vec<_>>().join("\n"),
+            report.onboarding.as_ref().map(|guide| self.generate_onboarding_html(guide)).unwrap_or_default(),
             report.recommendations.iter().take(5).map(|r| {
                 let priority_class = match r.priority {
                     Priority::High | Priority::Critical => "priority-high",
                     Priority::Medium => "priority-medium",
                     Priority::Low => "priority-low",
                 };
-                format!(r#"<div class="recommendation {}"><strong>{}</strong><p>{}</p></div>"#, 
-                    priority_class, r.title, r.description)
+                let files = if r.affected_files.is_empty() {
+                    String::new()
+                } else {
+                    format!(r#"<p class="affected-files">Files: {}</p>"#, Self::file_links_html(&r.affected_files, ", "))
+                };
+                format!(r#"<div class="recommendation {}"><strong>{}</strong> <span class="insight-category">{}</span>{}{}</div>"#, 
+                    priority_class, Self::escape_html(&r.title), r.category.name(), Self::markdown_html(&r.description), files)
             }).collect::<Vec<_>>().join("\n"),
-            self.generate_llm_insights_html(&report.llm_insights),
+            self.generate_recommendation_categories_html(&report.recommendations),
+            if report.metadata.llm_enabled {
+                self.generate_llm_insights_html(&report.llm_insights)
+            } else {
+                "<p>LLM analysis was disabled for this run (local-only mode).</p>".to_string()
+            },
             report.file_analysis.language_breakdown.iter().map(|l| {
                 format!("<tr><td>{}</td><td>{}</td><td>{:.2}</td><td>{:.1}%</td></tr>",
                     l.language, l.file_count, l.total_size as f64 / (1024.0 * 1024.0), l.percentage)
-            }).collect::<Vec<_>>().join("\n")
+            }).collect::<Vec<_>>().join("\n"),
+            self.generate_maintainability_html(&report.maintainability),
+            self.generate_module_guide_html(&report.module_analysis),
+            self.generate_module_html(&report.module_analysis),
+            self.generate_components_html(&report.components),
+            self.generate_api_surface_html(&report.api_surface),
+            self.generate_endpoints_html(&report.endpoints),
+            self.generate_contracts_html(&report.contracts),
+            self.generate_frontend_routes_html(&report.frontend_routes),
+            self.generate_i18n_html(&report.i18n),
+            self.generate_data_access_html(&report.data_access),
+            self.generate_concurrency_html(&report.concurrency),
+            self.generate_metaprogramming_html(&report.metaprogramming),
+            self.generate_doc_coverage_html(&report.documentation, &report.doc_drift),
+            self.generate_ownership_html(&report.ownership, &report.bus_factor),
+            self.generate_process_health_html(&report.process_health),
+            self.generate_change_coupling_html(&report.change_coupling),
+            self.generate_cycles_html(&report.dependency_analysis.circular_dependencies),
+            self.generate_barrels_html(&report.dependency_analysis.graph_metrics.barrel_files),
+            self.generate_registry_html(&report.registry_check),
+            self.generate_vulnerabilities_html(&report.vulnerability_scan),
+            self.generate_architecture_html(&report.architecture_violations, &report.lazy_boundaries),
+            self.generate_boundaries_html(&report.boundary_violations),
+            self.generate_rule_findings_html(&report.rule_findings),
+            self.generate_code_smells_html(&report.code_smells),
+            self.generate_class_cohesion_html(&report.class_cohesion),
+            self.generate_performance_signals_html(&report.performance_signals),
+            self.generate_testing_html(&report.testing),
+            self.generate_debt_markers_html(&report.debt_markers),
+            self.generate_docker_html(&report.docker_images),
+            self.generate_infrastructure_html(&report.infrastructure),
+            self.generate_assets_html(&report.assets),
+            self.generate_exclusions_html(&report.exclusions),
+            report.treemap_svg,
+            self.generate_parse_errors_html(&report.parse_errors),
+            self.generate_discarded_llm_output_html(&report.discarded_llm_output)
         );
 
-        Ok(html)
+        Ok(html_layout::page(&format!("Project Analysis Report - {}", report.metadata.project_name), &body))
     }
 
     fn generate_llm_insights_html(&self, llm_insights: &[AnalysisResponse]) -> String {
@@ -770,15 +1148,9 @@ impl Reporter {
         let mut html = String::new();
         
         for (index, analysis) in llm_insights.iter().enumerate() {
-            // Determine analysis type from position (based on analyzer.rs order)
-            let analysis_type = match index {
-                0 => "Overview",
-                1 => "Architecture", 
-                2 => "Dependencies",
-                3 => "Security",
-                4 => "Refactoring",
-                5 => "Documentation",
-                _ => "Additional Analysis",
+            let analysis_type = match &analysis.analysis_type {
+                Some(analysis_type) => analysis_type.name().to_string(),
+                None => format!("Additional Analysis {}", index + 1),
             };
 
             html.push_str(&format!(r#"<div class="llm-analysis">
@@ -786,7 +1158,7 @@ impl Reporter {
 
             // Extract and display the main analysis summary
             let analysis_text = self.extract_analysis_text(&analysis.analysis);
-            html.push_str(&format!(r#"<div class="analysis-summary">{}</div>"#, analysis_text));
+            html.push_str(&format!(r#"<div class="analysis-summary">{}</div>"#, Self::markdown_html(&analysis_text)));
 
             // Extract insights and display in table format
             let insights = if !analysis.insights.is_empty() {
@@ -821,7 +1193,7 @@ impl Reporter {
                     let evidence_text = if insight.evidence.is_empty() {
                         "No specific evidence".to_string()
                     } else {
-                        insight.evidence.join("<br>• ")
+                        insight.evidence.iter().map(Self::evidence_html).collect::<Vec<_>>().join("\n")
                     };
 
                     html.push_str(&format!(r#"<tr>
@@ -829,9 +1201,9 @@ impl Reporter {
                         <td>{:?}</td>
                         <td>{}</td>
                         <td class="{}">{:.0}%</td>
-                        <td>• {}</td>
+                        <td>{}</td>
                     </tr>"#, 
-                    insight.title, insight.category, insight.description, 
+                    Self::escape_html(&insight.title), insight.category, Self::markdown_html(&insight.description),
                     confidence_class, insight.confidence * 100.0, evidence_text));
                 }
                 
@@ -855,6 +1227,7 @@ impl Reporter {
                             <th>Priority</th>
                             <th>Effort</th>
                             <th>Impact</th>
+                            <th>Affected Files</th>
                             <th>Action Items</th>
                         </tr>
                     </thead>
@@ -870,7 +1243,7 @@ impl Reporter {
                     let action_items_text = if recommendation.action_items.is_empty() {
                         "No specific actions".to_string()
                     } else {
-                        recommendation.action_items.join("<br>• ")
+                        recommendation.action_items.iter().map(|item| Self::escape_html(item)).collect::<Vec<_>>().join("<br>• ")
                     };
 
                     html.push_str(&format!(r#"<tr class="{}">
@@ -879,10 +1252,12 @@ impl Reporter {
                         <td>{:?}</td>
                         <td>{:?}</td>
                         <td>{:?}</td>
+                        <td>{}</td>
                         <td>• {}</td>
                     </tr>"#, 
-                    priority_class, recommendation.title, recommendation.description,
+                    priority_class, Self::escape_html(&recommendation.title), Self::markdown_html(&recommendation.description),
                     recommendation.priority, recommendation.effort, recommendation.impact,
+                    Self::file_links_html(&recommendation.files, "<br>"),
                     action_items_text));
                 }
                 
@@ -895,10 +1270,1098 @@ impl Reporter {
         html
     }
 
-    fn extract_analysis_text(&self, content: &str) -> String {
-        // First try to parse as JSON and extract the analysis field
-        if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(content) {
-            if let Some(analysis_text) = json_value.get("analysis").and_then(|v| v.as_str()) {
+    fn generate_onboarding_html(&self, guide: &OnboardingGuide) -> String {
+        let mut html = String::from(r#"
+    <div class="section">
+        <h2>Onboarding Guide</h2>
+        <h3>Where to Start Reading</h3>"#);
+
+        if guide.reading_order.is_empty() {
+            html.push_str("<p>No entry points or shared files were detected.</p>");
+        } else {
+            html.push_str("<ol>");
+            for step in &guide.reading_order {
+                html.push_str(&format!("<li><code>{}</code> — {}</li>", Self::escape_html(&step.file.to_string_lossy()), Self::escape_html(&step.reason)));
+            }
+            html.push_str("</ol>");
+        }
+
+        if !guide.main_flows.is_empty() {
+            html.push_str("<h3>Main Flows</h3>");
+            for flow in &guide.main_flows {
+                html.push_str(&format!("<p><strong>{}</strong> → {}</p>",
+                    Self::escape_html(&flow.entry_point.to_string_lossy()),
+                    flow.files.iter().map(|p| format!("<code>{}</code>", Self::escape_html(&p.to_string_lossy()))).collect::<Vec<_>>().join(" → ")));
+            }
+        }
+
+        if !guide.glossary.is_empty() {
+            html.push_str("<h3>Glossary</h3><table><tr><th>Term</th><th>Identifiers</th><th>Examples</th></tr>");
+            for term in &guide.glossary {
+                html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    Self::escape_html(&term.term), term.occurrences, Self::escape_html(&term.examples.join(", "))));
+            }
+            html.push_str("</table>");
+        }
+
+        html.push_str("\n    </div>\n");
+        html
+    }
+
+    fn generate_maintainability_html(&self, files: &[FileMaintainability]) -> String {
+        if files.is_empty() {
+            return "<p>No files to score.</p>".to_string();
+        }
+
+        let mut html = String::from(r#"<p>Index 0-100 from lines of code, cyclomatic complexity, comment ratio and Halstead volume; click a column to sort.</p>
+        <table class="sortable">
+            <tr><th onclick="sortTable(this)">File</th><th onclick="sortTable(this)">LOC</th><th onclick="sortTable(this)">Cyclomatic</th><th onclick="sortTable(this)">Comment Ratio</th><th onclick="sortTable(this)">Index</th><th onclick="sortTable(this)">Rating</th></tr>"#);
+
+        for file in files {
+            html.push_str(&format!(
+                "<tr class=\"rating-{}\"><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{:.1}</td><td>{}</td></tr>",
+                file.rating.name(), Self::escape_html(&file.path.to_string_lossy()), file.lines_of_code,
+                file.cyclomatic_complexity, file.comment_ratio, file.index, file.rating.name()
+            ));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_module_guide_html(&self, module_analysis: &ModuleAnalysis) -> String {
+        let summarized: Vec<_> = module_analysis.modules.iter().filter(|m| m.summary.is_some()).collect();
+        if summarized.is_empty() {
+            return "<p>No module summaries. Enable <code>analysis.summarize_modules</code> to have the LLM describe each module.</p>".to_string();
+        }
+
+        summarized.iter().map(|m| {
+            format!("<h3>{}</h3>{}<p><em>{} files, {} lines of code</em></p>",
+                Self::escape_html(&m.module), Self::markdown_html(m.summary.as_deref().unwrap_or_default()), m.files, m.lines_of_code)
+        }).collect()
+    }
+
+    fn generate_module_html(&self, module_analysis: &ModuleAnalysis) -> String {
+        if module_analysis.modules.is_empty() {
+            return "<p>No modules found.</p>".to_string();
+        }
+
+        let mut html = String::from(r#"<h3>Module Rollups</h3>
+        <table>
+            <tr><th>Module</th><th>Files</th><th>LOC</th><th>Complexity</th><th>Internal Deps</th><th>External Deps</th><th>Ca</th><th>Ce</th><th>Instability</th><th>Abstractness</th><th>Distance</th></tr>"#);
+
+        for m in &module_analysis.modules {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{:.2}</td><td>{:.2}{}</td></tr>",
+                Self::escape_html(&m.module), m.files, m.lines_of_code, m.complexity, m.internal_dependencies,
+                m.external_dependencies, m.afferent_coupling, m.efferent_coupling, m.instability, m.abstractness,
+                m.distance, m.zone.map(|zone| format!(" ({})", zone.name())).unwrap_or_default()));
+        }
+        html.push_str("</table>");
+
+        let points: Vec<_> = module_analysis.modules.iter()
+            .map(|m| (m.module.as_str(), m.instability, m.abstractness, m.zone))
+            .collect();
+        html.push_str("<h3>Abstractness vs Instability</h3><p>Modules near the dashed main sequence balance abstraction against stability. The red corner is the zone of pain (concrete and depended upon), the yellow one the zone of uselessness (abstract and unused).</p>");
+        html.push_str(&render_main_sequence_svg(&points));
+        let in_pain: Vec<_> = module_analysis.modules.iter().filter(|m| m.zone == Some(SequenceZone::Pain)).collect();
+        if !in_pain.is_empty() {
+            html.push_str("<h4>Zone of Pain</h4><ul>");
+            for m in in_pain {
+                html.push_str(&format!("<li>{}: {} dependent module(s), abstractness {:.2}, instability {:.2}</li>",
+                    Self::escape_html(&m.module), m.afferent_coupling, m.abstractness, m.instability));
+            }
+            html.push_str("</ul>");
+        }
+
+        let matrix = &module_analysis.dependency_matrix;
+        html.push_str("<h3>Module Dependency Matrix</h3><p>Rows depend on columns.</p><table><tr><th></th>");
+        for i in 0..matrix.modules.len() {
+            html.push_str(&format!("<th>{}</th>", i + 1));
+        }
+        html.push_str("</tr>");
+        for (i, module) in matrix.modules.iter().enumerate() {
+            html.push_str(&format!("<tr><th>{}. {}</th>", i + 1, Self::escape_html(module)));
+            for count in &matrix.cells[i] {
+                if *count == 0 {
+                    html.push_str("<td></td>");
+                } else {
+                    html.push_str(&format!("<td>{}</td>", count));
+                }
+            }
+            html.push_str("</tr>");
+        }
+        html.push_str("</table>");
+
+        html
+    }
+
+    fn generate_components_html(&self, components: &ComponentAnalysis) -> String {
+        if components.is_empty() {
+            return "<p>No components configured. Group files under <code>[components]</code> in the config to roll metrics up to named components.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} components with {} dependencies between them; {} files match no component.</p>",
+            components.components.len(), components.dependencies.len(), components.unassigned_files);
+        html.push_str(&components.render_svg());
+        html.push_str("<table><tr><th>Component</th><th>Files</th><th>LOC</th><th>Complexity</th><th>Ca</th><th>Ce</th><th>Instability</th><th>Abstractness</th><th>Distance</th><th>Smells</th><th>Debt</th><th>Rules</th><th>Perf</th><th>Boundary</th></tr>");
+        for c in &components.components {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{:.2}</td><td>{:.2}{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                Self::escape_html(&c.name), c.files, c.lines_of_code, c.complexity, c.afferent_coupling, c.efferent_coupling, c.instability,
+                c.abstractness, c.distance, c.zone.map(|zone| format!(" ({})", zone.name())).unwrap_or_default(),
+                c.code_smells, c.debt_markers, c.rule_findings, c.performance_signals, c.boundary_violations));
+        }
+        html.push_str("</table>");
+        let points: Vec<_> = components.components.iter()
+            .map(|c| (c.name.as_str(), c.instability, c.abstractness, c.zone))
+            .collect();
+        html.push_str("<h3>Component Abstractness vs Instability</h3>");
+        html.push_str(&render_main_sequence_svg(&points));
+        if !components.dependencies.is_empty() {
+            html.push_str("<h3>Component Dependencies</h3><table><tr><th>From</th><th>To</th><th>Imports</th></tr>");
+            for dependency in &components.dependencies {
+                html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    Self::escape_html(&dependency.from), Self::escape_html(&dependency.to), dependency.imports));
+            }
+            html.push_str("</table>");
+        }
+        html
+    }
+
+    fn generate_api_surface_html(&self, api_surface: &ApiSurface) -> String {
+        if api_surface.modules.is_empty() {
+            return "<p>No exported symbols found.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} exported symbols across {} modules. Caller counts are estimated from imports.</p>",
+            api_surface.total_exported, api_surface.modules.len());
+
+        for module in &api_surface.modules {
+            html.push_str(&format!(r#"<h3>{}</h3>
+        <table>
+            <tr><th>Symbol</th><th>Kind</th><th>Signature</th><th>Location</th><th>Internal Callers</th><th>External Callers</th></tr>"#,
+                Self::escape_html(&module.module)));
+            for symbol in &module.symbols {
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{:?}</td><td><code>{}</code></td><td>{}:{}</td><td>{}</td><td>{}</td></tr>",
+                    Self::escape_html(&symbol.name), symbol.kind, Self::escape_html(&symbol.signature), Self::escape_html(&symbol.file), symbol.line,
+                    symbol.internal_callers, symbol.external_callers));
+            }
+            html.push_str("</table>");
+        }
+
+        html
+    }
+
+    fn generate_endpoints_html(&self, inventory: &EndpointInventory) -> String {
+        if inventory.endpoints.is_empty() {
+            return "<p>No OpenAPI/GraphQL specs or route declarations found.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} endpoints from {} spec file(s) and route declarations; {} declared in a spec have no matching route.</p>",
+            inventory.endpoints.len(), inventory.specs.len(), inventory.unimplemented().count());
+        html.push_str("<table><tr><th>Method</th><th>Path</th><th>Declared In</th><th>Implemented In</th></tr>");
+        for endpoint in &inventory.endpoints {
+            html.push_str(&format!("<tr><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+                endpoint.method,
+                Self::escape_html(&endpoint.path),
+                endpoint.declared_in.iter().map(|f| Self::escape_html(&f.to_string_lossy())).collect::<Vec<_>>().join("<br>"),
+                endpoint.implemented_in.iter().map(|r| format!("{}:{}", Self::escape_html(&r.file.to_string_lossy()), r.line)).collect::<Vec<_>>().join("<br>")));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_contracts_html(&self, inventory: &ContractInventory) -> String {
+        if inventory.is_empty() {
+            return "<p>No Protobuf, Thrift or Avro files found.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} contract file(s) declaring {} RPC(s); {} module(s) use the generated code.</p>",
+            inventory.contracts.len(), inventory.rpc_count(), inventory.modules.len());
+        html.push_str("<table><tr><th>Contract</th><th>Format</th><th>Package</th><th>Services</th><th>Types</th><th>Generated Files</th><th>Consumers</th></tr>");
+        for contract in &inventory.contracts {
+            let services = contract.services.iter()
+                .map(|service| format!("<strong>{}</strong><br>{}", Self::escape_html(&service.name),
+                    service.methods.iter().map(|method| format!("<code>{}</code>", Self::escape_html(&method.signature()))).collect::<Vec<_>>().join("<br>")))
+                .collect::<Vec<_>>()
+                .join("<br>");
+            html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                Self::escape_html(&contract.file.to_string_lossy()),
+                contract.format.name(),
+                Self::escape_html(contract.package.as_deref().unwrap_or("-")),
+                services,
+                contract.types.len(),
+                contract.generated_files.len(),
+                contract.consumers.len()));
+        }
+        html.push_str("</table>");
+
+        if !inventory.modules.is_empty() {
+            html.push_str("<h3>Contracts by Module</h3><table><tr><th>Module</th><th>Contracts</th><th>Files</th></tr>");
+            for module in &inventory.modules {
+                html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    Self::escape_html(&module.module),
+                    module.contracts.iter().map(|c| Self::escape_html(&c.to_string_lossy())).collect::<Vec<_>>().join("<br>"),
+                    module.files));
+            }
+            html.push_str("</table>");
+        }
+        html
+    }
+
+    fn generate_frontend_routes_html(&self, inventory: &FrontendRouteInventory) -> String {
+        if inventory.is_empty() {
+            return "<p>No React Router, Next.js or Angular routes found.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} pages and routes ({}); ⏳ marks components loaded lazily.</p>", inventory.routes.len(),
+            inventory.frameworks().iter().map(|framework| framework.name()).collect::<Vec<_>>().join(", "));
+        html.push_str("<table><tr><th>Router</th><th>Path</th><th>Component</th><th>Component File</th><th>Declared In</th></tr>");
+        for route in &inventory.routes {
+            let component = match (&route.redirect_to, &route.component) {
+                (Some(redirect), _) => format!("→ <code>{}</code>", Self::escape_html(redirect)),
+                (None, Some(component)) => format!("<code>{}</code>", Self::escape_html(component)),
+                (None, None) => "-".to_string(),
+            };
+            html.push_str(&format!("<tr><td>{}</td><td><code>{}</code></td><td>{}{}</td><td>{}</td><td>{}:{}</td></tr>",
+                route.framework.name(),
+                Self::escape_html(&route.path),
+                component,
+                if route.lazy { " ⏳" } else { "" },
+                route.component_file.as_ref().map(|file| Self::escape_html(&file.to_string_lossy())).unwrap_or_else(|| "-".to_string()),
+                Self::escape_html(&route.declared_in.to_string_lossy()),
+                route.line));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_i18n_html(&self, i18n: &I18nReport) -> String {
+        if i18n.is_empty() {
+            return "<p>No JSON, gettext or ARB translation resources found.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} translation bundle(s) in {} locale(s): {} missing and {} unused key(s), {} hardcoded user-facing string(s).</p>",
+            i18n.bundles.len(), i18n.locales().len(), i18n.missing_count(), i18n.unused_count(), i18n.hardcoded_strings.len());
+        html.push_str("<table><tr><th>Bundle</th><th>Format</th><th>Locale</th><th>Keys</th><th>Missing</th><th>Untranslated</th></tr>");
+        for bundle in &i18n.bundles {
+            for locale in &bundle.locales {
+                html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    Self::escape_html(&bundle.name),
+                    bundle.format.name(),
+                    locale.locale,
+                    if locale.locale == bundle.reference_locale { " (reference)" } else { "" },
+                    locale.keys,
+                    Self::key_list_html(&locale.missing),
+                    Self::key_list_html(&locale.untranslated)));
+            }
+        }
+        html.push_str("</table>");
+
+        let unused: Vec<&TranslationBundle> = i18n.bundles.iter().filter(|bundle| !bundle.unused_keys.is_empty()).collect();
+        if !unused.is_empty() {
+            html.push_str("<h3>Unused Keys</h3><p>No string literal in the code names these keys; keys built at runtime can't be seen.</p><ul>");
+            for bundle in unused {
+                html.push_str(&format!("<li>{}: {}</li>", Self::escape_html(&bundle.name), Self::key_list_html(&bundle.unused_keys)));
+            }
+            html.push_str("</ul>");
+        }
+
+        if !i18n.hardcoded_strings.is_empty() {
+            html.push_str("<h3>Hardcoded Strings</h3><table><tr><th>Location</th><th>Text</th></tr>");
+            for string in i18n.hardcoded_strings.iter().take(MAX_LISTED_STRINGS) {
+                html.push_str(&format!("<tr><td>{}:{}</td><td>{}</td></tr>", Self::escape_html(&string.file.display().to_string()), string.line, Self::escape_html(&string.text)));
+            }
+            html.push_str("</table>");
+            if i18n.hardcoded_strings.len() > MAX_LISTED_STRINGS {
+                html.push_str(&format!("<p>... and {} more in the JSON report.</p>", i18n.hardcoded_strings.len() - MAX_LISTED_STRINGS));
+            }
+        }
+        html
+    }
+
+    /// Keys as inline code, the first few only
+    fn key_list_html(keys: &[String]) -> String {
+        if keys.is_empty() {
+            return "-".to_string();
+        }
+        let mut list = keys.iter().take(MAX_LISTED_KEYS).map(|key| format!("<code>{}</code>", Self::escape_html(key))).collect::<Vec<_>>().join(", ");
+        if keys.len() > MAX_LISTED_KEYS {
+            list.push_str(&format!(" and {} more", keys.len() - MAX_LISTED_KEYS));
+        }
+        list
+    }
+
+    fn generate_data_access_html(&self, data_access: &DataAccess) -> String {
+        if data_access.is_empty() {
+            return "<p>No SQL migrations or inline SQL queries found.</p>".to_string();
+        }
+
+        let tables = |tables: &BTreeMap<String, BTreeSet<AccessKind>>, kind: AccessKind| {
+            tables.iter().filter(|(_, ops)| ops.contains(&kind)).map(|(t, _)| t.as_str()).collect::<Vec<_>>().join(", ")
+        };
+        let mut html = format!("<p>{} tables defined in {} migration(s); {} files contain SQL.</p>",
+            data_access.tables.len(), data_access.migrations.len(), data_access.files.len());
+
+        html.push_str("<h3>Tables by Module</h3><table><tr><th>Module</th><th>Reads</th><th>Writes</th><th>Schema</th></tr>");
+        for module in &data_access.modules {
+            html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                Self::escape_html(&module.module),
+                tables(&module.tables, AccessKind::Read),
+                tables(&module.tables, AccessKind::Write),
+                tables(&module.tables, AccessKind::Schema)));
+        }
+        html.push_str("</table>");
+
+        if !data_access.tables.is_empty() {
+            html.push_str("<h3>Schema</h3><table><tr><th>Table</th><th>Columns</th><th>Defined In</th></tr>");
+            for table in &data_access.tables {
+                html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    Self::escape_html(&table.name), Self::escape_html(&table.columns.join(", ")),
+                    table.defined_in.iter().map(|p| Self::escape_html(&p.to_string_lossy())).collect::<Vec<_>>().join("<br>")));
+            }
+            html.push_str("</table>");
+        }
+
+        let dynamic: Vec<String> = data_access.files.iter()
+            .flat_map(|file| file.dynamic_queries.iter().map(move |line| format!("{}:{}", Self::escape_html(&file.file.to_string_lossy()), line)))
+            .collect();
+        if !dynamic.is_empty() {
+            html.push_str(&format!("<h3>Dynamically Built SQL</h3><p>{} queries are built by concatenation or string formatting; check them for injection.</p><ul>", dynamic.len()));
+            for location in dynamic {
+                html.push_str(&format!("<li>{}</li>", location));
+            }
+            html.push_str("</ul>");
+        }
+        html
+    }
+
+    fn generate_concurrency_html(&self, inventory: &ConcurrencyInventory) -> String {
+        if inventory.is_empty() {
+            return "<p>No threads, async tasks, locks, channels, atomics or unsafe code found.</p>".to_string();
+        }
+
+        let kinds = Self::concurrency_kinds();
+        let mut html = format!("<p>{} concurrency primitives in {} modules. Modules that spawn work and share state are listed first.</p>",
+            inventory.sites.len(), inventory.hotspots.len());
+        html.push_str("<table><tr><th>Module</th>");
+        for kind in kinds {
+            html.push_str(&format!("<th>{}</th>", kind.name()));
+        }
+        html.push_str("<th>Total</th></tr>");
+        for hotspot in &inventory.hotspots {
+            html.push_str(&format!("<tr><td>{}{}</td>", Self::escape_html(&hotspot.module),
+                if hotspot.spawns_with_shared_state { " ⚠️" } else { "" }));
+            for kind in kinds {
+                html.push_str(&format!("<td>{}</td>", hotspot.counts.get(&kind).copied().unwrap_or(0)));
+            }
+            html.push_str(&format!("<td>{}</td></tr>", hotspot.total));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn concurrency_kinds() -> [ConcurrencyKind; 6] {
+        [ConcurrencyKind::Thread, ConcurrencyKind::AsyncTask, ConcurrencyKind::Lock,
+         ConcurrencyKind::Channel, ConcurrencyKind::Atomic, ConcurrencyKind::Unsafe]
+    }
+
+    fn generate_metaprogramming_html(&self, inventory: &MetaprogrammingInventory) -> String {
+        if inventory.is_empty() {
+            return "<p>No derives, macros, decorators, metaclasses or generated code found.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} metaprogramming sites in {} modules. Modules that generate code or define their own macros are listed first.</p>",
+            inventory.sites.len(), inventory.modules.len());
+        html.push_str("<table><tr><th>Module</th>");
+        for kind in MetaKind::ALL {
+            html.push_str(&format!("<th>{}</th>", kind.name()));
+        }
+        html.push_str("<th>Total</th><th>Most used</th></tr>");
+        for module in &inventory.modules {
+            html.push_str(&format!("<tr><td>{}{}</td>", Self::escape_html(&module.module),
+                if module.generates_code { " ⚙️" } else { "" }));
+            for kind in MetaKind::ALL {
+                html.push_str(&format!("<td>{}</td>", module.counts.get(&kind).copied().unwrap_or(0)));
+            }
+            let names = module.top_names.iter()
+                .map(|(name, uses)| format!("<code>{}</code> ×{}", Self::escape_html(name), uses))
+                .collect::<Vec<_>>()
+                .join(", ");
+            html.push_str(&format!("<td>{}</td><td>{}</td></tr>", module.total, names));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_doc_coverage_html(&self, documentation: &DocCoverage, doc_drift: &[DocDrift]) -> String {
+        let mut html = self.generate_doc_drift_html(doc_drift);
+        if documentation.total == 0 {
+            html.push_str("<p>No public functions or classes found.</p>");
+            return html;
+        }
+
+        html.push_str(&format!("<p>{} of {} public functions and classes are documented ({:.1}%).</p>",
+            documentation.documented, documentation.total, documentation.coverage));
+        html.push_str("<table><tr><th>Module</th><th>Documented</th><th>Public Symbols</th><th>Coverage</th></tr>");
+        for module in &documentation.modules {
+            html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.1}%</td></tr>",
+                Self::escape_html(&module.module), module.documented, module.total, module.coverage));
+        }
+        html.push_str("</table>");
+
+        if !documentation.undocumented.is_empty() {
+            html.push_str("<h3>Undocumented Public Symbols</h3><ul>");
+            for symbol in &documentation.undocumented {
+                html.push_str(&format!("<li><code>{}</code> ({:?}) — {}:{}</li>",
+                    Self::escape_html(&symbol.name), symbol.kind, Self::escape_html(&symbol.file), symbol.line));
+            }
+            html.push_str("</ul>");
+        }
+        html
+    }
+
+    fn generate_doc_drift_html(&self, doc_drift: &[DocDrift]) -> String {
+        if doc_drift.is_empty() {
+            return String::new();
+        }
+
+        let mut html = String::from("<h3>README/CHANGELOG Drift</h3><table><tr><th>Document</th><th>Kind</th><th>Reference</th><th>Problem</th></tr>");
+        for drift in doc_drift {
+            html.push_str(&format!("<tr><td>{}:{}</td><td>{:?}</td><td><code>{}</code></td><td>{}</td></tr>",
+                Self::escape_html(&drift.doc_file), drift.line, drift.kind, Self::escape_html(&drift.reference), Self::escape_html(&drift.detail)));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_ownership_html(&self, ownership: &[OwnerSummary], bus_factor: &[ModuleBusFactor]) -> String {
+        let mut html = if ownership.is_empty() {
+            "<p>No CODEOWNERS file found; ownership was not attributed.</p>".to_string()
+        } else {
+            let mut html = String::from("<table><tr><th>Owner</th><th>Files</th><th>LOC</th><th>Action Items</th></tr>");
+            for owner in ownership {
+                let items = if owner.recommendations.is_empty() {
+                    "None".to_string()
+                } else {
+                    format!("<ul>{}</ul>", owner.recommendations.iter().map(|r| format!("<li>{}</li>", r)).collect::<String>())
+                };
+                html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    owner.owner, owner.files.len(), owner.lines_of_code, items));
+            }
+            html.push_str("</table>");
+            html
+        };
+        html.push_str(&self.generate_bus_factor_html(bus_factor));
+        html
+    }
+
+    fn generate_bus_factor_html(&self, bus_factor: &[ModuleBusFactor]) -> String {
+        if bus_factor.is_empty() {
+            return String::new();
+        }
+
+        let single_owner = bus_factor.iter().filter(|module| module.bus_factor == 1).count();
+        let mut html = format!("<h3>Bus Factor</h3><p>{} of {} modules have a bus factor of 1: one person made more than half of their commits. \
+            They are listed first, most complex first, as knowledge-risk hotspots.</p>", single_owner, bus_factor.len());
+        html.push_str("<table class=\"sortable\"><tr><th>Module</th><th>Bus Factor</th><th>Authors</th><th>Top Author</th><th>Top Share</th><th>Commits</th><th>Complexity</th><th>Risk</th></tr>");
+        for module in bus_factor {
+            html.push_str(&format!("<tr><td>{}{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.0}%</td><td>{}</td><td>{}</td><td>{:.1}</td></tr>",
+                Self::escape_html(&module.module), if module.bus_factor == 1 { " ⚠️" } else { "" }, module.bus_factor, module.authors,
+                Self::escape_html(&module.top_author), module.top_author_share, module.commits, module.complexity, module.knowledge_risk));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_change_coupling_html(&self, coupling: &ChangeCouplingAnalysis) -> String {
+        if coupling.is_empty() {
+            return "<p>No hidden change coupling found.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} file pairs changed together in the last {} commits without either importing the other ({} more are explained by an import; \
+            {} commits touching too many files were skipped). Cross-module pairs (🔀) point at implicit contracts between modules.</p>",
+            coupling.pairs.len(), coupling.commits, coupling.explained_pairs, coupling.skipped_commits);
+        html.push_str("<table class=\"sortable\"><tr><th>File</th><th>Changes With</th><th>Shared Commits</th><th>Commits</th><th>Coupling</th></tr>");
+        for pair in &coupling.pairs {
+            html.push_str(&format!("<tr><td>{}{}</td><td>{}</td><td>{}</td><td>{} / {}</td><td>{:.0}%</td></tr>",
+                Self::escape_html(&pair.file_a.display().to_string()), if pair.cross_module { " 🔀" } else { "" },
+                Self::escape_html(&pair.file_b.display().to_string()), pair.shared_commits, pair.commits_a, pair.commits_b, pair.degree));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_process_health_html(&self, health: &ProcessHealth) -> String {
+        if health.is_empty() {
+            return "<p>No git history found.</p>".to_string();
+        }
+
+        let mut html = format!("<p>The last {} non-merge commits, {:.1} per week.</p>", health.commits, health.commits_per_week);
+        html.push_str("<table><tr><th>Measure</th><th>Value</th></tr>");
+        html.push_str(&format!("<tr><td>Conventional Commits</td><td>{} ({:.0}%)</td></tr>", health.conventional_commits, health.conventional_share));
+        if !health.commit_types.is_empty() {
+            html.push_str(&format!("<tr><td>Commit types</td><td>{}</td></tr>", health.commit_types.iter()
+                .map(|(kind, count)| format!("{} {}", kind, count))
+                .collect::<Vec<_>>()
+                .join(", ")));
+        }
+        html.push_str(&format!("<tr><td>Subjects over 72 characters</td><td>{}</td></tr>", health.long_subjects));
+        html.push_str(&format!("<tr><td>Lines changed per commit</td><td>{:.0} average, {} median</td></tr>", health.average_lines_changed, health.median_lines_changed));
+        html.push_str(&format!("<tr><td>Files changed per commit</td><td>{:.1} average</td></tr>", health.average_files_changed));
+        html.push_str(&format!("<tr><td>Large commits</td><td>{}</td></tr>", health.large_commits));
+        html.push_str(&format!("<tr><td>Unmerged branches</td><td>{} ({} long-lived)</td></tr>", health.unmerged_branches, health.long_lived_branches.len()));
+        html.push_str("</table>");
+
+        if !health.nonconforming_examples.is_empty() {
+            html.push_str("<p>Recent subjects not following Conventional Commits:</p><ul>");
+            for subject in &health.nonconforming_examples {
+                html.push_str(&format!("<li><code>{}</code></li>", Self::escape_html(subject)));
+            }
+            html.push_str("</ul>");
+        }
+        if !health.long_lived_branches.is_empty() {
+            html.push_str("<table><tr><th>Long-lived Branch</th><th>Age (days)</th><th>Idle (days)</th><th>Commits Ahead</th></tr>");
+            for branch in &health.long_lived_branches {
+                html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    Self::escape_html(&branch.name), branch.age_days, branch.idle_days, branch.ahead));
+            }
+            html.push_str("</table>");
+        }
+        html
+    }
+
+    fn generate_registry_html(&self, check: &RegistryCheck) -> String {
+        if check.is_empty() {
+            return "<p>Registries were not checked; run with <code>--check-registry</code> to compare declared versions with crates.io, npm and PyPI.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} of {} declared dependencies are behind the latest release or yanked.</p>", check.outdated.len(), check.checked);
+        if !check.outdated.is_empty() {
+            html.push_str("<table class=\"sortable\"><tr><th>Dependency</th><th>Registry</th><th>Declared</th><th>Latest</th><th>Behind</th><th>Age (days)</th><th>Manifest</th></tr>");
+            for dependency in &check.outdated {
+                html.push_str(&format!("<tr><td>{}{}{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    Self::escape_html(&dependency.name),
+                    dependency.withdrawn().map(|label| format!(" ⚠️ {}", label)).unwrap_or_default(),
+                    if dependency.dev { " (dev)" } else { "" },
+                    dependency.ecosystem.name(),
+                    Self::escape_html(&dependency.requirement),
+                    Self::escape_html(&dependency.latest),
+                    dependency.distance.name(),
+                    dependency.age_days.map(|days| days.to_string()).unwrap_or_default(),
+                    Self::escape_html(&dependency.manifest.to_string_lossy())));
+            }
+            html.push_str("</table>");
+        }
+        if !check.failures.is_empty() {
+            html.push_str("<p>Lookups that failed:</p><ul>");
+            for failure in &check.failures {
+                html.push_str(&format!("<li>{}</li>", Self::escape_html(failure)));
+            }
+            html.push_str("</ul>");
+        }
+        html
+    }
+
+    fn generate_vulnerabilities_html(&self, scan: &VulnerabilityScan) -> String {
+        if scan.is_empty() {
+            return "<p>Vulnerabilities were not checked; run with <code>--check-vulnerabilities</code> to look declared versions up on OSV.dev.</p>".to_string();
+        }
+        if scan.vulnerabilities.is_empty() {
+            return format!("<p>No published advisories affect the {} declared dependencies checked.</p>", scan.checked);
+        }
+
+        let mut html = format!("<p>{} published advisories affect declared dependencies ({} checked). Upgrade recommendations are listed under Security.</p>",
+            scan.vulnerabilities.len(), scan.checked);
+        html.push_str("<table class=\"sortable\"><tr><th>Severity</th><th>Advisory</th><th>Dependency</th><th>Fixed in</th><th>Summary</th><th>Imported by</th></tr>");
+        for vulnerability in &scan.vulnerabilities {
+            // Only web links are rendered; anything else (e.g. `javascript:`) stays plain text
+            let advisory = if vulnerability.url.starts_with("http://") || vulnerability.url.starts_with("https://") {
+                format!("<a href=\"{}\">{}</a>", Self::escape_attr(&vulnerability.url), Self::escape_html(&vulnerability.id))
+            } else {
+                Self::escape_html(&vulnerability.id)
+            };
+            html.push_str(&format!("<tr><td>{}</td><td>{}{}</td><td>{} {} ({})<br>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                Self::escape_html(&vulnerability.severity),
+                advisory,
+                if vulnerability.aliases.is_empty() { String::new() } else { format!("<br>{}", Self::escape_html(&vulnerability.aliases.join(", "))) },
+                Self::escape_html(&vulnerability.package),
+                Self::escape_html(&vulnerability.version),
+                vulnerability.ecosystem.name(),
+                Self::escape_html(&vulnerability.manifest.to_string_lossy()),
+                vulnerability.fixed.as_deref().map(Self::escape_html).unwrap_or_else(|| "—".to_string()),
+                Self::escape_html(&vulnerability.summary),
+                Self::file_links_html(&vulnerability.affected_files, "<br>")));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_cycles_html(&self, cycles: &[CircularDependency]) -> String {
+        if cycles.is_empty() {
+            return "<p>No dependency cycles between files.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} strongly connected components; the largest spans {} files.</p>",
+            cycles.len(), cycles[0].files.len());
+        html.push_str("<table><tr><th>Severity</th><th>Files</th><th>Members</th></tr>");
+        for cycle in cycles {
+            html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                cycle.severity, cycle.files.len(), cycle.files.join("<br>")));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_barrels_html(&self, barrels: &[BarrelFile]) -> String {
+        if barrels.is_empty() {
+            return "<p>No files that only re-export other files.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} files only re-export other files' symbols. Imports through them are attributed to the defining files; \
+            a barrel with many importers hides how widely the files behind it are used.</p>", barrels.len());
+        html.push_str("<table class=\"sortable\"><tr><th>File</th><th>Re-exports</th><th>Importers</th><th>Forwards To</th></tr>");
+        for barrel in barrels {
+            let forwards_to: Vec<String> = barrel.forwards_to.iter().map(|path| path.to_string_lossy().to_string()).collect();
+            html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                Self::file_links_html(&[barrel.path.to_string_lossy().to_string()], ""),
+                barrel.re_exports, barrel.importers, Self::file_links_html(&forwards_to, "<br>")));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_architecture_html(&self, violations: &[ArchitectureViolation], lazy_boundaries: &[LazyBoundary]) -> String {
+        let mut html = if violations.is_empty() {
+            "<p>No violations of the declared architecture (or no <code>[architecture]</code> layers configured).</p>".to_string()
+        } else {
+            let mut html = format!("<p>{} imports break the declared layer model.</p>", violations.len());
+            html.push_str("<table><tr><th>From Layer</th><th>To Layer</th><th>Location</th><th>Imports</th></tr>");
+            for v in violations {
+                html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}:{}</td><td>{}</td></tr>",
+                    v.from_layer, v.to_layer, v.from_file, v.line, v.to_file));
+            }
+            html.push_str("</table>");
+            html
+        };
+
+        if !lazy_boundaries.is_empty() {
+            html.push_str(&format!("<h3>Lazy Loading</h3><p>{} dynamic imports load code on demand across these boundaries.</p>",
+                lazy_boundaries.iter().map(|boundary| boundary.imports).sum::<usize>()));
+            html.push_str("<table class=\"sortable\"><tr><th>From</th><th>To</th><th>Imports</th><th>Loaded Files</th></tr>");
+            for boundary in lazy_boundaries {
+                html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    Self::escape_html(&boundary.from_module), Self::escape_html(&boundary.to_module), boundary.imports,
+                    boundary.loaded_files.iter().map(|file| Self::escape_html(file)).collect::<Vec<_>>().join("<br>")));
+            }
+            html.push_str("</table>");
+        }
+        html
+    }
+
+    fn generate_boundaries_html(&self, violations: &[BoundaryViolation]) -> String {
+        if violations.is_empty() {
+            return "<p>No imports bypass a workspace package's entry point (or no workspace was detected).</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} imports reach into another workspace package instead of going through its entry point.</p>", violations.len());
+        html.push_str("<table class=\"sortable\"><tr><th>Severity</th><th>From Package</th><th>To Package</th><th>Location</th><th>Imports</th><th>Problem</th></tr>");
+        for v in violations {
+            html.push_str(&format!("<tr><td>{:?}</td><td>{}</td><td>{}</td><td>{}:{}</td><td>{}</td><td>{}</td></tr>",
+                v.severity, Self::escape_html(&v.from_package), Self::escape_html(&v.to_package),
+                v.from_file, v.line, v.to_file, Self::escape_html(&v.reason)));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_rule_findings_html(&self, findings: &[RuleFinding]) -> String {
+        if findings.is_empty() {
+            return "<p>No custom rule findings.</p>".to_string();
+        }
+
+        let mut html = String::from("<table><tr><th>Severity</th><th>Rule</th><th>Location</th><th>Evidence</th></tr>");
+        for finding in findings {
+            html.push_str(&format!("<tr><td>{:?}</td><td title=\"{}\">{}</td><td>{}:{}</td><td><code>{}</code></td></tr>",
+                finding.severity, Self::escape_attr(&finding.description), Self::escape_html(&finding.rule_id),
+                Self::escape_html(&finding.file), finding.line, Self::escape_html(&finding.evidence)));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_code_smells_html(&self, smells: &[CodeSmell]) -> String {
+        if smells.is_empty() {
+            return "<p>No functions or classes exceed the configured <code>[analysis.smells]</code> thresholds.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} smells found, furthest over threshold first.</p>", smells.len());
+        html.push_str("<table><tr><th>Smell</th><th>Symbol</th><th>Location</th><th>Measured</th><th>Limit</th></tr>");
+        for smell in smells {
+            html.push_str(&format!("<tr><td>{}</td><td><code>{}</code></td><td>{}:{}</td><td>{} {}</td><td>{}</td></tr>",
+                smell.kind.name(), Self::escape_html(&smell.symbol), Self::escape_html(&smell.file), smell.line,
+                smell.value, smell.kind.unit(), smell.threshold));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_class_cohesion_html(&self, classes: &[ClassCohesion]) -> String {
+        if classes.is_empty() {
+            return "<p>No class has methods that split into groups sharing no fields.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} classes whose methods form unrelated groups (LCOM4 above 1); each group could become its own class.</p>", classes.len());
+        html.push_str("<table><tr><th>Class</th><th>Location</th><th>LCOM4</th><th>Methods</th><th>Fields</th><th>Method Groups</th></tr>");
+        for class in classes {
+            let groups = class.groups.iter()
+                .map(|group| format!("<code>{}</code> using {}",
+                    Self::escape_html(&group.methods.join(", ")),
+                    if group.fields.is_empty() { "no fields".to_string() } else { Self::escape_html(&group.fields.join(", ")) }))
+                .collect::<Vec<_>>()
+                .join("<br>");
+            html.push_str(&format!("<tr><td><code>{}</code></td><td>{}:{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                Self::escape_html(&class.class), Self::escape_html(&class.file), class.line, class.lcom, class.methods, class.fields, groups));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_performance_signals_html(&self, signals: &[PerformanceSignal]) -> String {
+        if signals.is_empty() {
+            return "<p>No query loops, blocking IO in async functions, nested loops or large files found.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} signals found by local heuristics; each is a lead to verify, not a confirmed problem.</p>", signals.len());
+        html.push_str("<table><tr><th>Signal</th><th>Symbol</th><th>Location</th><th>Detail</th></tr>");
+        for signal in signals {
+            html.push_str(&format!("<tr><td>{}</td><td><code>{}</code></td><td>{}:{}</td><td>{}</td></tr>",
+                signal.kind.name(), Self::escape_html(&signal.symbol), signal.file, signal.line, Self::escape_html(&signal.detail)));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_testing_html(&self, testing: &TestingSummary) -> String {
+        if testing.test_files.is_empty() {
+            return format!("<p>No test files found; none of {} source files has a matching test.</p>", testing.source_files);
+        }
+
+        let mut html = format!("<p>{} test files with {} test cases and {} assertions ({:.1} per test). {} of {} source files are matched to a test by name.</p>",
+            testing.test_files.len(), testing.test_cases, testing.assertions, testing.assertion_density,
+            testing.tested_sources, testing.source_files);
+        if !testing.frameworks.is_empty() {
+            html.push_str(&format!("<p><strong>Frameworks:</strong> {}</p>", testing.frameworks.iter()
+                .map(|(framework, files)| format!("{} ({} files)", framework, files))
+                .collect::<Vec<_>>()
+                .join(", ")));
+        }
+        if !testing.untested.is_empty() {
+            html.push_str("<h3>Untested Source Files</h3><table><tr><th>File</th><th>Lines of Code</th><th>Complexity</th></tr>");
+            for source in testing.untested.iter().take(20) {
+                html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    Self::escape_html(&source.path.to_string_lossy()), source.lines_of_code, source.complexity));
+            }
+            html.push_str("</table>");
+        }
+        html
+    }
+
+    fn generate_debt_markers_html(&self, debt_markers: &[DebtItem]) -> String {
+        if debt_markers.is_empty() {
+            return "<p>No TODO/FIXME/HACK/XXX comments found.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} markers found, oldest first.</p>", debt_markers.len());
+        html.push_str("<table><tr><th>Kind</th><th>Location</th><th>Age</th><th>Author</th><th>Comment</th></tr>");
+        for item in debt_markers {
+            html.push_str(&format!("<tr><td>{}</td><td>{}:{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                item.kind, Self::escape_html(&item.file), item.line,
+                item.age_days.map(|d| format!("{} days", d)).unwrap_or_else(|| "unknown".to_string()),
+                Self::escape_html(item.author.as_deref().unwrap_or("unknown")),
+                Self::escape_html(&item.text)));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_docker_html(&self, images: &[DockerImage]) -> String {
+        if images.is_empty() {
+            return "<p>No Dockerfiles found.</p>".to_string();
+        }
+
+        let mut html = String::from("<table><tr><th>Dockerfile</th><th>Base Images</th><th>Copied Paths</th><th>Exposed Ports</th><th>Shipped Files</th><th>Not Copied</th></tr>");
+        for image in images {
+            html.push_str(&format!("<tr><td>{}</td><td>{}</td><td><code>{}</code></td><td>{}</td><td title=\"{}\">{}</td><td>{}</td></tr>",
+                Self::escape_html(&image.dockerfile.to_string_lossy()),
+                Self::escape_html(&image.base_images.join(", ")),
+                Self::escape_html(&image.copied_paths.join(" ")),
+                Self::escape_html(&image.exposed_ports.join(", ")),
+                Self::escape_attr(&image.shipped_files.iter().map(|f| f.display().to_string()).collect::<Vec<_>>().join("\n")),
+                image.shipped_files.len(),
+                image.unshipped_files));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn generate_infrastructure_html(&self, infrastructure: &InfrastructureInventory) -> String {
+        if infrastructure.is_empty() {
+            return "<p>No Terraform, CloudFormation or Kubernetes files found.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} resources, {} module references and {} dependencies between resources. Providers: {}.</p>",
+            infrastructure.resources.len(), infrastructure.modules.len(), infrastructure.dependencies.len(),
+            infrastructure.providers.keys().cloned().collect::<Vec<_>>().join(", "));
+        if !infrastructure.risks.is_empty() {
+            html.push_str("<h3>Risky Settings</h3><table><tr><th>Location</th><th>Rule</th><th>Detail</th></tr>");
+            for risk in &infrastructure.risks {
+                html.push_str(&format!("<tr><td>{}:{}</td><td>{}</td><td>{}</td></tr>",
+                    Self::escape_html(&risk.file.to_string_lossy()), risk.line, risk.rule, Self::escape_html(&risk.detail)));
+            }
+            html.push_str("</table>");
+        }
+        html.push_str("<h3>Resources</h3><table><tr><th>Format</th><th>Kind</th><th>Count</th></tr>");
+        for (format, kind, count) in infrastructure.kinds() {
+            html.push_str(&format!("<tr><td>{}</td><td><code>{}</code></td><td>{}</td></tr>", format.name(), Self::escape_html(kind), count));
+        }
+        html.push_str("</table>");
+        if !infrastructure.modules.is_empty() {
+            html.push_str("<h3>Modules</h3><table><tr><th>Module</th><th>Source</th><th>Location</th></tr>");
+            for module in &infrastructure.modules {
+                html.push_str(&format!("<tr><td>{}</td><td><code>{}</code></td><td>{}:{}</td></tr>",
+                    Self::escape_html(&module.name), Self::escape_html(&module.source), module.file.display(), module.line));
+            }
+            html.push_str("</table>");
+        }
+        if !infrastructure.dependencies.is_empty() {
+            html.push_str("<h3>Dependencies</h3><table><tr><th>Resource</th><th>Depends On</th><th>File</th></tr>");
+            for dependency in &infrastructure.dependencies {
+                html.push_str(&format!("<tr><td><code>{}</code></td><td><code>{}</code></td><td>{}</td></tr>",
+                    Self::escape_html(&dependency.from), Self::escape_html(&dependency.to), Self::escape_html(&dependency.file.to_string_lossy())));
+            }
+            html.push_str("</table>");
+        }
+        html
+    }
+
+    fn generate_assets_html(&self, assets: &AssetInventory) -> String {
+        if assets.is_empty() {
+            return "<p>No images, media, models, archives or other binary files found.</p>".to_string();
+        }
+
+        let mut html = format!("<p>{} asset file(s) totalling {}, {:.0}% of the repository's {}.</p>",
+            assets.asset_files(), AssetInventory::format_size(assets.asset_bytes), assets.asset_share() * 100.0,
+            AssetInventory::format_size(assets.total_bytes));
+        if assets.asset_dominated {
+            html.push_str("<p><strong>⚠️ Non-code assets make up most of the repository's size.</strong> Consider Git LFS or an artifact store for them.</p>");
+        }
+        html.push_str("<table><tr><th>Kind</th><th>Files</th><th>Size</th></tr>");
+        for kind in &assets.kinds {
+            html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>", kind.kind.name(), kind.files, AssetInventory::format_size(kind.bytes)));
+        }
+        html.push_str("</table>");
+        if !assets.large_assets.is_empty() {
+            html.push_str("<h3>Large Assets</h3><table><tr><th>File</th><th>Kind</th><th>Size</th></tr>");
+            for asset in &assets.large_assets {
+                html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    Self::escape_html(&asset.path.display().to_string()), asset.kind.name(), AssetInventory::format_size(asset.size)));
+            }
+            html.push_str("</table>");
+        }
+        html
+    }
+
+    fn generate_exclusions_html(&self, exclusions: &[ExclusionGroup]) -> String {
+        if exclusions.is_empty() {
+            return "<p>No files were excluded by ignore patterns, size, extension or .gitattributes.</p>".to_string();
+        }
+
+        let total: usize = exclusions.iter().map(|group| group.files.len()).sum();
+        let mut html = format!("<p>{} files excluded. Files matched by .gitignore are skipped during the walk and not listed.</p>", total);
+        html.push_str("<table><tr><th>Reason</th><th>Rule</th><th>Files</th><th>Examples</th></tr>");
+        for group in exclusions {
+            html.push_str(&format!("<tr><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+                group.reason.name(), Self::escape_html(&group.rule), group.files.len(),
+                group.files.iter().take(3).map(|f| Self::escape_html(f)).collect::<Vec<_>>().join("<br>")));
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    /// Appendix listing files that failed to parse or were parsed with reduced
+    /// fidelity; empty when every file parsed in full
+    fn generate_parse_errors_html(&self, parse_errors: &[ParseError]) -> String {
+        if parse_errors.is_empty() {
+            return String::new();
+        }
+
+        let mut html = String::from("\n    <div class=\"section\">\n        <h2>Appendix: Parse Errors</h2>\n");
+        html.push_str(&format!("        <p>{}</p>\n", Self::parse_errors_summary(parse_errors)));
+        html.push_str("        <table><tr><th>File</th><th>Language</th><th>Kind</th><th>Error</th></tr>");
+        for error in parse_errors {
+            html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                Self::escape_html(&error.path.to_string_lossy()), error.language.as_deref().unwrap_or("unknown"),
+                error.kind.name(), Self::escape_html(&error.message)));
+        }
+        html.push_str("</table>\n    </div>\n");
+        html
+    }
+
+    fn parse_errors_summary(parse_errors: &[ParseError]) -> String {
+        let degraded = parse_errors.iter().filter(|error| error.degraded).count();
+        let failed = parse_errors.len() - degraded;
+        match (failed, degraded) {
+            (_, 0) => format!("{} files could not be parsed and are missing from the analysis.", failed),
+            (0, _) => format!("{} files were parsed with reduced fidelity: decoded from another encoding, or counted without parsing because of very long lines.", degraded),
+            _ => format!("{} files could not be parsed and are missing from the analysis; {} more were parsed with reduced fidelity.", failed, degraded),
+        }
+    }
+
+    /// Every recommendation grouped by category, most urgent first within each
+    fn generate_recommendation_categories_html(&self, recommendations: &[PrioritizedRecommendation]) -> String {
+        if recommendations.is_empty() {
+            return String::new();
+        }
+
+        let mut html = String::from("<h3>By Category</h3>");
+        for (category, recs) in Self::recommendations_by_category(recommendations) {
+            html.push_str(&format!("<h4>{} ({})</h4><ul>", category.name(), recs.len()));
+            for rec in recs {
+                html.push_str(&format!("<li><strong>{}</strong> ({:?})</li>", Self::escape_html(&rec.title), rec.priority));
+            }
+            html.push_str("</ul>");
+        }
+        html
+    }
+
+    fn recommendations_by_category(recommendations: &[PrioritizedRecommendation]) -> BTreeMap<RecommendationCategory, Vec<&PrioritizedRecommendation>> {
+        let mut by_category: BTreeMap<RecommendationCategory, Vec<&PrioritizedRecommendation>> = BTreeMap::new();
+        for rec in recommendations {
+            by_category.entry(rec.category).or_default().push(rec);
+        }
+        by_category
+    }
+
+    /// Appendix listing LLM insights discarded by the guardrails
+    fn generate_discarded_llm_output_html(&self, discarded: &[DiscardedItem]) -> String {
+        if discarded.is_empty() {
+            return String::new();
+        }
+
+        let mut html = String::from("\n    <div class=\"section\">\n        <h2>Appendix: Discarded LLM Output</h2>\n");
+        html.push_str(&format!("        <p>{} LLM insight(s) were left out of the report because they named files, functions or languages the project doesn't have, or the review pass found them unsupported.</p>\n", discarded.len()));
+        html.push_str("        <table><tr><th>Analysis</th><th>Insight</th><th>Reasons</th></tr>");
+        for item in discarded {
+            html.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                item.analysis_type.as_ref().map(|t| t.name()).unwrap_or("unknown"),
+                Self::escape_html(&item.title),
+                item.reasons.iter().map(|r| Self::escape_html(r)).collect::<Vec<_>>().join("<br>")));
+        }
+        html.push_str("</table>\n    </div>\n");
+        html
+    }
+
+    /// Header LLM line, noting when the run was offline
+    fn html_llm_label(metadata: &ReportMetadata) -> String {
+        let llm = if metadata.llm_enabled {
+            format!("{} ({})", metadata.llm_model, metadata.llm_provider)
+        } else {
+            "Disabled (local-only analysis)".to_string()
+        };
+        if metadata.offline {
+            format!("{} · Offline, no network calls", llm)
+        } else {
+            llm
+        }
+    }
+
+    /// Header scope line, noting the revision analyzed and when only a sample of
+    /// the project was
+    fn scope_label(metadata: &ReportMetadata) -> String {
+        let scope = if metadata.scope.is_empty() { "Whole project".to_string() } else { metadata.scope.join(", ") };
+        let scope = match metadata.revision {
+            Some(ref revision) => format!("{} at revision {}", scope, revision),
+            None => scope,
+        };
+        match metadata.sampling {
+            Some(ref sampling) => format!("{} (sampled: {})", scope, sampling),
+            None => scope,
+        }
+    }
+
+    /// `file://` links to the given files, labelled with the paths as analyzed
+    fn file_links_html(files: &[String], separator: &str) -> String {
+        files.iter().map(|file| {
+            format!(r#"<a href="{}"><code>{}</code></a>"#, Self::file_url(file), Self::escape_html(file))
+        }).collect::<Vec<_>>().join(separator)
+    }
+
+    fn file_url(file: &str) -> String {
+        let target = Path::new(file).canonicalize()
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|_| file.to_string());
+        format!("file://{}", Self::escape_html(&target).replace(' ', "%20").replace('"', "%22"))
+    }
+
+    /// Evidence as a quote linked to its file and line; quotes that could not be found
+    /// in the named file are marked
+    fn evidence_html(evidence: &Evidence) -> String {
+        let Some(file) = &evidence.file else {
+            return format!("<p class=\"evidence\">• {}</p>", Self::escape_html(&evidence.snippet));
+        };
+        let location = match evidence.line {
+            Some(line) => format!("{}:{}", file, line),
+            None => file.clone(),
+        };
+        let source = match evidence.status {
+            EvidenceStatus::UnknownFile => format!("<code>{}</code> <span class=\"evidence-flag\">⚠️ file not in the project</span>", Self::escape_html(&location)),
+            status => {
+                let anchor = evidence.line.map(|line| format!("#L{}", line)).unwrap_or_default();
+                let flag = if status == EvidenceStatus::SnippetNotFound {
+                    " <span class=\"evidence-flag\">⚠️ quote not found in file</span>"
+                } else {
+                    ""
+                };
+                format!(r#"<a href="{}{}"><code>{}</code></a>{}"#, Self::file_url(file), anchor, Self::escape_html(&location), flag)
+            }
+        };
+        let quote = if evidence.snippet.is_empty() {
+            String::new()
+        } else {
+            format!("<pre><code>{}</code></pre>", Self::escape_html(&evidence.snippet))
+        };
+        format!(r#"<blockquote class="evidence-quote{}">{}{}</blockquote>"#,
+            if evidence.status.is_flagged() { " evidence-flagged" } else { "" }, source, quote)
+    }
+
+    /// LLM-written Markdown as HTML. Raw HTML in the text is escaped rather than passed through.
+    fn markdown_html(text: &str) -> String {
+        use pulldown_cmark::{html, Event, Options, Parser};
+        let parser = Parser::new_ext(text, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH)
+            .map(|event| match event {
+                Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+                event => event,
+            });
+        let mut rendered = String::new();
+        html::push_html(&mut rendered, parser);
+        rendered
+    }
+
+    pub(crate) fn escape_html(text: &str) -> String {
+        text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+    }
+
+    /// Like `escape_html`, but also safe inside a quoted attribute value
+    pub(crate) fn escape_attr(text: &str) -> String {
+        Self::escape_html(text).replace('"', "&quot;").replace('\'', "&#39;")
+    }
+
+    fn extract_analysis_text(&self, content: &str) -> String {
+        // First try to parse as JSON and extract the analysis field
+        if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(content) {
+            if let Some(analysis_text) = json_value.get("analysis").and_then(|v| v.as_str()) {
                 return analysis_text.to_string();
             }
         }
@@ -996,32 +2459,805 @@ impl Reporter {
         Vec::new()
     }
 
-    fn generate_markdown_summary(&self, report: &Report) -> Result<String> {
+    /// The Markdown summary, or with `full` the complete report: every recommendation and
+    /// LLM insight with its evidence, dependency findings and untruncated tables
+    /// The Markdown summary, or with `full` the complete Markdown report
+    pub fn generate_markdown(&self, report: &Report, full: bool) -> Result<String> {
         let mut md = format!(
-            "# Project Analysis Summary\n\n**Project:** {}\n**Generated:** {}\n**Analysis Duration:** {}ms\n\n",
+            "# Project Analysis {}\n\n**Project:** {}\n**Generated:** {}\n**Analysis Duration:** {}ms\n**LLM:** {}\n\n",
+            if full { "Report" } else { "Summary" },
             report.metadata.project_name,
             report.metadata.generated_at,
-            report.metadata.analysis_duration_ms
+            report.metadata.analysis_duration_ms,
+            if report.metadata.llm_enabled {
+                format!("{} ({})", report.metadata.llm_model, report.metadata.llm_provider)
+            } else {
+                "disabled (local-only analysis)".to_string()
+            }
         );
 
+        if !report.metadata.scope.is_empty() {
+            md.push_str(&format!("**Scope:** {}\n\n", report.metadata.scope.join(", ")));
+        }
+        if let Some(ref sampling) = report.metadata.sampling {
+            md.push_str(&format!("**Sampled:** {} (set `analysis.max_files = 0` to analyze everything)\n\n", sampling));
+        }
+        if report.metadata.offline {
+            md.push_str("**Network:** offline, no data left the machine\n\n");
+        }
+        if let Some(ref revision) = report.metadata.revision {
+            md.push_str(&format!("**Revision:** `{}`\n\n", revision));
+        }
+
         md.push_str("## Executive Summary\n\n");
+        if full && !report.executive_summary.overview.is_empty() {
+            md.push_str(&format!("{}\n\n", report.executive_summary.overview));
+        }
         md.push_str(&format!("- **Complexity Score:** {:.2}/10\n", report.executive_summary.complexity_score));
         md.push_str(&format!("- **Maintainability Score:** {:.2}/10\n", report.executive_summary.maintainability_score));
+        md.push_str(&format!("- **Documentation Coverage:** {:.1}%\n", report.executive_summary.documentation_coverage));
         md.push_str(&format!("- **Total Files:** {}\n", report.metadata.total_files));
-        md.push_str(&format!("- **Total Size:** {:.2} MB\n\n", report.metadata.total_size as f64 / (1024.0 * 1024.0)));
+        md.push_str(&format!("- **Total Size:** {:.2} MB\n", report.metadata.total_size as f64 / (1024.0 * 1024.0)));
+        if !report.parse_errors.is_empty() {
+            md.push_str(&format!("- **Parse Errors:** {} (see appendix)\n", report.parse_errors.len()));
+        }
+        md.push('\n');
+
+        md.push_str("### Score Breakdown\n\n");
+        md.push_str("| Factor | Score | Raw Value | Weight | Contribution |\n");
+        md.push_str("|--------|-------|-----------|--------|--------------|\n");
+        for factor in &report.executive_summary.score_breakdown {
+            md.push_str(&format!("| {} | {:?} | {:.2} | {:.2} | {:+.2} |\n",
+                factor.name, factor.score, factor.raw_value, factor.weight, factor.contribution));
+        }
+        md.push('\n');
+
+        if let Some(ref guide) = report.onboarding {
+            md.push_str("## Onboarding Guide\n\n### Where to Start Reading\n\n");
+            for (i, step) in guide.reading_order.iter().enumerate() {
+                md.push_str(&format!("{}. `{}` — {}\n", i + 1, step.file.display(), step.reason));
+            }
+            if !guide.main_flows.is_empty() {
+                md.push_str("\n### Main Flows\n\n");
+                for flow in &guide.main_flows {
+                    md.push_str(&format!("- `{}` → {}\n", flow.entry_point.display(),
+                        flow.files.iter().map(|p| format!("`{}`", p.display())).collect::<Vec<_>>().join(" → ")));
+                }
+            }
+            if !guide.glossary.is_empty() {
+                md.push_str("\n### Glossary\n\n");
+                for term in &guide.glossary {
+                    md.push_str(&format!("- **{}** ({} identifiers): {}\n", term.term, term.occurrences, term.examples.join(", ")));
+                }
+            }
+            md.push('\n');
+        }
+
+        md.push_str(if full { "## Recommendations\n\n" } else { "## Top Recommendations\n\n" });
+        for (i, rec) in report.recommendations.iter().take(if full { usize::MAX } else { 5 }).enumerate() {
+            md.push_str(&format!("{}. **{}** (Priority: {:?}, Category: {})\n   {}\n", 
+                i + 1, rec.title, rec.priority, rec.category.name(), rec.description));
+            if !rec.affected_files.is_empty() {
+                md.push_str(&format!("   Files: {}\n", Self::file_links_markdown(&rec.affected_files)));
+            }
+            if full {
+                md.push_str(&format!("   Effort: {}, Impact: {}\n", rec.estimated_effort, rec.potential_impact));
+                for item in &rec.action_items {
+                    md.push_str(&format!("   - [ ] {}\n", item));
+                }
+            }
+            md.push('\n');
+        }
 
-        md.push_str("## Top Recommendations\n\n");
-        for (i, rec) in report.recommendations.iter().take(5).enumerate() {
-            md.push_str(&format!("{}. **{}** (Priority: {:?})\n   {}\n\n", 
-                i + 1, rec.title, rec.priority, rec.description));
+        if !report.recommendations.is_empty() {
+            md.push_str("## Recommendations by Category\n");
+            for (category, recs) in Self::recommendations_by_category(&report.recommendations) {
+                md.push_str(&format!("\n### {} ({})\n\n", category.name(), recs.len()));
+                for rec in recs {
+                    md.push_str(&format!("- **{}** ({:?})\n", rec.title, rec.priority));
+                }
+            }
+            md.push('\n');
         }
 
         md.push_str("## Language Distribution\n\n");
-        for lang in &report.file_analysis.language_breakdown {
-            md.push_str(&format!("- **{}:** {} files ({:.1}%), {:.2} MB\n", 
-                lang.language, lang.file_count, lang.percentage, lang.total_size as f64 / (1024.0 * 1024.0)));
+        if full {
+            md.push_str("| Language | Files | Size (MB) | Average File Size (KB) | Percentage |\n");
+            md.push_str("|----------|-------|-----------|------------------------|------------|\n");
+            for lang in &report.file_analysis.language_breakdown {
+                md.push_str(&format!("| {} | {} | {:.2} | {:.1} | {:.1}% |\n",
+                    lang.language, lang.file_count, lang.total_size as f64 / (1024.0 * 1024.0), lang.avg_file_size / 1024.0, lang.percentage));
+            }
+        } else {
+            for lang in &report.file_analysis.language_breakdown {
+                md.push_str(&format!("- **{}:** {} files ({:.1}%), {:.2} MB\n", 
+                    lang.language, lang.file_count, lang.percentage, lang.total_size as f64 / (1024.0 * 1024.0)));
+            }
+        }
+
+        if full {
+            self.push_markdown_file_details(&mut md, report);
+        }
+
+        // The full report lists every file; the summary only those needing attention
+        let flagged: Vec<_> = report.maintainability.iter().filter(|f| full || f.rating != MaintainabilityRating::Green).collect();
+        if !flagged.is_empty() {
+            md.push_str("\n## Maintainability\n\n");
+            md.push_str("| File | LOC | Cyclomatic | Comment Ratio | Index | Rating |\n");
+            md.push_str("|------|-----|------------|---------------|-------|--------|\n");
+            for file in flagged.iter().take(if full { usize::MAX } else { 20 }) {
+                md.push_str(&format!("| {} | {} | {} | {:.2} | {:.1} | {} |\n",
+                    file.path.display(), file.lines_of_code, file.cyclomatic_complexity, file.comment_ratio, file.index, file.rating.name()));
+            }
+        }
+
+        if report.module_analysis.modules.iter().any(|m| m.summary.is_some()) {
+            md.push_str("\n## Module Guide\n");
+            for m in report.module_analysis.modules.iter().filter(|m| m.summary.is_some()) {
+                md.push_str(&format!("\n### {}\n\n{}\n", m.module, m.summary.as_deref().unwrap_or_default()));
+            }
+        }
+
+        if !report.module_analysis.modules.is_empty() {
+            md.push_str("\n## Modules\n\n");
+            md.push_str("| Module | Files | LOC | Complexity | Ca | Ce | Instability | Abstractness | Distance |\n");
+            md.push_str("|--------|-------|-----|------------|----|----|-------------|--------------|----------|\n");
+            for m in &report.module_analysis.modules {
+                md.push_str(&format!("| {} | {} | {} | {} | {} | {} | {:.2} | {:.2} | {:.2}{} |\n",
+                    m.module, m.files, m.lines_of_code, m.complexity,
+                    m.afferent_coupling, m.efferent_coupling, m.instability, m.abstractness,
+                    m.distance, m.zone.map(|zone| format!(" ({})", zone.name())).unwrap_or_default()));
+            }
+            let in_pain: Vec<_> = report.module_analysis.modules.iter().filter(|m| m.zone == Some(SequenceZone::Pain)).collect();
+            if !in_pain.is_empty() {
+                md.push_str("\n**Zone of pain** (concrete and depended upon, so hard to change):\n\n");
+                for m in in_pain {
+                    md.push_str(&format!("- {}: {} dependent module(s), abstractness {:.2}, instability {:.2}\n",
+                        m.module, m.afferent_coupling, m.abstractness, m.instability));
+                }
+            }
+        }
+
+        if !report.components.is_empty() {
+            let components = &report.components;
+            md.push_str(&format!("\n## Components\n\n{} components with {} dependencies between them; {} files match no component.\n\n",
+                components.components.len(), components.dependencies.len(), components.unassigned_files));
+            md.push_str(&format!("```mermaid\n{}```\n\n", components.mermaid()));
+            md.push_str("| Component | Files | LOC | Complexity | Ca | Ce | Instability | Abstractness | Distance | Findings |\n");
+            md.push_str("|-----------|-------|-----|------------|----|----|-------------|--------------|----------|----------|\n");
+            for c in &components.components {
+                md.push_str(&format!("| {} | {} | {} | {} | {} | {} | {:.2} | {:.2} | {:.2}{} | {} |\n",
+                    c.name, c.files, c.lines_of_code, c.complexity,
+                    c.afferent_coupling, c.efferent_coupling, c.instability, c.abstractness,
+                    c.distance, c.zone.map(|zone| format!(" ({})", zone.name())).unwrap_or_default(), c.findings()));
+            }
+        }
+
+        if !report.documentation.modules.is_empty() {
+            md.push_str(&format!("\n## Documentation Coverage\n\n{} of {} public functions and classes are documented.\n\n",
+                report.documentation.documented, report.documentation.total));
+            md.push_str("| Module | Documented | Public Symbols | Coverage |\n");
+            md.push_str("|--------|------------|----------------|----------|\n");
+            for module in &report.documentation.modules {
+                md.push_str(&format!("| {} | {} | {} | {:.1}% |\n",
+                    module.module, module.documented, module.total, module.coverage));
+            }
+        }
+
+        if !report.doc_drift.is_empty() {
+            md.push_str("\n## README/CHANGELOG Drift\n\n");
+            for drift in &report.doc_drift {
+                md.push_str(&format!("- {}:{} `{}` ({:?}): {}\n",
+                    drift.doc_file, drift.line, drift.reference, drift.kind, drift.detail));
+            }
+        }
+
+        if !report.ownership.is_empty() {
+            md.push_str("\n## Ownership\n\n");
+            for owner in &report.ownership {
+                md.push_str(&format!("### {}\n\n- **Files:** {}\n- **Lines of code:** {}\n",
+                    owner.owner, owner.files.len(), owner.lines_of_code));
+                if !owner.recommendations.is_empty() {
+                    md.push_str("- **Action items:**\n");
+                    for rec in &owner.recommendations {
+                        md.push_str(&format!("  - {}\n", rec));
+                    }
+                }
+                md.push('\n');
+            }
+        }
+
+        if !report.bus_factor.is_empty() {
+            md.push_str("\n## Bus Factor\n\nModules where one person made more than half of the commits (⚠️) come first, most complex first.\n\n");
+            md.push_str("| Module | Bus Factor | Authors | Top Author | Top Share | Commits | Complexity | Risk |\n");
+            md.push_str("|--------|------------|---------|------------|-----------|---------|------------|------|\n");
+            for module in &report.bus_factor {
+                md.push_str(&format!("| {}{} | {} | {} | {} | {:.0}% | {} | {} | {:.1} |\n",
+                    module.module, if module.bus_factor == 1 { " ⚠️" } else { "" }, module.bus_factor, module.authors,
+                    module.top_author, module.top_author_share, module.commits, module.complexity, module.knowledge_risk));
+            }
+        }
+
+        if !report.process_health.is_empty() {
+            let health = &report.process_health;
+            md.push_str(&format!("\n## Process Health\n\n- **Commits audited:** {} ({:.1} per week)\n- **Conventional Commits:** {} ({:.0}%)\n",
+                health.commits, health.commits_per_week, health.conventional_commits, health.conventional_share));
+            md.push_str(&format!("- **Lines changed per commit:** {:.0} average, {} median; {} large commit(s)\n- **Subjects over 72 characters:** {}\n",
+                health.average_lines_changed, health.median_lines_changed, health.large_commits, health.long_subjects));
+            md.push_str(&format!("- **Unmerged branches:** {} ({} long-lived)\n", health.unmerged_branches, health.long_lived_branches.len()));
+            for branch in &health.long_lived_branches {
+                md.push_str(&format!("  - `{}`: {} commit(s) ahead, started {} days ago, idle {} days\n",
+                    branch.name, branch.ahead, branch.age_days, branch.idle_days));
+            }
+        }
+
+        if !report.change_coupling.is_empty() {
+            let coupling = &report.change_coupling;
+            md.push_str(&format!("\n## Change Coupling\n\n{} file pairs changed together in the last {} commits without either importing the other; cross-module pairs are marked 🔀.\n\n",
+                coupling.pairs.len(), coupling.commits));
+            md.push_str("| File | Changes With | Shared Commits | Commits | Coupling |\n");
+            md.push_str("|------|--------------|----------------|---------|----------|\n");
+            for pair in &coupling.pairs {
+                md.push_str(&format!("| {}{} | {} | {} | {} / {} | {:.0}% |\n",
+                    pair.file_a.display(), if pair.cross_module { " 🔀" } else { "" },
+                    pair.file_b.display(), pair.shared_commits, pair.commits_a, pair.commits_b, pair.degree));
+            }
+        }
+
+        if !report.registry_check.outdated.is_empty() {
+            md.push_str(&format!("\n## Outdated Dependencies\n\n{} of {} declared dependencies are behind the latest release or yanked.\n\n",
+                report.registry_check.outdated.len(), report.registry_check.checked));
+            md.push_str("| Dependency | Registry | Declared | Latest | Behind | Age (days) | Manifest |\n");
+            md.push_str("|------------|----------|----------|--------|--------|------------|----------|\n");
+            for dependency in &report.registry_check.outdated {
+                md.push_str(&format!("| {}{}{} | {} | `{}` | {} | {} | {} | {} |\n",
+                    dependency.name,
+                    dependency.withdrawn().map(|label| format!(" ⚠️ {}", label)).unwrap_or_default(),
+                    if dependency.dev { " (dev)" } else { "" },
+                    dependency.ecosystem.name(), dependency.requirement, dependency.latest, dependency.distance.name(),
+                    dependency.age_days.map(|days| days.to_string()).unwrap_or_default(),
+                    dependency.manifest.display()));
+            }
+        }
+
+        if !report.vulnerability_scan.vulnerabilities.is_empty() {
+            md.push_str(&format!("\n## Known Vulnerabilities\n\n{} published advisories affect declared dependencies ({} checked).\n\n",
+                report.vulnerability_scan.vulnerabilities.len(), report.vulnerability_scan.checked));
+            md.push_str("| Severity | Advisory | Dependency | Fixed in | Summary | Imported by |\n");
+            md.push_str("|----------|----------|------------|----------|---------|-------------|\n");
+            for vulnerability in &report.vulnerability_scan.vulnerabilities {
+                md.push_str(&format!("| {} | [{}]({}) | {} {} ({}) | {} | {} | {} |\n",
+                    vulnerability.severity, vulnerability.id, vulnerability.url,
+                    vulnerability.package, vulnerability.version, vulnerability.ecosystem.name(),
+                    vulnerability.fixed.as_deref().unwrap_or("—"),
+                    vulnerability.summary.replace('|', "\\|"),
+                    vulnerability.affected_files.join(", ")));
+            }
+        }
+
+        if full {
+            self.push_markdown_dependencies(&mut md, &report.dependency_analysis);
+        }
+
+        if !report.dependency_analysis.circular_dependencies.is_empty() {
+            md.push_str("\n## Dependency Cycles\n\n");
+            for cycle in &report.dependency_analysis.circular_dependencies {
+                md.push_str(&format!("- **{}** ({} files): {}\n", cycle.severity, cycle.files.len(), cycle.files.join(", ")));
+            }
+        }
+
+        let barrels = &report.dependency_analysis.graph_metrics.barrel_files;
+        if !barrels.is_empty() {
+            md.push_str("\n## Barrel Files\n\nFiles that only re-export other files' symbols; imports through them are attributed to the defining files.\n\n");
+            for barrel in barrels {
+                md.push_str(&format!("- `{}`: {} re-exports, {} importers, forwards to {} files\n",
+                    barrel.path.display(), barrel.re_exports, barrel.importers, barrel.forwards_to.len()));
+            }
+        }
+
+        if !report.architecture_violations.is_empty() {
+            md.push_str("\n## Architecture Conformance\n\n");
+            for v in &report.architecture_violations {
+                md.push_str(&format!("- **{} → {}**: {}:{} imports {}\n",
+                    v.from_layer, v.to_layer, v.from_file, v.line, v.to_file));
+            }
+        }
+
+        if !report.lazy_boundaries.is_empty() {
+            md.push_str("\n## Lazy Loading\n\n");
+            for boundary in &report.lazy_boundaries {
+                md.push_str(&format!("- **{} → {}**: {} dynamic imports of {}\n",
+                    boundary.from_module, boundary.to_module, boundary.imports, boundary.loaded_files.join(", ")));
+            }
+        }
+
+        if !report.boundary_violations.is_empty() {
+            md.push_str("\n## Package Boundaries\n\n");
+            for v in &report.boundary_violations {
+                md.push_str(&format!("- **{:?}** {} → {}: {}:{} imports {} ({})\n",
+                    v.severity, v.from_package, v.to_package, v.from_file, v.line, v.to_file, v.reason));
+            }
+        }
+
+        if !report.rule_findings.is_empty() {
+            md.push_str("\n## Rule Findings\n\n");
+            md.push_str("| Severity | Rule | Location | Evidence |\n");
+            md.push_str("|----------|------|----------|----------|\n");
+            for finding in &report.rule_findings {
+                md.push_str(&format!("| {:?} | {} | {}:{} | `{}` |\n",
+                    finding.severity, finding.rule_id, finding.file, finding.line,
+                    finding.evidence.replace('|', "\\|").replace('`', "'")));
+            }
+        }
+
+        if !report.code_smells.is_empty() {
+            md.push_str(&format!("\n## Code Smells\n\n{} smells found, furthest over threshold first.\n\n", report.code_smells.len()));
+            md.push_str("| Smell | Symbol | Location | Measured | Limit |\n");
+            md.push_str("|-------|--------|----------|----------|-------|\n");
+            for smell in &report.code_smells {
+                md.push_str(&format!("| {} | `{}` | {}:{} | {} {} | {} |\n",
+                    smell.kind.name(), smell.symbol, smell.file, smell.line, smell.value, smell.kind.unit(), smell.threshold));
+            }
+        }
+
+        if !report.class_cohesion.is_empty() {
+            md.push_str(&format!("\n## Class Cohesion\n\n{} classes whose methods form unrelated groups (LCOM4 above 1); each group could become its own class.\n\n", report.class_cohesion.len()));
+            for class in &report.class_cohesion {
+                md.push_str(&format!("- `{}` ({}:{}), LCOM4 {}:\n", class.class, class.file, class.line, class.lcom));
+                for group in &class.groups {
+                    md.push_str(&format!("  - {} using {}\n", group.methods.join(", "),
+                        if group.fields.is_empty() { "no fields".to_string() } else { group.fields.join(", ") }));
+                }
+            }
+        }
+
+        if !report.performance_signals.is_empty() {
+            md.push_str(&format!("\n## Performance Signals\n\n{} signals found by local heuristics; each is a lead to verify.\n\n", report.performance_signals.len()));
+            md.push_str("| Signal | Symbol | Location | Detail |\n");
+            md.push_str("|--------|--------|----------|--------|\n");
+            for signal in &report.performance_signals {
+                md.push_str(&format!("| {} | `{}` | {}:{} | {} |\n",
+                    signal.kind.name(), signal.symbol, signal.file, signal.line, signal.detail.replace('|', "\\|")));
+            }
+        }
+
+        if !report.testing.is_empty() {
+            let testing = &report.testing;
+            md.push_str(&format!("\n## Testing\n\n{} test files with {} test cases and {} assertions ({:.1} per test). {} of {} source files are matched to a test by name.\n",
+                testing.test_files.len(), testing.test_cases, testing.assertions, testing.assertion_density,
+                testing.tested_sources, testing.source_files));
+            if !testing.frameworks.is_empty() {
+                md.push_str(&format!("\n**Frameworks:** {}\n", testing.frameworks.iter()
+                    .map(|(framework, files)| format!("{} ({} files)", framework, files))
+                    .collect::<Vec<_>>()
+                    .join(", ")));
+            }
+            if !testing.untested.is_empty() {
+                md.push_str("\n| Untested File | Lines of Code | Complexity |\n|---------------|---------------|------------|\n");
+                for source in testing.untested.iter().take(if full { usize::MAX } else { 20 }) {
+                    md.push_str(&format!("| {} | {} | {} |\n", source.path.display(), source.lines_of_code, source.complexity));
+                }
+            }
+        }
+
+        if !report.debt_markers.is_empty() {
+            md.push_str(&format!("\n## Technical Debt Markers\n\n{} markers found, oldest first.\n\n", report.debt_markers.len()));
+            md.push_str("| Kind | Location | Age (days) | Comment |\n");
+            md.push_str("|------|----------|------------|---------|\n");
+            for item in &report.debt_markers {
+                md.push_str(&format!("| {} | {}:{} | {} | {} |\n",
+                    item.kind, item.file, item.line,
+                    item.age_days.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string()),
+                    item.text.replace('|', "\\|")));
+            }
+        }
+
+        if !report.docker_images.is_empty() {
+            md.push_str("\n## Container Images\n\n");
+            for image in &report.docker_images {
+                md.push_str(&format!("### {}\n\n- **Base images:** {}\n- **Copied paths:** {}\n- **Exposed ports:** {}\n- **Shipped files:** {} ({} analyzed files in the build context are not copied)\n",
+                    image.dockerfile.display(),
+                    if image.base_images.is_empty() { "none".to_string() } else { image.base_images.join(", ") },
+                    if image.copied_paths.is_empty() { "none".to_string() } else { image.copied_paths.iter().map(|p| format!("`{}`", p)).collect::<Vec<_>>().join(", ") },
+                    if image.exposed_ports.is_empty() { "none".to_string() } else { image.exposed_ports.join(", ") },
+                    image.shipped_files.len(), image.unshipped_files));
+                for file in image.shipped_files.iter().take(20) {
+                    md.push_str(&format!("  - {}\n", file.display()));
+                }
+                if image.shipped_files.len() > 20 {
+                    md.push_str(&format!("  - … and {} more\n", image.shipped_files.len() - 20));
+                }
+                md.push('\n');
+            }
+        }
+
+        if !report.infrastructure.is_empty() {
+            let infrastructure = &report.infrastructure;
+            md.push_str(&format!("\n## Infrastructure\n\n{} resources, {} module references and {} dependencies between resources. Providers: {}.\n\n",
+                infrastructure.resources.len(), infrastructure.modules.len(), infrastructure.dependencies.len(),
+                infrastructure.providers.keys().cloned().collect::<Vec<_>>().join(", ")));
+            if !infrastructure.risks.is_empty() {
+                md.push_str("**Risky settings:**\n\n");
+                for risk in &infrastructure.risks {
+                    md.push_str(&format!("- {}:{} `{}`: {}\n", risk.file.display(), risk.line, risk.rule, risk.detail));
+                }
+                md.push('\n');
+            }
+            md.push_str("| Format | Kind | Count |\n|--------|------|-------|\n");
+            for (format, kind, count) in infrastructure.kinds() {
+                md.push_str(&format!("| {} | `{}` | {} |\n", format.name(), kind, count));
+            }
+            if !infrastructure.modules.is_empty() {
+                md.push_str("\n**Modules:**\n\n");
+                for module in &infrastructure.modules {
+                    md.push_str(&format!("- {} from `{}` ({}:{})\n", module.name, module.source, module.file.display(), module.line));
+                }
+            }
+            if !infrastructure.dependencies.is_empty() {
+                md.push_str("\n**Dependencies:**\n\n");
+                for dependency in &infrastructure.dependencies {
+                    md.push_str(&format!("- `{}` → `{}` ({})\n", dependency.from, dependency.to, dependency.file.display()));
+                }
+            }
+        }
+
+        if !report.assets.is_empty() {
+            let assets = &report.assets;
+            md.push_str(&format!("\n## Assets\n\n{} asset file(s) totalling {}, {:.0}% of the repository's {}.\n\n",
+                assets.asset_files(), AssetInventory::format_size(assets.asset_bytes), assets.asset_share() * 100.0,
+                AssetInventory::format_size(assets.total_bytes)));
+            if assets.asset_dominated {
+                md.push_str("> ⚠️ Non-code assets make up most of the repository's size. Consider Git LFS or an artifact store for them.\n\n");
+            }
+            md.push_str("| Kind | Files | Size |\n|------|-------|------|\n");
+            for kind in &assets.kinds {
+                md.push_str(&format!("| {} | {} | {} |\n", kind.kind.name(), kind.files, AssetInventory::format_size(kind.bytes)));
+            }
+            if !assets.large_assets.is_empty() {
+                md.push_str("\n**Large assets:**\n\n");
+                for asset in &assets.large_assets {
+                    md.push_str(&format!("- {} ({}, {})\n", asset.path.display(), asset.kind.name(), AssetInventory::format_size(asset.size)));
+                }
+            }
+        }
+
+        if !report.exclusions.is_empty() {
+            let total: usize = report.exclusions.iter().map(|group| group.files.len()).sum();
+            md.push_str(&format!("\n## Exclusions\n\n{} files excluded (run with `--explain-excludes` to list them all).\n\n", total));
+            md.push_str("| Reason | Rule | Files | Examples |\n");
+            md.push_str("|--------|------|-------|----------|\n");
+            for group in &report.exclusions {
+                md.push_str(&format!("| {} | `{}` | {} | {} |\n",
+                    group.reason.name(), group.rule, group.files.len(), group.files.iter().take(3).cloned().collect::<Vec<_>>().join(", ")));
+            }
+        }
+
+        if !report.api_surface.modules.is_empty() {
+            md.push_str(&format!("\n## Public API Surface\n\n{} exported symbols; caller counts are estimated from imports.\n",
+                report.api_surface.total_exported));
+            for module in &report.api_surface.modules {
+                md.push_str(&format!("\n### {}\n\n", module.module));
+                md.push_str("| Symbol | Signature | Location | Internal Callers | External Callers |\n");
+                md.push_str("|--------|-----------|----------|------------------|------------------|\n");
+                for symbol in &module.symbols {
+                    md.push_str(&format!("| {} | `{}` | {}:{} | {} | {} |\n",
+                        symbol.name, symbol.signature, symbol.file, symbol.line,
+                        symbol.internal_callers, symbol.external_callers));
+                }
+            }
+        }
+
+        if !report.endpoints.endpoints.is_empty() {
+            md.push_str(&format!("\n## API Endpoints\n\n{} endpoints from {} spec file(s) and route declarations; {} declared in a spec have no matching route.\n\n",
+                report.endpoints.endpoints.len(), report.endpoints.specs.len(), report.endpoints.unimplemented().count()));
+            md.push_str("| Method | Path | Declared In | Implemented In |\n");
+            md.push_str("|--------|------|-------------|----------------|\n");
+            for endpoint in &report.endpoints.endpoints {
+                md.push_str(&format!("| {} | `{}` | {} | {} |\n",
+                    endpoint.method, endpoint.path,
+                    endpoint.declared_in.iter().map(|f| f.display().to_string()).collect::<Vec<_>>().join(", "),
+                    endpoint.implemented_in.iter().map(|r| format!("{}:{}", r.file.display(), r.line)).collect::<Vec<_>>().join(", ")));
+            }
+        }
+
+        if !report.contracts.is_empty() {
+            let contracts = &report.contracts;
+            md.push_str(&format!("\n## Contracts\n\n{} contract file(s) declaring {} RPC(s); {} module(s) use the generated code.\n\n",
+                contracts.contracts.len(), contracts.rpc_count(), contracts.modules.len()));
+            for contract in &contracts.contracts {
+                md.push_str(&format!("- **{}** ({}{}): {} types, {} generated file(s), {} consumer(s)\n",
+                    contract.file.display(), contract.format.name(),
+                    contract.package.as_ref().map(|package| format!(", `{}`", package)).unwrap_or_default(),
+                    contract.types.len(), contract.generated_files.len(), contract.consumers.len()));
+                for service in &contract.services {
+                    md.push_str(&format!("  - {}: {}\n", service.name,
+                        service.methods.iter().map(|method| format!("`{}`", method.signature())).collect::<Vec<_>>().join(", ")));
+                }
+            }
+            if !contracts.modules.is_empty() {
+                md.push_str("\n| Module | Contracts | Files |\n");
+                md.push_str("|--------|-----------|-------|\n");
+                for module in &contracts.modules {
+                    md.push_str(&format!("| {} | {} | {} |\n", module.module,
+                        module.contracts.iter().map(|c| c.display().to_string()).collect::<Vec<_>>().join(", "), module.files));
+                }
+            }
+        }
+
+        if !report.frontend_routes.is_empty() {
+            let routes = &report.frontend_routes;
+            md.push_str(&format!("\n## Pages & Routes\n\n{} pages and routes ({}); ⏳ marks components loaded lazily.\n\n", routes.routes.len(),
+                routes.frameworks().iter().map(|framework| framework.name()).collect::<Vec<_>>().join(", ")));
+            md.push_str("| Router | Path | Component | Component File | Declared In |\n");
+            md.push_str("|--------|------|-----------|----------------|-------------|\n");
+            for route in &routes.routes {
+                let component = match (&route.redirect_to, &route.component) {
+                    (Some(redirect), _) => format!("→ `{}`", redirect),
+                    (None, Some(component)) => format!("`{}`", component),
+                    (None, None) => "-".to_string(),
+                };
+                md.push_str(&format!("| {} | `{}` | {}{} | {} | {}:{} |\n",
+                    route.framework.name(), route.path, component, if route.lazy { " ⏳" } else { "" },
+                    route.component_file.as_ref().map(|file| file.display().to_string()).unwrap_or_else(|| "-".to_string()),
+                    route.declared_in.display(), route.line));
+            }
+        }
+
+        if !report.i18n.is_empty() {
+            let i18n = &report.i18n;
+            md.push_str(&format!("\n## Internationalization\n\n{} translation bundle(s) in {} locale(s): {} missing and {} unused key(s), {} hardcoded user-facing string(s).\n\n",
+                i18n.bundles.len(), i18n.locales().len(), i18n.missing_count(), i18n.unused_count(), i18n.hardcoded_strings.len()));
+            md.push_str("| Bundle | Format | Locale | Keys | Missing | Untranslated |\n");
+            md.push_str("|--------|--------|--------|------|---------|--------------|\n");
+            let keys = |keys: &[String]| if keys.is_empty() { "-".to_string() } else { format!("{}", keys.len()) };
+            for bundle in &i18n.bundles {
+                for locale in &bundle.locales {
+                    md.push_str(&format!("| {} | {} | {}{} | {} | {} | {} |\n", bundle.name, bundle.format.name(), locale.locale,
+                        if locale.locale == bundle.reference_locale { " (reference)" } else { "" },
+                        locale.keys, keys(&locale.missing), keys(&locale.untranslated)));
+                }
+            }
+            for bundle in i18n.bundles.iter().filter(|bundle| !bundle.unused_keys.is_empty()) {
+                md.push_str(&format!("\nUnused keys in {}: {}\n", bundle.name,
+                    bundle.unused_keys.iter().map(|key| format!("`{}`", key)).collect::<Vec<_>>().join(", ")));
+            }
+            if !i18n.hardcoded_strings.is_empty() {
+                md.push_str("\n### Hardcoded Strings\n\n");
+                for string in i18n.hardcoded_strings.iter().take(MAX_LISTED_STRINGS) {
+                    md.push_str(&format!("- {}:{} \"{}\"\n", string.file.display(), string.line, string.text));
+                }
+                if i18n.hardcoded_strings.len() > MAX_LISTED_STRINGS {
+                    md.push_str(&format!("- ... and {} more in the JSON report\n", i18n.hardcoded_strings.len() - MAX_LISTED_STRINGS));
+                }
+            }
+        }
+
+        if !report.data_access.is_empty() {
+            let data_access = &report.data_access;
+            md.push_str(&format!("\n## Data Access\n\n{} tables defined in {} migration(s); {} files contain SQL.\n\n",
+                data_access.tables.len(), data_access.migrations.len(), data_access.files.len()));
+            md.push_str("| Module | Reads | Writes | Schema |\n");
+            md.push_str("|--------|-------|--------|--------|\n");
+            for module in &data_access.modules {
+                let tables = |kind: AccessKind| module.tables.iter()
+                    .filter(|(_, ops)| ops.contains(&kind))
+                    .map(|(t, _)| t.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                md.push_str(&format!("| {} | {} | {} | {} |\n",
+                    module.module, tables(AccessKind::Read), tables(AccessKind::Write), tables(AccessKind::Schema)));
+            }
+            if !data_access.tables.is_empty() {
+                md.push_str("\n| Table | Columns | Defined In |\n|-------|---------|------------|\n");
+                for table in &data_access.tables {
+                    md.push_str(&format!("| {} | {} | {} |\n", table.name, table.columns.join(", "),
+                        table.defined_in.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")));
+                }
+            }
+            let dynamic: Vec<String> = data_access.files.iter()
+                .flat_map(|file| file.dynamic_queries.iter().map(move |line| format!("{}:{}", file.file.display(), line)))
+                .collect();
+            if !dynamic.is_empty() {
+                md.push_str("\n**SQL built by concatenation or string formatting** (check for injection):\n\n");
+                for location in dynamic {
+                    md.push_str(&format!("- {}\n", location));
+                }
+            }
+        }
+
+        if !report.concurrency.is_empty() {
+            let kinds = Self::concurrency_kinds();
+            md.push_str(&format!("\n## Concurrency\n\n{} concurrency primitives in {} modules; ⚠️ marks modules that spawn work and share state.\n\n",
+                report.concurrency.sites.len(), report.concurrency.hotspots.len()));
+            md.push_str(&format!("| Module | {} | Total |\n", kinds.iter().map(|k| k.name()).collect::<Vec<_>>().join(" | ")));
+            md.push_str(&format!("|--------|{}-------|\n", "------|".repeat(kinds.len())));
+            for hotspot in &report.concurrency.hotspots {
+                md.push_str(&format!("| {}{} | {} | {} |\n",
+                    hotspot.module,
+                    if hotspot.spawns_with_shared_state { " ⚠️" } else { "" },
+                    kinds.iter().map(|k| hotspot.counts.get(k).copied().unwrap_or(0).to_string()).collect::<Vec<_>>().join(" | "),
+                    hotspot.total));
+            }
+        }
+
+        if !report.metaprogramming.is_empty() {
+            md.push_str(&format!("\n## Metaprogramming\n\n{} metaprogramming sites in {} modules; ⚙️ marks modules that generate code or define their own macros.\n\n",
+                report.metaprogramming.sites.len(), report.metaprogramming.modules.len()));
+            md.push_str(&format!("| Module | {} | Total | Most used |\n", MetaKind::ALL.iter().map(|k| k.name()).collect::<Vec<_>>().join(" | ")));
+            md.push_str(&format!("|--------|{}-------|-----------|\n", "------|".repeat(MetaKind::ALL.len())));
+            for module in &report.metaprogramming.modules {
+                md.push_str(&format!("| {}{} | {} | {} | {} |\n",
+                    module.module,
+                    if module.generates_code { " ⚙️" } else { "" },
+                    MetaKind::ALL.iter().map(|k| module.counts.get(k).copied().unwrap_or(0).to_string()).collect::<Vec<_>>().join(" | "),
+                    module.total,
+                    module.top_names.iter().map(|(name, uses)| format!("`{}` ×{}", name, uses)).collect::<Vec<_>>().join(", ")));
+            }
+        }
+
+        if full && !report.llm_insights.is_empty() {
+            self.push_markdown_llm_analysis(&mut md, &report.llm_insights);
+        }
+
+        if !report.parse_errors.is_empty() {
+            md.push_str(&format!("\n## Appendix: Parse Errors\n\n{}\n\n", Self::parse_errors_summary(&report.parse_errors)));
+            md.push_str("| File | Language | Kind | Error |\n");
+            md.push_str("|------|----------|------|-------|\n");
+            for error in &report.parse_errors {
+                md.push_str(&format!("| {} | {} | {} | {} |\n",
+                    error.path.display(), error.language.as_deref().unwrap_or("unknown"),
+                    error.kind.name(), error.message.replace('|', "\\|")));
+            }
+        }
+
+        if !report.discarded_llm_output.is_empty() {
+            md.push_str(&format!("\n## Appendix: Discarded LLM Output\n\n{} LLM insight(s) were left out of the report because they named files, functions or languages the project doesn't have, or the review pass found them unsupported.\n\n",
+                report.discarded_llm_output.len()));
+            md.push_str("| Analysis | Insight | Reasons |\n");
+            md.push_str("|----------|---------|---------|\n");
+            for item in &report.discarded_llm_output {
+                md.push_str(&format!("| {} | {} | {} |\n",
+                    item.analysis_type.as_ref().map(|t| t.name()).unwrap_or("unknown"),
+                    item.title.replace('|', "\\|"),
+                    item.reasons.join("; ").replace('|', "\\|")));
+            }
         }
 
         Ok(md)
     }
+
+    /// Largest files and the complexity distribution, for the full Markdown report
+    fn push_markdown_file_details(&self, md: &mut String, report: &Report) {
+        let files = &report.file_analysis;
+        if !files.largest_files.is_empty() {
+            md.push_str("\n## Largest Files\n\n");
+            md.push_str("| File | Language | Size (KB) | Functions | Classes | Complexity |\n");
+            md.push_str("|------|----------|-----------|-----------|---------|------------|\n");
+            for file in &files.largest_files {
+                md.push_str(&format!("| {} | {} | {:.1} | {} | {} | {} |\n",
+                    file.path, file.language, file.size as f64 / 1024.0, file.functions, file.classes, file.complexity));
+            }
+        }
+        if files.complexity_distribution.iter().any(|bucket| bucket.count > 0) {
+            md.push_str("\n## Complexity Distribution\n\n");
+            md.push_str("| Complexity | Files | Percentage |\n");
+            md.push_str("|------------|-------|------------|\n");
+            for bucket in &files.complexity_distribution {
+                md.push_str(&format!("| {} | {} | {:.1}% |\n", bucket.range, bucket.count, bucket.percentage));
+            }
+        }
+    }
+
+    /// Dependency graph metrics, coupling and orphaned files, for the full Markdown report
+    fn push_markdown_dependencies(&self, md: &mut String, dependencies: &DependencyAnalysisReport) {
+        let graph = &dependencies.graph_metrics;
+        md.push_str("\n## Dependencies\n\n");
+        md.push_str(&format!("- **Nodes:** {}\n- **Edges:** {}\n- **Average degree:** {:.2}\n- **Cyclic components:** {} ({} over the size limit)\n",
+            graph.total_nodes, graph.total_edges, graph.avg_degree,
+            graph.strongly_connected_components, graph.oversized_components.len()));
+        if !graph.node_types.is_empty() {
+            md.push_str(&format!("- **Node types:** {}\n", graph.node_types.iter()
+                .map(|(kind, count)| format!("{} ({})", kind, count))
+                .collect::<Vec<_>>()
+                .join(", ")));
+        }
+        if !graph.edge_types.is_empty() {
+            md.push_str(&format!("- **Edge types:** {}\n", graph.edge_types.iter()
+                .map(|(kind, count)| format!("{} ({})", kind, count))
+                .collect::<Vec<_>>()
+                .join(", ")));
+        }
+        if !dependencies.highly_coupled_files.is_empty() {
+            md.push_str("\n| File | Incoming | Outgoing | Coupling Score |\n|------|----------|----------|----------------|\n");
+            for coupling in &dependencies.highly_coupled_files {
+                md.push_str(&format!("| {} | {} | {} | {:.2} |\n",
+                    coupling.file, coupling.incoming_dependencies, coupling.outgoing_dependencies, coupling.coupling_score));
+            }
+        }
+        if !dependencies.orphaned_files.is_empty() {
+            md.push_str("\n**Orphaned files** (nothing imports them and they import nothing):\n\n");
+            for file in &dependencies.orphaned_files {
+                md.push_str(&format!("- {}\n", file));
+            }
+        }
+    }
+
+    /// Every LLM analysis with its insights, evidence and recommendations, for the full Markdown report
+    fn push_markdown_llm_analysis(&self, md: &mut String, llm_insights: &[AnalysisResponse]) {
+        md.push_str("\n## LLM Analysis\n");
+        for (index, analysis) in llm_insights.iter().enumerate() {
+            match &analysis.analysis_type {
+                Some(analysis_type) => md.push_str(&format!("\n### {} Analysis\n\n", analysis_type.name())),
+                None => md.push_str(&format!("\n### Additional Analysis {}\n\n", index + 1)),
+            }
+            md.push_str(self.extract_analysis_text(&analysis.analysis).trim());
+            md.push('\n');
+
+            let insights = if !analysis.insights.is_empty() {
+                analysis.insights.clone()
+            } else {
+                self.extract_insights_from_text(&analysis.analysis)
+            };
+            if !insights.is_empty() {
+                md.push_str("\n#### Insights\n");
+                for insight in &insights {
+                    md.push_str(&format!("\n- **{}** ({:?}, {:.0}% confidence): {}\n",
+                        insight.title, insight.category, insight.confidence * 100.0, insight.description));
+                    for evidence in &insight.evidence {
+                        md.push_str(&Self::evidence_markdown(evidence));
+                    }
+                }
+            }
+
+            let recommendations = if !analysis.recommendations.is_empty() {
+                analysis.recommendations.clone()
+            } else {
+                self.extract_recommendations_from_text(&analysis.analysis)
+            };
+            if !recommendations.is_empty() {
+                md.push_str("\n#### Recommendations\n");
+                for recommendation in &recommendations {
+                    md.push_str(&format!("\n- **{}** (Priority: {:?}, Effort: {:?}, Impact: {:?}): {}\n",
+                        recommendation.title, recommendation.priority, recommendation.effort, recommendation.impact,
+                        recommendation.description));
+                    if !recommendation.files.is_empty() {
+                        md.push_str(&format!("  Files: {}\n", Self::file_links_markdown(&recommendation.files)));
+                    }
+                    for item in &recommendation.action_items {
+                        md.push_str(&format!("  - [ ] {}\n", item));
+                    }
+                }
+            }
+        }
+    }
+
+    fn file_links_markdown(files: &[String]) -> String {
+        files.iter()
+            .map(|file| format!("[`{}`]({})", file, file.replace(' ', "%20")))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Evidence as an indented quote under its insight; quotes that could not be found are marked
+    fn evidence_markdown(evidence: &Evidence) -> String {
+        let location = match (&evidence.file, evidence.line) {
+            (Some(file), Some(line)) => format!("`{}:{}`", file, line),
+            (Some(file), None) => format!("`{}`", file),
+            _ => return format!("  > {}\n", evidence.snippet),
+        };
+        let flag = match evidence.status {
+            EvidenceStatus::UnknownFile => " ⚠️ file not in the project",
+            EvidenceStatus::SnippetNotFound => " ⚠️ quote not found in file",
+            _ => "",
+        };
+        let mut quote = format!("  > {}{}\n", location, flag);
+        if !evidence.snippet.is_empty() {
+            quote.push_str("  > ```\n");
+            for line in evidence.snippet.lines() {
+                quote.push_str(&format!("  > {}\n", line));
+            }
+            quote.push_str("  > ```\n");
+        }
+        quote
+    }
 }
\ No newline at end of file
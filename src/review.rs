@@ -0,0 +1,199 @@
+use crate::{
+    grounding::DiscardedItem,
+    llm::{AnalysisResponse, Evidence},
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+const SYSTEM_PROMPT: &str = "You are a skeptical senior engineer reviewing another analyst's findings about a codebase. \
+Check each insight against the project context the analyst was given. Remove insights the context does not support, \
+and merge insights or recommendations that make the same point, including across analysis types. \
+Refer to items only by their ids and leave everything else out. \
+Respond with JSON only: {\"remove\": [{\"id\": \"I3\", \"reason\": \"...\"}], \"merge\": [{\"keep\": \"I1\", \"duplicates\": [\"I4\"]}]}";
+
+/// The critic's decisions, applied locally so the review can only remove and merge
+#[derive(Debug, Default, Deserialize)]
+pub struct ReviewVerdict {
+    #[serde(default)]
+    remove: Vec<Removal>,
+    #[serde(default)]
+    merge: Vec<Merge>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Removal {
+    id: String,
+    #[serde(default)]
+    reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Merge {
+    keep: String,
+    #[serde(default)]
+    duplicates: Vec<String>,
+}
+
+/// What applying a verdict changed
+#[derive(Debug, Default)]
+pub struct ReviewOutcome {
+    /// Insights removed as unsupported
+    pub removed: Vec<DiscardedItem>,
+    /// Insights and recommendations folded into another one
+    pub merged: usize,
+}
+
+/// An insight (`I<n>`) or recommendation (`R<n>`), by response and position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Item {
+    Insight(usize, usize),
+    Recommendation(usize, usize),
+}
+
+/// Second-stage review: a critic call that removes unsupported insights and merges
+/// duplicates across analysis types (`llm.enable_review_pass`)
+#[derive(Default)]
+pub struct ReviewPass;
+
+impl ReviewPass {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn system_prompt(&self) -> &'static str {
+        SYSTEM_PROMPT
+    }
+
+    /// The project context followed by every insight and recommendation, numbered across analyses
+    pub fn build_prompt(&self, responses: &[AnalysisResponse], context: &str) -> String {
+        let mut prompt = String::from("Project context given to the analyst:\n\n");
+        prompt.push_str(context);
+        prompt.push_str("\n\nFindings to review:\n");
+
+        let mut insight_id = 0;
+        let mut recommendation_id = 0;
+        for response in responses {
+            let analysis = response.analysis_type.as_ref().map(|t| t.name()).unwrap_or("Analysis");
+            for insight in &response.insights {
+                insight_id += 1;
+                prompt.push_str(&format!("\n[I{}] ({}) {}: {}\n", insight_id, analysis, insight.title, insight.description));
+                for evidence in &insight.evidence {
+                    prompt.push_str(&format!("  Evidence: {}{}\n", evidence,
+                        if evidence.status.is_flagged() { " (not found in the project)" } else { "" }));
+                }
+            }
+            for recommendation in &response.recommendations {
+                recommendation_id += 1;
+                prompt.push_str(&format!("\n[R{}] ({}) {}: {}\n", recommendation_id, analysis, recommendation.title, recommendation.description));
+            }
+        }
+        prompt
+    }
+
+    /// Parse the critic's reply, tolerating prose or code fences around the JSON
+    pub fn parse_response(&self, content: &str) -> ReviewVerdict {
+        let json = match (content.find('{'), content.rfind('}')) {
+            (Some(start), Some(end)) if start < end => &content[start..=end],
+            _ => return ReviewVerdict::default(),
+        };
+        serde_json::from_str(json).unwrap_or_default()
+    }
+
+    /// Merge duplicates into the item kept, carrying over their evidence, files and
+    /// action items, then drop removed insights. Unknown ids are ignored.
+    pub fn apply(&self, verdict: ReviewVerdict, responses: &mut [AnalysisResponse]) -> ReviewOutcome {
+        let mut insights = Vec::new();
+        let mut recommendations = Vec::new();
+        for (r, response) in responses.iter().enumerate() {
+            insights.extend((0..response.insights.len()).map(|i| Item::Insight(r, i)));
+            recommendations.extend((0..response.recommendations.len()).map(|i| Item::Recommendation(r, i)));
+        }
+        let lookup = |id: &str| -> Option<Item> {
+            let id = id.trim();
+            let index = id.get(1..)?.parse::<usize>().ok()?.checked_sub(1)?;
+            match id.chars().next()? {
+                'I' | 'i' => insights.get(index).copied(),
+                'R' | 'r' => recommendations.get(index).copied(),
+                _ => None,
+            }
+        };
+
+        let mut outcome = ReviewOutcome::default();
+        let mut gone: HashSet<Item> = HashSet::new();
+        for merge in &verdict.merge {
+            let Some(keep) = lookup(&merge.keep) else { continue };
+            if gone.contains(&keep) {
+                continue;
+            }
+            for duplicate in merge.duplicates.iter().filter_map(|id| lookup(id)) {
+                if duplicate == keep || gone.contains(&duplicate) {
+                    continue;
+                }
+                match (keep, duplicate) {
+                    (Item::Insight(kr, ki), Item::Insight(dr, di)) => {
+                        let duplicate = responses[dr].insights[di].clone();
+                        let kept = &mut responses[kr].insights[ki];
+                        kept.confidence = kept.confidence.max(duplicate.confidence);
+                        for evidence in duplicate.evidence {
+                            if !kept.evidence.iter().any(|e| same_evidence(e, &evidence)) {
+                                kept.evidence.push(evidence);
+                            }
+                        }
+                    }
+                    (Item::Recommendation(kr, ki), Item::Recommendation(dr, di)) => {
+                        let duplicate = responses[dr].recommendations[di].clone();
+                        let kept = &mut responses[kr].recommendations[ki];
+                        for file in duplicate.files {
+                            if !kept.files.contains(&file) {
+                                kept.files.push(file);
+                            }
+                        }
+                        for item in duplicate.action_items {
+                            if !kept.action_items.contains(&item) {
+                                kept.action_items.push(item);
+                            }
+                        }
+                    }
+                    _ => continue,
+                }
+                gone.insert(duplicate);
+                outcome.merged += 1;
+            }
+        }
+
+        for removal in &verdict.remove {
+            let Some(item @ Item::Insight(r, i)) = lookup(&removal.id) else { continue };
+            if !gone.insert(item) {
+                continue;
+            }
+            let response = &responses[r];
+            outcome.removed.push(DiscardedItem {
+                analysis_type: response.analysis_type.clone(),
+                title: response.insights[i].title.clone(),
+                reasons: vec![if removal.reason.trim().is_empty() {
+                    "review pass: not supported by the project context".to_string()
+                } else {
+                    format!("review pass: {}", removal.reason.trim())
+                }],
+            });
+        }
+
+        for (r, response) in responses.iter_mut().enumerate() {
+            let mut index = 0;
+            response.insights.retain(|_| {
+                index += 1;
+                !gone.contains(&Item::Insight(r, index - 1))
+            });
+            let mut index = 0;
+            response.recommendations.retain(|_| {
+                index += 1;
+                !gone.contains(&Item::Recommendation(r, index - 1))
+            });
+        }
+        outcome
+    }
+}
+
+fn same_evidence(a: &Evidence, b: &Evidence) -> bool {
+    a.file == b.file && a.line == b.line && a.snippet == b.snippet
+}
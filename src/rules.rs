@@ -0,0 +1,179 @@
+use crate::{dependency_graph::FileDependency, simple_parser::ParsedFile, sources::SourceSet};
+use crate::{Error, Result};
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// A user-defined rule from `[[rules]]` in the config file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    pub id: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(flatten)]
+    pub check: RuleCheck,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleCheck {
+    /// Regex matched against every line of the selected files
+    Pattern {
+        pattern: String,
+        #[serde(default)]
+        files: Option<String>,
+    },
+    /// Files matching `from` must not import files or modules matching `to`
+    ForbiddenImport { from: String, to: String },
+    /// Function and class names in the selected files must match `pattern`
+    SymbolName {
+        pattern: String,
+        #[serde(default)]
+        files: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    #[default]
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleFinding {
+    pub rule_id: String,
+    pub description: String,
+    pub severity: Severity,
+    pub file: String,
+    pub line: usize,
+    pub evidence: String,
+}
+
+enum CompiledCheck {
+    Pattern { pattern: Regex, files: Option<GlobMatcher> },
+    ForbiddenImport { from: GlobMatcher, to: GlobMatcher },
+    SymbolName { pattern: Regex, files: Option<GlobMatcher> },
+}
+
+struct CompiledRule {
+    rule: Rule,
+    check: CompiledCheck,
+}
+
+/// Evaluates rules locally against parsed files and the dependency graph
+pub struct RuleEngine {
+    root: PathBuf,
+    rules: Vec<CompiledRule>,
+}
+
+impl RuleEngine {
+    pub fn new(root: &Path, rules: &[Rule]) -> Result<Self> {
+        let rules = rules.iter()
+            .map(|rule| {
+                let check = Self::compile(&rule.check)
+                    .map_err(|e| Error::Config(format!("Invalid rule '{}': {}", rule.id, e)))?;
+                Ok(CompiledRule { rule: rule.clone(), check })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { root: root.to_path_buf(), rules })
+    }
+
+    fn compile(check: &RuleCheck) -> Result<CompiledCheck> {
+        let glob = |pattern: &str| -> Result<GlobMatcher> { Ok(Glob::new(pattern)?.compile_matcher()) };
+        Ok(match check {
+            RuleCheck::Pattern { pattern, files } => CompiledCheck::Pattern {
+                pattern: Regex::new(pattern)?,
+                files: files.as_deref().map(glob).transpose()?,
+            },
+            RuleCheck::ForbiddenImport { from, to } => CompiledCheck::ForbiddenImport {
+                from: glob(from)?,
+                to: glob(to)?,
+            },
+            RuleCheck::SymbolName { pattern, files } => CompiledCheck::SymbolName {
+                pattern: Regex::new(pattern)?,
+                files: files.as_deref().map(glob).transpose()?,
+            },
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn evaluate(&self, parsed_files: &[ParsedFile], file_dependencies: &[FileDependency], sources: &SourceSet) -> Vec<RuleFinding> {
+        let mut findings = Vec::new();
+
+        for compiled in &self.rules {
+            let mut finding = |file: &Path, line: usize, evidence: String| findings.push(RuleFinding {
+                rule_id: compiled.rule.id.clone(),
+                description: compiled.rule.description.clone(),
+                severity: compiled.rule.severity,
+                file: file.to_string_lossy().to_string(),
+                line,
+                evidence,
+            });
+
+            match &compiled.check {
+                CompiledCheck::Pattern { pattern, files } => {
+                    for pf in parsed_files.iter().filter(|pf| self.selected(files, &pf.file_info.path)) {
+                        let Ok(content) = sources.read(&pf.file_info.path) else { continue };
+                        for (line_num, line) in content.lines().enumerate() {
+                            if pattern.is_match(line) {
+                                finding(&pf.file_info.path, line_num + 1, line.trim().to_string());
+                            }
+                        }
+                    }
+                }
+                CompiledCheck::ForbiddenImport { from, to } => {
+                    let mut seen = HashSet::new();
+                    for dep in file_dependencies {
+                        if from.is_match(self.relative(&dep.from)) && to.is_match(self.relative(&dep.to)) {
+                            seen.insert((dep.from.clone(), dep.line_number));
+                            finding(&dep.from, dep.line_number, format!("imports {}", self.relative(&dep.to).display()));
+                        }
+                    }
+                    // Imports of external modules (or unresolved paths) are matched by name
+                    for pf in parsed_files.iter().filter(|pf| from.is_match(self.relative(&pf.file_info.path))) {
+                        for import in pf.imports.iter().filter(|i| to.is_match(&i.module)) {
+                            if seen.insert((pf.file_info.path.clone(), import.line_number)) {
+                                finding(&pf.file_info.path, import.line_number, format!("imports {}", import.module));
+                            }
+                        }
+                    }
+                }
+                CompiledCheck::SymbolName { pattern, files } => {
+                    for pf in parsed_files.iter().filter(|pf| self.selected(files, &pf.file_info.path)) {
+                        let functions = pf.functions.iter().map(|f| (f.name.as_str(), f.line_number));
+                        let classes = pf.classes.iter().map(|c| (c.name.as_str(), c.line_number));
+                        for (name, line) in functions.chain(classes) {
+                            if !pattern.is_match(name) {
+                                finding(&pf.file_info.path, line, format!("`{}` does not match /{}/", name, pattern));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        findings.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| (&a.file, a.line).cmp(&(&b.file, b.line))));
+        findings
+    }
+
+    fn selected(&self, files: &Option<GlobMatcher>, path: &Path) -> bool {
+        files.as_ref().is_none_or(|glob| glob.is_match(self.relative(path)))
+    }
+
+    /// Globs are written relative to the analyzed directory
+    fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.root).unwrap_or(path)
+    }
+}
@@ -0,0 +1,59 @@
+use crate::file_discovery::SkippedFile;
+use crate::perf::StageTiming;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Local-only statistics about a run, written next to the reports so users can
+/// see why files are missing. Holds counts only: no paths, names or contents.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunStats {
+    pub version: String,
+    /// Files selected by discovery
+    pub files_discovered: usize,
+    /// Files excluded by discovery, by reason
+    pub files_skipped: BTreeMap<String, usize>,
+    /// Files left after `analysis.max_files` sampling
+    pub files_analyzed: usize,
+    pub files_parsed: usize,
+    /// Files reused unparsed from the daemon's cache
+    #[serde(default)]
+    pub files_from_cache: usize,
+    /// Files that failed to read or parse, by language
+    pub parse_errors: BTreeMap<String, usize>,
+    /// Files parsed with reduced fidelity: decoded from another encoding or cut to size-only metrics
+    #[serde(default)]
+    pub files_degraded: usize,
+    pub stages: Vec<StageTiming>,
+    pub total_ms: f64,
+}
+
+impl RunStats {
+    pub fn new() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn record_discovery(&mut self, files: usize, skipped: &[SkippedFile]) {
+        self.files_discovered += files;
+        for file in skipped {
+            *self.files_skipped.entry(file.reason.name().to_string()).or_insert(0) += 1;
+        }
+    }
+
+    pub fn record_parse_error(&mut self, language: Option<&str>) {
+        *self.parse_errors.entry(language.unwrap_or("unknown").to_string()).or_insert(0) += 1;
+    }
+
+    /// Write `run_stats.json` to the output directory
+    pub fn write(&self, output_dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+        let path = output_dir.join("run_stats.json");
+        fs::write(&path, serde_json::to_string_pretty(self)?).map_err(|e| Error::report(&path, e))?;
+        Ok(path)
+    }
+}
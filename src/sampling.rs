@@ -0,0 +1,138 @@
+use crate::{
+    config::SamplingStrategy,
+    file_discovery::FileInfo,
+    git,
+    simple_parser::SimpleParser,
+    sources::SourceSet,
+};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Records that only part of the project was analyzed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplingSummary {
+    pub strategy: SamplingStrategy,
+    pub total_files: usize,
+    pub sampled_files: usize,
+}
+
+impl fmt::Display for SamplingSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} files, prioritized by {}", self.sampled_files, self.total_files, self.strategy.name())
+    }
+}
+
+/// Picks at most `max_files` files so huge repositories degrade gracefully
+pub struct FileSampler {
+    root: PathBuf,
+    max_files: usize,
+    strategy: SamplingStrategy,
+}
+
+impl FileSampler {
+    pub fn new(root: &Path, max_files: usize, strategy: SamplingStrategy) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            max_files,
+            strategy,
+        }
+    }
+
+    /// Keep the highest-priority files in their original order; the summary is
+    /// `None` when every file fits
+    pub fn sample(&self, files: Vec<FileInfo>, sources: &SourceSet) -> (Vec<FileInfo>, Option<SamplingSummary>) {
+        if self.max_files == 0 || files.len() <= self.max_files {
+            return (files, None);
+        }
+
+        let scores = match self.strategy {
+            SamplingStrategy::Size => files.iter().map(|f| f.size as i64).collect(),
+            SamplingStrategy::Centrality => Self::centrality_scores(&files, sources),
+            SamplingStrategy::Recency => self.recency_scores(&files, sources),
+        };
+
+        let mut ranked: Vec<usize> = (0..files.len()).collect();
+        ranked.sort_by(|&a, &b| scores[b].cmp(&scores[a]).then_with(|| files[a].path.cmp(&files[b].path)));
+        let keep: HashSet<usize> = ranked.into_iter().take(self.max_files).collect();
+
+        let summary = SamplingSummary {
+            strategy: self.strategy,
+            total_files: files.len(),
+            sampled_files: keep.len(),
+        };
+        let sampled = files.into_iter()
+            .enumerate()
+            .filter(|(i, _)| keep.contains(i))
+            .map(|(_, file)| file)
+            .collect();
+        (sampled, Some(summary))
+    }
+
+    /// How many imports anywhere in the project name each file. Only the import
+    /// lists are kept while scanning, so memory stays proportional to imports.
+    fn centrality_scores(files: &[FileInfo], sources: &SourceSet) -> Vec<i64> {
+        let imported: Vec<String> = files.par_iter()
+            .map_init(|| SimpleParser::new().ok(), |parser, file| {
+                let (Some(parser), Ok(content)) = (parser.as_ref(), sources.read(&file.path)) else {
+                    return Vec::new();
+                };
+                parser.parse_source(file, &content)
+                    .map(|pf| pf.imports.iter().flat_map(|import| Self::segments(&import.module)).collect())
+                    .unwrap_or_default()
+            })
+            .flatten()
+            .collect();
+
+        let mut references: HashMap<String, i64> = HashMap::new();
+        for name in imported {
+            *references.entry(name).or_insert(0) += 1;
+        }
+
+        files.iter()
+            .map(|file| Self::module_stem(&file.path).and_then(|stem| references.get(&stem)).copied().unwrap_or(0))
+            .collect()
+    }
+
+    /// Names an import may refer to a file by: `crate::graph::Node` -> `graph`, `node`
+    fn segments(module: &str) -> HashSet<String> {
+        module.trim_matches(|c| c == '"' || c == '\'')
+            .split(['/', ':', '.', '\\'])
+            .filter(|s| !s.is_empty() && !matches!(*s, "crate" | "self" | "super"))
+            .map(str::to_lowercase)
+            .collect()
+    }
+
+    /// Name other files use to import this one; index files are named by their directory
+    fn module_stem(path: &Path) -> Option<String> {
+        let stem = path.file_stem()?.to_string_lossy().to_lowercase();
+        if matches!(stem.as_str(), "index" | "mod" | "__init__" | "lib" | "main") {
+            return path.parent()?.file_name().map(|name| name.to_string_lossy().to_lowercase());
+        }
+        Some(stem)
+    }
+
+    /// Last commit time from git, falling back to the file's modification time
+    fn recency_scores(&self, files: &[FileInfo], sources: &SourceSet) -> Vec<i64> {
+        if sources.is_in_memory() {
+            return vec![0; files.len()];
+        }
+        let commit_times = git::last_commit_times(&self.root).unwrap_or_default();
+
+        files.iter()
+            .map(|file| {
+                commit_times.get(&file.path).copied().unwrap_or_else(|| {
+                    fs::metadata(&file.path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                        .map(|duration| duration.as_secs() as i64)
+                        .unwrap_or(0)
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,215 @@
+use crate::config::ScheduleConfig;
+use crate::{Error, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Prefix of the per-run directories the rotation owns; nothing else is ever deleted
+const RUN_PREFIX: &str = "run-";
+const RUN_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// How far ahead to look for the next matching minute before giving up (e.g. `0 0 31 2 *`)
+const SEARCH_YEARS: i32 = 5;
+
+const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const DAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A standard five-field cron expression (minute, hour, day of month, month, day of
+/// week) with `*`, lists, ranges, steps, month and day names and the `@daily`-style
+/// shortcuts. As in cron, a day matches when either day field matches if both are restricted.
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let expanded = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(Error::Config(format!("Cron expression \"{}\" needs five fields: minute hour day-of-month month day-of-week", expression)));
+        };
+        let field = |text: &str, name: &str, min: u32, max: u32, names: &[&str]| {
+            parse_field(text, min, max, names)
+                .map_err(|e| Error::Config(format!("Invalid {} field \"{}\" in \"{}\": {}", name, text, expression, e)))
+        };
+
+        let mut days_of_week = field(day_of_week, "day-of-week", 0, 7, &DAYS)?;
+        // 7 is another name for Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week |= 1;
+        }
+        Ok(Self {
+            minutes: field(minute, "minute", 0, 59, &[])?,
+            hours: field(hour, "hour", 0, 23, &[])?,
+            days_of_month: field(day_of_month, "day-of-month", 1, 31, &[])?,
+            months: field(month, "month", 1, 12, &MONTHS)?,
+            days_of_week,
+            any_day_of_month: day_of_month == "*",
+            any_day_of_week: day_of_week == "*",
+        })
+    }
+
+    /// The first matching minute strictly after `after`, in its time zone. Local times
+    /// skipped by a DST change are skipped; repeated ones fire once.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let zone = after.timezone();
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(366 * SEARCH_YEARS as i64);
+        let mut time = start;
+
+        while time < limit {
+            if !has(self.months, time.month()) {
+                let (year, month) = if time.month() == 12 { (time.year() + 1, 1) } else { (time.year(), time.month() + 1) };
+                time = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.day_matches(&time) {
+                time = (time.date() + Duration::days(1)).and_hms_opt(0, 0, 0)?;
+            } else if !has(self.hours, time.hour()) {
+                time = time.with_minute(0)? + Duration::hours(1);
+            } else if !has(self.minutes, time.minute()) {
+                time += Duration::minutes(1);
+            } else {
+                match zone.from_local_datetime(&time).earliest() {
+                    Some(next) if next > *after => return Some(next),
+                    _ => time += Duration::minutes(1),
+                }
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, time: &NaiveDateTime) -> bool {
+        let day_of_month = has(self.days_of_month, time.day());
+        let day_of_week = has(self.days_of_week, time.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (false, true) => day_of_month,
+            (true, false) => day_of_week,
+            (false, false) => day_of_month || day_of_week,
+        }
+    }
+}
+
+fn has(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// One cron field as a bit set of the values it matches
+fn parse_field(text: &str, min: u32, max: u32, names: &[&str]) -> Result<u64> {
+    let value = |part: &str| -> Result<u32> {
+        let offset = if min == 1 { 1 } else { 0 };
+        let number = match names.iter().position(|name| name.eq_ignore_ascii_case(part)) {
+            Some(index) => index as u32 + offset,
+            None => part.parse().map_err(|_| Error::Config(format!("\"{}\" is not a number", part)))?,
+        };
+        if number < min || number > max {
+            return Err(Error::Config(format!("{} is outside {}-{}", number, min, max)));
+        }
+        Ok(number)
+    };
+
+    let mut set = 0u64;
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| Error::Config(format!("bad step \"{}\"", step)))?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(Error::Config("step must be at least 1".to_string()));
+        }
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/15` runs from 5 to the end of the range
+                None if step > 1 => (value(range)?, max),
+                None => {
+                    let single = value(range)?;
+                    (single, single)
+                }
+            },
+        };
+        if start > end {
+            return Err(Error::Config(format!("range {}-{} runs backwards", start, end)));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+/// Per-run report directories under the output root, with a retention policy and a
+/// `latest` link to the newest completed run
+pub struct RunRotation {
+    root: PathBuf,
+    keep_runs: usize,
+    max_age_days: u64,
+}
+
+impl RunRotation {
+    pub fn new(root: &Path, config: &ScheduleConfig) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            keep_runs: config.keep_runs,
+            max_age_days: config.max_age_days,
+        }
+    }
+
+    pub fn run_dir(&self, started: NaiveDateTime) -> PathBuf {
+        self.root.join(format!("{}{}", RUN_PREFIX, started.format(RUN_FORMAT)))
+    }
+
+    /// Point `latest` at a completed run (a symlink; skipped where unsupported)
+    pub fn mark_latest(&self, run_dir: &Path) -> Result<()> {
+        let link = self.root.join("latest");
+        if link.is_symlink() {
+            fs::remove_file(&link)?;
+        } else if link.exists() {
+            return Err(Error::Config(format!("{} exists and is not a link; leaving it alone", link.display())));
+        }
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(run_dir.file_name().unwrap_or(run_dir.as_os_str()), &link)?;
+        Ok(())
+    }
+
+    /// Delete run directories beyond `keep_runs` or older than `max_age_days`,
+    /// never the newest one, returning what was removed
+    pub fn prune(&self, now: NaiveDateTime) -> Result<Vec<PathBuf>> {
+        let mut runs: Vec<(NaiveDateTime, PathBuf)> = fs::read_dir(&self.root)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().map(|kind| kind.is_dir()).unwrap_or(false))
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let started = NaiveDateTime::parse_from_str(name.strip_prefix(RUN_PREFIX)?, RUN_FORMAT).ok()?;
+                Some((started, entry.path()))
+            })
+            .collect();
+        runs.sort_by_key(|(started, _)| std::cmp::Reverse(*started));
+
+        let max_age = Duration::days(self.max_age_days as i64);
+        let mut removed = Vec::new();
+        for (position, (started, path)) in runs.into_iter().enumerate() {
+            let over_count = self.keep_runs > 0 && position >= self.keep_runs;
+            let too_old = self.max_age_days > 0 && now - started > max_age;
+            if position > 0 && (over_count || too_old) {
+                fs::remove_dir_all(&path)?;
+                removed.push(path);
+            }
+        }
+        Ok(removed)
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    config::ScorecardConfig,
+    graph_export::escape_xml,
+    html_layout,
+    llm::{Priority, RecommendationCategory},
+    reporter::Report,
+};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One graded category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryGrade {
+    pub category: String,
+    /// 0-100, or `None` when there was nothing to measure
+    pub score: Option<f64>,
+    /// `A` to `F`, or `N/A`
+    pub grade: String,
+    /// What the score was derived from, in one line
+    pub basis: String,
+}
+
+/// Letter grades per category for comparing projects at a glance, written as
+/// `scorecard.md` and `scorecard.html`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scorecard {
+    pub project: String,
+    pub generated_at: String,
+    /// Mean of the graded categories
+    pub overall: CategoryGrade,
+    pub categories: Vec<CategoryGrade>,
+}
+
+impl Scorecard {
+    pub fn from_report(report: &Report, config: &ScorecardConfig) -> Self {
+        let grade = |category: &str, score: Option<f64>, basis: String| CategoryGrade {
+            category: category.to_string(),
+            score: score.map(|score| score.clamp(0.0, 100.0)),
+            grade: score.map(|score| Self::letter(score, config)).unwrap_or("N/A").to_string(),
+            basis,
+        };
+
+        let maintainability = report.executive_summary.maintainability_score;
+        let violations = report.architecture_violations.len();
+        let architecture = grade("Architecture",
+            Some(maintainability * 10.0 - violations as f64 * config.architecture_violation_penalty),
+            format!("Maintainability {:.1}/10, {} layer violation(s)", maintainability, violations));
+
+        let cycles = report.dependency_analysis.circular_dependencies.len();
+        let dependencies = grade("Dependencies",
+            Some(100.0 - cycles as f64 * config.dependency_cycle_penalty),
+            format!("{} dependency cycle(s)", cycles));
+
+        let testing = &report.testing;
+        let tests = grade("Tests",
+            (testing.source_files > 0).then(|| testing.tested_sources as f64 / testing.source_files as f64 * 100.0),
+            format!("{} of {} source files have a matching test", testing.tested_sources, testing.source_files));
+
+        let docs = grade("Docs",
+            (report.documentation.total > 0).then_some(report.executive_summary.documentation_coverage),
+            format!("{:.0}% of public functions and classes have doc comments", report.executive_summary.documentation_coverage));
+
+        let security_recs: Vec<&Priority> = report.recommendations.iter()
+            .filter(|rec| rec.category == RecommendationCategory::Security)
+            .map(|rec| &rec.priority)
+            .collect();
+        let deduction: f64 = security_recs.iter().map(|priority| match priority {
+            Priority::Critical => config.security_critical_penalty,
+            Priority::High => config.security_high_penalty,
+            Priority::Medium => config.security_medium_penalty,
+            Priority::Low => 0.0,
+        }).sum();
+        let dynamic_queries: usize = report.data_access.files.iter().map(|file| file.dynamic_queries.len()).sum();
+        let mut basis = format!("{} security recommendation(s), {} SQL quer{} built by concatenation",
+            security_recs.len(), dynamic_queries, if dynamic_queries == 1 { "y" } else { "ies" });
+        if !report.metadata.llm_enabled {
+            basis.push_str("; local checks only, no LLM security review");
+        }
+        let security = grade("Security",
+            Some(100.0 - deduction - dynamic_queries as f64 * config.dynamic_query_penalty),
+            basis);
+
+        let categories = vec![architecture, dependencies, tests, docs, security];
+        let scores: Vec<f64> = categories.iter().filter_map(|category| category.score).collect();
+        let overall = grade("Overall",
+            (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64),
+            format!("Mean of {} graded categories", scores.len()));
+
+        Self {
+            project: report.metadata.project_name.clone(),
+            generated_at: report.metadata.generated_at.clone(),
+            overall,
+            categories,
+        }
+    }
+
+    fn letter(score: f64, config: &ScorecardConfig) -> &'static str {
+        if score >= config.grade_a {
+            "A"
+        } else if score >= config.grade_b {
+            "B"
+        } else if score >= config.grade_c {
+            "C"
+        } else if score >= config.grade_d {
+            "D"
+        } else {
+            "F"
+        }
+    }
+
+    fn score_text(category: &CategoryGrade) -> String {
+        category.score.map(|score| format!("{:.0}", score)).unwrap_or_else(|| "–".to_string())
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# Scorecard: {}\n\n**Overall: {}** ({}/100) · generated {}\n\n",
+            self.project, self.overall.grade, Self::score_text(&self.overall), self.generated_at);
+        md.push_str("| Category | Grade | Score | Based on |\n|----------|-------|-------|----------|\n");
+        for category in &self.categories {
+            md.push_str(&format!("| {} | **{}** | {} | {} |\n",
+                category.category, category.grade, Self::score_text(category), category.basis));
+        }
+        md.push_str("\nSee analysis_report.html for the findings behind each grade.\n");
+        md
+    }
+
+    pub fn to_html(&self) -> String {
+        let tile = |category: &CategoryGrade| format!("<span class=\"grade grade-{}\">{}</span>",
+            category.grade.replace('/', "").to_lowercase(), category.grade);
+        let mut body = format!(
+            "<div class=\"header\"><h1>Scorecard: {}</h1><p>{} Overall, {}/100 · generated {}</p></div>",
+            escape_xml(&self.project), tile(&self.overall), Self::score_text(&self.overall), escape_xml(&self.generated_at));
+        body.push_str("<div class=\"section\"><h2>Grades</h2><table><tr><th>Category</th><th>Grade</th><th>Score</th><th>Based on</th></tr>");
+        for category in &self.categories {
+            body.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_xml(&category.category), tile(category), Self::score_text(category), escape_xml(&category.basis)));
+        }
+        body.push_str("</table><p>See analysis_report.html for the findings behind each grade.</p></div>");
+        html_layout::page(&format!("Scorecard: {}", self.project), &body)
+    }
+
+    /// Write `scorecard.md` and `scorecard.html` to the output directory
+    pub fn export(&self, output_dir: &Path) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+
+        let md_path = output_dir.join("scorecard.md");
+        fs::write(&md_path, self.to_markdown()).map_err(|e| Error::report(&md_path, e))?;
+
+        let html_path = output_dir.join("scorecard.html");
+        fs::write(&html_path, self.to_html()).map_err(|e| Error::report(&html_path, e))?;
+
+        Ok(vec![md_path, html_path])
+    }
+}
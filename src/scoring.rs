@@ -0,0 +1,133 @@
+use crate::{
+    analyzer::ProjectAnalysis,
+    config::ScoringConfig,
+    simple_parser::ParsedFile,
+};
+use serde::{Deserialize, Serialize};
+
+/// Result of running the scoring model over a project, including every factor
+/// that contributed to the final numbers so the scores can be audited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    pub complexity_score: f64,
+    pub maintainability_score: f64,
+    pub factors: Vec<ScoreFactor>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreFactor {
+    pub name: String,
+    pub description: String,
+    pub score: ScoreKind,
+    pub raw_value: f64,
+    pub weight: f64,
+    pub contribution: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ScoreKind {
+    Complexity,
+    Maintainability,
+}
+
+/// Weighted scoring model driven by `[analysis.scoring]`.
+///
+/// Complexity is the weighted sum of per-file averages (functions, classes,
+/// imports, lines of code and the Halstead volume), capped at
+/// `max_complexity`. Maintainability starts at `base_maintainability` and is
+/// reduced by the complexity score and the graph's average degree.
+pub struct ScoringModel {
+    config: ScoringConfig,
+}
+
+impl Default for ScoringModel {
+    fn default() -> Self {
+        Self::new(ScoringConfig::default())
+    }
+}
+
+impl ScoringModel {
+    pub fn new(config: ScoringConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &ScoringConfig {
+        &self.config
+    }
+
+    pub fn score(&self, analysis: &ProjectAnalysis) -> ScoreBreakdown {
+        let mut factors = Vec::new();
+        let file_count = analysis.parsed_files.len();
+
+        let average = |f: &dyn Fn(&ParsedFile) -> f64| -> f64 {
+            if file_count == 0 {
+                0.0
+            } else {
+                analysis.parsed_files.iter().map(f).sum::<f64>() / file_count as f64
+            }
+        };
+
+        let complexity_inputs = [
+            ("functions", "Average functions per file", average(&|pf| pf.functions.len() as f64), self.config.function_weight),
+            ("classes", "Average classes/structs per file", average(&|pf| pf.classes.len() as f64), self.config.class_weight),
+            ("imports", "Average imports per file", average(&|pf| pf.imports.len() as f64), self.config.import_weight),
+            ("loc", "Average lines of code per file (per 100 lines)", average(&|pf| pf.lines_of_code as f64 / 100.0), self.config.loc_weight),
+            ("halstead_volume", "Average Halstead volume per file (per 100)", average(&|pf| Self::halstead_volume(pf) / 100.0), self.config.halstead_weight),
+        ];
+
+        let mut raw_complexity = 0.0;
+        for (name, description, raw_value, weight) in complexity_inputs {
+            let contribution = raw_value * weight;
+            raw_complexity += contribution;
+            factors.push(ScoreFactor {
+                name: name.to_string(),
+                description: description.to_string(),
+                score: ScoreKind::Complexity,
+                raw_value,
+                weight,
+                contribution,
+            });
+        }
+
+        let complexity_score = if file_count == 0 {
+            0.0
+        } else {
+            raw_complexity.min(self.config.max_complexity)
+        };
+
+        let coupling = analysis.dependency_analysis.avg_degree;
+        let complexity_penalty = complexity_score * self.config.complexity_penalty;
+        let coupling_penalty = coupling * self.config.coupling_penalty;
+
+        factors.push(ScoreFactor {
+            name: "complexity_penalty".to_string(),
+            description: "Complexity score deducted from the maintainability base".to_string(),
+            score: ScoreKind::Maintainability,
+            raw_value: complexity_score,
+            weight: self.config.complexity_penalty,
+            contribution: -complexity_penalty,
+        });
+        factors.push(ScoreFactor {
+            name: "coupling_penalty".to_string(),
+            description: "Average dependency graph degree deducted from the maintainability base".to_string(),
+            score: ScoreKind::Maintainability,
+            raw_value: coupling,
+            weight: self.config.coupling_penalty,
+            contribution: -coupling_penalty,
+        });
+
+        let maintainability_score =
+            (self.config.base_maintainability - complexity_penalty - coupling_penalty).max(0.0);
+
+        ScoreBreakdown {
+            complexity_score,
+            maintainability_score,
+            factors,
+        }
+    }
+
+    /// Halstead volume from the file's operator and operand tokens
+    pub fn halstead_volume(parsed_file: &ParsedFile) -> f64 {
+        parsed_file.halstead.volume()
+    }
+}
@@ -1,8 +1,28 @@
-use crate::file_discovery::FileInfo;
-use anyhow::Result;
+use crate::{file_discovery::FileInfo, sources::SourceEncoding};
+use crate::{Error, Result};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Lines after a declaration searched for the rest of a wrapped signature
+const SIGNATURE_LINES: usize = 10;
+
+/// Longest line, in bytes, parsed for symbols by default; minified bundles
+/// and generated data files go well past it
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 5000;
+
+/// Keywords counted as Halstead operators across the supported languages;
+/// other identifiers are operands
+const HALSTEAD_KEYWORDS: &[&str] = &[
+    "if", "else", "elif", "for", "while", "do", "loop", "switch", "case", "match", "default",
+    "return", "break", "continue", "yield", "await", "async", "try", "catch", "except", "finally",
+    "throw", "raise", "new", "delete", "in", "is", "not", "and", "or", "as", "fn", "def", "function",
+    "func", "class", "struct", "enum", "trait", "interface", "impl", "let", "var", "const", "mut",
+    "static", "pub", "public", "private", "protected", "import", "from", "export", "use", "mod",
+    "package", "where", "with", "lambda", "typeof", "instanceof", "extends", "implements", "go",
+    "defer", "select", "goto", "sizeof",
+];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParsedFile {
@@ -11,14 +31,126 @@ pub struct ParsedFile {
     pub exports: Vec<Export>,
     pub functions: Vec<Function>,
     pub classes: Vec<Class>,
+    #[serde(default)]
+    pub lines_of_code: usize,
+    #[serde(default)]
+    pub debt_markers: Vec<DebtMarker>,
+    /// Non-blank lines that are comments
+    #[serde(default)]
+    pub comment_lines: usize,
+    /// Approximate cyclomatic complexity: 1 + branch keywords and boolean operators
+    #[serde(default)]
+    pub cyclomatic_complexity: usize,
+    /// Set when the file wasn't UTF-8 and was decoded from another encoding
+    #[serde(default)]
+    pub encoding: Option<SourceEncoding>,
+    /// Length of the longest line, in bytes
+    #[serde(default)]
+    pub longest_line: usize,
+    /// The longest line exceeded the parser's limit, so only the line count was
+    /// recorded: no symbols, imports, comments or complexity
+    #[serde(default)]
+    pub size_only: bool,
+    /// Operator and operand counts over the code's tokens, comments excluded
+    #[serde(default)]
+    pub halstead: HalsteadCounts,
+}
+
+/// Halstead's token counts: operators are keywords and punctuation, operands are
+/// identifiers and literals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HalsteadCounts {
+    pub distinct_operators: usize,
+    pub distinct_operands: usize,
+    pub total_operators: usize,
+    pub total_operands: usize,
+}
+
+impl HalsteadCounts {
+    /// Volume `N * log2(n)`, where `N` is the program length (all operators and
+    /// operands) and `n` the vocabulary (distinct operators and operands)
+    pub fn volume(&self) -> f64 {
+        let length = (self.total_operators + self.total_operands) as f64;
+        let vocabulary = (self.distinct_operators + self.distinct_operands) as f64;
+        if vocabulary < 2.0 {
+            return 0.0;
+        }
+        length * vocabulary.log2()
+    }
+}
+
+/// Stage at which a file failed to parse, or why it was parsed with reduced fidelity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseErrorKind {
+    /// The file could not be read
+    Read,
+    /// The file is not valid UTF-8 and was decoded from UTF-16 or Latin-1
+    Encoding,
+    /// The parser rejected the contents
+    Parse,
+    /// A line exceeded `analysis.max_line_length`; only size metrics were recorded
+    LongLines,
+}
+
+impl ParseErrorKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ParseErrorKind::Read => "read",
+            ParseErrorKind::Encoding => "encoding",
+            ParseErrorKind::Parse => "parse",
+            ParseErrorKind::LongLines => "long lines",
+        }
+    }
+}
+
+/// A file that was discovered but could not be parsed, or was parsed with reduced fidelity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseError {
+    pub path: PathBuf,
+    pub language: Option<String>,
+    pub kind: ParseErrorKind,
+    pub message: String,
+    /// The file is still part of the analysis, with the metrics described in `message`
+    #[serde(default)]
+    pub degraded: bool,
+}
+
+impl ParseError {
+    pub fn new(file_info: &FileInfo, kind: ParseErrorKind, message: impl ToString) -> Self {
+        Self {
+            path: file_info.path.clone(),
+            language: file_info.language.clone(),
+            kind,
+            message: message.to_string(),
+            degraded: false,
+        }
+    }
+
+    /// Classify a read failure; invalid UTF-8 is reported as an encoding error
+    pub fn from_io(file_info: &FileInfo, error: &std::io::Error) -> Self {
+        let kind = match error.kind() {
+            std::io::ErrorKind::InvalidData => ParseErrorKind::Encoding,
+            _ => ParseErrorKind::Read,
+        };
+        Self::new(file_info, kind, error)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Import {
     pub module: String,
+    /// Named items as written, e.g. `a`, `b as c`, `* as ns`, `default as D`
     pub items: Vec<String>,
     pub is_default: bool,
     pub line_number: usize,
+    /// `export ... from` or `pub use`: the items become part of this file's exports
+    #[serde(default)]
+    pub is_reexport: bool,
+    /// Loaded at runtime rather than when the file loads: `import()`, `importlib`,
+    /// or a `require`/`import` inside a function body
+    #[serde(default)]
+    pub is_dynamic: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,8 +165,23 @@ pub struct Function {
     pub name: String,
     pub parameters: Vec<String>,
     pub return_type: Option<String>,
+    /// Generic parameters as written, e.g. `<T: Clone>`
+    #[serde(default)]
+    pub generics: Option<String>,
     pub line_number: usize,
     pub is_async: bool,
+    /// Preceded by a doc comment (`///`, `/** */`) or followed by a docstring
+    #[serde(default)]
+    pub is_documented: bool,
+    /// Text of the doc comment or docstring, without comment markers
+    #[serde(default)]
+    pub docstring: Option<String>,
+    /// Last line of the body (brace matching, or indentation for Python)
+    #[serde(default)]
+    pub end_line: usize,
+    /// Deepest block nesting inside the body; the body itself is level 0
+    #[serde(default)]
+    pub max_nesting: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,19 +191,138 @@ pub struct Class {
     pub implements: Vec<String>,
     pub methods: Vec<Function>,
     pub line_number: usize,
+    #[serde(default)]
+    pub is_abstract: bool,
+    #[serde(default)]
+    pub is_documented: bool,
+    /// Text of the doc comment or docstring, without comment markers
+    #[serde(default)]
+    pub docstring: Option<String>,
+    #[serde(default)]
+    pub end_line: usize,
+}
+
+/// A TODO/FIXME/HACK/XXX comment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebtMarker {
+    pub kind: String,
+    pub text: String,
+    pub line_number: usize,
 }
 
 pub struct SimpleParser {
     language_patterns: HashMap<String, LanguagePatterns>,
+    debt_marker_pattern: Regex,
+    decision_pattern: Regex,
+    halstead_token_pattern: Regex,
+    max_line_length: usize,
 }
 
 struct LanguagePatterns {
     import_patterns: Vec<Regex>,
+    /// Calls that load a module at runtime
+    dynamic_import_patterns: Vec<Regex>,
     export_patterns: Vec<Regex>,
     function_patterns: Vec<Regex>,
     class_patterns: Vec<Regex>,
 }
 
+/// What follows a function's name in its declaration
+#[derive(Default)]
+struct SignatureParts {
+    generics: Option<String>,
+    /// Text between the parentheses
+    parameters: Option<String>,
+    return_type: Option<String>,
+}
+
+impl Function {
+    /// `name<generics>(params) -> return`, with `async` when it applies
+    pub fn signature(&self) -> String {
+        let returns = self.return_type.as_deref().map(|t| format!(" -> {}", t)).unwrap_or_default();
+        format!("{}{}{}({}){}", if self.is_async { "async " } else { "" }, self.name,
+            self.generics.as_deref().unwrap_or_default(), self.parameters.join(", "), returns)
+    }
+
+    /// First line of the docstring
+    pub fn summary(&self) -> Option<&str> {
+        self.docstring.as_deref().and_then(|doc| doc.lines().next())
+    }
+}
+
+impl Class {
+    /// First line of the docstring
+    pub fn summary(&self) -> Option<&str> {
+        self.docstring.as_deref().and_then(|doc| doc.lines().next())
+    }
+}
+
+impl Import {
+    /// Named items as (name in the imported module, name bound here). Rust paths
+    /// carry their items, so they come from the last segment or braced group.
+    /// Empty for whole-module and glob imports.
+    pub fn names(&self) -> Vec<(&str, &str)> {
+        let items: Vec<&str> = if !self.items.is_empty() {
+            self.items.iter().map(String::as_str).collect()
+        } else if self.module.contains("::") {
+            match self.module.split_once('{') {
+                Some((_, group)) => group.trim_end_matches('}').split(',').map(str::trim).collect(),
+                None => self.module.rsplit("::").next().into_iter().collect(),
+            }
+        } else {
+            Vec::new()
+        };
+        items.into_iter()
+            .filter(|item| !item.is_empty() && !matches!(*item, "*" | "self"))
+            .map(|item| match item.split_once(" as ") {
+                Some((name, alias)) => (name.trim(), alias.trim()),
+                None => (item, item),
+            })
+            .collect()
+    }
+}
+
+impl ParsedFile {
+    /// Reduced-fidelity parsing of this file, for the parse error report
+    pub fn degradations(&self) -> Vec<ParseError> {
+        let mut degradations = Vec::new();
+        if let Some(encoding) = self.encoding {
+            degradations.push(ParseError {
+                degraded: true,
+                ..ParseError::new(&self.file_info, ParseErrorKind::Encoding, format!("not UTF-8; decoded as {}", encoding.name()))
+            });
+        }
+        if self.size_only {
+            degradations.push(ParseError {
+                degraded: true,
+                ..ParseError::new(&self.file_info, ParseErrorKind::LongLines,
+                    format!("longest line is {} bytes; only the line count was recorded", self.longest_line))
+            });
+        }
+        degradations
+    }
+
+    /// Drop details that only the LLM context and symbol listings use (return
+    /// types, docstrings) and release spare capacity; counts, spans, parameters
+    /// and imported items stay so metrics, smells and the graph are unaffected
+    pub fn compact(&mut self) {
+        for function in self.functions.iter_mut().chain(self.classes.iter_mut().flat_map(|c| c.methods.iter_mut())) {
+            function.return_type = None;
+            function.generics = None;
+            function.docstring = None;
+            function.parameters.shrink_to_fit();
+        }
+        for class in &mut self.classes {
+            class.docstring = None;
+        }
+        self.imports.shrink_to_fit();
+        self.exports.shrink_to_fit();
+        self.functions.shrink_to_fit();
+        self.classes.shrink_to_fit();
+        self.debt_markers.shrink_to_fit();
+    }
+}
+
 impl SimpleParser {
     pub fn new() -> Result<Self> {
         let mut language_patterns = HashMap::new();
@@ -66,7 +332,11 @@ impl SimpleParser {
             import_patterns: vec![
                 Regex::new(r#"import\s+.*?\s+from\s+['"]([^'"]+)['"]"#)?,
                 Regex::new(r#"import\s+['"]([^'"]+)['"]"#)?,
-                Regex::new(r#"const\s+.*?\s*=\s*require\s*\(\s*['"]([^'"]+)['"]"#)?,
+                Regex::new(r#"export\s+(?:type\s+)?(?:\*(?:\s+as\s+\w+)?|\{[^}]*\})\s*from\s+['"]([^'"]+)['"]"#)?,
+                Regex::new(r#"\brequire\s*\(\s*['"]([^'"]+)['"]"#)?,
+            ],
+            dynamic_import_patterns: vec![
+                Regex::new(r#"\bimport\s*\(\s*['"`]([^'"`]+)['"`]"#)?,
             ],
             export_patterns: vec![
                 Regex::new(r"export\s+(function|class|const|let|var)\s+(\w+)")?,
@@ -74,10 +344,11 @@ impl SimpleParser {
                 Regex::new(r"export\s*\{\s*([^}]+)\s*\}")?,
             ],
             function_patterns: vec![
-                Regex::new(r"function\s+(\w+)\s*\(([^)]*)\)")?,
-                Regex::new(r"(\w+)\s*:\s*function\s*\(([^)]*)\)")?,
-                Regex::new(r"(\w+)\s*=>\s*")?,
-                Regex::new(r"(async\s+)?function\s+(\w+)")?,
+                Regex::new(r"function\s+(?P<name>\w+)\s*(?:<.*?>)?\s*\((?P<params>[^)]*)")?,
+                Regex::new(r"(?P<name>\w+)\s*:\s*function\s*\((?P<params>[^)]*)\)")?,
+                Regex::new(r"(?:const|let|var)\s+(?P<name>\w+)\s*=\s*(?:async\s+)?(?:<[^>]*>\s*)?\((?P<params>[^)]*)\)\s*(?::[^=]*)?=>")?,
+                Regex::new(r"(?P<name>\w+)\s*=>\s*")?,
+                Regex::new(r"(async\s+)?function\s+(?P<name>\w+)")?,
             ],
             class_patterns: vec![
                 Regex::new(r"class\s+(\w+)(?:\s+extends\s+(\w+))?")?,
@@ -93,12 +364,16 @@ impl SimpleParser {
                 Regex::new(r"from\s+([^\s]+)\s+import")?,
                 Regex::new(r"import\s+([^\s,]+)")?,
             ],
+            dynamic_import_patterns: vec![
+                Regex::new(r#"\b(?:importlib\.)?import_module\s*\(\s*['"]([^'"]+)['"]"#)?,
+                Regex::new(r#"\b__import__\s*\(\s*['"]([^'"]+)['"]"#)?,
+            ],
             export_patterns: vec![
                 Regex::new(r"__all__\s*=\s*\[([^\]]+)\]")?,
             ],
             function_patterns: vec![
-                Regex::new(r"def\s+(\w+)\s*\(([^)]*)\)")?,
-                Regex::new(r"async\s+def\s+(\w+)\s*\(([^)]*)\)")?,
+                Regex::new(r"def\s+(?P<name>\w+)\s*\((?P<params>[^)]*)")?,
+                Regex::new(r"async\s+def\s+(?P<name>\w+)\s*\((?P<params>[^)]*)")?,
             ],
             class_patterns: vec![
                 Regex::new(r"class\s+(\w+)(?:\(([^)]+)\))?")?,
@@ -111,13 +386,14 @@ impl SimpleParser {
                 Regex::new(r"use\s+([^;]+);")?,
                 Regex::new(r"extern\s+crate\s+(\w+)")?,
             ],
+            dynamic_import_patterns: Vec::new(),
             export_patterns: vec![
                 Regex::new(r"pub\s+(fn|struct|enum|trait|mod)\s+(\w+)")?,
             ],
             function_patterns: vec![
-                Regex::new(r"fn\s+(\w+)\s*\(([^)]*)\)")?,
-                Regex::new(r"pub\s+fn\s+(\w+)\s*\(([^)]*)\)")?,
-                Regex::new(r"async\s+fn\s+(\w+)")?,
+                Regex::new(r"fn\s+(?P<name>\w+)\s*(?:<.*?>)?\s*\((?P<params>[^)]*)")?,
+                Regex::new(r"pub\s+fn\s+(?P<name>\w+)\s*(?:<.*?>)?\s*\((?P<params>[^)]*)")?,
+                Regex::new(r"async\s+fn\s+(?P<name>\w+)")?,
             ],
             class_patterns: vec![
                 Regex::new(r"struct\s+(\w+)")?,
@@ -125,13 +401,50 @@ impl SimpleParser {
                 Regex::new(r"trait\s+(\w+)")?,
             ],
         });
-        
-        Ok(Self { language_patterns })
+
+        // Shell patterns: sourced files and invocations of other scripts are the imports;
+        // everything a sourced script defines is visible, so there are no exports
+        language_patterns.insert("bash".to_string(), LanguagePatterns {
+            import_patterns: vec![
+                Regex::new(r#"^\s*(?:source|\.)\s+(["']?(?:\$\([^)]*\)|\$\{[^}]*\}|\$\w+)?[^\s;|&)"']*)"#)?,
+                Regex::new(r#"(?:^|[;&|(]|\bthen\b|\bdo\b|\belse\b)\s*(?:(?:bash|sh|zsh|exec|sudo|time)\s+)?(["']?(?:\$\([^)]*\)|\$\{[^}]*\}|\$\w+)?[\w./-]*\.(?:sh|bash))\b"#)?,
+            ],
+            dynamic_import_patterns: Vec::new(),
+            export_patterns: Vec::new(),
+            function_patterns: vec![
+                Regex::new(r"^\s*function\s+(?P<name>[\w:.-]+)")?,
+                Regex::new(r"^\s*(?P<name>[\w:.-]+)\s*\(\s*\)")?,
+            ],
+            class_patterns: Vec::new(),
+        });
+
+        // Only match markers that follow a comment token, not identifiers like `todo_list`
+        let debt_marker_pattern = Regex::new(r"(?://|#|/\*|\*|--|<!--)\s*(?P<kind>TODO|FIXME|HACK|XXX)(?:\([^)]*\))?(?::|\s|$)\s*(?P<text>.*)")?;
+
+        let decision_pattern = Regex::new(r"\b(?:if|elif|for|while|case|catch|except)\b|&&|\|\||\band\b|\bor\b|=>")?;
+
+        let halstead_token_pattern = Regex::new(concat!(
+            r#""(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'|[A-Za-z_$][A-Za-z0-9_$]*|\d[\w.]*"#,
+            r"|==|!=|<=|>=|&&|\|\||->|=>|::|\+=|-=|\*=|/=|<<|>>|\+\+|--|[-+*/%=<>!&|^~?:;,.@#(){}\[\]]",
+        ))?;
+        Ok(Self { language_patterns, debt_marker_pattern, decision_pattern, halstead_token_pattern, max_line_length: DEFAULT_MAX_LINE_LENGTH })
+    }
+
+    /// Files with a longer line get size-only metrics; 0 parses every file in full
+    pub fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = max_line_length;
+        self
     }
 
     pub fn parse_file(&self, file_info: &FileInfo) -> Result<ParsedFile> {
-        let content = std::fs::read_to_string(&file_info.path)?;
-        
+        let (content, encoding) = SourceEncoding::decode(std::fs::read(&file_info.path).map_err(|e| Error::parse(&file_info.path, e))?);
+        let mut parsed_file = self.parse_source(file_info, &content)?;
+        parsed_file.encoding = Some(encoding).filter(|encoding| *encoding != SourceEncoding::Utf8);
+        Ok(parsed_file)
+    }
+
+    /// Parse file contents that are already in memory
+    pub fn parse_source(&self, file_info: &FileInfo, content: &str) -> Result<ParsedFile> {
         let default_language = "unknown".to_string();
         let language = file_info.language.as_ref()
             .unwrap_or(&default_language);
@@ -144,39 +457,162 @@ impl SimpleParser {
             exports: Vec::new(),
             functions: Vec::new(),
             classes: Vec::new(),
+            lines_of_code: content.lines().filter(|l| !l.trim().is_empty()).count(),
+            debt_markers: Vec::new(),
+            comment_lines: 0,
+            cyclomatic_complexity: 0,
+            halstead: HalsteadCounts::default(),
+            encoding: None,
+            longest_line: content.lines().map(str::len).max().unwrap_or(0),
+            size_only: false,
         };
 
+        // Line-oriented patterns find nothing sensible in minified code and are slow on huge lines
+        if self.max_line_length > 0 && parsed_file.longest_line > self.max_line_length {
+            parsed_file.size_only = true;
+            parsed_file.cyclomatic_complexity = 1;
+            return Ok(parsed_file);
+        }
+
+        self.extract_debt_markers(content, &mut parsed_file);
+        self.measure_complexity(content, language, &mut parsed_file);
+
         if let Some(patterns) = patterns {
-            self.extract_imports(&content, patterns, &mut parsed_file)?;
-            self.extract_exports(&content, patterns, &mut parsed_file)?;
-            self.extract_functions(&content, patterns, &mut parsed_file)?;
-            self.extract_classes(&content, patterns, &mut parsed_file)?;
+            self.extract_imports(content, patterns, &mut parsed_file)?;
+            self.extract_exports(content, patterns, &mut parsed_file)?;
+            self.extract_functions(content, patterns, &mut parsed_file)?;
+            self.extract_classes(content, patterns, &mut parsed_file)?;
         } else {
             // Fallback: basic pattern matching for unknown languages
-            self.extract_basic_patterns(&content, &mut parsed_file)?;
+            self.extract_basic_patterns(content, &mut parsed_file)?;
+        }
+
+        self.mark_documented(content, language, &mut parsed_file);
+        if patterns.is_some() {
+            self.measure_spans(content, language, &mut parsed_file);
+            if language != "rust" {
+                Self::mark_lazy_imports(&mut parsed_file);
+            }
         }
 
         Ok(parsed_file)
     }
 
     fn extract_imports(&self, content: &str, patterns: &LanguagePatterns, parsed_file: &mut ParsedFile) -> Result<()> {
+        let javascript = matches!(parsed_file.file_info.language.as_deref(), Some("javascript" | "typescript"));
+        let shell = parsed_file.file_info.language.as_deref() == Some("bash");
         for (line_num, line) in content.lines().enumerate() {
+            if shell && line.trim_start().starts_with('#') {
+                continue;
+            }
             for pattern in &patterns.import_patterns {
                 if let Some(captures) = pattern.captures(line) {
                     if let Some(module) = captures.get(1) {
+                        let module = if shell {
+                            match Self::shell_module(module.as_str()) {
+                                Some(module) => module,
+                                None => continue,
+                            }
+                        } else {
+                            module.as_str().to_string()
+                        };
+                        let (items, is_default) = if javascript { Self::clause_items(line) } else { (Vec::new(), false) };
                         parsed_file.imports.push(Import {
-                            module: module.as_str().to_string(),
-                            items: Vec::new(),
-                            is_default: false,
+                            module,
+                            items,
+                            is_default,
                             line_number: line_num + 1,
+                            is_reexport: !shell && Self::is_reexport(line),
+                            is_dynamic: false,
                         });
                     }
                 }
             }
+            for pattern in &patterns.dynamic_import_patterns {
+                for captures in pattern.captures_iter(line) {
+                    parsed_file.imports.push(Import {
+                        module: captures[1].to_string(),
+                        items: Vec::new(),
+                        is_default: false,
+                        line_number: line_num + 1,
+                        is_reexport: false,
+                        is_dynamic: true,
+                    });
+                }
+            }
         }
         Ok(())
     }
 
+    /// A sourced or invoked script as an import module. Paths under the script's
+    /// own directory (`$(dirname "$0")`, `${BASH_SOURCE%/*}`, `$SCRIPT_DIR`)
+    /// become `./` relative ones; plain paths are relative to the working
+    /// directory, usually the project root, so they're matched like module
+    /// paths. Paths built from other variables, and absolute paths, can't be
+    /// resolved.
+    fn shell_module(path: &str) -> Option<String> {
+        let path = path.trim_matches(['"', '\'']);
+        if let Some(expansion) = path.strip_prefix('$') {
+            let end = match expansion.chars().next()? {
+                '(' => expansion.find(')')? + 1,
+                '{' => expansion.find('}')? + 1,
+                _ => expansion.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(expansion.len()),
+            };
+            let (variable, rest) = expansion.split_at(end);
+            let script_directory = variable.contains("dirname") || variable.contains("BASH_SOURCE")
+                || ["DIR", "HERE"].iter().any(|name| variable.trim_matches(['{', '}']).ends_with(name));
+            return (script_directory && rest.starts_with('/') && !rest.contains('$')).then(|| format!(".{}", rest));
+        }
+        let path = path.trim_start_matches("./");
+        (!path.is_empty() && !path.contains('$') && !path.starts_with('/')).then(|| path.to_string())
+    }
+
+    /// Items of a JavaScript `import ... from` or `export ... from` clause as written;
+    /// the flag is set when it binds the default export
+    fn clause_items(line: &str) -> (Vec<String>, bool) {
+        let line = line.trim_start();
+        let Some(clause) = line.strip_prefix("import").or_else(|| line.strip_prefix("export")) else { return (Vec::new(), false) };
+        let Some((clause, _)) = clause.rsplit_once(" from") else { return (Vec::new(), false) };
+        let clause = clause.trim().trim_start_matches("type ").trim();
+
+        let (outside, group) = match (clause.find('{'), clause.rfind('}')) {
+            (Some(open), Some(close)) if open < close => (format!("{}{}", &clause[..open], &clause[close + 1..]), Some(&clause[open + 1..close])),
+            _ => (clause.to_string(), None),
+        };
+        let mut items = Vec::new();
+        let mut is_default = false;
+        for part in outside.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            if part.starts_with('*') {
+                items.push(Self::collapse_whitespace(part));
+            } else {
+                is_default = true;
+                items.push(format!("default as {}", part));
+            }
+        }
+        items.extend(group.into_iter()
+            .flat_map(|group| group.split(','))
+            .map(|item| Self::collapse_whitespace(item.trim().trim_start_matches("type ")))
+            .filter(|item| !item.is_empty()));
+        (items, is_default)
+    }
+
+    /// `require` and Python `import` statements inside a function body only run
+    /// when the function is called
+    fn mark_lazy_imports(parsed_file: &mut ParsedFile) {
+        let bodies: Vec<(usize, usize)> = parsed_file.functions.iter()
+            .chain(parsed_file.classes.iter().flat_map(|class| class.methods.iter()))
+            .map(|function| (function.line_number, function.end_line))
+            .collect();
+        for import in &mut parsed_file.imports {
+            import.is_dynamic |= bodies.iter().any(|&(start, end)| import.line_number > start && import.line_number <= end);
+        }
+    }
+
+    fn is_reexport(line: &str) -> bool {
+        let line = line.trim_start();
+        line.starts_with("export") || (line.starts_with("pub") && line.contains(" use "))
+    }
+
     fn extract_exports(&self, content: &str, patterns: &LanguagePatterns, parsed_file: &mut ParsedFile) -> Result<()> {
         for (line_num, line) in content.lines().enumerate() {
             for pattern in &patterns.export_patterns {
@@ -195,41 +631,129 @@ impl SimpleParser {
     }
 
     fn extract_functions(&self, content: &str, patterns: &LanguagePatterns, parsed_file: &mut ParsedFile) -> Result<()> {
-        for (line_num, line) in content.lines().enumerate() {
+        let language = parsed_file.file_info.language.clone().unwrap_or_default();
+        let lines: Vec<&str> = content.lines().collect();
+        for (line_num, line) in lines.iter().enumerate() {
             for pattern in &patterns.function_patterns {
                 if let Some(captures) = pattern.captures(line) {
                     let is_async = line.contains("async");
-                    let name = if captures.len() > 2 {
-                        captures.get(2).map(|m| m.as_str()).unwrap_or("unknown")
-                    } else {
-                        captures.get(1).map(|m| m.as_str()).unwrap_or("unknown")
-                    };
+                    let name = captures.name("name").map(|m| m.as_str()).unwrap_or("unknown");
                     
-                    let params = if captures.len() > 2 {
-                        captures.get(captures.len() - 1)
-                    } else {
-                        captures.get(2)
-                    };
-                    
-                    let parameters = if let Some(params) = params {
-                        self.parse_parameters(params.as_str())
-                    } else {
-                        Vec::new()
+                    // Signatures may wrap, so read on past the declaration line
+                    let name_end = captures.name("name").map(|m| m.end()).unwrap_or(line.len());
+                    let signature: Vec<&str> = std::iter::once(&line[name_end..])
+                        .chain(lines.iter().skip(line_num + 1).take(SIGNATURE_LINES).copied())
+                        .collect();
+                    let signature = Self::split_signature(&language, &signature.join(" "));
+
+                    let parameters = match (&signature.parameters, captures.name("params")) {
+                        (Some(params), _) => self.parse_parameters(params),
+                        (None, Some(params)) => self.parse_parameters(params.as_str()),
+                        (None, None) => Vec::new(),
                     };
 
                     parsed_file.functions.push(Function {
                         name: name.to_string(),
                         parameters,
-                        return_type: None,
+                        return_type: signature.return_type,
+                        generics: signature.generics,
                         line_number: line_num + 1,
                         is_async,
+                        is_documented: false,
+                        docstring: None,
+                        end_line: line_num + 1,
+                        max_nesting: 0,
                     });
+
+                    // Patterns overlap (e.g. `fn` and `pub fn`), keep the first match per line
+                    break;
                 }
             }
         }
         Ok(())
     }
 
+    /// Generic parameters, parameter list and return type from the text following
+    /// a function's name: `<T>(params) -> T` in Rust and Python, `<T>(params): T`
+    /// in TypeScript
+    fn split_signature(language: &str, after_name: &str) -> SignatureParts {
+        let mut parts = SignatureParts::default();
+        let mut rest = after_name.trim_start();
+        // `name = async (`, `name: function (`
+        for prefix in ["=", ":", "async", "function"] {
+            if let Some(stripped) = rest.strip_prefix(prefix) {
+                rest = stripped.trim_start();
+            }
+        }
+
+        if rest.starts_with('<') {
+            let Some(end) = Self::closing_bracket(rest) else { return parts };
+            parts.generics = Some(Self::collapse_whitespace(&rest[..=end]));
+            rest = rest[end + 1..].trim_start();
+        }
+        if !rest.starts_with('(') {
+            return parts;
+        }
+        let Some(end) = Self::closing_bracket(rest) else { return parts };
+        parts.parameters = Some(rest[1..end].to_string());
+        let after = rest[end + 1..].trim_start();
+
+        let (return_type, stops): (&str, &[&str]) = if let Some(return_type) = after.strip_prefix("->") {
+            (return_type, if language == "python" { &[":"] } else { &["{", ";", " where "] })
+        } else if let (Some(return_type), true) = (after.strip_prefix(':'), matches!(language, "typescript" | "javascript")) {
+            (return_type, &["{", ";", "=>"])
+        } else {
+            return parts;
+        };
+        let return_type = Self::collapse_whitespace(Self::until_top_level(return_type.trim_start(), stops));
+        parts.return_type = Some(return_type).filter(|t| !t.is_empty());
+        parts
+    }
+
+    /// Byte index of the bracket closing the one `text` starts with; the `>` of
+    /// `->` and `=>` doesn't count
+    fn closing_bracket(text: &str) -> Option<usize> {
+        let mut depth = 0usize;
+        let mut previous = ' ';
+        for (index, c) in text.char_indices() {
+            match c {
+                '(' | '[' | '{' | '<' => depth += 1,
+                ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                '>' if previous != '-' && previous != '=' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            if depth == 0 {
+                return Some(index);
+            }
+            previous = c;
+        }
+        None
+    }
+
+    /// `text` up to the first of `stops` outside any brackets; a leading `{` opens
+    /// an object type rather than the body
+    fn until_top_level<'a>(text: &'a str, stops: &[&str]) -> &'a str {
+        let mut depth = 0usize;
+        let mut previous = ' ';
+        for (index, c) in text.char_indices() {
+            if depth == 0 && index > 0 && stops.iter().any(|stop| text[index..].starts_with(stop)) {
+                return &text[..index];
+            }
+            match c {
+                '(' | '[' | '{' | '<' => depth += 1,
+                ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                '>' if previous != '-' && previous != '=' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            previous = c;
+        }
+        text
+    }
+
+    fn collapse_whitespace(text: &str) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
     fn extract_classes(&self, content: &str, patterns: &LanguagePatterns, parsed_file: &mut ParsedFile) -> Result<()> {
         for (line_num, line) in content.lines().enumerate() {
             for pattern in &patterns.class_patterns {
@@ -243,6 +767,10 @@ impl SimpleParser {
                             implements: Vec::new(),
                             methods: Vec::new(),
                             line_number: line_num + 1,
+                            is_abstract: Self::is_abstract_declaration(line),
+                            is_documented: false,
+                            docstring: None,
+                            end_line: line_num + 1,
                         });
                     }
                 }
@@ -251,6 +779,242 @@ impl SimpleParser {
         Ok(())
     }
 
+    fn mark_documented(&self, content: &str, language: &str, parsed_file: &mut ParsedFile) {
+        let lines: Vec<&str> = content.lines().collect();
+        let doc = |line_number: usize| {
+            if language == "python" {
+                Self::docstring(&lines, line_number)
+            } else if language == "bash" {
+                Self::shell_comment(&lines, line_number)
+            } else {
+                Self::doc_comment(&lines, line_number)
+            }
+        };
+        let document = |function: &mut Function| {
+            let docstring = doc(function.line_number);
+            function.is_documented = docstring.is_some();
+            function.docstring = docstring.filter(|text| !text.is_empty());
+        };
+
+        for function in &mut parsed_file.functions {
+            document(function);
+        }
+        for class in &mut parsed_file.classes {
+            let docstring = doc(class.line_number);
+            class.is_documented = docstring.is_some();
+            class.docstring = docstring.filter(|text| !text.is_empty());
+            for method in &mut class.methods {
+                document(method);
+            }
+        }
+    }
+
+    /// Rustdoc or JSDoc directly above the declaration, skipping attributes and decorators
+    fn doc_comment(lines: &[&str], line_number: usize) -> Option<String> {
+        let above: Vec<&str> = lines.iter()
+            .take(line_number.saturating_sub(1))
+            .rev()
+            .map(|line| line.trim())
+            .skip_while(|line| line.starts_with("#[") || line.starts_with('@'))
+            .collect();
+        let first = above.first()?;
+        let mut text: Vec<&str> = if first.starts_with("///") {
+            above.iter()
+                .take_while(|line| line.starts_with("///"))
+                .map(|line| line.trim_start_matches('/').trim())
+                .collect()
+        } else if first.ends_with("*/") {
+            let start = above.iter().position(|line| line.contains("/*")).unwrap_or(0);
+            above[..=start].iter()
+                .map(|line| line.trim_end_matches("*/").trim_start_matches("/**").trim_start_matches("/*").trim_start_matches('*').trim())
+                .collect()
+        } else {
+            return None;
+        };
+        text.reverse();
+        Some(text.join("\n").trim().to_string())
+    }
+
+    /// `#` comment lines directly above a shell function, not counting the shebang
+    fn shell_comment(lines: &[&str], line_number: usize) -> Option<String> {
+        let mut text: Vec<&str> = lines.iter()
+            .take(line_number.saturating_sub(1))
+            .rev()
+            .map(|line| line.trim())
+            .take_while(|line| line.starts_with('#') && !line.starts_with("#!"))
+            .map(|line| line.trim_start_matches('#').trim())
+            .collect();
+        if text.is_empty() {
+            return None;
+        }
+        text.reverse();
+        Some(text.join("\n").trim().to_string())
+    }
+
+    /// Python docstring: a string literal as the first statement after the
+    /// (possibly multi-line) signature
+    fn docstring(lines: &[&str], line_number: usize) -> Option<String> {
+        let mut body = lines.iter().skip(line_number.saturating_sub(1)).map(|line| line.trim());
+        if !body.by_ref().take(10).any(|line| line.ends_with(':')) {
+            return None;
+        }
+        let first = body.find(|line| !line.is_empty())?.trim_start_matches(['r', 'u', 'R', 'U']);
+        let quote = ["\"\"\"", "'''", "\"", "'"].into_iter().find(|quote| first.starts_with(quote))?;
+        let rest = &first[quote.len()..];
+        if let Some(end) = rest.find(quote) {
+            return Some(rest[..end].trim().to_string());
+        }
+        let mut text = vec![rest.trim()];
+        for line in body {
+            match line.find(quote) {
+                Some(end) => {
+                    text.push(line[..end].trim());
+                    break;
+                }
+                None => text.push(line),
+            }
+        }
+        Some(text.join("\n").trim().to_string())
+    }
+
+    /// Find where each function and class body ends and how deeply function bodies nest
+    fn measure_spans(&self, content: &str, language: &str, parsed_file: &mut ParsedFile) {
+        let lines: Vec<&str> = content.lines().collect();
+        let span = |line_number: usize| if language == "python" {
+            Self::indented_block(&lines, line_number)
+        } else {
+            Self::braced_block(&lines, line_number)
+        };
+
+        for function in &mut parsed_file.functions {
+            (function.end_line, function.max_nesting) = span(function.line_number);
+        }
+        for class in &mut parsed_file.classes {
+            class.end_line = span(class.line_number).0;
+        }
+    }
+
+    /// End line and nesting depth of a `{ ... }` block starting on `line_number`;
+    /// declarations that end with `;` before any `{` span a single line
+    fn braced_block(lines: &[&str], line_number: usize) -> (usize, usize) {
+        let mut depth = 0usize;
+        let mut max_depth = 0usize;
+        let mut opened = false;
+
+        for (offset, line) in lines.iter().skip(line_number.saturating_sub(1)).enumerate() {
+            for c in line.chars() {
+                match c {
+                    '{' => {
+                        depth += 1;
+                        max_depth = max_depth.max(depth);
+                        opened = true;
+                    }
+                    '}' if depth > 0 => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return (line_number + offset, max_depth - 1);
+                        }
+                    }
+                    ';' if !opened => return (line_number + offset, 0),
+                    _ => {}
+                }
+            }
+        }
+        (lines.len().max(line_number), max_depth.saturating_sub(1))
+    }
+
+    /// End line and nesting depth of an indentation block whose header starts on `line_number`
+    fn indented_block(lines: &[&str], line_number: usize) -> (usize, usize) {
+        let indent_of = |line: &str| line.len() - line.trim_start().len();
+        let Some(header) = lines.get(line_number.saturating_sub(1)) else {
+            return (line_number, 0);
+        };
+        let header_indent = indent_of(header);
+
+        let mut end_line = line_number;
+        let mut levels: Vec<usize> = Vec::new();
+        let mut max_nesting = 0;
+        for (offset, line) in lines.iter().enumerate().skip(line_number) {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let indent = indent_of(line);
+            if indent <= header_indent {
+                break;
+            }
+            while levels.last().is_some_and(|&level| level > indent) {
+                levels.pop();
+            }
+            if levels.last() != Some(&indent) {
+                levels.push(indent);
+            }
+            max_nesting = max_nesting.max(levels.len() - 1);
+            end_line = offset + 1;
+        }
+        (end_line, max_nesting)
+    }
+
+    /// Count comment lines, decision points and Halstead tokens; code inside
+    /// comments is not counted
+    fn measure_complexity(&self, content: &str, language: &str, parsed_file: &mut ParsedFile) {
+        let hash_comments = matches!(language, "python" | "ruby" | "shell" | "bash" | "yaml" | "toml");
+        let mut in_block_comment = false;
+        let mut decisions = 0;
+        let mut operators: HashSet<&str> = HashSet::new();
+        let mut operands: HashSet<&str> = HashSet::new();
+
+        for line in content.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let is_comment = in_block_comment
+                || line.starts_with("//")
+                || line.starts_with("/*")
+                || (hash_comments && line.starts_with('#'));
+
+            if line.starts_with("/*") || in_block_comment {
+                in_block_comment = !line.contains("*/");
+            }
+
+            if is_comment {
+                parsed_file.comment_lines += 1;
+            } else {
+                // Rust match arms (`=>`) count as branches; JS arrow functions use `=>` too, so only count it for Rust
+                decisions += self.decision_pattern.find_iter(line)
+                    .filter(|m| m.as_str() != "=>" || language == "rust")
+                    .filter(|m| !matches!(m.as_str(), "and" | "or") || language == "python")
+                    .count();
+
+                for token in self.halstead_token_pattern.find_iter(line).map(|m| m.as_str()) {
+                    let first = token.chars().next().unwrap_or_default();
+                    let is_operand = (first.is_alphanumeric() || matches!(first, '_' | '$' | '"' | '\''))
+                        && !HALSTEAD_KEYWORDS.contains(&token);
+                    if is_operand {
+                        operands.insert(token);
+                        parsed_file.halstead.total_operands += 1;
+                    } else {
+                        operators.insert(token);
+                        parsed_file.halstead.total_operators += 1;
+                    }
+                }
+            }
+        }
+
+        parsed_file.cyclomatic_complexity = 1 + decisions;
+        parsed_file.halstead.distinct_operators = operators.len();
+        parsed_file.halstead.distinct_operands = operands.len();
+    }
+
+    fn extract_debt_markers(&self, content: &str, parsed_file: &mut ParsedFile) {
+        for (line_num, line) in content.lines().enumerate() {
+            if let Some(captures) = self.debt_marker_pattern.captures(line) {
+                let text = captures["text"].trim().trim_end_matches("*/").trim_end_matches("-->").trim();
+                parsed_file.debt_markers.push(DebtMarker {
+                    kind: captures["kind"].to_string(),
+                    text: text.to_string(),
+                    line_number: line_num + 1,
+                });
+            }
+        }
+    }
+
     fn extract_basic_patterns(&self, content: &str, parsed_file: &mut ParsedFile) -> Result<()> {
         // Basic patterns that work across languages
         let import_patterns = [
@@ -275,6 +1039,8 @@ impl SimpleParser {
                                 items: Vec::new(),
                                 is_default: false,
                                 line_number: line_num + 1,
+                                is_reexport: false,
+                                is_dynamic: false,
                             });
                         }
                     }
@@ -290,8 +1056,13 @@ impl SimpleParser {
                                 name: name.as_str().to_string(),
                                 parameters: Vec::new(),
                                 return_type: None,
+                                generics: None,
                                 line_number: line_num + 1,
                                 is_async: line.contains("async"),
+                                is_documented: false,
+                                docstring: None,
+                                end_line: line_num + 1,
+                                max_nesting: 0,
                             });
                         }
                     }
@@ -302,9 +1073,26 @@ impl SimpleParser {
         Ok(())
     }
 
+    fn is_abstract_declaration(line: &str) -> bool {
+        let line = line.trim_start();
+        ["trait ", "pub trait ", "interface ", "export interface ", "abstract class ", "export abstract class "]
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+            || line.contains("(ABC)")
+            || line.contains("(Protocol)")
+    }
+
     fn parse_parameters(&self, params_str: &str) -> Vec<String> {
-        params_str
-            .split(',')
+        // Commas inside brackets belong to a type, as in `HashMap<K, V>`
+        let mut params = Vec::new();
+        let mut rest = params_str;
+        while !rest.is_empty() {
+            let param = Self::until_top_level(rest, &[","]);
+            params.push(param);
+            rest = rest[param.len()..].strip_prefix(',').unwrap_or_default();
+        }
+        params
+            .into_iter()
             .map(|p| {
                 // Extract parameter name (before : or = if present)
                 p.trim()
@@ -332,6 +1120,7 @@ impl Clone for LanguagePatterns {
     fn clone(&self) -> Self {
         Self {
             import_patterns: self.import_patterns.iter().map(|r| Regex::new(r.as_str()).unwrap()).collect(),
+            dynamic_import_patterns: self.dynamic_import_patterns.iter().map(|r| Regex::new(r.as_str()).unwrap()).collect(),
             export_patterns: self.export_patterns.iter().map(|r| Regex::new(r.as_str()).unwrap()).collect(),
             function_patterns: self.function_patterns.iter().map(|r| Regex::new(r.as_str()).unwrap()).collect(),
             class_patterns: self.class_patterns.iter().map(|r| Regex::new(r.as_str()).unwrap()).collect(),
@@ -0,0 +1,95 @@
+use crate::{config::SmellConfig, simple_parser::ParsedFile};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeSmell {
+    pub kind: SmellKind,
+    pub file: String,
+    pub line: usize,
+    /// Function or class the smell was found in
+    pub symbol: String,
+    pub value: usize,
+    pub threshold: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SmellKind {
+    LongFunction,
+    LongParameterList,
+    DeepNesting,
+    GodClass,
+}
+
+impl SmellKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SmellKind::LongFunction => "Long function",
+            SmellKind::LongParameterList => "Long parameter list",
+            SmellKind::DeepNesting => "Deep nesting",
+            SmellKind::GodClass => "God class",
+        }
+    }
+
+    /// What `value` measures, for display next to it
+    pub fn unit(&self) -> &'static str {
+        match self {
+            SmellKind::LongFunction => "lines",
+            SmellKind::LongParameterList => "parameters",
+            SmellKind::DeepNesting => "levels",
+            SmellKind::GodClass => "methods",
+        }
+    }
+}
+
+impl std::fmt::Display for CodeSmell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{} {} `{}`: {} {} (limit {})",
+            self.file, self.line, self.kind.name(), self.symbol, self.value, self.kind.unit(), self.threshold)
+    }
+}
+
+/// Flags long functions, long parameter lists, deep nesting and god classes
+pub struct SmellDetector {
+    config: SmellConfig,
+}
+
+impl SmellDetector {
+    pub fn new(config: SmellConfig) -> Self {
+        Self { config }
+    }
+
+    /// Smells across all files, worst (furthest over its threshold) first
+    pub fn detect(&self, parsed_files: &[ParsedFile]) -> Vec<CodeSmell> {
+        let mut smells = Vec::new();
+
+        for pf in parsed_files {
+            let file = pf.file_info.path.to_string_lossy().to_string();
+            let mut flag = |kind, line, symbol: &str, value: usize, threshold: usize| {
+                if value > threshold {
+                    smells.push(CodeSmell { kind, file: file.clone(), line, symbol: symbol.to_string(), value, threshold });
+                }
+            };
+
+            for function in &pf.functions {
+                let length = function.end_line.saturating_sub(function.line_number) + 1;
+                flag(SmellKind::LongFunction, function.line_number, &function.name, length, self.config.max_function_lines);
+                flag(SmellKind::LongParameterList, function.line_number, &function.name, function.parameters.len(), self.config.max_parameters);
+                flag(SmellKind::DeepNesting, function.line_number, &function.name, function.max_nesting, self.config.max_nesting);
+            }
+
+            for class in &pf.classes {
+                let methods = pf.functions.iter()
+                    .filter(|f| f.line_number > class.line_number && f.line_number <= class.end_line)
+                    .count()
+                    + class.methods.len();
+                flag(SmellKind::GodClass, class.line_number, &class.name, methods, self.config.max_class_methods);
+            }
+        }
+
+        smells.sort_by(|a, b| {
+            let excess = |s: &CodeSmell| s.value as f64 / s.threshold.max(1) as f64;
+            excess(b).total_cmp(&excess(a)).then_with(|| (&a.file, a.line).cmp(&(&b.file, b.line)))
+        });
+        smells
+    }
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Condvar, Mutex};
+
+/// Where file contents are read from: the filesystem, or an in-memory file set
+/// supplied by an embedding application (a tarball, a git tree, editor buffers)
+#[derive(Debug, Clone, Default)]
+pub enum SourceSet {
+    #[default]
+    Disk,
+    Memory(HashMap<PathBuf, String>),
+}
+
+impl SourceSet {
+    pub fn in_memory(files: impl IntoIterator<Item = (PathBuf, String)>) -> Self {
+        SourceSet::Memory(files.into_iter().map(|(path, content)| (Self::key(&path), content)).collect())
+    }
+
+    pub fn is_in_memory(&self) -> bool {
+        matches!(self, SourceSet::Memory(_))
+    }
+
+    /// Read a file; in-memory sets never fall back to the filesystem
+    pub fn read(&self, path: &Path) -> io::Result<String> {
+        self.read_decoded(path).map(|(content, _)| content)
+    }
+
+    /// Read a file along with the encoding it was decoded from. Files on disk
+    /// that aren't UTF-8 are decoded as UTF-16 or Latin-1 rather than rejected.
+    pub fn read_decoded(&self, path: &Path) -> io::Result<(String, SourceEncoding)> {
+        match self {
+            SourceSet::Disk => Ok(SourceEncoding::decode(std::fs::read(path)?)),
+            SourceSet::Memory(files) => files.get(&Self::key(path))
+                .map(|content| (content.clone(), SourceEncoding::Utf8))
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{} is not in the in-memory file set", path.display()))),
+        }
+    }
+
+    /// `./src/lib.rs` and `src/lib.rs` name the same in-memory file
+    fn key(path: &Path) -> PathBuf {
+        path.components().filter(|c| !matches!(c, Component::CurDir)).collect()
+    }
+}
+
+/// Text encoding a source file was decoded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SourceEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// Any byte sequence that isn't UTF-8 or UTF-16; Windows-1252 text decodes
+    /// the same apart from a few punctuation characters
+    Latin1,
+}
+
+impl SourceEncoding {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SourceEncoding::Utf8 => "UTF-8",
+            SourceEncoding::Utf16Le => "UTF-16LE",
+            SourceEncoding::Utf16Be => "UTF-16BE",
+            SourceEncoding::Latin1 => "Latin-1",
+        }
+    }
+
+    /// Detect the encoding from a byte order mark, then from the zero bytes
+    /// UTF-16 leaves in mostly-ASCII text, then by UTF-8 validity
+    pub fn decode(bytes: Vec<u8>) -> (String, SourceEncoding) {
+        if let Some(rest) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+            if let Ok(content) = std::str::from_utf8(rest) {
+                return (content.to_string(), SourceEncoding::Utf8);
+            }
+        }
+        let (encoding, start) = match bytes.get(..2) {
+            Some([0xFF, 0xFE]) => (Some(SourceEncoding::Utf16Le), 2),
+            Some([0xFE, 0xFF]) => (Some(SourceEncoding::Utf16Be), 2),
+            _ => (Self::guess_utf16(&bytes), 0),
+        };
+        if let Some(encoding) = encoding {
+            let units: Vec<u16> = bytes[start..].chunks_exact(2)
+                .map(|pair| match encoding {
+                    SourceEncoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+                    _ => u16::from_le_bytes([pair[0], pair[1]]),
+                })
+                .collect();
+            return (String::from_utf16_lossy(&units), encoding);
+        }
+        match String::from_utf8(bytes) {
+            Ok(content) => (content, SourceEncoding::Utf8),
+            Err(error) => (error.into_bytes().into_iter().map(char::from).collect(), SourceEncoding::Latin1),
+        }
+    }
+
+    /// UTF-16 without a byte order mark: most of the first characters have a
+    /// zero high byte, which text in a byte-oriented encoding never has
+    fn guess_utf16(bytes: &[u8]) -> Option<SourceEncoding> {
+        let pairs: Vec<&[u8]> = bytes.chunks_exact(2).take(256).collect();
+        if pairs.len() < 2 {
+            return None;
+        }
+        let zero_high = |index: usize| pairs.iter().filter(|pair| pair[index] == 0 && pair[1 - index] != 0).count() * 10 >= pairs.len() * 8;
+        if zero_high(1) {
+            Some(SourceEncoding::Utf16Le)
+        } else if zero_high(0) {
+            Some(SourceEncoding::Utf16Be)
+        } else {
+            None
+        }
+    }
+}
+
+/// Caps how many files are read at once, independently of the number of
+/// parsing threads; a limit of 0 means unlimited
+#[derive(Debug, Default)]
+pub struct IoLimiter {
+    limit: usize,
+    in_flight: Mutex<usize>,
+    released: Condvar,
+}
+
+impl IoLimiter {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            ..Self::default()
+        }
+    }
+
+    pub fn read(&self, sources: &SourceSet, path: &Path) -> io::Result<(String, SourceEncoding)> {
+        if self.limit == 0 || sources.is_in_memory() {
+            return sources.read_decoded(path);
+        }
+
+        {
+            let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+            while *in_flight >= self.limit {
+                in_flight = self.released.wait(in_flight).unwrap_or_else(|e| e.into_inner());
+            }
+            *in_flight += 1;
+        }
+        let content = sources.read_decoded(path);
+        *self.in_flight.lock().unwrap_or_else(|e| e.into_inner()) -= 1;
+        self.released.notify_one();
+        content
+    }
+}
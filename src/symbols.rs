@@ -0,0 +1,233 @@
+use crate::simple_parser::ParsedFile;
+use crate::{Error, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::{Path, PathBuf}};
+
+/// Flat index of every symbol extracted by the parser
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolIndex {
+    pub symbols: Vec<Symbol>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub path: String,
+    pub line: usize,
+    pub language: Option<String>,
+    /// Enclosing class for methods
+    pub container: Option<String>,
+    pub exported: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Class,
+    Import,
+}
+
+impl SymbolKind {
+    /// Single-letter kind used in ctags files
+    fn ctags_kind(&self) -> &'static str {
+        match self {
+            SymbolKind::Function => "f",
+            SymbolKind::Method => "m",
+            SymbolKind::Class => "c",
+            SymbolKind::Import => "i",
+        }
+    }
+}
+
+impl SymbolIndex {
+    pub fn from_parsed_files(parsed_files: &[ParsedFile]) -> Self {
+        let mut symbols = Vec::new();
+
+        for pf in parsed_files {
+            let path = pf.file_info.path.to_string_lossy().to_string();
+            let language = pf.file_info.language.clone();
+            let is_exported = |name: &str| pf.exports.iter().any(|e| e.name == name);
+
+            for function in &pf.functions {
+                symbols.push(Symbol {
+                    name: function.name.clone(),
+                    kind: SymbolKind::Function,
+                    path: path.clone(),
+                    line: function.line_number,
+                    language: language.clone(),
+                    container: None,
+                    exported: is_exported(&function.name),
+                });
+            }
+
+            for class in &pf.classes {
+                symbols.push(Symbol {
+                    name: class.name.clone(),
+                    kind: SymbolKind::Class,
+                    path: path.clone(),
+                    line: class.line_number,
+                    language: language.clone(),
+                    container: None,
+                    exported: is_exported(&class.name),
+                });
+
+                for method in &class.methods {
+                    symbols.push(Symbol {
+                        name: method.name.clone(),
+                        kind: SymbolKind::Method,
+                        path: path.clone(),
+                        line: method.line_number,
+                        language: language.clone(),
+                        container: Some(class.name.clone()),
+                        exported: false,
+                    });
+                }
+            }
+
+            for import in &pf.imports {
+                symbols.push(Symbol {
+                    name: import.module.clone(),
+                    kind: SymbolKind::Import,
+                    path: path.clone(),
+                    line: import.line_number,
+                    language: language.clone(),
+                    container: None,
+                    exported: false,
+                });
+            }
+        }
+
+        symbols.sort_by(|a, b| (&a.name, &a.path, a.line).cmp(&(&b.name, &b.path, b.line)));
+        Self { symbols }
+    }
+
+    /// Symbols matching the query, in file and line order
+    pub fn search(&self, query: &SymbolQuery) -> Vec<&Symbol> {
+        let mut matches: Vec<&Symbol> = self.symbols.iter().filter(|symbol| query.matches(symbol)).collect();
+        matches.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+        matches
+    }
+
+    /// Render in Exuberant/Universal ctags extended format (sorted)
+    pub fn to_ctags(&self) -> String {
+        let mut tags = String::from(
+            "!_TAG_FILE_FORMAT\t2\t/extended format/\n\
+             !_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted/\n\
+             !_TAG_PROGRAM_NAME\tproject-examer\t//\n",
+        );
+        tags.push_str(&format!("!_TAG_PROGRAM_VERSION\t{}\t//\n", env!("CARGO_PKG_VERSION")));
+
+        for symbol in self.symbols.iter().filter(|s| !s.name.contains(['\t', '\n'])) {
+            tags.push_str(&format!("{}\t{}\t{};\"\t{}\tline:{}",
+                symbol.name, symbol.path, symbol.line, symbol.kind.ctags_kind(), symbol.line));
+            if let Some(ref language) = symbol.language {
+                tags.push_str(&format!("\tlanguage:{}", language));
+            }
+            if let Some(ref container) = symbol.container {
+                tags.push_str(&format!("\tclass:{}", container));
+            }
+            tags.push('\n');
+        }
+
+        tags
+    }
+
+    /// Write `tags` (ctags) and `symbols.json` to the output directory
+    pub fn export(&self, output_dir: &Path) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(output_dir).map_err(|e| Error::report(output_dir, e))?;
+
+        let tags_path = output_dir.join("tags");
+        fs::write(&tags_path, self.to_ctags()).map_err(|e| Error::report(&tags_path, e))?;
+
+        let json_path = output_dir.join("symbols.json");
+        fs::write(&json_path, serde_json::to_string_pretty(self)?).map_err(|e| Error::report(&json_path, e))?;
+
+        Ok(vec![tags_path, json_path])
+    }
+}
+
+/// Filters for `project-examer search`. The pattern is a regular expression matched
+/// against the symbol name, or `Class.method` for methods; paths are globs relative
+/// to the target directory.
+pub struct SymbolQuery {
+    pattern: Regex,
+    root: PathBuf,
+    kinds: Vec<SymbolKind>,
+    languages: Vec<String>,
+    paths: Option<GlobSet>,
+    exported_only: bool,
+}
+
+impl SymbolQuery {
+    pub fn new(pattern: &str, ignore_case: bool, root: &Path) -> Result<Self> {
+        let pattern = RegexBuilder::new(pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map_err(|e| Error::Config(format!("Invalid search pattern: {}", e)))?;
+        Ok(Self {
+            pattern,
+            root: root.to_path_buf(),
+            kinds: Vec::new(),
+            languages: Vec::new(),
+            paths: None,
+            exported_only: false,
+        })
+    }
+
+    pub fn with_kinds(mut self, kinds: Vec<SymbolKind>) -> Self {
+        self.kinds = kinds;
+        self
+    }
+
+    pub fn with_languages(mut self, languages: Vec<String>) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    pub fn with_paths(mut self, patterns: &[String]) -> Result<Self> {
+        if !patterns.is_empty() {
+            let mut paths = GlobSetBuilder::new();
+            for pattern in patterns {
+                paths.add(Glob::new(pattern).map_err(|e| Error::Config(format!("Invalid path filter '{}': {}", pattern, e)))?);
+            }
+            self.paths = Some(paths.build()?);
+        }
+        Ok(self)
+    }
+
+    /// Only symbols exported from their module; imports and methods never are
+    pub fn exported_only(mut self, exported_only: bool) -> Self {
+        self.exported_only = exported_only;
+        self
+    }
+
+    fn matches(&self, symbol: &Symbol) -> bool {
+        if self.exported_only && !symbol.exported {
+            return false;
+        }
+        if !self.kinds.is_empty() && !self.kinds.contains(&symbol.kind) {
+            return false;
+        }
+        if !self.languages.is_empty() {
+            let Some(ref language) = symbol.language else { return false };
+            if !self.languages.iter().any(|wanted| wanted.eq_ignore_ascii_case(language)) {
+                return false;
+            }
+        }
+        if let Some(ref paths) = self.paths {
+            let path = Path::new(&symbol.path);
+            if !paths.is_match(path.strip_prefix(&self.root).unwrap_or(path)) {
+                return false;
+            }
+        }
+        match symbol.container {
+            Some(ref container) => self.pattern.is_match(&format!("{}.{}", container, symbol.name)),
+            None => self.pattern.is_match(&symbol.name),
+        }
+    }
+}
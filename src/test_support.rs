@@ -0,0 +1,174 @@
+//! Fixture projects and golden-file comparisons for snapshot-testing reports,
+//! for applications embedding the [`Reporter`] with their own templates or
+//! scoring configuration. Enabled with the `test-support` feature.
+//!
+//! ```no_run
+//! # async fn snapshot() -> anyhow::Result<()> {
+//! use project_examer::test_support::{FixtureProject, Golden};
+//!
+//! let fixture = FixtureProject::new("shop")
+//!     .file("src/cart.py", "import pricing\n\ndef total(items):\n    return sum(pricing.price(i) for i in items)\n")
+//!     .file("src/pricing.py", "def price(item):\n    return item.cost\n");
+//! let reporter = FixtureProject::reporter();
+//! let report = fixture.report(&reporter).await?;
+//!
+//! let golden = Golden::new("tests/golden");
+//! golden.assert_report_json("shop.json", &report);
+//! golden.assert_text("shop.md", &reporter.generate_markdown(&report, true)?);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Run the tests with `PROJECT_EXAMER_UPDATE_GOLDEN=1` to write or refresh the
+//! golden files instead of comparing against them.
+
+use crate::{analyzer::ProjectAnalysis, config::{Config, LLMProvider}, reporter::Report, Analyzer, Reporter};
+use crate::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Set (to anything but `0`) to overwrite golden files with the actual output
+pub const UPDATE_ENV: &str = "PROJECT_EXAMER_UPDATE_GOLDEN";
+
+/// Lines of context shown around the first difference from a golden file
+const DIFF_CONTEXT: usize = 3;
+
+/// An in-memory project analyzed without an LLM, with deterministic ordering and
+/// timestamps, so the same files always produce the same report
+#[derive(Debug, Clone)]
+pub struct FixtureProject {
+    root: PathBuf,
+    files: Vec<(PathBuf, String)>,
+    config: Config,
+}
+
+impl FixtureProject {
+    /// An empty project; `name` is its root directory as reported in file paths
+    pub fn new(name: &str) -> Self {
+        let root = PathBuf::from(name);
+        let mut config = Config { target_directory: root.clone(), ..Config::default() };
+        config.llm.provider = LLMProvider::None;
+        config.report.deterministic = true;
+        Self { root, files: Vec::new(), config }
+    }
+
+    /// Add a file, with `path` relative to the project root
+    pub fn file(mut self, path: impl AsRef<Path>, content: impl Into<String>) -> Self {
+        self.files.push((self.root.join(path), content.into()));
+        self
+    }
+
+    /// Adjust the configuration the project is analyzed with, e.g. rules,
+    /// components or smell thresholds; the LLM stays disabled
+    pub fn configure(mut self, configure: impl FnOnce(&mut Config)) -> Self {
+        configure(&mut self.config);
+        self.config.llm.provider = LLMProvider::None;
+        self
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Write the files under `dir/<name>` for passes that need a real directory
+    /// (git history, asset walks), returning the project root
+    pub fn write_to(&self, dir: &Path) -> Result<PathBuf> {
+        for (path, content) in &self.files {
+            let path = dir.join(path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, content)?;
+        }
+        Ok(dir.join(&self.root))
+    }
+
+    /// Run every local pass over the files, without reading the disk
+    pub async fn analyze(&self) -> Result<ProjectAnalysis> {
+        let mut analyzer = Analyzer::new(self.config.clone(), false)?;
+        analyzer.analyze_files(self.files.clone(), true).await
+    }
+
+    /// Analyze the project and build its report with `reporter`
+    pub async fn report(&self, reporter: &Reporter) -> Result<Report> {
+        let analysis = self.analyze().await?;
+        Ok(reporter.generate_report(&analysis, 0, LLMProvider::None.name(), ""))
+    }
+
+    /// A reporter stamping reports with the deterministic timestamp; for custom
+    /// scoring use `Reporter::with_scoring(..).with_fixed_timestamp(..)`
+    pub fn reporter() -> Reporter {
+        Reporter::new().with_fixed_timestamp(Config::default().report.timestamp)
+    }
+}
+
+/// Compares output with golden files in a directory
+#[derive(Debug, Clone)]
+pub struct Golden {
+    dir: PathBuf,
+}
+
+impl Golden {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Panic with the first differing lines when `actual` doesn't match the golden
+    /// file `name`. A missing golden file is written and the comparison fails, so
+    /// new snapshots get reviewed; with `PROJECT_EXAMER_UPDATE_GOLDEN` set the file
+    /// is overwritten instead.
+    pub fn assert_text(&self, name: &str, actual: &str) {
+        let path = self.dir.join(name);
+        let update = std::env::var(UPDATE_ENV).is_ok_and(|value| value != "0");
+        let expected = fs::read_to_string(&path).ok();
+        if update || expected.is_none() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).unwrap_or_else(|e| panic!("cannot create {}: {}", parent.display(), e));
+            }
+            fs::write(&path, actual).unwrap_or_else(|e| panic!("cannot write {}: {}", path.display(), e));
+            if update {
+                return;
+            }
+            panic!("golden file {} did not exist and was written; review it and run again", path.display());
+        }
+
+        if let Some(diff) = expected.as_deref().and_then(|expected| Self::first_difference(expected, actual)) {
+            panic!("output differs from golden file {} (set {}=1 to update it):\n{}", path.display(), UPDATE_ENV, diff);
+        }
+    }
+
+    /// Compare a report's JSON, with the crate version masked so upgrades don't
+    /// invalidate every snapshot
+    pub fn assert_report_json(&self, name: &str, report: &Report) {
+        let mut value = serde_json::to_value(report).expect("reports serialize to JSON");
+        value["metadata"]["version"] = serde_json::Value::String("<version>".to_string());
+        let json = serde_json::to_string_pretty(&value).expect("reports serialize to JSON");
+        self.assert_text(name, &format!("{}\n", json));
+    }
+
+    /// The first differing line with a few lines around it, `-` expected and `+`
+    /// actual; `None` when the texts are equal
+    fn first_difference(expected: &str, actual: &str) -> Option<String> {
+        if expected == actual {
+            return None;
+        }
+        let expected: Vec<&str> = expected.lines().collect();
+        let actual: Vec<&str> = actual.lines().collect();
+        if expected == actual {
+            return Some("  (line endings or trailing newline differ)\n".to_string());
+        }
+        let first = (0..expected.len().max(actual.len()))
+            .find(|&i| expected.get(i) != actual.get(i))
+            .unwrap_or(expected.len().min(actual.len()));
+        let start = first.saturating_sub(DIFF_CONTEXT);
+        let numbered = |lines: &[&str], from: usize, to: usize, marker: char| {
+            lines.iter().enumerate().take(to).skip(from)
+                .map(|(i, line)| format!("{} {:>5} {}\n", marker, i + 1, line))
+                .collect::<String>()
+        };
+        let mut diff = numbered(&expected, start, first, ' ');
+        diff.push_str(&numbered(&expected, first, first + DIFF_CONTEXT, '-'));
+        diff.push_str(&numbered(&actual, first, first + DIFF_CONTEXT, '+'));
+        Some(diff)
+    }
+}
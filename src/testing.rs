@@ -0,0 +1,248 @@
+use crate::{simple_parser::ParsedFile, sources::SourceSet};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Untested source files listed in the report and LLM context
+const UNTESTED_LISTED: usize = 20;
+
+/// Directories whose files are tests regardless of their names
+const TEST_DIRECTORIES: &[&str] = &["tests", "test", "__tests__", "spec", "specs"];
+
+/// Languages whose files can be tests or test subjects; data and markup files are neither
+const CODE_LANGUAGES: &[&str] = &[
+    "rust", "python", "javascript", "typescript", "go", "java", "kotlin", "scala", "csharp",
+    "ruby", "php", "swift", "c", "cpp", "elixir", "dart",
+];
+
+/// A test file, or a source file with inline tests (`#[cfg(test)]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestFile {
+    pub path: PathBuf,
+    /// Test framework recognized from the file's imports and annotations
+    pub framework: Option<String>,
+    pub test_cases: usize,
+    pub assertions: usize,
+    /// Source files the tests are matched to by name, or the file itself for inline tests
+    pub subjects: Vec<PathBuf>,
+    pub inline: bool,
+}
+
+/// A source file no test was matched to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UntestedSource {
+    pub path: PathBuf,
+    pub lines_of_code: usize,
+    pub complexity: usize,
+}
+
+/// Test files, the frameworks they use, assertion density and the source they cover
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TestingSummary {
+    /// Test files per framework
+    pub frameworks: BTreeMap<String, usize>,
+    pub test_files: Vec<TestFile>,
+    pub test_cases: usize,
+    pub assertions: usize,
+    /// Assertions per test case
+    pub assertion_density: f64,
+    pub source_files: usize,
+    pub tested_sources: usize,
+    /// Untested source files, most complex first
+    pub untested: Vec<UntestedSource>,
+}
+
+impl TestingSummary {
+    pub fn is_empty(&self) -> bool {
+        self.test_files.is_empty() && self.source_files == 0
+    }
+
+    /// Frameworks, mapping and gaps as lines for the Testing LLM analysis
+    pub fn context_lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("{} test files, {} test cases, {} assertions ({:.1} per test)",
+                self.test_files.len(), self.test_cases, self.assertions, self.assertion_density),
+            format!("{} of {} source files have a matching test", self.tested_sources, self.source_files),
+        ];
+        if !self.frameworks.is_empty() {
+            lines.push(format!("Frameworks: {}", self.frameworks.iter()
+                .map(|(framework, files)| format!("{} ({} files)", framework, files))
+                .collect::<Vec<_>>()
+                .join(", ")));
+        }
+        for test in &self.test_files {
+            lines.push(format!("Test {}{}: {} tests, {} assertions -> {}",
+                test.path.display(),
+                if test.inline { " (inline)" } else { "" },
+                test.test_cases, test.assertions,
+                if test.subjects.is_empty() {
+                    "no matching source".to_string()
+                } else {
+                    test.subjects.iter().map(|s| s.display().to_string()).collect::<Vec<_>>().join(", ")
+                }));
+        }
+        for source in self.untested.iter().take(UNTESTED_LISTED) {
+            lines.push(format!("Untested {} ({} lines, complexity {})", source.path.display(), source.lines_of_code, source.complexity));
+        }
+        lines
+    }
+}
+
+/// Recognizes test files by name and directory conventions, detects their framework,
+/// counts test cases and assertions, and matches tests to source files by name
+pub struct TestingAnalyzer {
+    root: PathBuf,
+    frameworks: Vec<(&'static str, Regex)>,
+    test_case: Regex,
+    assertion: Regex,
+    inline_tests: Regex,
+}
+
+impl TestingAnalyzer {
+    pub fn new(root: &Path) -> Self {
+        let regex = |pattern: &str| Regex::new(pattern).expect("valid test pattern");
+        // Checked in order; the first match names the framework
+        let frameworks = vec![
+            ("pytest", r"(?m)^\s*import pytest|^\s*from pytest|@pytest\."),
+            ("unittest", r"(?m)^\s*import unittest|^\s*from unittest|unittest\.TestCase"),
+            ("vitest", r#"from ['"]vitest['"]"#),
+            ("jest", r#"from ['"]@jest/|require\(['"]jest|\bjest\.(?:fn|mock|spyOn)\("#),
+            ("mocha", r#"require\(['"](?:mocha|chai)['"]\)|from ['"]chai['"]"#),
+            ("junit", r"\borg\.junit\b"),
+            ("testng", r"\borg\.testng\b"),
+            ("go testing", r#""testing""#),
+            ("rspec", r"\bRSpec\.describe\b|\bdescribe\s+\w+\s+do\b"),
+            ("xunit", r"\[Fact\]|\[Theory\]"),
+            ("nunit", r"\[TestFixture\]|\[Test\]"),
+            ("rust test", r"#\[(?:\w+::)?test\]"),
+            // Globals shared by jest, jasmine and mocha without an import
+            ("jest", r"(?m)^\s*(?:describe|it|test)\s*\("),
+            ("pytest", r"(?m)^\s*def test_\w+"),
+        ];
+        Self {
+            root: root.to_path_buf(),
+            frameworks: frameworks.into_iter().map(|(name, pattern)| (name, regex(pattern))).collect(),
+            test_case: regex(r#"(?m)^\s*(?:#\[(?:\w+::)?test\]|(?:async\s+)?def test_\w+|func Test\w+\(|(?:it|test)\s*\(\s*['"`]|@Test\b|\[(?:Fact|Theory|Test)\]|it\s+['"].*['"]\s+do\b)"#),
+            assertion: regex(r"\bassert\w*!?\s*[(!\s]|\bexpect\s*\(|\.should\b|\bt\.(?:Error|Errorf|Fatal|Fatalf|Fail)\b|\brequire\.\w+\(|\bAssert\.\w+\(|\bself\.assert\w+\("),
+            inline_tests: regex(r"(?m)^\s*#\[cfg\(test\)\]"),
+        }
+    }
+
+    /// Whether a path relative to the project root names a test file
+    pub fn is_test_file(path: &Path) -> bool {
+        if path.components().any(|c| TEST_DIRECTORIES.contains(&c.as_os_str().to_string_lossy().as_ref())) {
+            return true;
+        }
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let stem = name.split('.').next().unwrap_or_default();
+        stem.starts_with("test_")
+            || stem.ends_with("_test")
+            || stem.ends_with("_tests")
+            || stem.ends_with("_spec")
+            || stem.ends_with("Test")
+            || stem.ends_with("Tests")
+            || name.contains(".test.")
+            || name.contains(".spec.")
+    }
+
+    /// File stem with test prefixes and suffixes removed: `test_parser.py` and `parser.test.ts` give `parser`
+    fn subject_stem(path: &Path) -> String {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let stem = name.split('.').next().unwrap_or_default();
+        let stem = stem.strip_prefix("test_").unwrap_or(stem);
+        ["_tests", "_test", "_spec", "Tests", "Test"].iter()
+            .find_map(|suffix| stem.strip_suffix(suffix))
+            .unwrap_or(stem)
+            .to_lowercase()
+    }
+
+    pub fn analyze(&self, parsed_files: &[ParsedFile], sources: &SourceSet) -> TestingSummary {
+        let code: Vec<&ParsedFile> = parsed_files.iter()
+            .filter(|pf| pf.file_info.language.as_deref().is_some_and(|language| CODE_LANGUAGES.contains(&language)))
+            .collect();
+        let (tests, source_files): (Vec<&ParsedFile>, Vec<&ParsedFile>) = code.iter()
+            .partition(|pf| Self::is_test_file(pf.file_info.path.strip_prefix(&self.root).unwrap_or(&pf.file_info.path)));
+
+        let mut by_stem: BTreeMap<String, Vec<&ParsedFile>> = BTreeMap::new();
+        for pf in &source_files {
+            let stem = pf.file_info.path.file_stem().map(|s| s.to_string_lossy().to_lowercase()).unwrap_or_default();
+            by_stem.entry(stem).or_default().push(pf);
+        }
+
+        let mut test_files = Vec::new();
+        for pf in &tests {
+            let Ok(content) = sources.read(&pf.file_info.path) else { continue };
+            let candidates = by_stem.get(&Self::subject_stem(&pf.file_info.path)).cloned().unwrap_or_default();
+            let candidates: Vec<&ParsedFile> = candidates.into_iter()
+                .filter(|source| source.file_info.language == pf.file_info.language)
+                .collect();
+            // Prefer the candidates sharing the most directories with the test
+            let shared = |source: &ParsedFile| source.file_info.path.components()
+                .zip(pf.file_info.path.components())
+                .take_while(|(a, b)| a == b)
+                .count();
+            let best = candidates.iter().map(|source| shared(source)).max();
+            let subjects = candidates.iter()
+                .filter(|source| Some(shared(source)) == best)
+                .map(|source| source.file_info.path.clone())
+                .collect();
+            test_files.push(self.test_file(&pf.file_info.path, &content, subjects, false));
+        }
+
+        for pf in &source_files {
+            let Ok(content) = sources.read(&pf.file_info.path) else { continue };
+            if self.inline_tests.is_match(&content) {
+                test_files.push(self.test_file(&pf.file_info.path, &content, vec![pf.file_info.path.clone()], true));
+            }
+        }
+
+        let mut frameworks = BTreeMap::new();
+        for test in &test_files {
+            if let Some(framework) = &test.framework {
+                *frameworks.entry(framework.clone()).or_insert(0) += 1;
+            }
+        }
+
+        let tested: Vec<&PathBuf> = test_files.iter().flat_map(|test| test.subjects.iter()).collect();
+        let mut untested: Vec<UntestedSource> = source_files.iter()
+            .filter(|pf| !tested.contains(&&pf.file_info.path) && !pf.functions.is_empty())
+            .map(|pf| UntestedSource {
+                path: pf.file_info.path.clone(),
+                lines_of_code: pf.lines_of_code,
+                complexity: pf.cyclomatic_complexity,
+            })
+            .collect();
+        untested.sort_by_key(|source| std::cmp::Reverse((source.complexity, source.lines_of_code)));
+
+        let test_cases = test_files.iter().map(|test| test.test_cases).sum();
+        let assertions = test_files.iter().map(|test| test.assertions).sum();
+        TestingSummary {
+            frameworks,
+            test_cases,
+            assertions,
+            assertion_density: if test_cases == 0 { 0.0 } else { assertions as f64 / test_cases as f64 },
+            source_files: source_files.len(),
+            tested_sources: source_files.iter().filter(|pf| tested.contains(&&pf.file_info.path)).count(),
+            untested,
+            test_files,
+        }
+    }
+
+    fn test_file(&self, path: &Path, content: &str, subjects: Vec<PathBuf>, inline: bool) -> TestFile {
+        // Inline tests only count from the test module on
+        let tests = if inline {
+            self.inline_tests.find(content).map(|m| &content[m.start()..]).unwrap_or(content)
+        } else {
+            content
+        };
+        TestFile {
+            path: path.to_path_buf(),
+            framework: self.frameworks.iter().find(|(_, regex)| regex.is_match(tests)).map(|(name, _)| name.to_string()),
+            test_cases: self.test_case.find_iter(tests).count(),
+            assertions: self.assertion.find_iter(tests).count(),
+            subjects,
+            inline,
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use crate::reporter::Reporter;
+use crate::simple_parser::ParsedFile;
+use std::collections::BTreeMap;
+use std::path::Component;
+
+/// Directory tree weighted by lines of code, used to render the treemap heatmap
+#[derive(Debug, Default)]
+pub struct TreemapNode {
+    pub name: String,
+    pub path: String,
+    pub lines_of_code: usize,
+    pub complexity: usize,
+    pub children: BTreeMap<String, TreemapNode>,
+}
+
+impl TreemapNode {
+    pub fn from_files(parsed_files: &[ParsedFile]) -> Self {
+        let mut root = TreemapNode {
+            name: ".".to_string(),
+            path: ".".to_string(),
+            ..Default::default()
+        };
+
+        for pf in parsed_files {
+            let loc = pf.lines_of_code.max(1);
+            let complexity = pf.functions.len() + pf.classes.len() * 2;
+            let mut node = &mut root;
+            node.lines_of_code += loc;
+            node.complexity += complexity;
+
+            let mut path = String::new();
+            for component in pf.file_info.path.components() {
+                let Component::Normal(part) = component else { continue };
+                let part = part.to_string_lossy().to_string();
+                if !path.is_empty() {
+                    path.push('/');
+                }
+                path.push_str(&part);
+
+                node = node.children.entry(part.clone()).or_insert_with(|| TreemapNode {
+                    name: part,
+                    path: path.clone(),
+                    ..Default::default()
+                });
+                node.lines_of_code += loc;
+                node.complexity += complexity;
+            }
+        }
+
+        root
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Complexity per 100 lines, the metric used for the heatmap color
+    fn density(&self) -> f64 {
+        if self.lines_of_code == 0 {
+            0.0
+        } else {
+            self.complexity as f64 * 100.0 / self.lines_of_code as f64
+        }
+    }
+}
+
+struct Rect {
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+}
+
+pub struct TreemapRenderer {
+    width: f64,
+    height: f64,
+}
+
+impl Default for TreemapRenderer {
+    fn default() -> Self {
+        Self::new(960.0, 540.0)
+    }
+}
+
+impl TreemapRenderer {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self { width, height }
+    }
+
+    /// Render an inline SVG where rectangle area is lines of code and color is
+    /// complexity density (green = low, red = high).
+    pub fn render_svg(&self, parsed_files: &[ParsedFile]) -> String {
+        let root = TreemapNode::from_files(parsed_files);
+        if root.lines_of_code == 0 {
+            return "<p>No files to visualize.</p>".to_string();
+        }
+
+        let mut leaves = Vec::new();
+        Self::collect_leaves(&root, &mut leaves);
+        let max_density = leaves.iter().map(|n| n.density()).fold(0.0_f64, f64::max);
+
+        let mut svg = format!(
+            r#"<svg class="treemap" viewBox="0 0 {w} {h}" width="100%" xmlns="http://www.w3.org/2000/svg">"#,
+            w = self.width, h = self.height
+        );
+        let bounds = Rect { x: 0.0, y: 0.0, w: self.width, h: self.height };
+        self.layout(&root, bounds, 0, max_density, &mut svg);
+        svg.push_str("</svg>");
+        svg
+    }
+
+    fn collect_leaves<'a>(node: &'a TreemapNode, leaves: &mut Vec<&'a TreemapNode>) {
+        if node.is_leaf() {
+            leaves.push(node);
+        }
+        for child in node.children.values() {
+            Self::collect_leaves(child, leaves);
+        }
+    }
+
+    /// Slice-and-dice layout alternating horizontal/vertical splits by depth
+    fn layout(&self, node: &TreemapNode, rect: Rect, depth: usize, max_density: f64, svg: &mut String) {
+        if node.is_leaf() {
+            let ratio = if max_density > 0.0 { node.density() / max_density } else { 0.0 };
+            let hue = 120.0 * (1.0 - ratio);
+            svg.push_str(&format!(
+                r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="hsl({:.0},70%,55%)" stroke="white" stroke-width="1"><title>{} — {} LOC, complexity {}</title></rect>"#,
+                rect.x, rect.y, rect.w, rect.h, hue, Reporter::escape_html(&node.path), node.lines_of_code, node.complexity
+            ));
+            if rect.w > 60.0 && rect.h > 14.0 {
+                svg.push_str(&format!(
+                    r#"<text x="{:.1}" y="{:.1}" font-size="10" fill="black" pointer-events="none">{}</text>"#,
+                    rect.x + 3.0, rect.y + 12.0, Reporter::escape_html(&node.name)
+                ));
+            }
+            return;
+        }
+
+        let total = node.lines_of_code as f64;
+        let mut offset = 0.0;
+        for child in node.children.values() {
+            let share = child.lines_of_code as f64 / total;
+            let child_rect = if depth.is_multiple_of(2) {
+                let w = rect.w * share;
+                let r = Rect { x: rect.x + offset, y: rect.y, w, h: rect.h };
+                offset += w;
+                r
+            } else {
+                let h = rect.h * share;
+                let r = Rect { x: rect.x, y: rect.y + offset, w: rect.w, h };
+                offset += h;
+                r
+            };
+            self.layout(child, child_rect, depth + 1, max_density, svg);
+        }
+    }
+}